@@ -10,7 +10,7 @@ use crate::message::SystemMessage;
 use crate::state::AppState;
 use crate::workspace::EditorType;
 use dispel_core::Extractor;
-use hexedit::HexEditorState;
+use hexedit::{HexEditorState, HexProvider};
 use iced::Task;
 use std::path::Path;
 
@@ -25,6 +25,10 @@ pub struct App {
     pub file_tree: FileTree,
     pub window_id: iced::window::Id,
     pub history_panel_visible: bool,
+    pub usages_panel_visible: bool,
+    pub usages_panel: crate::components::usages_panel::UsagesPanel,
+    pub loot_panel_visible: bool,
+    pub loot_panel: crate::components::loot_panel::LootPanel,
     pub sidebar_visible: bool,
     pub empty_edit_history: EditHistory,
     pub command_palette: Option<CommandPalette>,
@@ -122,6 +126,10 @@ impl App {
                 file_tree,
                 window_id: iced::window::Id::unique(),
                 history_panel_visible: false,
+                usages_panel_visible: false,
+                usages_panel: crate::components::usages_panel::UsagesPanel::default(),
+                loot_panel_visible: false,
+                loot_panel: crate::components::loot_panel::LootPanel::default(),
                 sidebar_visible: true,
                 empty_edit_history: EditHistory::default(),
                 command_palette: None,
@@ -159,6 +167,10 @@ impl App {
             file_tree: crate::components::file_tree::FileTree::default(),
             window_id: iced::window::Id::unique(),
             history_panel_visible: false,
+            usages_panel_visible: false,
+            usages_panel: crate::components::usages_panel::UsagesPanel::default(),
+            loot_panel_visible: false,
+            loot_panel: crate::components::loot_panel::LootPanel::default(),
             sidebar_visible: true,
             empty_edit_history: EditHistory::default(),
             command_palette: None,
@@ -368,7 +380,8 @@ impl App {
                     .or_insert_with(|| SaveFileViewerState {
                         loading: true,
                         ..Default::default()
-                    });
+                    })
+                    .file_path = Some(path.to_path_buf());
                 let path_buf = path.to_path_buf();
                 let game_path = self.state.workspace.game_path.clone();
                 Task::perform(
@@ -405,10 +418,16 @@ impl App {
                         let hex_editors =
                             crate::editors::save_file_viewer::state::get_hex_editors(&save_file);
 
+                        let item_catalog = game_path
+                            .as_deref()
+                            .map(crate::editors::save_file_viewer::state::SaveItemCatalog::load)
+                            .unwrap_or_default();
+
                         Ok(crate::editors::save_file_viewer::message::SaveFileLoaded {
                             save_file,
                             hex_editors,
                             map_names,
+                            item_catalog,
                         })
                     },
                     move |result| {
@@ -468,10 +487,12 @@ impl App {
 
 fn hex_editor_state_for_path(path: &Path) -> HexEditorState {
     let mut state = HexEditorState::load_from_path(path);
-    state.set_layout(crate::binary_layout::layout_for_path(
+    // Freshly loaded, so the whole file is one piece and nothing is copied.
+    let layout = crate::binary_layout::layout_for_path(
         path,
-        state.provider.as_slice(),
-    ));
+        &state.provider.read_range(0..state.provider.len()),
+    );
+    state.set_layout(layout);
     state.ensure_outline_pane();
     state
 }
@@ -38,6 +38,34 @@ impl Command {
                 action: || Message::Workspace(WorkspaceMessage::ToggleHistoryPanel),
                 applicable_editors: vec![],
             },
+            Command {
+                id: "journal-undo",
+                label: "Undo Last Project Edit (Journal)",
+                shortcut: None,
+                action: || Message::Workspace(WorkspaceMessage::JournalUndo),
+                applicable_editors: vec![],
+            },
+            Command {
+                id: "journal-redo",
+                label: "Redo Project Edit (Journal)",
+                shortcut: None,
+                action: || Message::Workspace(WorkspaceMessage::JournalRedo),
+                applicable_editors: vec![],
+            },
+            Command {
+                id: "find-usages",
+                label: "Find Usages",
+                shortcut: None,
+                action: || Message::Workspace(WorkspaceMessage::ToggleUsagesPanel),
+                applicable_editors: vec![],
+            },
+            Command {
+                id: "loot-sources",
+                label: "Loot Sources",
+                shortcut: None,
+                action: || Message::Workspace(WorkspaceMessage::ToggleLootPanel),
+                applicable_editors: vec![],
+            },
             Command {
                 id: "toggle-sidebar",
                 label: "Toggle Sidebar",
@@ -102,6 +102,12 @@ pub trait EditableRecord:
     fn detail_width() -> f32 {
         320.0
     }
+
+    /// Catalog this record belongs to when other files reference it by
+    /// index, e.g. an item that can be dropped, rewarded or sold.
+    fn record_kind() -> Option<dispel_core::references::usages::RecordKind> {
+        None
+    }
 }
 
 /// Blanket implementation: any type implementing `Extractor` + `EditableRecord`
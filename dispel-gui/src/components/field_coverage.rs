@@ -490,7 +490,7 @@ check_record!(
     struct "Store",
     virtual_desc [],
     skip_fields [
-        // Vec<StoreProduct> — nested complex type, requires its own editor
+        // Vec<StoreProduct> — edited in the store editor's product pane
         "products"
     ]
 );
@@ -13,6 +13,30 @@ pub fn ensure_item_lookups(game_path: &str, lookups: &mut HashMap<String, Vec<(S
     }
 }
 
+/// Locate an item database file (e.g. `weaponItem.db`) under
+/// `CharacterInGame/`, falling back to a case-insensitive match on macOS.
+pub fn find_item_db(game_path: &Path, file_name: &str) -> Option<PathBuf> {
+    let char_path = game_path.join("CharacterInGame");
+    let exact = char_path.join(file_name);
+    if exact.exists() {
+        return Some(exact);
+    }
+    // macOS case-insensitive fallback (skipped on case-sensitive systems)
+    if cfg!(target_os = "macos")
+        && let Ok(entries) = std::fs::read_dir(&char_path)
+    {
+        let target = file_name.to_lowercase();
+        for entry in entries.filter_map(Result::ok) {
+            if let Some(name) = entry.file_name().to_str()
+                && name.to_lowercase() == target
+            {
+                return Some(entry.path());
+            }
+        }
+    }
+    None
+}
+
 /// Populate `lookups["items"]` with all items from all DB files.
 ///
 /// Each entry: `(composite_key, display_name)` where
@@ -26,28 +50,7 @@ pub fn populate_item_lookups(
     game_path: &Path,
     lookups: &mut HashMap<String, Vec<(String, String)>>,
 ) {
-    let char_path = game_path.join("CharacterInGame");
-
-    let find_db = |file_name: &str| -> Option<std::path::PathBuf> {
-        let exact = char_path.join(file_name);
-        if exact.exists() {
-            return Some(exact);
-        }
-        // macOS case-insensitive fallback (skipped on case-sensitive systems)
-        if cfg!(target_os = "macos")
-            && let Ok(entries) = std::fs::read_dir(&char_path)
-        {
-            let target = file_name.to_lowercase();
-            for entry in entries.filter_map(Result::ok) {
-                if let Some(name) = entry.file_name().to_str()
-                    && name.to_lowercase() == target
-                {
-                    return Some(entry.path());
-                }
-            }
-        }
-        None
-    };
+    let find_db = |file_name: &str| find_item_db(game_path, file_name);
 
     let mut entries: Vec<(String, String)> = Vec::new();
 
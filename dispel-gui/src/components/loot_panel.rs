@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use crate::components::utils::{horizontal_rule, horizontal_space};
+use crate::message::{Message, workspace::WorkspaceMessage};
+use crate::style;
+use dispel_core::references::loot::{LootReport, LootSource};
+use dispel_core::references::usages::{RecordId, RecordKind};
+use iced::widget::{button, column, container, pick_list, row, scrollable, text, text_input};
+use iced::{Element, Fill, Font};
+
+/// Maximum number of sources listed; the count above the list stays exact.
+const MAX_ROWS: usize = 500;
+
+/// Which question the loot panel answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LootQuery {
+    /// "Where can I get this item?"
+    Item,
+    /// "What does this map drop?"
+    Map,
+}
+
+impl LootQuery {
+    pub const ALL: [LootQuery; 2] = [LootQuery::Item, LootQuery::Map];
+}
+
+impl std::fmt::Display for LootQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LootQuery::Item => "By item",
+            LootQuery::Map => "By map",
+        })
+    }
+}
+
+/// "Loot sources" side panel: every monster, extra, NPC, map placement and
+/// shop that hands out an item, or every item a map hands out.
+#[derive(Debug, Clone)]
+pub struct LootPanel {
+    pub query: LootQuery,
+    pub kind: RecordKind,
+    pub id_input: String,
+    /// `AllMap.ini` file name of the map shown in [`LootQuery::Map`].
+    pub map: Option<String>,
+    /// Report over `indexed_game_path`; `None` until built.
+    pub report: Option<Arc<LootReport>>,
+    pub indexed_game_path: Option<String>,
+    pub is_building: bool,
+}
+
+impl Default for LootPanel {
+    fn default() -> Self {
+        Self {
+            query: LootQuery::Item,
+            kind: RecordKind::WeaponItem,
+            id_input: String::new(),
+            map: None,
+            report: None,
+            indexed_game_path: None,
+            is_building: false,
+        }
+    }
+}
+
+impl LootPanel {
+    pub fn id(&self) -> Option<i32> {
+        self.id_input.trim().parse().ok()
+    }
+
+    /// Sources matching the current query, or `None` while there is no
+    /// report or the query is incomplete.
+    pub fn results(&self) -> Option<Vec<&LootSource>> {
+        let report = self.report.as_ref()?;
+        Some(match self.query {
+            LootQuery::Item => report.sources_of(RecordId {
+                kind: self.kind,
+                id: self.id()?,
+            }),
+            LootQuery::Map => report.sources_on(self.map.as_deref()?),
+        })
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = row![
+            text("Loot Sources").size(14),
+            horizontal_space(),
+            button(text("Rebuild").size(11))
+                .on_press_maybe(
+                    (!self.is_building)
+                        .then_some(Message::Workspace(WorkspaceMessage::LootRebuild))
+                )
+                .style(style::chip),
+            button(text("×").size(16))
+                .on_press(Message::Workspace(WorkspaceMessage::ToggleLootPanel))
+                .style(style::chip),
+        ]
+        .spacing(4)
+        .padding([8, 12])
+        .align_y(iced::Alignment::Center);
+
+        let mode = pick_list(Some(self.query), LootQuery::ALL, |q| q.to_string())
+            .on_select(|q| Message::Workspace(WorkspaceMessage::LootQuerySelected(q)))
+            .width(Fill)
+            .padding(4)
+            .text_size(11);
+
+        let query: Element<'_, Message> = match self.query {
+            LootQuery::Item => row![
+                pick_list(Some(self.kind), RecordKind::ITEMS, |kind| kind.to_string())
+                    .on_select(|kind| Message::Workspace(WorkspaceMessage::LootKindSelected(kind)))
+                    .width(Fill)
+                    .padding(4)
+                    .text_size(11),
+                text_input("id", &self.id_input)
+                    .on_input(|s| Message::Workspace(WorkspaceMessage::LootIdInput(s)))
+                    .width(64)
+                    .padding(4)
+                    .size(11)
+                    .accessible_label("Item id"),
+            ]
+            .spacing(4)
+            .into(),
+            LootQuery::Map => {
+                let maps: Vec<String> = self
+                    .report
+                    .as_ref()
+                    .map(|r| r.maps.iter().map(|m| m.map_filename.clone()).collect())
+                    .unwrap_or_default();
+                pick_list(self.map.clone(), maps, String::clone)
+                    .on_select(|map| Message::Workspace(WorkspaceMessage::LootMapSelected(map)))
+                    .width(Fill)
+                    .padding(4)
+                    .text_size(11)
+                    .into()
+            }
+        };
+
+        let results = self.results();
+        let status = match (&self.report, &results) {
+            _ if self.is_building => "Collecting loot sources...".to_string(),
+            (None, _) => "No report; open a game folder first".to_string(),
+            (Some(_), None) => match self.query {
+                LootQuery::Item => "Enter an item id".to_string(),
+                LootQuery::Map => "Pick a map".to_string(),
+            },
+            (Some(_), Some(sources)) => format!("{} source(s)", sources.len()),
+        };
+
+        let rows: Vec<Element<'_, Message>> = results
+            .unwrap_or_default()
+            .into_iter()
+            .take(MAX_ROWS)
+            .map(|source| self.source_row(source))
+            .collect();
+
+        let warnings = self.report.as_ref().map(|r| r.warnings.len()).unwrap_or(0);
+        let mut content = column![
+            row![mode].padding([0, 12]),
+            row![query].padding([0, 12]),
+            row![text(status).size(11).style(style::subtle_text)].padding([0, 12]),
+        ]
+        .spacing(6);
+        if warnings > 0 {
+            content = content.push(
+                row![
+                    text(format!("{warnings} file(s) missing or unreadable"))
+                        .size(11)
+                        .style(style::subtle_text)
+                ]
+                .padding([0, 12]),
+            );
+        }
+        content = content.push(scrollable(column(rows).spacing(2).padding([0, 8])).height(Fill));
+
+        container(column![header, horizontal_rule(1), content].spacing(6))
+            .height(Fill)
+            .width(320)
+            .style(style::sidebar_container)
+            .accessible_label("Loot sources")
+            .into()
+    }
+
+    fn source_row<'a>(&self, source: &'a LootSource) -> Element<'a, Message> {
+        // By item the map is the interesting part; by map, the item.
+        let title = match self.query {
+            LootQuery::Item => source
+                .map_name
+                .clone()
+                .unwrap_or_else(|| "Shop".to_string()),
+            LootQuery::Map => source.item_label(),
+        };
+        let mut line = format!("{}", source.source);
+        if !source.source_name.is_empty() {
+            line.push_str(&format!(": {}", source.source_name));
+        }
+        if let (Some(x), Some(y)) = (source.x, source.y) {
+            line.push_str(&format!(" at ({x}, {y})"));
+        }
+        if source.expected_quantity != 1.0 {
+            line.push_str(&format!(" ×{:.2}", source.expected_quantity));
+        }
+        button(
+            column![
+                text(title).size(12),
+                text(line).size(11).font(Font::MONOSPACE),
+                text(format!("{} #{}", source.file, source.record))
+                    .size(10)
+                    .style(style::subtle_text),
+            ]
+            .spacing(2),
+        )
+        .width(Fill)
+        .on_press(Message::Workspace(WorkspaceMessage::UsagesOpenFile(
+            source.file.clone(),
+        )))
+        .style(style::chip)
+        .into()
+    }
+}
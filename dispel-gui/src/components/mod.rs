@@ -11,9 +11,11 @@ pub mod generic_editor;
 pub mod global_search;
 pub mod item_catalog;
 pub mod loading_state;
+pub mod loot_panel;
 pub(crate) mod map_render;
 pub mod standard;
 pub mod tab_bar;
+pub mod usages_panel;
 pub mod utils;
 
 pub use file_tree::FileTree;
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use crate::components::utils::{horizontal_rule, horizontal_space};
+use crate::message::{Message, workspace::WorkspaceMessage};
+use crate::style;
+use dispel_core::references::usages::{RecordKind, ReferenceIndex, Usage};
+use iced::widget::{button, column, container, pick_list, row, scrollable, text, text_input};
+use iced::{Element, Fill, Font};
+
+/// Maximum number of usages listed; the count above the list stays exact.
+const MAX_ROWS: usize = 500;
+
+/// "Find usages" side panel: pick a record type and id, list every file
+/// location that references it.
+#[derive(Debug, Clone)]
+pub struct UsagesPanel {
+    pub kind: RecordKind,
+    pub id_input: String,
+    /// Index over `indexed_game_path`; `None` until built.
+    pub index: Option<Arc<ReferenceIndex>>,
+    pub indexed_game_path: Option<String>,
+    pub is_building: bool,
+}
+
+impl Default for UsagesPanel {
+    fn default() -> Self {
+        Self {
+            kind: RecordKind::HealItem,
+            id_input: String::new(),
+            index: None,
+            indexed_game_path: None,
+            is_building: false,
+        }
+    }
+}
+
+impl UsagesPanel {
+    pub fn id(&self) -> Option<i32> {
+        self.id_input.trim().parse().ok()
+    }
+
+    /// Usages of the selected record, or `None` while there is no index or
+    /// no valid id.
+    pub fn results(&self) -> Option<&[Usage]> {
+        Some(self.index.as_ref()?.usages(self.kind, self.id()?))
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = row![
+            text("Find Usages").size(14),
+            horizontal_space(),
+            button(text("Rebuild").size(11))
+                .on_press_maybe(
+                    (!self.is_building)
+                        .then_some(Message::Workspace(WorkspaceMessage::UsagesRebuildIndex))
+                )
+                .style(style::chip),
+            button(text("×").size(16))
+                .on_press(Message::Workspace(WorkspaceMessage::ToggleUsagesPanel))
+                .style(style::chip),
+        ]
+        .spacing(4)
+        .padding([8, 12])
+        .align_y(iced::Alignment::Center);
+
+        let query = row![
+            pick_list(Some(self.kind), RecordKind::ALL, |kind| kind.to_string())
+                .on_select(|kind| Message::Workspace(WorkspaceMessage::UsagesKindSelected(kind)))
+                .width(Fill)
+                .padding(4)
+                .text_size(11),
+            text_input("id", &self.id_input)
+                .on_input(|s| Message::Workspace(WorkspaceMessage::UsagesIdInput(s)))
+                .width(64)
+                .padding(4)
+                .size(11)
+                .accessible_label("Record id"),
+        ]
+        .spacing(4)
+        .padding([0, 12]);
+
+        let status = match (&self.index, self.results()) {
+            _ if self.is_building => "Indexing game files...".to_string(),
+            (None, _) => "No index; open a game folder first".to_string(),
+            (Some(_), None) => "Enter a record id".to_string(),
+            (Some(index), Some(usages)) => format!(
+                "{} usage(s) in {} indexed file(s)",
+                usages.len(),
+                index.files_indexed.len()
+            ),
+        };
+
+        let rows: Vec<Element<'_, Message>> = self
+            .results()
+            .unwrap_or_default()
+            .iter()
+            .take(MAX_ROWS)
+            .map(|usage| {
+                let mut line = format!("#{} {}", usage.record, usage.field);
+                if let Some(label) = &usage.label {
+                    line.push_str(&format!(" ({label})"));
+                }
+                button(
+                    column![
+                        text(&usage.file).size(11).style(style::subtle_text),
+                        text(line).size(11).font(Font::MONOSPACE),
+                    ]
+                    .spacing(2),
+                )
+                .width(Fill)
+                .on_press(Message::Workspace(WorkspaceMessage::UsagesOpenFile(
+                    usage.file.clone(),
+                )))
+                .style(style::chip)
+                .into()
+            })
+            .collect();
+
+        let skipped = self
+            .index
+            .as_ref()
+            .map(|index| index.errors.len())
+            .unwrap_or(0);
+        let mut content = column![
+            query,
+            row![text(status).size(11).style(style::subtle_text)].padding([0, 12]),
+        ]
+        .spacing(6);
+        if skipped > 0 {
+            content = content.push(
+                row![
+                    text(format!("{skipped} file(s) could not be parsed"))
+                        .size(11)
+                        .style(style::subtle_text)
+                ]
+                .padding([0, 12]),
+            );
+        }
+        content = content.push(scrollable(column(rows).spacing(2).padding([0, 8])).height(Fill));
+
+        container(column![header, horizontal_rule(1), content].spacing(6))
+            .height(Fill)
+            .width(320)
+            .style(style::sidebar_container)
+            .accessible_label("Find usages")
+            .into()
+    }
+}
+
+/// Record kind and catalog index of the record selected in the active
+/// editor, for editors whose records are referenced elsewhere.
+pub fn selected_record(app: &crate::app::App) -> Option<(RecordKind, i32)> {
+    use crate::workspace::EditorType;
+
+    fn catalog_index<R: crate::components::editable::EditableRecord>(
+        state: &crate::components::generic_editor::GenericEditorState<R>,
+    ) -> Option<i32> {
+        let (index, _) = state.filtered.get(state.selected_idx?)?;
+        i32::try_from(*index).ok()
+    }
+
+    let editors = &app.state.editors;
+    let (kind, index) = match app.state.workspace.active()?.editor_type {
+        EditorType::WeaponEditor => (
+            RecordKind::WeaponItem,
+            catalog_index(&editors.weapon_editor.state),
+        ),
+        EditorType::HealItemEditor => (
+            RecordKind::HealItem,
+            catalog_index(&editors.heal_item_editor.state),
+        ),
+        EditorType::MiscItemEditor => (
+            RecordKind::MiscItem,
+            catalog_index(&editors.misc_item_editor.state),
+        ),
+        EditorType::EditItemEditor => (
+            RecordKind::EditItem,
+            catalog_index(&editors.edit_item_editor.state),
+        ),
+        EditorType::EventItemEditor => (
+            RecordKind::EventItem,
+            catalog_index(&editors.event_item_editor.state),
+        ),
+        EditorType::MonsterEditor => (
+            RecordKind::Monster,
+            catalog_index(&editors.monster_editor.state),
+        ),
+        _ => return None,
+    };
+    Some((kind, index?))
+}
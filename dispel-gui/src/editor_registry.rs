@@ -149,6 +149,12 @@ macro_rules! undo_redo_dispatch {
                 .get_mut(&$tab_id)
                 .and_then(|e| e.$action()),
 
+            // Save file editor — one state per open `.sav` tab
+            EditorType::SaveFileViewer => $self
+                .save_file_viewers
+                .get_mut(&$tab_id)
+                .and_then(|v| v.$action()),
+
             _ => None,
         }
     }};
@@ -354,6 +360,10 @@ impl EditorRegistry {
                 .editors
                 .get(&tab_id)
                 .map(|ed| ed.edit_history()),
+            EditorType::SaveFileViewer => self
+                .save_file_viewers
+                .get(&tab_id)
+                .map(|viewer| viewer.edit_history()),
 
             // Safety net: has_edit_history() returned true but we're missing
             // an arm. This is a programming error.
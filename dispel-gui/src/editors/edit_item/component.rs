@@ -1,4 +1,5 @@
 use crate::components::editable::EditableRecord;
+use dispel_core::references::usages::RecordKind;
 use dispel_core::{EditItem, EditItemEffect, EditItemModification};
 
 use crate::editable_record_fields;
@@ -47,4 +48,7 @@ impl EditableRecord for EditItem {
     fn detail_width() -> f32 {
         340.0
     }
+    fn record_kind() -> Option<RecordKind> {
+        Some(RecordKind::EditItem)
+    }
 }
@@ -1,5 +1,6 @@
 use crate::components::editable::EditableRecord;
 use dispel_core::EventItem;
+use dispel_core::references::usages::RecordKind;
 
 crate::editable_record_fields!(EventItem, {
     { name = String / "Name:" },
@@ -26,4 +27,7 @@ impl EditableRecord for EventItem {
     fn detail_width() -> f32 {
         320.0
     }
+    fn record_kind() -> Option<RecordKind> {
+        Some(RecordKind::EventItem)
+    }
 }
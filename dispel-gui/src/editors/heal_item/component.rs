@@ -1,4 +1,5 @@
 use crate::components::editable::{EditableRecord, FieldKind};
+use dispel_core::references::usages::RecordKind;
 use dispel_core::{HealItem, HealItemFlag};
 
 use crate::editable_record_fields;
@@ -73,4 +74,7 @@ impl EditableRecord for HealItem {
     fn detail_width() -> f32 {
         320.0
     }
+    fn record_kind() -> Option<RecordKind> {
+        Some(RecordKind::HealItem)
+    }
 }
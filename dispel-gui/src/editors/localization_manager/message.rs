@@ -1,4 +1,5 @@
 use dispel_core::TextEntry;
+use dispel_core::localization::TransliterationTable;
 use iced::widget::text_editor;
 use std::path::PathBuf;
 
@@ -18,10 +19,15 @@ pub enum LocalizationMessage {
     ToggleOverlongOnly,
     ExportCsv,
     ExportPo,
+    ExportXliff,
     ExportDone(Result<(), String>),
     ImportFile,
     Imported(Result<Vec<TextEntry>, String>),
     TargetLangChanged(String),
+    FillFromMemory,
+    Transliterate,
+    LoadTransliterationTable,
+    TransliterationTableLoaded(Result<Option<(String, TransliterationTable)>, String>),
     ModNameChanged(String),
     ModVersionChanged(String),
     ModAuthorChanged(String),
@@ -1,7 +1,10 @@
 use crate::components::loading_state::LoadingState;
 use crate::editors::mod_packager::state::ModMetadata;
 use dispel_core::TextEntry;
+use dispel_core::localization::transliterate::unrepresentable_chars;
+use dispel_core::localization::{TransliterationTable, project};
 use gui_widgets::TextAreaContent;
+use std::path::Path;
 
 #[derive(Debug)]
 pub struct LocalizationManagerState {
@@ -17,6 +20,10 @@ pub struct LocalizationManagerState {
     pub translation_content: TextAreaContent,
     pub search_query: String,
     pub page: usize,
+    /// Applied by "Transliterate" and before encoding on apply.
+    pub transliteration: TransliterationTable,
+    /// File name of the loaded table, or "built-in".
+    pub transliteration_label: String,
 }
 
 impl Default for LocalizationManagerState {
@@ -34,6 +41,8 @@ impl Default for LocalizationManagerState {
             translation_content: TextAreaContent::with_text(""),
             search_query: String::new(),
             page: 0,
+            transliteration: TransliterationTable::builtin(),
+            transliteration_label: "built-in".into(),
         }
     }
 }
@@ -54,6 +63,23 @@ impl LocalizationManagerState {
             .count()
     }
 
+    /// Translated entries containing characters their encoding cannot hold,
+    /// even after the transliteration table.
+    pub fn lossy_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| {
+                e.is_translated()
+                    && e.is_lossy()
+                    && !unrepresentable_chars(
+                        &self.transliteration.apply(&e.translation, &e.encoding),
+                        &e.encoding,
+                    )
+                    .is_empty()
+            })
+            .count()
+    }
+
     /// Filtered view of entries based on current filter settings.
     /// Returns (global_index, entry) pairs so the view can dispatch SelectEntry with the correct index.
     pub fn visible_entries(&self) -> Vec<(usize, &TextEntry)> {
@@ -70,7 +96,9 @@ impl LocalizationManagerState {
                     .as_deref()
                     .is_none_or(|f| e.file_path.contains(f));
                 let translated_ok = !self.show_untranslated_only || !e.is_translated();
-                let overlong_ok = !self.show_overlong_only || e.would_truncate();
+                let overlong_ok = !self.show_overlong_only
+                    || e.would_truncate()
+                    || (e.is_translated() && e.is_lossy());
                 let search_ok = q.is_empty()
                     || e.original.to_lowercase().contains(&q)
                     || e.translation.to_lowercase().contains(&q);
@@ -98,13 +126,7 @@ impl LocalizationManagerState {
         if name.is_empty() || game_path.is_empty() {
             return None;
         }
-        let mod_name = name.replace(' ', "_");
-        Some(
-            std::path::PathBuf::from(game_path)
-                .join("mods")
-                .join(&mod_name)
-                .join("session.json"),
-        )
+        Some(project::session_path(Path::new(game_path), name))
     }
 
     /// Backup directory for the current mod name.
@@ -113,13 +135,7 @@ impl LocalizationManagerState {
         if name.is_empty() || game_path.is_empty() {
             return None;
         }
-        let mod_name = name.replace(' ', "_");
-        Some(
-            std::path::PathBuf::from(game_path)
-                .join("mods")
-                .join(&mod_name)
-                .join("backup"),
-        )
+        Some(project::backup_dir(Path::new(game_path), name))
     }
 
     /// True if a backup exists for the current mod name.
@@ -2,16 +2,19 @@ use crate::app::App;
 use crate::components::loading_state::LoadingState;
 use crate::editors::localization_manager::LocalizationMessage;
 use crate::message::MessageExt;
-use dispel_core::localization::Localizable;
+use dispel_core::localization::project::{self, LocalizationProject};
+use dispel_core::localization::transliterate::transliterate_entries;
+use dispel_core::localization::{TranslationMemory, TransliterationTable};
+use dispel_core::modding::{ModManifest, write_zip};
 use dispel_core::{
-    DialogueParagraph, EditItem, EventItem, EventNpcRef, ExtraRef, Extractor, HealItem, Map,
-    Message, MiscItem, MonsterIni, NPC, PartyIniNpc, PartyRef, Quest, Store, TextEntry, WeaponItem,
-    export_csv, export_po, import_csv, import_po,
+    TextEntry, export_csv, export_po, export_xliff, import_csv, import_po, import_xliff,
 };
 use iced::Task;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 
+/// Similarity threshold for translation-memory fuzzy matches.
+const FUZZY_MIN_SCORE: f32 = 0.85;
+
 pub fn handle(message: LocalizationMessage, app: &mut App) -> Task<crate::message::Message> {
     match message {
         LocalizationMessage::Scan => {
@@ -101,7 +104,9 @@ pub fn handle(message: LocalizationMessage, app: &mut App) -> Task<crate::messag
                 if let Some(idx) = app.state.editors.localization_manager.selected_idx
                     && let Some(entry) = app.state.editors.localization_manager.entries.get_mut(idx)
                 {
+                    // Editing a fuzzy fill confirms it.
                     entry.translation = text;
+                    entry.needs_review = false;
                 }
                 // Debounced session save
                 let game_path = app.state.shared_game_path.clone();
@@ -238,6 +243,28 @@ pub fn handle(message: LocalizationMessage, app: &mut App) -> Task<crate::messag
                 },
             )
         }
+        LocalizationMessage::ExportXliff => {
+            let entries = app.state.editors.localization_manager.entries.clone();
+            let target_lang = app.state.editors.localization_manager.target_lang.clone();
+            Task::perform(
+                async move {
+                    let xliff = export_xliff(&entries, "ko", &target_lang);
+                    let path = rfd::AsyncFileDialog::new()
+                        .set_file_name("localization.xlf")
+                        .add_filter("XLIFF 2.0", &["xlf", "xliff"])
+                        .save_file()
+                        .await
+                        .map(|h| h.path().to_path_buf());
+                    if let Some(p) = path {
+                        std::fs::write(&p, xliff.as_bytes()).map_err(|e| e.to_string())?;
+                    }
+                    Ok::<(), String>(())
+                },
+                |result| {
+                    crate::message::Message::localization(LocalizationMessage::ExportDone(result))
+                },
+            )
+        }
         LocalizationMessage::ExportDone(result) => {
             if let Err(e) = result {
                 app.state.editors.localization_manager.status_msg = format!("Export failed: {e}");
@@ -249,7 +276,7 @@ pub fn handle(message: LocalizationMessage, app: &mut App) -> Task<crate::messag
             Task::perform(
                 async move {
                     let handle = rfd::AsyncFileDialog::new()
-                        .add_filter("CSV or PO", &["csv", "po"])
+                        .add_filter("CSV, PO or XLIFF", &["csv", "po", "xlf", "xliff"])
                         .pick_file()
                         .await;
                     let Some(handle) = handle else {
@@ -257,10 +284,18 @@ pub fn handle(message: LocalizationMessage, app: &mut App) -> Task<crate::messag
                     };
                     let path = handle.path().to_path_buf();
                     let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-                    if path.extension().and_then(|e| e.to_str()) == Some("po") {
-                        import_po(&content, &mut current_entries);
-                    } else {
-                        import_csv(&content, &mut current_entries).map_err(|e| e.to_string())?;
+                    match path.extension().and_then(|e| e.to_str()) {
+                        Some("po") => {
+                            import_po(&content, &mut current_entries);
+                        }
+                        Some("xlf" | "xliff") => {
+                            import_xliff(&content, &mut current_entries)
+                                .map_err(|e| e.to_string())?;
+                        }
+                        _ => {
+                            import_csv(&content, &mut current_entries)
+                                .map_err(|e| e.to_string())?;
+                        }
                     }
                     Ok(current_entries)
                 },
@@ -312,6 +347,100 @@ pub fn handle(message: LocalizationMessage, app: &mut App) -> Task<crate::messag
             }
             Task::none()
         }
+        LocalizationMessage::FillFromMemory => {
+            let state = &mut app.state.editors.localization_manager;
+            let tm = TranslationMemory::from_entries(&state.entries);
+            let fills = tm.fill(&mut state.entries, FUZZY_MIN_SCORE);
+            let exact = fills.iter().filter(|f| f.is_exact()).count();
+            state.status_msg = format!(
+                "Memory: filled {exact} exact and {} fuzzy matches (marked for review; edit to confirm).",
+                fills.len() - exact
+            );
+            if let Some(idx) = state.selected_idx
+                && let Some(entry) = state.entries.get(idx)
+            {
+                state.translation_content =
+                    gui_widgets::TextAreaContent::with_text(&entry.translation);
+            }
+            let game_path = app.state.shared_game_path.clone();
+            if let Some(path) = app
+                .state
+                .editors
+                .localization_manager
+                .session_path(&game_path)
+            {
+                let entries = app.state.editors.localization_manager.entries.clone();
+                return Task::perform(async move { save_session(&path, &entries) }, |r| {
+                    crate::message::Message::localization(LocalizationMessage::ExportDone(r))
+                });
+            }
+            Task::none()
+        }
+        LocalizationMessage::Transliterate => {
+            let state = &mut app.state.editors.localization_manager;
+            let changed = transliterate_entries(&mut state.entries, &state.transliteration);
+            let remaining = state.lossy_count();
+            state.status_msg = if remaining > 0 {
+                format!(
+                    "Transliterated {changed} strings; {remaining} still contain unrepresentable characters."
+                )
+            } else {
+                format!("Transliterated {changed} strings.")
+            };
+            if let Some(idx) = state.selected_idx
+                && let Some(entry) = state.entries.get(idx)
+            {
+                state.translation_content =
+                    gui_widgets::TextAreaContent::with_text(&entry.translation);
+            }
+            let game_path = app.state.shared_game_path.clone();
+            if let Some(path) = app
+                .state
+                .editors
+                .localization_manager
+                .session_path(&game_path)
+            {
+                let entries = app.state.editors.localization_manager.entries.clone();
+                return Task::perform(async move { save_session(&path, &entries) }, |r| {
+                    crate::message::Message::localization(LocalizationMessage::ExportDone(r))
+                });
+            }
+            Task::none()
+        }
+        LocalizationMessage::LoadTransliterationTable => Task::perform(
+            async move {
+                let handle = rfd::AsyncFileDialog::new()
+                    .add_filter("Transliteration CSV", &["csv"])
+                    .pick_file()
+                    .await;
+                let Some(handle) = handle else {
+                    return Ok(None);
+                };
+                let content = std::fs::read_to_string(handle.path()).map_err(|e| e.to_string())?;
+                let mut table = TransliterationTable::builtin();
+                table.merge(&TransliterationTable::from_csv(&content).map_err(|e| e.to_string())?);
+                Ok(Some((handle.file_name(), table)))
+            },
+            |result| {
+                crate::message::Message::localization(
+                    LocalizationMessage::TransliterationTableLoaded(result),
+                )
+            },
+        ),
+        LocalizationMessage::TransliterationTableLoaded(result) => {
+            let state = &mut app.state.editors.localization_manager;
+            match result {
+                Ok(Some((name, table))) => {
+                    state.status_msg =
+                        format!("Transliteration table {name}: {} mappings.", table.len());
+                    state.transliteration = table;
+                    state.transliteration_label = name;
+                }
+                Ok(None) => {}
+                Err(e) => state.status_msg = format!("Could not load table: {e}"),
+            }
+            Task::none()
+        }
         LocalizationMessage::ModNameChanged(v) => {
             app.state.editors.localization_manager.mod_metadata.name = v;
             Task::none()
@@ -338,9 +467,10 @@ pub fn handle(message: LocalizationMessage, app: &mut App) -> Task<crate::messag
             state.status_msg = "Applying translations…".into();
             let entries = state.entries.clone();
             let meta = state.mod_metadata.clone();
+            let table = state.transliteration.clone();
             let game_path = PathBuf::from(&app.state.shared_game_path);
             Task::perform(
-                async move { apply_and_package(&game_path, &entries, &meta) },
+                async move { apply_and_package(&game_path, &entries, &meta, table) },
                 |result| {
                     crate::message::Message::localization(LocalizationMessage::Applied(result))
                 },
@@ -397,384 +527,63 @@ pub fn handle(message: LocalizationMessage, app: &mut App) -> Task<crate::messag
     }
 }
 
-// ─── Session persistence ──────────────────────────────────────────────────────
-
-/// Lightweight session record — fully owned so it serializes/deserializes without lifetime issues.
-/// `TextEntry.field_name` is `&'static str` and cannot be deserialized from JSON directly.
-#[derive(serde::Serialize, serde::Deserialize)]
-struct SavedTranslation {
-    file_path: String,
-    record_id: usize,
-    field_name: String,
-    translation: String,
-}
-
-fn save_session(path: &Path, entries: &[TextEntry]) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let saved: Vec<SavedTranslation> = entries
-        .iter()
-        .filter(|e| e.is_translated())
-        .map(|e| SavedTranslation {
-            file_path: e.file_path.clone(),
-            record_id: e.record_id,
-            field_name: e.field_name.to_owned(),
-            translation: e.translation.clone(),
-        })
-        .collect();
-    let json = serde_json::to_string(&saved).map_err(|e| e.to_string())?;
-    std::fs::write(path, json.as_bytes()).map_err(|e| e.to_string())
-}
-
-fn load_session(path: &Path) -> Option<Vec<SavedTranslation>> {
-    let s = std::fs::read_to_string(path).ok()?;
-    serde_json::from_str(&s).ok()
-}
-
-/// Merge saved translations into freshly-scanned entries.
-/// Matches by (file_path, record_id, field_name).
-fn merge_session(entries: &mut [TextEntry], saved: &[SavedTranslation]) {
-    use std::collections::HashMap;
-    let saved_map: HashMap<(&str, usize, &str), &str> = saved
-        .iter()
-        .map(|e| {
-            (
-                (e.file_path.as_str(), e.record_id, e.field_name.as_str()),
-                e.translation.as_str(),
-            )
-        })
-        .collect();
-    for entry in entries.iter_mut() {
-        if let Some(&t) =
-            saved_map.get(&(entry.file_path.as_str(), entry.record_id, entry.field_name))
-            && !t.is_empty()
-        {
-            entry.translation = t.to_owned();
-        }
-    }
-}
-
-// ─── Scan ─────────────────────────────────────────────────────────────────────
-
-fn scan_one<T: Extractor + Localizable>(
-    game_path: &Path,
-    rel: &str,
-    entries: &mut Vec<TextEntry>,
-) -> Result<(), String> {
-    let abs = game_path.join(rel.replace('/', std::path::MAIN_SEPARATOR_STR));
-    if !abs.exists() {
-        return Ok(());
-    }
-    let records = T::read_file(&abs).map_err(|e| e.to_string())?;
-    for (i, record) in records.iter().enumerate() {
-        entries.extend(record.extract_texts(i, rel));
-    }
-    Ok(())
-}
-
-const NPC_REF_FILES: &[&str] = &[
-    "NpcInGame/Npccat1.ref",
-    "NpcInGame/Npccat2.ref",
-    "NpcInGame/Npccat3.ref",
-    "NpcInGame/Npccatp.ref",
-    "NpcInGame/npcdun08.ref",
-    "NpcInGame/npcdun19.ref",
-    "NpcInGame/Npcmap1.ref",
-    "NpcInGame/Npcmap2.ref",
-    "NpcInGame/Npcmap3.ref",
-];
-
-const EXTRA_REF_FILES: &[&str] = &[
-    "ExtraInGame/Extcat3.ref",
-    "ExtraInGame/Extdun01.ref",
-    "ExtraInGame/Extdun02.ref",
-    "ExtraInGame/Extdun03.ref",
-    "ExtraInGame/Extdun04.ref",
-    "ExtraInGame/Extdun05.ref",
-    "ExtraInGame/Extdun06.ref",
-    "ExtraInGame/Extdun07.ref",
-    "ExtraInGame/Extdun08.ref",
-    "ExtraInGame/Extdun09.ref",
-    "ExtraInGame/Extdun10.ref",
-    "ExtraInGame/Extdun11.ref",
-    "ExtraInGame/Extdun12.ref",
-    "ExtraInGame/Extdun13.ref",
-    "ExtraInGame/Extdun14.ref",
-    "ExtraInGame/Extdun15.ref",
-    "ExtraInGame/Extdun16.ref",
-    "ExtraInGame/Extdun17.ref",
-    "ExtraInGame/Extdun18.ref",
-    "ExtraInGame/Extdun19.ref",
-    "ExtraInGame/Extdun20.ref",
-    "ExtraInGame/Extdun21.ref",
-    "ExtraInGame/Extdun22.ref",
-    "ExtraInGame/Extdun23.ref",
-    "ExtraInGame/Extdun24.ref",
-    "ExtraInGame/Extdun25.ref",
-    "ExtraInGame/Extfinal.ref",
-    "ExtraInGame/Extmap1.ref",
-    "ExtraInGame/Extmap2.ref",
-    "ExtraInGame/Extmap3.ref",
-];
+// ─── Pipeline glue ────────────────────────────────────────────────────────────
 
 fn scan_all_entries(
     game_path: &Path,
     session_path: Option<&Path>,
 ) -> Result<Vec<TextEntry>, String> {
-    let mut entries = Vec::new();
-
-    // Store.db — uses record.index as logical ID, not position
-    let store_path = game_path.join("CharacterInGame").join("STORE.DB");
-    if store_path.exists() {
-        let records = Store::read_file(&store_path).map_err(|e| e.to_string())?;
-        for (i, record) in records.iter().enumerate() {
-            entries.extend(record.extract_texts(i, "CharacterInGame/STORE.DB"));
-        }
-    }
-
-    scan_one::<WeaponItem>(game_path, "CharacterInGame/weaponItem.db", &mut entries)?;
-    scan_one::<HealItem>(game_path, "CharacterInGame/HealItem.db", &mut entries)?;
-    scan_one::<EditItem>(game_path, "CharacterInGame/EditItem.db", &mut entries)?;
-    scan_one::<EventItem>(game_path, "CharacterInGame/EventItem.db", &mut entries)?;
-    scan_one::<MiscItem>(game_path, "CharacterInGame/MiscItem.db", &mut entries)?;
-    scan_one::<Message>(game_path, "ExtraInGame/Message.scr", &mut entries)?;
-    scan_one::<PartyIniNpc>(game_path, "NpcInGame/PrtIni.db", &mut entries)?;
-    scan_one::<EventNpcRef>(game_path, "NpcInGame/Eventnpc.ref", &mut entries)?;
-    scan_one::<Quest>(game_path, "ExtraInGame/Quest.scr", &mut entries)?;
-    scan_one::<Map>(game_path, "AllMap.ini", &mut entries)?;
-    scan_one::<MonsterIni>(game_path, "Monster.ini", &mut entries)?;
-    scan_one::<PartyRef>(game_path, "Ref/PartyRef.ref", &mut entries)?;
-
-    for rel in NPC_REF_FILES {
-        scan_one::<NPC>(game_path, rel, &mut entries)?;
-    }
-    for rel in EXTRA_REF_FILES {
-        scan_one::<ExtraRef>(game_path, rel, &mut entries)?;
-    }
-
-    // Dialogue paragraphs — scan all *.pgp files
-    scan_pgp_files(game_path, &mut entries)?;
-
+    let mut project = LocalizationProject::scan(game_path).map_err(|e| e.to_string())?;
     // Merge saved session translations on top of fresh scan
-    if let Some(path) = session_path
-        && let Some(saved) = load_session(path)
-    {
-        merge_session(&mut entries, &saved);
+    if let Some(path) = session_path {
+        project
+            .merge_session_file(path)
+            .map_err(|e| e.to_string())?;
     }
-
-    Ok(entries)
-}
-
-fn scan_pgp_files(game_path: &Path, entries: &mut Vec<TextEntry>) -> Result<(), String> {
-    for entry in walkdir(game_path) {
-        let path = entry.map_err(|e| e.to_string())?;
-        if path.extension().and_then(|e| e.to_str()) == Some("pgp") {
-            let rel = path
-                .strip_prefix(game_path)
-                .unwrap_or(&path)
-                .to_string_lossy()
-                .replace('\\', "/");
-            let records = DialogueParagraph::read_file(&path).map_err(|e| e.to_string())?;
-            for (i, record) in records.iter().enumerate() {
-                entries.extend(record.extract_texts(i, &rel));
-            }
-        }
-    }
-    Ok(())
+    Ok(project.entries)
 }
 
-fn walkdir(root: &Path) -> impl Iterator<Item = Result<PathBuf, std::io::Error>> {
-    fn collect(dir: &Path, out: &mut Vec<Result<PathBuf, std::io::Error>>) {
-        let Ok(rd) = std::fs::read_dir(dir) else {
-            return;
-        };
-        for entry in rd.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                collect(&path, out);
-            } else {
-                out.push(Ok(path));
-            }
-        }
-    }
-    let mut results = Vec::new();
-    collect(root, &mut results);
-    results.into_iter()
+fn save_session(path: &Path, entries: &[TextEntry]) -> Result<(), String> {
+    project::save_session(path, entries).map_err(|e| e.to_string())
 }
 
-// ─── Apply & Package ──────────────────────────────────────────────────────────
-
+/// Apply translations in place (backing up originals) and package them as a
+/// mod zip under `<game>/mod_output/<name>.zip`. `transliteration` runs
+/// before encoding.
 fn apply_and_package(
     game_path: &Path,
     entries: &[TextEntry],
     meta: &crate::editors::mod_packager::state::ModMetadata,
+    transliteration: TransliterationTable,
 ) -> Result<PathBuf, String> {
-    let mod_name = meta.name.trim().replace(' ', "_");
-    let backup_dir = game_path.join("mods").join(&mod_name).join("backup");
-    std::fs::create_dir_all(&backup_dir).map_err(|e| e.to_string())?;
-
-    // Group translated entries by file_path
-    let mut by_file: std::collections::HashMap<&str, Vec<&TextEntry>> =
-        std::collections::HashMap::new();
-    for e in entries {
-        if e.is_translated() {
-            by_file.entry(&e.file_path).or_default().push(e);
-        }
-    }
-
-    if by_file.is_empty() {
-        return Err("No translated strings to apply.".into());
-    }
-
-    for (rel_path, file_entries) in &by_file {
-        let abs_path = game_path.join(rel_path.replace('/', std::path::MAIN_SEPARATOR_STR));
-        if !abs_path.exists() {
-            continue;
-        }
-        // Backup original (preserve relative path structure inside backup dir)
-        let rel_pb = PathBuf::from(rel_path);
-        let backup_path = if let Some(parent) = rel_pb.parent() {
-            let d = backup_dir.join(parent);
-            std::fs::create_dir_all(&d).map_err(|e| e.to_string())?;
-            d.join(rel_pb.file_name().unwrap_or_default())
-        } else {
-            backup_dir.join(rel_pb.file_name().unwrap_or_default())
-        };
-        if !backup_path.exists() {
-            std::fs::copy(&abs_path, &backup_path).map_err(|e| e.to_string())?;
-        }
-        apply_entries_to_file(&abs_path, rel_path, file_entries)?;
-    }
+    let project = LocalizationProject {
+        game_path: game_path.to_path_buf(),
+        entries: entries.to_vec(),
+        allow_lossy: false,
+        include_unreviewed: false,
+        transliteration: Some(transliteration),
+    };
+    let backup_dir = project::backup_dir(game_path, &meta.name);
+    project.apply(&backup_dir).map_err(|e| e.to_string())?;
+
+    let mut manifest = ModManifest::new(meta.name.trim());
+    manifest.version = meta.version.clone();
+    manifest.author = meta.author.clone();
+    manifest.description = meta.description.clone();
+    let package = project
+        .package(manifest, Some(&backup_dir))
+        .map_err(|e| e.to_string())?;
 
-    // Package as zip — use rel_path as entry name to preserve directory structure (A4)
     let output_dir = game_path.join("mod_output");
     std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-    let zip_path = output_dir.join(format!("{}.zip", mod_name));
+    let zip_path = output_dir.join(format!("{}.zip", meta.name.trim().replace(' ', "_")));
     let file = std::fs::File::create(&zip_path).map_err(|e| e.to_string())?;
-    let mut zip = zip::ZipWriter::new(file);
-    let options = zip::write::SimpleFileOptions::default()
-        .compression_method(zip::CompressionMethod::Deflated);
-
-    for rel_path in by_file.keys() {
-        let abs_path = game_path.join(rel_path.replace('/', std::path::MAIN_SEPARATOR_STR));
-        if abs_path.exists() {
-            // Use rel_path as zip entry name — preserves directory structure
-            zip.start_file(*rel_path, options)
-                .map_err(|e| e.to_string())?;
-            let data = std::fs::read(&abs_path).map_err(|e| e.to_string())?;
-            zip.write_all(&data).map_err(|e| e.to_string())?;
-        }
-    }
-
-    let manifest = serde_json::json!({
-        "name": meta.name,
-        "version": meta.version,
-        "author": meta.author,
-        "description": meta.description,
-        "files": by_file.keys().collect::<Vec<_>>(),
-        "backup_dir": backup_dir.to_string_lossy(),
-    });
-    zip.start_file("manifest.json", options)
-        .map_err(|e| e.to_string())?;
-    zip.write_all(&serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?)
-        .map_err(|e| e.to_string())?;
-    zip.finish().map_err(|e| e.to_string())?;
-
+    write_zip(file, &package).map_err(|e| e.to_string())?;
     Ok(zip_path)
 }
 
-// A2: generic helper eliminates repeated read→apply→save pattern
-fn apply_one<T: Extractor + Localizable>(
-    abs_path: &Path,
-    by_record: &std::collections::HashMap<usize, Vec<&TextEntry>>,
-) -> Result<(), String> {
-    let mut records = T::read_file(abs_path).map_err(|e| e.to_string())?;
-    for (i, record) in records.iter_mut().enumerate() {
-        if let Some(entries) = by_record.get(&i) {
-            let owned: Vec<TextEntry> = entries.iter().map(|e| (*e).clone()).collect();
-            record.apply_texts(&owned);
-        }
-    }
-    T::save_file(&records, abs_path).map_err(|e| e.to_string())
-}
-
-fn apply_entries_to_file(
-    abs_path: &Path,
-    rel_path: &str,
-    file_entries: &[&TextEntry],
-) -> Result<(), String> {
-    let mut by_record: std::collections::HashMap<usize, Vec<&TextEntry>> =
-        std::collections::HashMap::new();
-    for e in file_entries {
-        by_record.entry(e.record_id).or_default().push(e);
-    }
-
-    let lower = rel_path.to_lowercase();
-
-    if lower.ends_with("store.db") {
-        // Store uses record.index as the logical ID, not position
-        let mut records = Store::read_file(abs_path).map_err(|e| e.to_string())?;
-        for record in &mut records {
-            let idx = record.index as usize;
-            if let Some(entries) = by_record.get(&idx) {
-                let owned: Vec<TextEntry> = entries.iter().map(|e| (*e).clone()).collect();
-                record.apply_texts(&owned);
-            }
-        }
-        Store::save_file(&records, abs_path).map_err(|e| e.to_string())
-    } else if lower.ends_with("weaponitem.db") {
-        apply_one::<WeaponItem>(abs_path, &by_record)
-    } else if lower.ends_with("healitem.db") {
-        apply_one::<HealItem>(abs_path, &by_record)
-    } else if lower.ends_with("edititem.db") {
-        apply_one::<EditItem>(abs_path, &by_record)
-    } else if lower.ends_with("eventitem.db") {
-        apply_one::<EventItem>(abs_path, &by_record)
-    } else if lower.ends_with("miscitem.db") {
-        apply_one::<MiscItem>(abs_path, &by_record)
-    } else if lower.ends_with("message.scr") {
-        apply_one::<Message>(abs_path, &by_record)
-    } else if lower.ends_with("prtini.db") {
-        apply_one::<PartyIniNpc>(abs_path, &by_record)
-    } else if lower.ends_with("eventnpc.ref") {
-        apply_one::<EventNpcRef>(abs_path, &by_record)
-    } else if lower.contains("npcingame/") && lower.ends_with(".ref") {
-        apply_one::<NPC>(abs_path, &by_record)
-    } else if lower.contains("extraingame/") && lower.ends_with(".ref") {
-        apply_one::<ExtraRef>(abs_path, &by_record)
-    } else if lower.ends_with(".pgp") {
-        apply_one::<DialogueParagraph>(abs_path, &by_record)
-    } else if lower.ends_with("quest.scr") {
-        apply_one::<Quest>(abs_path, &by_record)
-    } else if lower.ends_with("allmap.ini") {
-        apply_one::<Map>(abs_path, &by_record)
-    } else if lower.ends_with("monster.ini") {
-        apply_one::<MonsterIni>(abs_path, &by_record)
-    } else if lower.ends_with("partyref.ref") {
-        apply_one::<PartyRef>(abs_path, &by_record)
-    } else {
-        Ok(())
-    }
-}
-
-// ─── Revert ───────────────────────────────────────────────────────────────────
-
 fn revert_from_backup(game_path: &Path, backup_dir: &Path) -> Result<(), String> {
-    // Walk backup dir; for each file, restore to game_path keeping relative structure
-    for entry in walkdir(backup_dir) {
-        let src = entry.map_err(|e| e.to_string())?;
-        let rel = src
-            .strip_prefix(backup_dir)
-            .unwrap_or(&src)
-            .to_string_lossy()
-            .replace('\\', "/");
-        let dest = game_path.join(rel.replace('/', std::path::MAIN_SEPARATOR_STR));
-        if let Some(parent) = dest.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-        }
-        std::fs::copy(&src, &dest)
-            .map_err(|e| format!("Failed to restore {}: {e}", dest.display()))?;
-    }
-    Ok(())
+    project::revert(game_path, backup_dir)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
 }
@@ -56,6 +56,32 @@ pub fn view(app: &App) -> Element<'_, Message> {
     } else {
         Some(Message::localization(LocalizationMessage::ExportPo))
     });
+    let xliff_btn =
+        button(text("Export XLIFF").size(12)).on_press_maybe(if is_loading || !has_entries {
+            None
+        } else {
+            Some(Message::localization(LocalizationMessage::ExportXliff))
+        });
+    let tm_btn =
+        button(text("Fill from TM").size(12)).on_press_maybe(if is_loading || !has_entries {
+            None
+        } else {
+            Some(Message::localization(LocalizationMessage::FillFromMemory))
+        });
+    let translit_btn =
+        button(text("Transliterate").size(12)).on_press_maybe(if is_loading || !has_entries {
+            None
+        } else {
+            Some(Message::localization(LocalizationMessage::Transliterate))
+        });
+    let table_btn = button(text(format!("Table: {}", state.transliteration_label)).size(12))
+        .on_press_maybe(if is_loading {
+            None
+        } else {
+            Some(Message::localization(
+                LocalizationMessage::LoadTransliterationTable,
+            ))
+        });
     let import_btn =
         button(text("Import…").size(12)).on_press_maybe(if is_loading || !has_entries {
             None
@@ -68,9 +94,19 @@ pub fn view(app: &App) -> Element<'_, Message> {
         .width(Length::Fixed(90.0))
         .size(12);
 
-    let toolbar = row![scan_btn, csv_btn, po_btn, target_lang_input, import_btn]
-        .spacing(6)
-        .align_y(Alignment::Center);
+    let toolbar = row![
+        scan_btn,
+        csv_btn,
+        po_btn,
+        xliff_btn,
+        target_lang_input,
+        import_btn,
+        tm_btn,
+        translit_btn,
+        table_btn
+    ]
+    .spacing(6)
+    .align_y(Alignment::Center);
 
     // ── Filter row ───────────────────────────────────────────────────────
     let mut filter_options = vec![FileFilter::All];
@@ -106,10 +142,16 @@ pub fn view(app: &App) -> Element<'_, Message> {
     .align_y(Alignment::Center);
 
     let overlong_count = state.overlong_count();
+    let lossy_count = state.lossy_count();
+    let overlong_label = if lossy_count > 0 {
+        format!("Overlong ({overlong_count}) / lossy ({lossy_count})")
+    } else {
+        format!("Overlong ({overlong_count})")
+    };
     let overlong_toggle = row![
         checkbox(state.show_overlong_only)
             .on_toggle(|_| Message::localization(LocalizationMessage::ToggleOverlongOnly)),
-        text(format!("Overlong ({overlong_count})")).size(12),
+        text(overlong_label).size(12),
     ]
     .spacing(4)
     .align_y(Alignment::Center);
@@ -153,8 +195,8 @@ pub fn view(app: &App) -> Element<'_, Message> {
         .map(|(idx, entry)| {
             let idx = *idx;
             let is_selected = state.selected_idx == Some(idx);
-            let is_overlong = entry.would_truncate();
             let is_translated = entry.is_translated();
+            let is_overlong = entry.would_truncate() || (is_translated && entry.is_lossy());
 
             // Status indicator
             let indicator = if is_overlong {
@@ -164,6 +206,14 @@ pub fn view(app: &App) -> Element<'_, Message> {
                     .style(|_t| iced::widget::text::Style {
                         color: Some(Color::from_rgb(0.85, 0.2, 0.2)),
                     })
+            } else if is_translated && entry.needs_review {
+                // Fuzzy memory fill awaiting review
+                text(icon_char(Icon::Check))
+                    .font(LUCIDE_FONT)
+                    .size(11)
+                    .style(|_t| iced::widget::text::Style {
+                        color: Some(Color::from_rgb(0.9, 0.65, 0.2)),
+                    })
             } else if is_translated {
                 text(icon_char(Icon::Check))
                     .font(LUCIDE_FONT)
@@ -344,6 +394,23 @@ pub fn view(app: &App) -> Element<'_, Message> {
                 .spacing(8)
                 .align_y(Alignment::Center);
 
+            // Characters the target encoding would replace
+            let bad_chars = entry.unrepresentable_chars();
+            let lossy_line: Element<'_, Message> = if bad_chars.is_empty() {
+                text("").size(11).into()
+            } else {
+                text(format!(
+                    "Not representable in {}: {} — transliterate or rephrase before applying.",
+                    entry.encoding.label(),
+                    bad_chars.iter().collect::<String>()
+                ))
+                .size(11)
+                .style(|_t| iced::widget::text::Style {
+                    color: Some(Color::from_rgb(0.85, 0.2, 0.2)),
+                })
+                .into()
+            };
+
             column![
                 context_line,
                 encoding_line,
@@ -352,6 +419,7 @@ pub fn view(app: &App) -> Element<'_, Message> {
                 translation_label,
                 translation_editor,
                 nav_row,
+                lossy_line,
             ]
             .spacing(6)
             .padding([8, 12])
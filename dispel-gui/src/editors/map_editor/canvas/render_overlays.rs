@@ -275,6 +275,55 @@ impl<'a> canvas::Program<Message> for MapCanvasOverlaysLayer<'a> {
                         }
                     }
 
+                    // Placement issues: red (error) or orange (warning)
+                    // diamond outline with the issue text above it.
+                    if self.state.view.show_placement_issues {
+                        use dispel_core::map::placement::Severity;
+                        for issue in &self.state.data.placement_issues {
+                            let (px, py) = tile_to_screen(
+                                issue.tile_x,
+                                issue.tile_y,
+                                diagonal,
+                                pan_x,
+                                pan_y,
+                                zoom,
+                            );
+                            if !is_visible(px, py, TILE_W * zoom, TILE_H * zoom, bounds) {
+                                continue;
+                            }
+                            let color = match issue.severity {
+                                Severity::Error => Color::from_rgb(0.95, 0.15, 0.15),
+                                Severity::Warning => Color::from_rgb(1.0, 0.6, 0.1),
+                            };
+                            let (tile_cx, tile_cy) = tile_center(px, py, zoom);
+                            let r = 12.0 * zoom;
+                            frame.stroke(
+                                &diamond_path(tile_cx, tile_cy, r),
+                                canvas::Stroke::default()
+                                    .with_color(color)
+                                    .with_width(2.0 * zoom),
+                            );
+                            let label_size = (10.0 * zoom).max(6.0);
+                            frame.fill_text(CanvasText {
+                                content: format!(
+                                    "{} #{} {}",
+                                    issue.entity, issue.record, issue.message
+                                ),
+                                position: Point::new(tile_cx, tile_cy - r - 2.0 * zoom),
+                                color,
+                                size: iced::Pixels(label_size),
+                                font: Font::DEFAULT,
+                                align_x: TextAlignment::Center,
+                                align_y: alignment::Vertical::Bottom,
+                                shaping: iced::widget::text::Shaping::Basic,
+                                line_height: iced::widget::text::LineHeight::default(),
+                                max_width: f32::INFINITY,
+                                ellipsis: iced::widget::text::Ellipsis::None,
+                                wrapping: iced::widget::text::Wrapping::None,
+                            });
+                        }
+                    }
+
                     // Selection ring
                     if let Some(sel) = self.state.view.selected_entity
                         && let Some((stx, sty)) = entity_tile(sel, self.state)
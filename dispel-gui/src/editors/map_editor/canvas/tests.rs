@@ -819,3 +819,39 @@ fn test_diamond_path_creates_valid_path() {
     // Canvas Path is opaque — just ensure it doesn't panic and has the right type.
     let _ = path; // (lint guard)
 }
+
+// ── Placement issues layer ────────────────────────────────────────────────────
+
+#[test]
+fn test_placement_issues_follow_layer_visibility() {
+    let monster = MonsterRef {
+        map_x: 2,
+        map_y: 2,
+        ..Default::default()
+    };
+    let collisions = HashMap::from([((2, 2), true)]);
+    let mut state = make_state(
+        10,
+        10,
+        vec![monster],
+        vec![],
+        vec![],
+        vec![],
+        collisions,
+        HashMap::new(),
+    );
+
+    state.refresh_placement_issues();
+    assert!(state.data.placement_issues.is_empty(), "layer hidden");
+
+    state.view.show_placement_issues = true;
+    state.refresh_placement_issues();
+    assert_eq!(state.data.placement_issues.len(), 1);
+    assert_eq!(
+        (
+            state.data.placement_issues[0].tile_x,
+            state.data.placement_issues[0].tile_y
+        ),
+        (2, 2)
+    );
+}
@@ -172,6 +172,9 @@ pub struct MapDataState {
     pub status_msg: Option<String>,
     /// Sprite export dialog state (None = dialog closed).
     pub sprite_export_dialog: Option<SpriteExportDialogState>,
+    /// Result of the last placement validation; empty while the issues
+    /// layer is hidden.
+    pub placement_issues: Vec<dispel_core::map::placement::PlacementIssue>,
 }
 
 impl Default for MapDataState {
@@ -205,6 +208,7 @@ impl Default for MapDataState {
             is_exporting: false,
             status_msg: None,
             sprite_export_dialog: None,
+            placement_issues: Vec::new(),
         }
     }
 }
@@ -297,6 +301,20 @@ impl MapEditorState {
         self.data.map_data()
     }
 
+    /// Re-validate entity placements for the issues layer, or clear them
+    /// while the layer is hidden.
+    pub fn refresh_placement_issues(&mut self) {
+        self.data.placement_issues = match (self.view.show_placement_issues, self.data.map_data()) {
+            (true, Some(map)) => dispel_core::map::placement::validate_placements(
+                &map.0,
+                &self.data.monsters,
+                &self.data.npcs,
+                &self.data.extra_refs,
+            ),
+            _ => Vec::new(),
+        };
+    }
+
     /// Push a reversible field-change action onto the undo stack and mark dirty.
     pub fn push_undo(&mut self, action: MapEditAction) {
         self.data.undo_stack.push_front(action);
@@ -18,6 +18,29 @@ mod sprite_export;
 const STATUS_DISMISS_SECS: u64 = 3;
 
 pub fn handle(message: MapEditorMessage, app: &mut App) -> Task<Message> {
+    // Messages that can move an entity or edit collisions; the placement
+    // issues layer is re-validated after them.
+    let revalidate = match &message {
+        MapEditorMessage::MapLoaded(tab_id, _)
+        | MapEditorMessage::EntitiesLoaded(tab_id, _)
+        | MapEditorMessage::EntityFieldChanged(tab_id, ..)
+        | MapEditorMessage::Undo(tab_id)
+        | MapEditorMessage::Redo(tab_id)
+        | MapEditorMessage::CanvasClicked(tab_id, ..)
+        | MapEditorMessage::LayerToggled(tab_id, MapLayer::PlacementIssues) => Some(*tab_id),
+        _ => None,
+    };
+    let task = handle_message(message, app);
+    if let Some(tab_id) = revalidate
+        && let Some(state) = app.state.editors.map_editors.get_mut(&tab_id)
+    {
+        state.refresh_placement_issues();
+        state.view.overlay_cache.clear();
+    }
+    task
+}
+
+fn handle_message(message: MapEditorMessage, app: &mut App) -> Task<Message> {
     match message {
         // ── Delegated to submodules ───────────────────────────────────────────
         MapEditorMessage::Open(tab_id, path) => map::open(app, tab_id, path),
@@ -196,6 +219,9 @@ pub fn handle(message: MapEditorMessage, app: &mut App) -> Task<Message> {
                     }
                     MapLayer::Objects => state.view.show_objects = !state.view.show_objects,
                     MapLayer::DrawItems => state.view.show_draw_items = !state.view.show_draw_items,
+                    MapLayer::PlacementIssues => {
+                        state.view.show_placement_issues = !state.view.show_placement_issues
+                    }
                 }
                 // Tile canvas renders entities and tile layers; overlay renders
                 // collisions and events — clear both caches.
@@ -209,6 +209,16 @@ pub fn view(app: &App) -> Element<'_, Message> {
                     MapLayer::DrawItems,
                     Some(state.data.draw_items.len())
                 ),
+                layer_toggle(
+                    "Issues",
+                    state.view.show_placement_issues,
+                    tab_id,
+                    MapLayer::PlacementIssues,
+                    state
+                        .view
+                        .show_placement_issues
+                        .then_some(state.data.placement_issues.len())
+                ),
             ]
             .spacing(12)
             .padding([6, 16])
@@ -1,5 +1,6 @@
 use crate::components::editable::EditableRecord;
 use dispel_core::MiscItem;
+use dispel_core::references::usages::RecordKind;
 
 use crate::editable_record_fields;
 
@@ -30,4 +31,7 @@ impl EditableRecord for MiscItem {
     fn detail_width() -> f32 {
         320.0
     }
+    fn record_kind() -> Option<RecordKind> {
+        Some(RecordKind::MiscItem)
+    }
 }
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use dispel_core::modding::{ChangeAction, ChangeOp, Workspace};
-use hexedit::{HexEditorMessage, HexEditorState, OnSaveFn};
+use hexedit::{HexEditorMessage, HexEditorState, HexProvider, OnSaveFn};
 use iced::Task;
 
 /// Build an optional save callback for the hex editor config.
@@ -29,7 +29,7 @@ pub fn build_save_callback(
         };
         let relative_str = relative.to_string_lossy().replace('\\', "/");
 
-        let current_bytes = state.provider.as_slice().to_vec();
+        let current_bytes = state.provider.read_to_vec(0..state.provider.len());
 
         Task::perform(
             async move {
@@ -7,8 +7,8 @@
 //! handler then debounces those observations per key and only persists the
 //! coalesced result after an idle interval has elapsed.
 //!
-//! When no session is active, `observe_field_change` is a no-op returning
-//! `Task::none()` — callers pay only an `Option::is_some()` check.
+//! When no session is active, `observe_field_change` only appends the edit to
+//! the project journal and returns `Task::none()`.
 
 use dispel_core::modding::{ChangeAction, ChangeOp, Value, Workspace, make_delta};
 use iced::Task;
@@ -71,20 +71,24 @@ pub fn record_file_replace(
 
 /// Observe one successful field-edit. Called from the editor macro after the
 /// underlying edit committed; old/new are the string values from the
-/// edit-history entry.
+/// edit-history entry. The edit always lands in the project journal (see
+/// [`crate::journal`]); it is only forwarded to the Mod Manager while a
+/// recording session is active.
 pub fn observe_field_change(
-    app: &App,
+    app: &mut App,
     file_path: impl Into<String>,
     record_id: u32,
     field: &str,
     old: String,
     new: String,
 ) -> Task<Message> {
+    let file_path = file_path.into();
+    crate::journal::record_field_change(app, file_path.as_str(), record_id, field, &old, &new);
     if app.state.recording.is_none() {
         return Task::none();
     }
     let key = RecordingKey {
-        file_path: file_path.into(),
+        file_path,
         record_id,
         field: field.to_owned(),
     };
@@ -0,0 +1,111 @@
+use iced::widget::{Column, Space, button, container, row, scrollable, text};
+use iced::{Alignment, Element, Fill};
+
+use crate::editors::save_file_viewer::SaveFileViewerMessage;
+use crate::editors::save_file_viewer::helpers::section_header;
+use crate::editors::save_file_viewer::state::SaveFileViewerState;
+use crate::message::Message;
+use crate::message::MessageExt;
+use crate::style;
+use dispel_core::references::save_file::{FieldChange, SectionDiff};
+
+/// Rows shown per section before the rest is summarised; whole-record
+/// changes such as a re-rolled map can touch thousands of fields.
+const MAX_ROWS_PER_SECTION: usize = 500;
+
+/// Compare section: differences between the loaded save and a second save.
+pub fn view<'a>(state: &'a SaveFileViewerState) -> Element<'a, Message> {
+    let pick = button(text("Compare with…").size(12))
+        .on_press(Message::save_file_viewer(
+            SaveFileViewerMessage::CompareWith,
+        ))
+        .padding([4, 10])
+        .style(style::export_button);
+    let target = match &state.compare_save {
+        Some((path, _)) => format!("This save → {}", path.display()),
+        None => "Pick a second save to see which fields changed between them.".to_string(),
+    };
+    let header = row![
+        text(target).size(13).style(style::subtle_text),
+        Space::new().width(Fill),
+        pick
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    let mut col = Column::new().spacing(4).padding(16).push(header);
+    match &state.save_diff {
+        None => {}
+        Some(Err(error)) => col = col.push(text(format!("Could not compare saves: {error}"))),
+        Some(Ok(diff)) if diff.is_empty() => col = col.push(text("Saves are identical.")),
+        Some(Ok(diff)) => {
+            col = col.push(
+                text(format!(
+                    "{} changed field(s) in {} section(s)",
+                    diff.change_count(),
+                    diff.sections.len()
+                ))
+                .size(13),
+            );
+            for section in &diff.sections {
+                col = col.push(section_view(section));
+            }
+        }
+    }
+
+    scrollable(col).width(Fill).height(Fill).into()
+}
+
+fn section_view(section: &SectionDiff) -> Element<'static, Message> {
+    let mut col = Column::new().spacing(2).push(section_header(&format!(
+        "{} (this: {:#x}..{:#x}, other: {:#x}..{:#x})",
+        section.section, section.range_a.0, section.range_a.1, section.range_b.0, section.range_b.1
+    )));
+
+    for change in section.changes.iter().take(MAX_ROWS_PER_SECTION) {
+        col = col.push(change_row(change));
+    }
+    let remaining_rows = MAX_ROWS_PER_SECTION.saturating_sub(section.changes.len());
+    for range in section.byte_ranges.iter().take(remaining_rows) {
+        let length = if range.len_a == range.len_b {
+            format!("{} bytes", range.len_a)
+        } else {
+            format!("{} → {} bytes", range.len_a, range.len_b)
+        };
+        col = col.push(
+            text(format!(
+                "bytes +{:#x} ({length}; this @ {:#x}, other @ {:#x})",
+                range.offset,
+                section.range_a.0 + range.offset,
+                section.range_b.0 + range.offset
+            ))
+            .size(12)
+            .style(style::subtle_text),
+        );
+    }
+    let total = section.changes.len() + section.byte_ranges.len();
+    if total > MAX_ROWS_PER_SECTION {
+        col = col.push(
+            text(format!("… {} more", total - MAX_ROWS_PER_SECTION))
+                .size(12)
+                .style(style::subtle_text),
+        );
+    }
+
+    container(col).width(Fill).into()
+}
+
+fn change_row(change: &FieldChange) -> Element<'static, Message> {
+    let value = match (&change.before, &change.after) {
+        (Some(before), Some(after)) => format!("{before} → {after}"),
+        (None, Some(_)) => "added".to_string(),
+        (Some(_), None) => "removed".to_string(),
+        (None, None) => String::new(),
+    };
+    row![
+        text(change.path.clone()).size(12).width(360),
+        text(value).size(12)
+    ]
+    .spacing(8)
+    .into()
+}
@@ -0,0 +1,479 @@
+//! Field-level edit model for the save file editor.
+//!
+//! Every edit is addressed by a `(record_idx, field)` pair so it can be stored
+//! as an [`EditAction::FieldChange`](crate::components::edit_history::EditAction)
+//! and replayed by undo/redo. Field keys are dotted paths:
+//!
+//! | Key                               | `record_idx`         | Value                  |
+//! |-----------------------------------|----------------------|------------------------|
+//! | `character.<field>`               | unused (0)           | integer                |
+//! | `party.<field>`                   | party member index   | integer / name         |
+//! | `spell`                           | learned-spell index  | `true` / `false`       |
+//! | `journal.<main/side/trade>.<field>` | journal entry index | integer / text / bool |
+//! | `event.<field>`                   | event index          | integer / bool         |
+//! | `inventory.<category>`            | inventory record     | JSON of the record     |
+//!
+//! Inventory records are swapped as a whole (see
+//! [`apply_definition`](dispel_core::references::save_file::InventoryWeaponItem::apply_definition)),
+//! so their value is the serialized record; this keeps undo exact even when
+//! the original record does not match any item database entry.
+
+use dispel_core::references::save_file::character::CharacterData;
+use dispel_core::references::save_file::party_members::PartyMemberBinaryRecord;
+use dispel_core::references::save_file::{EventRecord, PartyMember};
+use dispel_core::{JournalEntry, SaveFile};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::components::editable::EditableRecordGenerated;
+use crate::editable_record_fields;
+use crate::editors::save_file_viewer::state::{InventoryCategory, JournalSection};
+
+editable_record_fields!(CharacterData, {
+    { strength = Integer / "Strength" },
+    { agility = Integer / "Agility" },
+    { wisdom = Integer / "Wisdom" },
+    { constitution = Integer / "Constitution" },
+    { morale = Integer / "Morale" },
+    { hp_current = Integer / "HP Current" },
+    { hp_maximum = Integer / "HP Maximum" },
+    { mp_current = Integer / "MP Current" },
+    { mp_maximum = Integer / "MP Maximum" },
+    { experience = Integer / "Experience" },
+    { level = Integer / "Level" },
+    { unspent_stat_points = Integer / "Unspent Points" },
+    { gold = Integer / "Gold" },
+    { offense = Integer / "Offense" },
+    { defense = Integer / "Defense" },
+    { dodge_rate = Integer / "Dodge Rate" },
+    { hit_rate = Integer / "Hit Rate" },
+    { magic_power = Integer / "Magic Power" },
+    { attack_modifier = Integer / "Attack Modifier" },
+    { pickpocketing = Integer / "Pickpocketing" },
+    { lockpicking = Integer / "Lockpicking" },
+    { haggling = Integer / "Haggling" },
+    { perception = Integer / "Perception" },
+    { traps = Integer / "Traps" },
+    { swords_level = Integer / "Swords Level" },
+    { swords_kills = Integer / "Swords Kills" },
+    { axes_level = Integer / "Axes Level" },
+    { axes_kills = Integer / "Axes Kills" },
+    { archery_level = Integer / "Archery Level" },
+    { archery_kills = Integer / "Archery Kills" },
+    { polearm_level = Integer / "Polearm Level" },
+    { polearm_kills = Integer / "Polearm Kills" },
+    { magic_level = Integer / "Magic Level" },
+    { magic_kills = Integer / "Magic Kills" },
+    { holy_magic_level = Integer / "Holy Magic Level" },
+    { holy_magic_kills = Integer / "Holy Magic Kills" },
+    { dark_magic_level = Integer / "Dark Magic Level" },
+    { dark_magic_kills = Integer / "Dark Magic Kills" },
+});
+
+editable_record_fields!(PartyMember, {
+    { name = String / "Name" },
+});
+
+editable_record_fields!(PartyMemberBinaryRecord, {
+    { level = Integer / "Level" },
+    { experience_points = Integer / "XP" },
+    { current_health_points = Integer / "HP Current" },
+    { maximum_health_points = Integer / "HP Maximum" },
+    { current_mana_points = Integer / "MP Current" },
+    { maximum_mana_points = Integer / "MP Maximum" },
+    { strength = Integer / "Strength" },
+    { constitution = Integer / "Constitution" },
+    { wisdom = Integer / "Wisdom" },
+    { agility = Integer / "Agility" },
+    { attack = Integer / "Attack" },
+    { magic_spell_id_1 = Integer / "Spell 1" },
+    { magic_spell_id_2 = Integer / "Spell 2" },
+    { magic_spell_id_3 = Integer / "Spell 3" },
+});
+
+editable_record_fields!(JournalEntry, {
+    { quest_title = String / "Title" },
+    { quest_id = Integer / "Quest ID" },
+    { follow_up_quest_id_1 = Integer / "Follow-up 1" },
+    { follow_up_quest_id_2 = Integer / "Follow-up 2" },
+    { is_completed = Boolean / "Completed" },
+});
+
+editable_record_fields!(EventRecord, {
+    { execution_limit = Integer / "Execution Limit" },
+    { execution_count = Integer / "Execution Count" },
+    { has_triggered = Boolean / "Triggered" },
+});
+
+/// Field key for a character stat.
+pub fn character_key(field: &str) -> String {
+    format!("character.{field}")
+}
+
+/// Field key for a party member field (name or binary record field).
+pub fn party_key(field: &str) -> String {
+    format!("party.{field}")
+}
+
+/// Field key for a journal entry field in `section`.
+pub fn journal_key(section: JournalSection, field: &str) -> String {
+    format!("journal.{}.{field}", journal_section_name(section))
+}
+
+/// Field key for an event record field.
+pub fn event_key(field: &str) -> String {
+    format!("event.{field}")
+}
+
+/// Field key for a whole inventory record of `cat`.
+pub fn inventory_key(cat: InventoryCategory) -> String {
+    format!("inventory.{}", inventory_category_name(cat))
+}
+
+/// Field key for a learned-spell flag.
+pub const SPELL_KEY: &str = "spell";
+
+/// Which display cache an edited field belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditTarget {
+    Character,
+    Party,
+    Spells,
+    Journal,
+    Events,
+    Inventory,
+}
+
+/// Classify `field` by its key prefix.
+pub fn edit_target(field: &str) -> Option<EditTarget> {
+    let (head, _) = field.split_once('.').unwrap_or((field, ""));
+    Some(match head {
+        "character" => EditTarget::Character,
+        "party" => EditTarget::Party,
+        "spell" => EditTarget::Spells,
+        "journal" => EditTarget::Journal,
+        "event" => EditTarget::Events,
+        "inventory" => EditTarget::Inventory,
+        _ => return None,
+    })
+}
+
+/// Read the current value of `field` for `record_idx`.
+///
+/// Returns `None` when the key is unknown or the record does not exist.
+pub fn get_field(save: &SaveFile, record_idx: usize, field: &str) -> Option<String> {
+    let (head, rest) = field.split_once('.').unwrap_or((field, ""));
+    match head {
+        "character" => get_generated(&save.character, rest),
+        "party" => {
+            let member = save.party_members.get(record_idx)?;
+            get_generated(member, rest).or_else(|| get_generated(&member.record, rest))
+        }
+        "spell" => save
+            .learned_spells
+            .spells
+            .get(record_idx)
+            .map(|flag| (*flag != 0).to_string()),
+        "journal" => {
+            let (section, name) = rest.split_once('.')?;
+            let entries = journal_entries(save, journal_section_from_name(section)?);
+            get_generated(entries.get(record_idx)?, name)
+        }
+        "event" => get_generated(save.events.get(record_idx)?, rest),
+        "inventory" => {
+            let inv = &save.inventory;
+            match inventory_category_from_name(rest)? {
+                InventoryCategory::Weapon => to_json(inv.weapon_items.get(record_idx)?),
+                InventoryCategory::Heal => to_json(inv.heal_items.get(record_idx)?),
+                InventoryCategory::Edit => to_json(inv.edit_items.get(record_idx)?),
+                InventoryCategory::Event => to_json(inv.event_items.get(record_idx)?),
+                InventoryCategory::Misc => to_json(inv.misc_items.get(record_idx)?),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Write `value` into `field` for `record_idx`.
+///
+/// Returns `false` (leaving the save untouched) when the key is unknown, the
+/// record does not exist, or the value does not parse for the field's type.
+pub fn set_field(save: &mut SaveFile, record_idx: usize, field: &str, value: String) -> bool {
+    let (head, rest) = field.split_once('.').unwrap_or((field, ""));
+    match head {
+        "character" => set_generated(&mut save.character, rest, value),
+        "party" => {
+            let Some(member) = save.party_members.get_mut(record_idx) else {
+                return false;
+            };
+            if has_field::<PartyMember>(rest) {
+                set_generated(member, rest, value)
+            } else {
+                set_generated(&mut member.record, rest, value)
+            }
+        }
+        "spell" => {
+            let flag = match value.as_str() {
+                "true" | "1" => 1,
+                "false" | "0" => 0,
+                _ => return false,
+            };
+            match save.learned_spells.spells.get_mut(record_idx) {
+                Some(slot) => {
+                    *slot = flag;
+                    true
+                }
+                None => false,
+            }
+        }
+        "journal" => {
+            let Some((section, name)) = rest.split_once('.') else {
+                return false;
+            };
+            let Some(section) = journal_section_from_name(section) else {
+                return false;
+            };
+            match journal_entries_mut(save, section).get_mut(record_idx) {
+                Some(entry) => set_generated(entry, name, value),
+                None => false,
+            }
+        }
+        "event" => match save.events.get_mut(record_idx) {
+            Some(event) => set_generated(event, rest, value),
+            None => false,
+        },
+        "inventory" => {
+            let inv = &mut save.inventory;
+            match inventory_category_from_name(rest) {
+                Some(InventoryCategory::Weapon) => {
+                    replace_json(inv.weapon_items.get_mut(record_idx), &value)
+                }
+                Some(InventoryCategory::Heal) => {
+                    replace_json(inv.heal_items.get_mut(record_idx), &value)
+                }
+                Some(InventoryCategory::Edit) => {
+                    replace_json(inv.edit_items.get_mut(record_idx), &value)
+                }
+                Some(InventoryCategory::Event) => {
+                    replace_json(inv.event_items.get_mut(record_idx), &value)
+                }
+                Some(InventoryCategory::Misc) => {
+                    replace_json(inv.misc_items.get_mut(record_idx), &value)
+                }
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Short human-readable description of a value for status messages.
+///
+/// Inventory values are whole serialized records, so only the item name is
+/// shown for them.
+pub fn describe_value(field: &str, value: &str) -> String {
+    if edit_target(field) == Some(EditTarget::Inventory) {
+        return serde_json::from_str::<serde_json::Value>(value)
+            .ok()
+            .and_then(|v| v.get("name").and_then(|n| n.as_str()).map(str::to_owned))
+            .unwrap_or_default();
+    }
+    value.to_owned()
+}
+
+/// Descriptors of the editable party member fields (name first).
+pub fn party_fields() -> impl Iterator<Item = &'static crate::components::editable::FieldDescriptor>
+{
+    PartyMember::__editable_fields()
+        .iter()
+        .chain(PartyMemberBinaryRecord::__editable_fields())
+}
+
+/// Descriptors of the editable journal entry fields.
+pub fn journal_fields() -> &'static [crate::components::editable::FieldDescriptor] {
+    JournalEntry::__editable_fields()
+}
+
+/// Descriptors of the editable event fields.
+pub fn event_fields() -> &'static [crate::components::editable::FieldDescriptor] {
+    EventRecord::__editable_fields()
+}
+
+fn has_field<T: EditableRecordGenerated>(name: &str) -> bool {
+    T::__editable_fields().iter().any(|f| f.name == name)
+}
+
+fn get_generated<T: EditableRecordGenerated>(record: &T, name: &str) -> Option<String> {
+    has_field::<T>(name).then(|| record.__editable_get(name))
+}
+
+fn set_generated<T: EditableRecordGenerated>(record: &mut T, name: &str, value: String) -> bool {
+    has_field::<T>(name) && record.__editable_set(name, value)
+}
+
+fn to_json<T: Serialize>(record: &T) -> Option<String> {
+    serde_json::to_string(record).ok()
+}
+
+fn replace_json<T: DeserializeOwned>(slot: Option<&mut T>, value: &str) -> bool {
+    match (slot, serde_json::from_str::<T>(value)) {
+        (Some(slot), Ok(record)) => {
+            *slot = record;
+            true
+        }
+        _ => false,
+    }
+}
+
+fn journal_entries(save: &SaveFile, section: JournalSection) -> &[JournalEntry] {
+    match section {
+        JournalSection::Main => &save.journal.main,
+        JournalSection::Side => &save.journal.side,
+        JournalSection::Trade => &save.journal.trade,
+    }
+}
+
+fn journal_entries_mut(save: &mut SaveFile, section: JournalSection) -> &mut Vec<JournalEntry> {
+    match section {
+        JournalSection::Main => &mut save.journal.main,
+        JournalSection::Side => &mut save.journal.side,
+        JournalSection::Trade => &mut save.journal.trade,
+    }
+}
+
+fn journal_section_name(section: JournalSection) -> &'static str {
+    match section {
+        JournalSection::Main => "main",
+        JournalSection::Side => "side",
+        JournalSection::Trade => "trade",
+    }
+}
+
+fn journal_section_from_name(name: &str) -> Option<JournalSection> {
+    match name {
+        "main" => Some(JournalSection::Main),
+        "side" => Some(JournalSection::Side),
+        "trade" => Some(JournalSection::Trade),
+        _ => None,
+    }
+}
+
+fn inventory_category_name(cat: InventoryCategory) -> &'static str {
+    match cat {
+        InventoryCategory::Weapon => "weapon",
+        InventoryCategory::Heal => "heal",
+        InventoryCategory::Edit => "edit",
+        InventoryCategory::Event => "event",
+        InventoryCategory::Misc => "misc",
+    }
+}
+
+fn inventory_category_from_name(name: &str) -> Option<InventoryCategory> {
+    match name {
+        "weapon" => Some(InventoryCategory::Weapon),
+        "heal" => Some(InventoryCategory::Heal),
+        "edit" => Some(InventoryCategory::Edit),
+        "event" => Some(InventoryCategory::Event),
+        "misc" => Some(InventoryCategory::Misc),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dispel_core::references::save_file::InventoryWeaponItem;
+
+    fn sample_save() -> SaveFile {
+        let mut save = SaveFile::default();
+        save.learned_spells.spells = vec![0; 41];
+        save.party_members.push(PartyMember::default());
+        save.journal.side.push(JournalEntry::default());
+        save.events.push(EventRecord::default());
+        save.inventory.weapon_items.push(InventoryWeaponItem {
+            name: "Short Sword".into(),
+            ..Default::default()
+        });
+        save
+    }
+
+    #[test]
+    fn character_stat_round_trips_through_keys() {
+        let mut save = sample_save();
+        assert!(set_field(
+            &mut save,
+            0,
+            &character_key("gold"),
+            "1234".into()
+        ));
+        assert_eq!(save.character.gold, 1234);
+        assert_eq!(
+            get_field(&save, 0, &character_key("gold")).as_deref(),
+            Some("1234")
+        );
+    }
+
+    #[test]
+    fn invalid_values_leave_the_save_untouched() {
+        let mut save = sample_save();
+        assert!(!set_field(
+            &mut save,
+            0,
+            &character_key("level"),
+            "300".into()
+        ));
+        assert!(!set_field(
+            &mut save,
+            0,
+            &character_key("position"),
+            "1".into()
+        ));
+        assert!(!set_field(&mut save, 5, &party_key("level"), "3".into()));
+        assert_eq!(save.character.level, 0);
+    }
+
+    #[test]
+    fn party_name_and_record_fields_share_one_prefix() {
+        let mut save = sample_save();
+        assert!(set_field(&mut save, 0, &party_key("name"), "Mina".into()));
+        assert!(set_field(&mut save, 0, &party_key("level"), "7".into()));
+        assert_eq!(save.party_members[0].name, "Mina");
+        assert_eq!(save.party_members[0].record.level, 7);
+    }
+
+    #[test]
+    fn spells_journal_and_events_accept_boolean_values() {
+        let mut save = sample_save();
+        assert!(set_field(&mut save, 3, SPELL_KEY, "true".into()));
+        assert_eq!(save.learned_spells.spells[3], 1);
+        let key = journal_key(JournalSection::Side, "is_completed");
+        assert!(set_field(&mut save, 0, &key, "true".into()));
+        assert_eq!(save.journal.side[0].is_completed, 1);
+        assert!(set_field(
+            &mut save,
+            0,
+            &event_key("has_triggered"),
+            "1".into()
+        ));
+        assert_eq!(
+            get_field(&save, 0, &event_key("has_triggered")).as_deref(),
+            Some("true")
+        );
+    }
+
+    #[test]
+    fn inventory_records_are_swapped_whole() {
+        let mut save = sample_save();
+        let key = inventory_key(InventoryCategory::Weapon);
+        let original = get_field(&save, 0, &key).unwrap();
+
+        save.inventory.weapon_items[0].name = "Long Sword".into();
+        save.inventory.weapon_items[0].attack = 12;
+        let edited = get_field(&save, 0, &key).unwrap();
+        assert_eq!(describe_value(&key, &edited), "Long Sword");
+
+        assert!(set_field(&mut save, 0, &key, original));
+        assert_eq!(save.inventory.weapon_items[0].name, "Short Sword");
+        assert_eq!(save.inventory.weapon_items[0].attack, 0);
+    }
+}
@@ -3,6 +3,8 @@ use iced::widget::{container, mouse_area, text};
 use iced::{Element, Fill};
 
 use crate::components::filter::{self, ColumnFilterAction, FilterBarExtras, GlobalFilterMode};
+use crate::editors::save_file_viewer::edit;
+use crate::editors::save_file_viewer::helpers::edit_panel;
 use crate::editors::save_file_viewer::message::{
     SaveFileViewerMessage, TableFilterAction, TableKey,
 };
@@ -136,7 +138,22 @@ pub fn view<'a>(state: &'a SaveFileViewerState) -> Element<'a, Message> {
         },
     );
 
-    let content = iced::widget::column![filter_bar, table_element].spacing(8);
+    let mut content = iced::widget::Column::new().spacing(8);
+    if let (Some(save), Some(event_idx)) = (state.save_file.as_ref(), selected) {
+        let script = save
+            .events
+            .get(event_idx)
+            .map(|e| e.script_filename.as_str())
+            .unwrap_or_default();
+        content = content.push(edit_panel(
+            format!("Event #{event_idx} {script}"),
+            save,
+            event_idx,
+            edit::event_fields(),
+            edit::event_key,
+        ));
+    }
+    let content = content.push(filter_bar).push(table_element);
 
     if let Some(col) = filter.active_column_filter {
         let modal_content = filter::build_column_filter_modal(
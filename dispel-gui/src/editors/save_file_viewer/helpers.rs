@@ -1,9 +1,13 @@
 //! Shared UI helpers for the save file viewer sections.
 
-use iced::widget::{Row, container, text};
-use iced::{Element, Fill};
+use dispel_core::SaveFile;
+use iced::widget::{Column, Row, checkbox, container, text, text_input};
+use iced::{Alignment, Element, Fill};
 
-use crate::message::Message;
+use crate::components::editable::{FieldDescriptor, FieldKind};
+use crate::editors::save_file_viewer::SaveFileViewerMessage;
+use crate::editors::save_file_viewer::edit;
+use crate::message::{Message, MessageExt};
 
 /// Section header label (e.g. "Core Attributes", "Combat Stats").
 pub fn section_header(label: &str) -> Element<'static, Message> {
@@ -21,3 +25,69 @@ pub fn label_row(key: impl Into<String>, value: impl Into<String>) -> Element<'s
         .spacing(8)
         .into()
 }
+
+/// A labelled editor for one save field: a checkbox for boolean fields, a
+/// text input otherwise. Changes are sent as `EditField` for `key`.
+pub fn edit_row(
+    label: &str,
+    kind: &FieldKind,
+    key: String,
+    record_idx: usize,
+    value: String,
+) -> Element<'static, Message> {
+    let on_edit = move |value: String| {
+        Message::save_file_viewer(SaveFileViewerMessage::EditField {
+            record_idx,
+            field: key.clone(),
+            value,
+        })
+    };
+    let input: Element<'static, Message> = match kind {
+        FieldKind::Boolean => checkbox(value == "true")
+            .on_toggle(move |checked| on_edit(checked.to_string()))
+            .into(),
+        FieldKind::String | FieldKind::TextArea => text_input("", value)
+            .on_input(on_edit)
+            .padding(4)
+            .size(13)
+            .width(220)
+            .into(),
+        _ => text_input("", value)
+            .on_input(on_edit)
+            .padding(4)
+            .size(13)
+            .width(120)
+            .into(),
+    };
+    Row::new()
+        .push(text(label.to_string()).width(150))
+        .push(input)
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .into()
+}
+
+/// Editable form for one record: an [`edit_row`] per field descriptor.
+///
+/// `key_for` maps a descriptor name to its edit key (see [`edit`]).
+pub fn edit_panel<'f>(
+    title: String,
+    save: &SaveFile,
+    record_idx: usize,
+    fields: impl IntoIterator<Item = &'f FieldDescriptor>,
+    key_for: impl Fn(&str) -> String,
+) -> Element<'static, Message> {
+    let rows = fields.into_iter().map(|field| {
+        let key = key_for(field.name);
+        let value = edit::get_field(save, record_idx, &key).unwrap_or_default();
+        edit_row(field.label, &field.kind, key, record_idx, value)
+    });
+    container(
+        Column::new()
+            .push(text(title).size(14))
+            .extend(rows)
+            .spacing(4),
+    )
+    .padding(8)
+    .into()
+}
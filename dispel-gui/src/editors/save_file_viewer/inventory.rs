@@ -2,6 +2,7 @@ use iced::mouse::Interaction;
 use iced::widget::{Column, Row, button, container, mouse_area, text};
 use iced::{Element, Fill};
 
+use crate::components::composite_item::composite_item_picker;
 use crate::components::filter::{self, ColumnFilterAction, FilterBarExtras, GlobalFilterMode};
 use crate::editors::save_file_viewer::message::{
     SaveFileViewerMessage, TableFilterAction, TableKey,
@@ -61,7 +62,78 @@ pub fn view<'a>(state: &'a SaveFileViewerState) -> Element<'a, Message> {
             .into(),
     };
 
-    Column::<Message>::new().push(buttons).push(body).into()
+    let mut layout = Column::<Message>::new().push(buttons);
+    if let Some(cat) = active
+        && let Some(picker) = item_picker_panel(state, cat)
+    {
+        layout = layout.push(picker);
+    }
+    layout.push(body).into()
+}
+
+/// Item picker for the selected inventory record. Picking an item copies its
+/// database definition into the record (see `SaveFileViewerState::pick_inventory_item`).
+fn item_picker_panel(
+    state: &SaveFileViewerState,
+    cat: InventoryCategory,
+) -> Option<Element<'static, Message>> {
+    let record_idx = state.inventory_table_states.get(&cat)?.selected_orig?;
+    let save = state.save_file.as_ref()?;
+    let inv = &save.inventory;
+    let (name, definition_id) = match cat {
+        InventoryCategory::Weapon => inv
+            .weapon_items
+            .get(record_idx)
+            .map(|i| (&i.name, i.weapon_item_id))?,
+        InventoryCategory::Heal => inv
+            .heal_items
+            .get(record_idx)
+            .map(|i| (&i.name, i.heal_item_id))?,
+        InventoryCategory::Edit => inv
+            .edit_items
+            .get(record_idx)
+            .map(|i| (&i.name, i.edit_item_id))?,
+        InventoryCategory::Event => inv
+            .event_items
+            .get(record_idx)
+            .map(|i| (&i.name, i.event_item_id))?,
+        InventoryCategory::Misc => inv
+            .misc_items
+            .get(record_idx)
+            .map(|i| (&i.name, i.misc_item_id))?,
+    };
+
+    let entries = state.item_catalog.picker_entries(cat);
+    let picker: Element<'static, Message> = if entries.is_empty() {
+        text("Item databases not found; set the game path to replace items.")
+            .size(12)
+            .style(style::subtle_text)
+            .into()
+    } else {
+        composite_item_picker(
+            "Replace with:",
+            &format!("{}:{definition_id}", cat.item_type().value()),
+            Some(&entries),
+            move |key| {
+                Message::save_file_viewer(SaveFileViewerMessage::PickInventoryItem {
+                    cat,
+                    record_idx,
+                    key,
+                })
+            },
+        )
+    };
+
+    Some(
+        container(
+            Column::<Message>::new()
+                .push(text(format!("#{record_idx}: {name}")).size(14))
+                .push(picker)
+                .spacing(4),
+        )
+        .padding([4, 8])
+        .into(),
+    )
 }
 
 fn inventory_table<'a>(
@@ -3,6 +3,8 @@ use iced::widget::{Column, button, container, mouse_area, text};
 use iced::{Element, Fill};
 
 use crate::components::filter::{self, ColumnFilterAction, FilterBarExtras, GlobalFilterMode};
+use crate::editors::save_file_viewer::edit;
+use crate::editors::save_file_viewer::helpers::edit_panel;
 use crate::editors::save_file_viewer::message::{
     SaveFileViewerMessage, TableFilterAction, TableKey,
 };
@@ -192,5 +194,17 @@ pub fn view<'a>(state: &'a SaveFileViewerState) -> Element<'a, Message> {
         _ => container(text("No entries")).width(Fill).padding(16).into(),
     };
 
-    Column::<Message>::new().push(tab_bar).push(table).into()
+    let mut layout = Column::<Message>::new().push(tab_bar);
+    if let (Some(save), Some(entry_idx)) =
+        (state.save_file.as_ref(), ts.and_then(|ts| ts.selected_orig))
+    {
+        layout = layout.push(edit_panel(
+            format!("Entry #{entry_idx}"),
+            save,
+            entry_idx,
+            edit::journal_fields(),
+            |field| edit::journal_key(section, field),
+        ));
+    }
+    layout.push(table).into()
 }
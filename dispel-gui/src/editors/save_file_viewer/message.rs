@@ -5,6 +5,7 @@ use crate::components::filter::{ColumnFilterAction, GlobalFilterMode};
 use crate::editors::save_file_viewer::map_preview::PreviewMessage;
 use crate::editors::save_file_viewer::state::{
     CharacterTableKind, InventoryCategory, JournalSection, MapsTableKind, SaveFileSection,
+    SaveItemCatalog,
 };
 
 /// Messages for the save file viewer.
@@ -15,6 +16,27 @@ pub enum SaveFileViewerMessage {
     Load(PathBuf),
     /// Result of loading a save file.
     Loaded(Result<SaveFileLoaded, String>),
+    /// Set an editable field (see `save_file_viewer::edit` for the key format).
+    EditField {
+        record_idx: usize,
+        field: String,
+        value: String,
+    },
+    /// Replace an inventory record with an item picked from the item databases.
+    /// `key` is the picker's `"{type}:{db index}"` value.
+    PickInventoryItem {
+        cat: InventoryCategory,
+        record_idx: usize,
+        key: String,
+    },
+    /// Write the edited save back to disk (round-trip checked, with backup).
+    Save,
+    /// Result of a save; `Ok` carries the backup path if one was created.
+    Saved(Result<Option<PathBuf>, String>),
+    /// Pick a second save to compare against (opens a file dialog).
+    CompareWith,
+    /// Result of loading the comparison save.
+    CompareLoaded(Result<(PathBuf, dispel_core::references::save_file::SaveFile), String>),
     /// Switch to a different section.
     SelectSection(SaveFileSection),
     /// Select an inventory category to view.
@@ -305,6 +327,8 @@ pub struct SaveFileLoaded {
     pub hex_editors: Vec<RawHexEditorData>,
     /// Map ID → display name lookup from AllMap.ini (empty if unavailable).
     pub map_names: HashMap<u32, String>,
+    /// Item database records for the inventory pickers (empty without a game path).
+    pub item_catalog: SaveItemCatalog,
 }
 
 /// Data to initialize one embedded hex editor for a raw section.
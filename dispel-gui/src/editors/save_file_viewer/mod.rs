@@ -1,9 +1,17 @@
-//! Read-only save file viewer.
+//! Save file editor.
 //!
 //! Opens `.sav` files and displays parsed game state through sectioned tabs:
-//! Overview, Maps, Saved Viewport, Stats, Inventory, Identity, Events, Journal, Raw.
+//! Overview, Maps, Saved Viewport, Stats, Inventory, Identity, Events, Journal,
+//! Compare, Raw.
 //!
-//! **Read-only.** No edit buffers, no undo/redo, no save wiring.
+//! Character stats, inventory items, party members, learned spells, journal
+//! entries and event flags are editable. Edits go through [`edit`] as field
+//! changes recorded in the tab's edit history (undo/redo). Saving writes the
+//! file only after a round-trip check and keeps the original as `<name>.bak`
+//! (see [`dispel_core::SaveFile::save_with_backup`]).
+//!
+//! The Compare section diffs the loaded save against a second `.sav` file,
+//! listing changed fields and byte ranges per section.
 
 pub use message::SaveFileViewerMessage;
 pub use message::{RawHexEditorData, SaveFileLoaded};
@@ -12,6 +20,8 @@ pub use update::handle;
 pub use view::view;
 
 pub(crate) mod character;
+pub(crate) mod compare;
+pub(crate) mod edit;
 pub(crate) mod events;
 pub(crate) mod helpers;
 pub(crate) mod inventory;
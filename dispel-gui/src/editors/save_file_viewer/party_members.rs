@@ -1,6 +1,9 @@
-use crate::editors::save_file_viewer::helpers::{label_row, section_header};
+use crate::components::editable::FieldKind;
+use crate::editors::save_file_viewer::edit;
+use crate::editors::save_file_viewer::helpers::{edit_panel, edit_row, label_row, section_header};
 use crate::editors::save_file_viewer::state::SaveFileViewerState;
 use crate::message::Message;
+use dispel_core::SaveFile;
 use dispel_core::references::save_file::PartyMember;
 use iced::Element;
 use iced::widget::{Column, container, scrollable, text};
@@ -12,8 +15,6 @@ pub fn view<'a>(state: &'a SaveFileViewerState) -> Element<'a, Message> {
         None => return container(text("No save file loaded")).into(),
     };
 
-    let party_members = &sf.party_members;
-
     scrollable(
         Column::new()
             .push(section_header("Character Data Header"))
@@ -25,9 +26,12 @@ pub fn view<'a>(state: &'a SaveFileViewerState) -> Element<'a, Message> {
             .push(sf.learned_spells.spells.iter().enumerate().fold(
                 Column::new().spacing(4),
                 |col, (i, flag)| {
-                    col.push(label_row(
-                        format!("Spell {:02}", i + 1),
-                        if *flag != 0 { "learned" } else { "not learned" },
+                    col.push(edit_row(
+                        &format!("Spell {:02} learned", i + 1),
+                        &FieldKind::Boolean,
+                        edit::SPELL_KEY.to_string(),
+                        i,
+                        (*flag != 0).to_string(),
                     ))
                 },
             ))
@@ -36,20 +40,32 @@ pub fn view<'a>(state: &'a SaveFileViewerState) -> Element<'a, Message> {
                 "Party Members Count",
                 sf.party_members_count.to_string(),
             ))
-            .push(Column::new().spacing(4).push(match party_members.first() {
-                Some(member) => party_member_block(member),
-                None => text("No party member in slot 1").into(),
-            }))
-            .push(Column::new().spacing(4).push(match party_members.get(1) {
-                Some(member) => party_member_block(member),
-                None => text("No party member in slot 2").into(),
-            }))
+            .push(member_slot(sf, 0))
+            .push(member_slot(sf, 1))
             .spacing(8)
             .padding(16),
     )
     .into()
 }
 
+/// Editable fields followed by the full runtime record of one party slot.
+fn member_slot(sf: &SaveFile, idx: usize) -> Element<'static, Message> {
+    match sf.party_members.get(idx) {
+        Some(member) => Column::new()
+            .spacing(4)
+            .push(edit_panel(
+                format!("Edit {}", member.name),
+                sf,
+                idx,
+                edit::party_fields(),
+                edit::party_key,
+            ))
+            .push(party_member_block(member))
+            .into(),
+        None => text(format!("No party member in slot {}", idx + 1)).into(),
+    }
+}
+
 fn party_member_block(member: &PartyMember) -> Element<'static, Message> {
     container(
         Column::new()
@@ -1,11 +1,20 @@
+use crate::components::edit_history::{EditAction, EditHistory};
 use crate::components::filter::{ColumnFilterOption, GlobalFilterMode};
+use crate::components::generic_editor::UndoRedo;
+use crate::components::item_catalog::find_item_db;
 use crate::editors::save_file_viewer::RawHexEditorData;
+use crate::editors::save_file_viewer::edit::{self, EditTarget};
 use crate::editors::save_file_viewer::message::TableKey;
-use dispel_core::SaveFile;
+use crate::editors::save_file_viewer::update::table::hex_bytes;
+use dispel_core::references::save_file::SaveDiff;
+use dispel_core::{
+    EditItem, EventItem, Extractor, HealItem, ItemTypeId, MiscItem, SaveFile, WeaponItem,
+};
 use gui_widgets::TableColumn;
 use gui_widgets::components::paragraph_cache::ParagraphCache;
 use hexedit::HexEditorState;
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 /// Section tabs displayed in the save file viewer.
@@ -20,6 +29,7 @@ pub enum SaveFileSection {
     Character,
     Events,
     Journal,
+    Compare,
     Raw,
 }
 
@@ -36,6 +46,7 @@ impl SaveFileSection {
             SaveFileSection::Character => "Character",
             SaveFileSection::Events => "Events",
             SaveFileSection::Journal => "Journal",
+            SaveFileSection::Compare => "Compare",
             SaveFileSection::Raw => "Raw",
         }
     }
@@ -53,6 +64,7 @@ impl SaveFileSection {
             Character,
             Events,
             Journal,
+            Compare,
             Raw,
         ]
     }
@@ -609,6 +621,23 @@ pub struct SaveFileViewerState {
     pub show_preview: bool,
     /// Preview state for the currently selected map (replaced on map switch).
     pub map_preview: Option<crate::editors::save_file_viewer::map_preview::MapPreviewState>,
+
+    // ── Editing ────────────────────────────────────────────────────────────
+    /// Path the save was loaded from; edits are written back here.
+    pub file_path: Option<PathBuf>,
+    /// Field changes applied to `save_file`, replayed by undo/redo.
+    pub edit_history: EditHistory,
+    /// Item database records offered by the inventory item pickers.
+    pub item_catalog: SaveItemCatalog,
+    /// Whether a save write is in progress.
+    pub saving: bool,
+
+    // ── Compare ────────────────────────────────────────────────────────────
+    /// Second save picked in the Compare section, with the path it came from.
+    pub compare_save: Option<(PathBuf, SaveFile)>,
+    /// Differences from `save_file` to `compare_save`; rebuilt by
+    /// [`SaveFileViewerState::refresh_save_diff`].
+    pub save_diff: Option<Result<SaveDiff, String>>,
 }
 
 impl Default for SaveFileViewerState {
@@ -646,11 +675,461 @@ impl Default for SaveFileViewerState {
             last_resize_press: None,
             show_preview: false,
             map_preview: None,
+            file_path: None,
+            edit_history: EditHistory::default(),
+            item_catalog: SaveItemCatalog::default(),
+            saving: false,
+            compare_save: None,
+            save_diff: None,
+        }
+    }
+}
+
+impl SaveFileViewerState {
+    /// Recompute the diff against the comparison save, if one is loaded.
+    pub fn refresh_save_diff(&mut self) {
+        self.save_diff = match (&self.save_file, &self.compare_save) {
+            (Some(save), Some((_, other))) => {
+                Some(save.diff(other).map_err(|error| error.to_string()))
+            }
+            _ => None,
+        };
+    }
+
+    /// Rebuild the events table rows from the loaded save.
+    pub fn refresh_events_cache(&mut self) {
+        let Some(save) = &self.save_file else {
+            self.events_display_cache.clear();
+            return;
+        };
+        let n = save.events.len();
+        let mut display_cache = Vec::with_capacity(n);
+        for ev in save.events.iter() {
+            display_cache.push(vec![
+                ev.event_id.to_string(),
+                ev.required_event_id.to_string(),
+                ev.event_type.to_string(),
+                ev.script_filename.clone(),
+                ev.execution_limit.to_string(),
+                ev.execution_count.to_string(),
+                ev.has_triggered.to_string(),
+            ]);
+        }
+        self.events_display_cache = display_cache;
+    }
+
+    /// Rebuild every inventory category's table rows from the loaded save.
+    pub fn refresh_inventory_caches(&mut self) {
+        let Some(save) = &self.save_file else {
+            self.inventory_display_caches.clear();
+            return;
+        };
+        let inv = &save.inventory;
+        let mut inv_caches = HashMap::new();
+        inv_caches.insert(
+            InventoryCategory::Weapon,
+            inv.weapon_items
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.name.clone(),
+                        item.description.clone(),
+                        item.base_price.to_string(),
+                        item.weapon_item_id.to_string(),
+                        item.health_points.to_string(),
+                        item.mana_points.to_string(),
+                        item.strength.to_string(),
+                        item.agility.to_string(),
+                        item.wisdom.to_string(),
+                        item.constitution.to_string(),
+                        item.to_dodge.to_string(),
+                        item.to_hit.to_string(),
+                        item.attack.to_string(),
+                        item.defense.to_string(),
+                        item.magical_strength.to_string(),
+                        item.durability.to_string(),
+                        item.padding2.to_string(),
+                        item.padding3.to_string(),
+                        item.req_strength.to_string(),
+                        item.padding4.to_string(),
+                        item.req_agility.to_string(),
+                        item.padding5.to_string(),
+                        item.req_wisdom.to_string(),
+                        item.padding6.to_string(),
+                        item.padding7.to_string(),
+                        item.padding8.to_string(),
+                        item.item_category.to_string(),
+                        item.inventory_instance_id.to_string(),
+                    ]
+                })
+                .collect(),
+        );
+        inv_caches.insert(
+            InventoryCategory::Heal,
+            inv.heal_items
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.name.clone(),
+                        item.description.clone(),
+                        item.base_price.to_string(),
+                        item.heal_item_id.to_string(),
+                        item.health_points.to_string(),
+                        item.mana_points.to_string(),
+                        item.restore_full_health.to_string(),
+                        item.restore_full_mana.to_string(),
+                        item.poison_heal.to_string(),
+                        item.petrif_heal.to_string(),
+                        item.polimorph_heal.to_string(),
+                        item.reserved_definition_byte.to_string(),
+                        item.item_category.to_string(),
+                        item.inventory_record_index.to_string(),
+                        hex_bytes(&item.reserved_runtime_bytes),
+                    ]
+                })
+                .collect(),
+        );
+        inv_caches.insert(
+            InventoryCategory::Edit,
+            inv.edit_items
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.name.clone(),
+                        item.description.clone(),
+                        item.base_price.to_string(),
+                        item.edit_item_id.to_string(),
+                        item.health_points.to_string(),
+                        item.mana_points.to_string(),
+                        item.strength.to_string(),
+                        item.agility.to_string(),
+                        item.wisdom.to_string(),
+                        item.constitution.to_string(),
+                        item.to_dodge.to_string(),
+                        item.to_hit.to_string(),
+                        item.offense.to_string(),
+                        item.defense.to_string(),
+                        item.magical_power.to_string(),
+                        item.modification_resistance.to_string(),
+                        item.reserved_byte.to_string(),
+                        item.modifies_item.to_string(),
+                        item.additional_effect.to_string(),
+                        item.item_category.to_string(),
+                        item.item_category_padding.to_string(),
+                        item.inventory_record_index.to_string(),
+                    ]
+                })
+                .collect(),
+        );
+        inv_caches.insert(
+            InventoryCategory::Event,
+            inv.event_items
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.name.clone(),
+                        item.description.clone(),
+                        item.base_price.to_string(),
+                        item.event_item_id.to_string(),
+                        item.item_category.to_string(),
+                        item.item_category_padding.to_string(),
+                        item.inventory_record_index.to_string(),
+                    ]
+                })
+                .collect(),
+        );
+        inv_caches.insert(
+            InventoryCategory::Misc,
+            inv.misc_items
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.name.clone(),
+                        item.description.clone(),
+                        item.base_price.to_string(),
+                        hex_bytes(&item.reserved_definition_bytes),
+                        item.misc_item_id.to_string(),
+                        item.item_category.to_string(),
+                        item.inventory_record_index.to_string(),
+                        item.inventory_instance_id.to_string(),
+                    ]
+                })
+                .collect(),
+        );
+        self.inventory_display_caches = inv_caches;
+    }
+
+    /// Rebuild every journal section's table rows from the loaded save.
+    pub fn refresh_journal_caches(&mut self) {
+        let Some(save) = &self.save_file else {
+            self.journal_display_caches.clear();
+            return;
+        };
+        let mut journal_caches = HashMap::<JournalSection, Vec<Vec<String>>>::new();
+        for (section, entries) in [
+            (JournalSection::Main, &save.journal.main),
+            (JournalSection::Side, &save.journal.side),
+            (JournalSection::Trade, &save.journal.trade),
+        ] {
+            let cache: Vec<Vec<String>> = entries
+                .iter()
+                .map(|entry| {
+                    // let hex_rest: Vec<String> =
+                    //     entry.rest.iter().map(|b| format!("{:02X}", b)).collect();
+                    vec![
+                        entry.entry_index.to_string(),
+                        entry.quest_title.clone(),
+                        entry.quest_id.to_string(),
+                        entry.follow_up_quest_id_1.to_string(),
+                        entry.follow_up_quest_id_2.to_string(),
+                        entry.is_completed.to_string(),
+                        // hex_rest.join(" "),
+                    ]
+                })
+                .collect();
+            journal_caches.insert(section, cache);
         }
+        self.journal_display_caches = journal_caches;
+    }
+}
+
+impl SaveFileViewerState {
+    /// Apply `value` to `field` of record `record_idx` and record it for undo.
+    ///
+    /// Field keys are described in [`edit`]. Returns `false` and leaves the
+    /// save untouched when the value is rejected (unparseable, out of range,
+    /// or addressing a record that does not exist).
+    pub fn edit_field(&mut self, record_idx: usize, field: &str, value: String) -> bool {
+        let Some(save) = self.save_file.as_mut() else {
+            return false;
+        };
+        let Some(old_value) = edit::get_field(save, record_idx, field) else {
+            return false;
+        };
+        if old_value == value {
+            return true;
+        }
+        if !edit::set_field(save, record_idx, field, value.clone()) {
+            return false;
+        }
+        self.edit_history.push(EditAction::FieldChange {
+            record_idx,
+            field: field.to_string(),
+            old_value,
+            new_value: value,
+        });
+        self.refresh_after_edit(field);
+        true
+    }
+
+    /// Replace inventory record `record_idx` of `cat` with item-database
+    /// record `db_idx`, keeping the record's runtime bookkeeping.
+    pub fn pick_inventory_item(
+        &mut self,
+        cat: InventoryCategory,
+        record_idx: usize,
+        db_idx: usize,
+    ) -> bool {
+        let Some(save) = &self.save_file else {
+            return false;
+        };
+        let inv = &save.inventory;
+        let catalog = &self.item_catalog;
+        let value = match cat {
+            InventoryCategory::Weapon => with_definition(
+                &inv.weapon_items,
+                record_idx,
+                &catalog.weapons,
+                db_idx,
+                |r, d| r.apply_definition(d),
+            ),
+            InventoryCategory::Heal => with_definition(
+                &inv.heal_items,
+                record_idx,
+                &catalog.heal,
+                db_idx,
+                |r, d| r.apply_definition(d),
+            ),
+            InventoryCategory::Edit => with_definition(
+                &inv.edit_items,
+                record_idx,
+                &catalog.edit,
+                db_idx,
+                |r, d| r.apply_definition(d),
+            ),
+            InventoryCategory::Event => with_definition(
+                &inv.event_items,
+                record_idx,
+                &catalog.event,
+                db_idx,
+                |r, d| r.apply_definition(d),
+            ),
+            InventoryCategory::Misc => with_definition(
+                &inv.misc_items,
+                record_idx,
+                &catalog.misc,
+                db_idx,
+                |r, d| r.apply_definition(d),
+            ),
+        };
+        match value {
+            Some(value) => self.edit_field(record_idx, &edit::inventory_key(cat), value),
+            None => false,
+        }
+    }
+
+    /// Re-apply a value taken from the edit history.
+    fn apply_history_value(&mut self, record_idx: usize, field: &str, value: String) {
+        if let Some(save) = self.save_file.as_mut() {
+            edit::set_field(save, record_idx, field, value);
+        }
+        self.refresh_after_edit(field);
+    }
+
+    /// Rebuild the table cache that shows `field`. Stats, party members and
+    /// spells render straight from the model and need no refresh.
+    fn refresh_after_edit(&mut self, field: &str) {
+        match edit::edit_target(field) {
+            Some(EditTarget::Journal) => self.refresh_journal_caches(),
+            Some(EditTarget::Events) => self.refresh_events_cache(),
+            Some(EditTarget::Inventory) => self.refresh_inventory_caches(),
+            _ => {}
+        }
+    }
+}
+
+/// Clone `records[record_idx]`, apply `definitions[db_idx]` to it and
+/// serialize the result as an inventory edit value.
+fn with_definition<R: Clone + serde::Serialize, D>(
+    records: &[R],
+    record_idx: usize,
+    definitions: &[D],
+    db_idx: usize,
+    apply: impl Fn(&mut R, &D),
+) -> Option<String> {
+    let mut record = records.get(record_idx)?.clone();
+    apply(&mut record, definitions.get(db_idx)?);
+    serde_json::to_string(&record).ok()
+}
+
+impl UndoRedo for SaveFileViewerState {
+    fn undo(&mut self) -> Option<String> {
+        let action = self.edit_history.undo()?;
+        if let EditAction::FieldChange {
+            record_idx,
+            ref field,
+            ref old_value,
+            ref new_value,
+        } = action
+        {
+            self.apply_history_value(record_idx, field, old_value.clone());
+            return Some(format!(
+                "Undo: Changed {field} to \"{}\"",
+                edit::describe_value(field, new_value)
+            ));
+        }
+        Some(format!("Undo: {}", action.display_text()))
+    }
+
+    fn redo(&mut self) -> Option<String> {
+        let action = self.edit_history.redo()?;
+        // The redo action is the inverted undo action: old_value holds the value to re-apply.
+        if let EditAction::FieldChange {
+            record_idx,
+            ref field,
+            ref old_value,
+            ..
+        } = action
+        {
+            self.apply_history_value(record_idx, field, old_value.clone());
+            return Some(format!(
+                "Redo: Changed {field} to \"{}\"",
+                edit::describe_value(field, old_value)
+            ));
+        }
+        Some(format!("Redo: {}", action.display_text()))
+    }
+
+    fn can_undo(&self) -> bool {
+        self.edit_history.can_undo()
+    }
+
+    fn can_redo(&self) -> bool {
+        self.edit_history.can_redo()
+    }
+
+    fn edit_history(&self) -> &EditHistory {
+        &self.edit_history
+    }
+}
+
+/// Item database records offered by the inventory item pickers.
+///
+/// Indexed by database record position, which is also the id the game
+/// stores in an inventory record's definition slot.
+#[derive(Debug, Clone, Default)]
+pub struct SaveItemCatalog {
+    pub weapons: Vec<WeaponItem>,
+    pub heal: Vec<HealItem>,
+    pub edit: Vec<EditItem>,
+    pub event: Vec<EventItem>,
+    pub misc: Vec<MiscItem>,
+}
+
+impl SaveItemCatalog {
+    /// Load every item database under `game_path/CharacterInGame`. A missing
+    /// or unreadable file leaves that category empty.
+    pub fn load(game_path: &Path) -> Self {
+        fn read<T: Extractor>(game_path: &Path, file_name: &str) -> Vec<T> {
+            find_item_db(game_path, file_name)
+                .and_then(|path| T::read_file(&path).ok())
+                .unwrap_or_default()
+        }
+        SaveItemCatalog {
+            weapons: read(game_path, "weaponItem.db"),
+            heal: read(game_path, "HealItem.db"),
+            edit: read(game_path, "EditItem.db"),
+            event: read(game_path, "EventItem.db"),
+            misc: read(game_path, "MiscItem.db"),
+        }
+    }
+
+    /// Picker entries for `cat` as `("{type}:{db index}", "[Type] name")`,
+    /// the format expected by `composite_item_picker`.
+    pub fn picker_entries(&self, cat: InventoryCategory) -> Vec<(String, String)> {
+        let names: Vec<&str> = match cat {
+            InventoryCategory::Weapon => self.weapons.iter().map(|i| i.name.as_str()).collect(),
+            InventoryCategory::Heal => self.heal.iter().map(|i| i.name.as_str()).collect(),
+            InventoryCategory::Edit => self.edit.iter().map(|i| i.name.as_str()).collect(),
+            InventoryCategory::Event => self.event.iter().map(|i| i.name.as_str()).collect(),
+            InventoryCategory::Misc => self.misc.iter().map(|i| i.name.as_str()).collect(),
+        };
+        let item_type = cat.item_type();
+        names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                (
+                    format!("{}:{i}", item_type.value()),
+                    format!("[{item_type}] {name}"),
+                )
+            })
+            .collect()
     }
 }
 
 impl InventoryCategory {
+    /// Item type stored in composite item keys for this category.
+    pub fn item_type(&self) -> ItemTypeId {
+        match self {
+            InventoryCategory::Event => ItemTypeId::Event,
+            InventoryCategory::Misc => ItemTypeId::Misc,
+            InventoryCategory::Edit => ItemTypeId::Edit,
+            InventoryCategory::Weapon => ItemTypeId::Weapon,
+            InventoryCategory::Heal => ItemTypeId::Healing,
+        }
+    }
+
     /// Human-readable label.
     pub fn label(&self) -> &'static str {
         match self {
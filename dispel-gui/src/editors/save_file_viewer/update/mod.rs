@@ -221,6 +221,13 @@ fn apply_column_width(state: &mut SaveFileViewerState, key: TableKey, col: usize
     }
 }
 
+/// Flag the save file tab as having unsaved edits.
+fn mark_tab_modified(app: &mut App, tab_id: usize) {
+    if let Some(tab) = app.state.workspace.tabs.iter_mut().find(|t| t.id == tab_id) {
+        tab.modified = true;
+    }
+}
+
 pub fn handle(msg: SaveFileViewerMessage, app: &mut App) -> Task<Message> {
     let tab_id = match app.state.workspace.active() {
         Some(t) => t.id,
@@ -241,6 +248,10 @@ pub fn handle(msg: SaveFileViewerMessage, app: &mut App) -> Task<Message> {
                 state.map_preview = None;
             }
             state.active_section = section;
+            if section == SaveFileSection::Compare {
+                // The loaded save may have been edited since the last diff.
+                state.refresh_save_diff();
+            }
             if section == SaveFileSection::SavedViewport {
                 let active_map_index = state.save_file.as_ref().and_then(|save| {
                     save.maps
@@ -1120,6 +1131,95 @@ pub fn handle(msg: SaveFileViewerMessage, app: &mut App) -> Task<Message> {
                 move |result| Message::save_file_viewer(SaveFileViewerMessage::CsvExported(result)),
             )
         }
+        SaveFileViewerMessage::EditField {
+            record_idx,
+            field,
+            value,
+        } => {
+            if state.edit_field(record_idx, &field, value) {
+                mark_tab_modified(app, tab_id);
+            }
+            Task::none()
+        }
+        SaveFileViewerMessage::PickInventoryItem {
+            cat,
+            record_idx,
+            key,
+        } => {
+            let db_idx = key
+                .split(':')
+                .nth(1)
+                .and_then(|id| id.parse::<usize>().ok());
+            if let Some(db_idx) = db_idx
+                && state.pick_inventory_item(cat, record_idx, db_idx)
+            {
+                mark_tab_modified(app, tab_id);
+            }
+            Task::none()
+        }
+        SaveFileViewerMessage::Save => {
+            let (Some(save_file), Some(path)) = (state.save_file.clone(), state.file_path.clone())
+            else {
+                return Task::none();
+            };
+            if state.saving {
+                return Task::none();
+            }
+            state.saving = true;
+            state.status_msg = Some("Saving…".to_string());
+            Task::perform(
+                async move {
+                    save_file
+                        .save_with_backup(&path)
+                        .map_err(|e| format!("Failed to save {}: {e}", path.display()))
+                },
+                |result| Message::save_file_viewer(SaveFileViewerMessage::Saved(result)),
+            )
+        }
+        SaveFileViewerMessage::Saved(result) => {
+            state.saving = false;
+            match result {
+                Ok(backup) => {
+                    state.status_msg = Some(match backup {
+                        Some(backup) => format!("Saved; original kept as {}", backup.display()),
+                        None => "Saved".to_string(),
+                    });
+                    if let Some(tab) = app.state.workspace.tabs.iter_mut().find(|t| t.id == tab_id)
+                    {
+                        tab.modified = false;
+                    }
+                    Task::none()
+                }
+                Err(e) => {
+                    state.status_msg = None;
+                    Task::done(Message::System(crate::message::SystemMessage::ShowError(e)))
+                }
+            }
+        }
+        SaveFileViewerMessage::CompareWith => Task::perform(
+            async move {
+                let handle = rfd::AsyncFileDialog::new()
+                    .add_filter("Dispel save", &["sav"])
+                    .pick_file()
+                    .await
+                    .ok_or_else(|| "cancelled".to_string())?;
+                let path = handle.path().to_path_buf();
+                let data = tokio::fs::read(&path).await.map_err(|e| e.to_string())?;
+                let save = dispel_core::references::save_file::SaveFile::parse(&data)
+                    .map_err(|e| format!("Could not parse {}: {e}", path.display()))?;
+                Ok((path, save))
+            },
+            |result| Message::save_file_viewer(SaveFileViewerMessage::CompareLoaded(result)),
+        ),
+        SaveFileViewerMessage::CompareLoaded(result) => match result {
+            Ok(compare) => {
+                state.compare_save = Some(compare);
+                state.refresh_save_diff();
+                Task::none()
+            }
+            Err(e) if e == "cancelled" => Task::none(),
+            Err(e) => Task::done(Message::System(crate::message::SystemMessage::ShowError(e))),
+        },
         SaveFileViewerMessage::Load(_) => {
             // Load is handled by app.rs::open_file_in_workspace via Task::perform
             state.loading = true;
@@ -1134,22 +1234,11 @@ pub fn handle(msg: SaveFileViewerMessage, app: &mut App) -> Task<Message> {
                 Ok(loaded) => {
                     state.save_file = Some(loaded.save_file.clone());
                     state.map_name_lookup = loaded.map_names;
+                    state.item_catalog = loaded.item_catalog;
+                    state.edit_history.clear();
                     // Build events display cache
-                    let n = loaded.save_file.events.len();
-                    let mut display_cache = Vec::with_capacity(n);
-                    for ev in loaded.save_file.events.iter() {
-                        display_cache.push(vec![
-                            ev.event_id.to_string(),
-                            ev.required_event_id.to_string(),
-                            ev.event_type.to_string(),
-                            ev.script_filename.clone(),
-                            ev.execution_limit.to_string(),
-                            ev.execution_count.to_string(),
-                            ev.has_triggered.to_string(),
-                        ]);
-                    }
-                    state.events_display_cache = display_cache;
-                    state.events_filtered_indices = (0..n).collect();
+                    state.refresh_events_cache();
+                    state.events_filtered_indices = (0..state.events_display_cache.len()).collect();
                     state.raw_hex_viewers = loaded
                         .hex_editors
                         .into_iter()
@@ -1169,139 +1258,7 @@ pub fn handle(msg: SaveFileViewerMessage, app: &mut App) -> Task<Message> {
                         .collect();
                     // Build inventory display caches
                     use crate::editors::save_file_viewer::state::InventoryCategory;
-                    let inv = &loaded.save_file.inventory;
-                    let mut inv_caches = std::collections::HashMap::new();
-                    inv_caches.insert(
-                        InventoryCategory::Weapon,
-                        inv.weapon_items
-                            .iter()
-                            .map(|item| {
-                                vec![
-                                    item.name.clone(),
-                                    item.description.clone(),
-                                    item.base_price.to_string(),
-                                    item.weapon_item_id.to_string(),
-                                    item.health_points.to_string(),
-                                    item.mana_points.to_string(),
-                                    item.strength.to_string(),
-                                    item.agility.to_string(),
-                                    item.wisdom.to_string(),
-                                    item.constitution.to_string(),
-                                    item.to_dodge.to_string(),
-                                    item.to_hit.to_string(),
-                                    item.attack.to_string(),
-                                    item.defense.to_string(),
-                                    item.magical_strength.to_string(),
-                                    item.durability.to_string(),
-                                    item.padding2.to_string(),
-                                    item.padding3.to_string(),
-                                    item.req_strength.to_string(),
-                                    item.padding4.to_string(),
-                                    item.req_agility.to_string(),
-                                    item.padding5.to_string(),
-                                    item.req_wisdom.to_string(),
-                                    item.padding6.to_string(),
-                                    item.padding7.to_string(),
-                                    item.padding8.to_string(),
-                                    item.item_category.to_string(),
-                                    item.inventory_instance_id.to_string(),
-                                ]
-                            })
-                            .collect(),
-                    );
-                    inv_caches.insert(
-                        InventoryCategory::Heal,
-                        inv.heal_items
-                            .iter()
-                            .map(|item| {
-                                vec![
-                                    item.name.clone(),
-                                    item.description.clone(),
-                                    item.base_price.to_string(),
-                                    item.heal_item_id.to_string(),
-                                    item.health_points.to_string(),
-                                    item.mana_points.to_string(),
-                                    item.restore_full_health.to_string(),
-                                    item.restore_full_mana.to_string(),
-                                    item.poison_heal.to_string(),
-                                    item.petrif_heal.to_string(),
-                                    item.polimorph_heal.to_string(),
-                                    item.reserved_definition_byte.to_string(),
-                                    item.item_category.to_string(),
-                                    item.inventory_record_index.to_string(),
-                                    hex_bytes(&item.reserved_runtime_bytes),
-                                ]
-                            })
-                            .collect(),
-                    );
-                    inv_caches.insert(
-                        InventoryCategory::Edit,
-                        inv.edit_items
-                            .iter()
-                            .map(|item| {
-                                vec![
-                                    item.name.clone(),
-                                    item.description.clone(),
-                                    item.base_price.to_string(),
-                                    item.edit_item_id.to_string(),
-                                    item.health_points.to_string(),
-                                    item.mana_points.to_string(),
-                                    item.strength.to_string(),
-                                    item.agility.to_string(),
-                                    item.wisdom.to_string(),
-                                    item.constitution.to_string(),
-                                    item.to_dodge.to_string(),
-                                    item.to_hit.to_string(),
-                                    item.offense.to_string(),
-                                    item.defense.to_string(),
-                                    item.magical_power.to_string(),
-                                    item.modification_resistance.to_string(),
-                                    item.reserved_byte.to_string(),
-                                    item.modifies_item.to_string(),
-                                    item.additional_effect.to_string(),
-                                    item.item_category.to_string(),
-                                    item.item_category_padding.to_string(),
-                                    item.inventory_record_index.to_string(),
-                                ]
-                            })
-                            .collect(),
-                    );
-                    inv_caches.insert(
-                        InventoryCategory::Event,
-                        inv.event_items
-                            .iter()
-                            .map(|item| {
-                                vec![
-                                    item.name.clone(),
-                                    item.description.clone(),
-                                    item.base_price.to_string(),
-                                    item.event_item_id.to_string(),
-                                    item.item_category.to_string(),
-                                    item.item_category_padding.to_string(),
-                                    item.inventory_record_index.to_string(),
-                                ]
-                            })
-                            .collect(),
-                    );
-                    inv_caches.insert(
-                        InventoryCategory::Misc,
-                        inv.misc_items
-                            .iter()
-                            .map(|item| {
-                                vec![
-                                    item.name.clone(),
-                                    item.description.clone(),
-                                    item.base_price.to_string(),
-                                    hex_bytes(&item.reserved_definition_bytes),
-                                    item.misc_item_id.to_string(),
-                                    item.item_category.to_string(),
-                                    item.inventory_record_index.to_string(),
-                                    item.inventory_instance_id.to_string(),
-                                ]
-                            })
-                            .collect(),
-                    );
-                    state.inventory_display_caches = inv_caches;
+                    state.refresh_inventory_caches();
                     state.inventory_filtered_indices = state
                         .inventory_display_caches
                         .iter()
@@ -1866,38 +1823,12 @@ pub fn handle(msg: SaveFileViewerMessage, app: &mut App) -> Task<Message> {
                     }
                     state.maps_table_states = table_states;
                     // Build journal display caches
-                    use crate::editors::save_file_viewer::state::JournalSection;
-                    let mut journal_caches =
-                        std::collections::HashMap::<JournalSection, Vec<Vec<String>>>::new();
-                    let mut journal_indices =
-                        std::collections::HashMap::<JournalSection, Vec<usize>>::new();
-                    for (section, entries) in [
-                        (JournalSection::Main, &loaded.save_file.journal.main),
-                        (JournalSection::Side, &loaded.save_file.journal.side),
-                        (JournalSection::Trade, &loaded.save_file.journal.trade),
-                    ] {
-                        let cache: Vec<Vec<String>> = entries
-                            .iter()
-                            .map(|entry| {
-                                // let hex_rest: Vec<String> =
-                                //     entry.rest.iter().map(|b| format!("{:02X}", b)).collect();
-                                vec![
-                                    entry.entry_index.to_string(),
-                                    entry.quest_title.clone(),
-                                    entry.quest_id.to_string(),
-                                    entry.follow_up_quest_id_1.to_string(),
-                                    entry.follow_up_quest_id_2.to_string(),
-                                    entry.is_completed.to_string(),
-                                    // hex_rest.join(" "),
-                                ]
-                            })
-                            .collect();
-                        let indices: Vec<usize> = (0..cache.len()).collect();
-                        journal_caches.insert(section, cache);
-                        journal_indices.insert(section, indices);
-                    }
-                    state.journal_display_caches = journal_caches;
-                    state.journal_filtered_indices = journal_indices;
+                    state.refresh_journal_caches();
+                    state.journal_filtered_indices = state
+                        .journal_display_caches
+                        .iter()
+                        .map(|(section, rows)| (*section, (0..rows.len()).collect()))
+                        .collect();
                     state.error = None;
                 }
                 Err(e) => {
@@ -1,5 +1,5 @@
-use iced::widget::{Column, button, container, row, text};
-use iced::{Element, Fill};
+use iced::widget::{Column, Space, button, container, row, text};
+use iced::{Alignment, Element, Fill};
 
 use crate::app::App;
 use crate::editors::save_file_viewer::state::SaveFileSection;
@@ -49,7 +49,27 @@ pub fn view<'a>(app: &'a App) -> Element<'a, Message> {
         })
         .collect();
 
-    let section_tabs = row(buttons).spacing(4).padding(8);
+    // Save button; the tab's modified flag tracks unsaved edits.
+    let modified = app.state.workspace.active().is_some_and(|t| t.modified);
+    let save_btn = button(text(if modified { "Save *" } else { "Save" }).size(12))
+        .on_press_maybe((!state.saving && state.file_path.is_some()).then(|| {
+            Message::save_file_viewer(crate::editors::save_file_viewer::SaveFileViewerMessage::Save)
+        }))
+        .padding([4, 10])
+        .style(style::export_button);
+    let status = text(state.status_msg.clone().unwrap_or_default())
+        .size(12)
+        .style(style::subtle_text);
+
+    let section_tabs = row![
+        row(buttons).spacing(4),
+        Space::new().width(Fill),
+        status,
+        save_btn
+    ]
+    .spacing(8)
+    .padding(8)
+    .align_y(Alignment::Center);
 
     // Section content
     let content: Element<'a, Message> = match state.active_section {
@@ -63,6 +83,7 @@ pub fn view<'a>(app: &'a App) -> Element<'a, Message> {
         SaveFileSection::Raw => crate::editors::save_file_viewer::raw::view(state),
         SaveFileSection::Events => crate::editors::save_file_viewer::events::view(state),
         SaveFileSection::Journal => crate::editors::save_file_viewer::journal::view(state),
+        SaveFileSection::Compare => crate::editors::save_file_viewer::compare::view(state),
         SaveFileSection::Maps => crate::editors::save_file_viewer::maps::view(state),
         SaveFileSection::SavedViewport => {
             crate::editors::save_file_viewer::saved_viewport::view(state)
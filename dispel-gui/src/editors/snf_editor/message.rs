@@ -9,10 +9,11 @@ pub enum SnfEditorMessage {
     SetVolume(f32),
     ExportWav,
     ExportWavDone(Result<String, String>),
-    /// Replace current audio with audio from a WAV file.
-    ImportWav,
-    /// Async result of ImportWav.
-    ImportWavDone(Result<(SnfFile, String), String>),
+    /// Replace current audio with a WAV, OGG, FLAC or MP3 file, converted
+    /// to the format of the current audio.
+    ImportAudio,
+    /// Async result of ImportAudio.
+    ImportAudioDone(Result<(SnfFile, String), String>),
     /// Save the current audio back to the original .snf file.
     Save,
     /// Async result of Save.
@@ -168,19 +168,26 @@ pub fn handle(message: SnfEditorMessage, app: &mut App) -> Task<crate::message::
             }
         },
 
-        SnfEditorMessage::ImportWav => {
+        SnfEditorMessage::ImportAudio => {
+            // Keep the format the game expects for this sound.
+            let target = editor.snf.as_ref().map(|snf| snf.format());
             return Task::perform(
                 async move {
                     let handle = rfd::AsyncFileDialog::new()
-                        .add_filter("WAV Audio", &["wav"])
-                        .set_file_name("sound.wav")
+                        .add_filter("Audio", &["wav", "ogg", "flac", "mp3"])
                         .pick_file()
                         .await;
                     match handle {
                         Some(h) => {
-                            let wav_path = h.path().to_path_buf();
-                            match dispel_core::snf::read_wav(&wav_path) {
-                                Ok(snf) => Ok((snf, wav_path.to_string_lossy().to_string())),
+                            let audio_path = h.path().to_path_buf();
+                            let snf = dispel_core::snf::read_audio(&audio_path).and_then(|snf| {
+                                match target {
+                                    Some(target) => snf.convert(target),
+                                    None => Ok(snf),
+                                }
+                            });
+                            match snf {
+                                Ok(snf) => Ok((snf, audio_path.to_string_lossy().to_string())),
                                 Err(e) => Err(e.to_string()),
                             }
                         }
@@ -189,16 +196,16 @@ pub fn handle(message: SnfEditorMessage, app: &mut App) -> Task<crate::message::
                 },
                 |r: Result<(dispel_core::snf::SnfFile, String), String>| match r {
                     Ok((snf, path)) => crate::message::Message::snf_editor(
-                        SnfEditorMessage::ImportWavDone(Ok((snf, path))),
+                        SnfEditorMessage::ImportAudioDone(Ok((snf, path))),
+                    ),
+                    Err(e) => crate::message::Message::snf_editor(
+                        SnfEditorMessage::ImportAudioDone(Err(e)),
                     ),
-                    Err(e) => {
-                        crate::message::Message::snf_editor(SnfEditorMessage::ImportWavDone(Err(e)))
-                    }
                 },
             );
         }
 
-        SnfEditorMessage::ImportWavDone(result) => match result {
+        SnfEditorMessage::ImportAudioDone(result) => match result {
             Ok((snf, path)) => {
                 editor.snf = Some(snf.clone());
                 editor.waveform = snf.waveform_points(1000);
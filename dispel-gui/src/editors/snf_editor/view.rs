@@ -51,11 +51,11 @@ pub fn view(app: &App) -> Element<'_, Message> {
         button(
             row![
                 text(icon_char(Icon::FolderOpen)).font(LUCIDE_FONT).size(12),
-                text(" Import WAV…").size(12),
+                text(" Import Audio…").size(12),
             ]
             .spacing(4),
         )
-        .on_press(Message::snf_editor(SnfEditorMessage::ImportWav)),
+        .on_press(Message::snf_editor(SnfEditorMessage::ImportAudio)),
         button(text("Export WAV…").size(12))
             .on_press(Message::snf_editor(SnfEditorMessage::ExportWav)),
         if editor.modified {
@@ -1,4 +1,4 @@
-use dispel_core::{EditItem, HealItem, MiscItem, Store, WeaponItem};
+use dispel_core::{EditItem, HealItem, MiscItem, ProductType, Store, WeaponItem};
 use iced::widget::{pane_grid, text_editor};
 
 #[allow(clippy::type_complexity)]
@@ -20,9 +20,7 @@ pub enum StoreEditorMessage {
     SelectStore(usize),
     FieldChanged(usize, String, String),
     SelectProduct(usize),
-    AddProduct,
     RemoveProduct(usize),
-    ProductFieldChanged(usize, String, String),
     Save,
     Saved(Result<(), String>),
     InvitationChanged(text_editor::Action),
@@ -31,7 +29,7 @@ pub enum StoreEditorMessage {
     PaneResized(pane_grid::ResizeEvent),
     OpenProductModal(Option<usize>),
     CloseProductModal,
-    ModalTypeChanged(i16),
-    ModalItemIdChanged(String),
+    ModalTypeChanged(ProductType),
+    ModalItemChanged(i16),
     SaveModalProduct,
 }
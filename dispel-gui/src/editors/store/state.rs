@@ -1,6 +1,6 @@
 use crate::components::edit_history::EditHistory;
 use crate::components::generic_editor::UndoRedo;
-use dispel_core::{Extractor, ProductType, Store};
+use dispel_core::{Extractor, ProductSlot, ProductType, Store, StoreInventoryError};
 use iced::widget::{pane_grid, text_editor};
 use std::path::PathBuf;
 
@@ -11,13 +11,6 @@ pub enum StorePaneContent {
     ProductList,
 }
 
-#[derive(Debug, Clone)]
-pub struct EditableProduct {
-    pub order: i16,
-    pub product_type: i16,
-    pub item_id: i16,
-}
-
 #[derive(Debug, Clone)]
 pub struct StoreEditorState {
     pub catalog: Option<Vec<Store>>,
@@ -35,7 +28,7 @@ pub struct StoreEditorState {
     pub edit_haggle_success_content: text_editor::Content,
     pub edit_haggle_fail_content: text_editor::Content,
 
-    pub edit_products: Vec<EditableProduct>,
+    pub edit_products: Vec<ProductSlot>,
     pub selected_product_idx: Option<usize>,
 
     pub status_msg: String,
@@ -45,8 +38,8 @@ pub struct StoreEditorState {
     pub pane_state: pane_grid::State<StorePaneContent>,
     pub show_product_modal: bool,
     pub modal_product_idx: Option<usize>,
-    pub modal_edit_type: i16,
-    pub modal_edit_item_id: String,
+    pub modal_edit_type: ProductType,
+    pub modal_edit_item_id: i16,
 }
 
 impl Default for StoreEditorState {
@@ -87,8 +80,8 @@ impl Default for StoreEditorState {
             pane_state: pane_grid::State::with_configuration(config),
             show_product_modal: false,
             modal_product_idx: None,
-            modal_edit_type: 1,
-            modal_edit_item_id: String::from("1"),
+            modal_edit_type: ProductType::Weapon,
+            modal_edit_item_id: 0,
         }
     }
 }
@@ -161,15 +154,7 @@ impl StoreEditorState {
             self.edit_haggle_success_content =
                 text_editor::Content::with_text(&record.haggle_success);
             self.edit_haggle_fail_content = text_editor::Content::with_text(&record.haggle_fail);
-            self.edit_products = record
-                .products
-                .iter()
-                .map(|(order, ptype, item_id)| EditableProduct {
-                    order: *order,
-                    product_type: i32::from(*ptype) as i16,
-                    item_id: *item_id,
-                })
-                .collect();
+            self.edit_products = record.product_slots();
         }
     }
 
@@ -261,64 +246,71 @@ impl StoreEditorState {
         }
     }
 
-    pub fn add_product(&mut self) {
-        let new_order = self.edit_products.len() as i16;
-        self.edit_products.push(EditableProduct {
-            order: new_order,
-            product_type: 1,
-            item_id: 0,
-        });
-        self.sync_products_to_record();
+    /// Append a product to the selected store. Fails when the store is an
+    /// inn or already holds the maximum number of products.
+    pub fn add_product(&mut self, slot: ProductSlot) -> Result<(), StoreInventoryError> {
+        let mut products = self.edit_products.clone();
+        products.push(slot);
+        self.set_products(products)
     }
 
     pub fn remove_product(&mut self, prod_idx: usize) {
         if prod_idx < self.edit_products.len() {
-            self.edit_products.remove(prod_idx);
-            for (i, p) in self.edit_products.iter_mut().enumerate() {
-                p.order = i as i16;
+            let mut products = self.edit_products.clone();
+            products.remove(prod_idx);
+            if self.set_products(products).is_err() {
+                return;
             }
             if self.selected_product_idx == Some(prod_idx) {
                 self.selected_product_idx = None;
             } else if self.selected_product_idx > Some(prod_idx) {
                 self.selected_product_idx = self.selected_product_idx.map(|v| v - 1);
             }
-            self.sync_products_to_record();
         }
     }
 
-    pub fn update_product(&mut self, prod_idx: usize, field: &str, value: String) {
-        if let Some(product) = self.edit_products.get_mut(prod_idx) {
-            match field {
-                "product_type" => {
-                    if let Ok(v) = value.parse() {
-                        product.product_type = v;
-                    }
-                }
-                "item_id" => {
-                    if let Ok(v) = value.parse() {
-                        product.item_id = v;
-                    }
-                }
-                _ => {}
-            }
-            self.sync_products_to_record();
+    pub fn replace_product(
+        &mut self,
+        prod_idx: usize,
+        slot: ProductSlot,
+    ) -> Result<(), StoreInventoryError> {
+        let mut products = self.edit_products.clone();
+        if let Some(product) = products.get_mut(prod_idx) {
+            *product = slot;
         }
+        self.set_products(products)
     }
 
-    fn sync_products_to_record(&mut self) {
-        if let Some(selected_idx) = self.selected_idx
-            && let Some((_, record)) = self.filtered_stores.get_mut(selected_idx)
+    /// Validate `products` against the selected store and write them to
+    /// both the filtered view and the catalog that gets saved.
+    fn set_products(&mut self, products: Vec<ProductSlot>) -> Result<(), StoreInventoryError> {
+        let Some(orig_idx) = self
+            .selected_idx
+            .and_then(|sel| self.filtered_stores.get(sel))
+            .map(|(i, _)| *i)
+        else {
+            return Ok(());
+        };
+        if let Some((_, record)) = self
+            .filtered_stores
+            .iter_mut()
+            .find(|(i, _)| *i == orig_idx)
         {
-            record.products = self
-                .edit_products
-                .iter()
-                .map(|p| {
-                    let ptype = ProductType::from_i32(p.product_type as i32)
-                        .unwrap_or(ProductType::MiscItem);
-                    (p.order, ptype, p.item_id)
-                })
-                .collect();
+            record.set_products(products.iter().copied())?;
+        }
+        if let Some(catalog) = &mut self.catalog
+            && let Some(cat_record) = catalog.get_mut(orig_idx)
+        {
+            cat_record.set_products(products.iter().copied())?;
         }
+        self.edit_products = products;
+        Ok(())
+    }
+
+    /// The selected store's products in `type:id` form, as the store
+    /// patcher takes them.
+    pub fn products_text(&self) -> String {
+        ProductSlot::format_list(&self.edit_products)
     }
 
     pub fn select_product(&mut self, idx: usize) {
@@ -329,41 +321,6 @@ impl StoreEditorState {
         self.edit_inn_night_cost.parse::<i32>().unwrap_or(0) > 0
     }
 
-    pub fn get_product_item_name(
-        &self,
-        product_type: i16,
-        item_id: i16,
-        weapons: &Option<Vec<dispel_core::WeaponItem>>,
-        heals: &Option<Vec<dispel_core::HealItem>>,
-        misc: &Option<Vec<dispel_core::MiscItem>>,
-        edit: &Option<Vec<dispel_core::EditItem>>,
-    ) -> String {
-        let idx = item_id as usize;
-        match product_type {
-            1 => weapons
-                .as_ref()
-                .and_then(|w| w.get(idx))
-                .map(|i| i.name.clone())
-                .unwrap_or_else(|| format!("Weapon #{}", item_id)),
-            2 => heals
-                .as_ref()
-                .and_then(|h| h.get(idx))
-                .map(|i| i.name.clone())
-                .unwrap_or_else(|| format!("HealItem #{}", item_id)),
-            3 => edit
-                .as_ref()
-                .and_then(|e| e.get(idx))
-                .map(|i| i.name.clone())
-                .unwrap_or_else(|| format!("EditItem #{}", item_id)),
-            4 => misc
-                .as_ref()
-                .and_then(|m| m.get(idx))
-                .map(|i| i.name.clone())
-                .unwrap_or_else(|| format!("MiscItem #{}", item_id)),
-            _ => format!("Unknown #{}", item_id),
-        }
-    }
-
     pub fn save_stores(&self, game_path: &str) -> Result<(), String> {
         let path = PathBuf::from(game_path)
             .join("CharacterInGame")
@@ -461,4 +418,37 @@ mod tests {
         assert_eq!(editor.filtered_stores[0].1.store_name, "v0");
         assert!(!editor.edit_history.can_undo());
     }
+
+    #[test]
+    fn test_product_edits_reach_the_saved_catalog() {
+        let mut editor = editor_with_one_store("Smithy");
+        let dagger = ProductSlot::new(ProductType::Weapon, 2);
+        editor.add_product(dagger).unwrap();
+        editor
+            .add_product(ProductSlot::new(ProductType::Healing, 1))
+            .unwrap();
+        editor.remove_product(0);
+        let saved = &editor.catalog.as_ref().unwrap()[0];
+        assert_eq!(saved.product_slots(), editor.edit_products);
+        assert_eq!(editor.products_text(), "healing:1");
+        assert_eq!(saved.products[0].0, 0);
+    }
+
+    #[test]
+    fn test_product_limit_and_inn_mode_enforced() {
+        let mut editor = editor_with_one_store("Smithy");
+        let slot = ProductSlot::new(ProductType::MiscItem, 0);
+        for _ in 0..dispel_core::MAX_STORE_PRODUCTS {
+            editor.add_product(slot).unwrap();
+        }
+        assert!(editor.add_product(slot).is_err());
+        assert_eq!(editor.edit_products.len(), dispel_core::MAX_STORE_PRODUCTS);
+
+        let mut inn = editor_with_one_store("Tavern");
+        inn.update_field(0, "inn_night_cost", "25".to_string());
+        assert_eq!(
+            inn.add_product(slot),
+            Err(StoreInventoryError::InnHasProducts)
+        );
+    }
 }
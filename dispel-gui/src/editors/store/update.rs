@@ -1,9 +1,12 @@
 use crate::app::App;
 use crate::components::editable::EditableRecord;
 use crate::components::loading_state::LoadingState;
-use crate::editors::store::StoreEditorMessage;
+use crate::editors::store::{StoreEditorMessage, StoreEditorState};
 use crate::message::MessageExt;
-use dispel_core::{EditItem, Extractor, HealItem, MiscItem, Store, WeaponItem};
+use dispel_core::{
+    EditItem, Extractor, HealItem, MiscItem, ProductSlot, ProductType, Store, StoreInventoryError,
+    WeaponItem,
+};
 use iced::Task;
 use std::path::PathBuf;
 
@@ -136,21 +139,10 @@ pub fn handle(message: StoreEditorMessage, app: &mut App) -> Task<crate::message
             app.state.editors.store_editor.status_msg = format!("Selected product {}", index);
             Task::none()
         }
-        StoreEditorMessage::AddProduct => {
-            app.state.editors.store_editor.add_product();
-            Task::none()
-        }
-        StoreEditorMessage::RemoveProduct(index) => {
-            app.state.editors.store_editor.remove_product(index);
-            Task::none()
-        }
-        StoreEditorMessage::ProductFieldChanged(product_idx, field, value) => {
-            app.state
-                .editors
-                .store_editor
-                .update_product(product_idx, &field, value);
-            Task::none()
-        }
+        StoreEditorMessage::RemoveProduct(index) => record_product_edit(app, |editor| {
+            editor.remove_product(index);
+            Ok(())
+        }),
         StoreEditorMessage::Save => {
             if app.state.shared_game_path.is_empty() {
                 app.state.editors.store_editor.status_msg = "Please select game path first.".into();
@@ -234,15 +226,11 @@ pub fn handle(message: StoreEditorMessage, app: &mut App) -> Task<crate::message
             let editor = &mut app.state.editors.store_editor;
             editor.show_product_modal = true;
             editor.modal_product_idx = opt_idx;
-            if let Some(idx) = opt_idx {
-                if let Some(prod) = editor.edit_products.get(idx) {
-                    editor.modal_edit_type = prod.product_type;
-                    editor.modal_edit_item_id = prod.item_id.to_string();
-                }
-            } else {
-                editor.modal_edit_type = 1;
-                editor.modal_edit_item_id = "1".to_string();
-            }
+            let slot = opt_idx
+                .and_then(|idx| editor.edit_products.get(idx).copied())
+                .unwrap_or(ProductSlot::new(ProductType::Weapon, 0));
+            editor.modal_edit_type = slot.product_type;
+            editor.modal_edit_item_id = slot.item_id;
             Task::none()
         }
         StoreEditorMessage::CloseProductModal => {
@@ -250,29 +238,61 @@ pub fn handle(message: StoreEditorMessage, app: &mut App) -> Task<crate::message
             Task::none()
         }
         StoreEditorMessage::ModalTypeChanged(t) => {
-            app.state.editors.store_editor.modal_edit_type = t;
+            let editor = &mut app.state.editors.store_editor;
+            if editor.modal_edit_type != t {
+                editor.modal_edit_type = t;
+                editor.modal_edit_item_id = 0;
+            }
             Task::none()
         }
-        StoreEditorMessage::ModalItemIdChanged(s) => {
-            app.state.editors.store_editor.modal_edit_item_id = s;
+        StoreEditorMessage::ModalItemChanged(id) => {
+            app.state.editors.store_editor.modal_edit_item_id = id;
             Task::none()
         }
         StoreEditorMessage::SaveModalProduct => {
-            let editor = &mut app.state.editors.store_editor;
-            let type_val = editor.modal_edit_type.to_string();
-            let id_val = editor.modal_edit_item_id.clone();
-
-            if let Some(prod_idx) = editor.modal_product_idx {
-                editor.update_product(prod_idx, "product_type", type_val);
-                editor.update_product(prod_idx, "item_id", id_val);
-            } else {
-                editor.add_product();
-                let new_idx = editor.edit_products.len().saturating_sub(1);
-                editor.update_product(new_idx, "product_type", type_val);
-                editor.update_product(new_idx, "item_id", id_val);
-            }
-            editor.show_product_modal = false;
-            Task::none()
+            let editor = &app.state.editors.store_editor;
+            let slot = ProductSlot::new(editor.modal_edit_type, editor.modal_edit_item_id);
+            let target = editor.modal_product_idx;
+            let task = record_product_edit(app, |editor| match target {
+                Some(prod_idx) => editor.replace_product(prod_idx, slot),
+                None => editor.add_product(slot),
+            });
+            app.state.editors.store_editor.show_product_modal = false;
+            task
         }
     }
 }
+
+/// Apply a product-table edit to the selected store and, when the table
+/// changed, report it as a `products` field change so recordings and the
+/// journal pick it up in the store patcher's `type:id` form.
+fn record_product_edit(
+    app: &mut App,
+    edit: impl FnOnce(&mut StoreEditorState) -> Result<(), StoreInventoryError>,
+) -> Task<crate::message::Message> {
+    let editor = &mut app.state.editors.store_editor;
+    let Some(orig_idx) = editor
+        .selected_idx
+        .and_then(|sel| editor.filtered_stores.get(sel))
+        .map(|(i, _)| *i as u32)
+    else {
+        return Task::none();
+    };
+    let old_value = editor.products_text();
+    if let Err(e) = edit(editor) {
+        editor.status_msg = format!("Cannot change products: {}", e);
+        return Task::none();
+    }
+    let new_value = editor.products_text();
+    if old_value == new_value {
+        return Task::none();
+    }
+    crate::editors::mod_packager::recording::observe_field_change(
+        app,
+        "CharacterInGame/STORE.DB",
+        orig_idx,
+        "products",
+        old_value,
+        new_value,
+    )
+}
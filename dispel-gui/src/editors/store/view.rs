@@ -1,60 +1,25 @@
 use crate::app::App;
 use crate::components::utils::{horizontal_rule, horizontal_space};
 use crate::editors::store::StoreEditorMessage;
-use crate::editors::store::{EditableProduct, StoreEditorState, StorePaneContent};
+use crate::editors::store::{StoreEditorState, StorePaneContent};
 use crate::message::{Message, MessageExt};
 use crate::style;
+use dispel_core::references::store_inventory::{store_buy_price, store_sell_price};
+use dispel_core::{
+    MAX_STORE_PRODUCTS, PRODUCT_TYPES, ProductSlot, ProductType, ResolvedProduct, StoreCatalog,
+};
 use gui_widgets::components::modal::modal;
 use gui_widgets::textarea;
 use iced::widget::pane_grid::{self};
 use iced::widget::{button, column, container, pick_list, row, scrollable, text, text_input};
 use iced::{Element, Fill, Length};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ProductTypeOption {
-    Weapon,
-    HealItem,
-    EditItem,
-    MiscItem,
-}
-
-impl std::fmt::Display for ProductTypeOption {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ProductTypeOption::Weapon => write!(f, "Weapon"),
-            ProductTypeOption::HealItem => write!(f, "HealItem"),
-            ProductTypeOption::EditItem => write!(f, "EditItem"),
-            ProductTypeOption::MiscItem => write!(f, "MiscItem"),
-        }
-    }
-}
-
-impl ProductTypeOption {
-    fn to_id(self) -> i16 {
-        match self {
-            ProductTypeOption::Weapon => 1,
-            ProductTypeOption::HealItem => 2,
-            ProductTypeOption::EditItem => 3,
-            ProductTypeOption::MiscItem => 4,
-        }
-    }
-
-    fn from_id(id: i16) -> Self {
-        match id {
-            2 => ProductTypeOption::HealItem,
-            3 => ProductTypeOption::EditItem,
-            4 => ProductTypeOption::MiscItem,
-            _ => ProductTypeOption::Weapon,
-        }
-    }
-
-    fn all() -> &'static [ProductTypeOption] {
-        &[
-            ProductTypeOption::Weapon,
-            ProductTypeOption::HealItem,
-            ProductTypeOption::EditItem,
-            ProductTypeOption::MiscItem,
-        ]
+fn product_type_label(product_type: ProductType) -> &'static str {
+    match product_type {
+        ProductType::Weapon => "Weapon",
+        ProductType::Healing => "HealItem",
+        ProductType::EditItem => "EditItem",
+        ProductType::MiscItem => "MiscItem",
     }
 }
 
@@ -82,12 +47,12 @@ fn type_color_misc(_theme: &iced::Theme) -> iced::widget::text::Style {
     }
 }
 
-fn type_text_style(product_type: i16) -> fn(&iced::Theme) -> iced::widget::text::Style {
+fn type_text_style(product_type: ProductType) -> fn(&iced::Theme) -> iced::widget::text::Style {
     match product_type {
-        1 => type_color_weapon,
-        2 => type_color_heal,
-        3 => type_color_edit,
-        _ => type_color_misc,
+        ProductType::Weapon => type_color_weapon,
+        ProductType::Healing => type_color_heal,
+        ProductType::EditItem => type_color_edit,
+        ProductType::MiscItem => type_color_misc,
     }
 }
 
@@ -253,16 +218,22 @@ fn store_details_pane<'a>(editor: &'a StoreEditorState) -> Element<'a, Message>
 
 fn product_list_pane<'a>(
     editor: &'a StoreEditorState,
-    weapons: &'a Option<Vec<dispel_core::WeaponItem>>,
-    heals: &'a Option<Vec<dispel_core::HealItem>>,
-    misc: &'a Option<Vec<dispel_core::MiscItem>>,
-    edit_items: &'a Option<Vec<dispel_core::EditItem>>,
+    catalog: &StoreCatalog<'a>,
 ) -> Element<'a, Message> {
-    let can_add = editor.selected_idx.is_some() && !editor.is_inn();
+    let can_add = editor.selected_idx.is_some()
+        && !editor.is_inn()
+        && editor.edit_products.len() < MAX_STORE_PRODUCTS;
 
     let header = container(
         row![
             text("Products").size(13),
+            text(format!(
+                "{}/{}",
+                editor.edit_products.len(),
+                MAX_STORE_PRODUCTS
+            ))
+            .size(11)
+            .style(style::subtle_text),
             horizontal_space(),
             button(text("+ Add").size(11))
                 .padding([4, 10])
@@ -271,78 +242,75 @@ fn product_list_pane<'a>(
                     can_add.then_some(Message::store(StoreEditorMessage::OpenProductModal(None)))
                 ),
         ]
+        .spacing(6)
         .padding([8, 12])
         .align_y(iced::Alignment::Center),
     )
     .style(style::grid_header_cell)
     .width(Fill);
 
-    let body: Element<'_, Message> = if editor.selected_idx.is_none() {
-        container(
+    let selected_store = editor
+        .selected_idx
+        .and_then(|idx| editor.filtered_stores.get(idx))
+        .map(|(_, store)| store);
+
+    let body: Element<'_, Message> = match selected_store {
+        None => container(
             text("Select a store to view products")
                 .size(12)
                 .style(style::subtle_text),
         )
         .padding(16)
-        .into()
-    } else if editor.is_inn() {
-        container(
+        .into(),
+        Some(_) if editor.is_inn() => container(
             text("Inns have no products")
                 .size(12)
                 .style(style::subtle_text),
         )
         .padding(16)
-        .into()
-    } else {
-        let cards: Vec<Element<'a, Message>> = editor
-            .edit_products
-            .iter()
-            .enumerate()
-            .map(|(i, prod)| product_card(i, prod, editor, weapons, heals, misc, edit_items))
-            .collect();
-
-        scrollable(column(cards).spacing(4).padding([8, 8]))
-            .height(Fill)
-            .into()
+        .into(),
+        Some(store) => {
+            let cards: Vec<Element<'a, Message>> = catalog
+                .resolve(store)
+                .into_iter()
+                .map(|product| product_card(product, editor))
+                .collect();
+
+            scrollable(column(cards).spacing(4).padding([8, 8]))
+                .height(Fill)
+                .into()
+        }
     };
 
     column![header, body].height(Fill).into()
 }
 
 fn product_card<'a>(
-    i: usize,
-    prod: &'a EditableProduct,
+    product: ResolvedProduct,
     editor: &'a StoreEditorState,
-    weapons: &'a Option<Vec<dispel_core::WeaponItem>>,
-    heals: &'a Option<Vec<dispel_core::HealItem>>,
-    misc: &'a Option<Vec<dispel_core::MiscItem>>,
-    edit_items: &'a Option<Vec<dispel_core::EditItem>>,
 ) -> Element<'a, Message> {
+    let i = product.order;
     let is_selected = editor.selected_product_idx == Some(i);
-    let type_opt = ProductTypeOption::from_id(prod.product_type);
-    let item_name = editor.get_product_item_name(
-        prod.product_type,
-        prod.item_id,
-        weapons,
-        heals,
-        misc,
-        edit_items,
-    );
+    let product_type = product.slot.product_type;
 
     let card_info = row![
         text(format!("{:02}", i))
             .size(10)
             .style(style::subtle_text)
             .width(20),
-        text(type_opt.to_string())
+        text(product_type_label(product_type))
             .size(10)
-            .style(type_text_style(prod.product_type))
+            .style(type_text_style(product_type))
             .width(60),
-        text(format!("#{}", prod.item_id))
+        text(format!("#{}", product.slot.item_id))
             .size(10)
             .style(style::subtle_text)
             .width(32),
-        text(item_name).size(11).width(Fill),
+        text(product.label()).size(11).width(Fill),
+        text(format!("{}g", product.buy_price))
+            .size(10)
+            .style(style::subtle_text)
+            .width(48),
     ]
     .spacing(6)
     .align_y(iced::Alignment::Center);
@@ -363,6 +331,10 @@ fn product_card<'a>(
             ))))
             .padding([4, 8])
             .style(style::browse_button),
+        button(text("Remove").size(10))
+            .on_press(Message::store(StoreEditorMessage::RemoveProduct(i)))
+            .padding([4, 8])
+            .style(style::browse_button),
     ]
     .spacing(4)
     .align_y(iced::Alignment::Center)
@@ -371,10 +343,7 @@ fn product_card<'a>(
 
 fn product_modal<'a>(
     editor: &'a StoreEditorState,
-    weapons: &'a Option<Vec<dispel_core::WeaponItem>>,
-    heals: &'a Option<Vec<dispel_core::HealItem>>,
-    misc: &'a Option<Vec<dispel_core::MiscItem>>,
-    edit_items: &'a Option<Vec<dispel_core::EditItem>>,
+    catalog: &StoreCatalog<'a>,
 ) -> Element<'a, Message> {
     let title = if editor.modal_product_idx.is_some() {
         "Edit Product"
@@ -382,44 +351,57 @@ fn product_modal<'a>(
         "Add Product"
     };
 
-    let resolved_name = editor.get_product_item_name(
-        editor.modal_edit_type,
-        editor.modal_edit_item_id.parse().unwrap_or(0),
-        weapons,
-        heals,
-        misc,
-        edit_items,
-    );
+    let slot = ProductSlot::new(editor.modal_edit_type, editor.modal_edit_item_id);
+    let price_modifier = editor.edit_price_modifier.parse::<i16>().unwrap_or(0);
+    let resolved = match catalog.item(slot) {
+        Some(item) => format!(
+            "#{} — buys for {}g, sells for {}g",
+            item.slot.item_id,
+            store_buy_price(item.base_price, price_modifier),
+            store_sell_price(item.base_price, price_modifier)
+        ),
+        None => format!(
+            "{} #{} is not in the loaded item databases",
+            product_type_label(slot.product_type),
+            slot.item_id
+        ),
+    };
+
+    let items = catalog.items(editor.modal_edit_type);
+    let item_picker: Element<'a, Message> = if items.is_empty() {
+        text("Load the catalog to pick items by name")
+            .size(11)
+            .style(style::subtle_text)
+            .into()
+    } else {
+        let selected = items.iter().copied().find(|item| item.slot == slot);
+        pick_list(selected, items, |item| item.to_string())
+            .on_select(|item| {
+                Message::store(StoreEditorMessage::ModalItemChanged(item.slot.item_id))
+            })
+            .padding(6)
+            .width(Fill)
+            .into()
+    };
 
     let content = column![
         text(title).size(15).style(style::section_header),
         horizontal_rule(1),
         row![
             text("Type").size(12).width(70),
-            pick_list(
-                Some(ProductTypeOption::from_id(editor.modal_edit_type)),
-                ProductTypeOption::all(),
-                |v| v.to_string(),
-            )
-            .on_select(
-                |selected| Message::store(StoreEditorMessage::ModalTypeChanged(selected.to_id()))
-            )
+            pick_list(Some(editor.modal_edit_type), &PRODUCT_TYPES[..], |t| {
+                product_type_label(*t).to_string()
+            },)
+            .on_select(|selected| Message::store(StoreEditorMessage::ModalTypeChanged(selected)))
             .padding(6)
             .width(Fill),
         ]
         .spacing(8)
         .align_y(iced::Alignment::Center),
-        row![
-            text("Item ID").size(12).width(70),
-            text_input("", &editor.modal_edit_item_id)
-                .on_input(|v| Message::store(StoreEditorMessage::ModalItemIdChanged(v)))
-                .padding(6)
-                .size(12)
-                .width(Fill),
-        ]
-        .spacing(8)
-        .align_y(iced::Alignment::Center),
-        text(resolved_name).size(11).style(style::subtle_text),
+        row![text("Item").size(12).width(70), item_picker]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+        text(resolved).size(11).style(style::subtle_text),
         horizontal_rule(1),
         row![
             button(text("Save").size(12))
@@ -435,7 +417,7 @@ fn product_modal<'a>(
     ]
     .spacing(12)
     .padding(24)
-    .width(320);
+    .width(360);
 
     container(content).style(style::modal_container).into()
 }
@@ -459,10 +441,32 @@ fn multiline_field<'a>(label: &'a str, input: Element<'a, Message>) -> Element<'
 
 pub fn view(app: &App) -> Element<'_, Message> {
     let editor = &app.state.editors.store_editor;
-    let weapons = &app.state.editors.weapon_editor.catalog;
-    let heals = &app.state.editors.heal_item_editor.catalog;
-    let misc = &app.state.editors.misc_item_editor.catalog;
-    let edit_items = &app.state.editors.edit_item_editor.catalog;
+    let catalog = StoreCatalog::new(
+        app.state
+            .editors
+            .weapon_editor
+            .catalog
+            .as_deref()
+            .unwrap_or(&[]),
+        app.state
+            .editors
+            .heal_item_editor
+            .catalog
+            .as_deref()
+            .unwrap_or(&[]),
+        app.state
+            .editors
+            .edit_item_editor
+            .catalog
+            .as_deref()
+            .unwrap_or(&[]),
+        app.state
+            .editors
+            .misc_item_editor
+            .catalog
+            .as_deref()
+            .unwrap_or(&[]),
+    );
 
     let header = row![
         text("Store Editor").size(20),
@@ -487,9 +491,7 @@ pub fn view(app: &App) -> Element<'_, Message> {
         let body: Element<'_, Message> = match content {
             StorePaneContent::StoreList => store_list_pane(editor),
             StorePaneContent::StoreDetails => store_details_pane(editor),
-            StorePaneContent::ProductList => {
-                product_list_pane(editor, weapons, heals, misc, edit_items)
-            }
+            StorePaneContent::ProductList => product_list_pane(editor, &catalog),
         };
         pane_grid::Content::new(body)
     })
@@ -505,7 +507,7 @@ pub fn view(app: &App) -> Element<'_, Message> {
         .into();
 
     if editor.show_product_modal {
-        let modal_elem = product_modal(editor, weapons, heals, misc, edit_items);
+        let modal_elem = product_modal(editor, &catalog);
         modal(
             base,
             modal_elem,
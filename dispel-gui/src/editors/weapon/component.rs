@@ -1,5 +1,6 @@
 use crate::components::editable::EditableRecord;
 use dispel_core::WeaponItem;
+use dispel_core::references::usages::RecordKind;
 
 use crate::editable_record_fields;
 
@@ -62,4 +63,7 @@ impl EditableRecord for WeaponItem {
     fn detail_width() -> f32 {
         280.0
     }
+    fn record_kind() -> Option<RecordKind> {
+        Some(RecordKind::WeaponItem)
+    }
 }
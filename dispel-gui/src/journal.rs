@@ -0,0 +1,163 @@
+//! Project-wide edit journal.
+//!
+//! Every successful field edit, in any editor, is appended to one
+//! [`Journal`] per game folder. It is saved after each change under
+//! `<config dir>/dispel-gui/journals/`, next to `workspace.json`, so the
+//! timeline survives restarts and outlives the tabs that produced it. The
+//! history panel lists it and can move the project to any recorded point;
+//! those moves are written straight to the game files, which is why they
+//! are refused while one of the touched files is open in a tab.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use dispel_core::modding::{ChangeAction, ChangeOp, Journal, PatcherRegistry, Value};
+
+use crate::app::App;
+
+/// The journal of the current game folder and where it is saved.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectJournal {
+    pub journal: Journal,
+    /// `None` keeps the journal in memory only (no game folder, or the
+    /// saved file could not be read and must not be overwritten).
+    pub path: Option<PathBuf>,
+}
+
+impl ProjectJournal {
+    /// Load the journal saved for `game_path`, starting an empty one if
+    /// there is none yet.
+    pub fn open(game_path: &str) -> Self {
+        if game_path.is_empty() {
+            return Self::default();
+        }
+        let Some(path) = journal_path(Path::new(game_path)) else {
+            return Self::default();
+        };
+        match Journal::load(&path) {
+            Ok(journal) => Self {
+                journal,
+                path: Some(path),
+            },
+            Err(e) => {
+                eprintln!("Edit journal {} not loaded: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        match &self.path {
+            Some(path) => self.journal.save(path).map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `<config dir>/dispel-gui/journals/<game path>.json`, with every
+/// character of the game path that is not alphanumeric replaced by `_`.
+pub fn journal_path(game_path: &Path) -> Option<PathBuf> {
+    let name: String = game_path
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    Some(
+        dirs::config_dir()?
+            .join("dispel-gui")
+            .join("journals")
+            .join(format!("{name}.json")),
+    )
+}
+
+/// Append one committed field edit. `file_path` is relative to the game
+/// folder, `record_id` the record's index in that file.
+pub fn record_field_change(
+    app: &mut App,
+    file_path: impl Into<String>,
+    record_id: u32,
+    field: &str,
+    old: &str,
+    new: &str,
+) {
+    let action = ChangeAction::new(
+        file_path,
+        ChangeOp::FieldDelta {
+            record_id,
+            field: field.to_owned(),
+            old: Value::String(old.to_owned()),
+            new: Value::String(new.to_owned()),
+        },
+    );
+    app.state.journal.journal.record(action);
+    if let Err(e) = app.state.journal.save() {
+        app.state.status_msg = format!("Edit journal not saved: {e}");
+    }
+}
+
+/// One-line summary of a journal entry for the history panel.
+pub fn describe(action: &ChangeAction) -> String {
+    match &action.op {
+        ChangeOp::FieldDelta {
+            record_id,
+            field,
+            old,
+            new,
+        } => format!("{} #{record_id} {field}: {old} → {new}", action.file_path),
+        _ => action.file_path.clone(),
+    }
+}
+
+/// Move the project to journal entry `target` (`None`: before the first
+/// edit), rewriting the affected game files. Returns a status line.
+pub fn checkout(app: &mut App, target: Option<usize>) -> Result<String, String> {
+    if app.state.shared_game_path.is_empty() {
+        return Err("Open a game folder before using the edit journal".to_string());
+    }
+    let game_dir = PathBuf::from(&app.state.shared_game_path);
+
+    // Dry run to learn which files the move touches.
+    let mut files = BTreeSet::new();
+    let _ = app.state.journal.journal.clone().checkout(target, |step| {
+        if let Some((file, ..)) = step.target() {
+            files.insert(file.to_owned());
+        }
+        Ok::<_, ()>(())
+    });
+    if files.is_empty() {
+        return Ok("Already at this point in the edit journal".to_string());
+    }
+    let open: Vec<&str> = files
+        .iter()
+        .filter(|file| {
+            let path = game_dir.join(file);
+            app.state
+                .workspace
+                .tabs
+                .iter()
+                .any(|tab| tab.path.as_deref() == Some(path.as_path()))
+        })
+        .map(String::as_str)
+        .collect();
+    if !open.is_empty() {
+        return Err(format!(
+            "Close {} before moving through the edit journal",
+            open.join(", ")
+        ));
+    }
+
+    let registry = PatcherRegistry::with_defaults();
+    let result = app
+        .state
+        .journal
+        .journal
+        .checkout(target, |step| step.write(&game_dir, &registry));
+    // Save even on failure: the head records the steps that did land.
+    let saved = app.state.journal.save();
+    result.map_err(|e| format!("Edit journal: {e}"))?;
+    saved.map_err(|e| format!("Edit journal not saved: {e}"))?;
+    Ok(format!(
+        "Rewrote {} file(s) from the edit journal",
+        files.len()
+    ))
+}
@@ -11,6 +11,7 @@ pub mod dispatch_table;
 pub mod editor_registry;
 pub mod editors;
 pub mod indexation;
+pub mod journal;
 pub mod message;
 pub mod subscriptions;
 
@@ -8,6 +8,32 @@ pub enum WorkspaceMessage {
     ToggleCommandPalette,
     ToggleGlobalSearch,
     ToggleHistoryPanel,
+    /// Move the project to a journal entry (`None`: before the first edit).
+    JournalCheckout(Option<usize>),
+    JournalUndo,
+    JournalRedo,
+    /// Show or hide the "Find usages" panel; opening it prefills the record
+    /// selected in the active editor and builds the index if needed.
+    ToggleUsagesPanel,
+    UsagesKindSelected(dispel_core::references::usages::RecordKind),
+    UsagesIdInput(String),
+    UsagesRebuildIndex,
+    UsagesIndexBuilt(
+        Result<std::sync::Arc<dispel_core::references::usages::ReferenceIndex>, String>,
+    ),
+    /// Open a referencing file, given relative to the game path.
+    UsagesOpenFile(String),
+    /// Show or hide the "Loot sources" panel; opening it prefills the item
+    /// selected in the active editor and builds the report if needed.
+    ToggleLootPanel,
+    /// Open the loot panel on one item, from an item editor's inspector.
+    ShowLootSources(dispel_core::references::usages::RecordKind, i32),
+    LootQuerySelected(crate::components::loot_panel::LootQuery),
+    LootKindSelected(dispel_core::references::usages::RecordKind),
+    LootIdInput(String),
+    LootMapSelected(String),
+    LootRebuild,
+    LootBuilt(Result<std::sync::Arc<dispel_core::references::loot::LootReport>, String>),
     ToggleMaximizePane,
     CommandPaletteInput(String),
     CommandPaletteSelect(usize),
@@ -36,15 +36,25 @@ use std::path::PathBuf;
 
 #[test]
 fn observe_field_change_returns_none_when_no_session() {
-    let app = app_without_recording();
-    let task = observe_field_change(&app, "test.db", 0, "name", "old".into(), "new".into());
+    let mut app = app_without_recording();
+    let task = observe_field_change(&mut app, "test.db", 0, "name", "old".into(), "new".into());
     assert_eq!(task.units(), 0, "no-op when no recording session");
 }
 
+#[test]
+fn observe_field_change_appends_to_journal_without_session() {
+    let mut app = app_without_recording();
+    let _ = observe_field_change(&mut app, "test.db", 3, "name", "old".into(), "new".into());
+    let journal = &app.state.journal.journal;
+    assert_eq!(journal.len(), 1);
+    assert_eq!(journal.head(), Some(0));
+    assert_eq!(journal.entries()[0].action.file_path, "test.db");
+}
+
 #[test]
 fn observe_field_change_returns_task_when_session_active() {
-    let app = app_with_recording();
-    let _task = observe_field_change(&app, "test.db", 0, "name", "old".into(), "new".into());
+    let mut app = app_with_recording();
+    let _task = observe_field_change(&mut app, "test.db", 0, "name", "old".into(), "new".into());
 }
 
 // ============================================================================
@@ -28,6 +28,8 @@ pub struct AppState {
     /// Active mod-recording session, if any. While set, every successful
     /// catalog edit is appended to that mod's `ChangeLog`.
     pub recording: Option<RecordingSession>,
+    /// Project-wide edit timeline of `shared_game_path`.
+    pub journal: crate::journal::ProjectJournal,
 }
 
 /// In-flight recording state. Lives on [`AppState`] while the user has
@@ -88,6 +90,7 @@ impl AppState {
         // Sync game_path from workspace to shared_game_path
         if let Some(ref path) = self.workspace.game_path {
             self.shared_game_path = path.to_string_lossy().to_string();
+            self.journal = crate::journal::ProjectJournal::open(&self.shared_game_path);
         }
 
         // Sync recent files from workspace to app state
@@ -257,6 +260,7 @@ impl Default for AppState {
             file_index_cache_manager: None,
             recent_files: Vec::new(),
             recording: None,
+            journal: crate::journal::ProjectJournal::default(),
         }
     }
 }
@@ -389,6 +393,8 @@ pub enum PaneContent {
     Sidebar,
     MainContent,
     HistoryPanel,
+    UsagesPanel,
+    LootPanel,
 }
 
 #[derive(Debug, Clone)]
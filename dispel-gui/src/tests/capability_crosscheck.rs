@@ -45,6 +45,7 @@ mod crosscheck {
             EditorType::MapEditor,
             EditorType::ModPackager,
             EditorType::LocalizationManager,
+            EditorType::SaveFileViewer,
             EditorType::HexEditor,
             EditorType::Unknown,
         ]
@@ -10,8 +10,8 @@ mod hex_inspector_toggle_tests {
     use std::path::PathBuf;
 
     use gui_widgets::components::paragraph_cache::ParagraphCache;
+    use hexedit::domain::mapped_provider::MappedProvider;
     use hexedit::domain::panel::default_pane_grid;
-    use hexedit::domain::provider::BufferProvider;
     use hexedit::domain::search::SearchState;
     use hexedit::domain::selection::Selection;
     use hexedit::domain::write_mode::WriteMode;
@@ -29,23 +29,20 @@ mod hex_inspector_toggle_tests {
     fn state_with_comparison(baseline: Vec<u8>, comparison: Vec<u8>) -> HexEditorState {
         let panes = default_pane_grid();
         let pane_focus = *panes.iter().next().map(|(id, _)| id).unwrap();
-        let diff = baseline
-            .iter()
-            .zip(comparison.iter())
-            .enumerate()
-            .filter(|(_, (a, b))| a != b)
-            .map(|(i, _)| i as u64)
-            .collect();
+        let provider = MappedProvider::from_bytes(baseline);
+        let comparison_file = ComparisonFile::new("other.bin".into(), comparison, &provider);
         HexEditorState {
             path: PathBuf::from("test.bin"),
             name: "test.bin".to_string(),
             layout: None,
+            template: None,
+            template_error: None,
             outline: gui_widgets::sweeten::list::Content::new(),
             outline_all: Vec::new(),
             collapsed_outline: BTreeSet::new(),
             panes,
             pane_focus,
-            provider: BufferProvider::from_bytes(baseline),
+            provider,
             bytes_per_row: 16,
             bpr_input: "16".to_string(),
             selection: Selection::single(0),
@@ -54,11 +51,8 @@ mod hex_inspector_toggle_tests {
             inspector_source: InspectorSource::Baseline,
             vanilla: None,
             vanilla_diff: BTreeSet::new(),
-            comparison_file: Some(ComparisonFile {
-                name: "other.bin".into(),
-                data: comparison,
-                diff,
-            }),
+            vanilla_alignment: None,
+            comparison_file: Some(comparison_file),
             diff_review: false,
             patterns: Vec::new(),
             pattern_by_addr: BTreeMap::new(),
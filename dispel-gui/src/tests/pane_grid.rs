@@ -65,6 +65,64 @@ mod pane_grid_tests {
         assert_eq!(app.state.pane_state.state.len(), 2, "back to two panes");
     }
 
+    #[test]
+    fn toggle_usages_panel_survives_sidebar_toggle() {
+        let mut app = App::test_new(Workspace::new());
+
+        // No game path: the panel opens without starting an index build.
+        let _ = app.update(Message::Workspace(WorkspaceMessage::ToggleUsagesPanel));
+        assert!(app.usages_panel_visible);
+        assert!(!app.usages_panel.is_building);
+        assert_eq!(app.state.pane_state.state.len(), 3);
+
+        let _ = app.update(Message::Workspace(WorkspaceMessage::ToggleSidebar));
+        assert_eq!(
+            app.state.pane_state.state.len(),
+            2,
+            "main content and usages panel"
+        );
+
+        let _ = app.update(Message::Workspace(WorkspaceMessage::ToggleUsagesPanel));
+        assert!(!app.usages_panel_visible);
+        assert_eq!(app.state.pane_state.state.len(), 1);
+    }
+
+    #[test]
+    fn show_loot_sources_opens_panel_once_with_the_item() {
+        use dispel_core::references::usages::RecordKind;
+
+        let mut app = App::test_new(Workspace::new());
+
+        let _ = app.update(Message::Workspace(WorkspaceMessage::ShowLootSources(
+            RecordKind::HealItem,
+            7,
+        )));
+        assert!(app.loot_panel_visible);
+        assert!(!app.loot_panel.is_building);
+        assert_eq!(app.loot_panel.kind, RecordKind::HealItem);
+        assert_eq!(app.loot_panel.id(), Some(7));
+        assert_eq!(app.state.pane_state.state.len(), 3);
+
+        // A second item retargets the open panel instead of splitting again.
+        let _ = app.update(Message::Workspace(WorkspaceMessage::ShowLootSources(
+            RecordKind::WeaponItem,
+            2,
+        )));
+        assert_eq!(app.loot_panel.kind, RecordKind::WeaponItem);
+        assert_eq!(app.state.pane_state.state.len(), 3);
+
+        let _ = app.update(Message::Workspace(WorkspaceMessage::ToggleSidebar));
+        assert_eq!(
+            app.state.pane_state.state.len(),
+            2,
+            "main content and loot panel"
+        );
+
+        let _ = app.update(Message::Workspace(WorkspaceMessage::ToggleLootPanel));
+        assert!(!app.loot_panel_visible);
+        assert_eq!(app.state.pane_state.state.len(), 1);
+    }
+
     #[test]
     fn toggle_history_panel_with_hidden_sidebar() {
         let mut app = App::test_new(Workspace::new());
@@ -69,6 +69,7 @@ mod save_dispatch_tests {
             EditorType::MapEditor,
             EditorType::ModPackager,
             EditorType::LocalizationManager,
+            EditorType::SaveFileViewer,
             EditorType::HexEditor,
             EditorType::Unknown,
         ]
@@ -58,6 +58,7 @@ mod undo_redo_dispatch_tests {
             ExtraRefEditor,
             DialogueScriptEditor,
             DialogueTextEditor,
+            SaveFileViewer,
         ]
     }
 
@@ -209,6 +210,11 @@ mod edit_history_tests {
                 .get_active_edit_history(DialogueTextEditor, 0)
                 .is_none()
         );
+        assert!(
+            registry
+                .get_active_edit_history(SaveFileViewer, 0)
+                .is_none()
+        );
 
         registry
             .npc_ref_editor
@@ -218,6 +224,14 @@ mod edit_history_tests {
             registry.get_active_edit_history(NpcRefEditor, 42).is_some(),
             "NpcRefEditor with tab_id=42 should have history after insert"
         );
+
+        registry.save_file_viewers.insert(7, Default::default());
+        assert!(
+            registry
+                .get_active_edit_history(SaveFileViewer, 7)
+                .is_some(),
+            "SaveFileViewer with tab_id=7 should have history after insert"
+        );
     }
 
     #[test]
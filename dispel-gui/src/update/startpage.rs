@@ -46,6 +46,7 @@ pub fn enter_editor_mode(app: &mut App, path: PathBuf) -> Task<Message> {
     // Set game path
     app.state.workspace.game_path = Some(path.clone());
     app.state.shared_game_path = path.to_string_lossy().to_string();
+    app.state.journal = crate::journal::ProjectJournal::open(&app.state.shared_game_path);
 
     // let path_display = if self.state.shared_game_path.is_empty() {
     //     "No game path set"
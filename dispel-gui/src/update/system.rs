@@ -99,6 +99,11 @@ fn save_task_for_editor(
             Message::npc_ref(crate::editors::npc_ref::NpcRefEditorMessage::Save)
         }
 
+        // Save file editor — writes the `.sav` back with a backup
+        EditorType::SaveFileViewer => {
+            Message::save_file_viewer(crate::editors::save_file_viewer::SaveFileViewerMessage::Save)
+        }
+
         // Safety net: supports_save() returned true but we're missing an arm.
         // This is a programming error — the editor should either be listed above
         // or removed from supports_save().
@@ -197,7 +202,10 @@ pub fn handle(message: SystemMessage, app: &mut App) -> Task<crate::message::Mes
                     app.start_page_input = s.clone();
                     return Task::none();
                 }
-                "shared_game_path" => app.state.shared_game_path = s.clone(),
+                "shared_game_path" => {
+                    app.state.shared_game_path = s.clone();
+                    app.state.journal = crate::journal::ProjectJournal::open(&s);
+                }
                 "workspace_game_path" => {
                     // Clear all editor states to prevent stale references
                     app.state.clear_editor_states();
@@ -208,6 +216,7 @@ pub fn handle(message: SystemMessage, app: &mut App) -> Task<crate::message::Mes
                     let pathbuf = PathBuf::from(&s);
                     app.state.workspace.game_path = Some(pathbuf.clone());
                     app.state.shared_game_path = s.clone();
+                    app.state.journal = crate::journal::ProjectJournal::open(&s);
                     app.save_workspace();
                     // Clear old index and trigger re-index
                     app.search_index.clear();
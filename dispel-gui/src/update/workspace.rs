@@ -1,10 +1,12 @@
 // Workspace message handlers
 
 use crate::app::App;
+use crate::components::loot_panel::LootQuery;
 use crate::components::tab_bar;
 use crate::message::Message;
 use crate::message::workspace::WorkspaceMessage;
 use crate::update::file_tree;
+use dispel_core::references::usages::RecordKind;
 use iced::Task;
 use iced::widget::pane_grid;
 use std::path::PathBuf;
@@ -43,6 +45,14 @@ pub fn handle(message: WorkspaceMessage, app: &mut App) -> Task<crate::message::
                         app.state.pane_state.focus = main_pane;
                     }
 
+                    if app.usages_panel_visible {
+                        let _ =
+                            new_state.split(Axis::Vertical, main_pane, PaneContent::UsagesPanel);
+                    }
+                    if app.loot_panel_visible {
+                        let _ = new_state.split(Axis::Vertical, main_pane, PaneContent::LootPanel);
+                    }
+
                     app.state.pane_state.state = new_state;
                     app.state.pane_state.sidebar_split = Some(split);
                 }
@@ -61,6 +71,13 @@ pub fn handle(message: WorkspaceMessage, app: &mut App) -> Task<crate::message::
                     app.state.pane_state.focus = main_pane;
                 }
 
+                if app.usages_panel_visible {
+                    let _ = new_state.split(Axis::Vertical, main_pane, PaneContent::UsagesPanel);
+                }
+                if app.loot_panel_visible {
+                    let _ = new_state.split(Axis::Vertical, main_pane, PaneContent::LootPanel);
+                }
+
                 app.state.pane_state.state = new_state;
                 // sidebar_split will be recreated when sidebar is shown again
             }
@@ -112,6 +129,125 @@ pub fn handle(message: WorkspaceMessage, app: &mut App) -> Task<crate::message::
             }
             Task::none()
         }
+        WorkspaceMessage::ToggleUsagesPanel => {
+            app.usages_panel_visible = !app.usages_panel_visible;
+            if app.usages_panel_visible {
+                if let Some((kind, id)) = crate::components::usages_panel::selected_record(app) {
+                    app.usages_panel.kind = kind;
+                    app.usages_panel.id_input = id.to_string();
+                }
+                if let Some((new_pane, _split)) = app.state.pane_state.state.split(
+                    pane_grid::Axis::Vertical,
+                    app.state.pane_state.focus,
+                    crate::state::PaneContent::UsagesPanel,
+                ) {
+                    app.state.pane_state.focus = new_pane;
+                }
+                let is_stale = app.usages_panel.indexed_game_path.as_deref()
+                    != Some(app.state.shared_game_path.as_str());
+                if is_stale && !app.usages_panel.is_building {
+                    return build_usages_index(app);
+                }
+            } else {
+                close_panes(app, |content| {
+                    matches!(content, crate::state::PaneContent::UsagesPanel)
+                });
+            }
+            Task::none()
+        }
+        WorkspaceMessage::JournalCheckout(target) => journal_move(app, |_| Some(target)),
+        WorkspaceMessage::JournalUndo => journal_move(app, |journal| {
+            let head = journal.head()?;
+            Some(journal.entries()[head].parent)
+        }),
+        WorkspaceMessage::JournalRedo => journal_move(app, |journal| {
+            journal.children(journal.head()).last().map(Some)
+        }),
+        WorkspaceMessage::UsagesKindSelected(kind) => {
+            app.usages_panel.kind = kind;
+            Task::none()
+        }
+        WorkspaceMessage::UsagesIdInput(input) => {
+            app.usages_panel.id_input = input;
+            Task::none()
+        }
+        WorkspaceMessage::UsagesRebuildIndex => build_usages_index(app),
+        WorkspaceMessage::UsagesIndexBuilt(result) => {
+            app.usages_panel.is_building = false;
+            match result {
+                Ok(index) => {
+                    app.usages_panel.index = Some(index);
+                    Task::none()
+                }
+                Err(e) => {
+                    app.usages_panel.indexed_game_path = None;
+                    Task::done(Message::System(crate::message::SystemMessage::ShowError(e)))
+                }
+            }
+        }
+        WorkspaceMessage::UsagesOpenFile(relative_path) => {
+            if app.state.shared_game_path.is_empty() {
+                return Task::none();
+            }
+            let full_path = PathBuf::from(&app.state.shared_game_path).join(relative_path);
+            app.open_file_in_workspace(&full_path)
+        }
+        WorkspaceMessage::ToggleLootPanel => {
+            if app.loot_panel_visible {
+                app.loot_panel_visible = false;
+                close_panes(app, |content| {
+                    matches!(content, crate::state::PaneContent::LootPanel)
+                });
+                return Task::none();
+            }
+            if let Some((kind, id)) = crate::components::usages_panel::selected_record(app)
+                && RecordKind::ITEMS.contains(&kind)
+            {
+                app.loot_panel.query = LootQuery::Item;
+                app.loot_panel.kind = kind;
+                app.loot_panel.id_input = id.to_string();
+            }
+            open_loot_panel(app)
+        }
+        WorkspaceMessage::ShowLootSources(kind, id) => {
+            app.loot_panel.query = LootQuery::Item;
+            app.loot_panel.kind = kind;
+            app.loot_panel.id_input = id.to_string();
+            if app.loot_panel_visible {
+                return Task::none();
+            }
+            open_loot_panel(app)
+        }
+        WorkspaceMessage::LootQuerySelected(query) => {
+            app.loot_panel.query = query;
+            Task::none()
+        }
+        WorkspaceMessage::LootKindSelected(kind) => {
+            app.loot_panel.kind = kind;
+            Task::none()
+        }
+        WorkspaceMessage::LootIdInput(input) => {
+            app.loot_panel.id_input = input;
+            Task::none()
+        }
+        WorkspaceMessage::LootMapSelected(map) => {
+            app.loot_panel.map = Some(map);
+            Task::none()
+        }
+        WorkspaceMessage::LootRebuild => build_loot_report(app),
+        WorkspaceMessage::LootBuilt(result) => {
+            app.loot_panel.is_building = false;
+            match result {
+                Ok(report) => {
+                    app.loot_panel.report = Some(report);
+                    Task::none()
+                }
+                Err(e) => {
+                    app.loot_panel.indexed_game_path = None;
+                    Task::done(Message::System(crate::message::SystemMessage::ShowError(e)))
+                }
+            }
+        }
         WorkspaceMessage::ToggleMaximizePane => {
             if app.state.pane_state.maximized.is_some() {
                 app.state.pane_state.state.restore();
@@ -348,3 +484,105 @@ pub fn handle(message: WorkspaceMessage, app: &mut App) -> Task<crate::message::
         }
     }
 }
+
+/// Scan the game directory for record references off the UI thread.
+fn build_usages_index(app: &mut App) -> Task<Message> {
+    use dispel_core::references::usages::ReferenceIndex;
+
+    if app.state.shared_game_path.is_empty() {
+        return Task::none();
+    }
+    let game_path = app.state.shared_game_path.clone();
+    app.usages_panel.is_building = true;
+    app.usages_panel.indexed_game_path = Some(game_path.clone());
+    Task::perform(
+        async move {
+            tokio::task::spawn_blocking(move || {
+                std::sync::Arc::new(ReferenceIndex::build(std::path::Path::new(&game_path)))
+            })
+            .await
+            .map_err(|e| e.to_string())
+        },
+        |result| Message::Workspace(WorkspaceMessage::UsagesIndexBuilt(result)),
+    )
+}
+
+/// Split the focused pane to show the loot panel, building the report when
+/// the game path changed since the last build.
+fn open_loot_panel(app: &mut App) -> Task<Message> {
+    app.loot_panel_visible = true;
+    if let Some((new_pane, _split)) = app.state.pane_state.state.split(
+        pane_grid::Axis::Vertical,
+        app.state.pane_state.focus,
+        crate::state::PaneContent::LootPanel,
+    ) {
+        app.state.pane_state.focus = new_pane;
+    }
+    let is_stale =
+        app.loot_panel.indexed_game_path.as_deref() != Some(app.state.shared_game_path.as_str());
+    if is_stale && !app.loot_panel.is_building {
+        return build_loot_report(app);
+    }
+    Task::none()
+}
+
+/// Close every pane whose content matches, keeping at least one pane.
+fn close_panes(app: &mut App, matches: impl Fn(&crate::state::PaneContent) -> bool) {
+    let panes: Vec<_> = app
+        .state
+        .pane_state
+        .state
+        .iter()
+        .filter_map(|(id, content)| matches(content).then_some(*id))
+        .collect();
+    for pane_id in panes {
+        if app.state.pane_state.state.len() > 1
+            && let Some((_, sibling)) = app.state.pane_state.state.close(pane_id)
+        {
+            app.state.pane_state.focus = sibling;
+        }
+    }
+}
+
+/// Collect the loot sources of the game directory off the UI thread.
+fn build_loot_report(app: &mut App) -> Task<Message> {
+    use dispel_core::references::balance::BalanceInputs;
+    use dispel_core::references::loot::LootReport;
+
+    if app.state.shared_game_path.is_empty() {
+        return Task::none();
+    }
+    let game_path = app.state.shared_game_path.clone();
+    app.loot_panel.is_building = true;
+    app.loot_panel.indexed_game_path = Some(game_path.clone());
+    Task::perform(
+        async move {
+            tokio::task::spawn_blocking(move || {
+                let inputs = BalanceInputs::load(std::path::Path::new(&game_path));
+                std::sync::Arc::new(LootReport::build(&inputs))
+            })
+            .await
+            .map_err(|e| e.to_string())
+        },
+        |result| Message::Workspace(WorkspaceMessage::LootBuilt(result)),
+    )
+}
+
+/// Check the journal out at the entry picked by `target`, which returns
+/// `None` when there is nowhere to move.
+fn journal_move(
+    app: &mut App,
+    target: impl FnOnce(&dispel_core::modding::Journal) -> Option<Option<usize>>,
+) -> Task<Message> {
+    let Some(target) = target(&app.state.journal.journal) else {
+        app.state.status_msg = "Nothing to move to in the edit journal".to_string();
+        return Task::none();
+    };
+    match crate::journal::checkout(app, target) {
+        Ok(status) => {
+            app.state.status_msg = status;
+            Task::none()
+        }
+        Err(e) => Task::done(Message::System(crate::message::SystemMessage::ShowError(e))),
+    }
+}
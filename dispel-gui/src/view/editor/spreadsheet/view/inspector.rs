@@ -13,9 +13,11 @@ use crate::components::editable::{EditableRecord, FieldDescriptor, FieldKind};
 use crate::components::generic_editor::GenericEditorState;
 use crate::components::utils::horizontal_space;
 use crate::message::Message;
+use crate::message::workspace::WorkspaceMessage;
 use crate::style;
 use crate::view::editor::spreadsheet::message::SpreadsheetMessage;
 use crate::view::editor::spreadsheet::state::SpreadsheetState;
+use dispel_core::references::usages::RecordKind;
 use gui_widgets::lucide::{LUCIDE_FONT, icon_char};
 use gui_widgets::{TextAreaContent, textarea};
 use lucide_icons::Icon;
@@ -29,20 +31,33 @@ pub fn build_inspector_panel<'a, R: EditableRecord>(
 ) -> Element<'a, Message> {
     let descriptors = R::field_descriptors();
 
-    let header = container(
-        row![
-            text("Inspector").size(13),
-            horizontal_space(),
-            button(text(icon_char(Icon::X)).font(LUCIDE_FONT).size(11))
-                .on_press(spreadsheet_msg(SpreadsheetMessage::CloseInspector))
+    let mut header_row = row![text("Inspector").size(13), horizontal_space()]
+        .spacing(4)
+        .align_y(iced::Alignment::Center)
+        .padding([6, 10]);
+    // Item records link to the loot panel: where the item can be obtained.
+    if let (Some(kind), Some(orig_idx)) = (R::record_kind(), spreadsheet.selected_orig)
+        && RecordKind::ITEMS.contains(&kind)
+        && let Ok(id) = i32::try_from(orig_idx)
+    {
+        header_row = header_row.push(
+            button(text("Loot").size(11))
+                .on_press(Message::Workspace(WorkspaceMessage::ShowLootSources(
+                    kind, id,
+                )))
                 .style(style::browse_button)
                 .padding([2, 6]),
-        ]
-        .align_y(iced::Alignment::Center)
-        .padding([6, 10]),
-    )
-    .width(Fill)
-    .style(style::spreadsheet_header);
+        );
+    }
+    header_row = header_row.push(
+        button(text(icon_char(Icon::X)).font(LUCIDE_FONT).size(11))
+            .on_press(spreadsheet_msg(SpreadsheetMessage::CloseInspector))
+            .style(style::browse_button)
+            .padding([2, 6]),
+    );
+    let header = container(header_row)
+        .width(Fill)
+        .style(style::spreadsheet_header);
 
     let mut fields: Column<Message> = column![].spacing(6).padding([8, 12]);
 
@@ -1,9 +1,16 @@
 use crate::components::edit_history::EditHistory;
 use crate::message::{Message, system::SystemMessage, workspace::WorkspaceMessage};
+use dispel_core::modding::Journal;
 use iced::widget::{button, column, container, row, scrollable, text};
 use iced::{Element, Fill};
 
-pub fn view_history_panel(history: &EditHistory) -> Element<'_, crate::message::Message> {
+/// Maximum number of journal entries listed, newest first.
+const MAX_JOURNAL_ROWS: usize = 200;
+
+pub fn view_history_panel<'a>(
+    history: &'a EditHistory,
+    journal: &'a Journal,
+) -> Element<'a, crate::message::Message> {
     let header = row![
         text("Edit History").size(14),
         crate::components::utils::horizontal_space(),
@@ -53,6 +60,8 @@ pub fn view_history_panel(history: &EditHistory) -> Element<'_, crate::message::
         crate::components::utils::horizontal_space().height(10),
         redo_section,
         column(redo_items).spacing(2),
+        crate::components::utils::horizontal_space().height(10),
+        view_journal(journal),
     ]
     .spacing(4);
 
@@ -65,3 +74,88 @@ pub fn view_history_panel(history: &EditHistory) -> Element<'_, crate::message::
         .accessible_label("Edit history")
         .into()
 }
+
+/// Project-wide timeline: every entry of the journal, newest first. Applied
+/// entries are marked `●`, the current head `▶`; clicking a row moves the
+/// project there, across branches.
+fn view_journal(journal: &Journal) -> Element<'_, Message> {
+    let header = row![
+        text("Project Timeline")
+            .size(12)
+            .style(crate::style::subtle_text),
+        crate::components::utils::horizontal_space(),
+        button(text("Undo").size(11))
+            .on_press_maybe(
+                journal
+                    .can_undo()
+                    .then_some(Message::Workspace(WorkspaceMessage::JournalUndo))
+            )
+            .style(crate::style::chip),
+        button(text("Redo").size(11))
+            .on_press_maybe(
+                journal
+                    .can_redo()
+                    .then_some(Message::Workspace(WorkspaceMessage::JournalRedo))
+            )
+            .style(crate::style::chip),
+    ]
+    .spacing(4)
+    .padding([0, 8])
+    .align_y(iced::Alignment::Center);
+
+    let mut rows: Vec<Element<Message>> = journal
+        .entries()
+        .iter()
+        .enumerate()
+        .rev()
+        .take(MAX_JOURNAL_ROWS)
+        .map(|(id, entry)| {
+            let applied = journal.is_applied(id);
+            let marker = match (journal.head() == Some(id), applied) {
+                (true, _) => "▶",
+                (false, true) => "●",
+                (false, false) => "○",
+            };
+            let label = format!("{}. {}", id + 1, crate::journal::describe(&entry.action));
+            journal_row(marker, label, Some(id), applied)
+        })
+        .collect();
+    if journal.len() <= MAX_JOURNAL_ROWS {
+        let marker = if journal.head().is_none() {
+            "▶"
+        } else {
+            "●"
+        };
+        rows.push(journal_row(
+            marker,
+            "Before first edit".to_string(),
+            None,
+            true,
+        ));
+    }
+
+    column![header, column(rows).spacing(2)].spacing(4).into()
+}
+
+fn journal_row(
+    marker: &str,
+    label: String,
+    target: Option<usize>,
+    applied: bool,
+) -> Element<'static, Message> {
+    let label = text(format!("{marker} {label}"))
+        .size(11)
+        .font(iced::Font::MONOSPACE);
+    let label = if applied {
+        label
+    } else {
+        label.style(crate::style::subtle_text)
+    };
+    button(label)
+        .width(Fill)
+        .on_press(Message::Workspace(WorkspaceMessage::JournalCheckout(
+            target,
+        )))
+        .style(crate::style::chip)
+        .into()
+}
@@ -107,7 +107,10 @@ impl App {
                     }
                     PaneContent::HistoryPanel => {
                         if self.history_panel_visible {
-                            view_history_panel(self.get_active_edit_history())
+                            view_history_panel(
+                                self.get_active_edit_history(),
+                                &self.state.journal.journal,
+                            )
                         } else {
                             container(
                                 text("History panel hidden")
@@ -119,6 +122,8 @@ impl App {
                             .into()
                         }
                     }
+                    PaneContent::UsagesPanel => self.usages_panel.view(),
+                    PaneContent::LootPanel => self.loot_panel.view(),
                 };
                 pane_grid::Content::new(pane_content)
             })
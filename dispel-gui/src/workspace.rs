@@ -47,7 +47,7 @@ pub enum EditorType {
     MapEditor,
     ModPackager,
     LocalizationManager,
-    /// Read-only viewer for Dispel .sav save files.
+    /// Editor for Dispel .sav save files.
     SaveFileViewer,
     /// Universal fallback editor for any binary file no dedicated editor
     /// claims. Also reachable via "Open as Hex" from the file tree.
@@ -147,7 +147,6 @@ impl EditorType {
                 | EditorType::TilesetEditor
                 | EditorType::ModPackager
                 | EditorType::LocalizationManager
-                | EditorType::SaveFileViewer
                 | EditorType::HexEditor
                 | EditorType::Unknown
         )
@@ -167,7 +166,6 @@ impl EditorType {
                 | EditorType::MapEditor
                 | EditorType::ModPackager
                 | EditorType::LocalizationManager
-                | EditorType::SaveFileViewer
                 | EditorType::HexEditor
                 | EditorType::Unknown
         )
@@ -190,7 +188,6 @@ impl EditorType {
                 | EditorType::MapEditor
                 | EditorType::ModPackager
                 | EditorType::LocalizationManager
-                | EditorType::SaveFileViewer
                 | EditorType::HexEditor
                 | EditorType::Unknown
         )
@@ -312,9 +312,15 @@ pub fn expand(input: DeriveInput) -> TokenStream2 {
                     byteorder::WriteBytesExt::write_u8(writer, u8::from(record.#ident))?;
                 });
             }
-            FieldInfo::VecU8 { ident, size: _ } => {
+            FieldInfo::VecU8 { ident, size } => {
+                // Always fill the fixed-size slot: zero-pad short buffers
+                // (e.g. `Default` records) and drop excess bytes.
                 write_stmts.push(quote! {
-                    writer.write_all(&record.#ident)?;
+                    {
+                        let bytes = &record.#ident[..record.#ident.len().min(#size)];
+                        writer.write_all(bytes)?;
+                        writer.write_all(&vec![0u8; #size - bytes.len()])?;
+                    }
                 });
             }
             FieldInfo::InventoryItem { ident, wire_type } => {
@@ -369,8 +375,16 @@ pub fn expand(input: DeriveInput) -> TokenStream2 {
         }
     }
 
+    let constraints: Vec<TokenStream2> = field_infos.iter().filter_map(field_constraint).collect();
+
     // Generate the full implementation
     let expanded = quote! {
+        impl crate::references::validation::FieldConstraints for #name {
+            fn field_constraints() -> Vec<crate::references::validation::FieldConstraint> {
+                vec![#(#constraints),*]
+            }
+        }
+
         impl crate::references::extractor::Extractor for #name {
             fn parse<R: std::io::Read + std::io::Seek>(reader: &mut R, len: u64) -> std::io::Result<Vec<Self>> {
                 const COUNTER_SIZE: u8 = #counter_size;
@@ -412,6 +426,72 @@ pub fn expand(input: DeriveInput) -> TokenStream2 {
     expanded
 }
 
+/// The `FieldConstraint` a field's on-disk representation imposes, if any.
+fn field_constraint(info: &FieldInfo<'_>) -> Option<TokenStream2> {
+    let (ident, rule) = match info {
+        FieldInfo::String {
+            ident,
+            encoding,
+            size,
+        } => {
+            let encoding_tokens = get_encoding_tokens(encoding);
+            (
+                *ident,
+                quote! {
+                    crate::references::validation::FieldRule::Text {
+                        encoding: #encoding_tokens,
+                        max_bytes: Some(#size),
+                        forbidden: &[],
+                    }
+                },
+            )
+        }
+        FieldInfo::Primitive { ident, ty } => (*ident, integer_rule(ty)),
+        FieldInfo::EnumFromU8 { ident, enum_ty }
+        | FieldInfo::EnumFromU32 { ident, enum_ty }
+        | FieldInfo::EnumFromI16 { ident, enum_ty }
+        | FieldInfo::EnumFromI32 { ident, enum_ty }
+        | FieldInfo::EnumFromI32FromU8 { ident, enum_ty } => (*ident, enum_rule(enum_ty)),
+        FieldInfo::Array { ident, size, .. } | FieldInfo::VecU8 { ident, size } => (
+            *ident,
+            quote! { crate::references::validation::FieldRule::Items { min: #size, max: #size } },
+        ),
+        FieldInfo::Id { .. }
+        | FieldInfo::Index { .. }
+        | FieldInfo::InventoryItem { .. }
+        | FieldInfo::Padding { .. }
+        | FieldInfo::Skip => return None,
+    };
+    let field = ident.to_string();
+    Some(quote! {
+        crate::references::validation::FieldConstraint { field: #field, rule: #rule }
+    })
+}
+
+/// `FieldRule::Integer` spanning the range of an on-disk integer type.
+pub(crate) fn integer_rule(ty: &str) -> TokenStream2 {
+    let (min, max): (i64, i64) = match ty {
+        "u8" => (u8::MIN.into(), u8::MAX.into()),
+        "u16" => (u16::MIN.into(), u16::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "u32" => (u32::MIN.into(), u32::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        _ => panic!("Unsupported primitive type: {}", ty),
+    };
+    quote! { crate::references::validation::FieldRule::Integer { min: #min, max: #max } }
+}
+
+/// `FieldRule::Enum` checking values against the variants of `enum_ty`.
+pub(crate) fn enum_rule(enum_ty: &str) -> TokenStream2 {
+    let enum_ident = Ident::new(enum_ty, Span::call_site());
+    quote! {
+        crate::references::validation::FieldRule::Enum {
+            type_name: #enum_ty,
+            check: crate::references::validation::enum_domain::<#enum_ident>,
+        }
+    }
+}
+
 pub(crate) enum FieldInfo<'a> {
     Id {
         ident: &'a Ident,
@@ -43,23 +43,18 @@ pub fn expand(input: DeriveInput) -> TokenStream2 {
                         translation: String::new(),
                         encoding: #encoding_tokens,
                         max_bytes: #max_bytes,
+                        needs_review: false,
                     });
                 }
             });
             apply_arms.push(quote! {
                 #field_name_str => {
                     if !entry.translation.is_empty() {
-                        let (text, was_truncated) = crate::localization::truncate_to_fit(
+                        let (text, status) = crate::localization::fit_translation(
                             &entry.translation, &entry.encoding, entry.max_bytes,
                         );
                         self.#field_ident = Some(text);
-                        statuses.push(if was_truncated {
-                            crate::localization::TruncationStatus::Truncated {
-                                original_bytes: entry.translation.len(),
-                            }
-                        } else {
-                            crate::localization::TruncationStatus::Ok
-                        });
+                        statuses.push(status);
                     }
                 }
             });
@@ -73,21 +68,16 @@ pub fn expand(input: DeriveInput) -> TokenStream2 {
                     translation: String::new(),
                     encoding: #encoding_tokens,
                     max_bytes: #max_bytes,
+                    needs_review: false,
                 });
             });
             apply_arms.push(quote! {
                 #field_name_str => {
-                    let (text, was_truncated) = crate::localization::truncate_to_fit(
+                    let (text, status) = crate::localization::fit_translation(
                         &entry.translation, &entry.encoding, entry.max_bytes,
                     );
                     self.#field_ident = text;
-                    statuses.push(if was_truncated {
-                        crate::localization::TruncationStatus::Truncated {
-                            original_bytes: entry.translation.len(),
-                        }
-                    } else {
-                        crate::localization::TruncationStatus::Ok
-                    });
+                    statuses.push(status);
                 }
             });
         }
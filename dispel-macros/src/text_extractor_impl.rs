@@ -3,6 +3,8 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use syn::{Attribute, Data, DeriveInput, Fields, Ident, LitInt, LitStr, Type};
 
+use crate::extractor_impl::{enum_rule, integer_rule};
+
 pub fn expand(input: DeriveInput) -> TokenStream2 {
     let name = &input.ident;
 
@@ -17,6 +19,7 @@ pub fn expand(input: DeriveInput) -> TokenStream2 {
     // Parse struct-level attributes
     let mut encoding = quote! { encoding_rs::EUC_KR };
     let mut delimiter = quote! { "," };
+    let mut delimiter_chars = vec![','];
     let mut comment_char = quote! { ";" };
 
     for attr in &input.attrs {
@@ -39,6 +42,7 @@ pub fn expand(input: DeriveInput) -> TokenStream2 {
                     let value = meta.value()?;
                     let lit: LitStr = value.parse()?;
                     delimiter = quote! { #lit };
+                    delimiter_chars = lit.value().chars().collect();
                 } else if meta.path.is_ident("comment_char") {
                     let value = meta.value()?;
                     let lit: LitStr = value.parse()?;
@@ -136,7 +140,36 @@ pub fn expand(input: DeriveInput) -> TokenStream2 {
         writer.write_all(&cow)?;
     };
 
+    // Text must stay on one line and inside its column once written.
+    delimiter_chars.extend(['\r', '\n']);
+    let constraints: Vec<TokenStream2> = field_infos
+        .iter()
+        .map(|info| {
+            let field = info.ident.to_string();
+            let rule = match &info.ty {
+                TextFieldType::I32 => integer_rule("i32"),
+                TextFieldType::String | TextFieldType::OptionString => quote! {
+                    crate::references::validation::FieldRule::Text {
+                        encoding: #encoding,
+                        max_bytes: None,
+                        forbidden: &[#(#delimiter_chars),*],
+                    }
+                },
+                TextFieldType::EnumFromI32(enum_ty) => enum_rule(enum_ty),
+            };
+            quote! {
+                crate::references::validation::FieldConstraint { field: #field, rule: #rule }
+            }
+        })
+        .collect();
+
     let expanded = quote! {
+        impl crate::references::validation::FieldConstraints for #name {
+            fn field_constraints() -> Vec<crate::references::validation::FieldConstraint> {
+                vec![#(#constraints),*]
+            }
+        }
+
         impl crate::references::extractor::Extractor for #name {
             fn parse<R: std::io::Read + std::io::Seek>(reader: &mut R, _len: u64) -> std::io::Result<Vec<Self>> {
                 use std::io::{BufRead, BufReader};
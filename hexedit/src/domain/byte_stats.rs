@@ -98,6 +98,75 @@ pub fn compute_statistics(bytes: &[u8]) -> ByteStatistics {
     }
 }
 
+/// Incremental [`compute_statistics`] for data that arrives in chunks, e.g.
+/// from [`HexProvider::chunks`](crate::provider::HexProvider::chunks).
+#[derive(Debug, Clone)]
+pub struct StatisticsAccumulator {
+    histogram: [u64; 256],
+    total: u64,
+}
+
+impl Default for StatisticsAccumulator {
+    fn default() -> Self {
+        Self {
+            histogram: [0; 256],
+            total: 0,
+        }
+    }
+}
+
+impl StatisticsAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.histogram[b as usize] += 1;
+        }
+        self.total += bytes.len() as u64;
+    }
+
+    /// Derive the statistics from everything fed so far.
+    pub fn finish(&self) -> ByteStatistics {
+        let total = self.total;
+        if total == 0 {
+            return compute_statistics(&[]);
+        }
+        let histogram = self.histogram;
+        let (min, max, mean, null_count, printable_count, high_ascii_count) =
+            summarise_histogram(&histogram, total);
+        let entropy = compute_entropy_from_histogram(&histogram, total);
+        let median = compute_median(&histogram, total);
+        // The repeating-period probe in `detect_structure` only ever confirms
+        // `Mixed`, so the histogram alone yields the same classification.
+        let structure = detect_structure(&[], &histogram, total, entropy);
+
+        ByteStatistics {
+            histogram,
+            total,
+            entropy,
+            min,
+            max,
+            mean,
+            median,
+            structure,
+            null_count,
+            printable_count,
+            high_ascii_count,
+        }
+    }
+}
+
+/// Streaming counterpart of [`compute_statistics`].
+pub fn compute_statistics_chunks<'a>(chunks: impl IntoIterator<Item = &'a [u8]>) -> ByteStatistics {
+    let mut acc = StatisticsAccumulator::new();
+    for chunk in chunks {
+        acc.feed(chunk);
+    }
+    acc.finish()
+}
+
 /// Build the 256-bin histogram for a byte slice.
 pub fn build_histogram(bytes: &[u8]) -> [u64; 256] {
     let mut hist = [0u64; 256];
@@ -136,25 +205,44 @@ pub fn compute_entropy(bytes: &[u8]) -> f64 {
 /// The last partial row is included if non-empty. Returns a [`RowEntropyCache`]
 /// suitable for colour-band rendering in the address gutter.
 pub fn compute_row_entropies(bytes: &[u8], bytes_per_row: u8) -> RowEntropyCache {
+    compute_row_entropies_chunks(std::iter::once(bytes), bytes_per_row)
+}
+
+/// Streaming counterpart of [`compute_row_entropies`]. Rows may straddle
+/// chunk boundaries.
+pub fn compute_row_entropies_chunks<'a>(
+    chunks: impl IntoIterator<Item = &'a [u8]>,
+    bytes_per_row: u8,
+) -> RowEntropyCache {
     let bpr = bytes_per_row.max(1) as usize;
-    if bytes.is_empty() {
-        return RowEntropyCache {
-            rows: Vec::new(),
-            max_entropy: 0.0,
-            min_entropy: 0.0,
-        };
-    }
-    let mut rows = Vec::with_capacity(bytes.len().div_ceil(bpr));
+    let mut rows = Vec::new();
     let mut max_e = 0.0f64;
     let mut min_e = f64::MAX;
+    let mut hist = [0u64; 256];
+    let mut filled = 0usize;
+    let mut row_addr = 0u64;
 
-    for (chunk_idx, chunk) in bytes.chunks(bpr).enumerate() {
-        let row_addr = (chunk_idx * bpr) as u64;
-        let hist = build_histogram(chunk);
-        let e = compute_entropy_from_histogram(&hist, chunk.len() as u64);
+    let mut push_row = |hist: &mut [u64; 256], filled: usize, row_addr: u64| {
+        let e = compute_entropy_from_histogram(hist, filled as u64);
         max_e = max_e.max(e);
         min_e = min_e.min(e);
         rows.push((row_addr, e));
+        *hist = [0; 256];
+    };
+    for chunk in chunks {
+        for &b in chunk {
+            hist[b as usize] += 1;
+            filled += 1;
+            if filled == bpr {
+                push_row(&mut hist, filled, row_addr);
+                row_addr += bpr as u64;
+                filled = 0;
+            }
+        }
+    }
+    // The last partial row is included if non-empty.
+    if filled > 0 {
+        push_row(&mut hist, filled, row_addr);
     }
 
     if min_e == f64::MAX {
@@ -459,4 +547,24 @@ mod tests {
         let stats = compute_statistics(&bytes);
         assert_eq!(stats.structure, StructureHeuristic::Uniform(0x00));
     }
+
+    #[test]
+    fn chunked_statistics_match_slice_versions() {
+        let bytes: Vec<u8> = (0..1000u32).map(|i| (i * i % 251) as u8).collect();
+        let chunks = [&bytes[..7], &bytes[7..500], &bytes[500..]];
+
+        let whole = compute_statistics(&bytes);
+        let streamed = compute_statistics_chunks(chunks);
+        assert_eq!(streamed.histogram, whole.histogram);
+        assert_eq!(streamed.total, whole.total);
+        assert_eq!(streamed.entropy, whole.entropy);
+        assert_eq!(streamed.median, whole.median);
+        assert_eq!(streamed.structure, whole.structure);
+
+        let whole = compute_row_entropies(&bytes, 16);
+        let streamed = compute_row_entropies_chunks(chunks, 16);
+        assert_eq!(streamed.rows, whole.rows);
+        assert_eq!(streamed.rows.len(), 63, "partial last row is kept");
+        assert_eq!(compute_statistics_chunks([]).total, 0);
+    }
 }
@@ -1,5 +1,6 @@
 //! Query-oriented structure overlays for binary files.
 
+use std::borrow::Cow;
 use std::ops::Range;
 
 /// One resolved on-disk field.
@@ -7,7 +8,8 @@ use std::ops::Range;
 pub struct FieldSpan {
     pub range: Range<u64>,
     pub name: &'static str,
-    pub ty: &'static str,
+    /// Owned for generated labels such as `u8[16]`.
+    pub ty: Cow<'static, str>,
     pub record_type: &'static str,
     pub record_index: u64,
     pub color_index: u8,
@@ -20,7 +22,7 @@ pub struct LayoutOutlineItem {
     pub id: usize,
     pub range: Range<u64>,
     pub name: &'static str,
-    pub ty: &'static str,
+    pub ty: Cow<'static, str>,
     pub record_index: u64,
     pub depth: u8,
     pub has_children: bool,
@@ -97,7 +99,7 @@ impl BinaryLayout for SpanBinaryLayout {
             .map(|(index, span)| FieldSpan {
                 range: span.range.clone(),
                 name: span.name,
-                ty: span.ty,
+                ty: span.ty.into(),
                 record_type: self.type_name,
                 record_index: span.record_index,
                 color_index: (index % 16) as u8,
@@ -116,7 +118,7 @@ impl BinaryLayout for SpanBinaryLayout {
             .map(|(index, span)| FieldSpan {
                 range: span.range.clone(),
                 name: span.name,
-                ty: span.ty,
+                ty: span.ty.into(),
                 record_type: self.type_name,
                 record_index: span.record_index,
                 color_index: (index % 16) as u8,
@@ -150,7 +152,7 @@ impl BinaryLayout for SpanBinaryLayout {
                     id: 0,
                     range: span.range.clone(),
                     name: span.name,
-                    ty: span.ty,
+                    ty: span.ty.into(),
                     record_index: span.record_index,
                     depth: parents.len().min(u8::MAX as usize) as u8,
                     has_children: false,
@@ -196,7 +198,7 @@ impl FixedRecordBinaryLayout {
         FieldSpan {
             range: start..start + u64::from(field.size),
             name: field.name,
-            ty: field.ty,
+            ty: field.ty.into(),
             record_type: self.type_name,
             record_index,
             color_index: (self
@@ -0,0 +1,542 @@
+//! Memory-mapped, piece-table backed [`HexProvider`] for very large files.
+//!
+//! [`BufferProvider`](super::provider::BufferProvider) copies the whole file
+//! into a `Vec<u8>` and tracks one dirty entry per byte. [`MappedProvider`]
+//! instead maps the file read-only and describes the current contents as a
+//! list of pieces, each pointing either into the mapping or into an
+//! append-only buffer holding every byte written or inserted since load.
+//! Reads, writes and inserts cost O(pieces), i.e. they scale with the number
+//! of edits rather than the file size. Dirty state is kept as merged ranges.
+//!
+//! Pieces are not contiguous in memory, so [`HexProvider::read`] returns at
+//! most the rest of one piece. Views read the rows they show with
+//! [`HexProvider::read_range`] and streaming consumers walk the buffer with
+//! [`HexProvider::chunks`]; nothing keeps a flat copy of the file.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use memmap2::Mmap;
+
+use super::provider::HexProvider;
+
+/// Where a piece's bytes live.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    /// The file as loaded (mapped or owned).
+    Original,
+    /// The append-only edit buffer.
+    Added,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+    source: Source,
+    /// Offset into the source buffer.
+    start: u64,
+    len: u64,
+}
+
+/// Bytes the piece table was loaded from.
+#[derive(Debug)]
+enum Original {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Original {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Original::Mapped(map) => map,
+            Original::Owned(data) => data,
+        }
+    }
+}
+
+/// Source of [`MappedProvider::revision`] values, shared by all providers.
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Read-only mmap plus piece-table overlay.
+///
+/// Cloning shares the mapping, so a snapshot costs O(edits) rather than
+/// O(file size).
+#[derive(Debug, Clone)]
+pub struct MappedProvider {
+    original: Arc<Original>,
+    /// Every byte written or inserted since load, in edit order.
+    added: Vec<u8>,
+    pieces: Vec<Piece>,
+    /// Logical start offset of each piece, parallel to `pieces`.
+    starts: Vec<u64>,
+    len: u64,
+    /// Sorted, non-overlapping, non-adjacent ranges changed since load.
+    dirty: Vec<Range<u64>>,
+    path: Option<PathBuf>,
+    /// Renewed by every write or insert that changes the contents.
+    revision: u64,
+}
+
+impl Default for MappedProvider {
+    fn default() -> Self {
+        Self::from_bytes(Vec::new())
+    }
+}
+
+impl MappedProvider {
+    /// Map `path` read-only. Nothing is copied until the first edit.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        let original = if file.metadata()?.len() == 0 {
+            // Zero-length mappings are rejected on some platforms.
+            Original::Owned(Vec::new())
+        } else {
+            // SAFETY: the mapping is read-only. Like every mmap-based viewer
+            // we rely on the file not being truncated by another process
+            // while it is open; our own `save` never writes into it.
+            Original::Mapped(unsafe { Mmap::map(&file)? })
+        };
+        let mut provider = Self::with_original(original);
+        provider.path = Some(path.to_path_buf());
+        Ok(provider)
+    }
+
+    /// Wrap an in-memory buffer. Mostly useful for tests and small files.
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self::with_original(Original::Owned(data))
+    }
+
+    fn with_original(original: Original) -> Self {
+        let len = original.bytes().len() as u64;
+        let mut provider = Self {
+            original: Arc::new(original),
+            added: Vec::new(),
+            pieces: Vec::new(),
+            starts: Vec::new(),
+            len,
+            dirty: Vec::new(),
+            path: None,
+            revision: next_revision(),
+        };
+        if len > 0 {
+            provider.pieces.push(Piece {
+                source: Source::Original,
+                start: 0,
+                len,
+            });
+        }
+        provider.rebuild_starts(0);
+        provider
+    }
+
+    /// File this provider was opened from, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Stamp of the current contents. Every edit that changes them draws a
+    /// new value from a process-wide counter, so two providers share a
+    /// revision only while one is an unedited clone of the other. Saving
+    /// keeps it; caches and work scheduled for a revision use it to tell
+    /// whether they are stale.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Number of pieces describing the current contents.
+    pub fn piece_count(&self) -> usize {
+        self.pieces.len()
+    }
+
+    /// Changed ranges since load (or the last save), sorted and merged.
+    pub fn dirty_ranges(&self) -> &[Range<u64>] {
+        &self.dirty
+    }
+
+    /// Total number of changed bytes.
+    pub fn dirty_count(&self) -> u64 {
+        self.dirty.iter().map(|r| r.end - r.start).sum()
+    }
+
+    pub fn is_dirty(&self, addr: u64) -> bool {
+        let idx = self.dirty.partition_point(|r| r.end <= addr);
+        self.dirty.get(idx).is_some_and(|r| r.contains(&addr))
+    }
+
+    pub fn clear_dirty(&mut self) {
+        self.dirty.clear();
+    }
+
+    /// Insert `bytes` at `addr`, growing the buffer. Same semantics as
+    /// [`BufferProvider::insert`](super::provider::BufferProvider::insert):
+    /// `addr` past the end appends, inserted offsets become dirty and dirty
+    /// ranges at/after `addr` shift forward.
+    pub fn insert(&mut self, addr: u64, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        let addr = addr.min(self.len);
+        let count = bytes.len() as u64;
+        self.revision = next_revision();
+        let idx = self.split_at(addr);
+        let piece = self.append_added(bytes);
+        self.pieces.insert(idx, piece);
+        self.len += count;
+        let first = self.coalesce_around(idx);
+        self.rebuild_starts(first);
+
+        let mut shifted = Vec::with_capacity(self.dirty.len() + 1);
+        for r in self.dirty.drain(..) {
+            if r.end <= addr {
+                shifted.push(r);
+            } else if r.start >= addr {
+                shifted.push(r.start + count..r.end + count);
+            } else {
+                shifted.push(r.start..addr);
+                shifted.push(addr + count..r.end + count);
+            }
+        }
+        self.dirty = shifted;
+        self.mark_dirty(addr..addr + count);
+    }
+
+    /// Stream the current contents to `out`.
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        for chunk in self.chunks(0..self.len) {
+            out.write_all(chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Write the contents to `path` and remap from it.
+    pub fn save(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        self.write_file(path)?;
+        let revision = self.revision;
+        *self = Self::open(path)?;
+        self.revision = revision;
+        Ok(())
+    }
+
+    /// Write the contents to `path` without remapping.
+    ///
+    /// The data goes to a sibling temporary file first and is renamed into
+    /// place, so saving over the mapped file never rewrites bytes that are
+    /// still being read through the mapping (or through a clone of it).
+    pub fn write_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+        let mut tmp_name = std::ffi::OsString::from(".");
+        tmp_name.push(file_name);
+        tmp_name.push(".hexedit-tmp");
+        let tmp = path.with_file_name(tmp_name);
+        {
+            let mut out = io::BufWriter::new(File::create(&tmp)?);
+            self.write_to(&mut out)?;
+            out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        }
+        std::fs::rename(&tmp, path)
+    }
+
+    // ── Piece table internals ─────────────────────────────────────────
+
+    fn source(&self, source: Source) -> &[u8] {
+        match source {
+            Source::Original => self.original.bytes(),
+            Source::Added => &self.added,
+        }
+    }
+
+    /// Index of the piece containing `addr` (`addr < len`).
+    fn piece_at(&self, addr: u64) -> usize {
+        self.starts.partition_point(|&s| s <= addr) - 1
+    }
+
+    /// Make sure a piece starts at `addr` and return its index
+    /// (`pieces.len()` when `addr == len`).
+    fn split_at(&mut self, addr: u64) -> usize {
+        if addr >= self.len {
+            return self.pieces.len();
+        }
+        let idx = self.piece_at(addr);
+        let offset = addr - self.starts[idx];
+        if offset == 0 {
+            return idx;
+        }
+        let piece = self.pieces[idx];
+        self.pieces[idx].len = offset;
+        self.pieces.insert(
+            idx + 1,
+            Piece {
+                source: piece.source,
+                start: piece.start + offset,
+                len: piece.len - offset,
+            },
+        );
+        self.starts.insert(idx + 1, addr);
+        idx + 1
+    }
+
+    fn append_added(&mut self, bytes: &[u8]) -> Piece {
+        let start = self.added.len() as u64;
+        self.added.extend_from_slice(bytes);
+        Piece {
+            source: Source::Added,
+            start,
+            len: bytes.len() as u64,
+        }
+    }
+
+    /// Merge the piece at `idx` with neighbours that continue the same
+    /// source run (typing byte after byte produces exactly that). Returns
+    /// the first index whose start offset may have changed.
+    fn coalesce_around(&mut self, mut idx: usize) -> usize {
+        let joins = |a: &Piece, b: &Piece| a.source == b.source && a.start + a.len == b.start;
+        if idx + 1 < self.pieces.len() && joins(&self.pieces[idx], &self.pieces[idx + 1]) {
+            self.pieces[idx].len += self.pieces[idx + 1].len;
+            self.pieces.remove(idx + 1);
+        }
+        if idx > 0 && joins(&self.pieces[idx - 1], &self.pieces[idx]) {
+            self.pieces[idx - 1].len += self.pieces[idx].len;
+            self.pieces.remove(idx);
+            idx -= 1;
+        }
+        idx
+    }
+
+    fn rebuild_starts(&mut self, from: usize) {
+        self.starts.truncate(from);
+        let mut pos = match from {
+            0 => 0,
+            _ => self.starts[from - 1] + self.pieces[from - 1].len,
+        };
+        for piece in &self.pieces[from..] {
+            self.starts.push(pos);
+            pos += piece.len;
+        }
+    }
+
+    fn mark_dirty(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        // Ranges touching or overlapping `range` are folded into it.
+        let first = self.dirty.partition_point(|r| r.end < range.start);
+        let last = self.dirty.partition_point(|r| r.start <= range.end);
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.dirty[first].start);
+            merged.end = merged.end.max(self.dirty[last - 1].end);
+        }
+        self.dirty.splice(first..last, [merged]);
+    }
+}
+
+impl HexProvider for MappedProvider {
+    fn read(&self, range: Range<u64>) -> &[u8] {
+        let end = range.end.min(self.len);
+        if range.start >= end {
+            return &[];
+        }
+        let idx = self.piece_at(range.start);
+        let piece = self.pieces[idx];
+        let offset = range.start - self.starts[idx];
+        let take = (piece.len - offset).min(end - range.start);
+        let from = (piece.start + offset) as usize;
+        &self.source(piece.source)[from..from + take as usize]
+    }
+
+    fn write(&mut self, addr: u64, bytes: &[u8]) {
+        if addr >= self.len || bytes.is_empty() {
+            return;
+        }
+        let n = (bytes.len() as u64).min(self.len - addr);
+        let bytes = &bytes[..n as usize];
+
+        // Collect the runs that actually change; identical bytes neither
+        // create pieces nor become dirty.
+        let mut changed: Vec<Range<u64>> = Vec::new();
+        let mut pos = addr;
+        for chunk in self.chunks(addr..addr + n) {
+            for (i, &old) in chunk.iter().enumerate() {
+                let at = pos + i as u64;
+                if old != bytes[(at - addr) as usize] {
+                    match changed.last_mut() {
+                        Some(run) if run.end == at => run.end += 1,
+                        _ => changed.push(at..at + 1),
+                    }
+                }
+            }
+            pos += chunk.len() as u64;
+        }
+        let Some(span) = changed
+            .first()
+            .map(|f| f.start..changed[changed.len() - 1].end)
+        else {
+            return;
+        };
+
+        self.revision = next_revision();
+        let first = self.split_at(span.start);
+        let last = self.split_at(span.end);
+        let piece =
+            self.append_added(&bytes[(span.start - addr) as usize..(span.end - addr) as usize]);
+        self.pieces.splice(first..last, [piece]);
+        let from = self.coalesce_around(first);
+        self.rebuild_starts(from);
+        for run in changed {
+            self.mark_dirty(run);
+        }
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn is_writable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(p: &MappedProvider) -> Vec<u8> {
+        p.read_to_vec(0..p.len())
+    }
+
+    #[test]
+    fn read_is_bounded_by_piece_and_eof() {
+        let mut p = MappedProvider::from_bytes(vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(p.read(1..4), &[2, 3, 4]);
+        assert_eq!(p.read(4..99), &[5, 6]);
+        assert_eq!(p.read(6..9), b"");
+        p.write(2, &[0xAA]);
+        // The read stops at the piece boundary; read_to_vec stitches.
+        assert_eq!(p.read(0..6), &[1, 2]);
+        assert_eq!(p.read_to_vec(0..6), vec![1, 2, 0xAA, 4, 5, 6]);
+        assert_eq!(p.chunks(0..6).count(), 3);
+    }
+
+    #[test]
+    fn read_range_copies_only_across_pieces() {
+        use std::borrow::Cow;
+
+        let mut p = MappedProvider::from_bytes(vec![1, 2, 3, 4, 5, 6]);
+        p.write(2, &[9]);
+        assert!(matches!(p.read_range(3..6), Cow::Borrowed(&[4, 5, 6])));
+        assert!(matches!(p.read_range(1..4), Cow::Owned(_)));
+        assert_eq!(p.read_range(1..4).as_ref(), &[2, 9, 4]);
+
+        let snapshot = p.clone();
+        p.write(0, &[7]);
+        assert_eq!(contents(&snapshot), vec![1, 2, 9, 4, 5, 6]);
+        assert_eq!(contents(&p), vec![7, 2, 9, 4, 5, 6]);
+    }
+
+    #[test]
+    fn write_only_marks_changed_bytes() {
+        let mut p = MappedProvider::from_bytes(vec![0; 8]);
+        p.write(1, &[0, 7, 7, 0, 9]);
+        assert_eq!(contents(&p), vec![0, 0, 7, 7, 0, 9, 0, 0]);
+        assert_eq!(p.dirty_ranges(), &[2..4, 5..6]);
+        assert_eq!(p.dirty_count(), 3);
+        assert!(p.is_dirty(3) && !p.is_dirty(4));
+
+        let revision = p.revision();
+        p.write(0, &[0, 0]);
+        assert_eq!(p.dirty_count(), 3, "identical writes change nothing");
+        assert_eq!(p.revision(), revision);
+        p.write(7, &[1, 2, 3]);
+        assert_eq!(contents(&p)[7], 1);
+        assert_eq!(p.len(), 8, "writes never grow the buffer");
+    }
+
+    #[test]
+    fn sequential_typing_coalesces_pieces() {
+        let mut p = MappedProvider::from_bytes(vec![0; 64]);
+        for i in 0..16u64 {
+            p.write(10 + i, &[i as u8 + 1]);
+        }
+        assert_eq!(p.piece_count(), 3);
+        assert_eq!(p.dirty_ranges(), std::slice::from_ref(&(10..26)));
+    }
+
+    #[test]
+    fn insert_shifts_tail_and_dirty_ranges() {
+        let mut p = MappedProvider::from_bytes(vec![1, 2, 3, 4, 5, 6]);
+        p.write(1, &[0xF1, 0xF2, 0xF3]);
+        p.insert(2, &[9, 9]);
+        assert_eq!(contents(&p), vec![1, 0xF1, 9, 9, 0xF2, 0xF3, 5, 6]);
+        assert_eq!(p.dirty_ranges(), std::slice::from_ref(&(1..6)));
+
+        p.insert(99, &[7]);
+        assert_eq!(*contents(&p).last().unwrap(), 7);
+        p.insert(0, &[8]);
+        assert_eq!(contents(&p)[..3], [8, 1, 0xF1]);
+        assert_eq!(p.dirty_ranges(), &[0..1, 2..7, 9..10]);
+    }
+
+    #[test]
+    fn matches_buffer_provider_under_random_edits() {
+        use super::super::provider::BufferProvider;
+
+        let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+        let mut rand = move |n: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % n.max(1)
+        };
+        let data: Vec<u8> = (0..500).map(|i| (i * 7) as u8).collect();
+        let mut reference = BufferProvider::from_bytes(data.clone());
+        let mut p = MappedProvider::from_bytes(data);
+        for _ in 0..400 {
+            let addr = rand(p.len() + 2);
+            let bytes: Vec<u8> = (0..=rand(6)).map(|_| rand(4) as u8).collect();
+            if rand(3) == 0 {
+                reference.insert(addr, &bytes);
+                p.insert(addr, &bytes);
+            } else {
+                reference.write(addr, &bytes);
+                p.write(addr, &bytes);
+            }
+        }
+        assert_eq!(contents(&p), reference.as_slice());
+        let dirty: Vec<u64> = p.dirty_ranges().iter().flat_map(|r| r.clone()).collect();
+        assert_eq!(dirty, reference.dirty().iter().copied().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn open_maps_file_and_save_remaps() {
+        let dir = std::env::temp_dir().join(format!("hexedit-mapped-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("big.bin");
+        std::fs::write(&path, [1u8, 2, 3, 4]).unwrap();
+
+        let mut p = MappedProvider::open(&path).unwrap();
+        assert_eq!(p.path(), Some(path.as_path()));
+        p.write(0, &[0xAA]);
+        p.insert(4, &[5, 6]);
+        p.save(&path).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), vec![0xAA, 2, 3, 4, 5, 6]);
+        assert_eq!(p.piece_count(), 1, "saving compacts the piece table");
+        assert_eq!(p.dirty_count(), 0);
+
+        std::fs::write(dir.join("empty.bin"), []).unwrap();
+        let empty = MappedProvider::open(dir.join("empty.bin")).unwrap();
+        assert!(empty.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
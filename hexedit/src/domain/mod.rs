@@ -5,11 +5,14 @@ pub mod extend_dialog;
 pub mod fill_dialog;
 pub mod goto;
 pub mod layout;
+pub mod mapped_provider;
 pub mod panel;
 pub mod pattern;
 pub mod pattern_layout;
 pub mod provider;
 pub mod search;
 pub mod selection;
+pub mod structural_diff;
+pub mod template;
 pub mod vanilla_diff;
 pub mod write_mode;
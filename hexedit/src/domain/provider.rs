@@ -1,16 +1,21 @@
 //! Byte-source abstraction for the hex editor.
 //!
 //! The matrix widget reads through a [`HexProvider`] so that vanilla
-//! snapshots (read-only mmap, future commit) and the live editing buffer
-//! can coexist behind the same interface.
+//! snapshots, the live editing buffer and the memory-mapped
+//! [`MappedProvider`](super::mapped_provider::MappedProvider) can coexist
+//! behind the same interface.
 
+use std::borrow::Cow;
 use std::collections::BTreeSet;
 use std::ops::Range;
 
 /// A random-access source of bytes consumed by the hex editor.
 pub trait HexProvider {
     /// Read the byte range. Returned slice may be shorter than `range.len()`
-    /// if `range.end > self.len()`.
+    /// if `range.end > self.len()`, or if the provider does not store the
+    /// range contiguously (it then ends at the first internal boundary).
+    /// Use [`chunks`](Self::chunks) or [`read_to_vec`](Self::read_to_vec) to
+    /// get the whole range.
     fn read(&self, range: Range<u64>) -> &[u8];
 
     /// Overwrite bytes starting at `addr`. Out-of-range writes are ignored.
@@ -29,6 +34,77 @@ pub trait HexProvider {
 
     /// True if `write` has any effect.
     fn is_writable(&self) -> bool;
+
+    /// Iterate over `range` as consecutive contiguous slices. Streaming
+    /// consumers (search, statistics) use this instead of one big slice.
+    fn chunks(&self, range: Range<u64>) -> Chunks<'_, Self>
+    where
+        Self: Sized,
+    {
+        Chunks {
+            provider: self,
+            pos: range.start,
+            end: range.end.min(self.len()),
+        }
+    }
+
+    /// Read the whole of `range` (clamped to the end). Borrows when the
+    /// provider stores it contiguously and only copies across boundaries,
+    /// so views can ask for just the bytes they show.
+    fn read_range(&self, range: Range<u64>) -> Cow<'_, [u8]> {
+        let end = range.end.min(self.len());
+        let first = self.read(range.start..end);
+        let mut pos = range.start + first.len() as u64;
+        if pos >= end {
+            return Cow::Borrowed(first);
+        }
+        let mut out = first.to_vec();
+        while pos < end {
+            let chunk = self.read(pos..end);
+            if chunk.is_empty() {
+                break;
+            }
+            out.extend_from_slice(chunk);
+            pos += chunk.len() as u64;
+        }
+        Cow::Owned(out)
+    }
+
+    /// Copy `range` into an owned buffer, stitching internal boundaries.
+    fn read_to_vec(&self, range: Range<u64>) -> Vec<u8>
+    where
+        Self: Sized,
+    {
+        let mut out = Vec::new();
+        for chunk in self.chunks(range) {
+            out.extend_from_slice(chunk);
+        }
+        out
+    }
+}
+
+/// Iterator returned by [`HexProvider::chunks`].
+pub struct Chunks<'a, P> {
+    provider: &'a P,
+    pos: u64,
+    end: u64,
+}
+
+impl<'a, P: HexProvider> Iterator for Chunks<'a, P> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.pos >= self.end {
+            return None;
+        }
+        let chunk = self.provider.read(self.pos..self.end);
+        if chunk.is_empty() {
+            self.pos = self.end;
+            return None;
+        }
+        self.pos += chunk.len() as u64;
+        Some(chunk)
+    }
 }
 
 /// In-memory editing buffer. Tracks which addresses have been overwritten
@@ -95,6 +171,34 @@ impl BufferProvider {
     }
 }
 
+/// A plain slice: contiguous and overwrite-only, without dirty tracking.
+impl HexProvider for [u8] {
+    fn read(&self, range: Range<u64>) -> &[u8] {
+        let start = (range.start as usize).min(self.len());
+        let end = (range.end as usize).min(self.len());
+        &self[start..end.max(start)]
+    }
+
+    fn write(&mut self, addr: u64, bytes: &[u8]) {
+        let Ok(start) = usize::try_from(addr) else {
+            return;
+        };
+        if start >= self.len() {
+            return;
+        }
+        let n = bytes.len().min(self.len() - start);
+        self[start..start + n].copy_from_slice(&bytes[..n]);
+    }
+
+    fn len(&self) -> u64 {
+        <[u8]>::len(self) as u64
+    }
+
+    fn is_writable(&self) -> bool {
+        true
+    }
+}
+
 impl HexProvider for BufferProvider {
     fn read(&self, range: Range<u64>) -> &[u8] {
         let start = (range.start as usize).min(self.data.len());
@@ -150,6 +254,15 @@ mod tests {
         assert_eq!(p.read(5..10), b"");
     }
 
+    #[test]
+    fn read_range_borrows_contiguous_bytes() {
+        let data = [1u8, 2, 3, 4];
+        let bytes = &data[..];
+        assert!(matches!(bytes.read_range(1..3), Cow::Borrowed(&[2, 3])));
+        assert_eq!(bytes.read_range(2..99).as_ref(), &[3, 4]);
+        assert!(bytes.read_range(9..12).is_empty());
+    }
+
     #[test]
     fn write_marks_changed_bytes_dirty() {
         let mut p = BufferProvider::from_bytes(vec![0, 0, 0, 0]);
@@ -78,6 +78,14 @@ impl SearchState {
 
     /// Run the search against `data` and populate results.
     pub fn execute(&mut self, data: &[u8]) {
+        self.execute_chunks(std::iter::once(data));
+    }
+
+    /// Run the search over consecutive slices of one logical buffer, such as
+    /// [`HexProvider::chunks`](crate::provider::HexProvider::chunks). Matches
+    /// that straddle slice boundaries are found; memory use is bounded by
+    /// [`STREAM_BLOCK`] rather than by the buffer size.
+    pub fn execute_chunks<'a>(&mut self, chunks: impl IntoIterator<Item = &'a [u8]>) {
         self.results.clear();
         self.extents.clear();
         self.match_set.clear();
@@ -85,15 +93,30 @@ impl SearchState {
         self.query_len = 0;
 
         let q = self.query.trim().to_string();
-        if q.is_empty() || data.is_empty() {
+        if q.is_empty() {
             return;
         }
+        let needle = match self.mode {
+            SearchMode::Hex => parse_hex_query(&q),
+            SearchMode::Ascii => Some(collapse_whitespace(q.as_bytes())),
+            SearchMode::Decimal => self.decimal_needle(&q),
+        };
+        let Some(needle) = needle.filter(|n| !n.is_empty()) else {
+            return;
+        };
 
-        match self.mode {
-            SearchMode::Hex => self.search_hex(data, &q),
-            SearchMode::Ascii => self.search_ascii(data, &q),
-            SearchMode::Decimal => self.search_decimal(data, &q),
+        let mut matcher = StreamMatcher::new(needle, self.mode == SearchMode::Ascii);
+        for chunk in chunks {
+            for block in chunk.chunks(STREAM_BLOCK) {
+                matcher.feed(block, &mut self.results, &mut self.extents);
+            }
+        }
+        if matcher.pos == 0 {
+            // Nothing to search: keep the state of an empty result.
+            return;
         }
+        matcher.finish(&mut self.results, &mut self.extents);
+        self.query_len = matcher.needle.len() as u64;
 
         // Build match_set for the renderer, covering each match's FULL original
         // extent (which may be longer than `query_len` for collapsed ASCII).
@@ -105,52 +128,9 @@ impl SearchState {
         }
     }
 
-    fn search_hex(&mut self, data: &[u8], query: &str) {
-        let bytes = match parse_hex_query(query) {
-            Some(b) => b,
-            None => return,
-        };
-        if bytes.is_empty() {
-            return;
-        }
-        self.query_len = bytes.len() as u64;
-        if bytes.len() > data.len() {
-            return;
-        }
-        for i in 0..=data.len() - bytes.len() {
-            if data[i..i + bytes.len()] == bytes[..] {
-                self.results.push(i as u64);
-                self.extents.push(bytes.len() as u64);
-            }
-        }
-    }
-
-    fn search_ascii(&mut self, data: &[u8], query: &str) {
-        let (norm_data, starts, ends) = normalize_whitespace(data);
-        let needle = collapse_whitespace(query.as_bytes());
-        if needle.is_empty() {
-            return;
-        }
-        self.query_len = needle.len() as u64;
-        if needle.len() > norm_data.len() {
-            return;
-        }
-        // Sliding window over the normalized data; map each match back to the
-        // original byte offset and full original extent.
-        for i in 0..=norm_data.len() - needle.len() {
-            if norm_data[i..i + needle.len()] == needle[..] {
-                self.results.push(starts[i]);
-                let end = ends[i + needle.len() - 1] + 1;
-                self.extents.push(end - starts[i]);
-            }
-        }
-    }
-
-    fn search_decimal(&mut self, data: &[u8], query: &str) {
-        let value: i128 = match query.parse() {
-            Ok(v) => v,
-            Err(_) => return,
-        };
+    /// Encode a decimal query with the configured width and endianness.
+    fn decimal_needle(&self, query: &str) -> Option<Vec<u8>> {
+        let value: i128 = query.parse().ok()?;
         let width = self.width.clamp(1, 8) as usize;
         let bits = width * 8;
         // Reject values that don't fit the signed range of `width` bytes
@@ -158,19 +138,9 @@ impl SearchState {
         let max = (1i128 << (bits - 1)) - 1;
         let min = -(1i128 << (bits - 1));
         if value < min || value > max {
-            return;
-        }
-        let bytes = int_to_bytes(value, width, self.little_endian);
-        self.query_len = bytes.len() as u64;
-        if bytes.len() > data.len() {
-            return;
-        }
-        for i in 0..=data.len() - bytes.len() {
-            if data[i..i + bytes.len()] == bytes[..] {
-                self.results.push(i as u64);
-                self.extents.push(bytes.len() as u64);
-            }
+            return None;
         }
+        Some(int_to_bytes(value, width, self.little_endian))
     }
 
     /// Number of matches.
@@ -279,6 +249,108 @@ fn hex_nibble(c: u8) -> Option<u8> {
     }
 }
 
+/// Largest slice the streaming matcher buffers at once. Providers may hand
+/// out one huge contiguous chunk (a whole mmap); it is fed in blocks of
+/// this size so the working window stays small.
+pub const STREAM_BLOCK: usize = 1 << 20;
+
+/// Incremental needle matcher behind [`SearchState::execute_chunks`].
+///
+/// Keeps only the bytes that could still start a match, i.e. fewer than
+/// `needle.len()` entries between blocks. In whitespace-collapsing (ASCII)
+/// mode the window holds normalized bytes plus their original offsets, and a
+/// trailing whitespace run stays open until the next non-space byte arrives.
+struct StreamMatcher {
+    needle: Vec<u8>,
+    collapse_ws: bool,
+    /// Candidate bytes (normalized in ASCII mode).
+    window: Vec<u8>,
+    /// Original offset of `window[0]` when not collapsing.
+    base: u64,
+    /// Original first / last offset of each window entry when collapsing.
+    starts: Vec<u64>,
+    ends: Vec<u64>,
+    /// The last fed byte was whitespace (ASCII mode only).
+    in_ws: bool,
+    /// Original offset of the next byte to be fed.
+    pos: u64,
+}
+
+impl StreamMatcher {
+    fn new(needle: Vec<u8>, collapse_ws: bool) -> Self {
+        Self {
+            needle,
+            collapse_ws,
+            window: Vec::new(),
+            base: 0,
+            starts: Vec::new(),
+            ends: Vec::new(),
+            in_ws: false,
+            pos: 0,
+        }
+    }
+
+    fn feed(&mut self, block: &[u8], results: &mut Vec<u64>, extents: &mut Vec<u64>) {
+        if block.is_empty() {
+            return;
+        }
+        if self.collapse_ws {
+            let (norm, starts, ends) = normalize_whitespace(block);
+            let mut skip = 0;
+            // A whitespace run continuing from the previous block extends
+            // the pending entry instead of opening a new one.
+            if self.in_ws
+                && block[0].is_ascii_whitespace()
+                && let Some(last) = self.ends.last_mut()
+            {
+                *last = self.pos + ends[0];
+                skip = 1;
+            }
+            self.window.extend_from_slice(&norm[skip..]);
+            self.starts
+                .extend(starts[skip..].iter().map(|s| self.pos + s));
+            self.ends.extend(ends[skip..].iter().map(|e| self.pos + e));
+            self.in_ws = block[block.len() - 1].is_ascii_whitespace();
+        } else {
+            self.window.extend_from_slice(block);
+        }
+        self.pos += block.len() as u64;
+        self.scan(false, results, extents);
+    }
+
+    fn finish(&mut self, results: &mut Vec<u64>, extents: &mut Vec<u64>) {
+        self.scan(true, results, extents);
+    }
+
+    /// Report every match whose extent is settled and drop the window
+    /// entries that can no longer start one.
+    fn scan(&mut self, last: bool, results: &mut Vec<u64>, extents: &mut Vec<u64>) {
+        let n = self.needle.len();
+        // An open whitespace run may still grow, so its extent isn't final.
+        let settled = self.window.len() - usize::from(!last && self.in_ws);
+        let mut i = 0;
+        while i + n <= settled {
+            if self.window[i..i + n] == self.needle[..] {
+                if self.collapse_ws {
+                    results.push(self.starts[i]);
+                    extents.push(self.ends[i + n - 1] + 1 - self.starts[i]);
+                } else {
+                    results.push(self.base + i as u64);
+                    extents.push(n as u64);
+                }
+            }
+            i += 1;
+        }
+        self.window.drain(..i);
+        if self.collapse_ws {
+            self.starts.drain(..i);
+            self.ends.drain(..i);
+        } else {
+            self.base += i as u64;
+        }
+    }
+}
+
 /// Returns true if `s` contains at least one valid hex digit character.
 pub fn looks_like_hex(s: &str) -> bool {
     s.chars().any(|c| c.is_ascii_hexdigit())
@@ -778,4 +850,37 @@ mod tests {
         assert_eq!(s.count(), 1);
         assert_eq!(s.results[0], 0);
     }
+
+    #[test]
+    fn chunked_search_matches_across_boundaries() {
+        let data = b"ab \t\n cd ab cd\xDE\xAD ab  \ncd\xDE\xAD";
+        for (mode, query) in [
+            (SearchMode::Ascii, "ab cd"),
+            (SearchMode::Hex, "DE AD"),
+            (SearchMode::Decimal, "-21026"),
+        ] {
+            let mut whole = SearchState::new();
+            whole.mode = mode;
+            whole.width = 2;
+            whole.query = query.into();
+            whole.execute(data);
+            assert!(whole.count() >= 2, "{mode:?} should match twice");
+            // Every split point, including ones inside whitespace runs.
+            for split in 0..=data.len() {
+                let mut s = whole.clone();
+                s.execute_chunks([&data[..split], &data[split..]]);
+                assert_eq!(s.results, whole.results, "{mode:?} split at {split}");
+                assert_eq!(s.extents, whole.extents, "{mode:?} split at {split}");
+            }
+        }
+    }
+
+    #[test]
+    fn chunked_search_over_nothing_is_empty() {
+        let mut s = SearchState::new();
+        s.query = "AA".into();
+        s.execute_chunks(std::iter::empty());
+        assert_eq!(s.count(), 0);
+        assert_eq!(s.query_len, 0);
+    }
 }
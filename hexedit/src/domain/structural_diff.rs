@@ -0,0 +1,848 @@
+//! Alignment-aware binary diff.
+//!
+//! [`compute_diff`](super::vanilla_diff::compute_diff) compares bytes at the
+//! same offset, so a single inserted byte makes everything after it "differ".
+//! [`StructuralDiff`] aligns the two buffers first and describes the result as
+//! a list of [`DiffHunk`]s (equal / insert / delete / replace ranges).
+//!
+//! The alignment runs in three stages so it stays usable on files of tens of
+//! megabytes:
+//!
+//! 1. The common prefix and suffix are stripped with a plain scan.
+//! 2. The remaining middle is anchored with a rolling hash: the old side is
+//!    indexed in fixed [`BLOCK`]-byte blocks, the new side is scanned byte by
+//!    byte and every verified block hit is extended in both directions.
+//!    Anchors are kept monotonic, so the scan is linear in practice.
+//! 3. The gaps between anchors are aligned with a bounded Myers diff. Gaps
+//!    that are too large or too different fall back to a single replace hunk.
+//!
+//! Only [`StructuralDiff::compute`] needs both buffers whole. Updates after
+//! an edit and the changed-address queries read the ranges they touch
+//! through [`HexProvider`], so an edited piece table is never flattened.
+
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+use super::provider::HexProvider;
+
+/// Block size used for rolling-hash anchors. Matches shorter than this are
+/// only found inside gaps, by the Myers pass.
+const BLOCK: usize = 32;
+
+/// Gaps whose combined length exceeds this are not fed to Myers.
+const MYERS_MAX_INPUT: usize = 16 * 1024;
+
+/// Edit-distance budget for one Myers run.
+const MYERS_MAX_D: usize = 512;
+
+/// How many same-hash candidates to verify before giving up on a position.
+const MAX_CANDIDATES: usize = 8;
+
+/// Multiplier for the polynomial rolling hash (wrapping arithmetic).
+const HASH_BASE: u64 = 0x100_0000_01b3;
+
+/// What a hunk does to the old buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HunkKind {
+    /// Both ranges hold identical bytes.
+    Equal,
+    /// Bytes present only in the new buffer (`old` is empty).
+    Insert,
+    /// Bytes present only in the old buffer (`new` is empty).
+    Delete,
+    /// Old bytes replaced by different new bytes.
+    Replace,
+}
+
+/// One contiguous region of the alignment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub kind: HunkKind,
+    /// Byte range in the old (baseline) buffer.
+    pub old: Range<u64>,
+    /// Byte range in the new (comparison / current) buffer.
+    pub new: Range<u64>,
+}
+
+impl DiffHunk {
+    /// `true` for every hunk except [`HunkKind::Equal`].
+    pub fn is_change(&self) -> bool {
+        self.kind != HunkKind::Equal
+    }
+}
+
+/// Byte totals across all changed hunks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffSummary {
+    /// Number of non-equal hunks.
+    pub changes: usize,
+    /// Bytes only present in the new buffer.
+    pub inserted: u64,
+    /// Bytes only present in the old buffer.
+    pub deleted: u64,
+    /// Old bytes covered by replace hunks.
+    pub replaced: u64,
+}
+
+/// Aligned diff between an old and a new buffer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StructuralDiff {
+    hunks: Vec<DiffHunk>,
+    old_len: u64,
+    new_len: u64,
+}
+
+impl StructuralDiff {
+    /// Align `old` against `new`.
+    pub fn compute(old: &[u8], new: &[u8]) -> Self {
+        let mut builder = HunkBuilder::default();
+        align(old, new, 0..old.len(), 0..new.len(), &mut builder);
+        Self {
+            hunks: builder.hunks,
+            old_len: old.len() as u64,
+            new_len: new.len() as u64,
+        }
+    }
+
+    /// Alignment of two equal-length buffers compared position by position,
+    /// where `changed` holds every differing address.
+    pub fn positional(len: u64, changed: &BTreeSet<u64>) -> Self {
+        let mut builder = HunkBuilder::default();
+        let mut at = 0;
+        for &addr in changed.range(..len) {
+            let addr = addr as usize;
+            builder.push(HunkKind::Equal, at..addr, at..addr);
+            builder.push(HunkKind::Replace, addr..addr + 1, addr..addr + 1);
+            at = addr + 1;
+        }
+        let len = len as usize;
+        builder.push(HunkKind::Equal, at..len, at..len);
+        Self {
+            hunks: builder.hunks,
+            old_len: len as u64,
+            new_len: len as u64,
+        }
+    }
+
+    /// Re-align after an edit of the new buffer: the `removed` bytes that
+    /// started at `edit.start` are now the bytes in `edit`. Only the hunks
+    /// touching the edit are aligned again, and equal hunks are split at its
+    /// edges, so the cost follows the size of the surrounding change rather
+    /// than the file.
+    pub fn update(
+        &mut self,
+        old: &(impl HexProvider + ?Sized),
+        new: &(impl HexProvider + ?Sized),
+        edit: Range<u64>,
+        removed: u64,
+    ) {
+        if self.hunks.is_empty() {
+            *self = Self::compute(&old.read_range(0..old.len()), &new.read_range(0..new.len()));
+            return;
+        }
+        let removed_end = edit.start + removed;
+        let first = self
+            .hunks
+            .partition_point(|h| h.new.end < edit.start)
+            .min(self.hunks.len() - 1);
+        let last = self
+            .hunks
+            .partition_point(|h| h.new.start <= removed_end)
+            .saturating_sub(1)
+            .max(first);
+        // Split points inside equal hunks; change hunks are re-aligned whole.
+        let cut = |h: &DiffHunk, at: u64, at_end: bool| {
+            if h.kind == HunkKind::Equal {
+                let off = at.clamp(h.new.start, h.new.end) - h.new.start;
+                (h.old.start + off, h.new.start + off)
+            } else if at_end {
+                (h.old.end, h.new.end)
+            } else {
+                (h.old.start, h.new.start)
+            }
+        };
+        let (o0, n0) = cut(&self.hunks[first], edit.start, false);
+        let (o1, n1) = cut(&self.hunks[last], removed_end, true);
+        let shift = |at: u64| (at + edit.end).saturating_sub(removed_end) as usize;
+        let us = |r: &Range<u64>| r.start as usize..r.end as usize;
+
+        let mut builder = HunkBuilder::default();
+        for h in &self.hunks[..first] {
+            builder.push(h.kind, us(&h.old), us(&h.new));
+        }
+        let head = &self.hunks[first];
+        if head.kind == HunkKind::Equal {
+            builder.push(
+                HunkKind::Equal,
+                head.old.start as usize..o0 as usize,
+                head.new.start as usize..n0 as usize,
+            );
+        }
+        // Only the window being re-aligned is read.
+        let a = old.read_range(o0..o1);
+        let b = new.read_range(n0..shift(n1) as u64);
+        builder.base = (o0 as usize, n0 as usize);
+        align(&a, &b, 0..a.len(), 0..b.len(), &mut builder);
+        builder.base = (0, 0);
+        let tail = &self.hunks[last];
+        if tail.kind == HunkKind::Equal {
+            builder.push(
+                HunkKind::Equal,
+                o1 as usize..tail.old.end as usize,
+                shift(n1)..shift(tail.new.end),
+            );
+        }
+        for h in &self.hunks[last + 1..] {
+            builder.push(h.kind, us(&h.old), shift(h.new.start)..shift(h.new.end));
+        }
+        self.hunks = builder.hunks;
+        self.new_len = new.len();
+    }
+
+    /// All hunks in order, covering both buffers completely.
+    pub fn hunks(&self) -> &[DiffHunk] {
+        &self.hunks
+    }
+
+    /// Only the non-equal hunks.
+    pub fn changes(&self) -> impl Iterator<Item = &DiffHunk> {
+        self.hunks.iter().filter(|h| h.is_change())
+    }
+
+    /// `true` when both buffers are byte-identical.
+    pub fn is_identical(&self) -> bool {
+        self.hunks.iter().all(|h| !h.is_change())
+    }
+
+    pub fn summary(&self) -> DiffSummary {
+        let mut s = DiffSummary::default();
+        for h in self.changes() {
+            s.changes += 1;
+            match h.kind {
+                HunkKind::Insert => s.inserted += h.new.end - h.new.start,
+                HunkKind::Delete => s.deleted += h.old.end - h.old.start,
+                HunkKind::Replace => s.replaced += h.old.end - h.old.start,
+                HunkKind::Equal => {}
+            }
+        }
+        s
+    }
+
+    /// Addresses in the old buffer that belong to a change.
+    ///
+    /// Equal-length replacements only report the bytes that actually differ.
+    /// An insertion has no old bytes, so the byte at its insertion point is
+    /// reported instead to keep it visible.
+    pub fn old_changes(
+        &self,
+        old: &(impl HexProvider + ?Sized),
+        new: &(impl HexProvider + ?Sized),
+    ) -> BTreeSet<u64> {
+        collect_changes(&self.hunks, old, new, self.old_len, Side::Old)
+    }
+
+    /// Addresses in the new buffer that belong to a change. Mirror image of
+    /// [`old_changes`](Self::old_changes); deletions mark their join point.
+    pub fn new_changes(
+        &self,
+        old: &(impl HexProvider + ?Sized),
+        new: &(impl HexProvider + ?Sized),
+    ) -> BTreeSet<u64> {
+        collect_changes(&self.hunks, old, new, self.new_len, Side::New)
+    }
+
+    /// Offset in the new buffer aligned with `old_addr`, or `None` if that
+    /// byte was deleted. Addresses past the end of the old buffer continue
+    /// into a trailing insertion, so appended bytes stay reachable.
+    pub fn new_addr(&self, old_addr: u64) -> Option<u64> {
+        if old_addr >= self.old_len {
+            let tail_start = match self.hunks.last() {
+                Some(h) if h.kind == HunkKind::Insert => h.new.start,
+                _ => self.new_len,
+            };
+            let addr = tail_start + (old_addr - self.old_len);
+            return (addr < self.new_len).then_some(addr);
+        }
+        let idx = self.hunks.partition_point(|h| h.old.end <= old_addr);
+        let h = self.hunks.get(idx)?;
+        let offset = old_addr - h.old.start;
+        match h.kind {
+            HunkKind::Equal | HunkKind::Replace => {
+                let addr = h.new.start + offset;
+                (addr < h.new.end).then_some(addr)
+            }
+            HunkKind::Delete | HunkKind::Insert => None,
+        }
+    }
+
+    /// Old-side start of the first change strictly after `old_addr`.
+    pub fn next_change(&self, old_addr: u64) -> Option<u64> {
+        self.change_anchors().find(|&a| a > old_addr)
+    }
+
+    /// Old-side start of the last change strictly before `old_addr`.
+    pub fn prev_change(&self, old_addr: u64) -> Option<u64> {
+        self.change_anchors().filter(|&a| a < old_addr).last()
+    }
+
+    /// Old-side start of every change, clamped to the last valid address.
+    pub fn change_anchors(&self) -> impl Iterator<Item = u64> + '_ {
+        let max = self.old_len.saturating_sub(1);
+        self.changes().map(move |h| h.old.start.min(max))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Old,
+    New,
+}
+
+fn collect_changes(
+    hunks: &[DiffHunk],
+    old: &(impl HexProvider + ?Sized),
+    new: &(impl HexProvider + ?Sized),
+    len: u64,
+    side: Side,
+) -> BTreeSet<u64> {
+    let mut out = BTreeSet::new();
+    let marker = |at: u64| (len > 0).then(|| at.min(len - 1));
+    for h in hunks {
+        let (own, other) = match side {
+            Side::Old => (&h.old, &h.new),
+            Side::New => (&h.new, &h.old),
+        };
+        match h.kind {
+            HunkKind::Equal => {}
+            HunkKind::Replace if own.end - own.start == other.end - other.start => {
+                let (a, b) = (old.read_range(h.old.clone()), new.read_range(h.new.clone()));
+                for (i, (x, y)) in a.iter().zip(b.iter()).enumerate() {
+                    if x != y {
+                        out.insert(own.start + i as u64);
+                    }
+                }
+            }
+            _ if own.is_empty() => out.extend(marker(own.start)),
+            _ => out.extend(own.clone()),
+        }
+    }
+    out
+}
+
+/// Accumulates hunks, merging neighbours of the same kind and folding an
+/// adjacent delete/insert pair into a single replace.
+#[derive(Default)]
+struct HunkBuilder {
+    hunks: Vec<DiffHunk>,
+    /// Added to pushed ranges while aligning a window read out of the
+    /// buffers rather than the buffers themselves.
+    base: (usize, usize),
+}
+
+impl HunkBuilder {
+    fn push(&mut self, kind: HunkKind, old: Range<usize>, new: Range<usize>) {
+        if old.is_empty() && new.is_empty() {
+            return;
+        }
+        let (bo, bn) = self.base;
+        let old = (bo + old.start) as u64..(bo + old.end) as u64;
+        let new = (bn + new.start) as u64..(bn + new.end) as u64;
+        if let Some(last) = self.hunks.last_mut()
+            && last.old.end == old.start
+            && last.new.end == new.start
+        {
+            let merged = match (last.kind, kind) {
+                (HunkKind::Equal, HunkKind::Equal) => Some(HunkKind::Equal),
+                (HunkKind::Equal, _) | (_, HunkKind::Equal) => None,
+                (a, b) if a == b && a != HunkKind::Replace => Some(a),
+                _ => Some(HunkKind::Replace),
+            };
+            if let Some(kind) = merged {
+                last.kind = kind;
+                last.old.end = old.end;
+                last.new.end = new.end;
+                return;
+            }
+        }
+        self.hunks.push(DiffHunk { kind, old, new });
+    }
+
+    /// Push the non-equal region `old` × `new`.
+    fn push_change(&mut self, old: Range<usize>, new: Range<usize>) {
+        let kind = match (old.is_empty(), new.is_empty()) {
+            (true, true) => return,
+            (true, false) => HunkKind::Insert,
+            (false, true) => HunkKind::Delete,
+            (false, false) => HunkKind::Replace,
+        };
+        self.push(kind, old, new);
+    }
+}
+
+/// Align `old[o]` against `new[n]`: trim the common prefix and suffix, then
+/// align what is left in between.
+fn align(old: &[u8], new: &[u8], o: Range<usize>, n: Range<usize>, out: &mut HunkBuilder) {
+    let (a, b) = (&old[o.clone()], &new[n.clone()]);
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    out.push(
+        HunkKind::Equal,
+        o.start..o.start + prefix,
+        n.start..n.start + prefix,
+    );
+    let mid_old = o.start + prefix..o.end - suffix;
+    let mid_new = n.start + prefix..n.end - suffix;
+    if mid_old.len() + mid_new.len() <= MYERS_MAX_INPUT {
+        align_gap(old, new, mid_old, mid_new, out);
+    } else {
+        align_anchored(old, new, mid_old, mid_new, out);
+    }
+    out.push(
+        HunkKind::Equal,
+        o.end - suffix..o.end,
+        n.end - suffix..n.end,
+    );
+}
+
+/// Align a gap that is known to hold no long common run.
+fn align_gap(old: &[u8], new: &[u8], o: Range<usize>, n: Range<usize>, out: &mut HunkBuilder) {
+    if o.is_empty() || n.is_empty() || o.len() + n.len() > MYERS_MAX_INPUT {
+        out.push_change(o, n);
+        return;
+    }
+    let (a, b) = (&old[o.clone()], &new[n.clone()]);
+    // For equal lengths a positional replace costs two edits per differing
+    // byte; only accept an alignment that beats it.
+    let mut budget = MYERS_MAX_D;
+    if a.len() == b.len() {
+        let mismatches = a.iter().zip(b).filter(|(x, y)| x != y).count();
+        budget = budget.min((2 * mismatches).saturating_sub(1));
+    }
+    let Some(matches) = myers(a, b, budget) else {
+        out.push_change(o, n);
+        return;
+    };
+    let (mut x, mut y) = (0, 0);
+    for (mx, my, len) in matches {
+        out.push_change(o.start + x..o.start + mx, n.start + y..n.start + my);
+        out.push(
+            HunkKind::Equal,
+            o.start + mx..o.start + mx + len,
+            n.start + my..n.start + my + len,
+        );
+        (x, y) = (mx + len, my + len);
+    }
+    out.push_change(o.start + x..o.end, n.start + y..n.end);
+}
+
+/// Anchor a large middle section with rolling-hash block matches, then align
+/// the gaps between anchors.
+fn align_anchored(old: &[u8], new: &[u8], o: Range<usize>, n: Range<usize>, out: &mut HunkBuilder) {
+    let a = &old[o.clone()];
+    let b = &new[n.clone()];
+    if a.len() < BLOCK || b.len() < BLOCK {
+        out.push_change(o, n);
+        return;
+    }
+
+    // Index every aligned block of the old side. Positions are pushed in
+    // ascending order, so each bucket is already sorted.
+    let mut index: HashMap<u64, Vec<usize>> = HashMap::with_capacity(a.len() / BLOCK);
+    // Cheap membership pre-filter (~16 bits per block); most scanned
+    // positions miss it and never touch the hash map.
+    let filter_bits = (a.len() / BLOCK * 16)
+        .next_power_of_two()
+        .clamp(1 << 16, 1 << 30);
+    let mask = filter_bits - 1;
+    let mut filter = vec![0u64; filter_bits / 64];
+    for start in (0..=a.len() - BLOCK).step_by(BLOCK) {
+        let h = hash_block(&a[start..start + BLOCK]);
+        index.entry(h).or_default().push(start);
+        let bit = (h >> 32) as usize & mask;
+        filter[bit >> 6] |= 1 << (bit & 63);
+    }
+    let top = HASH_BASE.wrapping_pow(BLOCK as u32 - 1);
+
+    let (mut last_a, mut last_b) = (0usize, 0usize);
+    let mut j = 0usize;
+    let mut h = hash_block(&b[..BLOCK]);
+    while j + BLOCK <= b.len() {
+        let bit = (h >> 32) as usize & mask;
+        let hit = if filter[bit >> 6] & (1 << (bit & 63)) != 0 {
+            index.get(&h).and_then(|bucket| {
+                let first = bucket.partition_point(|&p| p < last_a);
+                bucket[first..]
+                    .iter()
+                    .take(MAX_CANDIDATES)
+                    .copied()
+                    .find(|&p| a[p..p + BLOCK] == b[j..j + BLOCK])
+            })
+        } else {
+            None
+        };
+
+        if let Some(p) = hit {
+            let (mut sa, mut sb) = (p, j);
+            while sa > last_a && sb > last_b && a[sa - 1] == b[sb - 1] {
+                sa -= 1;
+                sb -= 1;
+            }
+            let (mut ea, mut eb) = (p + BLOCK, j + BLOCK);
+            while ea < a.len() && eb < b.len() && a[ea] == b[eb] {
+                ea += 1;
+                eb += 1;
+            }
+            align_gap(
+                old,
+                new,
+                o.start + last_a..o.start + sa,
+                n.start + last_b..n.start + sb,
+                out,
+            );
+            out.push(
+                HunkKind::Equal,
+                o.start + sa..o.start + ea,
+                n.start + sb..n.start + eb,
+            );
+            (last_a, last_b) = (ea, eb);
+            j = eb;
+            if j + BLOCK <= b.len() {
+                h = hash_block(&b[j..j + BLOCK]);
+            }
+            continue;
+        }
+
+        if j + BLOCK < b.len() {
+            h = h
+                .wrapping_sub(u64::from(b[j]).wrapping_mul(top))
+                .wrapping_mul(HASH_BASE)
+                .wrapping_add(u64::from(b[j + BLOCK]));
+        }
+        j += 1;
+    }
+    align_gap(
+        old,
+        new,
+        o.start + last_a..o.end,
+        n.start + last_b..n.end,
+        out,
+    );
+}
+
+fn hash_block(block: &[u8]) -> u64 {
+    block.iter().fold(0u64, |h, &x| {
+        h.wrapping_mul(HASH_BASE).wrapping_add(u64::from(x))
+    })
+}
+
+/// Myers' O((N+M)·D) diff. Returns the matching runs as
+/// `(old_start, new_start, len)` in ascending order, or `None` when the edit
+/// distance exceeds `max_d`.
+fn myers(a: &[u8], b: &[u8], max_d: usize) -> Option<Vec<(usize, usize, usize)>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let limit = max_d.min(a.len() + b.len()) as isize;
+    let offset = limit + 1;
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    // trace[d] holds V[-d..=d] as it was before step d.
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    let mut found = None;
+
+    'outer: for d in 0..=limit {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let down =
+                k == -d || (k != d && v[(offset + k - 1) as usize] < v[(offset + k + 1) as usize]);
+            let mut x = if down {
+                v[(offset + k + 1) as usize]
+            } else {
+                v[(offset + k - 1) as usize] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[(offset + k) as usize] = x;
+            if x >= n && y >= m {
+                found = Some(d);
+                break 'outer;
+            }
+        }
+    }
+    let total = found?;
+
+    let mut runs = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (1..=total).rev() {
+        let snap = &trace[d as usize];
+        let at = |k: isize| snap[(k + d) as usize];
+        let k = x - y;
+        let down = k == -d || (k != d && at(k - 1) < at(k + 1));
+        let prev_k = if down { k + 1 } else { k - 1 };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        let (mid_x, mid_y) = if down {
+            (prev_x, prev_y + 1)
+        } else {
+            (prev_x + 1, prev_y)
+        };
+        if x > mid_x {
+            runs.push((mid_x as usize, mid_y as usize, (x - mid_x) as usize));
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    if x > 0 {
+        runs.push((0, 0, x as usize));
+    }
+    runs.reverse();
+    Some(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(d: &StructuralDiff) -> Vec<HunkKind> {
+        d.hunks().iter().map(|h| h.kind).collect()
+    }
+
+    /// Deterministic pseudo-random bytes (xorshift), so large tests do not
+    /// depend on repetitive data.
+    fn noise(len: usize, seed: u64) -> Vec<u8> {
+        let mut s = seed | 1;
+        (0..len)
+            .map(|_| {
+                s ^= s << 13;
+                s ^= s >> 7;
+                s ^= s << 17;
+                s as u8
+            })
+            .collect()
+    }
+
+    /// Every hunk must be contiguous with the previous one, cover both
+    /// buffers exactly and hold identical bytes when marked equal.
+    fn assert_consistent(d: &StructuralDiff, old: &[u8], new: &[u8]) {
+        let (mut o, mut n) = (0u64, 0u64);
+        for h in d.hunks() {
+            assert_eq!(
+                (h.old.start, h.new.start),
+                (o, n),
+                "hunks must be contiguous: {h:?}"
+            );
+            match h.kind {
+                HunkKind::Equal => assert_eq!(
+                    old[h.old.start as usize..h.old.end as usize],
+                    new[h.new.start as usize..h.new.end as usize]
+                ),
+                HunkKind::Insert => assert!(h.old.is_empty() && !h.new.is_empty()),
+                HunkKind::Delete => assert!(!h.old.is_empty() && h.new.is_empty()),
+                HunkKind::Replace => assert!(!h.old.is_empty() && !h.new.is_empty()),
+            }
+            (o, n) = (h.old.end, h.new.end);
+        }
+        assert_eq!((o, n), (old.len() as u64, new.len() as u64));
+    }
+
+    #[test]
+    fn identical_buffers_are_one_equal_hunk() {
+        let a = vec![1, 2, 3, 4];
+        let d = StructuralDiff::compute(&a, &a);
+        assert!(d.is_identical());
+        assert_eq!(kinds(&d), vec![HunkKind::Equal]);
+        assert!(StructuralDiff::compute(&[], &[]).hunks().is_empty());
+    }
+
+    #[test]
+    fn single_insert_does_not_mark_the_tail() {
+        let old = vec![0, 1, 2, 3, 4, 5];
+        let new = vec![0, 1, 2, 9, 3, 4, 5];
+        let d = StructuralDiff::compute(&old, &new);
+        assert_eq!(
+            d.hunks(),
+            &[
+                DiffHunk {
+                    kind: HunkKind::Equal,
+                    old: 0..3,
+                    new: 0..3
+                },
+                DiffHunk {
+                    kind: HunkKind::Insert,
+                    old: 3..3,
+                    new: 3..4
+                },
+                DiffHunk {
+                    kind: HunkKind::Equal,
+                    old: 3..6,
+                    new: 4..7
+                },
+            ]
+        );
+        assert_eq!(d.new_changes(&old[..], &new[..]), BTreeSet::from([3]));
+        assert_eq!(d.old_changes(&old[..], &new[..]), BTreeSet::from([3]));
+        assert_eq!(d.new_addr(3), Some(4));
+        assert_eq!(d.new_addr(5), Some(6));
+    }
+
+    #[test]
+    fn deletion_and_truncation() {
+        let old = vec![0, 1, 2, 3, 4, 5];
+        let new = vec![0, 1, 4, 5];
+        let d = StructuralDiff::compute(&old, &new);
+        assert_eq!(
+            kinds(&d),
+            vec![HunkKind::Equal, HunkKind::Delete, HunkKind::Equal]
+        );
+        assert_eq!(d.old_changes(&old[..], &new[..]), BTreeSet::from([2, 3]));
+        assert_eq!(d.new_addr(2), None);
+        assert_eq!(d.new_addr(4), Some(2));
+
+        let d = StructuralDiff::compute(&[0, 1, 2, 3], &[0, 1]);
+        assert_eq!(
+            d.hunks()[1],
+            DiffHunk {
+                kind: HunkKind::Delete,
+                old: 2..4,
+                new: 2..2
+            }
+        );
+        // The join point is clamped to the last byte of the shorter file.
+        assert_eq!(
+            d.new_changes(&[0u8, 1, 2, 3][..], &[0u8, 1][..]),
+            BTreeSet::from([1])
+        );
+    }
+
+    #[test]
+    fn equal_length_edits_stay_positional() {
+        let old = vec![0, 1, 2, 3, 4];
+        let new = vec![0, 9, 2, 8, 4];
+        let d = StructuralDiff::compute(&old, &new);
+        assert_consistent(&d, &old, &new);
+        assert_eq!(d.old_changes(&old[..], &new[..]), BTreeSet::from([1, 3]));
+        assert_eq!(d.new_changes(&old[..], &new[..]), BTreeSet::from([1, 3]));
+        assert_eq!(d.summary().inserted + d.summary().deleted, 0);
+    }
+
+    #[test]
+    fn appended_bytes_map_past_the_old_end() {
+        let old = vec![1, 2];
+        let new = vec![1, 2, 3, 4];
+        let d = StructuralDiff::compute(&old, &new);
+        assert_eq!(
+            d.summary(),
+            DiffSummary {
+                changes: 1,
+                inserted: 2,
+                deleted: 0,
+                replaced: 0
+            }
+        );
+        assert_eq!(d.new_addr(2), Some(2));
+        assert_eq!(d.new_addr(3), Some(3));
+        assert_eq!(d.new_addr(4), None);
+    }
+
+    #[test]
+    fn large_buffers_align_through_anchors() {
+        let old = noise(1 << 20, 7);
+        let mut new = old.clone();
+        new.splice(1000..1000, [0xAA; 17]);
+        new.drain(500_000..500_100);
+        new[900_000] ^= 0xFF;
+        new.splice(1_040_000..1_040_000, noise(40_000, 3));
+
+        let d = StructuralDiff::compute(&old, &new);
+        assert_consistent(&d, &old, &new);
+        let s = d.summary();
+        assert_eq!(s.changes, 4, "{:?}", d.changes().collect::<Vec<_>>());
+        assert_eq!(s.inserted, 17 + 40_000);
+        assert_eq!(s.deleted, 100);
+        assert_eq!(s.replaced, 1);
+        assert_eq!(d.next_change(0), Some(1000));
+        assert_eq!(d.prev_change(1000), None);
+        assert_eq!(d.new_addr(2000), Some(2017));
+    }
+
+    #[test]
+    fn unrelated_buffers_fall_back_to_replace() {
+        let old = noise(100_000, 1);
+        let new = noise(60_000, 2);
+        let d = StructuralDiff::compute(&old, &new);
+        assert_consistent(&d, &old, &new);
+        assert!(d.changes().count() >= 1);
+        assert_eq!(d.old_changes(&old[..], &new[..]).len(), old.len());
+    }
+
+    #[test]
+    fn update_realigns_only_around_edits() {
+        let old = noise(1 << 16, 5);
+        let mut new = old.clone();
+        new.splice(30_000..30_000, [0xAA; 8]);
+        let mut d = StructuralDiff::compute(&old, &new);
+
+        // Overwrite inside equal runs on both sides of the insert and at the end.
+        for at in [100usize, 40_000, new.len() - 1] {
+            new[at] ^= 0xFF;
+            d.update(&old[..], &new[..], at as u64..at as u64 + 1, 1);
+            assert_consistent(&d, &old, &new);
+        }
+        assert_eq!(d.summary().inserted, 8);
+        assert_eq!(d.summary().replaced, 3);
+        assert_eq!(
+            d.new_changes(&old[..], &new[..]),
+            StructuralDiff::compute(&old[..], &new[..]).new_changes(&old[..], &new[..])
+        );
+
+        // Writing over the inserted bytes keeps them marked.
+        new[30_003] = 0;
+        d.update(&old[..], &new[..], 30_003..30_004, 1);
+        assert_consistent(&d, &old, &new);
+        assert!(
+            d.new_changes(&old[..], &new[..])
+                .is_superset(&(30_000..30_008).collect())
+        );
+
+        // Insert and append.
+        new.splice(10..10, [1, 2, 3]);
+        d.update(&old[..], &new[..], 10..13, 0);
+        assert_consistent(&d, &old, &new);
+        let end = new.len() as u64;
+        new.extend([9, 9]);
+        d.update(&old[..], &new[..], end..end + 2, 0);
+        assert_consistent(&d, &old, &new);
+        let marked = d.new_changes(&old[..], &new[..]);
+        assert!((10..13).chain(end..end + 2).all(|a| marked.contains(&a)));
+    }
+
+    #[test]
+    fn positional_matches_a_byte_scan() {
+        let old = vec![1, 2, 3, 4, 5];
+        let mut new = old.clone();
+        new[1] = 9;
+        new[2] = 9;
+        let changed = BTreeSet::from([1, 2]);
+        let mut d = StructuralDiff::positional(5, &changed);
+        assert_consistent(&d, &old, &new);
+        assert_eq!(d.new_changes(&old[..], &new[..]), changed);
+
+        new.insert(4, 7);
+        d.update(&old[..], &new[..], 4..5, 0);
+        assert_consistent(&d, &old, &new);
+        assert_eq!(d.new_changes(&old[..], &new[..]), BTreeSet::from([1, 2, 4]));
+    }
+
+    #[test]
+    fn myers_finds_shortest_script() {
+        let runs = myers(b"abcabba", b"cbabac", 16).unwrap();
+        let matched: usize = runs.iter().map(|r| r.2).sum();
+        assert_eq!(matched, 4, "LCS of the classic example has length 4");
+        assert!(myers(b"abcd", b"wxyz", 3).is_none());
+    }
+}
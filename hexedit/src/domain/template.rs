@@ -0,0 +1,1822 @@
+//! Declarative binary templates evaluated into a [`BinaryLayout`].
+//!
+//! Templates use a small ImHex/010-style struct language, so file formats can
+//! be mapped without recompiling the host application:
+//!
+//! ```text
+//! // Store.db
+//! enum ProductType : i16 { None, Weapon, Healing, EditItem, MiscItem }
+//!
+//! struct Product {
+//!     ProductType kind;
+//!     i16 item_id;
+//! }
+//!
+//! struct Store {
+//!     char store_name[32] [[encoding("windows-1250")]];
+//!     i32 inn_night_cost;
+//!     if (inn_night_cost > 0) {
+//!         padding[144];
+//!     } else {
+//!         i16 price_modifier;
+//!         Product products[15];
+//!         padding[82];
+//!     }
+//!     char invitation[512] [[encoding("windows-1250")]];
+//! }
+//!
+//! i32 record_count;
+//! Store stores[record_count];
+//! ```
+//!
+//! - Primitives: `u8 i8 u16 i16 u32 i32 u64 i64 f32 f64 bool char`, plus
+//!   `cstring` (NUL-terminated) and `padding[n]`. Values are little-endian
+//!   unless the declaration is prefixed with `be`.
+//! - `T name[expr]` declares an array sized by any expression over earlier
+//!   fields (`header.count`, `width * height`, `items[0].kind`); `T name[]`
+//!   repeats until the end of the file. `char` arrays decode as strings in
+//!   the encoding named by `[[encoding("…")]]` (UTF-8 by default).
+//! - `T name @ expr` places a field at an absolute offset; reading continues
+//!   after it. `$` is the current offset.
+//! - `if (…) { … } else if (…) { … } else { … }` works at top level and in
+//!   struct bodies; enum constants are written `Kind::Variant`.
+//!
+//! Every struct, array and field becomes one outline node; struct array
+//! elements share the field name and carry their index in `record_index`.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use encoding_rs::Encoding;
+use once_cell::sync::Lazy;
+
+use super::layout::{BinaryLayout, FieldSpan, LayoutOutlineItem};
+use super::provider::HexProvider;
+
+/// Evaluation stops with an error past this many outline nodes.
+const MAX_NODES: usize = 1 << 20;
+/// Maximum struct nesting, which also bounds self-referencing structs.
+const MAX_DEPTH: usize = 64;
+/// `record_type` of fields declared at the top level of a template.
+const TOP_LEVEL: &str = "template";
+
+/// Outline and field names are `&'static str`; the identifiers declared by a
+/// template are interned so each distinct name is allocated once per process.
+/// Generated labels (`u8[16]`) are owned by the layout instead.
+fn intern(s: &str) -> &'static str {
+    static NAMES: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+    let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(name) = names.get(s) {
+        return name;
+    }
+    let name: &'static str = Box::leak(s.to_owned().into_boxed_str());
+    names.insert(name);
+    name
+}
+
+// ── Errors ────────────────────────────────────────────────────────────────
+
+/// A template parse or evaluation error. `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl TemplateError {
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+type Result<T> = std::result::Result<T, TemplateError>;
+
+// ── Lexer ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(String),
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Punct(&'static str),
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    tok: Tok,
+    line: usize,
+}
+
+/// Longest operators first so `::` wins over `:`.
+const PUNCTS: &[&str] = &[
+    "::", "==", "!=", "<=", ">=", "&&", "||", "<<", ">>", "{", "}", "(", ")", "[", "]", ";", ":",
+    ",", "=", "@", ".", "+", "-", "*", "/", "%", "<", ">", "!", "~", "&", "|", "^", "$",
+];
+
+fn lex(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let start = line;
+            i += 2;
+            loop {
+                match chars.get(i) {
+                    None => return Err(TemplateError::at(start, "unterminated comment")),
+                    Some('*') if chars.get(i + 1) == Some(&'/') => {
+                        i += 2;
+                        break;
+                    }
+                    Some('\n') => line += 1,
+                    Some(_) => {}
+                }
+                i += 1;
+            }
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let is_float = chars.get(i) == Some(&'.')
+                && chars.get(i + 1).is_some_and(char::is_ascii_digit)
+                && !chars[start..i].iter().any(|c| c.is_ascii_alphabetic());
+            if is_float {
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            let tok = if is_float {
+                text.parse().map(Tok::Float).ok()
+            } else if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+                i128::from_str_radix(hex, 16).map(Tok::Int).ok()
+            } else if let Some(bin) = text.strip_prefix("0b").or(text.strip_prefix("0B")) {
+                i128::from_str_radix(bin, 2).map(Tok::Int).ok()
+            } else {
+                text.parse().map(Tok::Int).ok()
+            };
+            let tok = tok.ok_or_else(|| TemplateError::at(line, format!("bad number `{text}`")))?;
+            tokens.push(Token { tok, line });
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token {
+                tok: Tok::Ident(chars[start..i].iter().collect()),
+                line,
+            });
+        } else if c == '"' {
+            let start = line;
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None | Some('\n') => {
+                        return Err(TemplateError::at(start, "unterminated string"));
+                    }
+                    Some('"') => break,
+                    Some('\\') => {
+                        i += 1;
+                        text.push(match chars.get(i) {
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some('0') => '\0',
+                            Some(other) => *other,
+                            None => return Err(TemplateError::at(start, "unterminated string")),
+                        });
+                    }
+                    Some(other) => text.push(*other),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token {
+                tok: Tok::Str(text),
+                line,
+            });
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let punct = PUNCTS
+                .iter()
+                .find(|p| rest.starts_with(**p))
+                .ok_or_else(|| TemplateError::at(line, format!("unexpected character `{c}`")))?;
+            i += punct.chars().count();
+            tokens.push(Token {
+                tok: Tok::Punct(punct),
+                line,
+            });
+        }
+    }
+    tokens.push(Token {
+        tok: Tok::Eof,
+        line,
+    });
+    Ok(tokens)
+}
+
+// ── Syntax tree ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prim {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F32,
+    F64,
+    Bool,
+    Char,
+}
+
+impl Prim {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "u8" => Self::U8,
+            "i8" => Self::I8,
+            "u16" => Self::U16,
+            "i16" => Self::I16,
+            "u32" => Self::U32,
+            "i32" => Self::I32,
+            "u64" => Self::U64,
+            "i64" => Self::I64,
+            "f32" => Self::F32,
+            "f64" => Self::F64,
+            "bool" => Self::Bool,
+            "char" => Self::Char,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::U8 => "u8",
+            Self::I8 => "i8",
+            Self::U16 => "u16",
+            Self::I16 => "i16",
+            Self::U32 => "u32",
+            Self::I32 => "i32",
+            Self::U64 => "u64",
+            Self::I64 => "i64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::Bool => "bool",
+            Self::Char => "char",
+        }
+    }
+
+    fn size(self) -> u64 {
+        match self {
+            Self::U8 | Self::I8 | Self::Bool | Self::Char => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+        }
+    }
+
+    fn is_integer(self) -> bool {
+        !matches!(self, Self::F32 | Self::F64)
+    }
+
+    /// Decode one value from exactly `self.size()` bytes.
+    fn read(self, bytes: &[u8], big_endian: bool) -> Value {
+        let mut buf = [0u8; 8];
+        let n = bytes.len();
+        buf[..n].copy_from_slice(bytes);
+        if big_endian {
+            buf[..n].reverse();
+        }
+        let raw = u64::from_le_bytes(buf);
+        match self {
+            Self::U8 | Self::U16 | Self::U32 | Self::U64 | Self::Bool | Self::Char => {
+                Value::Int(i128::from(raw))
+            }
+            Self::I8 => Value::Int(i128::from(raw as u8 as i8)),
+            Self::I16 => Value::Int(i128::from(raw as u16 as i16)),
+            Self::I32 => Value::Int(i128::from(raw as u32 as i32)),
+            Self::I64 => Value::Int(i128::from(raw as i64)),
+            Self::F32 => Value::Float(f64::from(f32::from_bits(raw as u32))),
+            Self::F64 => Value::Float(f64::from_bits(raw)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TypeRef {
+    Prim(Prim),
+    CString,
+    Padding,
+    Enum(usize),
+    Struct(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Count {
+    One,
+    Expr(Expr),
+    ToEnd,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    line: usize,
+    ty: TypeRef,
+    ty_name: &'static str,
+    big_endian: bool,
+    name: &'static str,
+    count: Count,
+    at: Option<Expr>,
+    encoding: &'static Encoding,
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Field(Field),
+    If {
+        branches: Vec<(Expr, Vec<Stmt>)>,
+        otherwise: Vec<Stmt>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct StructDef {
+    name: &'static str,
+    body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone)]
+struct EnumDef {
+    name: &'static str,
+    repr: Prim,
+    variants: Vec<(String, i128)>,
+}
+
+impl EnumDef {
+    fn variant(&self, value: i128) -> Option<&str> {
+        self.variants
+            .iter()
+            .find(|(_, v)| *v == value)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    name: String,
+    index: Option<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shl,
+    Shr,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnOp {
+    Neg,
+    Not,
+    BitNot,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Value(Value),
+    Offset,
+    Path(Vec<Segment>),
+    Unary(UnOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+/// Binary operators from loosest to tightest binding.
+const PRECEDENCE: &[&[(&str, BinOp)]] = &[
+    &[("||", BinOp::Or)],
+    &[("&&", BinOp::And)],
+    &[("==", BinOp::Eq), ("!=", BinOp::Ne)],
+    &[
+        ("<", BinOp::Lt),
+        ("<=", BinOp::Le),
+        (">", BinOp::Gt),
+        (">=", BinOp::Ge),
+    ],
+    &[("|", BinOp::BitOr)],
+    &[("^", BinOp::BitXor)],
+    &[("&", BinOp::BitAnd)],
+    &[("<<", BinOp::Shl), (">>", BinOp::Shr)],
+    &[("+", BinOp::Add), ("-", BinOp::Sub)],
+    &[("*", BinOp::Mul), ("/", BinOp::Div), ("%", BinOp::Rem)],
+];
+
+// ── Parser ────────────────────────────────────────────────────────────────
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    structs: Vec<StructDef>,
+    enums: Vec<EnumDef>,
+    types: HashMap<String, TypeRef>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Tok {
+        &self.tokens[self.pos].tok
+    }
+
+    fn peek_at(&self, offset: usize) -> &Tok {
+        let index = (self.pos + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].tok
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.pos].line
+    }
+
+    fn next(&mut self) -> Tok {
+        let tok = self.tokens[self.pos].tok.clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Tok::Punct(p) if *p == punct)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let found = self.is_punct(punct);
+        if found {
+            self.next();
+        }
+        found
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Tok::Ident(name) if name == keyword)
+    }
+
+    fn error(&self, expected: &str) -> TemplateError {
+        let found = match self.peek() {
+            Tok::Ident(name) => format!("`{name}`"),
+            Tok::Int(v) => v.to_string(),
+            Tok::Float(v) => v.to_string(),
+            Tok::Str(s) => format!("{s:?}"),
+            Tok::Punct(p) => format!("`{p}`"),
+            Tok::Eof => "end of template".to_string(),
+        };
+        TemplateError::at(self.line(), format!("expected {expected}, found {found}"))
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("`{punct}`")))
+        }
+    }
+
+    fn expect_ident(&mut self, what: &str) -> Result<String> {
+        match self.peek() {
+            Tok::Ident(name) => {
+                let name = name.clone();
+                self.next();
+                Ok(name)
+            }
+            _ => Err(self.error(what)),
+        }
+    }
+
+    fn declare(&mut self, name: &str, ty: TypeRef) -> Result<()> {
+        if Prim::from_name(name).is_some()
+            || matches!(name, "cstring" | "padding")
+            || self.types.contains_key(name)
+        {
+            return Err(TemplateError::at(
+                self.line(),
+                format!("type `{name}` is already defined"),
+            ));
+        }
+        self.types.insert(name.to_owned(), ty);
+        Ok(())
+    }
+
+    fn program(&mut self) -> Result<Vec<Stmt>> {
+        let mut body = Vec::new();
+        while *self.peek() != Tok::Eof {
+            if self.is_keyword("struct") {
+                self.struct_def()?;
+            } else if self.is_keyword("enum") {
+                self.enum_def()?;
+            } else {
+                body.push(self.stmt()?);
+            }
+        }
+        Ok(body)
+    }
+
+    fn struct_def(&mut self) -> Result<()> {
+        self.next();
+        let name = self.expect_ident("struct name")?;
+        let id = self.structs.len();
+        // Registered before the body so a struct may contain itself under a
+        // condition (linked chunks).
+        self.declare(&name, TypeRef::Struct(id))?;
+        self.structs.push(StructDef {
+            name: intern(&name),
+            body: Vec::new(),
+        });
+        self.expect_punct("{")?;
+        let mut body = Vec::new();
+        while !self.eat_punct("}") {
+            body.push(self.stmt()?);
+        }
+        self.eat_punct(";");
+        self.structs[id].body = body;
+        Ok(())
+    }
+
+    fn enum_def(&mut self) -> Result<()> {
+        self.next();
+        let name = self.expect_ident("enum name")?;
+        self.expect_punct(":")?;
+        let repr_line = self.line();
+        let repr_name = self.expect_ident("enum base type")?;
+        let repr = Prim::from_name(&repr_name)
+            .filter(|p| p.is_integer())
+            .ok_or_else(|| {
+                TemplateError::at(
+                    repr_line,
+                    format!("enum base type must be an integer, found `{repr_name}`"),
+                )
+            })?;
+        self.expect_punct("{")?;
+        let mut variants: Vec<(String, i128)> = Vec::new();
+        let mut next_value = 0;
+        while !self.eat_punct("}") {
+            let variant = self.expect_ident("enum variant")?;
+            if self.eat_punct("=") {
+                let line = self.line();
+                let expr = self.expr()?;
+                next_value = match const_eval(&expr) {
+                    Some(Value::Int(v)) => v,
+                    _ => {
+                        return Err(TemplateError::at(
+                            line,
+                            "enum values must be integer constants",
+                        ));
+                    }
+                };
+            }
+            variants.push((variant, next_value));
+            next_value += 1;
+            if !self.eat_punct(",") {
+                self.expect_punct("}")?;
+                break;
+            }
+        }
+        self.eat_punct(";");
+        self.declare(&name, TypeRef::Enum(self.enums.len()))?;
+        self.enums.push(EnumDef {
+            name: intern(&name),
+            repr,
+            variants,
+        });
+        Ok(())
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>> {
+        if self.eat_punct("{") {
+            let mut body = Vec::new();
+            while !self.eat_punct("}") {
+                body.push(self.stmt()?);
+            }
+            Ok(body)
+        } else {
+            Ok(vec![self.stmt()?])
+        }
+    }
+
+    fn stmt(&mut self) -> Result<Stmt> {
+        if self.is_keyword("if") {
+            return self.if_stmt();
+        }
+        self.field().map(Stmt::Field)
+    }
+
+    fn if_stmt(&mut self) -> Result<Stmt> {
+        let mut branches = Vec::new();
+        let mut otherwise = Vec::new();
+        loop {
+            self.next();
+            self.expect_punct("(")?;
+            let cond = self.expr()?;
+            self.expect_punct(")")?;
+            branches.push((cond, self.block()?));
+            if !self.is_keyword("else") {
+                break;
+            }
+            self.next();
+            if !self.is_keyword("if") {
+                otherwise = self.block()?;
+                break;
+            }
+        }
+        Ok(Stmt::If {
+            branches,
+            otherwise,
+        })
+    }
+
+    fn field(&mut self) -> Result<Field> {
+        let line = self.line();
+        let mut big_endian = false;
+        if matches!(self.peek(), Tok::Ident(k) if k == "be" || k == "le")
+            && matches!(self.peek_at(1), Tok::Ident(_))
+        {
+            big_endian = self.expect_ident("endianness")? == "be";
+        }
+        let type_name = self.expect_ident("a type or `if`")?;
+
+        if type_name == "padding" && self.is_punct("[") {
+            self.next();
+            let size = self.expr()?;
+            self.expect_punct("]")?;
+            self.expect_punct(";")?;
+            return Ok(Field {
+                line,
+                ty: TypeRef::Padding,
+                ty_name: "padding",
+                big_endian,
+                name: "padding",
+                count: Count::Expr(size),
+                at: None,
+                encoding: encoding_rs::UTF_8,
+            });
+        }
+
+        let (ty, ty_name) = if let Some(prim) = Prim::from_name(&type_name) {
+            (TypeRef::Prim(prim), prim.name())
+        } else if type_name == "cstring" {
+            (TypeRef::CString, "cstring")
+        } else {
+            match self.types.get(&type_name) {
+                Some(ty) => (*ty, intern(&type_name)),
+                None => {
+                    return Err(TemplateError::at(
+                        line,
+                        format!("unknown type `{type_name}`"),
+                    ));
+                }
+            }
+        };
+
+        let name = self.expect_ident("field name")?;
+        let count = if self.eat_punct("[") {
+            if self.eat_punct("]") {
+                Count::ToEnd
+            } else {
+                let count = self.expr()?;
+                self.expect_punct("]")?;
+                Count::Expr(count)
+            }
+        } else {
+            Count::One
+        };
+        let at = if self.eat_punct("@") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        let mut encoding = encoding_rs::UTF_8;
+        if self.is_punct("[") && matches!(self.peek_at(1), Tok::Punct("[")) {
+            self.next();
+            self.next();
+            loop {
+                let attr_line = self.line();
+                let attr = self.expect_ident("attribute")?;
+                self.expect_punct("(")?;
+                let arg = match self.next() {
+                    Tok::Str(s) => s,
+                    _ => return Err(TemplateError::at(attr_line, "expected a string argument")),
+                };
+                self.expect_punct(")")?;
+                match attr.as_str() {
+                    "encoding" => {
+                        encoding = Encoding::for_label(arg.as_bytes()).ok_or_else(|| {
+                            TemplateError::at(attr_line, format!("unknown encoding `{arg}`"))
+                        })?;
+                    }
+                    _ => {
+                        return Err(TemplateError::at(
+                            attr_line,
+                            format!("unknown attribute `{attr}`"),
+                        ));
+                    }
+                }
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+            self.expect_punct("]")?;
+            self.expect_punct("]")?;
+        }
+        self.expect_punct(";")?;
+
+        Ok(Field {
+            line,
+            ty,
+            ty_name,
+            big_endian,
+            name: intern(&name),
+            count,
+            at,
+            encoding,
+        })
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        self.binary(0)
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr> {
+        let Some(ops) = PRECEDENCE.get(level) else {
+            return self.unary();
+        };
+        let mut left = self.binary(level + 1)?;
+        loop {
+            let op = match self.peek() {
+                Tok::Punct(p) => ops.iter().find(|(s, _)| s == p).map(|(_, op)| *op),
+                _ => None,
+            };
+            let Some(op) = op else {
+                return Ok(left);
+            };
+            self.next();
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        let op = if self.eat_punct("-") {
+            Some(UnOp::Neg)
+        } else if self.eat_punct("!") {
+            Some(UnOp::Not)
+        } else if self.eat_punct("~") {
+            Some(UnOp::BitNot)
+        } else {
+            None
+        };
+        match op {
+            Some(op) => Ok(Expr::Unary(op, Box::new(self.unary()?))),
+            None => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let line = self.line();
+        match self.peek().clone() {
+            Tok::Int(v) => {
+                self.next();
+                Ok(Expr::Value(Value::Int(v)))
+            }
+            Tok::Float(v) => {
+                self.next();
+                Ok(Expr::Value(Value::Float(v)))
+            }
+            Tok::Str(s) => {
+                self.next();
+                Ok(Expr::Value(Value::Str(s)))
+            }
+            Tok::Punct("$") => {
+                self.next();
+                Ok(Expr::Offset)
+            }
+            Tok::Punct("(") => {
+                self.next();
+                let expr = self.expr()?;
+                self.expect_punct(")")?;
+                Ok(expr)
+            }
+            Tok::Ident(name) if matches!(name.as_str(), "true" | "false") => {
+                self.next();
+                Ok(Expr::Value(Value::Int(i128::from(name == "true"))))
+            }
+            Tok::Ident(name) if matches!(self.peek_at(1), Tok::Punct("::")) => {
+                self.next();
+                self.next();
+                let variant = self.expect_ident("enum variant")?;
+                let Some(TypeRef::Enum(id)) = self.types.get(&name) else {
+                    return Err(TemplateError::at(line, format!("unknown enum `{name}`")));
+                };
+                let def = &self.enums[*id];
+                def.variants
+                    .iter()
+                    .find(|(v, _)| *v == variant)
+                    .map(|(_, value)| Expr::Value(Value::Int(*value)))
+                    .ok_or_else(|| {
+                        TemplateError::at(line, format!("enum `{name}` has no variant `{variant}`"))
+                    })
+            }
+            Tok::Ident(_) => {
+                let mut segments = Vec::new();
+                loop {
+                    let name = self.expect_ident("field name")?;
+                    let index = if self.eat_punct("[") {
+                        let index = self.expr()?;
+                        self.expect_punct("]")?;
+                        Some(index)
+                    } else {
+                        None
+                    };
+                    segments.push(Segment { name, index });
+                    if !self.eat_punct(".") {
+                        break;
+                    }
+                }
+                Ok(Expr::Path(segments))
+            }
+            _ => Err(self.error("an expression")),
+        }
+    }
+}
+
+/// Fold an expression made only of literals (enum values).
+fn const_eval(expr: &Expr) -> Option<Value> {
+    match expr {
+        Expr::Value(v) => Some(v.clone()),
+        Expr::Unary(op, inner) => unary(*op, const_eval(inner)?).ok(),
+        Expr::Binary(op, l, r) => binary(*op, const_eval(l)?, const_eval(r)?).ok(),
+        Expr::Offset | Expr::Path(_) => None,
+    }
+}
+
+// ── Values ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i128),
+    Float(f64),
+    Str(String),
+}
+
+impl Value {
+    fn truthy(&self) -> bool {
+        match self {
+            Self::Int(v) => *v != 0,
+            Self::Float(v) => *v != 0.0,
+            Self::Str(s) => !s.is_empty(),
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Int(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v),
+            Self::Str(_) => None,
+        }
+    }
+}
+
+fn unary(op: UnOp, value: Value) -> std::result::Result<Value, String> {
+    match (op, value) {
+        (UnOp::Not, v) => Ok(Value::Int(i128::from(!v.truthy()))),
+        (UnOp::Neg, Value::Int(v)) => Ok(Value::Int(-v)),
+        (UnOp::Neg, Value::Float(v)) => Ok(Value::Float(-v)),
+        (UnOp::BitNot, Value::Int(v)) => Ok(Value::Int(!v)),
+        (op, v) => Err(format!("cannot apply {op:?} to {v:?}")),
+    }
+}
+
+fn binary(op: BinOp, left: Value, right: Value) -> std::result::Result<Value, String> {
+    let bool_value = |b: bool| Value::Int(i128::from(b));
+    match (&left, &right) {
+        (Value::Int(a), Value::Int(b)) => {
+            let (a, b) = (*a, *b);
+            let overflow = || format!("arithmetic overflow in {a} {op:?} {b}");
+            Ok(match op {
+                BinOp::Or => bool_value(a != 0 || b != 0),
+                BinOp::And => bool_value(a != 0 && b != 0),
+                BinOp::Eq => bool_value(a == b),
+                BinOp::Ne => bool_value(a != b),
+                BinOp::Lt => bool_value(a < b),
+                BinOp::Le => bool_value(a <= b),
+                BinOp::Gt => bool_value(a > b),
+                BinOp::Ge => bool_value(a >= b),
+                BinOp::BitOr => Value::Int(a | b),
+                BinOp::BitXor => Value::Int(a ^ b),
+                BinOp::BitAnd => Value::Int(a & b),
+                BinOp::Shl => Value::Int(
+                    u32::try_from(b)
+                        .ok()
+                        .and_then(|b| a.checked_shl(b))
+                        .ok_or_else(overflow)?,
+                ),
+                BinOp::Shr => Value::Int(
+                    u32::try_from(b)
+                        .ok()
+                        .and_then(|b| a.checked_shr(b))
+                        .ok_or_else(overflow)?,
+                ),
+                BinOp::Add => Value::Int(a.checked_add(b).ok_or_else(overflow)?),
+                BinOp::Sub => Value::Int(a.checked_sub(b).ok_or_else(overflow)?),
+                BinOp::Mul => Value::Int(a.checked_mul(b).ok_or_else(overflow)?),
+                BinOp::Div if b == 0 => return Err("division by zero".to_string()),
+                BinOp::Div => Value::Int(a / b),
+                BinOp::Rem if b == 0 => return Err("division by zero".to_string()),
+                BinOp::Rem => Value::Int(a % b),
+            })
+        }
+        (Value::Str(a), Value::Str(b)) => match op {
+            BinOp::Eq => Ok(bool_value(a == b)),
+            BinOp::Ne => Ok(bool_value(a != b)),
+            _ => Err(format!("cannot apply {op:?} to strings")),
+        },
+        _ => {
+            let (Some(a), Some(b)) = (left.as_float(), right.as_float()) else {
+                return Err(format!("cannot compare {left:?} with {right:?}"));
+            };
+            Ok(match op {
+                BinOp::Or => bool_value(a != 0.0 || b != 0.0),
+                BinOp::And => bool_value(a != 0.0 && b != 0.0),
+                BinOp::Eq => bool_value(a == b),
+                BinOp::Ne => bool_value(a != b),
+                BinOp::Lt => bool_value(a < b),
+                BinOp::Le => bool_value(a <= b),
+                BinOp::Gt => bool_value(a > b),
+                BinOp::Ge => bool_value(a >= b),
+                BinOp::Add => Value::Float(a + b),
+                BinOp::Sub => Value::Float(a - b),
+                BinOp::Mul => Value::Float(a * b),
+                BinOp::Div => Value::Float(a / b),
+                BinOp::Rem => Value::Float(a % b),
+                _ => return Err(format!("{op:?} needs integer operands")),
+            })
+        }
+    }
+}
+
+// ── Template ──────────────────────────────────────────────────────────────
+
+/// A parsed template, ready to be evaluated against file contents.
+#[derive(Debug, Clone)]
+pub struct BinaryTemplate {
+    body: Vec<Stmt>,
+    structs: Vec<StructDef>,
+    enums: Vec<EnumDef>,
+}
+
+impl BinaryTemplate {
+    pub fn parse(source: &str) -> std::result::Result<Self, TemplateError> {
+        let mut parser = Parser {
+            tokens: lex(source)?,
+            pos: 0,
+            structs: Vec::new(),
+            enums: Vec::new(),
+            types: HashMap::new(),
+        };
+        let body = parser.program()?;
+        Ok(Self {
+            body,
+            structs: parser.structs,
+            enums: parser.enums,
+        })
+    }
+
+    pub fn load(path: &Path) -> std::result::Result<Self, TemplateError> {
+        let source = std::fs::read_to_string(path).map_err(|e| TemplateError {
+            line: None,
+            message: format!("cannot read '{}': {e}", path.display()),
+        })?;
+        Self::parse(&source).map_err(|e| TemplateError {
+            line: e.line,
+            message: format!("{}: {}", path.display(), e.message),
+        })
+    }
+
+    /// Run the template over `data` and build the resulting layout. Only
+    /// the ranges the template declares are read.
+    pub fn evaluate(
+        &self,
+        data: &(impl HexProvider + ?Sized),
+    ) -> std::result::Result<TemplateBinaryLayout, TemplateError> {
+        let mut eval = Evaluator {
+            template: self,
+            data,
+            nodes: Vec::new(),
+            frames: vec![Frame {
+                scope: Scope::default(),
+                cursor: 0,
+                type_name: TOP_LEVEL,
+                record_index: 0,
+                depth: 0,
+                field_no: 0,
+            }],
+        };
+        eval.block(&self.body)?;
+        Ok(TemplateBinaryLayout::new(eval.nodes))
+    }
+}
+
+// ── Evaluation ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+enum Slot {
+    Value(Value),
+    Struct(Rc<Scope>),
+    Structs(Rc<Vec<Rc<Scope>>>),
+    Prims {
+        start: u64,
+        prim: Prim,
+        big_endian: bool,
+        count: u64,
+    },
+}
+
+#[derive(Debug, Default)]
+struct Scope {
+    fields: Vec<(&'static str, Slot)>,
+}
+
+impl Scope {
+    fn get(&self, name: &str) -> Option<&Slot> {
+        self.fields
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name)
+            .map(|(_, slot)| slot)
+    }
+}
+
+struct Frame {
+    scope: Scope,
+    cursor: u64,
+    type_name: &'static str,
+    record_index: u64,
+    depth: u8,
+    field_no: u8,
+}
+
+struct Evaluator<'a, P: ?Sized> {
+    template: &'a BinaryTemplate,
+    data: &'a P,
+    nodes: Vec<TemplateNode>,
+    frames: Vec<Frame>,
+}
+
+impl<P: HexProvider + ?Sized> Evaluator<'_, P> {
+    fn frame(&mut self) -> &mut Frame {
+        self.frames
+            .last_mut()
+            .expect("evaluator always has a frame")
+    }
+
+    fn block(&mut self, body: &[Stmt]) -> Result<()> {
+        for stmt in body {
+            match stmt {
+                Stmt::Field(field) => self.field(field)?,
+                Stmt::If {
+                    branches,
+                    otherwise,
+                } => {
+                    let mut taken = None;
+                    for (cond, branch) in branches {
+                        if self.eval(cond, 0)?.truthy() {
+                            taken = Some(branch);
+                            break;
+                        }
+                    }
+                    self.block(taken.unwrap_or(otherwise))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push_node(&mut self, node: TemplateNode) -> Result<usize> {
+        if self.nodes.len() >= MAX_NODES {
+            return Err(TemplateError {
+                line: None,
+                message: format!("template produced more than {MAX_NODES} nodes"),
+            });
+        }
+        self.nodes.push(node);
+        Ok(self.nodes.len() - 1)
+    }
+
+    fn node(
+        &self,
+        field: &Field,
+        range: Range<u64>,
+        ty: impl Into<Cow<'static, str>>,
+    ) -> TemplateNode {
+        let frame = self.frames.last().expect("evaluator always has a frame");
+        TemplateNode {
+            range,
+            name: field.name,
+            ty: ty.into(),
+            record_type: frame.type_name,
+            record_index: frame.record_index,
+            depth: frame.depth,
+            value: None,
+            is_leaf: true,
+            color_index: frame.field_no % 16,
+        }
+    }
+
+    fn need(&self, field: &Field, start: u64, size: u64) -> Result<u64> {
+        let end = start
+            .checked_add(size)
+            .filter(|end| *end <= self.data.len());
+        end.ok_or_else(|| {
+            TemplateError::at(
+                field.line,
+                format!(
+                    "`{}` at 0x{start:X} needs {size} bytes but the file ends at 0x{:X}",
+                    field.name,
+                    self.data.len()
+                ),
+            )
+        })
+    }
+
+    fn slice(&self, range: Range<u64>) -> Cow<'_, [u8]> {
+        self.data.read_range(range)
+    }
+
+    /// Distance from `start` to the next NUL byte, scanning piece by piece.
+    fn nul_offset(&self, start: u64) -> Option<u64> {
+        let end = self.data.len();
+        let mut pos = start;
+        while pos < end {
+            let chunk = self.data.read(pos..end);
+            if let Some(i) = chunk.iter().position(|b| *b == 0) {
+                return Some(pos - start + i as u64);
+            }
+            if chunk.is_empty() {
+                break;
+            }
+            pos += chunk.len() as u64;
+        }
+        None
+    }
+
+    fn decode(&self, field: &Field, range: Range<u64>) -> String {
+        let raw = self.slice(range);
+        let raw = raw.split(|b| *b == 0).next().unwrap_or_default();
+        field
+            .encoding
+            .decode_without_bom_handling(raw)
+            .0
+            .into_owned()
+    }
+
+    fn field(&mut self, field: &Field) -> Result<()> {
+        let start = match &field.at {
+            Some(at) => self.to_u64(at, field.line, "offset")?,
+            None => self.frames.last().map_or(0, |f| f.cursor),
+        };
+        let count = match &field.count {
+            Count::One => None,
+            Count::Expr(expr) => Some(Some(self.to_u64(expr, field.line, "array size")?)),
+            Count::ToEnd => Some(None),
+        };
+        let (end, slot) = match count {
+            None => self.single(field, start)?,
+            Some(count) => self.array(field, start, count)?,
+        };
+        let frame = self.frame();
+        frame.cursor = end;
+        frame.field_no = frame.field_no.wrapping_add(1);
+        if let Some(slot) = slot {
+            frame.scope.fields.push((field.name, slot));
+        }
+        Ok(())
+    }
+
+    /// Read one element of `field`'s type at `start`.
+    fn single(&mut self, field: &Field, start: u64) -> Result<(u64, Option<Slot>)> {
+        match field.ty {
+            TypeRef::Prim(prim) => {
+                let end = self.need(field, start, prim.size())?;
+                let value = prim.read(&self.slice(start..end), field.big_endian);
+                let mut node = self.node(field, start..end, field.ty_name);
+                node.value = Some(match (prim, &value) {
+                    (Prim::Char, Value::Int(v)) => char::from(*v as u8).to_string(),
+                    (Prim::Bool, v) => v.truthy().to_string(),
+                    (_, Value::Int(v)) => v.to_string(),
+                    (_, Value::Float(v)) => v.to_string(),
+                    (_, Value::Str(s)) => s.clone(),
+                });
+                self.push_node(node)?;
+                Ok((end, Some(Slot::Value(value))))
+            }
+            TypeRef::Enum(id) => {
+                let template = self.template;
+                let def = &template.enums[id];
+                let end = self.need(field, start, def.repr.size())?;
+                let value = def.repr.read(&self.slice(start..end), field.big_endian);
+                let mut node = self.node(field, start..end, def.name);
+                if let Value::Int(v) = value {
+                    node.value = Some(match def.variant(v) {
+                        Some(variant) => format!("{}::{variant}", def.name),
+                        None => format!("{}({v})", def.name),
+                    });
+                }
+                self.push_node(node)?;
+                Ok((end, Some(Slot::Value(value))))
+            }
+            TypeRef::CString => {
+                let len = self.nul_offset(start).ok_or_else(|| {
+                    TemplateError::at(
+                        field.line,
+                        format!("`{}` at 0x{start:X} has no NUL terminator", field.name),
+                    )
+                })?;
+                let end = start + len + 1;
+                let text = self.decode(field, start..end);
+                let mut node = self.node(field, start..end, field.ty_name);
+                node.value = Some(text.clone());
+                self.push_node(node)?;
+                Ok((end, Some(Slot::Value(Value::Str(text)))))
+            }
+            TypeRef::Padding => {
+                let end = self.need(field, start, 1)?;
+                self.push_node(self.node(field, start..end, field.ty_name))?;
+                Ok((end, None))
+            }
+            TypeRef::Struct(id) => {
+                let record_index = self.frames.last().map_or(0, |f| f.record_index);
+                let (end, scope) = self.structure(field, id, start, record_index)?;
+                Ok((end, Some(Slot::Struct(Rc::new(scope)))))
+            }
+        }
+    }
+
+    /// Evaluate struct `id` at `start` as a child node of the current frame.
+    fn structure(
+        &mut self,
+        field: &Field,
+        id: usize,
+        start: u64,
+        record_index: u64,
+    ) -> Result<(u64, Scope)> {
+        if self.frames.len() > MAX_DEPTH {
+            return Err(TemplateError::at(
+                field.line,
+                format!("structs nested deeper than {MAX_DEPTH} levels"),
+            ));
+        }
+        let template = self.template;
+        let def = &template.structs[id];
+        let mut node = self.node(field, start..start, def.name);
+        node.record_index = record_index;
+        node.is_leaf = false;
+        let depth = node.depth;
+        let index = self.push_node(node)?;
+        self.frames.push(Frame {
+            scope: Scope::default(),
+            cursor: start,
+            type_name: def.name,
+            record_index,
+            depth: depth.saturating_add(1),
+            field_no: 0,
+        });
+        let result = self.block(&def.body);
+        let frame = self.frames.pop().expect("struct frame was pushed");
+        result?;
+        let end = frame.cursor.max(start);
+        self.nodes[index].range = start..end;
+        Ok((end, frame.scope))
+    }
+
+    fn array(
+        &mut self,
+        field: &Field,
+        start: u64,
+        count: Option<u64>,
+    ) -> Result<(u64, Option<Slot>)> {
+        let remaining = self.data.len().saturating_sub(start);
+        let elem_size = match field.ty {
+            TypeRef::Prim(prim) => Some(prim.size()),
+            TypeRef::Enum(id) => Some(self.template.enums[id].repr.size()),
+            TypeRef::Padding => Some(1),
+            TypeRef::CString | TypeRef::Struct(_) => None,
+        };
+        if let Some(size) = elem_size {
+            let count = count.unwrap_or(remaining / size);
+            let end = self.need(field, start, count.saturating_mul(size))?;
+            let ty = if matches!(field.ty, TypeRef::Padding) {
+                format!("padding[{count}]")
+            } else {
+                format!("{}[{count}]", field.ty_name)
+            };
+            let mut node = self.node(field, start..end, ty);
+            let slot = match field.ty {
+                TypeRef::Prim(Prim::Char) => {
+                    let text = self.decode(field, start..end);
+                    node.value = Some(text.clone());
+                    Some(Slot::Value(Value::Str(text)))
+                }
+                TypeRef::Prim(prim) => Some(Slot::Prims {
+                    start,
+                    prim,
+                    big_endian: field.big_endian,
+                    count,
+                }),
+                TypeRef::Enum(id) => Some(Slot::Prims {
+                    start,
+                    prim: self.template.enums[id].repr,
+                    big_endian: field.big_endian,
+                    count,
+                }),
+                _ => None,
+            };
+            self.push_node(node)?;
+            return Ok((end, slot));
+        }
+
+        // Arrays of structs and strings: one container node with an
+        // outline child per element.
+        let label = match count {
+            Some(count) => format!("{}[{count}]", field.ty_name),
+            None => format!("{}[]", field.ty_name),
+        };
+        let mut container = self.node(field, start..start, label);
+        container.is_leaf = false;
+        let depth = container.depth;
+        let container_index = self.push_node(container)?;
+        self.frames.push(Frame {
+            scope: Scope::default(),
+            cursor: start,
+            type_name: self.frames.last().map_or(TOP_LEVEL, |f| f.type_name),
+            record_index: 0,
+            depth: depth.saturating_add(1),
+            field_no: 0,
+        });
+        let result = self.elements(field, start, count);
+        let frame = self.frames.pop().expect("array frame was pushed");
+        let elements = result?;
+        let end = frame.cursor.max(start);
+        self.nodes[container_index].range = start..end;
+        let slot = match field.ty {
+            TypeRef::Struct(_) => Some(Slot::Structs(Rc::new(elements))),
+            _ => None,
+        };
+        Ok((end, slot))
+    }
+
+    fn elements(
+        &mut self,
+        field: &Field,
+        start: u64,
+        count: Option<u64>,
+    ) -> Result<Vec<Rc<Scope>>> {
+        let len = self.data.len();
+        let mut elements = Vec::new();
+        let mut cursor = start;
+        let mut index = 0u64;
+        loop {
+            match count {
+                Some(count) if index >= count => break,
+                None if cursor >= len => break,
+                _ => {}
+            }
+            self.frame().record_index = index;
+            let end = match field.ty {
+                TypeRef::Struct(id) => {
+                    let (end, scope) = self.structure(field, id, cursor, index)?;
+                    elements.push(Rc::new(scope));
+                    end
+                }
+                _ => self.single(field, cursor)?.0,
+            };
+            if end == cursor && count.is_none() {
+                // A zero-sized element would repeat forever.
+                break;
+            }
+            cursor = end;
+            self.frame().cursor = end;
+            index += 1;
+        }
+        Ok(elements)
+    }
+
+    fn to_u64(&self, expr: &Expr, line: usize, what: &str) -> Result<u64> {
+        match self.eval(expr, line)? {
+            Value::Int(v) => u64::try_from(v)
+                .map_err(|_| TemplateError::at(line, format!("{what} {v} is negative"))),
+            other => Err(TemplateError::at(
+                line,
+                format!("{what} must be an integer, got {other:?}"),
+            )),
+        }
+    }
+
+    fn eval(&self, expr: &Expr, line: usize) -> Result<Value> {
+        match expr {
+            Expr::Value(v) => Ok(v.clone()),
+            Expr::Offset => Ok(Value::Int(i128::from(
+                self.frames.last().map_or(0, |f| f.cursor),
+            ))),
+            Expr::Unary(op, inner) => {
+                unary(*op, self.eval(inner, line)?).map_err(|e| TemplateError::at(line, e))
+            }
+            Expr::Binary(BinOp::And, l, r) => Ok(Value::Int(i128::from(
+                self.eval(l, line)?.truthy() && self.eval(r, line)?.truthy(),
+            ))),
+            Expr::Binary(BinOp::Or, l, r) => Ok(Value::Int(i128::from(
+                self.eval(l, line)?.truthy() || self.eval(r, line)?.truthy(),
+            ))),
+            Expr::Binary(op, l, r) => binary(*op, self.eval(l, line)?, self.eval(r, line)?)
+                .map_err(|e| TemplateError::at(line, e)),
+            Expr::Path(segments) => self.path(segments, line),
+        }
+    }
+
+    fn path(&self, segments: &[Segment], line: usize) -> Result<Value> {
+        let first = &segments[0];
+        let mut slot = self
+            .frames
+            .iter()
+            .rev()
+            .find_map(|frame| frame.scope.get(&first.name))
+            .cloned()
+            .ok_or_else(|| TemplateError::at(line, format!("unknown field `{}`", first.name)))?;
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 {
+                slot = match &slot {
+                    Slot::Struct(scope) => scope.get(&segment.name).cloned(),
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    TemplateError::at(line, format!("no field `{}` here", segment.name))
+                })?;
+            }
+            if let Some(index) = &segment.index {
+                let index = self.to_u64(index, line, "index")?;
+                let out_of_range = || {
+                    TemplateError::at(line, format!("`{}[{index}]` is out of range", segment.name))
+                };
+                slot = match &slot {
+                    Slot::Structs(items) => Slot::Struct(
+                        items
+                            .get(index as usize)
+                            .cloned()
+                            .ok_or_else(out_of_range)?,
+                    ),
+                    Slot::Prims {
+                        start,
+                        prim,
+                        big_endian,
+                        count,
+                    } => {
+                        if index >= *count {
+                            return Err(out_of_range());
+                        }
+                        let at = start + index * prim.size();
+                        Slot::Value(prim.read(&self.slice(at..at + prim.size()), *big_endian))
+                    }
+                    _ => {
+                        return Err(TemplateError::at(
+                            line,
+                            format!("`{}` is not an array", segment.name),
+                        ));
+                    }
+                };
+            }
+        }
+        match slot {
+            Slot::Value(v) => Ok(v),
+            _ => Err(TemplateError::at(
+                line,
+                format!(
+                    "`{}` is not a value",
+                    segments.last().map_or("", |s| s.name.as_str())
+                ),
+            )),
+        }
+    }
+}
+
+// ── Layout ────────────────────────────────────────────────────────────────
+
+/// One evaluated struct, array or field, in outline (pre-)order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateNode {
+    pub range: Range<u64>,
+    pub name: &'static str,
+    pub ty: Cow<'static, str>,
+    /// Struct type that declared the field (`"template"` at top level).
+    pub record_type: &'static str,
+    /// Index of the closest enclosing array element.
+    pub record_index: u64,
+    pub depth: u8,
+    /// Decoded value of scalar, enum and string fields.
+    pub value: Option<String>,
+    /// `false` for structs and struct/string arrays.
+    pub is_leaf: bool,
+    color_index: u8,
+}
+
+/// The result of evaluating a [`BinaryTemplate`] against one file.
+#[derive(Debug, Clone)]
+pub struct TemplateBinaryLayout {
+    nodes: Vec<TemplateNode>,
+    /// Leaf node indices sorted by start address.
+    leaves: Vec<usize>,
+    /// Running maximum of leaf end addresses, parallel to `leaves`.
+    max_end: Vec<u64>,
+}
+
+impl TemplateBinaryLayout {
+    fn new(nodes: Vec<TemplateNode>) -> Self {
+        let mut leaves: Vec<usize> = (0..nodes.len()).filter(|i| nodes[*i].is_leaf).collect();
+        leaves.sort_by_key(|i| nodes[*i].range.start);
+        let mut max_end = Vec::with_capacity(leaves.len());
+        let mut running = 0;
+        for i in &leaves {
+            running = nodes[*i].range.end.max(running);
+            max_end.push(running);
+        }
+        Self {
+            nodes,
+            leaves,
+            max_end,
+        }
+    }
+
+    pub fn nodes(&self) -> &[TemplateNode] {
+        &self.nodes
+    }
+
+    /// Leaves that may overlap `range`: everything before `lo` ends at or
+    /// before `range.start`, everything from `hi` starts at or after `range.end`.
+    fn candidates(&self, range: Range<u64>) -> impl Iterator<Item = &TemplateNode> {
+        let lo = self.max_end.partition_point(|end| *end <= range.start);
+        let hi = self
+            .leaves
+            .partition_point(|i| self.nodes[*i].range.start < range.end);
+        self.leaves[lo..hi.max(lo)]
+            .iter()
+            .map(|i| &self.nodes[*i])
+            .filter(move |node| node.range.end > range.start)
+    }
+
+    fn span(node: &TemplateNode) -> FieldSpan {
+        FieldSpan {
+            range: node.range.clone(),
+            name: node.name,
+            ty: node.ty.clone(),
+            record_type: node.record_type,
+            record_index: node.record_index,
+            color_index: node.color_index,
+        }
+    }
+}
+
+impl BinaryLayout for TemplateBinaryLayout {
+    fn field_at(&self, address: u64, file_len: u64) -> Option<FieldSpan> {
+        self.candidates(address..address.saturating_add(1))
+            .filter(|node| node.range.end <= file_len)
+            .min_by_key(|node| node.range.end - node.range.start)
+            .map(Self::span)
+    }
+
+    fn fields_in(&self, range: Range<u64>, file_len: u64) -> Vec<FieldSpan> {
+        if range.start >= range.end {
+            return Vec::new();
+        }
+        self.candidates(range)
+            .filter(|node| node.range.end <= file_len)
+            .map(Self::span)
+            .collect()
+    }
+
+    fn outline(&self, file_len: u64) -> Vec<LayoutOutlineItem> {
+        self.nodes
+            .iter()
+            .filter(|node| node.range.end <= file_len)
+            .map(|node| LayoutOutlineItem {
+                id: 0,
+                range: node.range.clone(),
+                name: node.name,
+                ty: node.ty.clone(),
+                record_index: node.record_index,
+                depth: node.depth,
+                has_children: false,
+                expanded: false,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORE: &str = r#"
+        enum ProductType : i16 { None, Weapon, Healing, EditItem, MiscItem }
+
+        struct Product {
+            ProductType kind;
+            i16 item_id;
+        }
+
+        struct Store {
+            char store_name[8] [[encoding("windows-1250")]];
+            i32 inn_night_cost;
+            if (inn_night_cost > 0) {
+                padding[6];
+            } else {
+                i16 price_modifier;
+                Product products[1];
+            }
+        }
+
+        i32 record_count;
+        Store stores[record_count];
+    "#;
+
+    fn store_bytes() -> Vec<u8> {
+        let mut bytes = 2i32.to_le_bytes().to_vec();
+        // Shop with one weapon.
+        bytes.extend(b"Kowa\xB3\0\0\0");
+        bytes.extend(0i32.to_le_bytes());
+        bytes.extend(10i16.to_le_bytes());
+        bytes.extend(1i16.to_le_bytes());
+        bytes.extend(7i16.to_le_bytes());
+        // Inn.
+        bytes.extend(b"Inn\0\0\0\0\0");
+        bytes.extend(25i32.to_le_bytes());
+        bytes.extend([0; 6]);
+        bytes
+    }
+
+    #[test]
+    fn test_template_nests_struct_arrays_sized_by_earlier_fields() {
+        let layout = BinaryTemplate::parse(STORE)
+            .unwrap()
+            .evaluate(store_bytes().as_slice())
+            .unwrap();
+        let outline: Vec<_> = layout
+            .nodes()
+            .iter()
+            .map(|n| (n.name, n.ty.as_ref(), n.depth, n.record_index))
+            .collect();
+        assert_eq!(outline[0], ("record_count", "i32", 0, 0));
+        assert_eq!(outline[1], ("stores", "Store[2]", 0, 0));
+        assert_eq!(outline[2], ("stores", "Store", 1, 0));
+        assert!(outline.contains(&("products", "Product[1]", 2, 0)));
+        assert!(outline.contains(&("kind", "ProductType", 4, 0)));
+        assert!(outline.contains(&("padding", "padding[6]", 2, 1)));
+        assert_eq!(layout.nodes()[1].range, 4..40);
+    }
+
+    #[test]
+    fn test_template_decodes_enums_and_encoded_strings() {
+        let layout = BinaryTemplate::parse(STORE)
+            .unwrap()
+            .evaluate(store_bytes().as_slice())
+            .unwrap();
+        let value = |name: &str| {
+            layout
+                .nodes()
+                .iter()
+                .find(|n| n.name == name)
+                .and_then(|n| n.value.clone())
+        };
+        assert_eq!(value("store_name").as_deref(), Some("Kował"));
+        assert_eq!(value("kind").as_deref(), Some("ProductType::Weapon"));
+        assert_eq!(value("item_id").as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn test_template_field_at_returns_innermost_leaf() {
+        let layout = BinaryTemplate::parse(STORE)
+            .unwrap()
+            .evaluate(store_bytes().as_slice())
+            .unwrap();
+        let field = layout.field_at(19, 40).unwrap();
+        assert_eq!(field.name, "kind");
+        assert_eq!(field.record_type, "Product");
+        assert_eq!(layout.fields_in(28..36, 40).len(), 3);
+        assert!(layout.field_at(39, 38).is_none());
+    }
+
+    #[test]
+    fn test_template_placement_cstring_and_open_array() {
+        let source = r#"
+            struct Entry { u8 id; cstring label; }
+            be u16 table_offset;
+            Entry entries[] @ table_offset;
+        "#;
+        let mut bytes = vec![0x00, 0x04, 0xFF, 0xFF];
+        bytes.extend(b"\x01ab\0\x02c\0");
+        let layout = BinaryTemplate::parse(source)
+            .unwrap()
+            .evaluate(bytes.as_slice())
+            .unwrap();
+        let entries = &layout.nodes()[1];
+        assert_eq!(entries.ty, "Entry[]");
+        assert_eq!(entries.range, 4..11);
+        let labels: Vec<_> = layout
+            .nodes()
+            .iter()
+            .filter(|n| n.name == "label")
+            .map(|n| (n.value.clone().unwrap(), n.record_index))
+            .collect();
+        assert_eq!(labels, vec![("ab".to_string(), 0), ("c".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_template_expressions_index_earlier_arrays() {
+        let source = r#"
+            u8 sizes[2];
+            u8 first[sizes[0] * 2];
+            if (sizes[1] == 0 || $ >= 100) { u8 never; } else { u8 rest[]; }
+        "#;
+        let layout = BinaryTemplate::parse(source)
+            .unwrap()
+            .evaluate(&[1u8, 3, 9, 9, 5, 5, 5][..])
+            .unwrap();
+        let names: Vec<_> = layout
+            .nodes()
+            .iter()
+            .map(|n| (n.name, n.ty.as_ref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("sizes", "u8[2]"), ("first", "u8[2]"), ("rest", "u8[3]")]
+        );
+    }
+
+    #[test]
+    fn test_template_errors_report_lines() {
+        let err = BinaryTemplate::parse("u32 a;\nFoo b;").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert!(err.to_string().contains("unknown type `Foo`"));
+
+        let err = BinaryTemplate::parse("u32 count;\nu32 values[count];")
+            .unwrap()
+            .evaluate(&[9u8, 0, 0, 0, 1, 0][..])
+            .unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert!(err.message.contains("file ends at 0x6"));
+    }
+}
@@ -9,20 +9,33 @@
 
 use std::collections::BTreeSet;
 
+use super::provider::HexProvider;
+
 /// Set of addresses where `current[i] != vanilla[i]`. Length differences
 /// past `min(len)` are reported as differing addresses too.
-pub fn compute_diff(vanilla: &[u8], current: &[u8]) -> BTreeSet<u64> {
+///
+/// `current` is compared piece by piece, so an edited buffer is not copied.
+pub fn compute_diff(
+    vanilla: &(impl HexProvider + ?Sized),
+    current: &(impl HexProvider + ?Sized),
+) -> BTreeSet<u64> {
     let mut out = BTreeSet::new();
     let common = vanilla.len().min(current.len());
-    for i in 0..common {
-        if vanilla[i] != current[i] {
-            out.insert(i as u64);
+    let mut pos = 0;
+    while pos < common {
+        let chunk = current.read(pos..common);
+        if chunk.is_empty() {
+            break;
         }
+        let old = vanilla.read_range(pos..pos + chunk.len() as u64);
+        for (i, (a, b)) in old.iter().zip(chunk).enumerate() {
+            if a != b {
+                out.insert(pos + i as u64);
+            }
+        }
+        pos += chunk.len() as u64;
     }
-    let extra = vanilla.len().max(current.len());
-    for i in common..extra {
-        out.insert(i as u64);
-    }
+    out.extend(common..vanilla.len().max(current.len()));
     out
 }
 
@@ -32,32 +45,32 @@ mod tests {
 
     #[test]
     fn identical_buffers_have_empty_diff() {
-        let a = vec![0, 1, 2, 3];
-        assert!(compute_diff(&a, &a).is_empty());
+        let a: &[u8] = &[0, 1, 2, 3];
+        assert!(compute_diff(a, a).is_empty());
     }
 
     #[test]
     fn single_byte_change_shows_one_addr() {
-        let a = vec![0, 1, 2, 3];
-        let b = vec![0, 9, 2, 3];
-        let d = compute_diff(&a, &b);
+        let a: &[u8] = &[0, 1, 2, 3];
+        let b: &[u8] = &[0, 9, 2, 3];
+        let d = compute_diff(a, b);
         assert_eq!(d.len(), 1);
         assert!(d.contains(&1));
     }
 
     #[test]
     fn longer_current_marks_tail_as_diff() {
-        let a = vec![0, 1];
-        let b = vec![0, 1, 2, 3];
-        let d = compute_diff(&a, &b);
+        let a: &[u8] = &[0, 1];
+        let b: &[u8] = &[0, 1, 2, 3];
+        let d = compute_diff(a, b);
         assert_eq!(d, BTreeSet::from([2, 3]));
     }
 
     #[test]
     fn shorter_current_marks_truncated_tail_as_diff() {
-        let a = vec![0, 1, 2, 3];
-        let b = vec![0, 1];
-        let d = compute_diff(&a, &b);
+        let a: &[u8] = &[0, 1, 2, 3];
+        let b: &[u8] = &[0, 1];
+        let d = compute_diff(a, b);
         assert_eq!(d, BTreeSet::from([2, 3]));
     }
 }
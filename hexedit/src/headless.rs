@@ -0,0 +1,533 @@
+//! Headless script runner: `hexedit run script.lua [--dry-run] file...`.
+//!
+//! The script runs once per file in a fresh (sandboxed) Lua state with the
+//! file exposed as the global `file`. Offsets are 0-based, lengths in bytes:
+//!
+//! ```lua
+//! local count = file:get("i32", 0)
+//! for i = 0, count - 1 do
+//!     local at = 4 + i * 56
+//!     if file:get("i32", at + 4) == 0 then
+//!         file:set("i32", at + 4, 1)
+//!     end
+//! end
+//! assert(#file:search("DE AD") == 0, "marker still present")
+//! file:save()
+//! ```
+//!
+//! Nothing is written unless the script calls `file:save()`; `--dry-run`
+//! turns saves into no-ops so probes can run against CI fixtures. A Lua error
+//! fails that file and makes the run exit non-zero.
+//!
+//! Paths passed to `file:template`, `file:load_patterns` and
+//! `file:save_patterns` are relative to the script's directory, and
+//! `file:save(path)` is relative to the target file's directory; absolute
+//! paths and `..` are rejected. `HEXEDIT_LUA_UNSAFE=1` lifts both the Lua
+//! library sandbox and these path checks.
+
+use std::path::{Path, PathBuf};
+
+/// Outcome of running a script over one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReport {
+    pub path: PathBuf,
+    /// Bytes modified or inserted by the script.
+    pub changed_bytes: usize,
+    /// Whether `file:save()` wrote the buffer (always `false` in dry runs).
+    pub saved: bool,
+}
+
+/// Entry point for `hexedit run …`; `args` excludes the binary name and
+/// `run`. Returns the process exit code.
+pub fn run_cli(args: &[String]) -> i32 {
+    let mut script: Option<PathBuf> = None;
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut dry_run = false;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" | "-n" => dry_run = true,
+            _ if script.is_none() => script = Some(PathBuf::from(arg)),
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+    let Some(script) = script.filter(|_| !files.is_empty()) else {
+        eprintln!("usage: hexedit run <script.lua> [--dry-run] <file>...");
+        return 2;
+    };
+
+    let unsafe_mode = std::env::var("HEXEDIT_LUA_UNSAFE").as_deref() == Ok("1");
+    let mut failed = 0;
+    for file in &files {
+        match run_script(&script, file, dry_run, unsafe_mode) {
+            Ok(report) => println!(
+                "ok    {} ({} byte(s) changed{})",
+                report.path.display(),
+                report.changed_bytes,
+                if report.saved {
+                    ", saved"
+                } else if dry_run && report.changed_bytes > 0 {
+                    ", dry run"
+                } else {
+                    ""
+                }
+            ),
+            Err(e) => {
+                failed += 1;
+                println!("FAIL  {}: {e}", file.display());
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("[hexedit] {failed} of {} file(s) failed", files.len());
+        1
+    } else {
+        0
+    }
+}
+
+// ── Real implementation (feature "lua") ───────────────────────────────────
+#[cfg(feature = "lua")]
+mod inner {
+    use std::path::{Component, Path, PathBuf};
+
+    use mlua::{UserData, UserDataFields, UserDataMethods};
+
+    use super::FileReport;
+    use crate::domain::pattern::{Pattern, PatternExport, RepeatedPatternGroup};
+    use crate::domain::provider::{BufferProvider, HexProvider};
+    use crate::domain::search::{SearchMode, SearchState};
+    use crate::domain::template::BinaryTemplate;
+    use crate::lua_engine::new_lua;
+
+    fn rt(message: impl Into<String>) -> mlua::Error {
+        mlua::Error::RuntimeError(message.into())
+    }
+
+    /// Parse a value type such as `u16`, `i32be` or `f64`.
+    fn parse_type(ty: &str) -> mlua::Result<(&str, usize, bool)> {
+        let (base, big_endian) = match ty.strip_suffix("be") {
+            Some(base) => (base, true),
+            None => (ty.strip_suffix("le").unwrap_or(ty), false),
+        };
+        let size = match base {
+            "u8" | "i8" => 1,
+            "u16" | "i16" => 2,
+            "u32" | "i32" | "f32" => 4,
+            "u64" | "i64" | "f64" => 8,
+            _ => return Err(rt(format!("unknown value type `{ty}`"))),
+        };
+        Ok((base, size, big_endian))
+    }
+
+    fn encoding(label: Option<String>) -> mlua::Result<&'static encoding_rs::Encoding> {
+        match label {
+            None => Ok(encoding_rs::UTF_8),
+            Some(label) => encoding_rs::Encoding::for_label(label.as_bytes())
+                .ok_or_else(|| rt(format!("unknown encoding `{label}`"))),
+        }
+    }
+
+    /// Resolve a script-supplied `path` under `base`, rejecting absolute
+    /// paths and `..` unless `unsafe_mode` is on.
+    fn sandboxed(base: &Path, path: &str, unsafe_mode: bool) -> mlua::Result<PathBuf> {
+        let rel = Path::new(path);
+        if unsafe_mode {
+            return Ok(base.join(rel));
+        }
+        let escapes = rel.components().any(|c| {
+            matches!(
+                c,
+                Component::ParentDir | Component::RootDir | Component::Prefix(_)
+            )
+        });
+        if escapes || path.is_empty() {
+            return Err(rt(format!(
+                "path '{path}' must be relative and stay inside '{}'",
+                base.display()
+            )));
+        }
+        Ok(base.join(rel))
+    }
+
+    struct ScriptBuffer {
+        path: PathBuf,
+        /// Directory of the running script; base for template and pattern files.
+        script_dir: PathBuf,
+        unsafe_mode: bool,
+        provider: BufferProvider,
+        patterns: Vec<Pattern>,
+        groups: Vec<RepeatedPatternGroup>,
+        next_pattern_id: usize,
+        dry_run: bool,
+        saved: bool,
+    }
+
+    impl ScriptBuffer {
+        fn script_path(&self, path: &str) -> mlua::Result<PathBuf> {
+            sandboxed(&self.script_dir, path, self.unsafe_mode)
+        }
+
+        fn file_dir(&self) -> &Path {
+            self.path.parent().unwrap_or(Path::new(""))
+        }
+
+        fn range(&self, offset: u64, len: usize) -> mlua::Result<std::ops::Range<u64>> {
+            let end = offset
+                .checked_add(len as u64)
+                .filter(|end| *end <= self.provider.len());
+            end.map(|end| offset..end).ok_or_else(|| {
+                rt(format!(
+                    "{len} byte(s) at 0x{offset:X} run past the end of the file (0x{:X})",
+                    self.provider.len()
+                ))
+            })
+        }
+
+        fn bytes(&self, offset: u64, len: usize) -> mlua::Result<&[u8]> {
+            let range = self.range(offset, len)?;
+            Ok(self.provider.read(range))
+        }
+
+        fn write(&mut self, offset: u64, bytes: &[u8]) -> mlua::Result<()> {
+            self.range(offset, bytes.len())?;
+            self.provider.write(offset, bytes);
+            Ok(())
+        }
+
+        fn get(&self, ty: &str, offset: u64) -> mlua::Result<mlua::Value> {
+            let (base, size, big_endian) = parse_type(ty)?;
+            let mut buf = [0u8; 8];
+            buf[..size].copy_from_slice(self.bytes(offset, size)?);
+            if big_endian {
+                buf[..size].reverse();
+            }
+            let raw = u64::from_le_bytes(buf);
+            Ok(match base {
+                "u8" | "u16" | "u32" | "u64" => mlua::Value::Integer(raw as i64),
+                "i8" => mlua::Value::Integer(i64::from(raw as u8 as i8)),
+                "i16" => mlua::Value::Integer(i64::from(raw as u16 as i16)),
+                "i32" => mlua::Value::Integer(i64::from(raw as u32 as i32)),
+                "i64" => mlua::Value::Integer(raw as i64),
+                "f32" => mlua::Value::Number(f64::from(f32::from_bits(raw as u32))),
+                _ => mlua::Value::Number(f64::from_bits(raw)),
+            })
+        }
+
+        fn set(&mut self, ty: &str, offset: u64, value: mlua::Value) -> mlua::Result<()> {
+            let (base, size, big_endian) = parse_type(ty)?;
+            let raw = match (base, value) {
+                ("f32", v) => u64::from((number(v)? as f32).to_bits()),
+                ("f64", v) => number(v)?.to_bits(),
+                (_, mlua::Value::Integer(v)) => {
+                    let bits = size as u32 * 8;
+                    let fits = if base.starts_with('u') {
+                        v >= 0 && (bits == 64 || v < 1i64 << bits)
+                    } else {
+                        bits == 64 || (-(1i64 << (bits - 1))..1i64 << (bits - 1)).contains(&v)
+                    };
+                    if !fits {
+                        return Err(rt(format!("{v} does not fit in {base}")));
+                    }
+                    v as u64
+                }
+                (_, other) => {
+                    return Err(rt(format!(
+                        "{base} needs an integer, got {}",
+                        other.type_name()
+                    )));
+                }
+            };
+            let mut bytes = raw.to_le_bytes()[..size].to_vec();
+            if big_endian {
+                bytes.reverse();
+            }
+            self.write(offset, &bytes)
+        }
+    }
+
+    /// Lua number or integer as `f64`.
+    fn number(value: mlua::Value) -> mlua::Result<f64> {
+        match value {
+            mlua::Value::Integer(v) => Ok(v as f64),
+            mlua::Value::Number(v) => Ok(v),
+            other => Err(rt(format!("expected a number, got {}", other.type_name()))),
+        }
+    }
+
+    impl UserData for ScriptBuffer {
+        fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+            fields.add_field_method_get("path", |_, this| Ok(this.path.display().to_string()));
+        }
+
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_method("len", |_, this, ()| Ok(this.provider.len()));
+            methods.add_method("dirty", |_, this, ()| Ok(this.provider.dirty_count()));
+
+            methods.add_method("read", |lua, this, (offset, len): (u64, usize)| {
+                lua.create_string(this.bytes(offset, len)?)
+            });
+            methods.add_method_mut("write", |_, this, (offset, bytes): (u64, mlua::String)| {
+                this.write(offset, &bytes.as_bytes())
+            });
+            methods.add_method("get", |_, this, (ty, offset): (String, u64)| {
+                this.get(&ty, offset)
+            });
+            methods.add_method_mut(
+                "set",
+                |_, this, (ty, offset, value): (String, u64, mlua::Value)| {
+                    this.set(&ty, offset, value)
+                },
+            );
+
+            // Fixed-size text fields: decoding stops at the first NUL,
+            // encoding pads with NULs and fails when the text does not fit.
+            methods.add_method(
+                "string",
+                |_, this, (offset, len, label): (u64, usize, Option<String>)| {
+                    let raw = this.bytes(offset, len)?;
+                    let raw = raw.split(|b| *b == 0).next().unwrap_or_default();
+                    Ok(encoding(label)?
+                        .decode_without_bom_handling(raw)
+                        .0
+                        .into_owned())
+                },
+            );
+            methods.add_method_mut(
+                "set_string",
+                |_, this, (offset, len, text, label): (u64, usize, String, Option<String>)| {
+                    let enc = encoding(label)?;
+                    let (encoded, _, had_errors) = enc.encode(&text);
+                    if had_errors {
+                        return Err(rt(format!(
+                            "{text:?} is not representable in {}",
+                            enc.name()
+                        )));
+                    }
+                    if encoded.len() > len {
+                        return Err(rt(format!(
+                            "{text:?} needs {} byte(s), field holds {len}",
+                            encoded.len()
+                        )));
+                    }
+                    let mut bytes = encoded.into_owned();
+                    bytes.resize(len, 0);
+                    this.write(offset, &bytes)
+                },
+            );
+
+            methods.add_method(
+                "search",
+                |_, this, (query, opts): (String, Option<mlua::Table>)| {
+                    let mut search = SearchState::new();
+                    search.query = query;
+                    if let Some(opts) = opts {
+                        search.mode = match opts.get::<Option<String>>("mode")?.as_deref() {
+                            None | Some("hex") => SearchMode::Hex,
+                            Some("ascii") => SearchMode::Ascii,
+                            Some("decimal") => SearchMode::Decimal,
+                            Some(other) => {
+                                return Err(rt(format!("unknown search mode `{other}`")));
+                            }
+                        };
+                        if let Some(width) = opts.get::<Option<u8>>("width")? {
+                            search.width = width;
+                        }
+                        if let Some(le) = opts.get::<Option<bool>>("little_endian")? {
+                            search.little_endian = le;
+                        }
+                    }
+                    search.execute_chunks(this.provider.chunks(0..this.provider.len()));
+                    Ok(search.results)
+                },
+            );
+
+            methods.add_method_mut("insert", |_, this, (offset, bytes): (u64, mlua::String)| {
+                if offset > this.provider.len() {
+                    return Err(rt(format!("insert offset 0x{offset:X} is past the end")));
+                }
+                this.provider.insert(offset, &bytes.as_bytes());
+                Ok(())
+            });
+            methods.add_method_mut(
+                "extend",
+                |_, this, (count, fill): (usize, Option<mlua::String>)| {
+                    let fill = fill.map(|f| f.as_bytes().to_vec()).unwrap_or_default();
+                    let fill = if fill.is_empty() { vec![0] } else { fill };
+                    let bytes: Vec<u8> = fill.iter().copied().cycle().take(count).collect();
+                    let end = this.provider.len();
+                    this.provider.insert(end, &bytes);
+                    Ok(())
+                },
+            );
+
+            methods.add_method_mut(
+                "add_pattern",
+                |_, this, (start, len, annotation): (u64, u64, Option<String>)| {
+                    if len == 0 {
+                        return Err(rt("pattern length must be at least 1"));
+                    }
+                    let id = this.next_pattern_id;
+                    this.next_pattern_id += 1;
+                    let mut pattern = Pattern::new(id, start, start + len - 1, (id % 16) as u8);
+                    pattern.annotation = annotation;
+                    this.patterns.push(pattern);
+                    Ok(id)
+                },
+            );
+            methods.add_method("patterns", |lua, this, ()| {
+                let list = lua.create_table()?;
+                for (i, p) in this.patterns.iter().enumerate() {
+                    let t = lua.create_table()?;
+                    t.set("id", p.id)?;
+                    t.set("start", p.start)?;
+                    t.set("len", p.len())?;
+                    t.set("annotation", p.annotation.clone())?;
+                    let group = p
+                        .group_id
+                        .and_then(|g| this.groups.iter().find(|group| group.id == g));
+                    t.set("group", group.map(|g| g.label.clone()))?;
+                    list.set(i + 1, t)?;
+                }
+                Ok(list)
+            });
+            methods.add_method_mut("load_patterns", |_, this, path: String| {
+                let json = std::fs::read_to_string(this.script_path(&path)?)
+                    .map_err(|e| rt(format!("cannot read '{path}': {e}")))?;
+                let export: PatternExport = serde_json::from_str(&json)
+                    .map_err(|e| rt(format!("invalid pattern file '{path}': {e}")))?;
+                let group_base = this.groups.iter().map(|g| g.id + 1).max().unwrap_or(0);
+                for g in &export.groups {
+                    this.groups.push(RepeatedPatternGroup::new(
+                        group_base + g.id,
+                        g.label.clone(),
+                        g.color_idx,
+                    ));
+                }
+                for p in &export.patterns {
+                    let id = this.next_pattern_id;
+                    this.next_pattern_id += 1;
+                    let mut pattern =
+                        Pattern::new(id, p.start.min(p.end), p.start.max(p.end), p.color_idx % 16);
+                    pattern.group_id = p.group_id.map(|g| group_base + g);
+                    pattern.annotation.clone_from(&p.annotation);
+                    this.patterns.push(pattern);
+                }
+                Ok(export.patterns.len())
+            });
+            methods.add_method("save_patterns", |_, this, path: String| {
+                let export = PatternExport {
+                    version: PatternExport::VERSION,
+                    groups: this.groups.clone(),
+                    patterns: this.patterns.clone(),
+                };
+                let json = serde_json::to_string_pretty(&export).map_err(|e| rt(e.to_string()))?;
+                std::fs::write(this.script_path(&path)?, format!("{json}\n"))
+                    .map_err(|e| rt(format!("cannot write '{path}': {e}")))
+            });
+
+            methods.add_method("template", |lua, this, path: String| {
+                let template = BinaryTemplate::load(&this.script_path(&path)?)
+                    .map_err(|e| rt(e.to_string()))?;
+                let layout = template
+                    .evaluate(&this.provider)
+                    .map_err(|e| rt(e.to_string()))?;
+                let list = lua.create_table()?;
+                for (i, node) in layout.nodes().iter().enumerate() {
+                    let t = lua.create_table()?;
+                    t.set("name", node.name)?;
+                    t.set("type", node.ty.as_ref())?;
+                    t.set("start", node.range.start)?;
+                    t.set("len", node.range.end - node.range.start)?;
+                    t.set("depth", node.depth)?;
+                    t.set("index", node.record_index)?;
+                    t.set("value", node.value.clone())?;
+                    list.set(i + 1, t)?;
+                }
+                Ok(list)
+            });
+
+            methods.add_method_mut("save", |_, this, path: Option<String>| {
+                if this.dry_run {
+                    return Ok(false);
+                }
+                let path = match path {
+                    Some(path) => sandboxed(this.file_dir(), &path, this.unsafe_mode)?,
+                    None => this.path.clone(),
+                };
+                std::fs::write(&path, this.provider.as_slice())
+                    .map_err(|e| rt(format!("cannot write '{}': {e}", path.display())))?;
+                this.saved = true;
+                Ok(true)
+            });
+        }
+    }
+
+    pub fn run_script(
+        script: &Path,
+        file: &Path,
+        dry_run: bool,
+        unsafe_mode: bool,
+    ) -> Result<FileReport, String> {
+        let code = std::fs::read_to_string(script)
+            .map_err(|e| format!("cannot read '{}': {e}", script.display()))?;
+        let bytes =
+            std::fs::read(file).map_err(|e| format!("cannot read '{}': {e}", file.display()))?;
+
+        let lua = new_lua(unsafe_mode)?;
+        let buffer = lua
+            .create_userdata(ScriptBuffer {
+                path: file.to_path_buf(),
+                script_dir: script.parent().unwrap_or(Path::new("")).to_path_buf(),
+                unsafe_mode,
+                provider: BufferProvider::from_bytes(bytes),
+                patterns: Vec::new(),
+                groups: Vec::new(),
+                next_pattern_id: 0,
+                dry_run,
+                saved: false,
+            })
+            .map_err(|e| e.to_string())?;
+        lua.globals()
+            .set("file", buffer.clone())
+            .map_err(|e| e.to_string())?;
+        lua.load(&code)
+            .set_name(script.to_string_lossy().as_ref())
+            .exec()
+            .map_err(|e| format!("lua error in '{}': {e}", script.display()))?;
+
+        let buffer: ScriptBuffer = buffer.take().map_err(|e| e.to_string())?;
+        Ok(FileReport {
+            path: buffer.path,
+            changed_bytes: buffer.provider.dirty_count(),
+            saved: buffer.saved,
+        })
+    }
+}
+
+// ── Stub (feature "lua" not enabled) ──────────────────────────────────────
+#[cfg(not(feature = "lua"))]
+mod inner {
+    use std::path::Path;
+
+    use super::FileReport;
+
+    pub fn run_script(
+        _script: &Path,
+        _file: &Path,
+        _dry_run: bool,
+        _unsafe_mode: bool,
+    ) -> Result<FileReport, String> {
+        Err("hexedit was built without the `lua` feature".to_string())
+    }
+}
+
+/// Run `script` over `file`. The file on disk changes only if the script
+/// calls `file:save()` and `dry_run` is off.
+pub fn run_script(
+    script: &Path,
+    file: &Path,
+    dry_run: bool,
+    unsafe_mode: bool,
+) -> Result<FileReport, String> {
+    inner::run_script(script, file, dry_run, unsafe_mode)
+}
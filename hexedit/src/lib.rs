@@ -17,13 +17,15 @@
 //
 //  domain/   — Pure data model (no internal Iced-widget dependencies)
 //  ui/       — Iced-specific (widget, view, update, coloring)
-//  Root      — config, message, state aggregate, lua_engine, standalone bin
+//  Root      — config, message, state aggregate, lua_engine, headless runner,
+//              standalone bin
 
 pub mod domain;
 pub mod ui;
 
 mod app;
 mod config;
+pub mod headless;
 pub mod lua_engine;
 mod message;
 mod state;
@@ -53,11 +55,14 @@ pub use domain::layout::{
     BinaryLayout, FieldSpan, FixedRecordBinaryLayout, FixedRecordField, LayoutOutlineItem,
     NamedSpan, SpanBinaryLayout,
 };
+pub use domain::mapped_provider::MappedProvider;
 pub use domain::panel::{HexPanel, HexPanelContent};
 pub use domain::pattern::{Pattern, RepeatPatternDialog, RepeatedPatternGroup};
-pub use domain::provider::{BufferProvider, HexProvider};
+pub use domain::provider::{BufferProvider, Chunks, HexProvider};
 pub use domain::search::{SearchMode, SearchState};
 pub use domain::selection::{NavDir, Selection};
+pub use domain::structural_diff::{DiffHunk, DiffSummary, HunkKind, StructuralDiff};
+pub use domain::template::{BinaryTemplate, TemplateBinaryLayout, TemplateError, TemplateNode};
 pub use domain::vanilla_diff::compute_diff;
 pub use domain::write_mode::{EncodingEntry, WriteMode};
 
@@ -69,8 +74,8 @@ pub use ui::inspector::{EncodeFn, InspectorEntry};
 // Module-level re-exports — allow `hexedit::selection::NavDir` and
 // `crate::selection::*` to keep working inside the crate.
 pub use domain::{
-    byte_stats, editing, goto, layout, pattern, provider, search, selection, vanilla_diff,
-    write_mode,
+    byte_stats, editing, goto, layout, mapped_provider, pattern, provider, search, selection,
+    structural_diff, template, vanilla_diff, write_mode,
 };
 pub use ui::{coloring, inspector, update, view};
 
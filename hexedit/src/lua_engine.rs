@@ -8,7 +8,7 @@ mod inner {
 
     const LUA_POISONED: &str = "Lua interpreter lock poisoned";
 
-    fn new_lua(unsafe_mode: bool) -> Result<mlua::Lua, String> {
+    pub(crate) fn new_lua(unsafe_mode: bool) -> Result<mlua::Lua, String> {
         let lua = mlua::Lua::new();
         if !unsafe_mode {
             let globals = lua.globals();
@@ -208,6 +208,8 @@ mod inner {
 }
 
 pub use inner::LuaScriptEngine;
+#[cfg(feature = "lua")]
+pub(crate) use inner::new_lua;
 
 impl Default for LuaScriptEngine {
     fn default() -> Self {
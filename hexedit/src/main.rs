@@ -6,8 +6,8 @@ use iced::{Element, Fill, Font, Task, Theme};
 
 use hexedit::ui::theme::DARK_THEME;
 use hexedit::{
-    AppMessage, EncodingEntry, HexEditorApp, HexEditorConfig, HexEditorDocument, HexEditorMessage,
-    HexEditorState, WriteMode, app_update, app_view,
+    AppMessage, BinaryTemplate, EncodingEntry, HexEditorApp, HexEditorConfig, HexEditorDocument,
+    HexEditorMessage, HexEditorState, WriteMode, app_update, app_view,
 };
 
 /// Settings persisted to `~/.config/hexedit/settings.json`.
@@ -43,6 +43,12 @@ fn load_settings() -> PersistedSettings {
 }
 
 fn main() -> iced::Result {
+    // `hexedit run script.lua file...` never opens a window.
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "run") {
+        std::process::exit(hexedit::headless::run_cli(&args[2..]));
+    }
+
     iced::application(App::new, App::update, App::view)
         .title("HexEdit")
         .theme(|_: &App| {
@@ -80,6 +86,7 @@ impl App {
     fn new() -> (Self, Task<Msg>) {
         let mut paths: Vec<PathBuf> = Vec::new();
         let mut script_dirs: Vec<PathBuf> = Vec::new();
+        let mut template_path: Option<PathBuf> = None;
         let args: Vec<String> = std::env::args().collect();
         let mut i = 1;
         while i < args.len() {
@@ -90,6 +97,12 @@ impl App {
                         script_dirs.push(PathBuf::from(&args[i]));
                     }
                 }
+                "--template" | "-t" => {
+                    i += 1;
+                    if i < args.len() {
+                        template_path = Some(PathBuf::from(&args[i]));
+                    }
+                }
                 _ => {
                     paths.push(PathBuf::from(&args[i]));
                 }
@@ -126,6 +139,20 @@ impl App {
                     }
                 }
             }
+
+            // Map every document with the binary template, if one was given.
+            if let Some(path) = &template_path {
+                match BinaryTemplate::load(path) {
+                    Ok(template) => {
+                        for doc in &mut app.documents {
+                            if let Err(e) = doc.state.apply_template(template.clone()) {
+                                eprintln!("[hexedit] template error in {}: {e}", doc.state.name);
+                            }
+                        }
+                    }
+                    Err(e) => eprintln!("[hexedit] template error: {e}"),
+                }
+            }
         }
 
         (
@@ -165,11 +192,14 @@ impl App {
             custom_encodings: current_encodings.clone(),
             on_save: Some(Arc::new(|state: &HexEditorState| {
                 let path = state.path.clone();
-                let bytes = state.provider.as_slice().to_vec();
+                // The file is mapped, so it is replaced via a temporary file
+                // rather than rewritten in place.
+                let snapshot = state.provider.clone();
                 Task::perform(
                     async move {
-                        tokio::fs::write(&path, bytes)
+                        tokio::task::spawn_blocking(move || snapshot.write_file(&path))
                             .await
+                            .map_err(|e| e.to_string())?
                             .map_err(|e| e.to_string())
                             .map(|_| "Saved".to_string())
                     },
@@ -37,6 +37,10 @@ pub enum HexEditorMessage {
     JumpToLayout(u64),
     /// Expand or collapse a branch in the structure outline.
     ToggleOutline(usize),
+    /// Debounce timer for re-evaluating the binary template fired. Ignored
+    /// unless the buffer is still at this
+    /// [`revision`](crate::MappedProvider::revision).
+    RefreshTemplate(u64),
     /// Shift-click or drag — moves cursor only.
     ExtendTo(u64),
     /// Keyboard navigation; `extend = true` for Shift-modified moves.
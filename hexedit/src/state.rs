@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::cell::Cell;
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
@@ -7,18 +8,21 @@ use gui_widgets::components::toast::Toast;
 use gui_widgets::sweeten::list::Content;
 use iced::widget::pane_grid;
 
-use super::domain::byte_stats::{ByteStatistics, RowEntropyCache, compute_row_entropies};
+use super::domain::byte_stats::{ByteStatistics, RowEntropyCache, compute_row_entropies_chunks};
 use super::domain::export_config::ExportConfig;
 use super::domain::extend_dialog::ExtendDialog;
 use super::domain::fill_dialog::FillDialog;
 use super::domain::layout::{BinaryLayout, LayoutOutlineItem};
+use super::domain::mapped_provider::MappedProvider;
 use super::domain::panel::{HexPanel, default_pane_grid};
+use super::domain::structural_diff::StructuralDiff;
+use super::domain::template::{BinaryTemplate, TemplateError};
 use super::domain::write_mode::{EncodingEntry, WriteMode};
 use super::editing::{EditState, InspectorEditState};
 use super::goto::GotoState;
 use super::lua_engine::LuaScriptEngine;
 use super::pattern::{Pattern, RepeatPatternDialog, RepeatedPatternGroup};
-use super::provider::{BufferProvider, HexProvider};
+use super::provider::HexProvider;
 use super::search::SearchState;
 use super::selection::Selection;
 use super::ui::coloring::ColorScheme;
@@ -32,8 +36,40 @@ pub struct ComparisonFile {
     pub name: String,
     /// Full file contents.
     pub data: Vec<u8>,
-    /// Set of addresses where `provider[i] != data[i]`.
+    /// Baseline addresses that belong to a changed hunk of [`alignment`].
+    ///
+    /// [`alignment`]: Self::alignment
     pub diff: BTreeSet<u64>,
+    /// Comparison addresses that belong to a changed hunk, so inserted
+    /// bytes stay visible on the comparison side.
+    pub comparison_diff: BTreeSet<u64>,
+    /// Insert/delete-aware alignment of the baseline against `data`.
+    pub alignment: StructuralDiff,
+}
+
+impl ComparisonFile {
+    /// Load `data` and align it against the current `baseline` bytes.
+    pub fn new(name: String, data: Vec<u8>, baseline: &MappedProvider) -> Self {
+        let mut cf = Self {
+            name,
+            data,
+            diff: BTreeSet::new(),
+            comparison_diff: BTreeSet::new(),
+            alignment: StructuralDiff::default(),
+        };
+        cf.realign(baseline);
+        cf
+    }
+
+    /// Recompute the alignment after the baseline changed. The full
+    /// alignment needs the baseline in one piece, so an edited baseline is
+    /// stitched for the duration of this call only.
+    pub fn realign(&mut self, baseline: &MappedProvider) {
+        let data = self.data.as_slice();
+        self.alignment = StructuralDiff::compute(&baseline.read_range(0..baseline.len()), data);
+        self.diff = self.alignment.old_changes(baseline, data);
+        self.comparison_diff = self.alignment.new_changes(baseline, data);
+    }
 }
 
 /// Which buffer the data inspector decodes from.
@@ -59,6 +95,12 @@ pub struct HexEditorState {
     pub name: String,
     /// Immutable, per-tab metadata that describes this file's binary structure.
     pub layout: Option<Box<dyn BinaryLayout>>,
+    /// Binary template the layout was evaluated from. Edits re-evaluate it
+    /// once typing pauses.
+    pub template: Option<BinaryTemplate>,
+    /// Error from the latest template evaluation, shown in the footer while
+    /// the last good layout stays in place.
+    pub template_error: Option<String>,
     /// Stable backing data for the virtualized structure-outline pane.
     pub outline: Content<LayoutOutlineItem>,
     /// Complete hierarchy retained while `outline` contains only visible rows.
@@ -69,7 +111,9 @@ pub struct HexEditorState {
     pub panes: pane_grid::State<HexPanel>,
     /// Which pane currently has keyboard focus in the grid.
     pub pane_focus: pane_grid::Pane,
-    pub provider: BufferProvider,
+    /// Editing buffer. Files are memory-mapped and edits live in a piece
+    /// table, so opening a large file does not copy it.
+    pub provider: MappedProvider,
     pub bytes_per_row: u8,
     /// Draft text for the custom bytes-per-row input in the settings modal.
     /// Parsed on submit; invalid or out-of-range input is simply ignored.
@@ -81,11 +125,15 @@ pub struct HexEditorState {
     pub inspector_source: InspectorSource,
     /// Original bytes used as the diff baseline. Populated either from a
     /// workspace vanilla snapshot or, lacking that, from the on-disk file at
-    /// load time. `None` when neither source is available.
-    pub vanilla: Option<Vec<u8>>,
-    /// Cached set of addresses where `provider != vanilla`. Recomputed on
-    /// every write through [`recompute_vanilla_diff`].
+    /// load time. `None` when neither source is available. A file loaded
+    /// from disk shares the provider's mapping instead of holding a copy.
+    pub vanilla: Option<MappedProvider>,
+    /// Cached set of addresses where `provider != vanilla`. Kept current by
+    /// [`vanilla_diff_after_write`] / [`vanilla_diff_after_insert`].
     pub vanilla_diff: BTreeSet<u64>,
+    /// Alignment of `provider` against `vanilla` once their lengths differ;
+    /// `None` while they compare position by position.
+    pub vanilla_alignment: Option<StructuralDiff>,
     /// Optional comparison file for side-by-side diff view.
     /// When `Some`, the Diff pane renders both files with diff-coloured cells.
     pub comparison_file: Option<ComparisonFile>,
@@ -196,7 +244,10 @@ pub struct HexEditorState {
 
 impl HexEditorState {
     /// Replace the tab's layout and prepare its virtualized outline data.
+    /// A template applied earlier is dropped.
     pub fn set_layout(&mut self, layout: Option<Box<dyn BinaryLayout>>) {
+        self.template = None;
+        self.template_error = None;
         self.layout = layout;
         self.collapsed_outline.clear();
         self.rebuild_outline();
@@ -322,7 +373,10 @@ impl HexEditorState {
     ) -> Self {
         let name = name.into();
         let path = path.unwrap_or_else(|| PathBuf::from(&name));
-        let row_entropies = compute_row_entropies(&data, DEFAULT_BYTES_PER_ROW);
+        let provider = MappedProvider::from_bytes(data);
+        let vanilla = vanilla.map(MappedProvider::from_bytes);
+        let row_entropies =
+            compute_row_entropies_chunks(provider.chunks(0..provider.len()), DEFAULT_BYTES_PER_ROW);
         let unsafe_mode = std::env::var("HEXEDIT_LUA_UNSAFE").as_deref() == Ok("1");
         let lua_engine = LuaScriptEngine::new(unsafe_mode).unwrap_or_default();
         let panes = default_pane_grid();
@@ -336,12 +390,14 @@ impl HexEditorState {
             path,
             name,
             layout,
+            template: None,
+            template_error: None,
             outline: Content::new(),
             outline_all: Vec::new(),
             collapsed_outline: BTreeSet::new(),
             panes,
             pane_focus,
-            provider: BufferProvider::from_bytes(data),
+            provider,
             bytes_per_row: DEFAULT_BYTES_PER_ROW,
             bpr_input: DEFAULT_BYTES_PER_ROW.to_string(),
             selection: Selection::default(),
@@ -350,6 +406,7 @@ impl HexEditorState {
             inspector_source: InspectorSource::Baseline,
             vanilla,
             vanilla_diff: BTreeSet::new(),
+            vanilla_alignment: None,
             comparison_file: None,
             diff_review: false,
             patterns: Vec::new(),
@@ -409,17 +466,17 @@ impl HexEditorState {
             .unwrap_or("Unknown")
             .to_string();
 
-        let (provider, vanilla, row_entropies, error) = match std::fs::read(path) {
-            Ok(bytes) => {
-                let re = compute_row_entropies(&bytes, DEFAULT_BYTES_PER_ROW);
-                (
-                    BufferProvider::from_bytes(bytes.clone()),
-                    Some(bytes),
-                    Some(re),
-                    None,
-                )
+        let (provider, vanilla, row_entropies, error) = match MappedProvider::open(path) {
+            Ok(provider) => {
+                let re = compute_row_entropies_chunks(
+                    provider.chunks(0..provider.len()),
+                    DEFAULT_BYTES_PER_ROW,
+                );
+                // The untouched clone keeps borrowing the mapping.
+                let vanilla = provider.clone();
+                (provider, Some(vanilla), Some(re), None)
             }
-            Err(e) => (BufferProvider::default(), None, None, Some(e.to_string())),
+            Err(e) => (MappedProvider::default(), None, None, Some(e.to_string())),
         };
 
         let unsafe_mode = std::env::var("HEXEDIT_LUA_UNSAFE").as_deref() == Ok("1");
@@ -436,6 +493,8 @@ impl HexEditorState {
             path: path.to_path_buf(),
             name,
             layout,
+            template: None,
+            template_error: None,
             outline: Content::new(),
             outline_all: Vec::new(),
             collapsed_outline: BTreeSet::new(),
@@ -450,6 +509,7 @@ impl HexEditorState {
             inspector_source: InspectorSource::Baseline,
             vanilla,
             vanilla_diff: BTreeSet::new(),
+            vanilla_alignment: None,
             comparison_file: None,
             diff_review: false,
             patterns: Vec::new(),
@@ -503,15 +563,97 @@ impl HexEditorState {
         self.provider.len().saturating_sub(1)
     }
 
-    /// Refresh [`vanilla_diff`] against the current provider contents.
-    /// Cheap (linear scan); call after any in-memory write.
+    /// Length of the buffer the inspector decodes, plus up to `limit` bytes
+    /// of it starting at the cursor.
+    pub fn inspector_window(&self, limit: u64) -> (u64, Cow<'_, [u8]>) {
+        fn window<P: HexProvider + ?Sized>(
+            source: &P,
+            cursor: u64,
+            limit: u64,
+        ) -> (u64, Cow<'_, [u8]>) {
+            let len = source.len();
+            let start = cursor.min(len);
+            let end = start.saturating_add(limit).min(len);
+            (len, source.read_range(start..end))
+        }
+        let cursor = self.selection.cursor;
+        match (self.inspector_source, &self.comparison_file) {
+            (InspectorSource::Baseline, _) => window(&self.provider, cursor, limit),
+            (InspectorSource::Comparison, Some(cf)) => window(cf.data.as_slice(), cursor, limit),
+            (InspectorSource::Comparison, None) => (0, Cow::Borrowed(&[])),
+        }
+    }
+
+    /// Rebuild [`vanilla_diff`] from scratch against the current provider
+    /// contents. Edits go through the incremental
+    /// [`vanilla_diff_after_write`] / [`vanilla_diff_after_insert`] instead.
+    ///
+    /// Same-length buffers use the linear positional scan, which is what
+    /// in-place edits need. Once the file grew or shrank, the buffers are
+    /// aligned first so an insertion does not mark the whole tail.
     pub fn recompute_vanilla_diff(&mut self) {
-        self.vanilla_diff = match &self.vanilla {
-            Some(v) => compute_diff(v, self.provider.as_slice()),
-            None => BTreeSet::new(),
+        let current = &self.provider;
+        let vanilla = self.vanilla.as_ref();
+        self.vanilla_alignment = match vanilla {
+            Some(v) if v.len() != current.len() => Some(StructuralDiff::compute(
+                &v.read_range(0..v.len()),
+                &current.read_range(0..current.len()),
+            )),
+            _ => None,
+        };
+        self.vanilla_diff = match (vanilla, &self.vanilla_alignment) {
+            (Some(v), Some(alignment)) => alignment.new_changes(v, current),
+            (Some(v), None) => compute_diff(v, current),
+            (None, _) => BTreeSet::new(),
         };
     }
 
+    /// Update [`vanilla_diff`] after `len` bytes at `addr` were overwritten.
+    pub fn vanilla_diff_after_write(&mut self, addr: u64, len: u64) {
+        let Some(vanilla) = self.vanilla.as_ref() else {
+            return;
+        };
+        let end = addr.saturating_add(len).min(self.provider.len());
+        if addr >= end {
+            return;
+        }
+        match &mut self.vanilla_alignment {
+            Some(alignment) => {
+                alignment.update(vanilla, &self.provider, addr..end, end - addr);
+                self.vanilla_diff = alignment.new_changes(vanilla, &self.provider);
+            }
+            None => {
+                // Positional comparison: only the written bytes are read.
+                let written = self.provider.read_range(addr..end);
+                let original = vanilla.read_range(addr..end);
+                for (a, (&b, &v)) in (addr..end).zip(written.iter().zip(original.iter())) {
+                    if v == b {
+                        self.vanilla_diff.remove(&a);
+                    } else {
+                        self.vanilla_diff.insert(a);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Update [`vanilla_diff`] after `len` bytes were inserted at `addr`.
+    /// The first insert switches from the positional comparison to an
+    /// alignment seeded from the current positional diff.
+    pub fn vanilla_diff_after_insert(&mut self, addr: u64, len: u64) {
+        let current = &self.provider;
+        let Some(vanilla) = self.vanilla.as_ref() else {
+            return;
+        };
+        let addr = addr.min(current.len());
+        let end = addr.saturating_add(len).min(current.len());
+        let alignment = self
+            .vanilla_alignment
+            .get_or_insert_with(|| StructuralDiff::positional(vanilla.len(), &self.vanilla_diff));
+        alignment.update(vanilla, current, addr..end, 0);
+        self.vanilla_diff = alignment.new_changes(vanilla, current);
+    }
+
     /// Add all addresses in [start, end] range as a new pattern.
     /// Returns the pattern id.
     pub fn add_pattern(&mut self, start: u64, end: u64) -> usize {
@@ -621,6 +763,37 @@ impl HexEditorState {
         self.row_entropies = None;
     }
 
+    /// Keep `template` as the tab's layout source and evaluate it against
+    /// the current buffer. Returns the number of outline nodes. On error the
+    /// existing layout is kept.
+    pub fn apply_template(&mut self, template: BinaryTemplate) -> Result<usize, TemplateError> {
+        self.template = Some(template);
+        self.collapsed_outline.clear();
+        self.refresh_template()
+    }
+
+    /// Re-evaluate the stored template after the buffer changed. Collapsed
+    /// outline branches stay collapsed. On error the last good layout is
+    /// kept and the message goes to [`template_error`](Self::template_error).
+    pub fn refresh_template(&mut self) -> Result<usize, TemplateError> {
+        let Some(template) = &self.template else {
+            return Ok(0);
+        };
+        match template.evaluate(&self.provider) {
+            Ok(layout) => {
+                let nodes = layout.nodes().len();
+                self.template_error = None;
+                self.layout = Some(Box::new(layout));
+                self.rebuild_outline();
+                Ok(nodes)
+            }
+            Err(e) => {
+                self.template_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
     /// Load all `.lua` scripts from a directory into the Lua engine.
     /// Errors are collected and returned; successfully loaded decoders are
     /// available via `lua_engine.entries()`.
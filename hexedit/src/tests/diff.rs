@@ -130,3 +130,35 @@ fn test_diff_drag_on_comparison_side_publishes_side() {
         "drag on the comparison side must publish DiffExtendTo with is_baseline=false, got {messages:?}"
     );
 }
+
+#[test]
+fn test_comparison_with_insert_aligns_and_navigates_by_hunk() {
+    // The comparison has two bytes inserted at 2 and one byte changed at
+    // baseline address 5: only those hunks differ, not the shifted tail.
+    let mut state = make_state(vec![0, 1, 2, 3, 4, 5, 6, 7]);
+    let config = default_config();
+    send(
+        &mut state,
+        &config,
+        HexEditorMessage::ComparisonFileLoaded(Ok((
+            vec![0, 1, 0xAA, 0xBB, 2, 3, 4, 0xFF, 6, 7],
+            "grown.bin".into(),
+        ))),
+    );
+    let cf = state.comparison_file.as_ref().expect("comparison loaded");
+    assert_eq!(cf.diff, BTreeSet::from([2, 5]));
+    // The comparison side marks its own inserted and changed bytes.
+    assert_eq!(cf.comparison_diff, BTreeSet::from([2, 3, 7]));
+    assert_eq!(cf.alignment.changes().count(), 2);
+    assert_eq!(cf.alignment.new_addr(6), Some(8));
+
+    send(&mut state, &config, HexEditorMessage::DiffNavNext);
+    assert_eq!(state.selection.cursor, 2);
+    send(&mut state, &config, HexEditorMessage::DiffNavNext);
+    assert_eq!(state.selection.cursor, 5);
+    // Wraps back to the first hunk.
+    send(&mut state, &config, HexEditorMessage::DiffNavNext);
+    assert_eq!(state.selection.cursor, 2);
+    send(&mut state, &config, HexEditorMessage::DiffNavPrev);
+    assert_eq!(state.selection.cursor, 5);
+}
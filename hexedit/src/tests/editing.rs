@@ -21,7 +21,7 @@ fn test_edit_type_first_nibble() {
     send(&mut state, &config, HexEditorMessage::EditTypeChar('A'));
     assert_eq!(state.edit_mode.as_ref().unwrap().draft, "A");
     // Not yet committed (need 2 chars).
-    assert_eq!(state.provider.as_slice()[0], 0x00);
+    assert_eq!(contents(&state)[0], 0x00);
 }
 
 #[test]
@@ -31,7 +31,7 @@ fn test_edit_second_nibble_commits_and_advances() {
     send(&mut state, &config, HexEditorMessage::BeginEdit(0));
     send(&mut state, &config, HexEditorMessage::EditTypeChar('A'));
     send(&mut state, &config, HexEditorMessage::EditTypeChar('B'));
-    assert_eq!(state.provider.as_slice()[0], 0xAB, "byte should be written");
+    assert_eq!(contents(&state)[0], 0xAB, "byte should be written");
     assert_eq!(state.selection.cursor, 1, "cursor should advance");
     assert!(
         state.edit_mode.is_some(),
@@ -52,7 +52,7 @@ fn test_edit_commit_with_advance() {
         HexEditorMessage::EditCommit { advance: true },
     );
     // Single nibble "F" → 0x0F
-    assert_eq!(state.provider.as_slice()[0], 0x0F);
+    assert_eq!(contents(&state)[0], 0x0F);
     assert_eq!(state.selection.cursor, 1, "cursor should advance");
     assert!(
         state.edit_mode.is_some(),
@@ -72,7 +72,7 @@ fn test_edit_commit_without_advance() {
         HexEditorMessage::EditCommit { advance: false },
     );
     // Single nibble "F" → 0x0F
-    assert_eq!(state.provider.as_slice()[0], 0x0F);
+    assert_eq!(contents(&state)[0], 0x0F);
     assert_eq!(
         state.selection.cursor, 0,
         "cursor should stay at committed addr"
@@ -88,11 +88,7 @@ fn test_edit_cancel_restores_state() {
     send(&mut state, &config, HexEditorMessage::EditTypeChar('0'));
     send(&mut state, &config, HexEditorMessage::EditCancel);
     assert!(state.edit_mode.is_none(), "edit mode should be cancelled");
-    assert_eq!(
-        state.provider.as_slice()[0],
-        0xAA,
-        "original byte must not change"
-    );
+    assert_eq!(contents(&state)[0], 0xAA, "original byte must not change");
 }
 
 #[test]
@@ -130,7 +126,7 @@ fn test_write_bytes_modifies_provider() {
         },
     );
     assert_eq!(
-        state.provider.as_slice(),
+        contents(&state),
         &[0x00, 0x00, 0xDE, 0xAD, 0xBE, 0x00, 0x00, 0x00]
     );
     assert_eq!(state.provider.dirty_count(), 3);
@@ -167,7 +163,7 @@ fn test_edit_commit_advance_at_last_byte_exits_edit_mode() {
     send(&mut state, &config, HexEditorMessage::BeginEdit(0));
     send(&mut state, &config, HexEditorMessage::EditTypeChar('F'));
     send(&mut state, &config, HexEditorMessage::EditTypeChar('F'));
-    assert_eq!(state.provider.as_slice()[0], 0xFF, "byte should be written");
+    assert_eq!(contents(&state)[0], 0xFF, "byte should be written");
     // After second nibble, it auto-commits with advance. Since addr 0 is max_addr,
     // advance should set edit_mode to None.
     assert!(
@@ -191,7 +187,7 @@ fn test_multiple_sequential_edits() {
     // Now at addr 2
     send(&mut state, &config, HexEditorMessage::EditTypeChar('E'));
     send(&mut state, &config, HexEditorMessage::EditTypeChar('F'));
-    assert_eq!(state.provider.as_slice(), &[0xAB, 0xCD, 0xEF, 0x00]);
+    assert_eq!(contents(&state), &[0xAB, 0xCD, 0xEF, 0x00]);
     assert_eq!(state.selection.cursor, 3);
     assert_eq!(state.provider.dirty_count(), 3);
 }
@@ -206,7 +202,7 @@ fn test_text_mode_typing_writes_and_advances() {
     let config = default_config();
     state.write_mode = WriteMode::Ascii;
     send(&mut state, &config, HexEditorMessage::EditTypeChar('H'));
-    assert_eq!(state.provider.as_slice()[0], b'H');
+    assert_eq!(contents(&state)[0], b'H');
     assert_eq!(state.selection.cursor, 1);
 }
 
@@ -225,7 +221,7 @@ fn test_text_mode_backspace_moves_cursor_left() {
         "backspace should move cursor left"
     );
     assert_eq!(
-        state.provider.as_slice()[1],
+        contents(&state)[1],
         b'B',
         "byte at addr 1 should be unchanged"
     );
@@ -271,9 +267,9 @@ fn test_text_mode_delete_writes_zero_and_advances() {
     let config = default_config();
     state.write_mode = WriteMode::Ascii;
     send(&mut state, &config, HexEditorMessage::DeleteByteAtCursor);
-    assert_eq!(state.provider.as_slice()[0], 0x00, "should write 0x00");
+    assert_eq!(contents(&state)[0], 0x00, "should write 0x00");
     assert_eq!(state.selection.cursor, 1, "cursor should advance");
-    assert_eq!(state.provider.as_slice()[1], 0xCD, "other bytes unchanged");
+    assert_eq!(contents(&state)[1], 0xCD, "other bytes unchanged");
 }
 
 #[test]
@@ -282,7 +278,7 @@ fn test_text_mode_delete_at_max_addr_stays_put() {
     let config = default_config();
     state.write_mode = WriteMode::Ascii;
     send(&mut state, &config, HexEditorMessage::DeleteByteAtCursor);
-    assert_eq!(state.provider.as_slice()[0], 0x00);
+    assert_eq!(contents(&state)[0], 0x00);
     assert_eq!(state.selection.cursor, 0, "cursor stays at last byte");
 }
 
@@ -292,11 +288,7 @@ fn test_hex_mode_delete_does_nothing() {
     let config = default_config();
     state.write_mode = WriteMode::Hex;
     send(&mut state, &config, HexEditorMessage::DeleteByteAtCursor);
-    assert_eq!(
-        state.provider.as_slice()[0],
-        0xAB,
-        "byte unchanged (hex mode)"
-    );
+    assert_eq!(contents(&state)[0], 0xAB, "byte unchanged (hex mode)");
 }
 
 #[test]
@@ -310,7 +302,7 @@ fn test_text_mode_unencodable_shows_status() {
         !state.status_msg.is_empty(),
         "should show status message for unencodable char"
     );
-    assert_eq!(state.provider.as_slice()[0], 0x00, "byte should not change");
+    assert_eq!(contents(&state)[0], 0x00, "byte should not change");
     assert_eq!(state.selection.cursor, 0, "cursor should not advance");
 }
 
@@ -350,5 +342,5 @@ fn test_write_bytes_empty_slice() {
         0,
         "empty write should not dirty"
     );
-    assert_eq!(state.provider.as_slice(), &[0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(contents(&state), &[0x00, 0x00, 0x00, 0x00]);
 }
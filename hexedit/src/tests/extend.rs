@@ -85,7 +85,7 @@ fn commit_extend_inserts_at_right_clicked_byte() {
         HexEditorMessage::SetExtendPattern("FF".into()),
     );
     send(&mut state, &config, HexEditorMessage::CommitExtend);
-    assert_eq!(state.provider.as_slice(), &[1, 2, 0xFF, 3, 4, 5]);
+    assert_eq!(contents(&state), &[1, 2, 0xFF, 3, 4, 5]);
     // The cursor parks on the inserted byte at commit time.
     assert_eq!(state.selection.cursor, 2);
 }
@@ -110,7 +110,7 @@ fn commit_extend_rejects_clicked_past_eof() {
     );
     send(&mut state, &config, HexEditorMessage::CommitExtend);
     assert_eq!(state.status_msg, "Cannot extend: clicked past end of file");
-    assert_eq!(state.provider.as_slice(), &[1, 2, 3], "nothing inserted");
+    assert_eq!(contents(&state), &[1, 2, 3], "nothing inserted");
     assert!(state.extend_dialog.is_some(), "dialog stays open");
 }
 
@@ -141,10 +141,7 @@ fn commit_extend_inserts_mid_file() {
     let mut state = make_state(vec![1, 2, 3, 4, 5]);
     let config = default_config();
     commit_extend(&mut state, &config, 2, "3", "FF");
-    assert_eq!(
-        state.provider.as_slice(),
-        &[1, 2, 0xFF, 0xFF, 0xFF, 3, 4, 5]
-    );
+    assert_eq!(contents(&state), &[1, 2, 0xFF, 0xFF, 0xFF, 3, 4, 5]);
     assert!(state.extend_dialog.is_none(), "dialog closes on success");
     assert_eq!(state.provider.len(), 8);
 }
@@ -154,7 +151,7 @@ fn commit_extend_inserts_at_zero() {
     let mut state = make_state(vec![1, 2, 3]);
     let config = default_config();
     commit_extend(&mut state, &config, 0, "2", "AA");
-    assert_eq!(state.provider.as_slice(), &[0xAA, 0xAA, 1, 2, 3]);
+    assert_eq!(contents(&state), &[0xAA, 0xAA, 1, 2, 3]);
 }
 
 #[test]
@@ -163,7 +160,7 @@ fn commit_extend_appends_at_eof() {
     let config = default_config();
     // addr == len is a valid append (no shift).
     commit_extend(&mut state, &config, 5, "2", "AA");
-    assert_eq!(state.provider.as_slice(), &[1, 2, 3, 4, 5, 0xAA, 0xAA]);
+    assert_eq!(contents(&state), &[1, 2, 3, 4, 5, 0xAA, 0xAA]);
 }
 
 #[test]
@@ -187,7 +184,7 @@ fn commit_extend_cursor_past_eof_shows_status() {
         state.status_msg,
         "Cannot extend: cursor is past end of file"
     );
-    assert_eq!(state.provider.as_slice(), &[1, 2, 3], "nothing inserted");
+    assert_eq!(contents(&state), &[1, 2, 3], "nothing inserted");
     assert!(state.extend_dialog.is_some(), "dialog stays open");
 }
 
@@ -197,7 +194,7 @@ fn commit_extend_repeats_pattern_across_count() {
     let config = default_config();
     commit_extend(&mut state, &config, 0, "7", "01 02");
     assert_eq!(
-        state.provider.as_slice(),
+        contents(&state),
         &[1, 2, 1, 2, 1, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0]
     );
 }
@@ -207,7 +204,7 @@ fn commit_extend_truncates_long_pattern_to_count() {
     let mut state = make_state(vec![0, 0, 0]);
     let config = default_config();
     commit_extend(&mut state, &config, 0, "2", "AA BB CC");
-    assert_eq!(state.provider.as_slice(), &[0xAA, 0xBB, 0, 0, 0]);
+    assert_eq!(contents(&state), &[0xAA, 0xBB, 0, 0, 0]);
 }
 
 #[test]
@@ -296,7 +293,7 @@ fn commit_extend_invalid_count_keeps_dialog_and_sets_error() {
     assert!(state.extend_dialog.is_some(), "dialog stays open");
     let dlg = state.extend_dialog.as_ref().unwrap();
     assert_eq!(dlg.error.as_deref(), Some("Count must be at least 1 byte"));
-    assert_eq!(state.provider.as_slice(), &[1, 2, 3], "nothing inserted");
+    assert_eq!(contents(&state), &[1, 2, 3], "nothing inserted");
 }
 
 #[test]
@@ -318,7 +315,7 @@ fn commit_extend_invalid_pattern_keeps_dialog_and_sets_error() {
     assert!(state.extend_dialog.is_some(), "dialog stays open");
     let dlg = state.extend_dialog.as_ref().unwrap();
     assert_eq!(dlg.error.as_deref(), Some("Invalid hex input: \"XYZ\""));
-    assert_eq!(state.provider.as_slice(), &[1, 2, 3], "nothing inserted");
+    assert_eq!(contents(&state), &[1, 2, 3], "nothing inserted");
 }
 
 #[test]
@@ -329,25 +326,51 @@ fn close_extend_dismisses() {
     assert!(state.extend_dialog.is_some());
     send(&mut state, &config, HexEditorMessage::CloseExtend);
     assert!(state.extend_dialog.is_none());
-    assert_eq!(state.provider.as_slice(), &[1, 2, 3], "no bytes inserted");
+    assert_eq!(contents(&state), &[1, 2, 3], "no bytes inserted");
 }
 
 #[test]
-fn commit_extend_marks_tail_vanilla_diff_and_dirty() {
+fn commit_extend_marks_inserted_vanilla_diff_and_dirty() {
     let mut state = make_state(vec![1, 2, 3, 4, 5]);
-    state.vanilla = Some(vec![1, 2, 3, 4, 5]);
+    state.vanilla = Some(MappedProvider::from_bytes(vec![1, 2, 3, 4, 5]));
     let config = default_config();
     commit_extend(&mut state, &config, 2, "2", "00");
-    // Inserted bytes (2,3) differ from vanilla, and the shifted tail (old
-    // 3,4,5 now at 4,5,6) differs too — the whole tail is marked.
+    // Only the inserted bytes (2,3) differ from vanilla; the shifted tail
+    // (old 3,4,5 now at 4,5,6) is aligned back onto its vanilla bytes.
     assert_eq!(
         state.vanilla_diff,
-        (2..7).collect::<BTreeSet<u64>>(),
-        "tail at/after the insert point must differ from vanilla"
+        BTreeSet::from([2, 3]),
+        "only the inserted bytes may differ from vanilla"
     );
-    // The dirty set covers the inserted offsets.
-    assert!(state.provider.dirty().contains(&2));
-    assert!(state.provider.dirty().contains(&3));
+    // The dirty ranges cover the inserted offsets.
+    assert!(state.provider.is_dirty(2));
+    assert!(state.provider.is_dirty(3));
+}
+
+#[test]
+fn writes_after_extend_update_the_alignment_in_place() {
+    let mut state = make_state(vec![1, 2, 3, 4, 5]);
+    state.vanilla = Some(MappedProvider::from_bytes(vec![1, 2, 3, 4, 5]));
+    let config = default_config();
+    commit_extend(&mut state, &config, 2, "2", "00");
+    let write = |state: &mut HexEditorState, addr, byte| {
+        send(
+            state,
+            &config,
+            HexEditorMessage::WriteBytes {
+                addr,
+                bytes: vec![byte],
+            },
+        );
+    };
+    write(&mut state, 6, 0xEE);
+    assert_eq!(state.vanilla_diff, BTreeSet::from([2, 3, 6]));
+    // Restoring the vanilla byte clears its mark without a full re-diff.
+    write(&mut state, 6, 5);
+    assert_eq!(state.vanilla_diff, BTreeSet::from([2, 3]));
+    let incremental = state.vanilla_diff.clone();
+    state.recompute_vanilla_diff();
+    assert_eq!(state.vanilla_diff, incremental);
 }
 
 #[test]
@@ -368,7 +391,7 @@ fn commit_extend_twice_accumulates() {
     let mut state = make_state(vec![1, 2, 3, 4, 5]);
     let config = default_config();
     commit_extend(&mut state, &config, 2, "2", "AA");
-    assert_eq!(state.provider.as_slice(), &[1, 2, 0xAA, 0xAA, 3, 4, 5]);
+    assert_eq!(contents(&state), &[1, 2, 0xAA, 0xAA, 3, 4, 5]);
     assert_eq!(
         state.selection.cursor, 3,
         "selection covers first inserted range"
@@ -376,10 +399,7 @@ fn commit_extend_twice_accumulates() {
     // Second extend at the same addr inserts before the previously inserted
     // bytes — they shift forward along with the tail.
     commit_extend(&mut state, &config, 2, "1", "BB");
-    assert_eq!(
-        state.provider.as_slice(),
-        &[1, 2, 0xBB, 0xAA, 0xAA, 3, 4, 5]
-    );
+    assert_eq!(contents(&state), &[1, 2, 0xBB, 0xAA, 0xAA, 3, 4, 5]);
     assert_eq!(state.selection.anchor, 2);
     assert_eq!(
         state.selection.cursor, 2,
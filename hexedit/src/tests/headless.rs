@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::headless::run_script;
+
+static DIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Write `script` and `data` into a fresh temp dir; returns (script, file).
+fn fixture(name: &str, script: &str, data: &[u8]) -> (PathBuf, PathBuf) {
+    let counter = DIR_COUNTER.fetch_add(1, Ordering::SeqCst);
+    let dir = std::env::temp_dir()
+        .join("hexedit_headless_test")
+        .join(name)
+        .join(counter.to_string());
+    std::fs::create_dir_all(&dir).expect("create temp dir for headless test");
+    let script_path = dir.join("script.lua");
+    let file_path = dir.join("data.bin");
+    std::fs::write(&script_path, script).expect("write headless test script");
+    std::fs::write(&file_path, data).expect("write headless test data");
+    (script_path, file_path)
+}
+
+#[test]
+fn test_headless_typed_read_write_and_save() {
+    let script = r#"
+        assert(file:len() == 8)
+        assert(file:get("u16", 0) == 0x0201)
+        assert(file:get("u16be", 0) == 0x0102)
+        assert(file:get("i8", 7) == -1)
+        file:set("i32", 2, -2)
+        file:set_string(6, 2, "A")
+        assert(file:string(6, 2) == "A")
+        assert(not pcall(function() file:set("u8", 0, 300) end))
+        assert(file:save())
+    "#;
+    let (script, file) = fixture("typed", script, &[1, 2, 0, 0, 0, 0, 0, 0xFF]);
+    let report = run_script(&script, &file, false, false).unwrap();
+    assert!(report.saved);
+    assert_eq!(report.changed_bytes, 6);
+    assert_eq!(
+        std::fs::read(&file).unwrap(),
+        vec![1, 2, 0xFE, 0xFF, 0xFF, 0xFF, b'A', 0]
+    );
+}
+
+#[test]
+fn test_headless_search_extend_and_dry_run() {
+    let script = r#"
+        local hits = file:search("AB CD")
+        assert(#hits == 2 and hits[1] == 1 and hits[2] == 4, "hex search")
+        assert(#file:search("7", { mode = "decimal", width = 1 }) == 1)
+        file:insert(0, "\0")
+        file:extend(3, "\x11\x22")
+        assert(file:len() == 11)
+        assert(file:read(8, 3) == "\x11\x22\x11")
+        assert(file:add_pattern(1, 2, "marker") == 0)
+        assert(file:patterns()[1].annotation == "marker")
+        assert(file:save() == false)
+    "#;
+    let data = [0, 0xAB, 0xCD, 7, 0xAB, 0xCD, 0];
+    let (script, file) = fixture("search", script, &data);
+    let report = run_script(&script, &file, true, false).unwrap();
+    assert!(!report.saved);
+    assert_eq!(std::fs::read(&file).unwrap(), data);
+}
+
+#[test]
+fn test_headless_reports_out_of_range_reads() {
+    let (script, file) = fixture("error", "file:get(\"u32\", 2)", &[0; 4]);
+    let err = run_script(&script, &file, false, false).unwrap_err();
+    assert!(err.contains("past the end"), "unexpected error: {err}");
+}
+
+#[test]
+fn test_headless_paths_stay_next_to_script_and_file() {
+    let script = r#"
+        assert(file:add_pattern(0, 1, "head") == 0)
+        file:save_patterns("patterns.json")
+        assert(file:load_patterns("patterns.json") == 1)
+        assert(not pcall(function() file:save_patterns("../escape.json") end))
+        assert(not pcall(function() file:load_patterns("/etc/hostname") end))
+        assert(not pcall(function() file:template("../x.bt") end))
+        assert(not pcall(function() file:save("../data.bin") end))
+        assert(file:save("copy.bin"))
+    "#;
+    let (script, file) = fixture("paths", script, &[1, 2]);
+    let report = run_script(&script, &file, false, false).unwrap();
+    assert!(report.saved);
+    let dir = script.parent().unwrap();
+    assert!(dir.join("patterns.json").exists());
+    assert!(!dir.parent().unwrap().join("escape.json").exists());
+    assert_eq!(std::fs::read(dir.join("copy.bin")).unwrap(), vec![1, 2]);
+}
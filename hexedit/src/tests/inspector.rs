@@ -89,7 +89,7 @@ fn test_inspector_edit_commit() {
         state.inspector_edit.is_none(),
         "modal should close after commit"
     );
-    assert_eq!(state.provider.as_slice()[0], 255);
+    assert_eq!(contents(&state)[0], 255);
 }
 
 #[test]
@@ -108,7 +108,7 @@ fn test_inspector_edit_cancel() {
         "modal should close on cancel"
     );
     assert_eq!(
-        state.provider.as_slice()[0],
+        contents(&state)[0],
         0x00,
         "original data should be unchanged"
     );
@@ -323,7 +323,7 @@ fn test_inspector_edit_with_hex_prefix() {
         HexEditorMessage::SetInspectorDraft("0xFF".into()),
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
-    assert_eq!(state.provider.as_slice()[0], 0xFF);
+    assert_eq!(contents(&state)[0], 0xFF);
 }
 
 #[test]
@@ -338,7 +338,7 @@ fn test_inspector_edit_i8() {
         HexEditorMessage::SetInspectorDraft("-128".into()),
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
-    assert_eq!(state.provider.as_slice()[0], 0x80);
+    assert_eq!(contents(&state)[0], 0x80);
 }
 
 #[test]
@@ -352,7 +352,7 @@ fn test_inspector_edit_u16() {
         HexEditorMessage::SetInspectorDraft("0x1234".into()),
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
-    assert_eq!(state.provider.as_slice()[0..2], [0x34, 0x12]);
+    assert_eq!(contents(&state)[0..2], [0x34, 0x12]);
 }
 
 #[test]
@@ -366,7 +366,7 @@ fn test_inspector_edit_i16() {
         HexEditorMessage::SetInspectorDraft("-1".into()),
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
-    assert_eq!(state.provider.as_slice()[0..2], [0xFF, 0xFF]);
+    assert_eq!(contents(&state)[0..2], [0xFF, 0xFF]);
 }
 
 #[test]
@@ -381,7 +381,7 @@ fn test_inspector_edit_u32() {
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
     // 305419896 = 0x12345678 in LE
-    assert_eq!(state.provider.as_slice()[0..4], [0x78, 0x56, 0x34, 0x12]);
+    assert_eq!(contents(&state)[0..4], [0x78, 0x56, 0x34, 0x12]);
 }
 
 #[test]
@@ -396,7 +396,7 @@ fn test_inspector_edit_i32() {
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
     // -128 as i32 LE = [0x80, 0xFF, 0xFF, 0xFF]
-    assert_eq!(state.provider.as_slice()[0..4], [0x80, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(contents(&state)[0..4], [0x80, 0xFF, 0xFF, 0xFF]);
 }
 
 #[test]
@@ -411,7 +411,7 @@ fn test_inspector_edit_u64() {
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
     assert_eq!(
-        state.provider.as_slice(),
+        contents(&state),
         &[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]
     );
 }
@@ -427,7 +427,7 @@ fn test_inspector_edit_i64() {
         HexEditorMessage::SetInspectorDraft("-1".into()),
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
-    assert_eq!(state.provider.as_slice(), &[0xFF; 8]);
+    assert_eq!(contents(&state), &[0xFF; 8]);
 }
 
 #[test]
@@ -442,10 +442,10 @@ fn test_inspector_edit_f32() {
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
     let v = f32::from_le_bytes([
-        state.provider.as_slice()[0],
-        state.provider.as_slice()[1],
-        state.provider.as_slice()[2],
-        state.provider.as_slice()[3],
+        contents(&state)[0],
+        contents(&state)[1],
+        contents(&state)[2],
+        contents(&state)[3],
     ]);
     assert!((v - 1.5).abs() < f32::EPSILON);
 }
@@ -461,7 +461,7 @@ fn test_inspector_edit_f64() {
         HexEditorMessage::SetInspectorDraft("3.14159".into()),
     );
     send(&mut state, &config, HexEditorMessage::CommitInspectorEdit);
-    let bytes = state.provider.as_slice();
+    let bytes = contents(&state);
     let v = f64::from_le_bytes([
         bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
     ]);
@@ -496,20 +496,11 @@ fn test_inspector_displays_cstr_for_printable() {
 
 fn state_with_comparison(baseline: Vec<u8>, comparison: Vec<u8>) -> HexEditorState {
     let mut state = make_state(baseline);
-    let diff = state
-        .provider
-        .as_slice()
-        .iter()
-        .zip(comparison.iter())
-        .enumerate()
-        .filter(|(_, (a, b))| a != b)
-        .map(|(i, _)| i as u64)
-        .collect();
-    state.comparison_file = Some(ComparisonFile {
-        name: "other.bin".into(),
-        data: comparison,
-        diff,
-    });
+    state.comparison_file = Some(ComparisonFile::new(
+        "other.bin".into(),
+        comparison,
+        contents(&state),
+    ));
     state
 }
 
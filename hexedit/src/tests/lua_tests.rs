@@ -536,12 +536,14 @@ return { name = "from_dir_b", min_size = 1, decode = function(b) return "B" end
         path: std::path::PathBuf::from("test.bin"),
         name: "test.bin".to_string(),
         layout: None,
+        template: None,
+        template_error: None,
         outline: gui_widgets::sweeten::list::Content::new(),
         outline_all: Vec::new(),
         collapsed_outline: std::collections::BTreeSet::new(),
         panes,
         pane_focus,
-        provider: crate::provider::BufferProvider::from_bytes(vec![0x00]),
+        provider: crate::MappedProvider::from_bytes(vec![0x00]),
         bytes_per_row: 16,
         bpr_input: "16".to_string(),
         selection: crate::selection::Selection::single(0),
@@ -550,6 +552,7 @@ return { name = "from_dir_b", min_size = 1, decode = function(b) return "B" end
         inspector_source: crate::state::InspectorSource::Baseline,
         vanilla: None,
         vanilla_diff: std::collections::BTreeSet::new(),
+        vanilla_alignment: None,
         comparison_file: None,
         diff_review: false,
         patterns: Vec::new(),
@@ -611,12 +614,14 @@ fn test_load_lua_scripts_nonexistent_dir_returns_no_errors() {
         path: std::path::PathBuf::from("test.bin"),
         name: "test.bin".to_string(),
         layout: None,
+        template: None,
+        template_error: None,
         outline: gui_widgets::sweeten::list::Content::new(),
         outline_all: Vec::new(),
         collapsed_outline: std::collections::BTreeSet::new(),
         panes,
         pane_focus,
-        provider: crate::provider::BufferProvider::from_bytes(vec![0x00]),
+        provider: crate::MappedProvider::from_bytes(vec![0x00]),
         bytes_per_row: 16,
         bpr_input: "16".to_string(),
         selection: crate::selection::Selection::single(0),
@@ -625,6 +630,7 @@ fn test_load_lua_scripts_nonexistent_dir_returns_no_errors() {
         inspector_source: crate::state::InspectorSource::Baseline,
         vanilla: None,
         vanilla_diff: std::collections::BTreeSet::new(),
+        vanilla_alignment: None,
         comparison_file: None,
         diff_review: false,
         patterns: Vec::new(),
@@ -717,12 +723,14 @@ return {
         path: std::path::PathBuf::from("test.bin"),
         name: "test.bin".to_string(),
         layout: None,
+        template: None,
+        template_error: None,
         outline: gui_widgets::sweeten::list::Content::new(),
         outline_all: Vec::new(),
         collapsed_outline: std::collections::BTreeSet::new(),
         panes,
         pane_focus,
-        provider: crate::provider::BufferProvider::from_bytes(vec![0xAB]),
+        provider: crate::MappedProvider::from_bytes(vec![0xAB]),
         bytes_per_row: 16,
         bpr_input: "16".to_string(),
         selection: crate::selection::Selection::single(0),
@@ -731,6 +739,7 @@ return {
         inspector_source: crate::state::InspectorSource::Baseline,
         vanilla: None,
         vanilla_diff: std::collections::BTreeSet::new(),
+        vanilla_alignment: None,
         comparison_file: None,
         diff_review: false,
         patterns: Vec::new(),
@@ -17,10 +17,10 @@ use crate::ui::theme::{DARK_THEME, ThemeVariant};
 use gui_widgets::components::paragraph_cache::ParagraphCache;
 
 use crate::LuaScriptEngine;
+use crate::MappedProvider;
 use crate::config::HexEditorConfig;
 use crate::domain::write_mode::WriteMode;
 use crate::message::HexEditorMessage;
-use crate::provider::BufferProvider;
 use crate::provider::HexProvider;
 use crate::search::SearchState;
 use crate::selection::{NavDir, Selection};
@@ -40,12 +40,14 @@ pub fn make_state(data: Vec<u8>) -> HexEditorState {
         path: PathBuf::from("test.bin"),
         name: "test.bin".to_string(),
         layout: None,
+        template: None,
+        template_error: None,
         outline: gui_widgets::sweeten::list::Content::new(),
         outline_all: Vec::new(),
         collapsed_outline: BTreeSet::new(),
         panes,
         pane_focus,
-        provider: BufferProvider::from_bytes(data),
+        provider: MappedProvider::from_bytes(data),
         bytes_per_row: 16,
         bpr_input: "16".to_string(),
         selection: Selection::single(0),
@@ -54,6 +56,7 @@ pub fn make_state(data: Vec<u8>) -> HexEditorState {
         inspector_source: InspectorSource::Baseline,
         vanilla: None,
         vanilla_diff: BTreeSet::new(),
+        vanilla_alignment: None,
         comparison_file: None,
         diff_review: false,
         patterns: Vec::new(),
@@ -108,6 +111,11 @@ pub fn send(state: &mut HexEditorState, config: &HexEditorConfig, msg: HexEditor
     let _task = update(state, config, msg);
 }
 
+// Helper: the whole edited buffer, stitched across pieces.
+pub fn contents(state: &HexEditorState) -> Vec<u8> {
+    state.provider.read_to_vec(0..state.provider.len())
+}
+
 // ============================================================================
 // Error state
 // ============================================================================
@@ -224,6 +232,96 @@ fn test_outline_collapses_descendants_without_removing_siblings() {
     assert_eq!(state.outline.len(), 2);
 }
 
+#[test]
+fn test_apply_template_builds_outline_from_buffer() {
+    use crate::domain::template::BinaryTemplate;
+
+    let template =
+        BinaryTemplate::parse("struct Item { u8 id; }\nu8 count;\nItem items[count];").unwrap();
+    let mut state = make_state(vec![2, 7, 9, 0xFF]);
+    assert_eq!(state.apply_template(template), Ok(6));
+    assert_eq!(state.outline.len(), 6);
+    let field = state.layout.as_deref().unwrap().field_at(2, 4).unwrap();
+    assert_eq!((field.name, field.record_index), ("id", 1));
+
+    let broken = BinaryTemplate::parse("u32 count;\nu32 values[count];").unwrap();
+    assert!(state.apply_template(broken).is_err());
+    assert_eq!(
+        state.outline.len(),
+        6,
+        "failed template keeps the old layout"
+    );
+    assert!(state.template_error.is_some());
+}
+
+#[test]
+fn test_template_refreshes_after_edits_settle() {
+    use crate::domain::template::BinaryTemplate;
+
+    let template = BinaryTemplate::parse("u8 count;\nu8 items[count];").unwrap();
+    let mut state = make_state(vec![1, 7, 9, 0xFF]);
+    let config = default_config();
+    state.apply_template(template).unwrap();
+    let items_len = |state: &HexEditorState| {
+        let field = state.layout.as_deref().unwrap().field_at(1, 4).unwrap();
+        field.range.end - field.range.start
+    };
+    assert_eq!(items_len(&state), 1);
+
+    send(
+        &mut state,
+        &config,
+        HexEditorMessage::WriteBytes {
+            addr: 0,
+            bytes: vec![3],
+        },
+    );
+    let stale = state.provider.revision();
+    send(
+        &mut state,
+        &config,
+        HexEditorMessage::WriteBytes {
+            addr: 0,
+            bytes: vec![2],
+        },
+    );
+    send(
+        &mut state,
+        &config,
+        HexEditorMessage::RefreshTemplate(stale),
+    );
+    assert_eq!(items_len(&state), 1, "a superseded timer does nothing");
+
+    let current = state.provider.revision();
+    send(
+        &mut state,
+        &config,
+        HexEditorMessage::RefreshTemplate(current),
+    );
+    assert_eq!(items_len(&state), 2);
+
+    // Counts past the end fail; the last good layout and the error remain.
+    send(
+        &mut state,
+        &config,
+        HexEditorMessage::WriteBytes {
+            addr: 0,
+            bytes: vec![9],
+        },
+    );
+    let current = state.provider.revision();
+    send(
+        &mut state,
+        &config,
+        HexEditorMessage::RefreshTemplate(current),
+    );
+    assert_eq!(items_len(&state), 2);
+    assert!(
+        crate::ui::view::footer::format_footer(&state).contains("template: "),
+        "the error shows in the footer"
+    );
+}
+
 pub mod pane_grid;
 
 pub mod diff;
@@ -241,5 +339,7 @@ pub mod search;
 pub mod settings;
 pub mod toolbar;
 
+#[cfg(feature = "lua")]
+pub mod headless;
 #[cfg(feature = "lua")]
 pub mod lua_tests;
@@ -60,7 +60,7 @@ fn test_saved_into_recording_error() {
 #[test]
 fn test_vanilla_diff_updated_on_write() {
     let mut state = make_state(vec![0x00, 0x00, 0x00]);
-    state.vanilla = Some(vec![0x00, 0x00, 0x00]);
+    state.vanilla = Some(MappedProvider::from_bytes(vec![0x00, 0x00, 0x00]));
     let config = default_config();
     send(
         &mut state,
@@ -2,7 +2,9 @@ use iced::widget::pane_grid;
 use iced::{Task, clipboard};
 
 use crate::config::HexEditorConfig;
-use crate::domain::byte_stats::{compute_row_entropies, compute_statistics};
+use crate::domain::byte_stats::{
+    compute_row_entropies_chunks, compute_statistics, compute_statistics_chunks,
+};
 use crate::domain::export_config::ExportConfig;
 use crate::domain::panel::HexPanel;
 use crate::domain::write_mode::{encode_text, is_text_mode, remap_write_mode};
@@ -29,20 +31,9 @@ const PAGE_ROWS: u64 = 24;
 /// entry (largest is u128 + string at 18 bytes) with plenty of headroom.
 const INSPECTOR_READ_LIMIT: u64 = 64;
 
-/// Returns `(length, bytes)` of the buffer the inspector currently decodes.
-fn inspector_source_bytes(state: &crate::HexEditorState) -> (u64, &[u8]) {
-    match state.inspector_source {
-        InspectorSource::Baseline => (state.provider.len(), state.provider.as_slice()),
-        InspectorSource::Comparison => {
-            let data = state
-                .comparison_file
-                .as_ref()
-                .map(|cf| cf.data.as_slice())
-                .unwrap_or(&[]);
-            (data.len() as u64, data)
-        }
-    }
-}
+/// Pause after the last edit before the binary template is re-evaluated, so
+/// typing a run of bytes evaluates it once.
+const TEMPLATE_REFRESH_DELAY: std::time::Duration = std::time::Duration::from_millis(300);
 
 /// Parse a bytes-per-row draft from the settings modal's custom text input.
 ///
@@ -60,6 +51,25 @@ pub fn update(
     state: &mut crate::HexEditorState,
     config: &HexEditorConfig,
     message: HexEditorMessage,
+) -> Task<HexEditorMessage> {
+    let revision = state.provider.revision();
+    let task = handle(state, config, message);
+    let edited = state.provider.revision();
+    if state.template.is_none() || edited == revision {
+        return task;
+    }
+    // Restarted by every edit; only the timer of the last one finds the
+    // buffer at its revision.
+    let refresh = Task::perform(tokio::time::sleep(TEMPLATE_REFRESH_DELAY), move |()| {
+        HexEditorMessage::RefreshTemplate(edited)
+    });
+    Task::batch([task, refresh])
+}
+
+fn handle(
+    state: &mut crate::HexEditorState,
+    config: &HexEditorConfig,
+    message: HexEditorMessage,
 ) -> Task<HexEditorMessage> {
     let max_addr = state.max_addr();
     match message {
@@ -108,7 +118,8 @@ pub fn update(
                 // Recompute row entropies immediately so the gutter band stays
                 // visible after a row-width change.
                 if !state.provider.is_empty() {
-                    state.row_entropies = Some(compute_row_entropies(state.provider.as_slice(), n));
+                    let chunks = state.provider.chunks(0..state.provider.len());
+                    state.row_entropies = Some(compute_row_entropies_chunks(chunks, n));
                 }
             }
         }
@@ -135,6 +146,12 @@ pub fn update(
             state.refresh_active_patterns();
         }
         HexEditorMessage::ToggleOutline(id) => state.toggle_outline(id),
+        HexEditorMessage::RefreshTemplate(revision) => {
+            if revision == state.provider.revision() {
+                // Failures are kept in `template_error` for the footer.
+                let _ = state.refresh_template();
+            }
+        }
         HexEditorMessage::ExtendTo(addr) => {
             state.selection.extend(addr, max_addr);
             state.refresh_active_patterns();
@@ -181,7 +198,7 @@ pub fn update(
                 }
                 let addr = state.selection.cursor;
                 state.provider.write(addr, &encoded);
-                state.recompute_vanilla_diff();
+                state.vanilla_diff_after_write(addr, encoded.len() as u64);
                 let next = addr.saturating_add(encoded.len() as u64).min(max_addr);
                 state.selection.select(next, max_addr);
             } else {
@@ -200,7 +217,7 @@ pub fn update(
                 if let Some((addr, byte)) = staged {
                     if let Some(byte) = byte {
                         state.provider.write(addr, &[byte]);
-                        state.recompute_vanilla_diff();
+                        state.vanilla_diff_after_write(addr, 1);
                     }
                     let next = (addr + 1).min(max_addr);
                     if next == addr {
@@ -228,7 +245,7 @@ pub fn update(
             if let Some(edit) = state.edit_mode.take() {
                 if let Some(byte) = edit.staged_byte() {
                     state.provider.write(edit.addr, &[byte]);
-                    state.recompute_vanilla_diff();
+                    state.vanilla_diff_after_write(edit.addr, 1);
                 }
                 if advance {
                     let next = (edit.addr + 1).min(max_addr);
@@ -246,7 +263,7 @@ pub fn update(
             if !state.provider.is_empty() && is_text_mode(state.write_mode) {
                 let addr = state.selection.cursor;
                 state.provider.write(addr, &[0x00]);
-                state.recompute_vanilla_diff();
+                state.vanilla_diff_after_write(addr, 1);
                 let next = (addr + 1).min(max_addr);
                 state.selection.select(next, max_addr);
             }
@@ -255,7 +272,7 @@ pub fn update(
         HexEditorMessage::WriteBytes { addr, bytes } => {
             if !state.provider.is_empty() {
                 state.provider.write(addr, &bytes);
-                state.recompute_vanilla_diff();
+                state.vanilla_diff_after_write(addr, bytes.len() as u64);
             }
         }
 
@@ -271,20 +288,18 @@ pub fn update(
 
         HexEditorMessage::CopyInspectorValue(idx) => {
             let cursor = state.selection.cursor;
-            let (len, src) = inspector_source_bytes(state);
-            let read_end = (cursor + INSPECTOR_READ_LIMIT).min(len);
-            let start = (cursor as usize).min(src.len());
-            let end = (read_end as usize).min(src.len()).max(start);
-            let bytes = &src[start..end];
             let entry = if idx < ENTRIES.len() {
                 ENTRIES.get(idx)
             } else {
                 config.extra_entries.get(idx - ENTRIES.len())
             };
-            if let Some(entry) = entry
-                && len - cursor >= entry.min_size as u64
-            {
-                let decoded = (entry.decode)(bytes);
+            let decoded = {
+                let (len, bytes) = state.inspector_window(INSPECTOR_READ_LIMIT);
+                entry
+                    .filter(|entry| len - cursor >= entry.min_size as u64)
+                    .map(|entry| (entry.decode)(&bytes))
+            };
+            if let Some(decoded) = decoded {
                 state.notify(format!("Copied: {decoded}"));
                 return clipboard::write(decoded).map(|_| HexEditorMessage::ClipboardWriteResult);
             }
@@ -312,8 +327,10 @@ pub fn update(
             if cursor + entry.min_size as u64 > len {
                 return Task::none();
             }
-            let bytes = state.provider.read(cursor..cursor + entry.min_size as u64);
-            let initial = (entry.decode)(bytes);
+            let bytes = state
+                .provider
+                .read_to_vec(cursor..cursor + entry.min_size as u64);
+            let initial = (entry.decode)(&bytes);
             let initial = initial
                 .split_once(' ')
                 .map(|(lhs, _)| lhs.to_string())
@@ -350,7 +367,7 @@ pub fn update(
                 Ok(bytes) => {
                     let addr = ie.addr;
                     state.provider.write(addr, &bytes);
-                    state.recompute_vanilla_diff();
+                    state.vanilla_diff_after_write(addr, bytes.len() as u64);
                     state.inspector_edit = None;
                 }
                 Err(msg) => {
@@ -396,12 +413,14 @@ pub fn update(
                 state.show_stats = true;
                 // If stats haven't been computed yet, trigger analysis.
                 if state.file_stats.is_none() && !state.provider.is_empty() {
-                    let bytes = state.provider.as_slice().to_vec();
+                    // The snapshot shares the mapping; only the edits are copied.
+                    let snapshot = state.provider.clone();
                     let bpr = state.bytes_per_row;
                     return Task::perform(
                         async move {
-                            let stats = compute_statistics(&bytes);
-                            let entropies = compute_row_entropies(&bytes, bpr);
+                            let all = 0..snapshot.len();
+                            let stats = compute_statistics_chunks(snapshot.chunks(all.clone()));
+                            let entropies = compute_row_entropies_chunks(snapshot.chunks(all), bpr);
                             HexEditorMessage::FileAndRowEntropiesComputed(
                                 Box::new(stats),
                                 Box::new(entropies),
@@ -414,12 +433,13 @@ pub fn update(
         }
         HexEditorMessage::AnalyzeFile => {
             if !state.provider.is_empty() {
-                let bytes = state.provider.as_slice().to_vec();
+                let snapshot = state.provider.clone();
                 let bpr = state.bytes_per_row;
                 return Task::perform(
                     async move {
-                        let stats = compute_statistics(&bytes);
-                        let entropies = compute_row_entropies(&bytes, bpr);
+                        let all = 0..snapshot.len();
+                        let stats = compute_statistics_chunks(snapshot.chunks(all.clone()));
+                        let entropies = compute_row_entropies_chunks(snapshot.chunks(all), bpr);
                         HexEditorMessage::FileAndRowEntropiesComputed(
                             Box::new(stats),
                             Box::new(entropies),
@@ -433,7 +453,7 @@ pub fn update(
             if !state.provider.is_empty() && !state.selection.is_single() {
                 let start = state.selection.start();
                 let end = state.selection.end();
-                let bytes = state.provider.read(start..end.saturating_add(1)).to_vec();
+                let bytes = state.provider.read_to_vec(start..end.saturating_add(1));
                 return Task::perform(
                     async move {
                         let stats = compute_statistics(&bytes);
@@ -482,7 +502,9 @@ pub fn update(
         HexEditorMessage::Search(query) => {
             state.search.visible = true;
             state.search.query = query;
-            state.search.execute(state.provider.as_slice());
+            state
+                .search
+                .execute_chunks(state.provider.chunks(0..state.provider.len()));
             if let Some(addr) = state.search.current_addr() {
                 state.selection.select(addr.min(max_addr), max_addr);
                 state.pending_center_on.set(Some(addr.min(max_addr)));
@@ -491,7 +513,9 @@ pub fn update(
         HexEditorMessage::ToggleSearchMode => {
             state.search.mode = state.search.mode.toggle();
             if !state.search.query.is_empty() {
-                state.search.execute(state.provider.as_slice());
+                state
+                    .search
+                    .execute_chunks(state.provider.chunks(0..state.provider.len()));
                 if let Some(addr) = state.search.current_addr() {
                     state.selection.select(addr.min(max_addr), max_addr);
                     state.pending_center_on.set(Some(addr.min(max_addr)));
@@ -521,7 +545,9 @@ pub fn update(
             }
             state.search.width = width;
             if state.search.mode == SearchMode::Decimal && !state.search.query.is_empty() {
-                state.search.execute(state.provider.as_slice());
+                state
+                    .search
+                    .execute_chunks(state.provider.chunks(0..state.provider.len()));
                 if let Some(addr) = state.search.current_addr() {
                     state.selection.select(addr.min(max_addr), max_addr);
                     state.pending_center_on.set(Some(addr.min(max_addr)));
@@ -531,7 +557,9 @@ pub fn update(
         HexEditorMessage::ToggleSearchEndian => {
             state.search.little_endian = !state.search.little_endian;
             if state.search.mode == SearchMode::Decimal && !state.search.query.is_empty() {
-                state.search.execute(state.provider.as_slice());
+                state
+                    .search
+                    .execute_chunks(state.provider.chunks(0..state.provider.len()));
                 if let Some(addr) = state.search.current_addr() {
                     state.selection.select(addr.min(max_addr), max_addr);
                     state.pending_center_on.set(Some(addr.min(max_addr)));
@@ -1055,7 +1083,7 @@ pub fn update(
             }
             let start = state.selection.start();
             let end = state.selection.end();
-            let bytes = state.provider.read(start..end.saturating_add(1));
+            let bytes = state.provider.read_to_vec(start..end.saturating_add(1));
             if bytes.is_empty() {
                 return Task::none();
             }
@@ -1101,7 +1129,7 @@ pub fn update(
                 return Task::none();
             }
             state.provider.write(addr, &bytes);
-            state.recompute_vanilla_diff();
+            state.vanilla_diff_after_write(addr, bytes.len() as u64);
             state.notify(format!("Pasted {} byte(s)", bytes.len()));
         }
 
@@ -1140,7 +1168,7 @@ pub fn update(
                             state.provider.write(start + offset, chunk);
                             offset += pattern_len;
                         }
-                        state.recompute_vanilla_diff();
+                        state.vanilla_diff_after_write(start, range_len);
                         let written = range_len;
                         state.notify(format!("Filled {} byte(s) with {:02X?}", written, pattern));
                     } else {
@@ -1216,12 +1244,11 @@ pub fn update(
                     }
 
                     state.provider.insert(addr, &fill);
-                    state.recompute_vanilla_diff();
+                    state.vanilla_diff_after_insert(addr, fill.len() as u64);
                     // The comparison file's diff addresses shift with the
                     // insert — recompute so the diff pane stays accurate.
                     if let Some(cf) = state.comparison_file.as_mut() {
-                        cf.diff =
-                            crate::vanilla_diff::compute_diff(state.provider.as_slice(), &cf.data);
+                        cf.realign(&state.provider);
                     }
                     // Extend shifts every row boundary after the insert, so the
                     // per-row entropy band and cached stats are stale until the
@@ -1314,9 +1341,17 @@ pub fn update(
         }
         HexEditorMessage::ComparisonFileLoaded(result) => match result {
             Ok((data, name)) => {
-                let diff = crate::vanilla_diff::compute_diff(state.provider.as_slice(), &data);
-                state.comparison_file = Some(ComparisonFile { name, data, diff });
-                state.notify("Comparison file loaded");
+                let cf = ComparisonFile::new(name, data, &state.provider);
+                let summary = cf.alignment.summary();
+                state.comparison_file = Some(cf);
+                state.notify(if summary.changes == 0 {
+                    "Comparison file loaded — identical".to_string()
+                } else {
+                    format!(
+                        "Comparison file loaded — {} change(s): +{} / -{} / ~{} bytes",
+                        summary.changes, summary.inserted, summary.deleted, summary.replaced
+                    )
+                });
 
                 // Ensure the focused pane switches to Diff view.
                 let focus = state.pane_focus;
@@ -1365,32 +1400,30 @@ pub fn update(
             };
         }
         HexEditorMessage::DiffNavNext => {
-            // Jump to next diff chunk — find the first address in `comparison_file.diff`
-            // that is strictly greater than the cursor address.
+            // Jump to the start of the next changed hunk after the cursor.
             if let Some(ref cf) = state.comparison_file {
                 let cursor = state.selection.cursor;
-                if let Some(&addr) = cf.diff.range(cursor + 1..).next() {
+                let target = cf
+                    .alignment
+                    .next_change(cursor)
+                    .or_else(|| cf.alignment.change_anchors().next()); // Wrap around.
+                if let Some(addr) = target {
                     state.selection.select(addr, state.max_addr());
                     state.pending_center_on.set(Some(addr));
-                } else if let Some(&first) = cf.diff.first() {
-                    // Wrap around.
-                    state.selection.select(first, state.max_addr());
-                    state.pending_center_on.set(Some(first));
                 }
             }
         }
         HexEditorMessage::DiffNavPrev => {
-            // Jump to previous diff chunk — find the last address in `comparison_file.diff`
-            // that is strictly less than the cursor address.
+            // Jump to the start of the previous changed hunk before the cursor.
             if let Some(ref cf) = state.comparison_file {
                 let cursor = state.selection.cursor;
-                if let Some(&addr) = cf.diff.range(..cursor).next_back() {
+                let target = cf
+                    .alignment
+                    .prev_change(cursor)
+                    .or_else(|| cf.alignment.change_anchors().last()); // Wrap around.
+                if let Some(addr) = target {
                     state.selection.select(addr, state.max_addr());
                     state.pending_center_on.set(Some(addr));
-                } else if let Some(&last) = cf.diff.last() {
-                    // Wrap around.
-                    state.selection.select(last, state.max_addr());
-                    state.pending_center_on.set(Some(last));
                 }
             }
         }
@@ -1433,7 +1466,7 @@ pub fn update(
         }
 
         HexEditorMessage::CommitExport => {
-            let bytes = state.provider.as_slice().to_vec();
+            let bytes = state.provider.read_to_vec(0..state.provider.len());
             if bytes.is_empty() {
                 state.notify("Nothing to export — file is empty");
                 state.export_config = None;
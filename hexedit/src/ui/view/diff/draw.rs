@@ -16,6 +16,7 @@ use iced::{Background, Border, Color, Font, Pixels, Rectangle, Shadow, Size, ali
 use gui_widgets::components::paragraph_cache::{ParagraphCache, ParagraphKey};
 
 use crate::coloring::{ColorScheme, default_byte_colors};
+use crate::domain::provider::HexProvider;
 use crate::ui::theme::HexEditorTheme;
 use crate::ui::view::minimap;
 
@@ -58,9 +59,9 @@ pub fn draw_diff_view<'a, Message>(
 
     // Use the longer of the two buffers for row count.
     let total_bytes = widget
-        .baseline_bytes
+        .baseline
         .len()
-        .max(widget.comparison_bytes.len());
+        .max(widget.comparison_bytes.len() as u64) as usize;
     let total_h = display_rows.len() as f32 * ROW_HEIGHT;
 
     let content_top = bounds.y + HEADER_HEIGHT;
@@ -217,11 +218,8 @@ pub fn draw_diff_view<'a, Message>(
         );
 
         // ── Baseline side (A) ──────────────────────────────────────────
-        let row_bytes_a = widget
-            .baseline_bytes
-            .get(base_addr as usize..)
-            .and_then(|s| s.get(..bpr))
-            .unwrap_or(&[]);
+        let row_end_a = (base_addr + bpr64).min(widget.baseline.len());
+        let row_bytes_a = widget.baseline.read_range(base_addr..row_end_a);
 
         let hex_a_start = bounds.x + layout::baseline_hex_start(adj_addr_col_w) - scroll_x;
         let ascii_a_start = bounds.x + layout::baseline_ascii_start(adj_addr_col_w, bpr) - scroll_x;
@@ -241,6 +239,7 @@ pub fn draw_diff_view<'a, Message>(
                 ascii_a_start,
                 sel_range.clone(),
                 cursor_addr,
+                widget.diff,
                 diff_bg_baseline,
                 diff_text_baseline,
                 selection_bg,
@@ -253,16 +252,16 @@ pub fn draw_diff_view<'a, Message>(
         }
 
         // ── Comparison side (B) ────────────────────────────────────────
+        let hex_b_start = bounds.x + layout::comparison_hex_start(adj_addr_col_w, bpr) - scroll_x;
+        let ascii_b_start =
+            bounds.x + layout::comparison_ascii_start(adj_addr_col_w, bpr) - scroll_x;
+
         let row_bytes_b = widget
             .comparison_bytes
             .get(base_addr as usize..)
-            .and_then(|s| s.get(..bpr))
+            .map(|s| &s[..s.len().min(bpr)])
             .unwrap_or(&[]);
 
-        let hex_b_start = bounds.x + layout::comparison_hex_start(adj_addr_col_w, bpr) - scroll_x;
-        let ascii_b_start =
-            bounds.x + layout::comparison_ascii_start(adj_addr_col_w, bpr) - scroll_x;
-
         for (col, &b) in row_bytes_b.iter().enumerate() {
             let addr = base_addr + col as u64;
             render_byte_cell(
@@ -278,6 +277,7 @@ pub fn draw_diff_view<'a, Message>(
                 ascii_b_start,
                 sel_range.clone(),
                 cursor_addr,
+                widget.comparison_diff,
                 diff_bg_comparison,
                 diff_text_comparison,
                 selection_bg,
@@ -332,13 +332,12 @@ pub fn draw_diff_view<'a, Message>(
     if needs_vscroll && widget.show_minimap && !widget.diff_review {
         let total_len = total_bytes as u64;
         let h_px = viewport_h.max(1.0) as u32;
-        let empty_dirty = BTreeSet::new();
         let ctx = minimap::BlockContext {
-            bytes: widget.baseline_bytes,
+            bytes: widget.baseline,
             total_len,
             pattern_by_addr: widget.patterns,
             alternate_patterns: &widget.alternate_patterns,
-            dirty: &empty_dirty,
+            dirty: &[],
             vanilla_diff: widget.diff,
             color_scheme: widget.color_scheme,
             dim_nulls: widget.dim_nulls,
@@ -358,9 +357,9 @@ pub fn draw_diff_view<'a, Message>(
                 color_scheme: widget.color_scheme,
                 dim_nulls: widget.dim_nulls,
                 pattern_hash: minimap::pattern_hash(widget.patterns),
-                dirty_fingerprint: minimap::set_fingerprint(&empty_dirty),
+                dirty_fingerprint: minimap::ranges_fingerprint(&[]),
                 diff_fingerprint: minimap::set_fingerprint(widget.diff),
-                content_ptr: widget.baseline_bytes.as_ptr() as usize,
+                revision: widget.baseline.revision(),
             });
         }
         let columns = &cache.as_ref().unwrap().columns;
@@ -384,17 +383,14 @@ pub fn draw_diff_view<'a, Message>(
     // ── Scrollbars ──────────────────────────────────────────────────────
     // Bucket diff addresses to row-level to avoid rendering one marker per
     // differing byte (which can be tens of thousands for large diffs).
-    let mut diff_rows: Vec<u64> = Vec::new();
-    if !widget.diff.is_empty() {
-        let mut prev_row = u64::MAX;
-        for &addr in widget.diff {
-            let row = addr / bpr as u64;
-            if row != prev_row {
-                prev_row = row;
-                diff_rows.push(row * bpr as u64);
-            }
-        }
-    }
+    let diff_rows: Vec<u64> = widget
+        .diff
+        .iter()
+        .chain(widget.comparison_diff)
+        .map(|&addr| addr / bpr64 * bpr64)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
     draw_vscrollbar(
         renderer,
         content_bounds,
@@ -497,6 +493,7 @@ fn render_byte_cell<Message>(
     ascii_start_x: f32,
     sel_range: std::ops::RangeInclusive<u64>,
     cursor_addr: u64,
+    diff: &BTreeSet<u64>,
     diff_bg: Color,
     diff_text: Color,
     selection_bg: Color,
@@ -511,7 +508,7 @@ fn render_byte_cell<Message>(
     let ax = ascii_start_x + col as f32 * ASCII_CELL_WIDTH;
 
     let in_sel = sel_range.contains(&addr);
-    let is_diff = widget.diff.contains(&addr);
+    let is_diff = diff.contains(&addr);
     let pat_entry = widget.patterns.get(&addr).copied();
 
     // Background priority: selection > cursor > pattern > diff > none.
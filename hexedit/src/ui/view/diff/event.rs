@@ -6,6 +6,7 @@ use iced::keyboard::{self, key};
 use iced::mouse;
 use iced::{Event, Rectangle};
 
+use crate::domain::provider::HexProvider;
 use crate::ui::view::minimap::{self, MINIMAP_WIDTH};
 
 use super::draw;
@@ -30,9 +31,9 @@ pub fn handle_event<Message>(
     let content_top = bounds.y + super::layout::HEADER_HEIGHT;
     let viewport_h = widget.content_viewport_h(bounds.height, bounds.width);
     let total_bytes = widget
-        .baseline_bytes
+        .baseline
         .len()
-        .max(widget.comparison_bytes.len());
+        .max(widget.comparison_bytes.len() as u64) as usize;
     let total_h = state.display_rows(widget).len() as f32 * ROW_HEIGHT;
 
     match event {
@@ -414,9 +415,9 @@ fn handle_keyboard_event<Message>(
 
     let bpr = widget.bytes_per_row as u64;
     let total_bytes = widget
-        .baseline_bytes
+        .baseline
         .len()
-        .max(widget.comparison_bytes.len()) as u64;
+        .max(widget.comparison_bytes.len() as u64);
     if total_bytes == 0 {
         return;
     }
@@ -29,6 +29,8 @@ use iced::mouse;
 use iced::{Element, Length, Rectangle, Size};
 
 use crate::coloring::ColorScheme;
+use crate::domain::mapped_provider::MappedProvider;
+use crate::domain::provider::HexProvider;
 use crate::domain::selection::Selection;
 use crate::ui::theme::HexEditorTheme;
 use gui_widgets::components::paragraph_cache::ParagraphCache;
@@ -101,8 +103,8 @@ impl DisplayRows {
 /// between the two buffers with tinted backgrounds.
 pub struct DiffView<'a, Message> {
     // ── Data sources ───────────────────────────────────────────────────
-    /// Baseline file bytes (left side).
-    pub(super) baseline_bytes: &'a [u8],
+    /// Baseline buffer (left side), read one visible row at a time.
+    pub(super) baseline: &'a MappedProvider,
     /// Comparison file bytes (right side).
     pub(super) comparison_bytes: &'a [u8],
     /// How many bytes per row.
@@ -111,8 +113,12 @@ pub struct DiffView<'a, Message> {
     // ── Selection ──────────────────────────────────────────────────────
     /// Shared cursor / selection across both sides.
     pub(super) selection: Selection,
-    /// Addresses that differ between baseline and comparison.
+    /// Baseline addresses that differ from the comparison.
     pub(super) diff: &'a BTreeSet<u64>,
+    /// Comparison addresses that differ from the baseline. Each side is
+    /// drawn in its own address space, so inserted bytes get their own
+    /// cells instead of being hidden behind the baseline offsets.
+    pub(super) comparison_diff: &'a BTreeSet<u64>,
 
     // ── Pattern overlays ───────────────────────────────────────────────
     pub(super) patterns: &'a BTreeMap<u64, (usize, u8)>,
@@ -164,7 +170,7 @@ pub struct DiffView<'a, Message> {
 impl<'a, Message> DiffView<'a, Message> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        baseline_bytes: &'a [u8],
+        baseline: &'a MappedProvider,
         comparison_bytes: &'a [u8],
         bytes_per_row: u8,
         selection: Selection,
@@ -183,11 +189,12 @@ impl<'a, Message> DiffView<'a, Message> {
         theme: &'static HexEditorTheme,
     ) -> Self {
         DiffView {
-            baseline_bytes,
+            baseline,
             comparison_bytes,
             bytes_per_row: bytes_per_row.max(1),
             selection,
             diff,
+            comparison_diff: diff,
             patterns,
             search_match_set,
             search_query_len,
@@ -260,6 +267,12 @@ impl<'a, Message> DiffView<'a, Message> {
         self
     }
 
+    /// Highlight `diff` on the comparison side instead of the baseline set.
+    pub fn comparison_diff(mut self, diff: &'a BTreeSet<u64>) -> Self {
+        self.comparison_diff = diff;
+        self
+    }
+
     /// Ctrl+Down → jump to next diff chunk.
     pub fn on_diff_nav_next(mut self, f: impl Fn() -> Message + 'a) -> Self {
         self.on_diff_nav_next = Some(Box::new(f));
@@ -283,7 +296,7 @@ impl<'a, Message> DiffView<'a, Message> {
 impl<'a, Message> DiffView<'a, Message> {
     pub(super) fn total_rows(&self) -> u64 {
         let bpr = self.bytes_per_row as usize;
-        let total = self.baseline_bytes.len().max(self.comparison_bytes.len());
+        let total = self.baseline.len().max(self.comparison_bytes.len() as u64) as usize;
         if total == 0 {
             0
         } else {
@@ -329,7 +342,7 @@ impl<'a, Message> DiffView<'a, Message> {
 
         let bpr = self.bytes_per_row as u64;
         let mut kept = BTreeSet::new();
-        for &addr in self.diff {
+        for &addr in self.diff.iter().chain(self.comparison_diff) {
             let changed_row = addr / bpr;
             let first = changed_row.saturating_sub(REVIEW_CONTEXT_ROWS);
             let last = (changed_row + REVIEW_CONTEXT_ROWS).min(total_rows.saturating_sub(1));
@@ -478,9 +491,21 @@ pub fn view<'a>(
     .on_press(crate::HexEditorMessage::CloseComparison);
 
     let comparison_name = text(&cf.name).size(11).font(Font::MONOSPACE);
+    let summary = cf.alignment.summary();
+    let hunk_summary = text(if summary.changes == 0 {
+        "identical".to_string()
+    } else {
+        format!(
+            "{} hunk(s) · +{} −{} ~{} bytes",
+            summary.changes, summary.inserted, summary.deleted, summary.replaced
+        )
+    })
+    .size(11)
+    .font(Font::MONOSPACE);
     let header = container(
         row![
             comparison_name,
+            hunk_summary,
             container(close_btn)
                 .width(Fill)
                 .align_x(iced::alignment::Horizontal::Right),
@@ -517,7 +542,7 @@ pub fn view<'a>(
     };
 
     let diff_view: iced::Element<'a, crate::HexEditorMessage> = DiffView::new(
-        state.provider.as_slice(),
+        &state.provider,
         &cf.data,
         state.bytes_per_row,
         clamped_sel,
@@ -543,6 +568,7 @@ pub fn view<'a>(
     .on_nav(|dir, extend| crate::HexEditorMessage::Nav { dir, extend })
     .on_diff_nav_next(|| crate::HexEditorMessage::DiffNavNext)
     .on_diff_nav_prev(|| crate::HexEditorMessage::DiffNavPrev)
+    .comparison_diff(&cf.comparison_diff)
     .center_on(state.pending_center_on.take())
     .diff_review(state.diff_review)
     .show_minimap(state.show_minimap)
@@ -564,6 +590,7 @@ mod tests {
     use gui_widgets::components::paragraph_cache::ParagraphCache;
 
     use super::DisplayRow;
+    use super::MappedProvider;
     use super::draw::col_at_x;
     use super::layout::*;
 
@@ -573,7 +600,7 @@ mod tests {
 
     /// Construct a minimal DiffView for tests. All non-essential buffers
     /// reference static empty collections.
-    fn minimal_dv<'a>(a: &'a [u8], b: &'a [u8], bpr: u8) -> super::DiffView<'a, ()> {
+    fn minimal_dv<'a>(a: &'a MappedProvider, b: &'a [u8], bpr: u8) -> super::DiffView<'a, ()> {
         static EMPTY_SET: BTreeSet<u64> = BTreeSet::new();
         static EMPTY_MAP: BTreeMap<u64, (usize, u8)> = BTreeMap::new();
         static EMPTY_ANN: BTreeMap<u64, Vec<(usize, String)>> = BTreeMap::new();
@@ -603,13 +630,17 @@ mod tests {
 
     #[test]
     fn empty_buffers_yield_zero_rows() {
-        let dv = minimal_dv(&[], &[], 16);
+        let baseline = MappedProvider::from_bytes(Vec::new());
+
+        let dv = minimal_dv(&baseline, &[], 16);
         assert_eq!(dv.total_rows(), 0);
     }
 
     #[test]
     fn total_rows_computed_from_longer_buffer() {
-        let dv = minimal_dv(&[0u8; 32], &[0u8; 48], 16);
+        let baseline = MappedProvider::from_bytes(vec![0u8; 32]);
+
+        let dv = minimal_dv(&baseline, &[0u8; 48], 16);
         assert_eq!(dv.total_rows(), 3);
     }
 
@@ -618,7 +649,9 @@ mod tests {
         let mut diff = BTreeSet::new();
         // One changed byte on source row 10 in a 20-row file.
         diff.insert(10 * 16 + 3);
-        let mut dv = minimal_dv(&[0u8; 20 * 16], &[0u8; 20 * 16], 16);
+        let baseline = MappedProvider::from_bytes(vec![0u8; 20 * 16]);
+
+        let mut dv = minimal_dv(&baseline, &[0u8; 20 * 16], 16);
         dv.diff = &diff;
         dv.diff_review = true;
 
@@ -647,7 +680,9 @@ mod tests {
         let mut diff = BTreeSet::new();
         diff.insert(4 * 16);
         diff.insert(8 * 16);
-        let mut dv = minimal_dv(&[0u8; 16 * 16], &[0u8; 16 * 16], 16);
+        let baseline = MappedProvider::from_bytes(vec![0u8; 16 * 16]);
+
+        let mut dv = minimal_dv(&baseline, &[0u8; 16 * 16], 16);
         dv.diff = &diff;
         dv.diff_review = true;
 
@@ -675,9 +710,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn review_mode_keeps_rows_changed_only_on_the_comparison_side() {
+        // Bytes inserted past the end of the baseline only show up in the
+        // comparison's own address space.
+        let comparison_diff = BTreeSet::from([6 * 16]);
+        let baseline = MappedProvider::from_bytes(vec![0u8; 4 * 16]);
+
+        let mut dv = minimal_dv(&baseline, &[0u8; 8 * 16], 16);
+        dv.comparison_diff = &comparison_diff;
+        dv.diff_review = true;
+
+        assert_eq!(
+            dv.build_display_rows().compact_rows(),
+            &[
+                DisplayRow::Collapsed {
+                    first_row: 0,
+                    count: 4,
+                },
+                DisplayRow::Data { source_row: 4 },
+                DisplayRow::Data { source_row: 5 },
+                DisplayRow::Data { source_row: 6 },
+                DisplayRow::Data { source_row: 7 },
+            ]
+        );
+    }
+
     #[test]
     fn right_strip_is_scrollbar_only() {
-        let dv = minimal_dv(&[0u8; 16], &[0u8; 16], 16);
+        let baseline = MappedProvider::from_bytes(vec![0u8; 16]);
+
+        let dv = minimal_dv(&baseline, &[0u8; 16], 16);
         assert_eq!(dv.right_strip(), SCROLLBAR_THICKNESS);
     }
 
@@ -686,7 +749,9 @@ mod tests {
     #[test]
     fn builder_on_right_click_sets_callback() {
         let called = std::cell::Cell::new(None);
-        let dv = minimal_dv(&[0], &[0], 16).on_right_click(|addr, _is_baseline| {
+        let baseline = MappedProvider::from_bytes(vec![0]);
+
+        let dv = minimal_dv(&baseline, &[0], 16).on_right_click(|addr, _is_baseline| {
             called.set(Some(addr));
         });
         let cb = dv.on_right_click.as_ref().unwrap();
@@ -697,7 +762,9 @@ mod tests {
     #[test]
     fn builder_on_extend_to_sets_callback() {
         let called = std::cell::Cell::new(None);
-        let dv = minimal_dv(&[0], &[0], 16).on_extend_to(|addr, _is_baseline| {
+        let baseline = MappedProvider::from_bytes(vec![0]);
+
+        let dv = minimal_dv(&baseline, &[0], 16).on_extend_to(|addr, _is_baseline| {
             called.set(Some(addr));
         });
         let cb = dv.on_extend_to.as_ref().unwrap();
@@ -709,7 +776,9 @@ mod tests {
     fn builder_on_nav_sets_callback() {
         // NavDir does not implement PartialEq, so verify invocation by flag
         let called = std::cell::Cell::new(false);
-        let dv = minimal_dv(&[0], &[0], 16).on_nav(|_, _| {
+        let baseline = MappedProvider::from_bytes(vec![0]);
+
+        let dv = minimal_dv(&baseline, &[0], 16).on_nav(|_, _| {
             called.set(true);
         });
         let cb = dv.on_nav.as_ref().unwrap();
@@ -719,10 +788,14 @@ mod tests {
 
     #[test]
     fn builder_show_decimal_sets_flag() {
-        let dv = minimal_dv(&[0], &[0], 16).show_decimal(true);
+        let baseline = MappedProvider::from_bytes(vec![0]);
+
+        let dv = minimal_dv(&baseline, &[0], 16).show_decimal(true);
         assert!(dv.show_decimal);
 
-        let dv = minimal_dv(&[0], &[0], 16).show_decimal(false);
+        let baseline = MappedProvider::from_bytes(vec![0]);
+
+        let dv = minimal_dv(&baseline, &[0], 16).show_decimal(false);
         assert!(!dv.show_decimal);
     }
 
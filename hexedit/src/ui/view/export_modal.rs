@@ -92,8 +92,8 @@ pub fn view(state: &HexEditorState) -> Element<'_, HexEditorMessage> {
     let preview_body: Element<'_, HexEditorMessage> = if preview_len == 0 {
         text("").size(12).font(Font::MONOSPACE).into()
     } else {
-        let preview_bytes = &state.provider.as_slice()[..preview_len];
-        let preview_text = format_hex_dump(preview_bytes, bpr, cfg);
+        let preview_bytes = state.provider.read_range(0..preview_len as u64);
+        let preview_text = format_hex_dump(&preview_bytes, bpr, cfg);
         scrollable(
             container(
                 text(preview_text)
@@ -82,10 +82,14 @@ pub fn format_footer(editor: &HexEditorState) -> String {
     } else {
         format!("0x{cursor:X}")
     };
-    format!(
+    let mut footer = format!(
         "{sel}  ·  total: {total_fmt} ({total_str})  ·  dirty: {dirty}  ·  cursor: {cursor_fmt}",
         sel = sel_str,
-    )
+    );
+    if let Some(error) = &editor.template_error {
+        footer.push_str(&format!("  ·  template: {error}"));
+    }
+    footer
 }
 
 pub fn format_selection(sel: Selection, show_decimal: bool) -> String {
@@ -5,7 +5,7 @@ use iced::{Element, Fill, Font, Length};
 use crate::config::HexEditorConfig;
 use crate::inspector::ENTRIES;
 use crate::state::InspectorSource;
-use crate::{HexEditorMessage, HexEditorState, HexProvider};
+use crate::{HexEditorMessage, HexEditorState};
 
 pub fn view<'a>(
     editor: &'a HexEditorState,
@@ -28,17 +28,8 @@ pub fn view<'a>(
     .padding([6, 12])
     .width(Fill);
 
-    let (src_len, src_bytes, editable) = match editor.inspector_source {
-        InspectorSource::Baseline => (editor.provider.len(), editor.provider.as_slice(), true),
-        InspectorSource::Comparison => {
-            let data = editor
-                .comparison_file
-                .as_ref()
-                .map(|cf| cf.data.as_slice())
-                .unwrap_or(&[]);
-            (data.len() as u64, data, false)
-        }
-    };
+    let editable = editor.inspector_source == InspectorSource::Baseline;
+    let (src_len, window) = editor.inspector_window(64);
 
     let rows: Element<'_, HexEditorMessage> = if src_len == 0 {
         container(text("(empty file)").size(11).font(Font::MONOSPACE))
@@ -47,8 +38,7 @@ pub fn view<'a>(
     } else {
         let cursor = editor.selection.cursor;
         let avail = src_len.saturating_sub(cursor) as usize;
-        let read_end = (cursor + 64).min(src_len);
-        let bytes = &src_bytes[cursor as usize..read_end as usize];
+        let bytes = &window[..];
 
         let mut col = column![].spacing(1).padding([4, 12]);
         let mut last_category: Option<&str> = None;
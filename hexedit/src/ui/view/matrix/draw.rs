@@ -15,6 +15,7 @@ use gui_widgets::components::paragraph_cache::{ParagraphCache, ParagraphKey};
 
 use crate::coloring::default_byte_colors;
 use crate::domain::byte_stats::entropy_to_color;
+use crate::domain::provider::HexProvider;
 use crate::ui::theme::HexEditorTheme;
 
 use crate::ui::view::minimap::{self};
@@ -293,8 +294,8 @@ pub fn draw_matrix<'a, Message>(
         );
 
         // Hex + ASCII columns.
-        let row_end = (base_addr as usize + bpr).min(widget.bytes.len());
-        let row_bytes = &widget.bytes[base_addr as usize..row_end];
+        let row_end = (base_addr + bpr64).min(widget.provider.len());
+        let row_bytes = widget.provider.read_range(base_addr..row_end);
 
         for (col, &b) in row_bytes.iter().enumerate() {
             let addr = base_addr + col as u64;
@@ -303,12 +304,12 @@ pub fn draw_matrix<'a, Message>(
             let ax = ascii_start_x + col as f32 * ASCII_CELL_WIDTH;
 
             let in_sel = sel_range.contains(&addr);
-            let is_dirty = widget.dirty.contains(&addr);
+            let is_dirty = minimap::ranges_overlap(widget.dirty, addr..addr + 1);
             let is_diff = widget.vanilla_diff.contains(&addr);
             let pat_entry = widget.patterns.get(&addr).copied();
             let structure_field = widget
                 .layout
-                .and_then(|layout| layout.field_at(addr, widget.bytes.len() as u64));
+                .and_then(|layout| layout.field_at(addr, widget.provider.len()));
             let is_editing = edit_addr == Some(addr);
 
             // Structure overlays deliberately do not paint cell backgrounds:
@@ -541,13 +542,13 @@ pub fn draw_matrix<'a, Message>(
     }
 
     // Minimap overview strip (between content and scrollbar).
-    let total_len = widget.bytes.len() as u64;
+    let total_len = widget.provider.len();
     let needs_vscroll = total_h > viewport_h;
     if needs_vscroll && widget.show_minimap {
         // Compute or reuse the minimap pixel cache.
         let h_px = viewport_h.max(1.0) as u32;
         let ctx = minimap::BlockContext {
-            bytes: widget.bytes,
+            bytes: widget.provider,
             total_len,
             pattern_by_addr: widget.patterns,
             alternate_patterns: &widget.alternate_patterns,
@@ -571,9 +572,9 @@ pub fn draw_matrix<'a, Message>(
                 color_scheme: widget.color_scheme,
                 dim_nulls: widget.dim_nulls,
                 pattern_hash: minimap::pattern_hash(widget.patterns),
-                dirty_fingerprint: minimap::set_fingerprint(widget.dirty),
+                dirty_fingerprint: minimap::ranges_fingerprint(widget.dirty),
                 diff_fingerprint: minimap::set_fingerprint(widget.vanilla_diff),
-                content_ptr: widget.bytes.as_ptr() as usize,
+                revision: widget.provider.revision(),
             });
         }
         let columns = &cache.as_ref().unwrap().columns;
@@ -13,6 +13,7 @@ use iced::keyboard::{self, key};
 use iced::mouse;
 use iced::{Event, Point, Rectangle};
 
+use crate::domain::provider::HexProvider;
 use crate::domain::write_mode::WriteMode;
 use crate::selection::NavDir;
 use crate::ui::view::minimap::{self, MINIMAP_WIDTH};
@@ -41,7 +42,7 @@ pub fn handle_event<'a, Message>(
 ) {
     let bounds = layout.bounds();
     let total_h = widget.total_height();
-    let total_len = widget.bytes.len() as u64;
+    let total_len = widget.provider.len();
     let viewport_h = widget.content_viewport_h(bounds.height, bounds.width);
     let content_bounds = Rectangle {
         x: bounds.x,
@@ -523,7 +524,7 @@ pub fn handle_event<'a, Message>(
                                 shell.capture_event();
                                 return;
                             }
-                        } else if !widget.bytes.is_empty() {
+                        } else if !widget.provider.is_empty() {
                             if let Some(begin) = &widget.on_begin_edit {
                                 shell.publish(begin(widget.selection.cursor));
                             }
@@ -534,7 +535,7 @@ pub fn handle_event<'a, Message>(
                             return;
                         }
                     } else {
-                        if !widget.bytes.is_empty()
+                        if !widget.provider.is_empty()
                             && let Some(cb) = &widget.on_edit_type
                         {
                             shell.publish(cb(c));
@@ -24,6 +24,7 @@ pub use state::{EditView, State};
 
 use std::cell::Cell;
 use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
 
 use iced::advanced::Shell;
 use iced::advanced::layout::{Layout, Limits, Node};
@@ -34,6 +35,8 @@ use iced::{Element, Event, Length, Rectangle, Size};
 
 use crate::coloring::ColorScheme;
 use crate::domain::layout::BinaryLayout;
+use crate::domain::mapped_provider::MappedProvider;
+use crate::domain::provider::HexProvider;
 use crate::domain::write_mode::WriteMode;
 use crate::selection::{NavDir, Selection};
 use crate::ui::theme::HexEditorTheme;
@@ -49,12 +52,12 @@ use gui_widgets::components::paragraph_cache::ParagraphCache;
 /// search-match highlights, inline editing support, and both vertical and
 /// horizontal scrollbars.
 pub struct HexMatrix<'a, Message> {
-    pub(super) bytes: &'a [u8],
+    pub(super) provider: &'a MappedProvider,
     pub(super) layout: Option<&'a dyn BinaryLayout>,
     pub(super) bytes_per_row: u8,
     pub(super) selection: Selection,
     pub(super) edit: Option<EditView<'a>>,
-    pub(super) dirty: &'a BTreeSet<u64>,
+    pub(super) dirty: &'a [Range<u64>],
     pub(super) vanilla_diff: &'a BTreeSet<u64>,
     pub(super) patterns: &'a BTreeMap<u64, (usize, u8)>,
     pub(super) search_match_set: &'a BTreeSet<u64>,
@@ -109,12 +112,12 @@ pub struct HexMatrix<'a, Message> {
 impl<'a, Message> HexMatrix<'a, Message> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        bytes: &'a [u8],
+        provider: &'a MappedProvider,
         layout: Option<&'a dyn BinaryLayout>,
         bytes_per_row: u8,
         selection: Selection,
         edit: Option<EditView<'a>>,
-        dirty: &'a BTreeSet<u64>,
+        dirty: &'a [Range<u64>],
         vanilla_diff: &'a BTreeSet<u64>,
         patterns: &'a BTreeMap<u64, (usize, u8)>,
         search_match_set: &'a BTreeSet<u64>,
@@ -130,7 +133,7 @@ impl<'a, Message> HexMatrix<'a, Message> {
         theme: &'static HexEditorTheme,
     ) -> Self {
         HexMatrix {
-            bytes,
+            provider,
             layout,
             bytes_per_row: bytes_per_row.max(1),
             selection,
@@ -302,7 +305,7 @@ impl<'a, Message> HexMatrix<'a, Message> {
         let char_w = 9.0;
         let pad = 16.0;
         let chars = if self.show_decimal {
-            let max_addr = self.bytes.len().saturating_sub(1);
+            let max_addr = self.provider.len().saturating_sub(1);
             format!("{}", max_addr).len().max(1)
         } else {
             8usize
@@ -312,10 +315,10 @@ impl<'a, Message> HexMatrix<'a, Message> {
 
     fn total_rows(&self) -> u64 {
         let bpr = self.bytes_per_row as u64;
-        if self.bytes.is_empty() {
+        if self.provider.is_empty() {
             0
         } else {
-            self.bytes.len().div_ceil(bpr as usize) as u64
+            self.provider.len().div_ceil(bpr)
         }
     }
 
@@ -399,14 +402,14 @@ impl<'a, Message> HexMatrix<'a, Message> {
         bounds: Rectangle,
         shell: &mut Shell<'_, Message>,
     ) {
-        if self.bytes.is_empty() {
+        if self.provider.is_empty() {
             return;
         }
         if let Some(cb) = &self.on_nav {
             shell.publish(cb(dir, extend));
         }
         let bpr = self.bytes_per_row as u64;
-        let max_addr = (self.bytes.len() as u64).saturating_sub(1);
+        let max_addr = self.provider.len().saturating_sub(1);
         let viewport_h = self.content_viewport_h(bounds.height, bounds.width);
         let target = crate::selection::nav_target(
             self.selection.cursor,
@@ -861,10 +864,10 @@ mod tests {
         use gui_widgets::components::paragraph_cache::ParagraphCache;
 
         use crate::coloring::ColorScheme;
+        use crate::domain::mapped_provider::MappedProvider;
         use crate::selection::Selection;
         use crate::ui::theme::DARK_THEME;
 
-        let dirty = BTreeSet::new();
         let diff = BTreeSet::new();
         let patterns = BTreeMap::new();
         let search_match_set = BTreeSet::new();
@@ -872,13 +875,14 @@ mod tests {
         let active_patterns = BTreeSet::new();
         let alternate_patterns = BTreeSet::new();
 
+        let provider = MappedProvider::from_bytes(Vec::new());
         let matrix = super::HexMatrix::<()>::new(
-            &[],                       // bytes (empty)
+            &provider,                 // provider (empty)
             None,                      // layout
             16,                        // bytes_per_row
             Selection::default(),      // selection
             None,                      // edit
-            &dirty,                    // dirty
+            &[],                       // dirty
             &diff,                     // vanilla_diff
             &patterns,                 // patterns
             &search_match_set,         // search_match_set
@@ -9,10 +9,13 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
 
 use iced::advanced::Renderer as _;
 use iced::{Background, Border, Color, Rectangle, Shadow};
 
+use crate::domain::mapped_provider::MappedProvider;
+use crate::domain::provider::HexProvider;
 use crate::ui::coloring::{ColorScheme, default_byte_colors};
 use crate::ui::theme::HexEditorTheme;
 
@@ -121,11 +124,12 @@ fn thumb_height(mm_rect: Rectangle, total_h: f32) -> f32 {
 /// Groups the parameters that rarely change frame-to-frame so they can be
 /// passed as a single value through the computation pipeline.
 pub struct BlockContext<'a> {
-    pub bytes: &'a [u8],
+    pub bytes: &'a MappedProvider,
     pub total_len: u64,
     pub pattern_by_addr: &'a BTreeMap<u64, (usize, u8)>,
     pub alternate_patterns: &'a BTreeSet<usize>,
-    pub dirty: &'a BTreeSet<u64>,
+    /// Sorted, merged ranges edited since load.
+    pub dirty: &'a [Range<u64>],
     pub vanilla_diff: &'a BTreeSet<u64>,
     pub color_scheme: ColorScheme,
     pub dim_nulls: bool,
@@ -171,10 +175,10 @@ pub fn compute_block_pixels(h_px: u32, ctx: &BlockContext) -> [Vec<Color>; 4] {
         let start = (block_idx as u64)
             .saturating_mul(stride)
             .min(ctx.total_len)
-            .min(ctx.bytes.len() as u64);
+            .min(ctx.bytes.len());
         let end = ((block_idx as u64 + 1).saturating_mul(stride))
             .min(ctx.total_len)
-            .min(ctx.bytes.len() as u64);
+            .min(ctx.bytes.len());
         cols[block_idx % MINIMAP_COLS].push(block_color(start, end, ctx));
     }
     cols
@@ -193,7 +197,7 @@ fn block_color(block_start: u64, block_end: u64, ctx: &BlockContext) -> Color {
     } else {
         None
     };
-    let has_dirty = block_len > 0 && ctx.dirty.range(block_start..block_end).next().is_some();
+    let has_dirty = block_len > 0 && ranges_overlap(ctx.dirty, block_start..block_end);
     let has_diff = block_len > 0
         && ctx
             .vanilla_diff
@@ -225,12 +229,12 @@ fn block_color(block_start: u64, block_end: u64, ctx: &BlockContext) -> Color {
 /// during minimap cache rebuild (not every frame), so the O(block_len) cost
 /// is acceptable.
 fn block_mean_variance_color(block_start: u64, block_end: u64, ctx: &BlockContext) -> Color {
-    let last_valid = ctx.bytes.len().saturating_sub(1) as u64;
+    let last_valid = ctx.bytes.len().saturating_sub(1);
     // Clamp the range to available bytes (handle partial mappings where
     // total_len > bytes.len()).
-    let lo = block_start.min(last_valid) as usize;
-    let hi = ((block_end - 1).min(last_valid) + 1) as usize;
-    let slice = &ctx.bytes[lo..hi.min(ctx.bytes.len())];
+    let lo = block_start.min(last_valid);
+    let hi = (block_end - 1).min(last_valid) + 1;
+    let slice = ctx.bytes.read_range(lo..hi.min(ctx.bytes.len()));
     let n = slice.len() as f32;
 
     if n == 0.0 {
@@ -248,7 +252,7 @@ fn block_mean_variance_color(block_start: u64, block_end: u64, ctx: &BlockContex
     let mean_f = sum as f32 / n;
 
     let mut var_sum = 0.0_f32;
-    for &b in slice {
+    for &b in slice.iter() {
         let d = b as f32 - mean_f;
         var_sum += d * d;
     }
@@ -445,15 +449,15 @@ pub(crate) struct MinimapCache {
     /// Hash of pattern_by_addr contents (*not* the full map — just a
     /// checksum so we can cheaply detect changes).
     pub pattern_hash: u64,
-    /// Fingerprint of dirty addresses — catches swapped edits that
-    /// a simple count would miss (hash of len + first + last).
+    /// Fingerprint of dirty ranges — catches edits that move or grow a
+    /// range without changing how many there are.
     pub dirty_fingerprint: u64,
     /// Fingerprint of diff addresses — same rationale as dirty.
     pub diff_fingerprint: u64,
-    /// Raw pointer of the byte slice that was used to build the cache.
-    /// Catches external file modifications that don't change the other
+    /// [`MappedProvider::revision`] of the buffer that was used to build
+    /// the cache. Catches edits and reloads that don't change the other
     /// cache keys (total_len, patterns, dirty/diff sets).
-    pub content_ptr: usize,
+    pub revision: u64,
 }
 
 /// Quick (non-cryptographic) hash of a pattern_by_addr map.
@@ -485,6 +489,27 @@ pub(crate) fn set_fingerprint(set: &BTreeSet<u64>) -> u64 {
     h.finish()
 }
 
+/// Range-list counterpart of [`set_fingerprint`]: hashes the range count,
+/// the covered byte count and the outermost ranges.
+pub(crate) fn ranges_fingerprint(ranges: &[Range<u64>]) -> u64 {
+    let mut h = DefaultHasher::new();
+    ranges.len().hash(&mut h);
+    ranges
+        .iter()
+        .map(|r| r.end - r.start)
+        .sum::<u64>()
+        .hash(&mut h);
+    ranges.first().hash(&mut h);
+    ranges.last().hash(&mut h);
+    h.finish()
+}
+
+/// True if any of the sorted, non-overlapping `ranges` intersects `span`.
+pub(crate) fn ranges_overlap(ranges: &[Range<u64>], span: Range<u64>) -> bool {
+    let idx = ranges.partition_point(|r| r.end <= span.start);
+    ranges.get(idx).is_some_and(|r| r.start < span.end)
+}
+
 /// Check whether a cached minimap pixel array is still valid for the
 /// current file state.
 pub(crate) fn minimap_cache_valid(cache: &MinimapCache, h_px: u32, ctx: &BlockContext) -> bool {
@@ -493,9 +518,9 @@ pub(crate) fn minimap_cache_valid(cache: &MinimapCache, h_px: u32, ctx: &BlockCo
         && cache.color_scheme == ctx.color_scheme
         && cache.dim_nulls == ctx.dim_nulls
         && cache.pattern_hash == pattern_hash(ctx.pattern_by_addr)
-        && cache.dirty_fingerprint == set_fingerprint(ctx.dirty)
+        && cache.dirty_fingerprint == ranges_fingerprint(ctx.dirty)
         && cache.diff_fingerprint == set_fingerprint(ctx.vanilla_diff)
-        && cache.content_ptr == ctx.bytes.as_ptr() as usize
+        && cache.revision == ctx.bytes.revision()
 }
 
 /// Compute the y-offset and height of the selection-range band on the
@@ -765,11 +790,11 @@ mod tests {
     #[test]
     fn compute_block_pixels_empty_file() {
         let ctx = BlockContext {
-            bytes: &[],
+            bytes: &MappedProvider::from_bytes(Vec::new()),
             total_len: 0,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -784,11 +809,11 @@ mod tests {
     #[test]
     fn compute_block_pixels_zero_height() {
         let ctx = BlockContext {
-            bytes: &[0xFF; 100],
+            bytes: &MappedProvider::from_bytes(vec![0xFF; 100]),
             total_len: 100,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -804,11 +829,11 @@ mod tests {
     fn compute_block_pixels_uniform_bytes() {
         let bytes = [0xFFu8; 200];
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 200,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -842,11 +867,11 @@ mod tests {
         let mut patterns = BTreeMap::new();
         patterns.insert(1, (0usize, 3u8));
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 40,
             pattern_by_addr: &patterns,
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -868,11 +893,11 @@ mod tests {
         let mut patterns = BTreeMap::new();
         patterns.insert(3, (0usize, 4u8));
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 40,
             pattern_by_addr: &patterns,
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -896,11 +921,11 @@ mod tests {
         let mut alternate = BTreeSet::new();
         alternate.insert(17);
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 40,
             pattern_by_addr: &patterns,
             alternate_patterns: &alternate,
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -920,12 +945,11 @@ mod tests {
         // total_len = 40, h_px = 10 → stride = 1.
         // Dirty and diff at addr 1 → block 1 → col 1, row 0.
         let bytes = [0x00u8; 40];
-        let mut dirty = BTreeSet::new();
-        dirty.insert(1);
+        let dirty = [1..2];
         let mut diff = BTreeSet::new();
         diff.insert(1);
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 40,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
@@ -955,11 +979,11 @@ mod tests {
         let mut diff = BTreeSet::new();
         diff.insert(1);
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 40,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &diff,
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -984,12 +1008,11 @@ mod tests {
         let bytes = [0x00u8; 40];
         let mut patterns = BTreeMap::new();
         patterns.insert(1, (0usize, 1u8));
-        let mut dirty = BTreeSet::new();
-        dirty.insert(1);
+        let dirty = [1..2];
         let mut diff = BTreeSet::new();
         diff.insert(1);
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 40,
             pattern_by_addr: &patterns,
             alternate_patterns: &BTreeSet::new(),
@@ -1044,11 +1067,11 @@ mod tests {
         assert_eq!(pixel_stride(101, 10), 3);
         let bytes = vec![0x42u8; 101];
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 101,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -1086,15 +1109,12 @@ mod tests {
     fn minimap_cache_valid_matches() {
         let mut patterns = BTreeMap::new();
         patterns.insert(10, (0usize, 1u8));
-        let mut dirty = BTreeSet::new();
-        for i in 0..5 {
-            dirty.insert(i);
-        }
+        let dirty = [0..5];
         let mut diff = BTreeSet::new();
         for i in 0..3 {
             diff.insert(100 + i);
         }
-        let cache_bytes: &[u8] = &[];
+        let provider = MappedProvider::from_bytes(Vec::new());
         let cache = MinimapCache {
             columns: [vec![Color::WHITE], vec![], vec![], vec![]],
             total_len: 100,
@@ -1102,12 +1122,12 @@ mod tests {
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
             pattern_hash: pattern_hash(&patterns),
-            dirty_fingerprint: set_fingerprint(&dirty),
+            dirty_fingerprint: ranges_fingerprint(&dirty),
             diff_fingerprint: set_fingerprint(&diff),
-            content_ptr: cache_bytes.as_ptr() as usize,
+            revision: provider.revision(),
         };
         let ctx = BlockContext {
-            bytes: cache_bytes,
+            bytes: &provider,
             total_len: 100,
             pattern_by_addr: &patterns,
             alternate_patterns: &BTreeSet::new(),
@@ -1123,7 +1143,7 @@ mod tests {
     #[test]
     fn minimap_cache_valid_detects_size_change() {
         let empty: BTreeSet<u64> = BTreeSet::new();
-        let empty_ptr = (&[] as &[u8]).as_ptr() as usize;
+        let provider = MappedProvider::from_bytes(Vec::new());
         let cache = MinimapCache {
             columns: [vec![Color::WHITE], vec![], vec![], vec![]],
             total_len: 100,
@@ -1131,16 +1151,16 @@ mod tests {
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
             pattern_hash: 0,
-            dirty_fingerprint: set_fingerprint(&empty),
+            dirty_fingerprint: ranges_fingerprint(&[]),
             diff_fingerprint: set_fingerprint(&empty),
-            content_ptr: empty_ptr,
+            revision: provider.revision(),
         };
         let ctx = BlockContext {
-            bytes: &[],
+            bytes: &provider,
             total_len: 200,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &empty,
+            dirty: &[],
             vanilla_diff: &empty,
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -1152,7 +1172,7 @@ mod tests {
     #[test]
     fn minimap_cache_valid_detects_scheme_change() {
         let empty: BTreeSet<u64> = BTreeSet::new();
-        let empty_ptr = (&[] as &[u8]).as_ptr() as usize;
+        let provider = MappedProvider::from_bytes(Vec::new());
         let cache = MinimapCache {
             columns: [vec![Color::WHITE], vec![], vec![], vec![]],
             total_len: 100,
@@ -1160,16 +1180,16 @@ mod tests {
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
             pattern_hash: 0,
-            dirty_fingerprint: set_fingerprint(&empty),
+            dirty_fingerprint: ranges_fingerprint(&[]),
             diff_fingerprint: set_fingerprint(&empty),
-            content_ptr: empty_ptr,
+            revision: provider.revision(),
         };
         let ctx = BlockContext {
-            bytes: &[],
+            bytes: &provider,
             total_len: 100,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &empty,
+            dirty: &[],
             vanilla_diff: &empty,
             color_scheme: ColorScheme::Nybble,
             dim_nulls: false,
@@ -1181,7 +1201,7 @@ mod tests {
     #[test]
     fn minimap_cache_valid_detects_dirty_fingerprint_change() {
         let empty: BTreeSet<u64> = BTreeSet::new();
-        let empty_ptr = (&[] as &[u8]).as_ptr() as usize;
+        let provider = MappedProvider::from_bytes(Vec::new());
         let cache = MinimapCache {
             columns: [vec![Color::WHITE], vec![], vec![], vec![]],
             total_len: 100,
@@ -1189,13 +1209,13 @@ mod tests {
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
             pattern_hash: 0,
-            dirty_fingerprint: set_fingerprint(&empty),
+            dirty_fingerprint: ranges_fingerprint(&[]),
             diff_fingerprint: set_fingerprint(&empty),
-            content_ptr: empty_ptr,
+            revision: provider.revision(),
         };
-        let dirty: BTreeSet<u64> = [1, 2, 3].into_iter().collect();
+        let dirty = [1..4];
         let ctx = BlockContext {
-            bytes: &[],
+            bytes: &provider,
             total_len: 100,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
@@ -1208,6 +1228,46 @@ mod tests {
         assert!(!minimap_cache_valid(&cache, 10, &ctx));
     }
 
+    #[test]
+    fn minimap_cache_valid_detects_edit() {
+        let empty: BTreeSet<u64> = BTreeSet::new();
+        let mut provider = MappedProvider::from_bytes(vec![0; 100]);
+        let cache = MinimapCache {
+            columns: [vec![Color::WHITE], vec![], vec![], vec![]],
+            total_len: 100,
+            h_px: 10,
+            color_scheme: ColorScheme::Monochrome,
+            dim_nulls: false,
+            pattern_hash: 0,
+            dirty_fingerprint: ranges_fingerprint(&[]),
+            diff_fingerprint: set_fingerprint(&empty),
+            revision: provider.revision(),
+        };
+        provider.write(3, &[0xFF]);
+        let ctx = BlockContext {
+            bytes: &provider,
+            total_len: 100,
+            pattern_by_addr: &BTreeMap::new(),
+            alternate_patterns: &BTreeSet::new(),
+            dirty: &[],
+            vanilla_diff: &empty,
+            color_scheme: ColorScheme::Monochrome,
+            dim_nulls: false,
+            theme: &DARK_THEME,
+        };
+        assert!(!minimap_cache_valid(&cache, 10, &ctx));
+    }
+
+    #[test]
+    fn ranges_overlap_checks_half_open_bounds() {
+        let ranges = [2..4, 10..12];
+        assert!(ranges_overlap(&ranges, 3..4));
+        assert!(ranges_overlap(&ranges, 0..11));
+        assert!(!ranges_overlap(&ranges, 4..10));
+        assert!(!ranges_overlap(&ranges, 12..20));
+        assert!(!ranges_overlap(&[], 0..1));
+    }
+
     // ── compute_block_pixels —─────────────────────────────────────────
 
     #[test]
@@ -1220,11 +1280,11 @@ mod tests {
         // brightness=0.30) but alternate between dim 0x00 and brighter 0xFF.
         let bytes = [0x00u8, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00];
         let ctx = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 9,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -1373,22 +1433,22 @@ mod tests {
         let uniform_bytes = [0x80u8; 24];
 
         let ctx_mixed = BlockContext {
-            bytes: &bytes,
+            bytes: &MappedProvider::from_bytes(bytes.to_vec()),
             total_len: 24,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
             theme: &DARK_THEME,
         };
         let ctx_uniform = BlockContext {
-            bytes: &uniform_bytes,
+            bytes: &MappedProvider::from_bytes(uniform_bytes.to_vec()),
             total_len: 24,
             pattern_by_addr: &BTreeMap::new(),
             alternate_patterns: &BTreeSet::new(),
-            dirty: &BTreeSet::new(),
+            dirty: &[],
             vanilla_diff: &BTreeSet::new(),
             color_scheme: ColorScheme::Monochrome,
             dim_nulls: false,
@@ -82,12 +82,12 @@ fn matrix_content<'a>(state: &'a HexEditorState) -> Element<'a, HexEditorMessage
     }
 
     HexMatrix::new(
-        state.provider.as_slice(),
+        &state.provider,
         state.layout.as_deref(),
         state.bytes_per_row,
         state.selection,
         edit,
-        state.provider.dirty(),
+        state.provider.dirty_ranges(),
         &state.vanilla_diff,
         &state.pattern_by_addr,
         &state.search.match_set,
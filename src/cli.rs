@@ -4,6 +4,8 @@ use clap::{Args, Parser, Subcommand, ValueEnum};
 
 use crate::commands::list::ListArgs;
 use crate::commands::pack::ModPackArgs;
+use crate::commands::refs::RefsArgs;
+use crate::commands::renumber::RenumberArgs;
 use crate::commands::schema::SchemaArgs;
 use crate::commands::template::TemplateArgs;
 use crate::commands::unified::{ExtractArgs, PatchArgs};
@@ -64,7 +66,7 @@ pub enum Commands {
     /// Map operations (tiles, atlas, render)
     #[command(
         about = "Extract and render map assets",
-        long_about = "Operations for handling binary .MAP files and their associated .GTL/.BTL tilesets.\n\nUsage Examples:\n  dispel-extractor map tiles cat1.gtl\n  dispel-extractor map atlas cat1.gtl atlas.png\n  dispel-extractor map render --map cat1.map --btl cat1.btl --gtl cat1.gtl --output map.png"
+        long_about = "Operations for handling binary .MAP files and their associated .GTL/.BTL tilesets.\n\nUsage Examples:\n  dispel-extractor map tiles cat1.gtl\n  dispel-extractor map atlas cat1.gtl atlas.png\n  dispel-extractor map render --map cat1.map --btl cat1.btl --gtl cat1.gtl --output map.png\n  dispel-extractor map render --map cat1.map --btl cat1.btl --gtl cat1.gtl --output map.png --game-path fixtures/Dispel --html map.html"
     )]
     Map(MapArgs),
 
@@ -102,13 +104,57 @@ pub enum Commands {
     /// Audio conversion
     #[command(
         about = "Convert SNF/WAV audio files",
-        long_about = "Convert SNF to WAV or WAV to SNF.\n\nExamples:\n  dispel-extractor sound to-wav track.snf track.wav\n  dispel-extractor sound from-wav track.wav track.snf"
+        long_about = "Convert SNF to WAV or WAV to SNF, one file or the whole Wave.ini library at once.\n\nExamples:\n  dispel-extractor sound to-wav track.snf track.wav\n  dispel-extractor sound from-wav track.wav track.snf\n  dispel-extractor sound from-audio music.ogg Sound/Music.snf\n  dispel-extractor sound export-all -g fixtures/Dispel -o sounds\n  dispel-extractor sound import-all -i sounds -o mod --normalize -16\n  dispel-extractor sound check -g fixtures/Dispel\n  dispel-extractor sound render fixtures/Dispel -o audit"
     )]
     Sound {
         #[command(subcommand)]
         command: SoundCommands,
     },
 
+    /// Save file tools
+    #[command(
+        about = "Inspect and compare .sav save files",
+        long_about = "Tools for Dispel save files. `diff` parses two saves and lists the changed fields per section (character stats, inventory, map monsters/NPCs, events, journal, ...) together with the changed byte ranges relative to each section, which locates changes inside reserved fields. Save once before and once after an in-game action to see what it touches.\n\nUsage Examples:\n  dispel-extractor save diff fixtures/Dispel/zapisy/3.sav fixtures/Dispel/zapisy/4.sav\n  dispel-extractor save diff before.sav after.sav --section events\n  dispel-extractor save diff before.sav after.sav --json"
+    )]
+    Save {
+        #[command(subcommand)]
+        command: SaveCommands,
+    },
+
+    /// Cross-file record usages
+    #[command(
+        about = "List every place that references a record",
+        long_about = "Scans a game directory and lists where a record id is referenced: Store.db products, DRAWITEM.ref placements, event-script [ACT] parameters, DLG event triggers, PartyRef/PrtLevel links, monster placements and drops, Extra.ref objects, NPC placements and save files.\n\nUsage Examples:\n  dispel-extractor refs -g fixtures/Dispel --type heal_items --id 7\n  dispel-extractor refs -g fixtures/Dispel --type monsters --id 12 --json"
+    )]
+    Refs(RefsArgs),
+
+    /// Delete, insert or move a catalog record and fix every reference to it
+    #[command(
+        about = "Delete, insert or move a catalog record and fix references",
+        long_about = "Item databases, Monster.db, Magic.db and PrtIni.db identify records by position, so deleting or moving one shifts every later id. This command edits the catalog and rewrites each reference found by `refs` (stores, drops, placements, event scripts, party levels, saves). It prints a preview and, with --output, writes the edited files as a mod package; the game directory itself is never modified.\n\nUsage Examples:\n  dispel-extractor renumber -g fixtures/Dispel --type heal_items --delete 12 --redirect 3\n  dispel-extractor renumber -g fixtures/Dispel --type weapons --move 40 2 -o reorder.zip\n  dispel-extractor renumber -g fixtures/Dispel --type monsters --insert 5 --copy-of 4 --json"
+    )]
+    Renumber(RenumberArgs),
+
+    /// Design reports across catalogs and maps
+    #[command(
+        about = "Generate design reports from the game data",
+        long_about = "Reports that join several game files into tables for designers.\n\nUsage Examples:\n  dispel-extractor report balance -g fixtures/Dispel -o balance.html\n  dispel-extractor report balance -g fixtures/Dispel --format csv -o balance/\n  dispel-extractor report balance -g fixtures/Dispel --format json\n  dispel-extractor report loot -g fixtures/Dispel --type weapons --id 12\n  dispel-extractor report quests -g fixtures/Dispel -o quests.dot"
+    )]
+    Report {
+        #[command(subcommand)]
+        command: ReportCommands,
+    },
+
+    /// Localization pipeline
+    #[command(
+        about = "Scan, translate, apply and package game text",
+        long_about = "Localization pipeline over a whole game installation. Translations are kept in a session file at <game>/mods/<mod>/session.json so each step can run separately (e.g. in CI).\n\nUsage Examples:\n  dispel-extractor l10n scan -g fixtures/Dispel\n  dispel-extractor l10n export -g fixtures/Dispel -o strings.po --target-lang pl\n  dispel-extractor l10n import -g fixtures/Dispel -m polish -i strings.po\n  dispel-extractor l10n apply -g fixtures/Dispel -m polish\n  dispel-extractor l10n package -g fixtures/Dispel -m polish -i strings.po -o polish.zip --version 1.0\n  dispel-extractor l10n export -g fixtures/Dispel -m polish -o strings.xlf --target-lang pl\n  dispel-extractor l10n tm -g fixtures/Dispel -m polish --min-score 0.9\n  dispel-extractor l10n glossary -g fixtures/Dispel -m polish --terms glossary.csv --strict\n  dispel-extractor l10n encoding -g fixtures/Dispel -m russian --fix\n  dispel-extractor l10n revert -g fixtures/Dispel -m polish"
+    )]
+    L10n {
+        #[command(subcommand)]
+        command: L10nCommands,
+    },
+
     /// Dialog flow visualization
     #[command(
         about = "Print dialog flow from DLG and PGP files",
@@ -159,6 +205,392 @@ pub enum SoundCommands {
         /// Destination .SNF file
         output: String,
     },
+    /// Convert OGG Vorbis, FLAC, MP3 or WAV to SNF
+    #[command(
+        long_about = "Decodes OGG Vorbis, FLAC, MP3 or WAV and writes it as SNF, resampling, mixing channels and changing the sample size as needed. The target format is the one of the SNF being replaced at the output path; a new file keeps the source's sample rate and channels at 16 bits or less. --sample-rate, --channels and --bits override single fields.\n\nUsage Examples:\n  dispel-extractor sound from-audio music.ogg fixtures/Dispel/Sound/Music.snf\n  dispel-extractor sound from-audio hit.flac hit.snf --sample-rate 22050 --channels 1 --bits 8"
+    )]
+    FromAudio {
+        /// Source .ogg, .flac, .mp3 or .wav file
+        input: String,
+        /// Destination .SNF file
+        output: String,
+        /// Target sample rate in Hz
+        #[arg(long)]
+        sample_rate: Option<i32>,
+        /// Target channel count (1 = mono, 2 = stereo)
+        #[arg(long)]
+        channels: Option<i16>,
+        /// Target bits per sample (8 or 16 for the game)
+        #[arg(long)]
+        bits: Option<i16>,
+    },
+    /// Export every SNF listed in Wave.ini to a WAV folder with a manifest
+    #[command(
+        long_about = "Converts every SNF file Wave.ini names to WAV, keeping the folder layout (Sound/hit.snf becomes <output>/Sound/hit.wav), and writes manifest.json with each id, its SNF path and its original format. SNF files are found by name, ignoring case, anywhere under the game directory. Edit the WAVs and bring them back with import-all.\n\nUsage Examples:\n  dispel-extractor sound export-all -g fixtures/Dispel -o sounds"
+    )]
+    ExportAll {
+        /// Path to the Dispel game directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Output folder for the WAV files and manifest.json
+        #[arg(short, long)]
+        output: String,
+    },
+    /// Import a WAV folder written by export-all back to SNF
+    #[command(
+        long_about = "Reads manifest.json from the input folder and writes each WAV back as SNF at <output>/<SNF path>. WAVs in another sample rate, channel count or sample size are converted to the format of the original SNF; with --normalize every sound is scaled to the given RMS level in dBFS without clipping. Pass the game directory as output to overwrite the game's files. Wave.ini is not changed.\n\nUsage Examples:\n  dispel-extractor sound import-all -i sounds -o mod\n  dispel-extractor sound import-all -i sounds -o mod --normalize -16"
+    )]
+    ImportAll {
+        /// Folder written by export-all
+        #[arg(short, long)]
+        input: String,
+        /// Output folder; SNF files keep their path relative to the game directory
+        #[arg(short, long)]
+        output: String,
+        /// Target RMS level in dBFS, e.g. -16
+        #[arg(long, allow_hyphen_values = true)]
+        normalize: Option<f32>,
+    },
+    /// Render waveform and spectrogram PNGs and a level report for SNF files
+    #[command(
+        long_about = "Renders <path>.waveform.png and <path>.spectrogram.png for every SNF file given or found under a given folder, and writes report.json with each file's format, duration, peak and RMS level (dBFS), clipped-sample count, DC offset and leading/trailing silence, plus the number of files per sample rate. Files with clipping, DC offset or only silence are listed on stderr. Rendering is done on the CPU and needs no display.\n\nUsage Examples:\n  dispel-extractor sound render fixtures/Dispel -o audit\n  dispel-extractor sound render Sound/Hit.snf Sound/Music.snf -o audit --width 2048\n  dispel-extractor sound render fixtures/Dispel -o audit --stats-only --fail-on-issues"
+    )]
+    Render {
+        /// SNF files or folders to search for them
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Output folder for the PNGs and report.json
+        #[arg(short, long)]
+        output: String,
+        /// Image width in pixels
+        #[arg(long, default_value_t = 1024, value_parser = clap::value_parser!(u32).range(1..))]
+        width: u32,
+        /// Image height in pixels
+        #[arg(long, default_value_t = 256, value_parser = clap::value_parser!(u32).range(1..))]
+        height: u32,
+        /// Samples per spectrogram column (rounded up to a power of two)
+        #[arg(long, default_value_t = 512)]
+        fft_size: usize,
+        /// Level below which samples count as silence, in dBFS
+        #[arg(long, default_value_t = -60.0, allow_hyphen_values = true)]
+        silence_db: f32,
+        /// Only write report.json
+        #[arg(long)]
+        stats_only: bool,
+        /// Exit with an error when any file clips, is silent or has a DC offset
+        #[arg(long)]
+        fail_on_issues: bool,
+    },
+    /// Report missing and unused SNF files and undefined sound ids
+    #[command(
+        long_about = "Checks Wave.ini against the SNF files on disk and the [WAV] sections of Ref/Event*.scr: Wave.ini entries whose SNF is missing, SNF files no entry names, and script sound ids Wave.ini does not define. Ids no script names are listed too, but the engine plays most sounds by id itself.\n\nUsage Examples:\n  dispel-extractor sound check -g fixtures/Dispel\n  dispel-extractor sound check -g fixtures/Dispel --json --fail-on-missing"
+    )]
+    Check {
+        /// Path to the Dispel game directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+        /// Exit with an error when an SNF file or script sound id is missing
+        #[arg(long)]
+        fail_on_missing: bool,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum SaveCommands {
+    /// Report the differences between two save files
+    Diff {
+        /// Earlier .sav file
+        a: String,
+        /// Later .sav file
+        b: String,
+        /// Only report sections whose name contains this text
+        #[arg(short, long)]
+        section: Option<String>,
+        /// Print the diff as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+// --------------------------------------------------------------------------
+// Report subcommands
+// --------------------------------------------------------------------------
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+    Html,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Json,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ReportCommands {
+    /// Economy and progression tables: per-map XP and income, party level curve, shop prices, price outliers
+    #[command(
+        long_about = "Joins item prices, STORE.DB products and price modifiers, Monster.db XP and gold ranges, monster and chest loot from each map's Mondun/Extdun refs, and the PrtLevel.db stat curve.\n\nTables:\n  maps            per map in AllMap.ini order: expected XP, gold, loot value, running totals and shop products affordable\n  levels          party stats per level (the XP needed per level is not stored in the data files)\n  shop_items      every store product with buy and sell price\n  price_outliers  weapons, edit and heal items whose price per stat point is over 2x off their catalog median\n\nJSON and HTML go to --output or stdout; CSV writes one file per table into the --output directory.\n\nUsage Examples:\n  dispel-extractor report balance -g fixtures/Dispel -o balance.html\n  dispel-extractor report balance -g fixtures/Dispel --format csv -o balance/"
+    )]
+    Balance {
+        /// Path to the Dispel game directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Html)]
+        format: ReportFormat,
+        /// Output file (JSON, HTML) or directory (CSV)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Per-map encounter difficulty scores and spikes against the expected party strength
+    #[command(
+        long_about = "Joins each map's monster placements with Monster.db stats (HP, to-hit, dodge, defense, offense, magic attack, AI type, special attack) and scores them against an average party member built from PrtLevel.db and ChData.db at the level the party is expected to have on that map (maps in AllMap.ini order, placed on the level curve by their share of the monster XP handed out before them).\n\nA monster's threat is the share of a party member's HP lost while killing it one on one; a map's score is its mean threat in percent. Spikes list maps scoring over 1.5x the previous map, monsters over 3x their map's mean and monsters that win a one-on-one fight.\n\nUsage Examples:\n  dispel-extractor report difficulty -g fixtures/Dispel -o difficulty.html\n  dispel-extractor report difficulty -g fixtures/Dispel --format csv -o difficulty/\n  dispel-extractor report difficulty -g mods/rebalance --format json --fail-on-spikes"
+    )]
+    Difficulty {
+        /// Path to the Dispel game directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Html)]
+        format: ReportFormat,
+        /// Output file (JSON, HTML) or directory (CSV)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Exit with an error when any spike is found
+        #[arg(long)]
+        fail_on_spikes: bool,
+    },
+    /// Where each item can be obtained and what each map drops
+    #[command(
+        long_about = "Collects every loot source with the item resolved to its catalog record: monster drops (Mondun refs, loot_item_1..3, one slot per kill unless drop_all_loot), extras such as chests (Extdun refs, loot_item x loot_item_count and gold_amount), NPC rewards (interaction_result_item), items lying on maps (Ref/DRAWITEM.ref) and STORE.DB products.\n\nTables:\n  maps     per map in AllMap.ini order: source counts, distinct items, chest gold and item value\n  sources  one row per item source with map, file, record and position\n\n--type/--id answer \"where can I get this item?\"; --map answers \"what does this map drop?\". Both can be combined.\n\nUsage Examples:\n  dispel-extractor report loot -g fixtures/Dispel -o loot.html\n  dispel-extractor report loot -g fixtures/Dispel --type heal_items --id 7 --format json\n  dispel-extractor report loot -g fixtures/Dispel --map cat1 --format csv -o loot/"
+    )]
+    Loot {
+        /// Path to the Dispel game directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Html)]
+        format: ReportFormat,
+        /// Output file (JSON, HTML) or directory (CSV)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Item database of the item to look up (weapons, heal_items, edit_items, event_items, misc_items)
+        #[arg(short = 't', long = "type", requires = "id")]
+        record_type: Option<String>,
+        /// Record id of the item to look up
+        #[arg(short, long, requires = "record_type")]
+        id: Option<i32>,
+        /// Only this map, by AllMap.ini file or display name
+        #[arg(short, long)]
+        map: Option<String>,
+    },
+    /// Event dependency graph: where quests start and finish, and events nothing triggers
+    #[command(
+        long_about = "Links every Event.ini event to what requires, triggers or advances it: Event.ini prerequisites (required_event_id), addquest calls in Ref/Event<id>.scr, dialogue lines that need or trigger an event (each map's .dlg), monster kills (event_id_on_kill), extra interactions (interaction_event_id), map tile events and Map.ini camera events.\n\nThe lowest state addquest gives a quest counts as its start and the highest as its finish. Untriggered events are required by an event or dialogue but triggered by nothing.\n\nDOT renders with Graphviz; JSON holds the nodes, edges, per-quest start/finish events and the untriggered and undefined events.\n\nUsage Examples:\n  dispel-extractor report quests -g fixtures/Dispel -o quests.dot\n  dot -Tsvg quests.dot -o quests.svg\n  dispel-extractor report quests -g fixtures/Dispel --quest 12 -o quest12.dot\n  dispel-extractor report quests -g fixtures/Dispel --format json --fail-on-untriggered"
+    )]
+    Quests {
+        /// Path to the Dispel game directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+        /// Output file (stdout when omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Only the part of the graph leading up to this Quest.scr id
+        #[arg(short, long)]
+        quest: Option<i32>,
+        /// Exit with an error when any required event is never triggered
+        #[arg(long)]
+        fail_on_untriggered: bool,
+    },
+}
+
+// --------------------------------------------------------------------------
+// Localization subcommands
+// --------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum L10nCommands {
+    /// Summarise translatable strings per file
+    Scan {
+        /// Game root directory
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Mod name whose session should be merged into the summary
+        #[arg(short, long)]
+        mod_name: Option<String>,
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export strings to a CSV, PO or XLIFF 2.0 file (chosen by extension)
+    Export {
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Destination .csv, .po or .xlf file
+        #[arg(short, long)]
+        output: String,
+        /// Mod name whose session translations are included
+        #[arg(short, long)]
+        mod_name: Option<String>,
+        /// Target language code written to the PO header
+        #[arg(long, default_value = "")]
+        target_lang: String,
+    },
+    /// Import a CSV, PO or XLIFF file into the mod session
+    Import {
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Mod name; session and backups live in <game>/mods/<mod>
+        #[arg(short, long)]
+        mod_name: String,
+        /// Source .csv, .po or .xlf file
+        #[arg(short, long)]
+        input: String,
+    },
+    /// Write translations into the game files, backing up originals
+    Apply {
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Mod name; session and backups live in <game>/mods/<mod>
+        #[arg(short, long)]
+        mod_name: String,
+        /// Optional .csv, .po or .xlf file merged on top of the session first
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Write text even if the encoding cannot represent some characters
+        #[arg(long)]
+        allow_lossy: bool,
+        /// Also write fuzzy memory fills that are still marked for review
+        #[arg(long)]
+        include_unreviewed: bool,
+        /// Transliterate with the built-in punctuation/Cyrillic table before encoding
+        #[arg(long)]
+        transliterate: bool,
+        /// Transliteration CSV (from,to) applied before encoding; layered over
+        /// the built-in table when --transliterate is given
+        #[arg(long)]
+        table: Option<String>,
+    },
+    /// Package translations as a mod zip without touching game files
+    Package {
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Mod name; session and backups live in <game>/mods/<mod>
+        #[arg(short, long)]
+        mod_name: String,
+        /// Optional .csv, .po or .xlf file merged on top of the session first
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Destination mod zip
+        #[arg(short, long)]
+        output: String,
+        /// Mod version written to the manifest
+        #[arg(long, default_value = "")]
+        version: String,
+        /// Mod author written to the manifest
+        #[arg(long, default_value = "")]
+        author: String,
+        /// Mod description written to the manifest
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Write text even if the encoding cannot represent some characters
+        #[arg(long)]
+        allow_lossy: bool,
+        /// Also write fuzzy memory fills that are still marked for review
+        #[arg(long)]
+        include_unreviewed: bool,
+        /// Transliterate with the built-in punctuation/Cyrillic table before encoding
+        #[arg(long)]
+        transliterate: bool,
+        /// Transliteration CSV (from,to) applied before encoding; layered over
+        /// the built-in table when --transliterate is given
+        #[arg(long)]
+        table: Option<String>,
+    },
+    /// Fill untranslated strings from a translation memory
+    Tm {
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Mod name; session and backups live in <game>/mods/<mod>
+        #[arg(short, long)]
+        mod_name: String,
+        /// Extra memory (JSON) merged on top of the session's own translations
+        #[arg(long)]
+        memory: Option<String>,
+        /// Minimum similarity (0-1) for fuzzy matches; 1 for exact only
+        #[arg(long, default_value_t = 0.85)]
+        min_score: f32,
+        /// Write the resulting memory (JSON) for reuse in other projects
+        #[arg(long)]
+        save_memory: Option<String>,
+        /// Report matches without updating the session
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check that names are translated consistently everywhere
+    Glossary {
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Mod name; session and backups live in <game>/mods/<mod>
+        #[arg(short, long)]
+        mod_name: String,
+        /// Glossary CSV (source,target) overriding learned terms
+        #[arg(long)]
+        terms: Option<String>,
+        /// Print issues as JSON
+        #[arg(long)]
+        json: bool,
+        /// Exit with an error when any issue is found
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Report characters the game encodings cannot represent
+    Encoding {
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Mod name; session and backups live in <game>/mods/<mod>
+        #[arg(short, long)]
+        mod_name: String,
+        /// Transliteration CSV (from,to) layered over the built-in table
+        #[arg(long)]
+        table: Option<String>,
+        /// Do not use the built-in punctuation/Cyrillic table
+        #[arg(long)]
+        no_builtin: bool,
+        /// Transliterate the session translations in place
+        #[arg(long)]
+        fix: bool,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Restore original files from the mod backup
+    Revert {
+        /// Game root directory
+        #[arg(short, long)]
+        game_path: String,
+        /// Mod name; session and backups live in <game>/mods/<mod>
+        #[arg(short, long)]
+        mod_name: String,
+    },
 }
 
 // --------------------------------------------------------------------------
@@ -267,6 +699,51 @@ pub enum MapCommands {
         /// Show NPC waypoint arrows (coloured per waypoint slot: green→blue→red→yellow, with index labels)
         #[arg(long)]
         npc_waypoints: bool,
+        /// Show a heat-map of walking distance from the Map.ini start position (requires --game-path)
+        #[arg(long)]
+        reachability: bool,
+
+        // Vector overlay export
+        /// Also write an SVG overlay with hoverable NPCs, monsters, extras, draw items and events
+        #[arg(long, value_name = "SVG_FILE")]
+        svg: Option<String>,
+        /// Also write a self-contained HTML viewer (PNG + interactive SVG overlay)
+        #[arg(long, value_name = "HTML_FILE")]
+        html: Option<String>,
+    },
+    /// Render every map listed in AllMap.ini
+    #[command(
+        about = "Render all maps with a world atlas index",
+        long_about = "Discovers every map through AllMap.ini and Ref/Map.ini, renders each one with entity overlays in parallel, and writes index.json and index.html with map names, dimensions, lighting and links.\n\nUsage Examples:\n  dispel-extractor map render-all --game-path fixtures/Dispel --output atlas/\n  dispel-extractor map render-all --game-path fixtures/Dispel --output atlas/ --jobs 4 --events"
+    )]
+    RenderAll {
+        /// Path to the Dispel game directory
+        #[arg(long)]
+        game_path: String,
+        /// Output directory for the PNGs and the index
+        #[arg(short, long, default_value = "atlas")]
+        output: String,
+        /// Number of maps rendered in parallel (0 = all CPU cores)
+        #[arg(short, long, default_value = "0")]
+        jobs: usize,
+        /// Render the full map canvas instead of the occluded viewport
+        #[arg(long)]
+        full_map: bool,
+        /// Show collision overlay
+        #[arg(long)]
+        collisions: bool,
+        /// Show event overlay
+        #[arg(long)]
+        events: bool,
+        /// Show draw items overlay
+        #[arg(long)]
+        draw_items: bool,
+        /// Show NPC waypoint arrows
+        #[arg(long)]
+        npc_waypoints: bool,
+        /// Show a heat-map of walking distance from each map's start position
+        #[arg(long)]
+        reachability: bool,
     },
     /// Render a map from SQLite database
     #[command(
@@ -356,6 +833,38 @@ pub enum MapCommands {
         #[arg(short, long, default_value = "tmx_out")]
         output: String,
     },
+    /// Check NPC, monster and extra placements against map collisions
+    #[command(
+        about = "Validate entity placements against collisions",
+        long_about = "Loads each map with the Mondun/Npccat/Extdun refs assigned to it in Ref/Map.ini and reports entities on blocked or out-of-bounds tiles, NPC waypoints that cannot be walked to, wandering NPCs without a walkable tile and overlapping extra footprints. Exits with an error when any error-level issue is found.\n\nUsage Examples:\n  dispel-extractor map validate --game-path fixtures/Dispel\n  dispel-extractor map validate --game-path fixtures/Dispel --map cat1 --json"
+    )]
+    Validate {
+        /// Path to the Dispel game directory
+        #[arg(long)]
+        game_path: String,
+        /// Only validate this map (AllMap.ini filename, e.g. "cat1")
+        #[arg(short, long)]
+        map: Option<String>,
+        /// Print the issues as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Report what cannot be walked to from the player's start position
+    #[command(
+        about = "Find event tiles, extras and NPCs unreachable from the start",
+        long_about = "Floods each map's collision grid from the start position Ref/Map.ini gives it, using the game's 8-direction movement without corner cutting, and lists the event tiles, extras and NPCs the player can never walk to or stand next to. Also prints how many walkable tiles are reachable. Use `map render --reachability` to see the distance heat-map.\n\nUsage Examples:\n  dispel-extractor map reachability --game-path fixtures/Dispel\n  dispel-extractor map reachability --game-path fixtures/Dispel --map cat1 --json"
+    )]
+    Reachability {
+        /// Path to the Dispel game directory
+        #[arg(long)]
+        game_path: String,
+        /// Only analyse this map (AllMap.ini filename, e.g. "cat1")
+        #[arg(short, long)]
+        map: Option<String>,
+        /// Print the reports as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 // --------------------------------------------------------------------------
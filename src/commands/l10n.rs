@@ -0,0 +1,436 @@
+use super::Command;
+use crate::cli::L10nCommands;
+use dispel_core::localization::project::{self, LocalizationProject};
+use dispel_core::localization::transliterate::{encoding_report, transliterate_entries};
+use dispel_core::localization::{Glossary, GlossaryIssue, TranslationMemory, TransliterationTable};
+use dispel_core::modding::{ModManifest, write_zip};
+use dispel_core::{export_csv, export_po, export_xliff, import_csv, import_po, import_xliff};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+
+pub struct L10nCommand {
+    pub command: L10nCommands,
+}
+
+impl Command for L10nCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        match &self.command {
+            L10nCommands::Scan {
+                game_path,
+                mod_name,
+                json,
+            } => {
+                let project = open_project(game_path, mod_name.as_deref())?;
+                print_summary(&project, *json)
+            }
+            L10nCommands::Export {
+                game_path,
+                output,
+                mod_name,
+                target_lang,
+            } => {
+                let project = open_project(game_path, mod_name.as_deref())?;
+                let content = match Format::of(output) {
+                    Format::Po => export_po(&project.entries, "ko", target_lang),
+                    Format::Xliff => export_xliff(&project.entries, "ko", target_lang),
+                    Format::Csv => export_csv(&project.entries)
+                        .map_err(|e| format!("ERROR: could not export CSV: {e}"))?,
+                };
+                std::fs::write(output, content)
+                    .map_err(|e| format!("ERROR: could not write {output}: {e}"))?;
+                eprintln!("Exported {} strings to {output}", project.entries.len());
+                Ok(())
+            }
+            L10nCommands::Import {
+                game_path,
+                mod_name,
+                input,
+            } => {
+                let mut project = open_project(game_path, Some(mod_name))?;
+                let imported = import_file(&mut project, input)?;
+                let session = project::session_path(Path::new(game_path), mod_name);
+                project
+                    .save_session(&session)
+                    .map_err(|e| format!("ERROR: could not save session: {e}"))?;
+                eprintln!(
+                    "Imported {imported} strings; session now holds {} translations ({} overlong) at {}",
+                    project.translated_count(),
+                    project.overlong_count(),
+                    session.display()
+                );
+                Ok(())
+            }
+            L10nCommands::Apply {
+                game_path,
+                mod_name,
+                input,
+                allow_lossy,
+                include_unreviewed,
+                transliterate,
+                table,
+            } => {
+                let mut project = open_with_input(game_path, mod_name, input.as_deref())?;
+                project.allow_lossy = *allow_lossy;
+                project.include_unreviewed = *include_unreviewed;
+                if *transliterate || table.is_some() {
+                    project.transliteration = Some(load_table(*transliterate, table.as_deref())?);
+                }
+                warn_before_write(&project);
+                let backup = project::backup_dir(Path::new(game_path), mod_name);
+                let written = project
+                    .apply(&backup)
+                    .map_err(|e| format!("ERROR: could not apply translations: {e}"))?;
+                for path in &written {
+                    eprintln!("  {path}");
+                }
+                eprintln!(
+                    "Applied translations to {} files; originals backed up in {}",
+                    written.len(),
+                    backup.display()
+                );
+                Ok(())
+            }
+            L10nCommands::Package {
+                game_path,
+                mod_name,
+                input,
+                output,
+                version,
+                author,
+                description,
+                allow_lossy,
+                include_unreviewed,
+                transliterate,
+                table,
+            } => {
+                let mut project = open_with_input(game_path, mod_name, input.as_deref())?;
+                project.allow_lossy = *allow_lossy;
+                project.include_unreviewed = *include_unreviewed;
+                if *transliterate || table.is_some() {
+                    project.transliteration = Some(load_table(*transliterate, table.as_deref())?);
+                }
+                warn_before_write(&project);
+                let mut manifest = ModManifest::new(mod_name.trim());
+                manifest.version = version.clone();
+                manifest.author = author.clone();
+                manifest.description = description.clone();
+                // Build from backups when present so an earlier `apply` does
+                // not leak into the packaged originals.
+                let backup = project::backup_dir(Path::new(game_path), mod_name);
+                let package = project
+                    .package(manifest, Some(&backup))
+                    .map_err(|e| format!("ERROR: could not package translations: {e}"))?;
+                let file = std::fs::File::create(output)
+                    .map_err(|e| format!("ERROR: could not create {output}: {e}"))?;
+                write_zip(file, &package)
+                    .map_err(|e| format!("ERROR: could not write mod package: {e}"))?;
+                eprintln!(
+                    "Packaged {} translated files into {output}",
+                    package.changes.len()
+                );
+                Ok(())
+            }
+            L10nCommands::Tm {
+                game_path,
+                mod_name,
+                memory,
+                min_score,
+                save_memory,
+                dry_run,
+            } => {
+                let mut project = open_project(game_path, Some(mod_name))?;
+                let mut tm = TranslationMemory::from_entries(&project.entries);
+                if let Some(path) = memory {
+                    let external = TranslationMemory::load(Path::new(path))
+                        .map_err(|e| format!("ERROR: could not load memory {path}: {e}"))?;
+                    tm.merge(&external);
+                }
+                let fills = tm.fill(&mut project.entries, *min_score);
+                for f in fills.iter().filter(|f| !f.is_exact()) {
+                    let e = &project.entries[f.index];
+                    println!(
+                        "fuzzy {:>3.0}%  {}:{} {}  {:?} ~ {:?}",
+                        f.score * 100.0,
+                        e.file_path,
+                        e.record_id,
+                        e.field_name,
+                        e.original,
+                        f.source
+                    );
+                }
+                let exact = fills.iter().filter(|f| f.is_exact()).count();
+                eprintln!(
+                    "Memory holds {} units; filled {exact} exact and {} fuzzy matches (marked for review)",
+                    tm.len(),
+                    fills.len() - exact
+                );
+                if let Some(path) = save_memory {
+                    TranslationMemory::from_entries(&project.entries)
+                        .save(Path::new(path))
+                        .map_err(|e| format!("ERROR: could not save memory {path}: {e}"))?;
+                }
+                if !*dry_run {
+                    let session = project::session_path(Path::new(game_path), mod_name);
+                    project
+                        .save_session(&session)
+                        .map_err(|e| format!("ERROR: could not save session: {e}"))?;
+                }
+                Ok(())
+            }
+            L10nCommands::Glossary {
+                game_path,
+                mod_name,
+                terms,
+                json,
+                strict,
+            } => {
+                let project = open_project(game_path, Some(mod_name))?;
+                let mut glossary = Glossary::from_entries(&project.entries);
+                if let Some(path) = terms {
+                    let csv = std::fs::read_to_string(path)
+                        .map_err(|e| format!("ERROR: could not read {path}: {e}"))?;
+                    let user = Glossary::from_csv(&csv)
+                        .map_err(|e| format!("ERROR: could not parse glossary {path}: {e}"))?;
+                    glossary.merge(&user);
+                }
+                let issues = glossary.check(&project.entries);
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&issues)?);
+                } else {
+                    for issue in &issues {
+                        match issue {
+                            GlossaryIssue::Inconsistent {
+                                source,
+                                translations,
+                            } => println!("inconsistent  {source:?} -> {translations:?}"),
+                            GlossaryIssue::MissingTerm {
+                                file_path,
+                                record_id,
+                                field_name,
+                                source,
+                                expected,
+                                ..
+                            } => println!(
+                                "missing term  {file_path}:{record_id} {field_name}: {source:?} should be {expected:?}"
+                            ),
+                        }
+                    }
+                }
+                eprintln!("{} terms, {} issues", glossary.len(), issues.len());
+                if *strict && !issues.is_empty() {
+                    return Err(format!("ERROR: {} glossary issues", issues.len()).into());
+                }
+                Ok(())
+            }
+            L10nCommands::Encoding {
+                game_path,
+                mod_name,
+                table,
+                no_builtin,
+                fix,
+                json,
+            } => {
+                let mut project = open_project(game_path, Some(mod_name))?;
+                let translit = load_table(!*no_builtin, table.as_deref())?;
+                let issues = encoding_report(&project.entries, &translit);
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&issues)?);
+                } else {
+                    for i in &issues {
+                        println!(
+                            "{}:{} {} [{}]  fixable: {}  lossy: {}",
+                            i.file_path,
+                            i.record_id,
+                            i.field_name,
+                            i.encoding,
+                            i.transliterable.iter().collect::<String>(),
+                            i.lossy.iter().collect::<String>()
+                        );
+                    }
+                }
+                let lossy = issues.iter().filter(|i| !i.lossy.is_empty()).count();
+                eprintln!(
+                    "{} entries with unrepresentable characters, {lossy} not fixable by the table",
+                    issues.len()
+                );
+                if *fix {
+                    let changed = transliterate_entries(&mut project.entries, &translit);
+                    let session = project::session_path(Path::new(game_path), mod_name);
+                    project
+                        .save_session(&session)
+                        .map_err(|e| format!("ERROR: could not save session: {e}"))?;
+                    eprintln!("Transliterated {changed} translations");
+                }
+                Ok(())
+            }
+            L10nCommands::Revert {
+                game_path,
+                mod_name,
+            } => {
+                let backup = project::backup_dir(Path::new(game_path), mod_name);
+                if !backup.exists() {
+                    return Err(format!("ERROR: no backup found at {}", backup.display()).into());
+                }
+                let restored = project::revert(Path::new(game_path), &backup)
+                    .map_err(|e| format!("ERROR: could not revert: {e}"))?;
+                eprintln!("Restored {restored} files from {}", backup.display());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Translation exchange format, chosen by file extension.
+enum Format {
+    Csv,
+    Po,
+    Xliff,
+}
+
+impl Format {
+    fn of(path: &str) -> Self {
+        let ext = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "po" => Format::Po,
+            "xlf" | "xliff" => Format::Xliff,
+            _ => Format::Csv,
+        }
+    }
+}
+
+/// Scan the game and merge the mod session, if a mod name is given.
+fn open_project(
+    game_path: &str,
+    mod_name: Option<&str>,
+) -> Result<LocalizationProject, Box<dyn Error>> {
+    eprintln!("Scanning {game_path}...");
+    let mut project = LocalizationProject::scan(game_path)
+        .map_err(|e| format!("ERROR: could not scan game files: {e}"))?;
+    if let Some(mod_name) = mod_name {
+        let session = project::session_path(Path::new(game_path), mod_name);
+        let merged = project
+            .merge_session_file(&session)
+            .map_err(|e| format!("ERROR: could not load session {}: {e}", session.display()))?;
+        if merged > 0 {
+            eprintln!("Merged {merged} translations from {}", session.display());
+        }
+    }
+    Ok(project)
+}
+
+fn open_with_input(
+    game_path: &str,
+    mod_name: &str,
+    input: Option<&str>,
+) -> Result<LocalizationProject, Box<dyn Error>> {
+    let mut project = open_project(game_path, Some(mod_name))?;
+    if let Some(input) = input {
+        let imported = import_file(&mut project, input)?;
+        eprintln!("Imported {imported} strings from {input}");
+    }
+    Ok(project)
+}
+
+/// The built-in transliteration table (when `builtin`) with the CSV table at
+/// `path` layered on top.
+fn load_table(builtin: bool, path: Option<&str>) -> Result<TransliterationTable, Box<dyn Error>> {
+    let mut table = if builtin {
+        TransliterationTable::builtin()
+    } else {
+        TransliterationTable::new()
+    };
+    if let Some(path) = path {
+        let csv = std::fs::read_to_string(path)
+            .map_err(|e| format!("ERROR: could not read {path}: {e}"))?;
+        table.merge(
+            &TransliterationTable::from_csv(&csv).map_err(|e| format!("ERROR: {path}: {e}"))?,
+        );
+    }
+    Ok(table)
+}
+
+/// Warn about translations apply/package will truncate, mangle or skip.
+/// Call once the project options are set.
+fn warn_before_write(project: &LocalizationProject) {
+    if project.overlong_count() > 0 {
+        eprintln!(
+            "WARNING: {} translations exceed their field size and will be truncated",
+            project.overlong_count()
+        );
+    }
+    let lossy = project.lossy_entries().count();
+    if lossy > 0 {
+        eprintln!(
+            "WARNING: {lossy} translations contain characters their encoding cannot represent; see `l10n encoding`"
+        );
+    }
+    let unreviewed = project.unreviewed_count();
+    if unreviewed > 0 && !project.include_unreviewed {
+        eprintln!(
+            "WARNING: skipping {unreviewed} fuzzy translations that still need review; pass --include-unreviewed to write them"
+        );
+    }
+}
+
+fn import_file(project: &mut LocalizationProject, input: &str) -> Result<usize, Box<dyn Error>> {
+    let content = std::fs::read_to_string(input)
+        .map_err(|e| format!("ERROR: could not read {input}: {e}"))?;
+    match Format::of(input) {
+        Format::Po => Ok(import_po(&content, &mut project.entries)),
+        Format::Xliff => Ok(import_xliff(&content, &mut project.entries)
+            .map_err(|e| format!("ERROR: could not parse XLIFF {input}: {e}"))?),
+        Format::Csv => Ok(import_csv(&content, &mut project.entries)
+            .map_err(|e| format!("ERROR: could not parse CSV {input}: {e}"))?),
+    }
+}
+
+#[derive(serde::Serialize, Default)]
+struct FileSummary {
+    strings: usize,
+    translated: usize,
+    overlong: usize,
+    lossy: usize,
+}
+
+fn print_summary(project: &LocalizationProject, json: bool) -> Result<(), Box<dyn Error>> {
+    let mut files: BTreeMap<&str, FileSummary> = BTreeMap::new();
+    for e in project.entries.iter().filter(|e| !e.original.is_empty()) {
+        let s = files.entry(&e.file_path).or_default();
+        s.strings += 1;
+        if e.is_translated() {
+            s.translated += 1;
+            if e.would_truncate() {
+                s.overlong += 1;
+            }
+            if e.is_lossy() {
+                s.lossy += 1;
+            }
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&files)?);
+        return Ok(());
+    }
+    println!(
+        "{:<40} {:>8} {:>10} {:>8} {:>6}",
+        "FILE", "STRINGS", "TRANSLATED", "OVERLONG", "LOSSY"
+    );
+    for (path, s) in &files {
+        println!(
+            "{:<40} {:>8} {:>10} {:>8} {:>6}",
+            path, s.strings, s.translated, s.overlong, s.lossy
+        );
+    }
+    let total: usize = files.values().map(|s| s.strings).sum();
+    let translated: usize = files.values().map(|s| s.translated).sum();
+    println!(
+        "{} files, {total} strings, {translated} translated",
+        files.len()
+    );
+    Ok(())
+}
@@ -53,6 +53,9 @@ impl Command for MapCommand {
                 events,
                 draw_items,
                 npc_waypoints,
+                reachability,
+                svg,
+                html,
             } => {
                 eprintln!("Rendering map...");
                 map::extract(
@@ -76,9 +79,58 @@ impl Command for MapCommand {
                         show_events: *events,
                         show_draw_items: *draw_items,
                         show_npc_waypoints: *npc_waypoints,
+                        show_reachability: *reachability,
                     },
                 )
                 .map_err(|e| format!("ERROR: could not render map: {e}"))?;
+
+                if svg.is_some() || html.is_some() {
+                    eprintln!("Exporting interactive overlay...");
+                    map::overlay::export_overlay(map::overlay::OverlayExportConfig {
+                        map_path: Path::new(map),
+                        png_path: Path::new(output),
+                        game_path: game_path.as_deref().map(Path::new),
+                        occlusion: !full_map,
+                        svg_output: svg.as_deref().map(Path::new),
+                        html_output: html.as_deref().map(Path::new),
+                    })
+                    .map_err(|e| format!("ERROR: could not export overlay: {e}"))?;
+                }
+                Ok(())
+            }
+            MapCommands::RenderAll {
+                game_path,
+                output,
+                jobs,
+                full_map,
+                collisions,
+                events,
+                draw_items,
+                npc_waypoints,
+                reachability,
+            } => {
+                eprintln!("Rendering all maps from {game_path:?} into {output:?}...");
+                let index = map::atlas::render_all(map::atlas::RenderAllConfig {
+                    game_path: Path::new(game_path),
+                    output_dir: Path::new(output),
+                    toggles: map::render::LayerToggles {
+                        full_map: *full_map,
+                        show_collisions: *collisions,
+                        show_events: *events,
+                        show_draw_items: *draw_items,
+                        show_npc_waypoints: *npc_waypoints,
+                        show_reachability: *reachability,
+                        ..Default::default()
+                    },
+                    jobs: *jobs,
+                })
+                .map_err(|e| format!("ERROR: could not render maps: {e}"))?;
+                let failed = index.maps.iter().filter(|m| m.error.is_some()).count();
+                eprintln!(
+                    "Rendered {} of {} maps; index written to {output}/index.html",
+                    index.maps.len() - failed,
+                    index.maps.len()
+                );
                 Ok(())
             }
             MapCommands::FromDb {
@@ -144,6 +196,16 @@ impl Command for MapCommand {
                 eprintln!("TMX export complete: {output}");
                 Ok(())
             }
+            MapCommands::Validate {
+                game_path,
+                map,
+                json,
+            } => validate(Path::new(game_path), map.as_deref(), *json),
+            MapCommands::Reachability {
+                game_path,
+                map,
+                json,
+            } => reachability(Path::new(game_path), map.as_deref(), *json),
             MapCommands::ToJson {
                 input,
                 output,
@@ -174,3 +236,124 @@ impl Command for MapCommand {
         }
     }
 }
+
+/// Maps listed in AllMap.ini, or only the one named `only`.
+fn select_maps(
+    game_path: &Path,
+    only: Option<&str>,
+) -> Result<Vec<map::atlas::AtlasMap>, Box<dyn Error>> {
+    let maps = map::atlas::discover_maps(game_path)
+        .map_err(|e| format!("ERROR: could not read AllMap.ini: {e}"))?;
+    let maps: Vec<_> = maps
+        .into_iter()
+        .filter(|m| only.is_none_or(|name| m.map.map_filename.eq_ignore_ascii_case(name)))
+        .collect();
+    if let Some(name) = only
+        && maps.is_empty()
+    {
+        return Err(format!("ERROR: map '{name}' is not listed in AllMap.ini").into());
+    }
+    Ok(maps)
+}
+
+fn validate(game_path: &Path, only: Option<&str>, json: bool) -> Result<(), Box<dyn Error>> {
+    use map::placement::{Severity, validate_map};
+
+    let maps = select_maps(game_path, only)?;
+    let mut results = Vec::new();
+    for m in &maps {
+        match validate_map(&m.map_path, game_path) {
+            Ok(issues) => results.push((m, issues)),
+            Err(e) => eprintln!("Warning: skipped {}: {e}", m.map.map_filename),
+        }
+    }
+    let errors = results
+        .iter()
+        .flat_map(|(_, issues)| issues)
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+
+    if json {
+        let output: Vec<_> = results
+            .iter()
+            .map(|(m, issues)| {
+                serde_json::json!({
+                    "map": m.map.map_filename,
+                    "name": m.map.map_name,
+                    "issues": issues,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        for (m, issues) in &results {
+            if issues.is_empty() {
+                continue;
+            }
+            println!("{} ({}):", m.map.map_filename, m.map.map_name);
+            for issue in issues {
+                println!("  {issue}");
+            }
+        }
+        let total: usize = results.iter().map(|(_, issues)| issues.len()).sum();
+        println!(
+            "{} map(s) checked, {total} issue(s), {errors} error(s)",
+            results.len()
+        );
+    }
+
+    if errors > 0 {
+        return Err(format!("ERROR: {errors} placement error(s) found").into());
+    }
+    Ok(())
+}
+
+fn reachability(game_path: &Path, only: Option<&str>, json: bool) -> Result<(), Box<dyn Error>> {
+    let maps = select_maps(game_path, only)?;
+    let mut results = Vec::new();
+    for m in &maps {
+        match map::pathfinding::analyze_map(&m.map_path, game_path) {
+            Ok(report) => results.push((m, report)),
+            Err(e) => eprintln!("Warning: skipped {}: {e}", m.map.map_filename),
+        }
+    }
+
+    if json {
+        let output: Vec<_> = results
+            .iter()
+            .map(|(m, report)| {
+                serde_json::json!({
+                    "map": m.map.map_filename,
+                    "name": m.map.map_name,
+                    "reachability": report,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    for (m, report) in &results {
+        println!(
+            "{} ({}): start ({}, {}), {} of {} walkable tile(s) reachable",
+            m.map.map_filename,
+            m.map.map_name,
+            report.start_x,
+            report.start_y,
+            report.reachable_tiles,
+            report.walkable_tiles
+        );
+        if !report.start_walkable {
+            println!("  start position is blocked or outside the map");
+        }
+        for target in &report.unreachable {
+            println!("  unreachable {target}");
+        }
+    }
+    let total: usize = results.iter().map(|(_, r)| r.unreachable.len()).sum();
+    println!(
+        "{} map(s) analysed, {total} unreachable target(s)",
+        results.len()
+    );
+    Ok(())
+}
@@ -2,10 +2,15 @@
 
 pub mod database;
 pub mod dialog;
+pub mod l10n;
 pub mod list;
 pub mod map;
 pub mod pack;
+pub mod refs;
 pub mod registry;
+pub mod renumber;
+pub mod report;
+pub mod save;
 pub mod schema;
 pub mod sound;
 pub mod sprite;
@@ -0,0 +1,78 @@
+use std::error::Error;
+use std::path::Path;
+
+use dispel_core::references::usages::{RecordKind, ReferenceIndex};
+
+use crate::commands::Command;
+
+#[derive(clap::Args, Clone)]
+pub struct RefsArgs {
+    /// Path to the Dispel game directory
+    #[arg(short, long)]
+    pub game_path: String,
+
+    /// Referenced record type (e.g. heal_items, weapons, monsters, magic, event_ini)
+    #[arg(short = 't', long = "type")]
+    pub record_type: String,
+
+    /// Referenced record id
+    #[arg(short, long, allow_negative_numbers = true)]
+    pub id: i32,
+
+    /// Print the usages as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub struct RefsCommand {
+    pub args: RefsArgs,
+}
+
+impl Command for RefsCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let kind = RecordKind::from_key(&self.args.record_type).ok_or_else(|| {
+            let keys: Vec<_> = RecordKind::ALL.iter().map(|kind| kind.key()).collect();
+            format!(
+                "ERROR: unknown record type '{}'. Expected one of: {}",
+                self.args.record_type,
+                keys.join(", ")
+            )
+        })?;
+        let game_dir = Path::new(&self.args.game_path);
+        if !game_dir.is_dir() {
+            return Err(format!("ERROR: game directory not found: {}", game_dir.display()).into());
+        }
+
+        let index = ReferenceIndex::build(game_dir);
+        for (file, error) in &index.errors {
+            eprintln!("Warning: skipped {file}: {error}");
+        }
+        let usages = index.usages(kind, self.args.id);
+
+        if self.args.json {
+            let output = serde_json::json!({
+                "type": kind.key(),
+                "id": self.args.id,
+                "usages": usages,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(());
+        }
+
+        if usages.is_empty() {
+            println!(
+                "No usages of {} #{} in {} indexed file(s).",
+                kind,
+                self.args.id,
+                index.files_indexed.len()
+            );
+            return Ok(());
+        }
+        println!("{} #{} is used in:", kind, self.args.id);
+        for usage in usages {
+            println!("  {usage}");
+        }
+        println!("{} usage(s)", usages.len());
+        Ok(())
+    }
+}
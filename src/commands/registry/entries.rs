@@ -1,6 +1,6 @@
 use super::types::{
     DetectKind, FileType, extract_as, extract_map_file, extract_sprite_info, extract_tileset,
-    patch_as, patch_not_supported, validate_as,
+    patch_as, patch_not_supported, validate_as, validate_localized_as, validate_save_file,
 };
 
 pub(crate) fn make_all_map_ini() -> FileType {
@@ -12,7 +12,7 @@ pub(crate) fn make_all_map_ini() -> FileType {
         detect_kind: DetectKind::Ini("AllMap.ini"),
         extract_fn: extract_as::<dispel_core::references::all_map_ini::Map>,
         patch_fn: patch_as::<dispel_core::references::all_map_ini::Map>,
-        validate_fn: Some(validate_as::<dispel_core::references::all_map_ini::Map>),
+        validate_fn: Some(validate_localized_as::<dispel_core::references::all_map_ini::Map>),
     }
 }
 
@@ -64,7 +64,9 @@ pub(crate) fn make_monster_ini() -> FileType {
         detect_kind: DetectKind::Ini("Monster.ini"),
         extract_fn: extract_as::<dispel_core::references::monster_ini::MonsterIni>,
         patch_fn: patch_as::<dispel_core::references::monster_ini::MonsterIni>,
-        validate_fn: Some(validate_as::<dispel_core::references::monster_ini::MonsterIni>),
+        validate_fn: Some(
+            validate_localized_as::<dispel_core::references::monster_ini::MonsterIni>,
+        ),
     }
 }
 
@@ -103,7 +105,7 @@ pub(crate) fn make_weapons() -> FileType {
         detect_kind: DetectKind::Db(&["WeaponItem.db", "weaponItem.db"]),
         extract_fn: extract_as::<dispel_core::references::weapons_db::WeaponItem>,
         patch_fn: patch_as::<dispel_core::references::weapons_db::WeaponItem>,
-        validate_fn: Some(validate_as::<dispel_core::references::weapons_db::WeaponItem>),
+        validate_fn: Some(validate_localized_as::<dispel_core::references::weapons_db::WeaponItem>),
     }
 }
 
@@ -129,7 +131,7 @@ pub(crate) fn make_magic() -> FileType {
         detect_kind: DetectKind::Db(&["Magic.db", "magic.db", "MulMagic.db"]),
         extract_fn: extract_as::<dispel_core::references::magic_db::MagicSpell>,
         patch_fn: patch_as::<dispel_core::references::magic_db::MagicSpell>,
-        validate_fn: None,
+        validate_fn: Some(validate_as::<dispel_core::references::magic_db::MagicSpell>),
     }
 }
 
@@ -142,7 +144,7 @@ pub(crate) fn make_store() -> FileType {
         detect_kind: DetectKind::Db(&["Store.db", "STORE.DB", "store.db"]),
         extract_fn: extract_as::<dispel_core::references::store_db::Store>,
         patch_fn: patch_as::<dispel_core::references::store_db::Store>,
-        validate_fn: None,
+        validate_fn: Some(validate_localized_as::<dispel_core::references::store_db::Store>),
     }
 }
 
@@ -155,7 +157,7 @@ pub(crate) fn make_misc_item() -> FileType {
         detect_kind: DetectKind::Db(&["MiscItem.db", "miscitem.db"]),
         extract_fn: extract_as::<dispel_core::references::misc_item_db::MiscItem>,
         patch_fn: patch_as::<dispel_core::references::misc_item_db::MiscItem>,
-        validate_fn: Some(validate_as::<dispel_core::references::misc_item_db::MiscItem>),
+        validate_fn: Some(validate_localized_as::<dispel_core::references::misc_item_db::MiscItem>),
     }
 }
 
@@ -168,7 +170,7 @@ pub(crate) fn make_heal_item() -> FileType {
         detect_kind: DetectKind::Db(&["HealItem.db", "healitem.db"]),
         extract_fn: extract_as::<dispel_core::references::heal_item_db::HealItem>,
         patch_fn: patch_as::<dispel_core::references::heal_item_db::HealItem>,
-        validate_fn: Some(validate_as::<dispel_core::references::heal_item_db::HealItem>),
+        validate_fn: Some(validate_localized_as::<dispel_core::references::heal_item_db::HealItem>),
     }
 }
 
@@ -181,7 +183,9 @@ pub(crate) fn make_event_item() -> FileType {
         detect_kind: DetectKind::Db(&["EventItem.db", "eventitem.db"]),
         extract_fn: extract_as::<dispel_core::references::event_item_db::EventItem>,
         patch_fn: patch_as::<dispel_core::references::event_item_db::EventItem>,
-        validate_fn: Some(validate_as::<dispel_core::references::event_item_db::EventItem>),
+        validate_fn: Some(
+            validate_localized_as::<dispel_core::references::event_item_db::EventItem>,
+        ),
     }
 }
 
@@ -194,7 +198,7 @@ pub(crate) fn make_edit_item() -> FileType {
         detect_kind: DetectKind::Db(&["EditItem.db", "edititem.db"]),
         extract_fn: extract_as::<dispel_core::references::edit_item_db::EditItem>,
         patch_fn: patch_as::<dispel_core::references::edit_item_db::EditItem>,
-        validate_fn: Some(validate_as::<dispel_core::references::edit_item_db::EditItem>),
+        validate_fn: Some(validate_localized_as::<dispel_core::references::edit_item_db::EditItem>),
     }
 }
 
@@ -207,7 +211,7 @@ pub(crate) fn make_party_level() -> FileType {
         detect_kind: DetectKind::Db(&["PrtLevel.db", "prtlevel.db"]),
         extract_fn: extract_as::<dispel_core::references::party_level_db::PartyLevelNpc>,
         patch_fn: patch_as::<dispel_core::references::party_level_db::PartyLevelNpc>,
-        validate_fn: None,
+        validate_fn: Some(validate_as::<dispel_core::references::party_level_db::PartyLevelNpc>),
     }
 }
 
@@ -220,7 +224,9 @@ pub(crate) fn make_party_ini() -> FileType {
         detect_kind: DetectKind::Db(&["PrtIni.db", "prtini.db"]),
         extract_fn: extract_as::<dispel_core::references::party_ini_db::PartyIniNpc>,
         patch_fn: patch_as::<dispel_core::references::party_ini_db::PartyIniNpc>,
-        validate_fn: None,
+        validate_fn: Some(
+            validate_localized_as::<dispel_core::references::party_ini_db::PartyIniNpc>,
+        ),
     }
 }
 
@@ -233,7 +239,7 @@ pub(crate) fn make_chdata() -> FileType {
         detect_kind: DetectKind::Db(&["ChData.db", "chdata.db"]),
         extract_fn: extract_as::<dispel_core::references::chdata_db::ChData>,
         patch_fn: patch_as::<dispel_core::references::chdata_db::ChData>,
-        validate_fn: None,
+        validate_fn: Some(validate_as::<dispel_core::references::chdata_db::ChData>),
     }
 }
 
@@ -246,7 +252,7 @@ pub(crate) fn make_party_ref() -> FileType {
         detect_kind: DetectKind::RefPrefix("PartyRef"),
         extract_fn: extract_as::<dispel_core::references::party_ref::PartyRef>,
         patch_fn: patch_as::<dispel_core::references::party_ref::PartyRef>,
-        validate_fn: Some(validate_as::<dispel_core::references::party_ref::PartyRef>),
+        validate_fn: Some(validate_localized_as::<dispel_core::references::party_ref::PartyRef>),
     }
 }
 
@@ -272,7 +278,7 @@ pub(crate) fn make_npc_ref() -> FileType {
         detect_kind: DetectKind::RefPrefix("Npc"),
         extract_fn: extract_as::<dispel_core::references::npc_ref::NPC>,
         patch_fn: patch_as::<dispel_core::references::npc_ref::NPC>,
-        validate_fn: None,
+        validate_fn: Some(validate_localized_as::<dispel_core::references::npc_ref::NPC>),
     }
 }
 
@@ -285,7 +291,7 @@ pub(crate) fn make_monster_ref() -> FileType {
         detect_kind: DetectKind::RefPrefix("Mon"),
         extract_fn: extract_as::<dispel_core::references::monster_ref::MonsterRef>,
         patch_fn: patch_as::<dispel_core::references::monster_ref::MonsterRef>,
-        validate_fn: None,
+        validate_fn: Some(validate_as::<dispel_core::references::monster_ref::MonsterRef>),
     }
 }
 
@@ -298,7 +304,7 @@ pub(crate) fn make_extra_ref() -> FileType {
         detect_kind: DetectKind::RefPrefix("Ext"),
         extract_fn: extract_as::<dispel_core::references::extra_ref::ExtraRef>,
         patch_fn: patch_as::<dispel_core::references::extra_ref::ExtraRef>,
-        validate_fn: Some(validate_as::<dispel_core::references::extra_ref::ExtraRef>),
+        validate_fn: Some(validate_localized_as::<dispel_core::references::extra_ref::ExtraRef>),
     }
 }
 
@@ -311,7 +317,9 @@ pub(crate) fn make_event_npc_ref() -> FileType {
         detect_kind: DetectKind::RefPrefix("Eventnpc"),
         extract_fn: extract_as::<dispel_core::references::event_npc_ref::EventNpcRef>,
         patch_fn: patch_as::<dispel_core::references::event_npc_ref::EventNpcRef>,
-        validate_fn: None,
+        validate_fn: Some(
+            validate_localized_as::<dispel_core::references::event_npc_ref::EventNpcRef>,
+        ),
     }
 }
 
@@ -338,7 +346,7 @@ pub(crate) fn make_dialog_text() -> FileType {
         extract_fn: extract_as::<dispel_core::references::dialogue_paragraph::DialogueParagraph>,
         patch_fn: patch_as::<dispel_core::references::dialogue_paragraph::DialogueParagraph>,
         validate_fn: Some(
-            validate_as::<dispel_core::references::dialogue_paragraph::DialogueParagraph>,
+            validate_localized_as::<dispel_core::references::dialogue_paragraph::DialogueParagraph>,
         ),
     }
 }
@@ -352,7 +360,7 @@ pub(crate) fn make_quest() -> FileType {
         detect_kind: DetectKind::Scr("Quest.scr"),
         extract_fn: extract_as::<dispel_core::references::quest_scr::Quest>,
         patch_fn: patch_as::<dispel_core::references::quest_scr::Quest>,
-        validate_fn: None,
+        validate_fn: Some(validate_localized_as::<dispel_core::references::quest_scr::Quest>),
     }
 }
 
@@ -365,7 +373,7 @@ pub(crate) fn make_message() -> FileType {
         detect_kind: DetectKind::Scr("Message.scr"),
         extract_fn: extract_as::<dispel_core::references::message_scr::Message>,
         patch_fn: patch_as::<dispel_core::references::message_scr::Message>,
-        validate_fn: None,
+        validate_fn: Some(validate_localized_as::<dispel_core::references::message_scr::Message>),
     }
 }
 
@@ -378,7 +386,7 @@ pub(crate) fn make_event_scr() -> FileType {
         detect_kind: DetectKind::ScrPrefix("Event"),
         extract_fn: extract_as::<dispel_core::references::event_scr::EventScript>,
         patch_fn: patch_as::<dispel_core::references::event_scr::EventScript>,
-        validate_fn: None,
+        validate_fn: Some(validate_as::<dispel_core::references::event_scr::EventScript>),
     }
 }
 
@@ -443,6 +451,6 @@ pub(crate) fn make_save_file() -> FileType {
         detect_kind: DetectKind::Db(&["0.sav", "1.sav", "2.sav", "3.sav", "4.sav", "5.sav"]),
         extract_fn: extract_as::<dispel_core::references::save_file::SaveFile>,
         patch_fn: patch_as::<dispel_core::references::save_file::SaveFile>,
-        validate_fn: None,
+        validate_fn: Some(validate_save_file),
     }
 }
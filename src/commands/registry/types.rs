@@ -1,7 +1,11 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+use dispel_core::Localizable;
 use dispel_core::references::extractor::Extractor;
+use dispel_core::references::save_file::SaveFile;
+use dispel_core::references::validation::{self, FieldConstraints, FieldViolation};
 
 /// Detection strategy for a file type.
 pub(crate) enum DetectKind {
@@ -21,19 +25,24 @@ pub(crate) enum DetectKind {
     PgpPrefix(&'static str),
 }
 
-/// A structured validation error with optional record index for arrays.
+/// A structured validation error with optional record index for arrays and
+/// the offending field's path within the record.
 #[derive(Debug, Clone, Serialize)]
 pub struct ValidationError {
     pub record_index: Option<usize>,
+    pub field_path: Option<String>,
     pub message: String,
 }
 
 impl std::fmt::Display for ValidationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.record_index {
-            Some(i) => write!(f, "Record {}: {}", i, self.message),
-            None => write!(f, "{}", self.message),
+        if let Some(i) = self.record_index {
+            write!(f, "Record {}: ", i)?;
         }
+        if let Some(path) = &self.field_path {
+            write!(f, "{}: ", path)?;
+        }
+        write!(f, "{}", self.message)
     }
 }
 
@@ -134,35 +143,70 @@ where
     Ok(())
 }
 
+/// Validate JSON records of type `T` against their field constraints.
 pub(crate) fn validate_as<T>(data: &serde_json::Value) -> Result<(), Vec<ValidationError>>
 where
-    T: for<'de> Deserialize<'de>,
+    T: FieldConstraints + DeserializeOwned,
 {
-    match data {
-        serde_json::Value::Array(arr) => {
-            let mut errors = vec![];
-            for (i, item) in arr.iter().enumerate() {
-                if let Err(e) = serde_json::from_value::<T>(item.clone()) {
-                    errors.push(ValidationError {
-                        record_index: Some(i),
-                        message: e.to_string(),
-                    });
-                }
-            }
-            if errors.is_empty() {
-                Ok(())
-            } else {
-                Err(errors)
-            }
+    validate_each(data, validation::validate_record::<T>)
+}
+
+/// [`validate_as`] plus the `#[translatable]` text limits of `T`.
+pub(crate) fn validate_localized_as<T>(data: &serde_json::Value) -> Result<(), Vec<ValidationError>>
+where
+    T: FieldConstraints + Localizable + DeserializeOwned,
+{
+    validate_each(data, validation::validate_localizable_record::<T>)
+}
+
+/// A save is a single record; check that it serializes and reloads unchanged.
+pub(crate) fn validate_save_file(data: &serde_json::Value) -> Result<(), Vec<ValidationError>> {
+    let error = |message: String| {
+        vec![ValidationError {
+            record_index: None,
+            field_path: None,
+            message,
+        }]
+    };
+    let record = match data {
+        serde_json::Value::Array(records) if records.len() == 1 => &records[0],
+        serde_json::Value::Array(records) => {
+            return Err(error(format!(
+                "expected one save record, found {}",
+                records.len()
+            )));
         }
-        _ => serde_json::from_value::<T>(data.clone())
-            .map(|_| ())
-            .map_err(|e| {
-                vec![ValidationError {
-                    record_index: None,
-                    message: e.to_string(),
-                }]
-            }),
+        record => record,
+    };
+    let save: SaveFile =
+        serde_json::from_value(record.clone()).map_err(|e| error(e.to_string()))?;
+    save.to_verified_bytes()
+        .map(|_| ())
+        .map_err(|e| error(e.to_string()))
+}
+
+/// Run `check` on each record of an array (or on a single record).
+fn validate_each(
+    data: &serde_json::Value,
+    check: fn(&serde_json::Value) -> Vec<FieldViolation>,
+) -> Result<(), Vec<ValidationError>> {
+    let to_error = |record_index: Option<usize>, violation: FieldViolation| ValidationError {
+        record_index,
+        field_path: (!violation.path.is_empty()).then_some(violation.path),
+        message: violation.message,
+    };
+    let errors: Vec<ValidationError> = match data {
+        serde_json::Value::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .flat_map(|(i, item)| check(item).into_iter().map(move |v| to_error(Some(i), v)))
+            .collect(),
+        _ => check(data).into_iter().map(|v| to_error(None, v)).collect(),
+    };
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
     }
 }
 
@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::path::Path;
+
+use clap::ArgGroup;
+use dispel_core::modding::{ModManifest, ModPackage, write_zip};
+use dispel_core::references::renumber::{RecordEdit, Renumbering};
+use dispel_core::references::usages::{RecordKind, ReferenceIndex};
+
+use crate::commands::Command;
+
+#[derive(clap::Args, Clone)]
+#[command(group(ArgGroup::new("edit").required(true).args(["delete", "insert", "move_record"])))]
+pub struct RenumberArgs {
+    /// Path to the Dispel game directory
+    #[arg(short, long)]
+    pub game_path: String,
+
+    /// Catalog to edit (weapons, heal_items, edit_items, event_items, misc_items, magic, monsters, party_ini)
+    #[arg(short = 't', long = "type")]
+    pub record_type: String,
+
+    /// Delete the record at this index
+    #[arg(long, value_name = "INDEX")]
+    pub delete: Option<usize>,
+
+    /// Point references to the deleted record at this index instead
+    #[arg(long, value_name = "INDEX", requires = "delete")]
+    pub redirect: Option<usize>,
+
+    /// Insert a new record at this index
+    #[arg(long, value_name = "INDEX")]
+    pub insert: Option<usize>,
+
+    /// Make the inserted record a copy of this index (default: all fields zero)
+    #[arg(long, value_name = "INDEX", requires = "insert")]
+    pub copy_of: Option<usize>,
+
+    /// Move the record at FROM so that it ends up at TO
+    #[arg(long = "move", num_args = 2, value_names = ["FROM", "TO"])]
+    pub move_record: Option<Vec<usize>>,
+
+    /// Write the edited files as a mod package (.zip); without it only a
+    /// preview is printed
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Mod name written to the package manifest
+    #[arg(long, default_value = "renumber")]
+    pub name: String,
+
+    /// Print the preview as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl RenumberArgs {
+    fn edit(&self) -> RecordEdit {
+        match (&self.delete, &self.insert, &self.move_record) {
+            (Some(index), _, _) => RecordEdit::Delete {
+                index: *index,
+                redirect_to: self.redirect,
+            },
+            (_, Some(index), _) => RecordEdit::Insert {
+                index: *index,
+                copy_of: self.copy_of,
+            },
+            (_, _, Some(positions)) => RecordEdit::Move {
+                from: positions[0],
+                to: positions[1],
+            },
+            (None, None, None) => unreachable!("clap requires one edit"),
+        }
+    }
+}
+
+pub struct RenumberCommand {
+    pub args: RenumberArgs,
+}
+
+impl Command for RenumberCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        let kind = RecordKind::from_key(&self.args.record_type)
+            .ok_or_else(|| format!("ERROR: unknown record type '{}'", self.args.record_type))?;
+        let game_dir = Path::new(&self.args.game_path);
+        if !game_dir.is_dir() {
+            return Err(format!("ERROR: game directory not found: {}", game_dir.display()).into());
+        }
+
+        let index = ReferenceIndex::build(game_dir);
+        for (file, error) in &index.errors {
+            eprintln!("Warning: skipped {file}: {error}");
+        }
+        let renumbering = Renumbering::plan(game_dir, &index, kind, self.args.edit())
+            .map_err(|e| format!("ERROR: {e}"))?;
+
+        if self.args.json {
+            let files: Vec<_> = renumbering
+                .files
+                .iter()
+                .map(|file| serde_json::json!({ "file": file.file, "fixups": file.fixups }))
+                .collect();
+            let output = serde_json::json!({
+                "type": kind.key(),
+                "edit": renumbering.edit,
+                "files": files,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            println!("{}", renumbering.description());
+            for file in &renumbering.files {
+                println!("  {} ({} reference(s))", file.file, file.fixups.len());
+                for fixup in &file.fixups {
+                    let label = fixup
+                        .label
+                        .as_ref()
+                        .map(|label| format!(" ({label})"))
+                        .unwrap_or_default();
+                    println!(
+                        "    #{} {}{label}: {} -> {}",
+                        fixup.record, fixup.field, fixup.old, fixup.new
+                    );
+                }
+            }
+            println!(
+                "{} file(s), {} reference(s) rewritten",
+                renumbering.files.len(),
+                renumbering.fixup_count()
+            );
+        }
+
+        let Some(output) = &self.args.output else {
+            return Ok(());
+        };
+        let changes = renumbering
+            .to_changelog()
+            .map_err(|e| format!("ERROR: {e}"))?;
+        let mut manifest = ModManifest::new(self.args.name.trim());
+        manifest.description = renumbering.description();
+        let package = ModPackage::new(manifest, changes);
+        let file = std::fs::File::create(output)
+            .map_err(|e| format!("ERROR: could not create {output}: {e}"))?;
+        write_zip(file, &package)
+            .map_err(|e| format!("ERROR: could not write mod package: {e}"))?;
+        eprintln!(
+            "Packaged {} edited files into {output}",
+            package.changes.len()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+use std::error::Error;
+use std::path::Path;
+
+use dispel_core::references::balance::{BalanceInputs, BalanceReport};
+use dispel_core::references::difficulty::DifficultyReport;
+use dispel_core::references::loot::LootReport;
+use dispel_core::references::quest_flow::{QuestFlowInputs, QuestGraph};
+use dispel_core::references::usages::{RecordId, RecordKind};
+use serde::Serialize;
+
+use super::Command;
+use crate::cli::{GraphFormat, ReportCommands, ReportFormat};
+
+pub struct ReportCommand {
+    pub command: ReportCommands,
+}
+
+impl Command for ReportCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        match &self.command {
+            ReportCommands::Balance {
+                game_path,
+                format,
+                output,
+            } => {
+                let inputs = load_inputs(Path::new(game_path))?;
+                let report = BalanceReport::build(&inputs);
+                write_report(
+                    *format,
+                    output.as_deref().map(Path::new),
+                    &report,
+                    || report.to_html(),
+                    || report.to_csv_tables(),
+                )?;
+                eprintln!(
+                    "{} map(s), {} level(s), {} shop item(s), {} price outlier(s)",
+                    report.maps.len(),
+                    report.levels.len(),
+                    report.shop_items.len(),
+                    report.price_outliers.len()
+                );
+                Ok(())
+            }
+            ReportCommands::Difficulty {
+                game_path,
+                format,
+                output,
+                fail_on_spikes,
+            } => {
+                let inputs = load_inputs(Path::new(game_path))?;
+                let report = DifficultyReport::build(&inputs);
+                write_report(
+                    *format,
+                    output.as_deref().map(Path::new),
+                    &report,
+                    || report.to_html(),
+                    || report.to_csv_tables(),
+                )?;
+                for spike in &report.spikes {
+                    eprintln!("  {spike}");
+                }
+                eprintln!(
+                    "{} map(s) scored, {} spike(s)",
+                    report.maps.len(),
+                    report.spikes.len()
+                );
+                if *fail_on_spikes && !report.spikes.is_empty() {
+                    return Err(format!(
+                        "ERROR: {} difficulty spike(s) found",
+                        report.spikes.len()
+                    )
+                    .into());
+                }
+                Ok(())
+            }
+            ReportCommands::Loot {
+                game_path,
+                format,
+                output,
+                record_type,
+                id,
+                map,
+            } => {
+                let item = match (record_type, id) {
+                    (Some(key), Some(id)) => Some(RecordId {
+                        kind: item_kind(key)?,
+                        id: *id,
+                    }),
+                    _ => None,
+                };
+                let inputs = load_inputs(Path::new(game_path))?;
+                let report = LootReport::build(&inputs).filtered(item, map.as_deref());
+                if let Some(map) = map
+                    && report.maps.is_empty()
+                {
+                    return Err(format!("ERROR: map not found in AllMap.ini: {map}").into());
+                }
+                write_report(
+                    *format,
+                    output.as_deref().map(Path::new),
+                    &report,
+                    || report.to_html(),
+                    || report.to_csv_tables(),
+                )?;
+                eprintln!(
+                    "{} loot source(s) on {} map(s)",
+                    report.sources.len(),
+                    report.maps.len()
+                );
+                Ok(())
+            }
+            ReportCommands::Quests {
+                game_path,
+                format,
+                output,
+                quest,
+                fail_on_untriggered,
+            } => {
+                let game_dir = Path::new(game_path);
+                if !game_dir.is_dir() {
+                    return Err(
+                        format!("ERROR: game directory not found: {}", game_dir.display()).into(),
+                    );
+                }
+                let inputs = QuestFlowInputs::load(game_dir);
+                for warning in &inputs.warnings {
+                    eprintln!("Warning: skipped {warning}");
+                }
+                let mut graph = QuestGraph::build(&inputs);
+                if let Some(id) = quest {
+                    if !graph.quests.iter().any(|q| q.id == *id) {
+                        return Err(format!("ERROR: quest not found in Quest.scr: {id}").into());
+                    }
+                    graph = graph.upstream_of(*id);
+                }
+                let text = match format {
+                    GraphFormat::Dot => graph.to_dot(),
+                    GraphFormat::Json => serde_json::to_string_pretty(&graph)?,
+                };
+                match output {
+                    Some(path) => {
+                        std::fs::write(path, text)
+                            .map_err(|e| format!("ERROR: could not write {path}: {e}"))?;
+                        eprintln!("Graph written to {path}");
+                    }
+                    None => println!("{text}"),
+                }
+                for event in &graph.untriggered {
+                    eprintln!(
+                        "  event {} is required by {} but never triggered{}",
+                        event.event_id,
+                        event.required_by.join(", "),
+                        if event.defined {
+                            ""
+                        } else {
+                            " (not in Event.ini)"
+                        }
+                    );
+                }
+                let started = graph
+                    .quests
+                    .iter()
+                    .filter(|q| !q.started_by.is_empty())
+                    .count();
+                let finished = graph
+                    .quests
+                    .iter()
+                    .filter(|q| !q.finished_by.is_empty())
+                    .count();
+                eprintln!(
+                    "{} node(s), {} edge(s); {} of {} quest(s) started, {finished} finished; {} untriggered event(s)",
+                    graph.nodes.len(),
+                    graph.edges.len(),
+                    started,
+                    graph.quests.len(),
+                    graph.untriggered.len()
+                );
+                if *fail_on_untriggered && !graph.untriggered.is_empty() {
+                    return Err(format!(
+                        "ERROR: {} required event(s) are never triggered",
+                        graph.untriggered.len()
+                    )
+                    .into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Item database named by a `refs`-style type key.
+fn item_kind(key: &str) -> Result<RecordKind, Box<dyn Error>> {
+    RecordKind::from_key(key)
+        .filter(|kind| RecordKind::ITEMS.contains(kind))
+        .ok_or_else(|| {
+            let keys: Vec<_> = RecordKind::ITEMS.iter().map(|kind| kind.key()).collect();
+            format!(
+                "ERROR: unknown item type '{key}'. Expected one of: {}",
+                keys.join(", ")
+            )
+            .into()
+        })
+}
+
+fn load_inputs(game_dir: &Path) -> Result<BalanceInputs, Box<dyn Error>> {
+    if !game_dir.is_dir() {
+        return Err(format!("ERROR: game directory not found: {}", game_dir.display()).into());
+    }
+    let inputs = BalanceInputs::load(game_dir);
+    for warning in &inputs.warnings {
+        eprintln!("Warning: skipped {warning}");
+    }
+    Ok(inputs)
+}
+
+/// Write `report` as JSON or HTML to `output` (stdout when `None`), or as
+/// one CSV file per table into the `output` directory.
+fn write_report<T: Serialize>(
+    format: ReportFormat,
+    output: Option<&Path>,
+    report: &T,
+    html: impl FnOnce() -> String,
+    csv: impl FnOnce() -> Result<Vec<(&'static str, String)>, csv::Error>,
+) -> Result<(), Box<dyn Error>> {
+    let text = match format {
+        ReportFormat::Json => serde_json::to_string_pretty(report)?,
+        ReportFormat::Html => html(),
+        ReportFormat::Csv => {
+            let Some(dir) = output else {
+                return Err(
+                    "ERROR: --format csv writes one file per table; pass an --output directory"
+                        .into(),
+                );
+            };
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("ERROR: could not create {}: {e}", dir.display()))?;
+            for (name, table) in csv()? {
+                let path = dir.join(name);
+                std::fs::write(&path, table)
+                    .map_err(|e| format!("ERROR: could not write {}: {e}", path.display()))?;
+            }
+            eprintln!("Report tables written to {}", dir.display());
+            return Ok(());
+        }
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, text)
+                .map_err(|e| format!("ERROR: could not write {}: {e}", path.display()))?;
+            eprintln!("Report written to {}", path.display());
+        }
+        None => println!("{text}"),
+    }
+    Ok(())
+}
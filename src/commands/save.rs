@@ -0,0 +1,91 @@
+use super::Command;
+use crate::cli::SaveCommands;
+use dispel_core::references::save_file::{FieldChange, SaveDiff, SaveFile};
+use std::error::Error;
+
+pub struct SaveCommand {
+    pub command: SaveCommands,
+}
+
+impl Command for SaveCommand {
+    fn execute(&self) -> Result<(), Box<dyn Error>> {
+        match &self.command {
+            SaveCommands::Diff {
+                a,
+                b,
+                section,
+                json,
+            } => {
+                let save_a = load_save(a)?;
+                let save_b = load_save(b)?;
+                let mut diff = save_a
+                    .diff(&save_b)
+                    .map_err(|e| format!("ERROR: could not compare saves: {e}"))?;
+                if let Some(filter) = section {
+                    diff.sections
+                        .retain(|section| section.section.contains(filter.as_str()));
+                }
+
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&diff)?);
+                } else {
+                    print_diff(&diff);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn load_save(path: &str) -> Result<SaveFile, Box<dyn Error>> {
+    let data = std::fs::read(path).map_err(|e| format!("ERROR: could not read {path}: {e}"))?;
+    Ok(SaveFile::parse(&data).map_err(|e| format!("ERROR: could not parse {path}: {e}"))?)
+}
+
+fn print_diff(diff: &SaveDiff) {
+    if diff.is_empty() {
+        println!("Saves are identical.");
+        return;
+    }
+
+    for section in &diff.sections {
+        println!(
+            "== {} (a: {:#x}..{:#x}, b: {:#x}..{:#x}) ==",
+            section.section,
+            section.range_a.0,
+            section.range_a.1,
+            section.range_b.0,
+            section.range_b.1
+        );
+        for change in &section.changes {
+            println!("  {}", describe_change(change));
+        }
+        for range in &section.byte_ranges {
+            let length = if range.len_a == range.len_b {
+                format!("{} bytes", range.len_a)
+            } else {
+                format!("{} -> {} bytes", range.len_a, range.len_b)
+            };
+            println!(
+                "  bytes +{:#x} ({length}; a @ {:#x}, b @ {:#x})",
+                range.offset,
+                section.range_a.0 + range.offset,
+                section.range_b.0 + range.offset
+            );
+        }
+    }
+    println!(
+        "{} changed field(s) in {} section(s)",
+        diff.change_count(),
+        diff.sections.len()
+    );
+}
+
+fn describe_change(change: &FieldChange) -> String {
+    match (&change.before, &change.after) {
+        (Some(before), Some(after)) => format!("{}: {before} -> {after}", change.path),
+        (None, Some(_)) => format!("{}: added", change.path),
+        (Some(_), None) => format!("{}: removed", change.path),
+        (None, None) => change.path.clone(),
+    }
+}
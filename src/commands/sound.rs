@@ -1,6 +1,8 @@
 use super::Command;
 use crate::cli::SoundCommands;
-use dispel_core::snf;
+use dispel_core::snf::{self, PcmFormat};
+use dispel_core::sound_library::{self, ImportOptions, SoundLibrary};
+use dispel_core::sound_render::{self, RenderOptions};
 use std::error::Error;
 use std::path::Path;
 
@@ -23,6 +25,192 @@ impl Command for SoundCommand {
                     .map_err(|e| format!("ERROR: could not convert WAV to SNF: {e}"))?;
                 Ok(())
             }
+            SoundCommands::FromAudio {
+                input,
+                output,
+                sample_rate,
+                channels,
+                bits,
+            } => {
+                let output_path = Path::new(output);
+                let overrides = sample_rate.is_some() || channels.is_some() || bits.is_some();
+                let target = if overrides {
+                    let base = match snf::read(output_path) {
+                        Ok(replaced) => replaced.format(),
+                        Err(_) => snf::read_audio(Path::new(input))
+                            .map_err(|e| format!("ERROR: could not decode {input}: {e}"))?
+                            .format(),
+                    };
+                    Some(PcmFormat {
+                        pcmaudio_format: 1,
+                        number_of_channels: channels.unwrap_or(base.number_of_channels),
+                        sample_rate: sample_rate.unwrap_or(base.sample_rate),
+                        bits_per_sample: bits.unwrap_or(base.bits_per_sample.min(16)),
+                    })
+                } else {
+                    None
+                };
+                let snf = snf::import_audio(Path::new(input), output_path, target)
+                    .map_err(|e| format!("ERROR: could not convert {input} to SNF: {e}"))?;
+                eprintln!(
+                    "{input} → {output} ({}, {:.2} s)",
+                    snf.format(),
+                    snf.duration_secs()
+                );
+                Ok(())
+            }
+            SoundCommands::ExportAll { game_path, output } => {
+                let game_dir = Path::new(game_path);
+                let library = load_library(game_dir)?;
+                let summary = sound_library::export_all(&library, game_dir, Path::new(output))
+                    .map_err(|e| format!("ERROR: could not export sounds to {output}: {e}"))?;
+                for (file, e) in &summary.failed {
+                    eprintln!("Warning: skipped {file}: {e}");
+                }
+                let missing = library.check().missing_snf;
+                for entry in &missing {
+                    eprintln!(
+                        "Warning: skipped id {}: {} not found",
+                        entry.id, entry.snf_filename
+                    );
+                }
+                eprintln!(
+                    "{} WAV file(s) for {} Wave.ini id(s) written to {output}; {} failed, {} missing",
+                    summary.written.len(),
+                    summary.manifest.entries.len(),
+                    summary.failed.len(),
+                    missing.len()
+                );
+                Ok(())
+            }
+            SoundCommands::ImportAll {
+                input,
+                output,
+                normalize,
+            } => {
+                let options = ImportOptions {
+                    normalize_dbfs: *normalize,
+                };
+                let summary =
+                    sound_library::import_all(Path::new(input), Path::new(output), options)
+                        .map_err(|e| format!("ERROR: could not import sounds from {input}: {e}"))?;
+                for (file, e) in &summary.failed {
+                    eprintln!("Warning: skipped {file}: {e}");
+                }
+                eprintln!(
+                    "{} SNF file(s) written to {output} ({} converted); {} failed",
+                    summary.written.len(),
+                    summary.converted,
+                    summary.failed.len()
+                );
+                Ok(())
+            }
+            SoundCommands::Render {
+                inputs,
+                output,
+                width,
+                height,
+                fft_size,
+                silence_db,
+                stats_only,
+                fail_on_issues,
+            } => {
+                let out_dir = Path::new(output);
+                std::fs::create_dir_all(out_dir)
+                    .map_err(|e| format!("ERROR: could not create {output}: {e}"))?;
+                let options = RenderOptions {
+                    width: *width,
+                    height: *height,
+                    fft_size: *fft_size,
+                    silence_dbfs: *silence_db,
+                    stats_only: *stats_only,
+                };
+                let paths: Vec<&Path> = inputs.iter().map(Path::new).collect();
+                let report = sound_render::render_all(&paths, out_dir, options);
+                let report_path = out_dir.join("report.json");
+                std::fs::write(&report_path, serde_json::to_string_pretty(&report)?).map_err(
+                    |e| format!("ERROR: could not write {}: {e}", report_path.display()),
+                )?;
+                for (file, e) in &report.failed {
+                    eprintln!("Warning: skipped {file}: {e}");
+                }
+                let flagged: Vec<_> = report
+                    .sounds
+                    .iter()
+                    .filter(|s| !s.issues.is_empty())
+                    .collect();
+                for sound in &flagged {
+                    eprintln!("  {}: {}", sound.file, sound.issues.join(", "));
+                }
+                let rates: Vec<String> = report
+                    .sample_rates
+                    .iter()
+                    .map(|(rate, count)| format!("{count} at {rate} Hz"))
+                    .collect();
+                eprintln!(
+                    "{} file(s) {} ({}); {} with issues, {} failed",
+                    report.sounds.len(),
+                    if *stats_only {
+                        format!("analysed, report in {output}")
+                    } else {
+                        format!("rendered to {output}")
+                    },
+                    rates.join(", "),
+                    flagged.len(),
+                    report.failed.len()
+                );
+                if *fail_on_issues && !flagged.is_empty() {
+                    return Err(format!("ERROR: {} sound(s) have issues", flagged.len()).into());
+                }
+                Ok(())
+            }
+            SoundCommands::Check {
+                game_path,
+                json,
+                fail_on_missing,
+            } => {
+                let library = load_library(Path::new(game_path))?;
+                let report = library.check();
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    for entry in &report.missing_snf {
+                        println!("missing SNF: id {} -> {}", entry.id, entry.snf_filename);
+                    }
+                    for file in &report.unused_snf {
+                        println!("unused SNF: {file}");
+                    }
+                    for sound in &report.undefined_ids {
+                        println!("undefined id: {} ({})", sound.line, sound.file);
+                    }
+                    for sound in &report.unparsed {
+                        println!("unrecognised [WAV] line: {} ({})", sound.line, sound.file);
+                    }
+                }
+                eprintln!(
+                    "{} missing SNF file(s), {} unused SNF file(s), {} undefined script id(s), {} Wave.ini id(s) not named by any script",
+                    report.missing_snf.len(),
+                    report.unused_snf.len(),
+                    report.undefined_ids.len(),
+                    report.unreferenced_ids.len()
+                );
+                if *fail_on_missing && !report.is_clean() {
+                    return Err("ERROR: sound library has missing files or ids".into());
+                }
+                Ok(())
+            }
         }
     }
 }
+
+fn load_library(game_dir: &Path) -> Result<SoundLibrary, Box<dyn Error>> {
+    if !game_dir.is_dir() {
+        return Err(format!("ERROR: game directory not found: {}", game_dir.display()).into());
+    }
+    let library = SoundLibrary::load(game_dir)
+        .map_err(|e| format!("ERROR: could not read sound library: {e}"))?;
+    for warning in &library.warnings {
+        eprintln!("Warning: skipped {warning}");
+    }
+    Ok(library)
+}
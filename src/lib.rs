@@ -11,12 +11,14 @@ pub mod references;
 
 pub use references::layout::{FieldDef, FixedRecordLayout, RecordLayout};
 pub mod snf;
+pub mod sound_library;
+pub mod sound_render;
 pub mod sprite;
 
 // Re-export key types for easy access
 pub use localization::{
-    Localizable, TextEncoding, TextEntry, TruncationStatus, export_csv, export_po, import_csv,
-    import_po, truncate_to_fit,
+    Localizable, TextEncoding, TextEntry, TruncationStatus, export_csv, export_po, export_xliff,
+    fit_translation, import_csv, import_po, import_xliff, truncate_to_fit,
 };
 pub use references::{
     all_map_ini::Map,
@@ -53,6 +55,10 @@ pub use references::{
         MapViewportCell, MapViewportState, PostEventsData, PostMapsData, SaveFile,
     },
     store_db::Store,
+    store_inventory::{
+        CatalogItem, MAX_STORE_PRODUCTS, PRODUCT_TYPES, ProductSlot, ResolvedProduct, StoreCatalog,
+        StoreInventoryError,
+    },
     wave_ini::WaveIni,
     weapons_db::WeaponItem,
 };
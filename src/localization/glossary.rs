@@ -0,0 +1,241 @@
+//! Glossary consistency checks.
+//!
+//! Terms are proper names — items, NPCs, monsters, maps, stores — taken from
+//! the scanned entries themselves (see [`TERM_FIELDS`]) and optionally from a
+//! user glossary CSV. [`Glossary::check`] flags terms translated in more than
+//! one way and texts (dialogue, descriptions, quests) that mention a term
+//! without using its agreed translation.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::TextEntry;
+
+/// Field names whose values are treated as glossary terms.
+pub const TERM_FIELDS: &[&str] = &["name", "map_name", "store_name", "full_name"];
+
+/// Terms shorter than this (in chars) are ignored when searching inside
+/// other texts; they match too much by accident.
+const MIN_TERM_CHARS: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GlossaryIssue {
+    /// The same term has several different translations.
+    Inconsistent {
+        source: String,
+        translations: Vec<String>,
+    },
+    /// A translated text mentions the term in the original but its
+    /// translation does not contain the preferred term translation.
+    MissingTerm {
+        /// Index into the checked entries slice.
+        index: usize,
+        file_path: String,
+        record_id: usize,
+        field_name: String,
+        source: String,
+        expected: String,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    /// source term → distinct translations, most frequent first.
+    terms: BTreeMap<String, Vec<String>>,
+}
+
+impl Glossary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect translated term entries (fields listed in [`TERM_FIELDS`]).
+    pub fn from_entries(entries: &[TextEntry]) -> Self {
+        let mut counts: BTreeMap<&str, Vec<(&str, usize)>> = BTreeMap::new();
+        for e in entries
+            .iter()
+            .filter(|e| e.is_translated() && TERM_FIELDS.contains(&e.field_name))
+        {
+            let source = e.original.trim();
+            let target = e.translation.trim();
+            if source.is_empty() {
+                continue;
+            }
+            let list = counts.entry(source).or_default();
+            match list.iter_mut().find(|(t, _)| *t == target) {
+                Some((_, n)) => *n += 1,
+                None => list.push((target, 1)),
+            }
+        }
+        let mut glossary = Self::new();
+        for (source, mut list) in counts {
+            // Stable sort keeps first-seen order among equal counts.
+            list.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+            glossary.terms.insert(
+                source.to_owned(),
+                list.into_iter().map(|(t, _)| t.to_owned()).collect(),
+            );
+        }
+        glossary
+    }
+
+    /// Parse a two-column `source,target` CSV (header row required).
+    pub fn from_csv(csv: &str) -> Result<Self, csv::Error> {
+        let mut rdr = csv::Reader::from_reader(csv.as_bytes());
+        let mut glossary = Self::new();
+        for record in rdr.records() {
+            let record = record?;
+            let source = record.get(0).unwrap_or("").trim();
+            let target = record.get(1).unwrap_or("").trim();
+            if !source.is_empty() && !target.is_empty() {
+                glossary.insert(source, target);
+            }
+        }
+        Ok(glossary)
+    }
+
+    /// Set the preferred translation of a term. An explicit entry overrides
+    /// whatever was learned from the entries.
+    pub fn insert(&mut self, source: &str, target: &str) {
+        let list = self.terms.entry(source.to_owned()).or_default();
+        list.retain(|t| t != target);
+        list.insert(0, target.to_owned());
+    }
+
+    /// Overlay `other` on top of this glossary; its preferred translations win.
+    pub fn merge(&mut self, other: &Glossary) {
+        for (source, targets) in &other.terms {
+            for target in targets.iter().rev() {
+                self.insert(source, target);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Preferred translation of `source`, if known.
+    pub fn preferred(&self, source: &str) -> Option<&str> {
+        self.terms
+            .get(source)
+            .and_then(|t| t.first())
+            .map(String::as_str)
+    }
+
+    pub fn check(&self, entries: &[TextEntry]) -> Vec<GlossaryIssue> {
+        let mut issues: Vec<GlossaryIssue> = self
+            .terms
+            .iter()
+            .filter(|(_, t)| t.len() > 1)
+            .map(|(source, translations)| GlossaryIssue::Inconsistent {
+                source: source.clone(),
+                translations: translations.clone(),
+            })
+            .collect();
+
+        for (index, e) in entries.iter().enumerate() {
+            if !e.is_translated() {
+                continue;
+            }
+            let translation = e.translation.to_lowercase();
+            for (source, targets) in &self.terms {
+                let Some(expected) = targets.first() else {
+                    continue;
+                };
+                if source.chars().count() < MIN_TERM_CHARS
+                    || e.original.trim() == source
+                    || !e.original.contains(source.as_str())
+                    || translation.contains(&expected.to_lowercase())
+                {
+                    continue;
+                }
+                issues.push(GlossaryIssue::MissingTerm {
+                    index,
+                    file_path: e.file_path.clone(),
+                    record_id: e.record_id,
+                    field_name: e.field_name.to_owned(),
+                    source: source.clone(),
+                    expected: expected.clone(),
+                });
+            }
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::localization::TextEncoding;
+
+    fn entry(field_name: &'static str, original: &str, translation: &str) -> TextEntry {
+        TextEntry {
+            file_path: "x".into(),
+            record_id: 0,
+            field_name,
+            original: original.into(),
+            translation: translation.into(),
+            encoding: TextEncoding::Windows1250,
+            max_bytes: 100,
+            needs_review: false,
+        }
+    }
+
+    #[test]
+    fn flags_inconsistent_term_translations() {
+        let entries = vec![
+            entry("name", "Healing Potion", "Mikstura leczenia"),
+            entry("name", "Healing Potion", "Eliksir zdrowia"),
+            entry("name", "Healing Potion", "Mikstura leczenia"),
+        ];
+        let g = Glossary::from_entries(&entries);
+        assert_eq!(g.preferred("Healing Potion"), Some("Mikstura leczenia"));
+        let issues = g.check(&entries);
+        assert_eq!(
+            issues,
+            vec![GlossaryIssue::Inconsistent {
+                source: "Healing Potion".into(),
+                translations: vec!["Mikstura leczenia".into(), "Eliksir zdrowia".into()],
+            }]
+        );
+    }
+
+    #[test]
+    fn flags_item_name_missing_from_dialogue() {
+        let entries = vec![
+            entry("name", "Healing Potion", "Mikstura leczenia"),
+            entry("text", "Bring me a Healing Potion.", "Przynieś mi eliksir."),
+            entry(
+                "text",
+                "A Healing Potion, thanks!",
+                "Mikstura leczenia, dzięki!",
+            ),
+            entry("text", "Healing Potion? No.", ""),
+        ];
+        let issues = Glossary::from_entries(&entries).check(&entries);
+        assert_eq!(issues.len(), 1);
+        let GlossaryIssue::MissingTerm {
+            index, expected, ..
+        } = &issues[0]
+        else {
+            panic!("expected MissingTerm");
+        };
+        assert_eq!(*index, 1);
+        assert_eq!(expected, "Mikstura leczenia");
+    }
+
+    #[test]
+    fn csv_glossary_overrides_learned_terms() {
+        let entries = vec![entry("name", "Dragon", "Smok")];
+        let mut g = Glossary::from_entries(&entries);
+        g.merge(&Glossary::from_csv("source,target\nDragon,Wielki Smok\n").unwrap());
+        assert_eq!(g.preferred("Dragon"), Some("Wielki Smok"));
+    }
+}
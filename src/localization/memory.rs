@@ -0,0 +1,323 @@
+//! Translation memory: reuse existing translations for repeated and
+//! near-identical source strings across files.
+//!
+//! Exact matches compare the original text verbatim. Fuzzy matches use a
+//! character-level edit-distance similarity in `0.0..=1.0`; candidates whose
+//! length alone rules out reaching the threshold are never compared.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::TextEntry;
+
+/// One source → target pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TmUnit {
+    pub source: String,
+    pub target: String,
+}
+
+/// A translation proposed by the memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TmMatch<'a> {
+    pub source: &'a str,
+    pub target: &'a str,
+    /// 1.0 for an exact match.
+    pub score: f32,
+}
+
+/// An entry filled by [`TranslationMemory::fill`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TmFill {
+    /// Index into the entries slice.
+    pub index: usize,
+    /// Source text of the memory unit that was used.
+    pub source: String,
+    pub score: f32,
+}
+
+impl TmFill {
+    pub fn is_exact(&self) -> bool {
+        self.score >= 1.0
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranslationMemory {
+    units: Vec<TmUnit>,
+    #[serde(skip)]
+    by_source: HashMap<String, usize>,
+}
+
+impl TranslationMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a memory from the translated, reviewed entries. When the same original has
+    /// several translations the most frequent one wins (ties: first seen).
+    pub fn from_entries(entries: &[TextEntry]) -> Self {
+        let mut counts: HashMap<(&str, &str), usize> = HashMap::new();
+        let mut order: Vec<(&str, &str)> = Vec::new();
+        for e in entries
+            .iter()
+            .filter(|e| e.is_translated() && !e.needs_review)
+        {
+            let key = (e.original.as_str(), e.translation.as_str());
+            let n = counts.entry(key).or_insert(0);
+            if *n == 0 {
+                order.push(key);
+            }
+            *n += 1;
+        }
+        let mut best: HashMap<&str, ((&str, &str), usize)> = HashMap::new();
+        for key in &order {
+            let n = counts[key];
+            match best.get(key.0) {
+                Some((_, m)) if *m >= n => {}
+                _ => {
+                    best.insert(key.0, (*key, n));
+                }
+            }
+        }
+        let mut tm = Self::new();
+        for (source, _) in &order {
+            if let Some(((s, t), _)) = best.remove(source) {
+                tm.insert(s, t);
+            }
+        }
+        tm
+    }
+
+    /// Add or replace a unit.
+    pub fn insert(&mut self, source: &str, target: &str) {
+        if source.is_empty() || target.is_empty() {
+            return;
+        }
+        match self.by_source.get(source) {
+            Some(&i) => self.units[i].target = target.to_owned(),
+            None => {
+                self.by_source.insert(source.to_owned(), self.units.len());
+                self.units.push(TmUnit {
+                    source: source.to_owned(),
+                    target: target.to_owned(),
+                });
+            }
+        }
+    }
+
+    /// Add every unit of `other`, replacing existing targets.
+    pub fn merge(&mut self, other: &TranslationMemory) {
+        for u in &other.units {
+            self.insert(&u.source, &u.target);
+        }
+    }
+
+    pub fn units(&self) -> &[TmUnit] {
+        &self.units
+    }
+
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    /// Best match for `source` scoring at least `min_score`. Exact matches
+    /// are returned without a fuzzy search.
+    pub fn lookup(&self, source: &str, min_score: f32) -> Option<TmMatch<'_>> {
+        if let Some(&i) = self.by_source.get(source) {
+            let u = &self.units[i];
+            return Some(TmMatch {
+                source: &u.source,
+                target: &u.target,
+                score: 1.0,
+            });
+        }
+        if min_score >= 1.0 || source.is_empty() {
+            return None;
+        }
+        let len = source.chars().count();
+        let query: Vec<char> = source.chars().collect();
+        let mut best: Option<TmMatch<'_>> = None;
+        for u in &self.units {
+            let other_len = u.source.chars().count();
+            if !length_can_match(len, other_len, min_score) {
+                continue;
+            }
+            let score = similarity(&query, &u.source);
+            if score >= min_score && best.as_ref().is_none_or(|b| score > b.score) {
+                best = Some(TmMatch {
+                    source: &u.source,
+                    target: &u.target,
+                    score,
+                });
+            }
+        }
+        best
+    }
+
+    /// Fill untranslated entries from the memory. Entries that already have a
+    /// translation are left alone. Pass `min_score = 1.0` for exact matches
+    /// only; fuzzy fills are flagged `needs_review`.
+    pub fn fill(&self, entries: &mut [TextEntry], min_score: f32) -> Vec<TmFill> {
+        let mut fills = Vec::new();
+        for (index, e) in entries.iter_mut().enumerate() {
+            if e.is_translated() || e.original.is_empty() {
+                continue;
+            }
+            if let Some(m) = self.lookup(&e.original, min_score) {
+                e.translation = m.target.to_owned();
+                e.needs_review = m.score < 1.0;
+                fills.push(TmFill {
+                    index,
+                    source: m.source.to_owned(),
+                    score: m.score,
+                });
+            }
+        }
+        fills
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let loaded: TranslationMemory =
+            serde_json::from_slice(&bytes).map_err(std::io::Error::other)?;
+        // Rebuild the index; later duplicates win like `insert`.
+        let mut tm = Self::new();
+        for u in &loaded.units {
+            tm.insert(&u.source, &u.target);
+        }
+        Ok(tm)
+    }
+}
+
+/// Whether two strings of these lengths can reach `min_score` at all: the
+/// edit distance is at least the length difference.
+fn length_can_match(a: usize, b: usize, min_score: f32) -> bool {
+    let max = a.max(b);
+    if max == 0 {
+        return true;
+    }
+    let best = 1.0 - a.abs_diff(b) as f32 / max as f32;
+    best >= min_score
+}
+
+/// `1 - levenshtein / max_len` over chars.
+fn similarity(a: &[char], b: &str) -> f32 {
+    let b: Vec<char> = b.chars().collect();
+    let max = a.len().max(b.len());
+    if max == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, &b) as f32 / max as f32
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::localization::TextEncoding;
+
+    fn entry(file: &str, original: &str, translation: &str) -> TextEntry {
+        TextEntry {
+            file_path: file.into(),
+            record_id: 0,
+            field_name: "description",
+            original: original.into(),
+            translation: translation.into(),
+            encoding: TextEncoding::Windows1250,
+            max_bytes: 100,
+            needs_review: false,
+        }
+    }
+
+    #[test]
+    fn exact_and_fuzzy_fill_across_files() {
+        let mut entries = vec![
+            entry(
+                "a.db",
+                "Restores a little health.",
+                "Przywraca trochę zdrowia.",
+            ),
+            entry("b.db", "Restores a little health.", ""),
+            entry("c.db", "Restores a lot of health.", ""),
+            entry("d.db", "Completely unrelated text", ""),
+        ];
+        let tm = TranslationMemory::from_entries(&entries);
+        assert_eq!(tm.len(), 1);
+
+        let fills = tm.fill(&mut entries, 0.8);
+        assert_eq!(fills.len(), 2);
+        assert!(fills[0].is_exact());
+        assert_eq!(fills[0].index, 1);
+        assert_eq!(entries[1].translation, "Przywraca trochę zdrowia.");
+        assert!(!entries[1].needs_review);
+        assert!(!fills[1].is_exact());
+        assert_eq!(fills[1].index, 2);
+        assert!(entries[2].needs_review);
+        assert!(entries[3].translation.is_empty());
+
+        // Unreviewed fuzzy fills do not seed a new memory.
+        let tm = TranslationMemory::from_entries(&entries);
+        assert!(tm.lookup("Restores a lot of health.", 1.0).is_none());
+    }
+
+    #[test]
+    fn exact_only_threshold() {
+        let mut entries = vec![entry("a.db", "Sword", "Miecz"), entry("b.db", "Swords", "")];
+        let tm = TranslationMemory::from_entries(&entries);
+        assert!(tm.fill(&mut entries, 1.0).is_empty());
+    }
+
+    #[test]
+    fn most_frequent_translation_wins() {
+        let entries = vec![
+            entry("a", "Potion", "Mikstura"),
+            entry("b", "Potion", "Eliksir"),
+            entry("c", "Potion", "Eliksir"),
+        ];
+        let tm = TranslationMemory::from_entries(&entries);
+        assert_eq!(tm.lookup("Potion", 1.0).unwrap().target, "Eliksir");
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tm.json");
+        let mut tm = TranslationMemory::new();
+        tm.insert("Gold", "Złoto");
+        tm.save(&path).unwrap();
+        let loaded = TranslationMemory::load(&path).unwrap();
+        assert_eq!(loaded.lookup("Gold", 1.0).unwrap().target, "Złoto");
+    }
+
+    #[test]
+    fn levenshtein_basics() {
+        let a: Vec<char> = "kitten".chars().collect();
+        let b: Vec<char> = "sitting".chars().collect();
+        assert_eq!(levenshtein(&a, &b), 3);
+    }
+}
@@ -1,5 +1,22 @@
 //! Localization support: extract translatable text from game records, export/import
 //! CSV and PO files, and apply translations back to game structs.
+//!
+//! [`project`] builds on these primitives to scan, apply and package a whole
+//! game installation; [`xliff`], [`memory`] and [`glossary`] serve CAT-tool
+//! workflows; [`transliterate`] guards against characters the game's code
+//! pages cannot hold.
+
+pub mod glossary;
+pub mod memory;
+pub mod project;
+pub mod transliterate;
+pub mod xliff;
+
+pub use glossary::{Glossary, GlossaryIssue};
+pub use memory::{TmFill, TranslationMemory};
+pub use project::{LocalizationError, LocalizationProject, SavedTranslation};
+pub use transliterate::{EncodingIssue, TransliterationTable};
+pub use xliff::{XliffError, export_xliff, import_xliff};
 
 use std::collections::HashMap;
 
@@ -31,6 +48,10 @@ pub struct TextEntry {
     pub translation: String,
     pub encoding: TextEncoding,
     pub max_bytes: usize,
+    /// Set when `translation` came from a fuzzy memory match and has not been
+    /// confirmed yet; such entries are left out of apply/package by default.
+    #[serde(default)]
+    pub needs_review: bool,
 }
 
 impl TextEntry {
@@ -48,12 +69,37 @@ impl TextEntry {
     pub fn would_truncate(&self) -> bool {
         self.encoded_translation_len() > self.max_bytes
     }
+
+    /// Characters of `translation` the target encoding cannot represent.
+    pub fn unrepresentable_chars(&self) -> Vec<char> {
+        transliterate::unrepresentable_chars(&self.translation, &self.encoding)
+    }
+
+    /// Whether encoding the translation would silently replace characters.
+    pub fn is_lossy(&self) -> bool {
+        self.translation
+            .chars()
+            .any(|c| !transliterate::is_representable(c, &self.encoding))
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TruncationStatus {
     Ok,
-    Truncated { original_bytes: usize },
+    Truncated {
+        original_bytes: usize,
+    },
+    /// The text contains characters the encoding cannot represent; they were
+    /// replaced when saving. Takes precedence over `Truncated`.
+    Lossy {
+        chars: Vec<char>,
+    },
+}
+
+impl TruncationStatus {
+    pub fn is_lossy(&self) -> bool {
+        matches!(self, TruncationStatus::Lossy { .. })
+    }
 }
 
 // ─── Localizable trait ───────────────────────────────────────────────────────
@@ -102,6 +148,27 @@ pub fn truncate_to_fit(s: &str, enc: &TextEncoding, max_bytes: usize) -> (String
     (result, true)
 }
 
+/// Fit a translation into a field: truncate to `max_bytes` and report the
+/// outcome. Used by `#[derive(Localizable)]`.
+pub fn fit_translation(
+    s: &str,
+    enc: &TextEncoding,
+    max_bytes: usize,
+) -> (String, TruncationStatus) {
+    let (text, was_truncated) = truncate_to_fit(s, enc, max_bytes);
+    let chars = transliterate::unrepresentable_chars(&text, enc);
+    let status = if !chars.is_empty() {
+        TruncationStatus::Lossy { chars }
+    } else if was_truncated {
+        TruncationStatus::Truncated {
+            original_bytes: s.len(),
+        }
+    } else {
+        TruncationStatus::Ok
+    };
+    (text, status)
+}
+
 // ─── CSV export / import ─────────────────────────────────────────────────────
 
 /// Export entries to UTF-8 CSV with headers.
@@ -156,6 +223,7 @@ pub fn import_csv(csv: &str, entries: &mut [TextEntry]) -> Result<usize, csv::Er
         let translation = record.get(4).unwrap_or("").to_owned();
         if let Some(&idx) = index.get(&(file_path, record_id, field_name)) {
             entries[idx].translation = translation;
+            entries[idx].needs_review = false;
             updated += 1;
         }
     }
@@ -218,6 +286,7 @@ pub fn import_po(po: &str, entries: &mut [TextEntry]) -> usize {
                 {
                     for &i in idxs {
                         entries[i].translation = msgstr_buf.clone();
+                        entries[i].needs_review = false;
                         updated += 1;
                     }
                 }
@@ -239,6 +308,7 @@ pub fn import_po(po: &str, entries: &mut [TextEntry]) -> usize {
             {
                 for &i in idxs {
                     entries[i].translation = msgstr_buf.clone();
+                    entries[i].needs_review = false;
                     updated += 1;
                 }
             }
@@ -255,6 +325,7 @@ pub fn import_po(po: &str, entries: &mut [TextEntry]) -> usize {
     {
         for &i in idxs {
             entries[i].translation = msgstr_buf.clone();
+            entries[i].needs_review = false;
             updated += 1;
         }
     }
@@ -303,6 +374,7 @@ mod tests {
                 translation: String::new(),
                 encoding: TextEncoding::Windows1250,
                 max_bytes: 32,
+                needs_review: false,
             },
             TextEntry {
                 file_path: "CharacterInGame/STORE.DB".to_owned(),
@@ -312,6 +384,7 @@ mod tests {
                 translation: "Witaj, wędrowcze.".to_owned(),
                 encoding: TextEncoding::Windows1250,
                 max_bytes: 512,
+                needs_review: false,
             },
         ]
     }
@@ -352,6 +425,21 @@ mod tests {
         assert_eq!(s, "Hi");
     }
 
+    #[test]
+    fn fit_translation_reports_lossy_before_truncation() {
+        let (_, status) = fit_translation("Жук long", &TextEncoding::Windows1250, 20);
+        assert!(status.is_lossy());
+        let (s, status) = fit_translation("Hello", &TextEncoding::Windows1250, 3);
+        assert_eq!(s, "Hel");
+        assert_eq!(status, TruncationStatus::Truncated { original_bytes: 5 });
+        let e = TextEntry {
+            translation: "Привет".to_owned(),
+            ..sample_entries().remove(0)
+        };
+        assert!(e.is_lossy());
+        assert_eq!(e.unrepresentable_chars().len(), 6);
+    }
+
     #[test]
     fn is_translated() {
         let mut e = sample_entries().remove(0);
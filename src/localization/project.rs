@@ -0,0 +1,792 @@
+//! Project-level localization pipeline.
+//!
+//! Scans every translatable file of a game installation into a flat list of
+//! [`TextEntry`] values, persists translator sessions, writes translations
+//! back into the game files (keeping backups of the originals), and packages
+//! the translated files as a regular [`ModPackage`].
+//!
+//! Pure data + I/O. Shared by the CLI `l10n` subcommands and the GUI
+//! localization manager.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use super::transliterate::unrepresentable_chars;
+use super::{Localizable, TextEntry, TransliterationTable};
+use crate::modding::{ChangeAction, ChangeLog, ChangeOp, ModManifest, ModPackage, ModdingError};
+use crate::{
+    DialogueParagraph, EditItem, EventItem, EventNpcRef, ExtraRef, Extractor, HealItem, Map,
+    Message, MiscItem, MonsterIni, NPC, PartyIniNpc, PartyRef, Quest, Store, WeaponItem,
+};
+
+// ─── Errors ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Error)]
+pub enum LocalizationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("could not read {path}: {source}")]
+    File {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("csv error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("mod packaging error: {0}")]
+    Modding(#[from] ModdingError),
+
+    #[error("no translated strings to apply")]
+    NothingToApply,
+
+    #[error(
+        "{count} translations contain characters their encoding cannot represent (first: {first}); transliterate them or allow lossy output"
+    )]
+    LossyEncoding { count: usize, first: String },
+
+    #[error("invalid transliteration table: {0}")]
+    InvalidTable(String),
+}
+
+pub type Result<T> = std::result::Result<T, LocalizationError>;
+
+// ─── Project layout ──────────────────────────────────────────────────────────
+
+/// Directory holding session and backups for a translation mod:
+/// `<game>/mods/<mod_name>` with spaces replaced by underscores.
+pub fn mod_dir(game_path: &Path, mod_name: &str) -> PathBuf {
+    game_path
+        .join("mods")
+        .join(mod_name.trim().replace(' ', "_"))
+}
+
+/// Location of the translator session (`session.json`) for a mod.
+pub fn session_path(game_path: &Path, mod_name: &str) -> PathBuf {
+    mod_dir(game_path, mod_name).join("session.json")
+}
+
+/// Location of the original-file backups for a mod.
+pub fn backup_dir(game_path: &Path, mod_name: &str) -> PathBuf {
+    mod_dir(game_path, mod_name).join("backup")
+}
+
+/// Top-level directories produced by the tooling itself; never scanned.
+const TOOL_DIRS: &[&str] = &["mods", "mod_output"];
+
+const NPC_REF_FILES: &[&str] = &[
+    "NpcInGame/Npccat1.ref",
+    "NpcInGame/Npccat2.ref",
+    "NpcInGame/Npccat3.ref",
+    "NpcInGame/Npccatp.ref",
+    "NpcInGame/npcdun08.ref",
+    "NpcInGame/npcdun19.ref",
+    "NpcInGame/Npcmap1.ref",
+    "NpcInGame/Npcmap2.ref",
+    "NpcInGame/Npcmap3.ref",
+];
+
+const EXTRA_REF_FILES: &[&str] = &[
+    "ExtraInGame/Extcat3.ref",
+    "ExtraInGame/Extdun01.ref",
+    "ExtraInGame/Extdun02.ref",
+    "ExtraInGame/Extdun03.ref",
+    "ExtraInGame/Extdun04.ref",
+    "ExtraInGame/Extdun05.ref",
+    "ExtraInGame/Extdun06.ref",
+    "ExtraInGame/Extdun07.ref",
+    "ExtraInGame/Extdun08.ref",
+    "ExtraInGame/Extdun09.ref",
+    "ExtraInGame/Extdun10.ref",
+    "ExtraInGame/Extdun11.ref",
+    "ExtraInGame/Extdun12.ref",
+    "ExtraInGame/Extdun13.ref",
+    "ExtraInGame/Extdun14.ref",
+    "ExtraInGame/Extdun15.ref",
+    "ExtraInGame/Extdun16.ref",
+    "ExtraInGame/Extdun17.ref",
+    "ExtraInGame/Extdun18.ref",
+    "ExtraInGame/Extdun19.ref",
+    "ExtraInGame/Extdun20.ref",
+    "ExtraInGame/Extdun21.ref",
+    "ExtraInGame/Extdun22.ref",
+    "ExtraInGame/Extdun23.ref",
+    "ExtraInGame/Extdun24.ref",
+    "ExtraInGame/Extdun25.ref",
+    "ExtraInGame/Extfinal.ref",
+    "ExtraInGame/Extmap1.ref",
+    "ExtraInGame/Extmap2.ref",
+    "ExtraInGame/Extmap3.ref",
+];
+
+fn abs_path(root: &Path, rel: &str) -> PathBuf {
+    root.join(rel.replace('/', std::path::MAIN_SEPARATOR_STR))
+}
+
+// ─── Session persistence ─────────────────────────────────────────────────────
+
+/// Lightweight session record — fully owned so it serializes/deserializes without lifetime issues.
+/// `TextEntry.field_name` is `&'static str` and cannot be deserialized from JSON directly.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SavedTranslation {
+    pub file_path: String,
+    pub record_id: usize,
+    pub field_name: String,
+    pub translation: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub needs_review: bool,
+}
+
+/// Write the translated subset of `entries` to `path` as JSON.
+pub fn save_session(path: &Path, entries: &[TextEntry]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let saved: Vec<SavedTranslation> = entries
+        .iter()
+        .filter(|e| e.is_translated())
+        .map(|e| SavedTranslation {
+            file_path: e.file_path.clone(),
+            record_id: e.record_id,
+            field_name: e.field_name.to_owned(),
+            translation: e.translation.clone(),
+            needs_review: e.needs_review,
+        })
+        .collect();
+    std::fs::write(path, serde_json::to_vec(&saved)?)?;
+    Ok(())
+}
+
+/// Read a session file. A missing file is an empty session.
+pub fn load_session(path: &Path) -> Result<Vec<SavedTranslation>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let s = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&s)?)
+}
+
+/// Merge saved translations into freshly-scanned entries.
+/// Matches by (file_path, record_id, field_name). Returns the number of entries updated.
+pub fn merge_session(entries: &mut [TextEntry], saved: &[SavedTranslation]) -> usize {
+    let saved_map: HashMap<(&str, usize, &str), &SavedTranslation> = saved
+        .iter()
+        .map(|e| {
+            (
+                (e.file_path.as_str(), e.record_id, e.field_name.as_str()),
+                e,
+            )
+        })
+        .collect();
+    let mut merged = 0;
+    for entry in entries.iter_mut() {
+        if let Some(&t) =
+            saved_map.get(&(entry.file_path.as_str(), entry.record_id, entry.field_name))
+            && !t.translation.is_empty()
+        {
+            entry.translation = t.translation.clone();
+            entry.needs_review = t.needs_review;
+            merged += 1;
+        }
+    }
+    merged
+}
+
+// ─── Scan ────────────────────────────────────────────────────────────────────
+
+fn scan_one<T: Extractor + Localizable>(
+    game_path: &Path,
+    rel: &str,
+    entries: &mut Vec<TextEntry>,
+) -> Result<()> {
+    let abs = abs_path(game_path, rel);
+    if !abs.exists() {
+        return Ok(());
+    }
+    let records = T::read_file(&abs).map_err(|source| LocalizationError::File {
+        path: rel.to_owned(),
+        source,
+    })?;
+    for (i, record) in records.iter().enumerate() {
+        entries.extend(record.extract_texts(i, rel));
+    }
+    Ok(())
+}
+
+/// Extract every translatable string from a game installation.
+pub fn scan_entries(game_path: &Path) -> Result<Vec<TextEntry>> {
+    let mut entries = Vec::new();
+
+    // Store.db — uses record.index as logical ID, not position
+    let store_rel = "CharacterInGame/STORE.DB";
+    let store_path = abs_path(game_path, store_rel);
+    if store_path.exists() {
+        let records = Store::read_file(&store_path).map_err(|source| LocalizationError::File {
+            path: store_rel.to_owned(),
+            source,
+        })?;
+        for record in &records {
+            entries.extend(record.extract_texts(record.index as usize, store_rel));
+        }
+    }
+
+    scan_one::<WeaponItem>(game_path, "CharacterInGame/weaponItem.db", &mut entries)?;
+    scan_one::<HealItem>(game_path, "CharacterInGame/HealItem.db", &mut entries)?;
+    scan_one::<EditItem>(game_path, "CharacterInGame/EditItem.db", &mut entries)?;
+    scan_one::<EventItem>(game_path, "CharacterInGame/EventItem.db", &mut entries)?;
+    scan_one::<MiscItem>(game_path, "CharacterInGame/MiscItem.db", &mut entries)?;
+    scan_one::<Message>(game_path, "ExtraInGame/Message.scr", &mut entries)?;
+    scan_one::<PartyIniNpc>(game_path, "NpcInGame/PrtIni.db", &mut entries)?;
+    scan_one::<EventNpcRef>(game_path, "NpcInGame/Eventnpc.ref", &mut entries)?;
+    scan_one::<Quest>(game_path, "ExtraInGame/Quest.scr", &mut entries)?;
+    scan_one::<Map>(game_path, "AllMap.ini", &mut entries)?;
+    scan_one::<MonsterIni>(game_path, "Monster.ini", &mut entries)?;
+    scan_one::<PartyRef>(game_path, "Ref/PartyRef.ref", &mut entries)?;
+
+    for rel in NPC_REF_FILES {
+        scan_one::<NPC>(game_path, rel, &mut entries)?;
+    }
+    for rel in EXTRA_REF_FILES {
+        scan_one::<ExtraRef>(game_path, rel, &mut entries)?;
+    }
+
+    // Dialogue paragraphs — scan all *.pgp files
+    for rel in pgp_files(game_path) {
+        scan_one::<DialogueParagraph>(game_path, &rel, &mut entries)?;
+    }
+
+    Ok(entries)
+}
+
+/// Relative paths of all `*.pgp` files under `game_path`, sorted, skipping
+/// the tooling's own `mods/` and `mod_output/` directories.
+fn pgp_files(game_path: &Path) -> Vec<String> {
+    let mut out: Vec<String> = walk_files(game_path)
+        .into_iter()
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("pgp"))
+        .map(|p| rel_string(game_path, &p))
+        .filter(|rel| {
+            let top = rel.split('/').next().unwrap_or_default();
+            !TOOL_DIRS.contains(&top)
+        })
+        .collect();
+    out.sort();
+    out
+}
+
+fn rel_string(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn walk_files(root: &Path) -> Vec<PathBuf> {
+    fn collect(dir: &Path, out: &mut Vec<PathBuf>) {
+        let Ok(rd) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in rd.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect(&path, out);
+            } else {
+                out.push(path);
+            }
+        }
+    }
+    let mut results = Vec::new();
+    collect(root, &mut results);
+    results
+}
+
+// ─── Apply ───────────────────────────────────────────────────────────────────
+
+type ByRecord<'a> = HashMap<usize, Vec<&'a TextEntry>>;
+
+/// Parse `bytes` as `T`, apply translations keyed by `key(position, record)`,
+/// and serialise the records back.
+fn translate_records<T: Extractor + Localizable>(
+    bytes: &[u8],
+    by_record: &ByRecord<'_>,
+    key: impl Fn(usize, &T) -> usize,
+) -> std::io::Result<Vec<u8>> {
+    let mut records = T::parse(&mut Cursor::new(bytes), bytes.len() as u64)?;
+    for (i, record) in records.iter_mut().enumerate() {
+        if let Some(entries) = by_record.get(&key(i, record)) {
+            let owned: Vec<TextEntry> = entries.iter().map(|e| (*e).clone()).collect();
+            record.apply_texts(&owned);
+        }
+    }
+    let mut out = Vec::with_capacity(bytes.len());
+    T::to_writer(&records, &mut out)?;
+    Ok(out)
+}
+
+fn by_position<T>(i: usize, _: &T) -> usize {
+    i
+}
+
+/// Translate the raw bytes of one game file. Returns `None` when the path is
+/// not a known localizable file type.
+fn translate_file(
+    rel_path: &str,
+    bytes: &[u8],
+    file_entries: &[&TextEntry],
+) -> std::io::Result<Option<Vec<u8>>> {
+    let mut by_record: ByRecord<'_> = HashMap::new();
+    for e in file_entries {
+        by_record.entry(e.record_id).or_default().push(e);
+    }
+
+    let lower = rel_path.to_lowercase();
+    let out = if lower.ends_with("store.db") {
+        translate_records::<Store>(bytes, &by_record, |_, r| r.index as usize)?
+    } else if lower.ends_with("weaponitem.db") {
+        translate_records::<WeaponItem>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("healitem.db") {
+        translate_records::<HealItem>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("edititem.db") {
+        translate_records::<EditItem>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("eventitem.db") {
+        translate_records::<EventItem>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("miscitem.db") {
+        translate_records::<MiscItem>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("message.scr") {
+        translate_records::<Message>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("prtini.db") {
+        translate_records::<PartyIniNpc>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("eventnpc.ref") {
+        translate_records::<EventNpcRef>(bytes, &by_record, by_position)?
+    } else if lower.contains("npcingame/") && lower.ends_with(".ref") {
+        translate_records::<NPC>(bytes, &by_record, by_position)?
+    } else if lower.contains("extraingame/") && lower.ends_with(".ref") {
+        translate_records::<ExtraRef>(bytes, &by_record, by_position)?
+    } else if lower.ends_with(".pgp") {
+        translate_records::<DialogueParagraph>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("quest.scr") {
+        translate_records::<Quest>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("allmap.ini") {
+        translate_records::<Map>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("monster.ini") {
+        translate_records::<MonsterIni>(bytes, &by_record, by_position)?
+    } else if lower.ends_with("partyref.ref") {
+        translate_records::<PartyRef>(bytes, &by_record, by_position)?
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(out))
+}
+
+// ─── Project ─────────────────────────────────────────────────────────────────
+
+/// A scanned game installation together with the translator's work.
+#[derive(Debug, Clone)]
+pub struct LocalizationProject {
+    pub game_path: PathBuf,
+    pub entries: Vec<TextEntry>,
+    /// Write translations even when characters would be replaced by the
+    /// encoder. Off by default: [`translated_files`](Self::translated_files)
+    /// refuses lossy text.
+    pub allow_lossy: bool,
+    /// Also write fuzzy memory fills still marked `needs_review`. Off by
+    /// default: unreviewed entries are skipped.
+    pub include_unreviewed: bool,
+    /// Table applied to every written translation before encoding, so
+    /// characters it covers no longer count as lossy.
+    pub transliteration: Option<TransliterationTable>,
+}
+
+impl LocalizationProject {
+    /// Scan every translatable file under `game_path`.
+    pub fn scan(game_path: impl Into<PathBuf>) -> Result<Self> {
+        let game_path = game_path.into();
+        let entries = scan_entries(&game_path)?;
+        Ok(Self {
+            game_path,
+            entries,
+            allow_lossy: false,
+            include_unreviewed: false,
+            transliteration: None,
+        })
+    }
+
+    /// Merge a session file on top of the scanned entries. Returns the number
+    /// of entries that received a saved translation.
+    pub fn merge_session_file(&mut self, path: &Path) -> Result<usize> {
+        let saved = load_session(path)?;
+        Ok(merge_session(&mut self.entries, &saved))
+    }
+
+    /// Persist the translated entries to a session file.
+    pub fn save_session(&self, path: &Path) -> Result<()> {
+        save_session(path, &self.entries)
+    }
+
+    pub fn translated_count(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_translated()).count()
+    }
+
+    pub fn overlong_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.is_translated() && e.would_truncate())
+            .count()
+    }
+
+    /// Translated entries still marked `needs_review`.
+    pub fn unreviewed_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.is_translated() && e.needs_review)
+            .count()
+    }
+
+    /// Whether `e` is written by apply/package.
+    fn is_applied(&self, e: &TextEntry) -> bool {
+        e.is_translated() && (self.include_unreviewed || !e.needs_review)
+    }
+
+    /// The text written for `e`, after the transliteration table.
+    fn output_text<'a>(&self, e: &'a TextEntry) -> Cow<'a, str> {
+        match &self.transliteration {
+            Some(table) => Cow::Owned(table.apply(&e.translation, &e.encoding)),
+            None => Cow::Borrowed(&e.translation),
+        }
+    }
+
+    /// Applied entries whose text the target encoding cannot represent, even
+    /// after the transliteration table.
+    pub fn lossy_entries(&self) -> impl Iterator<Item = &TextEntry> {
+        self.entries.iter().filter(|e| {
+            self.is_applied(e)
+                && !unrepresentable_chars(&self.output_text(e), &e.encoding).is_empty()
+        })
+    }
+
+    /// Applied entries, transliterated, grouped by file in path order.
+    fn translated_by_file(&self) -> Vec<(&str, Vec<TextEntry>)> {
+        let mut by_file: HashMap<&str, Vec<TextEntry>> = HashMap::new();
+        for e in &self.entries {
+            if self.is_applied(e) {
+                let mut out = e.clone();
+                if let Cow::Owned(text) = self.output_text(e) {
+                    out.translation = text;
+                }
+                by_file.entry(&e.file_path).or_default().push(out);
+            }
+        }
+        let mut files: Vec<_> = by_file.into_iter().collect();
+        files.sort_by_key(|(path, _)| *path);
+        files
+    }
+
+    /// Produce translated bytes for every file that has at least one
+    /// translation, as `(relative_path, bytes)`. Entries marked
+    /// `needs_review` are skipped unless `include_unreviewed` is set; the
+    /// transliteration table, if any, runs before the lossy check.
+    ///
+    /// Translations are always applied on top of the original file: when
+    /// `backup_dir` holds a backup from a previous apply, that copy is used as
+    /// the source instead of the (already translated) game file.
+    pub fn translated_files(&self, backup_dir: Option<&Path>) -> Result<Vec<(String, Vec<u8>)>> {
+        let by_file = self.translated_by_file();
+        if by_file.is_empty() {
+            return Err(LocalizationError::NothingToApply);
+        }
+        if !self.allow_lossy {
+            let lossy: Vec<&TextEntry> = self.lossy_entries().collect();
+            if let Some(first) = lossy.first() {
+                return Err(LocalizationError::LossyEncoding {
+                    count: lossy.len(),
+                    first: format!(
+                        "{}:{} {} {:?}",
+                        first.file_path,
+                        first.record_id,
+                        first.field_name,
+                        unrepresentable_chars(&self.output_text(first), &first.encoding)
+                    ),
+                });
+            }
+        }
+        let mut out = Vec::with_capacity(by_file.len());
+        for (rel_path, file_entries) in by_file {
+            let source = backup_dir
+                .map(|dir| abs_path(dir, rel_path))
+                .filter(|p| p.exists())
+                .unwrap_or_else(|| abs_path(&self.game_path, rel_path));
+            if !source.exists() {
+                continue;
+            }
+            let file_err = |source| LocalizationError::File {
+                path: rel_path.to_owned(),
+                source,
+            };
+            let bytes = std::fs::read(&source).map_err(file_err)?;
+            let file_entries: Vec<&TextEntry> = file_entries.iter().collect();
+            if let Some(translated) =
+                translate_file(rel_path, &bytes, &file_entries).map_err(file_err)?
+            {
+                out.push((rel_path.to_owned(), translated));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Write translations into the game files, backing up each original into
+    /// `backup_dir` (mirroring relative paths) the first time it is touched.
+    /// Returns the relative paths written.
+    pub fn apply(&self, backup_dir: &Path) -> Result<Vec<String>> {
+        let files = self.translated_files(Some(backup_dir))?;
+        let mut written = Vec::with_capacity(files.len());
+        for (rel_path, bytes) in files {
+            let target = abs_path(&self.game_path, &rel_path);
+            let backup = abs_path(backup_dir, &rel_path);
+            if !backup.exists() {
+                if let Some(parent) = backup.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::copy(&target, &backup)?;
+            }
+            std::fs::write(&target, bytes)?;
+            written.push(rel_path);
+        }
+        Ok(written)
+    }
+
+    /// Package the translated files as a mod: one `FileReplace` action per
+    /// file. Game files are not modified.
+    pub fn package(&self, manifest: ModManifest, backup_dir: Option<&Path>) -> Result<ModPackage> {
+        let actions = self
+            .translated_files(backup_dir)?
+            .into_iter()
+            .map(|(rel_path, content)| {
+                ChangeAction::new(rel_path, ChangeOp::FileReplace { content })
+                    .with_description("translation")
+            })
+            .collect();
+        Ok(ModPackage::new(manifest, ChangeLog::from_actions(actions)))
+    }
+}
+
+// ─── Revert ──────────────────────────────────────────────────────────────────
+
+/// Restore every file in `backup_dir` into `game_path`, keeping relative
+/// structure. Returns the number of files restored.
+pub fn revert(game_path: &Path, backup_dir: &Path) -> Result<usize> {
+    let files = walk_files(backup_dir);
+    for src in &files {
+        let dest = abs_path(game_path, &rel_string(backup_dir, src));
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(src, &dest).map_err(|source| LocalizationError::File {
+            path: dest.display().to_string(),
+            source,
+        })?;
+    }
+    Ok(files.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_maps() -> Vec<Map> {
+        vec![
+            Map {
+                id: 1,
+                map_filename: "cat1.map".into(),
+                map_name: "Town".into(),
+                ..Default::default()
+            },
+            Map {
+                id: 2,
+                map_filename: "dun01.map".into(),
+                map_name: "Cave".into(),
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn game_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        Map::save_file(&sample_maps(), &dir.path().join("AllMap.ini")).unwrap();
+        dir
+    }
+
+    fn translate(project: &mut LocalizationProject, original: &str, translation: &str) {
+        let entry = project
+            .entries
+            .iter_mut()
+            .find(|e| e.original == original)
+            .unwrap();
+        entry.translation = translation.into();
+    }
+
+    #[test]
+    fn scan_finds_map_names() {
+        let dir = game_dir();
+        let project = LocalizationProject::scan(dir.path()).unwrap();
+        let originals: Vec<_> = project
+            .entries
+            .iter()
+            .map(|e| e.original.as_str())
+            .collect();
+        assert!(originals.contains(&"Town"));
+        assert!(originals.contains(&"Cave"));
+        assert!(project.entries.iter().all(|e| e.file_path == "AllMap.ini"));
+    }
+
+    #[test]
+    fn session_round_trip() {
+        let dir = game_dir();
+        let mut project = LocalizationProject::scan(dir.path()).unwrap();
+        translate(&mut project, "Town", "Miasto");
+        let session = session_path(dir.path(), "my mod");
+        project.save_session(&session).unwrap();
+        assert!(session.ends_with("mods/my_mod/session.json"));
+
+        let mut fresh = LocalizationProject::scan(dir.path()).unwrap();
+        assert_eq!(fresh.merge_session_file(&session).unwrap(), 1);
+        assert_eq!(fresh.translated_count(), 1);
+    }
+
+    #[test]
+    fn apply_backs_up_and_revert_restores() {
+        let dir = game_dir();
+        let mut project = LocalizationProject::scan(dir.path()).unwrap();
+        translate(&mut project, "Cave", "Jaskinia");
+        let backup = backup_dir(dir.path(), "pl");
+
+        assert_eq!(project.apply(&backup).unwrap(), vec!["AllMap.ini"]);
+        let maps = Map::read_file(&dir.path().join("AllMap.ini")).unwrap();
+        assert_eq!(maps[1].map_name, "Jaskinia");
+        assert!(backup.join("AllMap.ini").exists());
+
+        // A re-scan sees translated text; the backup still holds the original.
+        let rescanned = LocalizationProject::scan(dir.path()).unwrap();
+        assert!(rescanned.entries.iter().any(|e| e.original == "Jaskinia"));
+
+        assert_eq!(revert(dir.path(), &backup).unwrap(), 1);
+        let maps = Map::read_file(&dir.path().join("AllMap.ini")).unwrap();
+        assert_eq!(maps[1].map_name, "Cave");
+    }
+
+    #[test]
+    fn package_leaves_game_files_untouched() {
+        let dir = game_dir();
+        let mut project = LocalizationProject::scan(dir.path()).unwrap();
+        assert!(matches!(
+            project.package(ModManifest::new("pl"), None),
+            Err(LocalizationError::NothingToApply)
+        ));
+
+        translate(&mut project, "Town", "Miasto");
+        let before = std::fs::read(dir.path().join("AllMap.ini")).unwrap();
+        let package = project.package(ModManifest::new("pl"), None).unwrap();
+        assert_eq!(
+            before,
+            std::fs::read(dir.path().join("AllMap.ini")).unwrap()
+        );
+
+        let actions = package.changes.actions();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].file_path, "AllMap.ini");
+        let ChangeOp::FileReplace { content } = &actions[0].op else {
+            panic!("expected FileReplace");
+        };
+        let maps = Map::parse(&mut Cursor::new(content), content.len() as u64).unwrap();
+        assert_eq!(maps[0].map_name, "Miasto");
+        assert_eq!(maps[1].map_name, "Cave");
+    }
+
+    #[test]
+    fn lossy_translations_are_refused_unless_allowed() {
+        let dir = game_dir();
+        let mut project = LocalizationProject::scan(dir.path()).unwrap();
+        translate(&mut project, "Town", "Город");
+        assert!(matches!(
+            project.translated_files(None),
+            Err(LocalizationError::LossyEncoding { count: 1, .. })
+        ));
+        project.allow_lossy = true;
+        assert_eq!(project.translated_files(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn unreviewed_translations_are_skipped_unless_included() {
+        let dir = game_dir();
+        let mut project = LocalizationProject::scan(dir.path()).unwrap();
+        translate(&mut project, "Town", "Miasto");
+        translate(&mut project, "Cave", "Jaskinka");
+        project
+            .entries
+            .iter_mut()
+            .find(|e| e.original == "Cave")
+            .unwrap()
+            .needs_review = true;
+        assert_eq!(project.unreviewed_count(), 1);
+
+        let session = session_path(dir.path(), "pl");
+        project.save_session(&session).unwrap();
+        let mut fresh = LocalizationProject::scan(dir.path()).unwrap();
+        fresh.merge_session_file(&session).unwrap();
+        assert_eq!(fresh.unreviewed_count(), 1);
+
+        let read_back = |project: &LocalizationProject| {
+            let files = project.translated_files(None).unwrap();
+            let content = &files[0].1;
+            Map::parse(&mut Cursor::new(content), content.len() as u64).unwrap()
+        };
+        let maps = read_back(&fresh);
+        assert_eq!(maps[0].map_name, "Miasto");
+        assert_eq!(maps[1].map_name, "Cave");
+
+        fresh.include_unreviewed = true;
+        assert_eq!(read_back(&fresh)[1].map_name, "Jaskinka");
+    }
+
+    #[test]
+    fn transliteration_table_runs_before_lossy_check() {
+        let dir = game_dir();
+        let mut project = LocalizationProject::scan(dir.path()).unwrap();
+        translate(&mut project, "Town", "Город");
+        project.transliteration = Some(TransliterationTable::cyrillic());
+        assert_eq!(project.lossy_entries().count(), 0);
+
+        let files = project.translated_files(None).unwrap();
+        let content = &files[0].1;
+        let maps = Map::parse(&mut Cursor::new(content), content.len() as u64).unwrap();
+        assert_eq!(maps[0].map_name, "Gorod");
+        // The session keeps the translator's text.
+        assert_eq!(
+            project
+                .entries
+                .iter()
+                .find(|e| e.original == "Town")
+                .unwrap()
+                .translation,
+            "Город"
+        );
+    }
+
+    #[test]
+    fn pgp_scan_skips_tool_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        for rel in ["NpcInGame/a.pgp", "mods/x/backup/NpcInGame/a.pgp", "b.txt"] {
+            let p = abs_path(dir.path(), rel);
+            std::fs::create_dir_all(p.parent().unwrap()).unwrap();
+            std::fs::write(p, b"").unwrap();
+        }
+        assert_eq!(pgp_files(dir.path()), vec!["NpcInGame/a.pgp"]);
+    }
+}
@@ -0,0 +1,363 @@
+//! Encoding-aware transliteration and lossy-character reporting.
+//!
+//! `encoding_rs` silently replaces characters the target code page cannot
+//! represent (Cyrillic in WINDOWS-1250, many symbols in EUC-KR). These
+//! helpers find such characters per entry and rewrite them through a
+//! [`TransliterationTable`] before anything is encoded. Only unrepresentable
+//! characters are rewritten; text the encoding can hold is left untouched.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::project::{LocalizationError, Result};
+use super::{TextEncoding, TextEntry, encoding_rs_for};
+
+/// Whether `c` survives a round trip through `enc`.
+pub fn is_representable(c: char, enc: &TextEncoding) -> bool {
+    if matches!(enc, TextEncoding::Utf8) {
+        return true;
+    }
+    let mut buf = [0u8; 4];
+    let (_, _, had_errors) = encoding_rs_for(enc).encode(c.encode_utf8(&mut buf));
+    !had_errors
+}
+
+/// Distinct characters of `s` that `enc` cannot represent, in order of first
+/// appearance.
+pub fn unrepresentable_chars(s: &str, enc: &TextEncoding) -> Vec<char> {
+    let mut out: Vec<char> = Vec::new();
+    for c in s.chars() {
+        if !out.contains(&c) && !is_representable(c, enc) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// ─── Tables ──────────────────────────────────────────────────────────────────
+
+/// Punctuation commonly pasted from word processors.
+const PUNCTUATION: &[(char, &str)] = &[
+    ('\u{2018}', "'"),
+    ('\u{2019}', "'"),
+    ('\u{201A}', ","),
+    ('\u{201B}', "'"),
+    ('\u{201C}', "\""),
+    ('\u{201D}', "\""),
+    ('\u{201E}', "\""),
+    ('\u{201F}', "\""),
+    ('\u{00AB}', "\""),
+    ('\u{00BB}', "\""),
+    ('\u{2039}', "'"),
+    ('\u{203A}', "'"),
+    ('\u{2010}', "-"),
+    ('\u{2011}', "-"),
+    ('\u{2012}', "-"),
+    ('\u{2013}', "-"),
+    ('\u{2014}', "-"),
+    ('\u{2015}', "-"),
+    ('\u{2212}', "-"),
+    ('\u{2026}', "..."),
+    ('\u{00A0}', " "),
+    ('\u{2002}', " "),
+    ('\u{2003}', " "),
+    ('\u{2009}', " "),
+    ('\u{200B}', ""),
+    ('\u{2022}', "*"),
+    ('\u{2122}', "TM"),
+];
+
+/// Russian/Ukrainian Cyrillic to Latin, roughly following ISO 9 / BGN.
+const CYRILLIC: &[(char, &str)] = &[
+    ('А', "A"),
+    ('Б', "B"),
+    ('В', "V"),
+    ('Г', "G"),
+    ('Д', "D"),
+    ('Е', "E"),
+    ('Ё', "Yo"),
+    ('Ж', "Zh"),
+    ('З', "Z"),
+    ('И', "I"),
+    ('Й', "Y"),
+    ('К', "K"),
+    ('Л', "L"),
+    ('М', "M"),
+    ('Н', "N"),
+    ('О', "O"),
+    ('П', "P"),
+    ('Р', "R"),
+    ('С', "S"),
+    ('Т', "T"),
+    ('У', "U"),
+    ('Ф', "F"),
+    ('Х', "Kh"),
+    ('Ц', "Ts"),
+    ('Ч', "Ch"),
+    ('Ш', "Sh"),
+    ('Щ', "Shch"),
+    ('Ъ', ""),
+    ('Ы', "Y"),
+    ('Ь', ""),
+    ('Э', "E"),
+    ('Ю', "Yu"),
+    ('Я', "Ya"),
+    ('Є', "Ye"),
+    ('І', "I"),
+    ('Ї', "Yi"),
+    ('Ґ', "G"),
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('д', "d"),
+    ('е', "e"),
+    ('ё', "yo"),
+    ('ж', "zh"),
+    ('з', "z"),
+    ('и', "i"),
+    ('й', "y"),
+    ('к', "k"),
+    ('л', "l"),
+    ('м', "m"),
+    ('н', "n"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('у', "u"),
+    ('ф', "f"),
+    ('х', "kh"),
+    ('ц', "ts"),
+    ('ч', "ch"),
+    ('ш', "sh"),
+    ('щ', "shch"),
+    ('ъ', ""),
+    ('ы', "y"),
+    ('ь', ""),
+    ('э', "e"),
+    ('ю', "yu"),
+    ('я', "ya"),
+    ('є', "ye"),
+    ('і', "i"),
+    ('ї', "yi"),
+    ('ґ', "g"),
+];
+
+/// Character → replacement map applied to unrepresentable characters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransliterationTable {
+    map: BTreeMap<char, String>,
+}
+
+impl TransliterationTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Smart quotes, dashes, ellipsis and special spaces to ASCII.
+    pub fn punctuation() -> Self {
+        Self::from_pairs(PUNCTUATION)
+    }
+
+    /// Cyrillic to Latin.
+    pub fn cyrillic() -> Self {
+        Self::from_pairs(CYRILLIC)
+    }
+
+    /// [`punctuation`](Self::punctuation) plus [`cyrillic`](Self::cyrillic).
+    pub fn builtin() -> Self {
+        let mut t = Self::punctuation();
+        t.merge(&Self::cyrillic());
+        t
+    }
+
+    fn from_pairs(pairs: &[(char, &str)]) -> Self {
+        let mut t = Self::new();
+        for &(c, r) in pairs {
+            t.insert(c, r);
+        }
+        t
+    }
+
+    /// Parse a two-column `from,to` CSV (header row required). `from` must be
+    /// exactly one character; `to` may be empty to drop the character.
+    pub fn from_csv(csv: &str) -> Result<Self> {
+        let mut rdr = csv::Reader::from_reader(csv.as_bytes());
+        let mut t = Self::new();
+        for (line, record) in rdr.records().enumerate() {
+            let record = record?;
+            let from = record.get(0).unwrap_or("");
+            let mut chars = from.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return Err(LocalizationError::InvalidTable(format!(
+                    "row {}: expected a single character, got {from:?}",
+                    line + 2
+                )));
+            };
+            t.insert(c, record.get(1).unwrap_or(""));
+        }
+        Ok(t)
+    }
+
+    pub fn insert(&mut self, from: char, to: &str) {
+        self.map.insert(from, to.to_owned());
+    }
+
+    /// Add every mapping of `other`, replacing existing ones.
+    pub fn merge(&mut self, other: &TransliterationTable) {
+        for (c, r) in &other.map {
+            self.map.insert(*c, r.clone());
+        }
+    }
+
+    pub fn get(&self, c: char) -> Option<&str> {
+        self.map.get(&c).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Rewrite the characters of `s` that `enc` cannot represent and that
+    /// have a mapping. Everything else is copied unchanged.
+    pub fn apply(&self, s: &str, enc: &TextEncoding) -> String {
+        let mut out = String::with_capacity(s.len());
+        for c in s.chars() {
+            match self.map.get(&c) {
+                Some(r) if !is_representable(c, enc) => out.push_str(r),
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+}
+
+// ─── Entry reports ───────────────────────────────────────────────────────────
+
+/// Encoding problems of one translated entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EncodingIssue {
+    /// Index into the checked entries slice.
+    pub index: usize,
+    pub file_path: String,
+    pub record_id: usize,
+    pub field_name: String,
+    pub encoding: &'static str,
+    /// Characters the table would rewrite.
+    pub transliterable: Vec<char>,
+    /// Characters that stay lossy even after transliteration.
+    pub lossy: Vec<char>,
+}
+
+/// Report unrepresentable characters of every translated entry, split into
+/// those `table` can fix and those it cannot.
+pub fn encoding_report(entries: &[TextEntry], table: &TransliterationTable) -> Vec<EncodingIssue> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.is_translated())
+        .filter_map(|(index, e)| {
+            let bad = unrepresentable_chars(&e.translation, &e.encoding);
+            if bad.is_empty() {
+                return None;
+            }
+            let (transliterable, lossy) = bad.into_iter().partition(|c| table.get(*c).is_some());
+            Some(EncodingIssue {
+                index,
+                file_path: e.file_path.clone(),
+                record_id: e.record_id,
+                field_name: e.field_name.to_owned(),
+                encoding: e.encoding.label(),
+                transliterable,
+                lossy,
+            })
+        })
+        .collect()
+}
+
+/// Apply `table` to every translation. Returns the number of entries changed.
+pub fn transliterate_entries(entries: &mut [TextEntry], table: &TransliterationTable) -> usize {
+    let mut changed = 0;
+    for e in entries.iter_mut().filter(|e| e.is_translated()) {
+        let fixed = table.apply(&e.translation, &e.encoding);
+        if fixed != e.translation {
+            e.translation = fixed;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(translation: &str, encoding: TextEncoding) -> TextEntry {
+        TextEntry {
+            file_path: "x".into(),
+            record_id: 0,
+            field_name: "text",
+            original: "orig".into(),
+            translation: translation.into(),
+            encoding,
+            max_bytes: 100,
+            needs_review: false,
+        }
+    }
+
+    #[test]
+    fn detects_cyrillic_in_windows_1250() {
+        assert!(is_representable('ż', &TextEncoding::Windows1250));
+        assert!(!is_representable('Ж', &TextEncoding::Windows1250));
+        assert_eq!(
+            unrepresentable_chars("Żółw Жук Жук", &TextEncoding::Windows1250),
+            vec!['Ж', 'у', 'к']
+        );
+        assert!(unrepresentable_chars("Жук", &TextEncoding::Utf8).is_empty());
+    }
+
+    #[test]
+    fn only_unrepresentable_chars_are_rewritten() {
+        let table = TransliterationTable::builtin();
+        // Curly quotes exist in WINDOWS-1250 and are kept; Cyrillic is not.
+        assert_eq!(table.apply("„Жук”", &TextEncoding::Windows1250), "„Zhuk”");
+        assert_eq!(table.apply("a\u{2009}b", &TextEncoding::Windows1250), "a b");
+    }
+
+    #[test]
+    fn report_splits_fixable_and_lossy() {
+        let entries = vec![
+            entry("Привет ☃", TextEncoding::Windows1250),
+            entry("Cześć", TextEncoding::Windows1250),
+            entry("", TextEncoding::EucKr),
+        ];
+        let report = encoding_report(&entries, &TransliterationTable::builtin());
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].lossy, vec!['☃']);
+        assert_eq!(report[0].transliterable.len(), 6);
+    }
+
+    #[test]
+    fn transliterate_entries_updates_translations() {
+        let mut entries = vec![entry("Да", TextEncoding::Windows1250)];
+        assert_eq!(
+            transliterate_entries(&mut entries, &TransliterationTable::cyrillic()),
+            1
+        );
+        assert_eq!(entries[0].translation, "Da");
+    }
+
+    #[test]
+    fn csv_table() {
+        let t = TransliterationTable::from_csv("from,to\n☃,snowman\n").unwrap();
+        assert_eq!(t.get('☃'), Some("snowman"));
+        assert!(TransliterationTable::from_csv("from,to\nab,x\n").is_err());
+    }
+}
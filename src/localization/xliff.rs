@@ -0,0 +1,491 @@
+//! XLIFF 2.0 export/import for CAT tools.
+//!
+//! One `<file>` element is written per (game file, encoding) pair so the
+//! encoding can travel as the Size and Length Restriction module's
+//! `storageProfile`; each entry becomes a `<unit>` whose
+//! `slr:storageRestriction` carries `max_bytes`. Unit ids encode the record
+//! id and field name (`r<record_id>_<field_name>`), which is what import
+//! matches on together with the file's `original` attribute.
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use super::TextEntry;
+
+pub const XLIFF_NS: &str = "urn:oasis:names:tc:xliff:document:2.0";
+pub const SLR_NS: &str = "urn:oasis:names:tc:xliff:sizerestriction:2.0";
+
+#[derive(Debug, Error)]
+pub enum XliffError {
+    #[error("malformed XLIFF: {0}")]
+    Malformed(String),
+
+    #[error("unsupported XLIFF version {0:?} (expected 2.x)")]
+    UnsupportedVersion(String),
+}
+
+// ─── Export ──────────────────────────────────────────────────────────────────
+
+/// Export entries to an XLIFF 2.0 document.
+pub fn export_xliff(entries: &[TextEntry], source_lang: &str, target_lang: &str) -> String {
+    // Group by (file, encoding) keeping first-seen order.
+    let mut groups: Vec<(&str, &'static str, Vec<&TextEntry>)> = Vec::new();
+    let mut group_idx: HashMap<(&str, &'static str), usize> = HashMap::new();
+    for e in entries {
+        let key = (e.file_path.as_str(), e.encoding.label());
+        let idx = *group_idx.entry(key).or_insert_with(|| {
+            groups.push((key.0, key.1, Vec::new()));
+            groups.len() - 1
+        });
+        groups[idx].2.push(e);
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<xliff xmlns=\"{XLIFF_NS}\" xmlns:slr=\"{SLR_NS}\" version=\"2.0\" srcLang=\"{}\" trgLang=\"{}\">\n",
+        xml_escape(source_lang),
+        xml_escape(target_lang)
+    ));
+    for (i, (file_path, encoding, file_entries)) in groups.iter().enumerate() {
+        out.push_str(&format!(
+            "  <file id=\"f{}\" original=\"{}\">\n",
+            i + 1,
+            xml_escape(file_path)
+        ));
+        out.push_str(&format!(
+            "    <slr:profiles generalProfile=\"xliff:codepoints\" storageProfile=\"{encoding}\"/>\n"
+        ));
+        for e in file_entries {
+            out.push_str(&format!(
+                "    <unit id=\"{}\" name=\"{}\" slr:storageRestriction=\"{}\" xml:space=\"preserve\">\n",
+                unit_id(e.record_id, e.field_name),
+                e.field_name,
+                e.max_bytes
+            ));
+            // Unreviewed fuzzy fills keep their target but stay "initial".
+            let state = if e.is_translated() && !e.needs_review {
+                "translated"
+            } else {
+                "initial"
+            };
+            out.push_str(&format!("      <segment state=\"{state}\">\n"));
+            out.push_str(&format!(
+                "        <source>{}</source>\n",
+                xml_escape(&e.original)
+            ));
+            if e.is_translated() {
+                out.push_str(&format!(
+                    "        <target>{}</target>\n",
+                    xml_escape(&e.translation)
+                ));
+            }
+            out.push_str("      </segment>\n");
+            out.push_str("    </unit>\n");
+        }
+        out.push_str("  </file>\n");
+    }
+    out.push_str("</xliff>\n");
+    out
+}
+
+fn unit_id(record_id: usize, field_name: &str) -> String {
+    format!("r{record_id}_{field_name}")
+}
+
+fn parse_unit_id(id: &str) -> Option<(usize, &str)> {
+    let (record, field) = id.strip_prefix('r')?.split_once('_')?;
+    Some((record.parse().ok()?, field))
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters other than tab/newline are not allowed in XML 1.0.
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// ─── Import ──────────────────────────────────────────────────────────────────
+
+/// Import translations from an XLIFF 2.0 document; matches units by
+/// (`<file original>`, `<unit id>`). Multi-segment units are joined in order.
+/// Units without a non-empty `<target>` are skipped; a target in a segment
+/// with `state="initial"` marks the entry `needs_review`. Returns the number
+/// of entries updated.
+pub fn import_xliff(xml: &str, entries: &mut [TextEntry]) -> Result<usize, XliffError> {
+    let mut index: HashMap<(&str, usize, &str), Vec<usize>> = HashMap::new();
+    for (i, e) in entries.iter().enumerate() {
+        index
+            .entry((e.file_path.as_str(), e.record_id, e.field_name))
+            .or_default()
+            .push(i);
+    }
+
+    let mut updates: Vec<(usize, String, bool)> = Vec::new();
+    let mut current_file: Option<String> = None;
+    let mut current_unit: Option<String> = None;
+    let mut target: Option<String> = None;
+    let mut initial = false;
+    let mut in_target = 0usize;
+    let mut saw_root = false;
+
+    for token in Tokenizer::new(xml) {
+        match token? {
+            Token::Start {
+                name,
+                attrs,
+                self_closing,
+            } => match local_name(name) {
+                "xliff" => {
+                    saw_root = true;
+                    let version = attr(&attrs, "version").unwrap_or_default();
+                    if !version.starts_with("2.") {
+                        return Err(XliffError::UnsupportedVersion(version.to_owned()));
+                    }
+                }
+                "file" => current_file = attr(&attrs, "original").map(str::to_owned),
+                "unit" => {
+                    current_unit = attr(&attrs, "id").map(str::to_owned);
+                    target = None;
+                    initial = false;
+                }
+                "segment" if current_unit.is_some() => {
+                    initial |= attr(&attrs, "state") == Some("initial");
+                }
+                "target" if current_unit.is_some() && !self_closing => {
+                    in_target += 1;
+                    target.get_or_insert_with(String::new);
+                }
+                _ => {}
+            },
+            Token::End { name } => match local_name(name) {
+                "target" if in_target > 0 => in_target -= 1,
+                "unit" => {
+                    if let (Some(file), Some(unit), Some(text)) =
+                        (&current_file, current_unit.take(), target.take())
+                        && !text.is_empty()
+                        && let Some((record_id, field)) = parse_unit_id(&unit)
+                        && let Some(idxs) = index.get(&(file.as_str(), record_id, field))
+                    {
+                        updates.extend(idxs.iter().map(|&i| (i, text.clone(), initial)));
+                    }
+                }
+                "file" => current_file = None,
+                _ => {}
+            },
+            Token::Text(text) => {
+                if in_target > 0
+                    && let Some(t) = target.as_mut()
+                {
+                    t.push_str(&text);
+                }
+            }
+        }
+    }
+
+    if !saw_root {
+        return Err(XliffError::Malformed("missing <xliff> root element".into()));
+    }
+    let updated = updates.len();
+    for (i, text, needs_review) in updates {
+        entries[i].translation = text;
+        entries[i].needs_review = needs_review;
+    }
+    Ok(updated)
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn attr<'a>(attrs: &'a [(&str, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| *k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+// ─── Minimal XML tokenizer ───────────────────────────────────────────────────
+//
+// Just enough XML to read back what CAT tools write: elements, attributes,
+// text with entity references, CDATA, comments and processing instructions.
+
+enum Token<'a> {
+    Start {
+        name: &'a str,
+        attrs: Vec<(&'a str, String)>,
+        self_closing: bool,
+    },
+    End {
+        name: &'a str,
+    },
+    Text(String),
+}
+
+struct Tokenizer<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    fn skip_past(&mut self, pat: &str) -> Result<&'a str, XliffError> {
+        let rest = &self.src[self.pos..];
+        let end = rest
+            .find(pat)
+            .ok_or_else(|| XliffError::Malformed(format!("unterminated markup, expected {pat}")))?;
+        self.pos += end + pat.len();
+        Ok(&rest[..end])
+    }
+
+    /// Consume a start or end tag up to its closing `>`, skipping any `>`
+    /// inside quoted attribute values. Returns the text between `<` and `>`.
+    fn tag_body(&mut self) -> Result<&'a str, XliffError> {
+        let rest = &self.src[self.pos..];
+        let mut quote = None;
+        for (i, c) in rest.char_indices().skip(1) {
+            match (quote, c) {
+                (None, '"' | '\'') => quote = Some(c),
+                (Some(q), c) if c == q => quote = None,
+                (None, '>') => {
+                    self.pos += i + 1;
+                    return Ok(&rest[1..i]);
+                }
+                _ => {}
+            }
+        }
+        Err(XliffError::Malformed("unterminated tag".into()))
+    }
+
+    fn tag(&mut self) -> Result<Token<'a>, XliffError> {
+        let body = self.tag_body()?;
+        if let Some(name) = body.strip_prefix('/') {
+            return Ok(Token::End { name: name.trim() });
+        }
+        let (body, self_closing) = match body.strip_suffix('/') {
+            Some(b) => (b, true),
+            None => (body, false),
+        };
+        let name_end = body.find(|c: char| c.is_whitespace()).unwrap_or(body.len());
+        let name = &body[..name_end];
+        let mut attrs = Vec::new();
+        let mut rest = body[name_end..].trim_start();
+        while !rest.is_empty() {
+            let eq = rest
+                .find('=')
+                .ok_or_else(|| XliffError::Malformed(format!("bad attribute in <{name}>")))?;
+            let key = rest[..eq].trim();
+            let after = rest[eq + 1..].trim_start();
+            let quote = after
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')
+                .ok_or_else(|| XliffError::Malformed(format!("unquoted attribute {key}")))?;
+            let close = after[1..]
+                .find(quote)
+                .ok_or_else(|| XliffError::Malformed(format!("unterminated attribute {key}")))?;
+            attrs.push((key, unescape(&after[1..1 + close])?));
+            rest = after[close + 2..].trim_start();
+        }
+        Ok(Token::Start {
+            name,
+            attrs,
+            self_closing,
+        })
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, XliffError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = &self.src[self.pos..];
+            if rest.is_empty() {
+                return None;
+            }
+            if !rest.starts_with('<') {
+                let end = rest.find('<').unwrap_or(rest.len());
+                self.pos += end;
+                return Some(unescape(&rest[..end]).map(Token::Text));
+            }
+            let skipped = if rest.starts_with("<?") {
+                self.skip_past("?>")
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                return Some(self.skip_past("]]>").map(|t| Token::Text(t.to_owned())));
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")
+            } else {
+                return Some(self.tag());
+            };
+            if let Err(e) = skipped {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+fn unescape(s: &str) -> Result<String, XliffError> {
+    if !s.contains('&') {
+        return Ok(s.to_owned());
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let semi = rest[amp..]
+            .find(';')
+            .ok_or_else(|| XliffError::Malformed("unterminated entity".into()))?;
+        let entity = &rest[amp + 1..amp + semi];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| XliffError::Malformed(format!("unknown entity &{entity};")))?
+            }
+        };
+        out.push(c);
+        rest = &rest[amp + semi + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::localization::TextEncoding;
+
+    fn entries() -> Vec<TextEntry> {
+        vec![
+            TextEntry {
+                file_path: "CharacterInGame/weaponItem.db".into(),
+                record_id: 3,
+                field_name: "name",
+                original: "Sword & <Shield>".into(),
+                translation: "Miecz i \"Tarcza\"".into(),
+                encoding: TextEncoding::Windows1250,
+                max_bytes: 30,
+                needs_review: false,
+            },
+            TextEntry {
+                file_path: "NpcInGame/Pgpcat1.pgp".into(),
+                record_id: 0,
+                field_name: "text",
+                original: "첫째 줄\n둘째 줄".into(),
+                translation: String::new(),
+                encoding: TextEncoding::EucKr,
+                max_bytes: 1024,
+                needs_review: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn export_carries_size_restrictions() {
+        let xml = export_xliff(&entries(), "ko", "pl");
+        assert!(xml.contains("version=\"2.0\" srcLang=\"ko\" trgLang=\"pl\""));
+        assert!(xml.contains("storageProfile=\"WINDOWS-1250\""));
+        assert!(xml.contains("storageProfile=\"EUC-KR\""));
+        assert!(xml.contains("<unit id=\"r3_name\" name=\"name\" slr:storageRestriction=\"30\""));
+        assert!(xml.contains("<source>Sword &amp; &lt;Shield&gt;</source>"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let xml = export_xliff(&entries(), "ko", "pl");
+        let mut fresh = entries();
+        fresh[0].translation.clear();
+        assert_eq!(import_xliff(&xml, &mut fresh).unwrap(), 1);
+        assert_eq!(fresh[0].translation, "Miecz i \"Tarcza\"");
+        assert!(fresh[1].translation.is_empty());
+    }
+
+    #[test]
+    fn unreviewed_entries_round_trip_as_initial() {
+        let mut e = entries();
+        e[0].needs_review = true;
+        let xml = export_xliff(&e, "ko", "pl");
+        assert!(xml.contains("<segment state=\"initial\">\n        <source>Sword"));
+        assert!(xml.contains("<target>Miecz i &quot;Tarcza&quot;</target>"));
+
+        let mut fresh = entries();
+        fresh[0].translation.clear();
+        assert_eq!(import_xliff(&xml, &mut fresh).unwrap(), 1);
+        assert!(fresh[0].needs_review);
+
+        let reviewed = xml.replace("state=\"initial\"", "state=\"reviewed\"");
+        import_xliff(&reviewed, &mut fresh).unwrap();
+        assert!(!fresh[0].needs_review);
+    }
+
+    #[test]
+    fn import_joins_segments_and_reads_cdata() {
+        let xml = r#"<?xml version="1.0"?>
+<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.1" srcLang="ko">
+  <file id="x" original="NpcInGame/Pgpcat1.pgp">
+    <unit id="r0_text">
+      <segment><source>a</source><target>Pierwsza linia</target></segment>
+      <ignorable><source>
+</source><target>&#10;</target></ignorable>
+      <segment><source>b</source><target><![CDATA[Druga <linia>]]></target></segment>
+    </unit>
+  </file>
+</xliff>"#;
+        let mut e = entries();
+        assert_eq!(import_xliff(xml, &mut e).unwrap(), 1);
+        assert_eq!(e[1].translation, "Pierwsza linia\nDruga <linia>");
+    }
+
+    #[test]
+    fn import_allows_angle_brackets_in_attributes() {
+        let xml = r#"<xliff version="2.0">
+  <file id="f1" original="NpcInGame/Pgpcat1.pgp">
+    <unit id="r0_text" name='a > b'>
+      <notes><note category="<source> hint">x > y</note></notes>
+      <segment><source>a</source><target>Tekst</target></segment>
+    </unit>
+  </file>
+</xliff>"#;
+        let mut e = entries();
+        assert_eq!(import_xliff(xml, &mut e).unwrap(), 1);
+        assert_eq!(e[1].translation, "Tekst");
+    }
+
+    #[test]
+    fn rejects_xliff_1() {
+        let xml = r#"<xliff version="1.2"><file original="a"/></xliff>"#;
+        assert!(matches!(
+            import_xliff(xml, &mut entries()),
+            Err(XliffError::UnsupportedVersion(_))
+        ));
+    }
+}
@@ -6,9 +6,14 @@ use cli::{Cli, Commands};
 use commands::Command;
 use commands::database::DatabaseCommand;
 use commands::dialog::DialogCommand;
+use commands::l10n::L10nCommand;
 use commands::list::ListCommand;
 use commands::map::MapCommand;
 use commands::pack::ModPackCommand;
+use commands::refs::RefsCommand;
+use commands::renumber::RenumberCommand;
+use commands::report::ReportCommand;
+use commands::save::SaveCommand;
 use commands::schema::SchemaCommand;
 use commands::sound::SoundCommand;
 use commands::sprite::SpriteCommand;
@@ -36,6 +41,20 @@ fn main() {
             command: command.clone(),
         }
         .execute(),
+        Some(Commands::Save { command }) => SaveCommand {
+            command: command.clone(),
+        }
+        .execute(),
+        Some(Commands::Refs(args)) => RefsCommand { args: args.clone() }.execute(),
+        Some(Commands::Renumber(args)) => RenumberCommand { args: args.clone() }.execute(),
+        Some(Commands::Report { command }) => ReportCommand {
+            command: command.clone(),
+        }
+        .execute(),
+        Some(Commands::L10n { command }) => L10nCommand {
+            command: command.clone(),
+        }
+        .execute(),
         Some(Commands::Dialog {
             dlg_path,
             pgp_path,
@@ -0,0 +1,424 @@
+//! World atlas – batch rendering of every map in the game.
+//!
+//! Discovers all maps through `AllMap.ini` (the master index) joined with
+//! `Ref/Map.ini` (per-map placement files), resolves each map's `.map`,
+//! `.gtl` and `.btl` files under `Map/`, renders them with entity overlays and
+//! writes an `index.json` + `index.html` describing the whole world.
+//!
+//! Maps are rendered in parallel on a fixed pool of scoped worker threads;
+//! each worker pulls the next map index from a shared counter so long maps do
+//! not hold back the rest of the queue.
+
+use std::fs::File;
+use std::io::{BufReader, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::references::all_map_ini::{Map, read_all_map_ini};
+use crate::references::enums::MapLighting;
+use crate::references::map_ini::{MapIni, read_map_ini};
+
+use super::read_map_data;
+use super::render::{LayerToggles, MapRenderConfig, render_map};
+use super::tileset;
+
+// --------------------------------------------------------------------------
+// Discovery
+// --------------------------------------------------------------------------
+
+/// A map found in `AllMap.ini`, with its resolved asset paths.
+#[derive(Debug, Clone)]
+pub struct AtlasMap {
+    /// Entry from `AllMap.ini`.
+    pub map: Map,
+    /// Matching `Ref/Map.ini` entry (joined on `id`), if any.
+    pub map_ini: Option<MapIni>,
+    pub map_path: PathBuf,
+    pub gtl_path: PathBuf,
+    pub btl_path: PathBuf,
+}
+
+/// Resolves `<game_path>/<dir>/<name>` trying the exact, upper- and
+/// lower-case spellings, since the shipped game mixes them freely.
+pub(crate) fn resolve_case_insensitive(game_path: &Path, dir: &str, name: &str) -> PathBuf {
+    let dir = game_path.join(dir);
+    for candidate in [
+        name.to_string(),
+        name.to_ascii_lowercase(),
+        name.to_ascii_uppercase(),
+    ] {
+        let p = dir.join(&candidate);
+        if p.exists() {
+            return p;
+        }
+    }
+    dir.join(name)
+}
+
+/// Reads `AllMap.ini` and `Ref/Map.ini` from `game_path` and resolves the
+/// `.map`, `.gtl` and `.btl` paths of every listed map.
+///
+/// Paths are resolved but not checked; maps with missing files are reported
+/// as failed entries by [`render_all`].
+pub fn discover_maps(game_path: &Path) -> Result<Vec<AtlasMap>> {
+    let all_maps = read_all_map_ini(&game_path.join("AllMap.ini"))?;
+    let map_inis = read_map_ini(&game_path.join("Ref").join("Map.ini")).unwrap_or_default();
+
+    Ok(all_maps
+        .into_iter()
+        .map(|map| {
+            let map_ini = map_inis.iter().find(|ini| ini.id == map.id).cloned();
+            let stem = &map.map_filename;
+            AtlasMap {
+                map_path: resolve_case_insensitive(game_path, "Map", &format!("{stem}.map")),
+                gtl_path: resolve_case_insensitive(game_path, "Map", &format!("{stem}.gtl")),
+                btl_path: resolve_case_insensitive(game_path, "Map", &format!("{stem}.btl")),
+                map,
+                map_ini,
+            }
+        })
+        .collect())
+}
+
+// --------------------------------------------------------------------------
+// Index model
+// --------------------------------------------------------------------------
+
+/// One row of the world atlas index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtlasIndexEntry {
+    pub id: i32,
+    pub map_filename: String,
+    pub map_name: String,
+    pub lighting: MapLighting,
+    /// Map size in tiles.
+    pub tiled_width: i32,
+    pub tiled_height: i32,
+    /// Rendered PNG size in pixels.
+    pub image_width: i32,
+    pub image_height: i32,
+    /// PNG filename, relative to the index directory.
+    pub image: Option<String>,
+    pub start_pos: Option<(i32, i32)>,
+    pub monsters_filename: Option<String>,
+    pub npc_filename: Option<String>,
+    pub extra_filename: Option<String>,
+    /// Why the map could not be rendered, if it failed.
+    pub error: Option<String>,
+}
+
+/// The full world atlas written to `index.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtlasIndex {
+    pub maps: Vec<AtlasIndexEntry>,
+}
+
+// --------------------------------------------------------------------------
+// Rendering
+// --------------------------------------------------------------------------
+
+/// Configuration for [`render_all`].
+pub struct RenderAllConfig<'a> {
+    pub game_path: &'a Path,
+    pub output_dir: &'a Path,
+    pub toggles: LayerToggles,
+    /// Number of worker threads; `0` uses all available CPU cores.
+    pub jobs: usize,
+}
+
+/// Renders every map listed in `AllMap.ini` into `output_dir` and writes
+/// `index.json` and `index.html` next to the PNGs.
+///
+/// A map that fails to render does not abort the batch; its index entry
+/// carries the error instead of an image link.
+pub fn render_all(config: RenderAllConfig) -> Result<AtlasIndex> {
+    let RenderAllConfig {
+        game_path,
+        output_dir,
+        toggles,
+        jobs,
+    } = config;
+
+    std::fs::create_dir_all(output_dir)?;
+    let maps = discover_maps(game_path)?;
+
+    let workers = if jobs == 0 {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    } else {
+        jobs
+    }
+    .clamp(1, maps.len().max(1));
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<AtlasIndexEntry>>> = Mutex::new(vec![None; maps.len()]);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(atlas_map) = maps.get(i) else {
+                        break;
+                    };
+                    eprintln!(
+                        "[{}/{}] Rendering {} ({})...",
+                        i + 1,
+                        maps.len(),
+                        atlas_map.map.map_filename,
+                        atlas_map.map.map_name
+                    );
+                    let entry = render_one(atlas_map, game_path, output_dir, toggles);
+                    if let Some(err) = &entry.error {
+                        eprintln!("  {}: {err}", atlas_map.map.map_filename);
+                    }
+                    results.lock().unwrap()[i] = Some(entry);
+                }
+            });
+        }
+    });
+
+    let index = AtlasIndex {
+        maps: results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect(),
+    };
+
+    let json = serde_json::to_string_pretty(&index).map_err(std::io::Error::other)?;
+    std::fs::write(output_dir.join("index.json"), json)?;
+    std::fs::write(output_dir.join("index.html"), index_to_html(&index))?;
+
+    Ok(index)
+}
+
+/// Renders a single map, turning any failure into an index entry error.
+fn render_one(
+    atlas_map: &AtlasMap,
+    game_path: &Path,
+    output_dir: &Path,
+    toggles: LayerToggles,
+) -> AtlasIndexEntry {
+    let map = &atlas_map.map;
+    let ini = atlas_map.map_ini.as_ref();
+    let mut entry = AtlasIndexEntry {
+        id: map.id,
+        map_filename: map.map_filename.clone(),
+        map_name: map.map_name.clone(),
+        lighting: map.lighting,
+        tiled_width: 0,
+        tiled_height: 0,
+        image_width: 0,
+        image_height: 0,
+        image: None,
+        start_pos: ini.map(|i| (i.start_pos_x, i.start_pos_y)),
+        monsters_filename: ini.and_then(|i| i.monsters_filename.clone()),
+        npc_filename: ini.and_then(|i| i.npc_filename.clone()),
+        extra_filename: ini.and_then(|i| i.extra_filename.clone()),
+        error: None,
+    };
+
+    let image_name = format!("{}.png", map.map_filename);
+    let result = (|| -> Result<(i32, i32, i32, i32)> {
+        let file = File::open(&atlas_map.map_path)?;
+        let mut reader = BufReader::new(file);
+        let data = read_map_data(&mut reader)?;
+        let gtl_tileset = tileset::extract(&atlas_map.gtl_path)?;
+        let btl_tileset = tileset::extract(&atlas_map.btl_path)?;
+
+        render_map(MapRenderConfig {
+            reader: &mut reader,
+            output_path: &output_dir.join(&image_name),
+            data: &data,
+            occlusion: !toggles.full_map,
+            gtl_tileset: &gtl_tileset,
+            btl_tileset: &btl_tileset,
+            map_id: &map.map_filename,
+            game_path: Some(game_path),
+            toggles,
+        })?;
+
+        let (w, h) = if toggles.full_map {
+            (
+                data.model.map_width_in_pixels,
+                data.model.map_height_in_pixels,
+            )
+        } else {
+            (
+                data.model.occluded_map_in_pixels_width,
+                data.model.occluded_map_in_pixels_height,
+            )
+        };
+        Ok((
+            data.model.tiled_map_width,
+            data.model.tiled_map_height,
+            w.abs(),
+            h.abs(),
+        ))
+    })();
+
+    match result {
+        Ok((tw, th, iw, ih)) => {
+            entry.tiled_width = tw;
+            entry.tiled_height = th;
+            entry.image_width = iw;
+            entry.image_height = ih;
+            entry.image = Some(image_name);
+        }
+        Err(e) => entry.error = Some(e.to_string()),
+    }
+    entry
+}
+
+// --------------------------------------------------------------------------
+// HTML index
+// --------------------------------------------------------------------------
+
+/// Escapes text for inclusion in HTML element content and attribute values.
+pub(crate) fn html_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Builds a self-contained HTML page listing every map with a thumbnail link.
+pub fn index_to_html(index: &AtlasIndex) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Dispel world atlas</title>\n<style>\n\
+         body { font-family: sans-serif; background: #1e1e1e; color: #ddd; }\n\
+         table { border-collapse: collapse; }\n\
+         td, th { border: 1px solid #444; padding: 4px 8px; vertical-align: top; }\n\
+         a { color: #8cf; }\n\
+         img { max-width: 240px; max-height: 160px; }\n\
+         .error { color: #f66; }\n\
+         </style>\n</head>\n<body>\n<h1>Dispel world atlas</h1>\n<table>\n\
+         <tr><th>ID</th><th>Map</th><th>Name</th><th>Lighting</th><th>Tiles</th><th>Pixels</th><th>Start</th><th>Preview</th></tr>\n",
+    );
+
+    for m in &index.maps {
+        let start = m
+            .start_pos
+            .map(|(x, y)| format!("{x}, {y}"))
+            .unwrap_or_default();
+        let preview = match (&m.image, &m.error) {
+            (Some(img), _) => {
+                let img = html_escape(img);
+                format!("<a href=\"{img}\"><img src=\"{img}\" loading=\"lazy\"></a>")
+            }
+            (None, Some(err)) => format!("<span class=\"error\">{}</span>", html_escape(err)),
+            (None, None) => String::new(),
+        };
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}×{}</td><td>{}×{}</td><td>{}</td><td>{}</td></tr>\n",
+            m.id,
+            html_escape(&m.map_filename),
+            html_escape(&m.map_name),
+            m.lighting,
+            m.tiled_width,
+            m.tiled_height,
+            m.image_width,
+            m.image_height,
+            start,
+            preview,
+        ));
+    }
+
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn discover_joins_all_map_and_map_ini() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("Ref")).unwrap();
+        std::fs::create_dir_all(dir.path().join("Map")).unwrap();
+        std::fs::write(
+            dir.path().join("AllMap.ini"),
+            "1,cat1,Forest,null,null,0\r\n2,dun1,Cave,null,null,1\r\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Ref").join("Map.ini"),
+            "1,0,5,10,1,moncat1.ref,npccat1.ref,extcat1.ref,3\r\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("Map").join("CAT1.MAP"), b"").unwrap();
+
+        let maps = discover_maps(dir.path()).unwrap();
+        assert_eq!(maps.len(), 2);
+        assert_eq!(maps[0].map_ini.as_ref().unwrap().start_pos_x, 5);
+        assert!(maps[0].map_path.ends_with("CAT1.MAP"));
+        assert!(maps[1].map_ini.is_none());
+        assert!(maps[1].map_path.ends_with("dun1.map"));
+    }
+
+    #[test]
+    fn missing_files_become_entry_errors() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("AllMap.ini"),
+            "1,cat1,Forest,null,null,0\r\n",
+        )
+        .unwrap();
+        let out = dir.path().join("out");
+
+        let index = render_all(RenderAllConfig {
+            game_path: dir.path(),
+            output_dir: &out,
+            toggles: LayerToggles::default(),
+            jobs: 2,
+        })
+        .unwrap();
+
+        assert_eq!(index.maps.len(), 1);
+        assert!(index.maps[0].image.is_none());
+        assert!(index.maps[0].error.is_some());
+        assert!(out.join("index.json").exists());
+        assert!(out.join("index.html").exists());
+    }
+
+    #[test]
+    fn html_escapes_map_names() {
+        let index = AtlasIndex {
+            maps: vec![AtlasIndexEntry {
+                id: 1,
+                map_filename: "cat1".into(),
+                map_name: "<Forest & Co>".into(),
+                lighting: MapLighting::Light,
+                tiled_width: 0,
+                tiled_height: 0,
+                image_width: 0,
+                image_height: 0,
+                image: Some("cat1.png".into()),
+                start_pos: None,
+                monsters_filename: None,
+                npc_filename: None,
+                extra_filename: None,
+                error: None,
+            }],
+        };
+        let html = index_to_html(&index);
+        assert!(html.contains("&lt;Forest &amp; Co&gt;"));
+        assert!(html.contains("href=\"cat1.png\""));
+    }
+}
@@ -2,8 +2,14 @@
 //
 // The former monolithic `map.rs` has been split into focused sub-modules:
 //
+//  atlas.rs        – World atlas: render every map in AllMap.ini + index
 //  types.rs        – Coords, EventBlock, SpriteInfoBlock, TiledObjectInfo,
 //                    coordinate constants and helpers
+//  overlay.rs      – Interactive SVG/HTML overlay export for rendered maps
+//  pathfinding.rs  – A* paths, distance fields and start-position
+//                    reachability on the collision grid
+//  placement.rs    – Placement validation of NPCs, monsters and extras
+//                    against the collision grid
 //  model.rs        – MapModel struct and geometry parser (read_map_model)
 //  reader.rs       – Binary block readers for the native .map file format
 //  render.rs       – Isometric rendering pipeline (ground / objects / roofs,
@@ -86,8 +92,12 @@
 // FILE SIZE CALCULATION:
 // Total size = header + blocks + (width×height×(2+4+2)) + optional roof data
 //
+pub mod atlas;
 pub mod database;
 pub mod model;
+pub mod overlay;
+pub mod pathfinding;
+pub mod placement;
 pub mod reader;
 pub mod render;
 pub mod sprite_loader;
@@ -359,24 +369,7 @@ pub fn read_map_data(reader: &mut BufReader<File>) -> IoResult<MapData> {
     let sprite_blocks = sprite_info_block(reader, &internal_sprites)?;
     let tiled_infos = tiled_objects_block(reader)?;
 
-    // Event and tile blocks live at the end of the file
-    // Calculate expected size for the three end blocks
-    let expected_end_blocks_size = tiled_map_height * tiled_map_width * 4 * 3;
-
-    // Validate that we have enough data for the end blocks
-    if file_len < expected_end_blocks_size as u64 {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            format!(
-                "File too small for expected map dimensions. File size: {}, Expected end blocks: {}",
-                file_len, expected_end_blocks_size
-            ),
-        ));
-    }
-
-    let skip = -(expected_end_blocks_size as i64);
-    reader.seek(SeekFrom::End(skip))?;
-
+    seek_end_blocks(reader, file_len, &map_model)?;
     let events = read_events_block(reader, tiled_map_width, tiled_map_height)?;
     let (gtl_tiles, collisions) =
         read_tiles_and_access_block(reader, tiled_map_width, tiled_map_height)?;
@@ -410,6 +403,37 @@ pub fn read_map_data(reader: &mut BufReader<File>) -> IoResult<MapData> {
     })
 }
 
+/// Reads only the per-tile event triggers of a map: the header for the map
+/// size, then the event block at the end of the file, skipping the sprite
+/// and object blocks [`read_map_data`] parses.
+pub fn read_map_events(reader: &mut BufReader<File>) -> IoResult<HashMap<Coords, EventBlock>> {
+    let file_len = reader.get_ref().metadata()?.len();
+    let map_model = read_map_model(reader)?;
+    seek_end_blocks(reader, file_len, &map_model)?;
+    read_events_block(
+        reader,
+        map_model.tiled_map_width,
+        map_model.tiled_map_height,
+    )
+}
+
+/// Validates that the file can hold the end blocks for `model`'s dimensions
+/// and seeks to the first of them, the event block.
+fn seek_end_blocks(reader: &mut BufReader<File>, file_len: u64, model: &MapModel) -> IoResult<()> {
+    let expected_end_blocks_size = (model.tiled_map_height * model.tiled_map_width * 4 * 3) as u64;
+    if file_len < expected_end_blocks_size {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "File too small for expected map dimensions. File size: {}, Expected end blocks: {}",
+                file_len, expected_end_blocks_size
+            ),
+        ));
+    }
+    reader.seek(SeekFrom::End(-(expected_end_blocks_size as i64)))?;
+    Ok(())
+}
+
 // --------------------------------------------------------------------------
 // CLI commands
 // --------------------------------------------------------------------------
@@ -0,0 +1,642 @@
+//! Interactive vector overlay export.
+//!
+//! The raster overlays in [`super::render`] burn markers into the PNG. This
+//! module produces the same information as vector shapes instead: an SVG
+//! layer whose elements carry the underlying record data, and a
+//! self-contained HTML viewer that stacks that SVG over the PNG render with
+//! hover tooltips, a click-to-inspect panel and per-layer toggles.
+//!
+//! Feature positions use [`convert_map_coords_to_image_coords`] with the same
+//! occlusion offset and tile-centre adjustment as the raster overlays, so the
+//! SVG lines up pixel-for-pixel with a PNG rendered with the same settings.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Result};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::references::event_ini::Event;
+use crate::references::extractor::Extractor;
+use crate::references::monster_db::read_monster_db;
+use crate::references::npc_ini::NpcIni;
+
+use super::atlas::html_escape;
+use super::model::MapModel;
+use super::placement::npc_waypoints;
+use super::render::{ExternalEntities, collect_external_entities};
+use super::tileset::{TILE_HEIGHT, TILE_WIDTH};
+use super::types::convert_map_coords_to_image_coords;
+use super::{MapData, read_map_data};
+
+// --------------------------------------------------------------------------
+// Feature model
+// --------------------------------------------------------------------------
+
+/// Layer an overlay feature belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayKind {
+    Npc,
+    Monster,
+    Extra,
+    DrawItem,
+    Event,
+}
+
+impl OverlayKind {
+    /// CSS class / layer name used in the SVG and HTML output.
+    pub fn class(self) -> &'static str {
+        match self {
+            OverlayKind::Npc => "npc",
+            OverlayKind::Monster => "monster",
+            OverlayKind::Extra => "extra",
+            OverlayKind::DrawItem => "draw_item",
+            OverlayKind::Event => "event",
+        }
+    }
+
+    fn color(self) -> &'static str {
+        match self {
+            OverlayKind::Npc => "#3264dc",
+            OverlayKind::Monster => "#dc3232",
+            OverlayKind::Extra => "#c8b41e",
+            OverlayKind::DrawItem => "#28e628",
+            OverlayKind::Event => "#c819c8",
+        }
+    }
+
+    const ALL: [OverlayKind; 5] = [
+        OverlayKind::Npc,
+        OverlayKind::Monster,
+        OverlayKind::Extra,
+        OverlayKind::DrawItem,
+        OverlayKind::Event,
+    ];
+}
+
+/// A single hoverable/clickable element of the overlay.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayFeature {
+    pub kind: OverlayKind,
+    /// Short label shown on hover.
+    pub label: String,
+    /// Map tile coordinates.
+    pub tile_x: i32,
+    pub tile_y: i32,
+    /// Tile centre in image pixels.
+    pub px: i32,
+    pub py: i32,
+    /// Waypoint path in image pixels (NPCs only).
+    pub path: Vec<(i32, i32)>,
+    /// Full record data shown in the inspector.
+    pub data: Value,
+}
+
+/// Display names used to label features, keyed by catalog id.
+#[derive(Debug, Clone, Default)]
+pub struct OverlayNames {
+    /// `Monster.db` id → monster name.
+    pub monsters: HashMap<i32, String>,
+    /// `Npc.ini` id → NPC description.
+    pub npcs: HashMap<i32, String>,
+    /// `Event.ini` records by event id.
+    pub events: HashMap<i32, Event>,
+}
+
+impl OverlayNames {
+    /// Loads `MonsterInGame/Monster.db`, `Npc.ini` and `Event.ini` from the
+    /// game directory. Missing files simply leave the lookup empty.
+    pub fn load(game_path: &Path) -> Self {
+        let monsters = read_monster_db(&game_path.join("MonsterInGame").join("Monster.db"))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| (m.id, m.name))
+            .collect();
+        let npcs = NpcIni::read_file(&game_path.join("Npc.ini"))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|n| (n.id, n.description))
+            .collect();
+        let events = Event::read_file(&game_path.join("Event.ini"))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| (e.event_id, e))
+            .collect();
+        Self {
+            monsters,
+            npcs,
+            events,
+        }
+    }
+}
+
+/// Converts a tile coordinate to the pixel centre of that tile, matching the
+/// raster overlays in [`super::render`].
+pub fn tile_center_px(model: &MapModel, x: i32, y: i32, occlusion: bool) -> (i32, i32) {
+    let diagonal = model.tiled_map_width + model.tiled_map_height;
+    let (mut px, mut py) = convert_map_coords_to_image_coords(x, y, diagonal);
+    if occlusion {
+        px -= model.map_non_occluded_start_x;
+        py -= model.map_non_occluded_start_y;
+    }
+    (px + TILE_WIDTH as i32 / 2, py + TILE_HEIGHT as i32 / 2)
+}
+
+/// Collects every overlay feature for a parsed map.
+///
+/// Event tiles come from the map itself; NPCs, monsters, extras and draw
+/// items are only present when `external` was loaded from a game directory.
+pub fn collect_features(
+    data: &MapData,
+    external: Option<&ExternalEntities>,
+    names: &OverlayNames,
+    occlusion: bool,
+) -> Vec<OverlayFeature> {
+    let model = &data.model;
+    let mut features = Vec::new();
+
+    if let Some(ext) = external {
+        for npc in &ext.npc_records {
+            // Same waypoints and anchor as the PNG render.
+            let waypoints: Vec<(i32, i32)> = npc_waypoints(npc)
+                .into_iter()
+                .map(|(_, tile)| tile)
+                .collect();
+            let (tx, ty) = waypoints
+                .first()
+                .copied()
+                .unwrap_or((npc.goto1_x, npc.goto1_y));
+            let (px, py) = tile_center_px(model, tx, ty, occlusion);
+            let mut label = format!("NPC #{} {}", npc.index, npc.name);
+            if let Some(desc) = names.npcs.get(&npc.npc_ini_id) {
+                label.push_str(&format!(" ({desc})"));
+            }
+            features.push(OverlayFeature {
+                kind: OverlayKind::Npc,
+                label,
+                tile_x: tx,
+                tile_y: ty,
+                px,
+                py,
+                path: waypoints
+                    .iter()
+                    .map(|&(wx, wy)| tile_center_px(model, wx, wy, occlusion))
+                    .collect(),
+                data: record_value(npc),
+            });
+        }
+
+        for m in &ext.monster_records {
+            let (px, py) = tile_center_px(model, m.map_x, m.map_y, occlusion);
+            let name = names
+                .monsters
+                .get(&m.monster_db_id)
+                .map(String::as_str)
+                .unwrap_or("?");
+            features.push(OverlayFeature {
+                kind: OverlayKind::Monster,
+                label: format!("Monster #{} {name} (db {})", m.index, m.monster_db_id),
+                tile_x: m.map_x,
+                tile_y: m.map_y,
+                px,
+                py,
+                path: Vec::new(),
+                data: record_value(m),
+            });
+        }
+
+        for e in &ext.extra_records {
+            let (px, py) = tile_center_px(model, e.map_x, e.map_y, occlusion);
+            features.push(OverlayFeature {
+                kind: OverlayKind::Extra,
+                label: format!("Extra #{} {}", e.record_index, e.object_name),
+                tile_x: e.map_x,
+                tile_y: e.map_y,
+                px,
+                py,
+                path: Vec::new(),
+                data: record_value(e),
+            });
+        }
+
+        for di in &ext.draw_items {
+            let (px, py) = tile_center_px(model, di.x_coord, di.y_coord, occlusion);
+            let item_type = di
+                .item
+                .item_type()
+                .map(|t| format!("{t:?}"))
+                .unwrap_or_else(|| "Unknown".to_string());
+            features.push(OverlayFeature {
+                kind: OverlayKind::DrawItem,
+                label: format!("Draw item {item_type} #{}", di.item.item_id()),
+                tile_x: di.x_coord,
+                tile_y: di.y_coord,
+                px,
+                py,
+                path: Vec::new(),
+                data: record_value(di),
+            });
+        }
+    }
+
+    let mut events: Vec<_> = data
+        .events
+        .iter()
+        .filter(|(_, e)| e.event_id != 0)
+        .collect();
+    events.sort_by_key(|&(&(x, y), _)| (y, x));
+    for (&(x, y), event) in events {
+        let (px, py) = tile_center_px(model, x, y, occlusion);
+        let id = event.event_id as i32;
+        let data = match names.events.get(&id) {
+            Some(record) => json!({ "event_id": id, "event_ini": record_value(record) }),
+            None => json!({ "event_id": id }),
+        };
+        features.push(OverlayFeature {
+            kind: OverlayKind::Event,
+            label: format!("Event {id}"),
+            tile_x: x,
+            tile_y: y,
+            px,
+            py,
+            path: Vec::new(),
+            data,
+        });
+    }
+
+    features
+}
+
+/// Serialises a record for the inspector panel.
+fn record_value<T: Serialize>(record: &T) -> Value {
+    serde_json::to_value(record).unwrap_or(Value::Null)
+}
+
+// --------------------------------------------------------------------------
+// SVG / HTML output
+// --------------------------------------------------------------------------
+
+/// Renders features as an SVG document of `width`×`height` pixels.
+///
+/// Every feature is a `<g class="feature <kind>">` with a `<title>` (native
+/// hover tooltip) and its record JSON in a `data-record` attribute.
+pub fn to_svg(features: &[OverlayFeature], width: u32, height: u32) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    );
+    for kind in OverlayKind::ALL {
+        svg.push_str(&format!(
+            "<g class=\"layer {}\" data-layer=\"{}\">\n",
+            kind.class(),
+            kind.class()
+        ));
+        for (i, f) in features.iter().enumerate().filter(|(_, f)| f.kind == kind) {
+            svg.push_str(&feature_to_svg(i, f));
+        }
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn feature_to_svg(index: usize, f: &OverlayFeature) -> String {
+    let color = f.kind.color();
+    let record = html_escape(&f.data.to_string());
+    let mut out = format!(
+        "<g class=\"feature {}\" data-index=\"{index}\" data-tile=\"{},{}\" data-record=\"{record}\"><title>{} ({}, {})</title>",
+        f.kind.class(),
+        f.tile_x,
+        f.tile_y,
+        html_escape(&f.label),
+        f.tile_x,
+        f.tile_y,
+    );
+    if f.path.len() >= 2 {
+        let mut points: Vec<String> = f.path.iter().map(|(x, y)| format!("{x},{y}")).collect();
+        points.push(points[0].clone());
+        out.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"2\" stroke-dasharray=\"6 3\"/>",
+            points.join(" ")
+        ));
+    }
+    let (x, y) = (f.px, f.py);
+    match f.kind {
+        OverlayKind::Event => out.push_str(&format!(
+            "<circle cx=\"{x}\" cy=\"{y}\" r=\"5\" fill=\"{color}\" fill-opacity=\"0.7\"/>"
+        )),
+        OverlayKind::DrawItem | OverlayKind::Extra => {
+            let r = 7;
+            out.push_str(&format!(
+                "<polygon points=\"{x},{} {},{y} {x},{} {},{y}\" fill=\"{color}\" fill-opacity=\"0.6\" stroke=\"#fff\" stroke-width=\"1\"/>",
+                y - r,
+                x + r,
+                y + r,
+                x - r
+            ))
+        }
+        OverlayKind::Npc | OverlayKind::Monster => out.push_str(&format!(
+            "<circle cx=\"{x}\" cy=\"{y}\" r=\"8\" fill=\"{color}\" fill-opacity=\"0.5\" stroke=\"#fff\" stroke-width=\"1.5\"/>"
+        )),
+    }
+    out.push_str("</g>\n");
+    out
+}
+
+/// Minimal standard base64 encoder used to inline the PNG into the HTML.
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        out.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        out.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 63] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[n as usize & 63] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+/// Builds a self-contained HTML viewer: the PNG (inlined as a data URI) with
+/// the SVG overlay on top, layer checkboxes and a record inspector panel.
+pub fn to_html(title: &str, png_bytes: &[u8], svg: &str, width: u32, height: u32) -> String {
+    let mut toggles = String::new();
+    for kind in OverlayKind::ALL {
+        toggles.push_str(&format!(
+            "<label style=\"color:{}\"><input type=\"checkbox\" data-layer=\"{}\" checked> {}</label>\n",
+            kind.color(),
+            kind.class(),
+            kind.class()
+        ));
+    }
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ margin: 0; font-family: sans-serif; background: #1e1e1e; color: #ddd; display: flex; }}
+#map {{ position: relative; width: {width}px; height: {height}px; flex: none; }}
+#map img, #map svg {{ position: absolute; left: 0; top: 0; }}
+#side {{ position: sticky; top: 0; height: 100vh; overflow: auto; width: 360px; padding: 8px; box-sizing: border-box; background: #2a2a2a; }}
+.feature {{ cursor: pointer; }}
+.feature:hover > * {{ stroke: #ff0; stroke-width: 3; }}
+pre {{ white-space: pre-wrap; font-size: 11px; }}
+label {{ display: block; }}
+</style>
+</head>
+<body>
+<div id="map">
+<img src="data:image/png;base64,{png}" width="{width}" height="{height}">
+{svg}</div>
+<div id="side">
+<h3>{title}</h3>
+{toggles}<hr>
+<div id="info">Click a marker to inspect its record.</div>
+</div>
+<script>
+document.querySelectorAll('#side input[data-layer]').forEach(function (cb) {{
+  cb.addEventListener('change', function () {{
+    document.querySelectorAll('g.layer.' + cb.dataset.layer).forEach(function (g) {{
+      g.style.display = cb.checked ? '' : 'none';
+    }});
+  }});
+}});
+document.querySelectorAll('g.feature').forEach(function (g) {{
+  g.addEventListener('click', function () {{
+    var title = g.querySelector('title').textContent;
+    var record = JSON.stringify(JSON.parse(g.dataset.record), null, 2);
+    var info = document.getElementById('info');
+    info.innerHTML = '';
+    var h = document.createElement('h4');
+    h.textContent = title;
+    var pre = document.createElement('pre');
+    pre.textContent = record;
+    info.appendChild(h);
+    info.appendChild(pre);
+  }});
+}});
+</script>
+</body>
+</html>
+"#,
+        title = html_escape(title),
+        png = base64_encode(png_bytes),
+    )
+}
+
+// --------------------------------------------------------------------------
+// File-level export
+// --------------------------------------------------------------------------
+
+/// Configuration for [`export_overlay`].
+pub struct OverlayExportConfig<'a> {
+    pub map_path: &'a Path,
+    /// Rendered PNG to embed in the HTML viewer.
+    pub png_path: &'a Path,
+    pub game_path: Option<&'a Path>,
+    /// Must match the `occlusion` used for the PNG render.
+    pub occlusion: bool,
+    pub svg_output: Option<&'a Path>,
+    pub html_output: Option<&'a Path>,
+}
+
+/// Writes the SVG overlay and/or HTML viewer for a map that was already
+/// rendered to `png_path`.
+pub fn export_overlay(config: OverlayExportConfig) -> Result<()> {
+    let OverlayExportConfig {
+        map_path,
+        png_path,
+        game_path,
+        occlusion,
+        svg_output,
+        html_output,
+    } = config;
+
+    let file = File::open(map_path)?;
+    let mut reader = BufReader::new(file);
+    let data = read_map_data(&mut reader)?;
+    let map_id = map_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("map");
+
+    let external = game_path.and_then(|gp| collect_external_entities(map_id, gp, &data.model).ok());
+    let names = game_path.map(OverlayNames::load).unwrap_or_default();
+    let features = collect_features(&data, external.as_ref(), &names, occlusion);
+
+    let (width, height) = if occlusion {
+        (
+            data.model.occluded_map_in_pixels_width,
+            data.model.occluded_map_in_pixels_height,
+        )
+    } else {
+        (
+            data.model.map_width_in_pixels,
+            data.model.map_height_in_pixels,
+        )
+    };
+    let svg = to_svg(&features, width.unsigned_abs(), height.unsigned_abs());
+
+    if let Some(path) = svg_output {
+        std::fs::write(path, &svg)?;
+    }
+    if let Some(path) = html_output {
+        let png = std::fs::read(png_path)?;
+        let html = to_html(
+            map_id,
+            &png,
+            &svg,
+            width.unsigned_abs(),
+            height.unsigned_abs(),
+        );
+        std::fs::write(path, html)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::types::EventBlock;
+    use crate::references::enums::BooleanFlag;
+    use crate::references::npc_ref::NPC;
+
+    fn model() -> MapModel {
+        MapModel {
+            tiled_map_width: 50,
+            tiled_map_height: 50,
+            map_non_occluded_start_x: 64,
+            map_non_occluded_start_y: 32,
+            ..Default::default()
+        }
+    }
+
+    fn map_data(events: &[(i32, i32, i16)]) -> MapData {
+        MapData {
+            model: model(),
+            gtl_tiles: HashMap::new(),
+            btl_tiles: HashMap::new(),
+            collisions: HashMap::new(),
+            events: events
+                .iter()
+                .map(|&(x, y, event_id)| {
+                    (
+                        (x, y),
+                        EventBlock {
+                            x,
+                            y,
+                            _unknown_value: 0,
+                            event_id,
+                        },
+                    )
+                })
+                .collect(),
+            tiled_infos: Vec::new(),
+            internal_sprites: Vec::new(),
+            sprite_blocks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn tile_center_matches_render_math() {
+        let m = model();
+        let (px, py) = convert_map_coords_to_image_coords(10, 12, 100);
+        assert_eq!(
+            tile_center_px(&m, 10, 12, true),
+            (
+                px - 64 + TILE_WIDTH as i32 / 2,
+                py - 32 + TILE_HEIGHT as i32 / 2
+            )
+        );
+        assert_eq!(
+            tile_center_px(&m, 10, 12, false),
+            (px + TILE_WIDTH as i32 / 2, py + TILE_HEIGHT as i32 / 2)
+        );
+    }
+
+    #[test]
+    fn events_become_features_without_game_path() {
+        let data = map_data(&[(3, 4, 12), (5, 5, 0)]);
+        let features = collect_features(&data, None, &OverlayNames::default(), true);
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].kind, OverlayKind::Event);
+        assert_eq!(features[0].data["event_id"], 12);
+    }
+
+    #[test]
+    fn npc_waypoints_follow_filled_flags() {
+        let npc = NPC {
+            name: "Guard".into(),
+            goto1_filled: BooleanFlag::True,
+            goto2_x: 7,
+            goto2_y: 9,
+            goto3_filled: BooleanFlag::True,
+            goto3_x: 4,
+            goto3_y: 2,
+            ..Default::default()
+        };
+        let external = ExternalEntities {
+            monsters: Vec::new(),
+            npcs: Vec::new(),
+            extras: Vec::new(),
+            npc_records: vec![npc],
+            monster_records: Vec::new(),
+            extra_records: Vec::new(),
+            draw_items: Vec::new(),
+            start_pos: (0, 0),
+        };
+        let m = model();
+        let features = collect_features(
+            &map_data(&[]),
+            Some(&external),
+            &OverlayNames::default(),
+            true,
+        );
+        let npc = features
+            .iter()
+            .find(|f| f.kind == OverlayKind::Npc)
+            .unwrap();
+        assert_eq!((npc.tile_x, npc.tile_y), (0, 0));
+        assert_eq!(
+            npc.path,
+            [
+                tile_center_px(&m, 0, 0, true),
+                tile_center_px(&m, 4, 2, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn svg_escapes_record_json() {
+        let data = map_data(&[(1, 1, 7)]);
+        let features = collect_features(&data, None, &OverlayNames::default(), false);
+        let svg = to_svg(&features, 100, 100);
+        assert!(svg.contains("data-record=\"{&quot;event_id&quot;:7}\""));
+        assert!(svg.contains("<title>Event 7 (1, 1)</title>"));
+    }
+
+    #[test]
+    fn base64_matches_reference() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+}
@@ -0,0 +1,553 @@
+//! Pathfinding and reachability on a map's collision grid.
+//!
+//! Characters move on the tiled grid one tile at a time in any of the eight
+//! directions. A diagonal step is only allowed when both orthogonal tiles it
+//! passes between are walkable, so walls cannot be slipped through at their
+//! corners. Orthogonal steps cost [`STEP_COST`] and diagonal steps
+//! [`DIAGONAL_STEP_COST`], which keeps path lengths close to the distance
+//! walked on screen.
+//!
+//! [`CollisionGrid`] answers walkability and connectivity questions,
+//! [`CollisionGrid::find_path`] runs A* between two tiles and
+//! [`CollisionGrid::distances_from`] floods the whole grid from one tile.
+//! [`analyze_reachability`] builds on the latter to list the event tiles,
+//! extras and NPCs a player entering the map at its `Map.ini` start position
+//! can never get to.
+
+use std::cell::OnceCell;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufReader, Result};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::references::enums::NpcMovementMode;
+use crate::references::extra_ref::ExtraRef;
+use crate::references::npc_ref::NPC;
+
+use super::placement::{min_max, npc_waypoints};
+use super::render::collect_external_entities;
+use super::types::Coords;
+use super::{MapData, read_map_data};
+
+/// Cost of a step to an orthogonally adjacent tile.
+pub const STEP_COST: u32 = 10;
+/// Cost of a diagonal step (≈ `STEP_COST` × √2).
+pub const DIAGONAL_STEP_COST: u32 = 14;
+
+const STEPS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// Walkability of a map's tiles and their connected regions.
+pub struct CollisionGrid<'a> {
+    collisions: &'a HashMap<Coords, bool>,
+    width: i32,
+    height: i32,
+    /// Region id per tile (row-major), `None` for blocked tiles. Built on
+    /// first use.
+    regions: OnceCell<Vec<Option<u32>>>,
+}
+
+impl<'a> CollisionGrid<'a> {
+    pub fn new(data: &'a MapData) -> Self {
+        Self {
+            collisions: &data.collisions,
+            width: data.model.tiled_map_width.max(0),
+            height: data.model.tiled_map_height.max(0),
+            regions: OnceCell::new(),
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn in_bounds(&self, (x, y): Coords) -> bool {
+        (0..self.width).contains(&x) && (0..self.height).contains(&y)
+    }
+
+    /// Inside the grid and not marked as a collision.
+    pub fn is_walkable(&self, tile: Coords) -> bool {
+        self.in_bounds(tile) && self.collisions.get(&tile) != Some(&true)
+    }
+
+    /// Number of walkable tiles on the map.
+    pub fn walkable_count(&self) -> usize {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&tile| self.is_walkable(tile))
+            .count()
+    }
+
+    /// Walkable tiles one step away from `(x, y)`, with the cost of the step.
+    pub fn neighbours(&self, (x, y): Coords) -> impl Iterator<Item = (Coords, u32)> + '_ {
+        STEPS.into_iter().filter_map(move |(dx, dy)| {
+            let next = (x + dx, y + dy);
+            let diagonal = dx != 0 && dy != 0;
+            let corner_free =
+                !diagonal || (self.is_walkable((x + dx, y)) && self.is_walkable((x, y + dy)));
+            let cost = if diagonal {
+                DIAGONAL_STEP_COST
+            } else {
+                STEP_COST
+            };
+            (self.is_walkable(next) && corner_free).then_some((next, cost))
+        })
+    }
+
+    /// Whether a walk from `from` to `to` exists.
+    pub fn connected(&self, from: Coords, to: Coords) -> bool {
+        match (self.region(from), self.region(to)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Cheapest walk from `from` to `to` (A* with the octile distance as
+    /// heuristic), or `None` when either end is blocked or they are not
+    /// connected.
+    pub fn find_path(&self, from: Coords, to: Coords) -> Option<Route> {
+        if !self.connected(from, to) {
+            return None;
+        }
+        let mut cost = vec![u32::MAX; self.tile_count()];
+        let mut came_from: HashMap<Coords, Coords> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        cost[self.index(from)] = 0;
+        open.push(Reverse((octile(from, to), 0, from)));
+
+        while let Some(Reverse((_, spent, tile))) = open.pop() {
+            if tile == to {
+                let mut tiles = vec![to];
+                while let Some(&prev) = came_from.get(tiles.last()?) {
+                    tiles.push(prev);
+                }
+                tiles.reverse();
+                return Some(Route { tiles, cost: spent });
+            }
+            if spent > cost[self.index(tile)] {
+                continue;
+            }
+            for (next, step) in self.neighbours(tile) {
+                let next_cost = spent + step;
+                let slot = &mut cost[self.index(next)];
+                if next_cost < *slot {
+                    *slot = next_cost;
+                    came_from.insert(next, tile);
+                    open.push(Reverse((next_cost + octile(next, to), next_cost, next)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Walking cost from `start` to every tile (Dijkstra). Every tile is
+    /// unreached when `start` is blocked.
+    pub fn distances_from(&self, start: Coords) -> DistanceField {
+        let mut cost = vec![None; self.tile_count()];
+        if self.is_walkable(start) {
+            let mut open = BinaryHeap::new();
+            cost[self.index(start)] = Some(0);
+            open.push(Reverse((0, start)));
+            while let Some(Reverse((spent, tile))) = open.pop() {
+                if cost[self.index(tile)].is_some_and(|best| spent > best) {
+                    continue;
+                }
+                for (next, step) in self.neighbours(tile) {
+                    let next_cost = spent + step;
+                    let slot = &mut cost[self.index(next)];
+                    if slot.is_none_or(|best| next_cost < best) {
+                        *slot = Some(next_cost);
+                        open.push(Reverse((next_cost, next)));
+                    }
+                }
+            }
+        }
+        DistanceField {
+            width: self.width,
+            height: self.height,
+            cost,
+        }
+    }
+
+    fn tile_count(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    fn index(&self, (x, y): Coords) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    fn region(&self, tile: Coords) -> Option<u32> {
+        if !self.is_walkable(tile) {
+            return None;
+        }
+        self.regions.get_or_init(|| self.label_regions())[self.index(tile)]
+    }
+
+    fn label_regions(&self) -> Vec<Option<u32>> {
+        let mut regions = vec![None; self.tile_count()];
+        let mut next_region = 0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if regions[self.index((x, y))].is_some() || !self.is_walkable((x, y)) {
+                    continue;
+                }
+                regions[self.index((x, y))] = Some(next_region);
+                let mut queue = VecDeque::from([(x, y)]);
+                while let Some(tile) = queue.pop_front() {
+                    for (next, _) in self.neighbours(tile) {
+                        let slot = &mut regions[self.index(next)];
+                        if slot.is_none() {
+                            *slot = Some(next_region);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+                next_region += 1;
+            }
+        }
+        regions
+    }
+}
+
+/// Octile distance: the cost of the cheapest walk on an empty grid.
+fn octile(a: Coords, b: Coords) -> u32 {
+    let dx = a.0.abs_diff(b.0);
+    let dy = a.1.abs_diff(b.1);
+    let (short, long) = (dx.min(dy), dx.max(dy));
+    short * DIAGONAL_STEP_COST + (long - short) * STEP_COST
+}
+
+/// A walk between two tiles, both ends included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub tiles: Vec<Coords>,
+    /// Sum of the step costs.
+    pub cost: u32,
+}
+
+/// Walking cost from one start tile to every tile of the grid.
+#[derive(Debug, Clone)]
+pub struct DistanceField {
+    width: i32,
+    height: i32,
+    cost: Vec<Option<u32>>,
+}
+
+impl DistanceField {
+    /// Cost to walk to `tile`, `None` if it cannot be reached.
+    pub fn get(&self, (x, y): Coords) -> Option<u32> {
+        if !(0..self.width).contains(&x) || !(0..self.height).contains(&y) {
+            return None;
+        }
+        self.cost[(y * self.width + x) as usize]
+    }
+
+    pub fn is_reached(&self, tile: Coords) -> bool {
+        self.get(tile).is_some()
+    }
+
+    /// Whether `tile` is reached or a player on a reached tile stands next
+    /// to it — enough to open a chest or talk to an NPC.
+    pub fn is_touched(&self, (x, y): Coords) -> bool {
+        self.is_reached((x, y))
+            || STEPS
+                .iter()
+                .any(|(dx, dy)| self.is_reached((x + dx, y + dy)))
+    }
+
+    pub fn reached_count(&self) -> usize {
+        self.cost.iter().filter(|c| c.is_some()).count()
+    }
+
+    /// Highest cost of any reached tile.
+    pub fn max_cost(&self) -> Option<u32> {
+        self.cost.iter().flatten().copied().max()
+    }
+}
+
+/// What cannot be reached from the start position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ReachTarget {
+    Event,
+    Extra,
+    Npc,
+}
+
+impl std::fmt::Display for ReachTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReachTarget::Event => "event",
+            ReachTarget::Extra => "extra",
+            ReachTarget::Npc => "npc",
+        })
+    }
+}
+
+/// An event tile, extra or NPC the player cannot walk to.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnreachableTarget {
+    pub target: ReachTarget,
+    /// Event id for events, record index for extras and NPCs.
+    pub id: i32,
+    /// NPC or object name, empty for events.
+    pub label: String,
+    pub tile_x: i32,
+    pub tile_y: i32,
+}
+
+impl std::fmt::Display for UnreachableTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} #{}", self.target, self.id)?;
+        if !self.label.is_empty() {
+            write!(f, " \"{}\"", self.label)?;
+        }
+        write!(f, " at ({}, {})", self.tile_x, self.tile_y)
+    }
+}
+
+/// Reachability of one map from its start position.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReachabilityReport {
+    pub start_x: i32,
+    pub start_y: i32,
+    /// False when the start tile is blocked or off the map; nothing is
+    /// reachable then.
+    pub start_walkable: bool,
+    pub walkable_tiles: usize,
+    pub reachable_tiles: usize,
+    pub unreachable: Vec<UnreachableTarget>,
+}
+
+/// Flood the map from `start` and list the event tiles, extras and NPCs the
+/// player cannot get to. Extras and events count as reached when the player
+/// can stand on them or next to them; an NPC when any tile it walks to
+/// (its waypoints, or any tile of its activation rectangle for wandering
+/// NPCs) is. Targets are ordered events, extras, NPCs.
+pub fn analyze_reachability(
+    data: &MapData,
+    start: Coords,
+    npcs: &[NPC],
+    extras: &[ExtraRef],
+) -> ReachabilityReport {
+    let grid = CollisionGrid::new(data);
+    let field = grid.distances_from(start);
+    let mut unreachable = Vec::new();
+
+    let mut events: Vec<_> = data
+        .events
+        .iter()
+        .filter(|(_, e)| e.event_id != 0)
+        .collect();
+    events.sort_by_key(|(tile, e)| (e.event_id, tile.1, tile.0));
+    for (&(x, y), event) in events {
+        if !field.is_touched((x, y)) {
+            unreachable.push(UnreachableTarget {
+                target: ReachTarget::Event,
+                id: i32::from(event.event_id),
+                label: String::new(),
+                tile_x: x,
+                tile_y: y,
+            });
+        }
+    }
+
+    for extra in extras {
+        if !field.is_touched((extra.map_x, extra.map_y)) {
+            unreachable.push(UnreachableTarget {
+                target: ReachTarget::Extra,
+                id: extra.record_index,
+                label: extra.object_name.clone(),
+                tile_x: extra.map_x,
+                tile_y: extra.map_y,
+            });
+        }
+    }
+
+    for npc in npcs {
+        let mut tiles: Vec<Coords> = npc_waypoints(npc).into_iter().map(|(_, t)| t).collect();
+        if npc.movement_mode == NpcMovementMode::RandomInActivationRect {
+            let (x1, x2) = min_max(npc.activation_rect_x1, npc.activation_rect_x2);
+            let (y1, y2) = min_max(npc.activation_rect_y1, npc.activation_rect_y2);
+            tiles.extend((y1..=y2).flat_map(|y| (x1..=x2).map(move |x| (x, y))));
+        }
+        let shown = tiles.first().copied().unwrap_or((npc.goto1_x, npc.goto1_y));
+        if tiles.is_empty() {
+            tiles.push(shown);
+        }
+        if !tiles.iter().any(|&tile| field.is_touched(tile)) {
+            unreachable.push(UnreachableTarget {
+                target: ReachTarget::Npc,
+                id: npc.index,
+                label: npc.name.clone(),
+                tile_x: shown.0,
+                tile_y: shown.1,
+            });
+        }
+    }
+
+    ReachabilityReport {
+        start_x: start.0,
+        start_y: start.1,
+        start_walkable: grid.is_walkable(start),
+        walkable_tiles: grid.walkable_count(),
+        reachable_tiles: field.reached_count(),
+        unreachable,
+    }
+}
+
+/// Parse `map_path` and analyse it from the start position `Ref/Map.ini`
+/// gives it, together with the NPCs and extras assigned to it there.
+pub fn analyze_map(map_path: &Path, game_path: &Path) -> Result<ReachabilityReport> {
+    let mut reader = BufReader::new(File::open(map_path)?);
+    let data = read_map_data(&mut reader)?;
+    let map_id = map_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("map");
+    let entities = collect_external_entities(map_id, game_path, &data.model)?;
+    Ok(analyze_reachability(
+        &data,
+        entities.start_pos,
+        &entities.npc_records,
+        &entities.extra_records,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::model::MapModel;
+    use crate::map::types::EventBlock;
+    use crate::references::enums::BooleanFlag;
+
+    /// `rows` drawn top to bottom, `#` marking a collision.
+    fn map(rows: &[&str]) -> MapData {
+        let mut collisions = HashMap::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                collisions.insert((x as i32, y as i32), c == '#');
+            }
+        }
+        MapData {
+            model: MapModel {
+                tiled_map_width: rows[0].len() as i32,
+                tiled_map_height: rows.len() as i32,
+                ..Default::default()
+            },
+            gtl_tiles: HashMap::new(),
+            btl_tiles: HashMap::new(),
+            collisions,
+            events: HashMap::new(),
+            tiled_infos: Vec::new(),
+            internal_sprites: Vec::new(),
+            sprite_blocks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn finds_the_cheapest_path_around_a_wall() {
+        let data = map(&["....", ".##.", "...."]);
+        let grid = CollisionGrid::new(&data);
+        let route = grid.find_path((0, 1), (3, 1)).unwrap();
+        assert_eq!(route.tiles.first(), Some(&(0, 1)));
+        assert_eq!(route.tiles.last(), Some(&(3, 1)));
+        // Down, three steps along the bottom row, up: no diagonal can skip
+        // the wall's corners.
+        assert_eq!(route.tiles.len(), 6);
+        assert_eq!(route.cost, 5 * STEP_COST);
+        for pair in route.tiles.windows(2) {
+            assert!(grid.neighbours(pair[0]).any(|(t, _)| t == pair[1]));
+        }
+    }
+
+    #[test]
+    fn open_ground_prefers_diagonals() {
+        let data = map(&["....", "....", "...."]);
+        let grid = CollisionGrid::new(&data);
+        let route = grid.find_path((0, 0), (3, 2)).unwrap();
+        assert_eq!(route.cost, 2 * DIAGONAL_STEP_COST + STEP_COST);
+        assert_eq!(route.tiles.len(), 4);
+    }
+
+    #[test]
+    fn no_path_into_a_sealed_room() {
+        let data = map(&["..#.", "..#.", "..#."]);
+        let grid = CollisionGrid::new(&data);
+        assert_eq!(grid.find_path((0, 0), (3, 0)), None);
+        assert_eq!(grid.find_path((0, 0), (2, 0)), None);
+        let field = grid.distances_from((0, 0));
+        assert_eq!(field.reached_count(), 6);
+        assert_eq!(field.get((1, 1)), Some(DIAGONAL_STEP_COST));
+        assert_eq!(field.get((3, 0)), None);
+    }
+
+    #[test]
+    fn reports_targets_behind_walls() {
+        let mut data = map(&["..#..", "..#..", "..#.."]);
+        data.events.insert(
+            (4, 2),
+            EventBlock {
+                x: 4,
+                y: 2,
+                _unknown_value: 0,
+                event_id: 7,
+            },
+        );
+        // Chest inside the wall but next to the start side: reachable.
+        let chest = ExtraRef {
+            record_index: 0,
+            map_x: 2,
+            map_y: 1,
+            ..Default::default()
+        };
+        let door = ExtraRef {
+            record_index: 1,
+            map_x: 4,
+            map_y: 0,
+            ..Default::default()
+        };
+        let npc = NPC {
+            index: 3,
+            name: "Hermit".into(),
+            movement_mode: NpcMovementMode::Waypoints,
+            goto1_filled: BooleanFlag::True,
+            goto1_x: 4,
+            goto1_y: 1,
+            ..Default::default()
+        };
+
+        let report = analyze_reachability(&data, (0, 0), &[npc], &[chest, door]);
+        assert!(report.start_walkable);
+        assert_eq!(report.walkable_tiles, 12);
+        assert_eq!(report.reachable_tiles, 6);
+        let found: Vec<(ReachTarget, i32)> = report
+            .unreachable
+            .iter()
+            .map(|t| (t.target, t.id))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (ReachTarget::Event, 7),
+                (ReachTarget::Extra, 1),
+                (ReachTarget::Npc, 3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,476 @@
+//! Placement validation of map entities against the collision grid.
+//!
+//! Monster, NPC and extra placements live in their own `.ref` files and are
+//! edited independently of the `.map` collision layer, so nothing stops a
+//! monster from ending up inside a wall or an NPC from patrolling towards a
+//! waypoint it can never reach. [`validate_placements`] checks one map's
+//! entities against its [`MapData`]:
+//!
+//! - every position lies inside the tiled grid and on a walkable tile
+//!   (extras only warn: doors and chests are often placed on blocked tiles);
+//! - every waypoint of a patrolling NPC is reachable from its first one;
+//! - an NPC wandering inside its activation rectangle has room to move;
+//! - extra footprints do not overlap each other.
+//!
+//! Reachability follows the movement rules of [`super::pathfinding`].
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Result};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::references::enums::NpcMovementMode;
+use crate::references::extra_ref::ExtraRef;
+use crate::references::monster_ref::MonsterRef;
+use crate::references::npc_ref::NPC;
+
+use super::pathfinding::CollisionGrid;
+use super::render::collect_external_entities;
+use super::{MapData, read_map_data};
+
+/// Entity layer a placement issue belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacedKind {
+    Monster,
+    Npc,
+    Extra,
+}
+
+impl std::fmt::Display for PlacedKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PlacedKind::Monster => "Monster",
+            PlacedKind::Npc => "NPC",
+            PlacedKind::Extra => "Extra",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// Position outside the map's tiled grid.
+    OutOfBounds,
+    /// Position on a tile marked as blocked in the collision layer.
+    BlockedTile,
+    /// NPC waypoint that cannot be walked to from the NPC's first waypoint.
+    UnreachableWaypoint,
+    /// Activation rectangle of a wandering NPC without a walkable tile.
+    NoRoamingRoom,
+    /// Extra whose footprint shares tiles with another extra's.
+    FootprintOverlap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found with an entity placement.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlacementIssue {
+    pub kind: IssueKind,
+    pub severity: Severity,
+    pub entity: PlacedKind,
+    /// Record index in the entity's `.ref` file.
+    pub record: i32,
+    /// Entity name, for display.
+    pub label: String,
+    /// Offending tile.
+    pub tile_x: i32,
+    pub tile_y: i32,
+    pub message: String,
+}
+
+impl std::fmt::Display for PlacementIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{severity}: {} #{}", self.entity, self.record)?;
+        if !self.label.is_empty() {
+            write!(f, " \"{}\"", self.label)?;
+        }
+        write!(
+            f,
+            " at ({}, {}): {}",
+            self.tile_x, self.tile_y, self.message
+        )
+    }
+}
+
+/// Check one map's monster, NPC and extra placements against its collision
+/// grid. Issues are ordered by entity layer, then record.
+pub fn validate_placements(
+    data: &MapData,
+    monsters: &[MonsterRef],
+    npcs: &[NPC],
+    extras: &[ExtraRef],
+) -> Vec<PlacementIssue> {
+    let grid = CollisionGrid::new(data);
+    let mut issues = Vec::new();
+
+    for monster in monsters {
+        let issue = |kind, tile: (i32, i32), message: String| PlacementIssue {
+            kind,
+            severity: Severity::Error,
+            entity: PlacedKind::Monster,
+            record: monster.index,
+            label: String::new(),
+            tile_x: tile.0,
+            tile_y: tile.1,
+            message,
+        };
+        let tile = (monster.map_x, monster.map_y);
+        if let Some((kind, message)) = tile_problem(&grid, tile) {
+            issues.push(issue(kind, tile, message));
+        }
+    }
+
+    for npc in npcs {
+        let issue = |kind, severity, tile: (i32, i32), message: String| PlacementIssue {
+            kind,
+            severity,
+            entity: PlacedKind::Npc,
+            record: npc.index,
+            label: npc.name.clone(),
+            tile_x: tile.0,
+            tile_y: tile.1,
+            message,
+        };
+        let waypoints: Vec<(usize, (i32, i32))> = npc_waypoints(npc);
+        let mut start = None;
+        for &(slot, tile) in &waypoints {
+            if let Some((kind, message)) = tile_problem(&grid, tile) {
+                issues.push(issue(
+                    kind,
+                    Severity::Error,
+                    tile,
+                    format!("waypoint {slot} {message}"),
+                ));
+                continue;
+            }
+            let Some(from) = start else {
+                start = Some(tile);
+                continue;
+            };
+            if npc.movement_mode == NpcMovementMode::Waypoints && !grid.connected(from, tile) {
+                issues.push(issue(
+                    IssueKind::UnreachableWaypoint,
+                    Severity::Error,
+                    tile,
+                    format!(
+                        "waypoint {slot} cannot be reached from ({}, {})",
+                        from.0, from.1
+                    ),
+                ));
+            }
+        }
+
+        if npc.movement_mode == NpcMovementMode::RandomInActivationRect {
+            let (x1, x2) = min_max(npc.activation_rect_x1, npc.activation_rect_x2);
+            let (y1, y2) = min_max(npc.activation_rect_y1, npc.activation_rect_y2);
+            let corner = (x1, y1);
+            if !grid.in_bounds((x1, y1)) || !grid.in_bounds((x2, y2)) {
+                issues.push(issue(
+                    IssueKind::OutOfBounds,
+                    Severity::Warning,
+                    corner,
+                    format!("activation rectangle ({x1}, {y1})-({x2}, {y2}) leaves the map"),
+                ));
+            }
+            let roomy = (y1..=y2).any(|y| (x1..=x2).any(|x| grid.is_walkable((x, y))));
+            if !roomy {
+                issues.push(issue(
+                    IssueKind::NoRoamingRoom,
+                    Severity::Error,
+                    corner,
+                    format!("activation rectangle ({x1}, {y1})-({x2}, {y2}) has no walkable tile"),
+                ));
+            }
+        }
+    }
+
+    let mut occupied: HashMap<(i32, i32), i32> = HashMap::new();
+    for extra in extras {
+        let issue = |kind, severity, tile: (i32, i32), message: String| PlacementIssue {
+            kind,
+            severity,
+            entity: PlacedKind::Extra,
+            record: extra.record_index,
+            label: extra.object_name.clone(),
+            tile_x: tile.0,
+            tile_y: tile.1,
+            message,
+        };
+        let tile = (extra.map_x, extra.map_y);
+        match tile_problem(&grid, tile) {
+            Some((IssueKind::BlockedTile, message)) => {
+                issues.push(issue(
+                    IssueKind::BlockedTile,
+                    Severity::Warning,
+                    tile,
+                    message,
+                ));
+            }
+            Some((kind, message)) => {
+                issues.push(issue(kind, Severity::Error, tile, message));
+                continue;
+            }
+            None => {}
+        }
+
+        let mut overlapped: Vec<i32> = Vec::new();
+        let mut first_shared = None;
+        for cell in extra_footprint(extra) {
+            match occupied.get(&cell) {
+                Some(&other) => {
+                    if !overlapped.contains(&other) {
+                        overlapped.push(other);
+                    }
+                    first_shared.get_or_insert(cell);
+                }
+                None => {
+                    occupied.insert(cell, extra.record_index);
+                }
+            }
+        }
+        if let Some(cell) = first_shared {
+            let others: Vec<String> = overlapped.iter().map(|id| format!("#{id}")).collect();
+            issues.push(issue(
+                IssueKind::FootprintOverlap,
+                Severity::Warning,
+                cell,
+                format!("footprint overlaps extra {}", others.join(", ")),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Parse `map_path` and validate the entities `Ref/Map.ini` assigns to it.
+pub fn validate_map(map_path: &Path, game_path: &Path) -> Result<Vec<PlacementIssue>> {
+    let mut reader = BufReader::new(File::open(map_path)?);
+    let data = read_map_data(&mut reader)?;
+    let map_id = map_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("map");
+    let entities = collect_external_entities(map_id, game_path, &data.model)?;
+    Ok(validate_placements(
+        &data,
+        &entities.monster_records,
+        &entities.npc_records,
+        &entities.extra_records,
+    ))
+}
+
+/// Why an entity cannot stand on `tile`, if it cannot.
+fn tile_problem(grid: &CollisionGrid, tile: (i32, i32)) -> Option<(IssueKind, String)> {
+    if !grid.in_bounds(tile) {
+        return Some((
+            IssueKind::OutOfBounds,
+            format!("is outside the {}x{} map", grid.width(), grid.height()),
+        ));
+    }
+    if !grid.is_walkable(tile) {
+        return Some((IssueKind::BlockedTile, "is on a blocked tile".to_string()));
+    }
+    None
+}
+
+/// Filled waypoints of an NPC as `(slot, tile)`, slot counted from 1.
+pub(crate) fn npc_waypoints(npc: &NPC) -> Vec<(usize, (i32, i32))> {
+    [
+        (npc.goto1_filled, npc.goto1_x, npc.goto1_y),
+        (npc.goto2_filled, npc.goto2_x, npc.goto2_y),
+        (npc.goto3_filled, npc.goto3_x, npc.goto3_y),
+        (npc.goto4_filled, npc.goto4_x, npc.goto4_y),
+    ]
+    .into_iter()
+    .enumerate()
+    .filter(|(_, (filled, _, _))| i32::from(*filled) != 0)
+    .map(|(slot, (_, x, y))| (slot + 1, (x, y)))
+    .collect()
+}
+
+/// Tiles covered by an extra: `footprint_width` × `footprint_height` tiles
+/// growing from its position along +x/+y, with the axes swapped when
+/// `footprint_orientation` is set. A zero dimension covers nothing.
+pub fn extra_footprint(extra: &ExtraRef) -> Vec<(i32, i32)> {
+    let mut width = extra.footprint_width as i32;
+    let mut height = extra.footprint_height as i32;
+    if extra.footprint_orientation != 0 {
+        std::mem::swap(&mut width, &mut height);
+    }
+    (0..height)
+        .flat_map(|dy| (0..width).map(move |dx| (extra.map_x + dx, extra.map_y + dy)))
+        .collect()
+}
+
+pub(crate) fn min_max(a: i32, b: i32) -> (i32, i32) {
+    (a.min(b), a.max(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::model::MapModel;
+    use crate::references::enums::{BooleanFlag, SmallRange0to3};
+
+    /// 6×4 map with a wall along x = 3 (open at y = 0 unless `sealed`).
+    fn map(sealed: bool) -> MapData {
+        let mut collisions = HashMap::new();
+        for y in 0..4 {
+            for x in 0..6 {
+                let wall = x == 3 && (sealed || y > 0);
+                collisions.insert((x, y), wall);
+            }
+        }
+        MapData {
+            model: MapModel {
+                tiled_map_width: 6,
+                tiled_map_height: 4,
+                ..Default::default()
+            },
+            gtl_tiles: HashMap::new(),
+            btl_tiles: HashMap::new(),
+            collisions,
+            events: HashMap::new(),
+            tiled_infos: Vec::new(),
+            internal_sprites: Vec::new(),
+            sprite_blocks: Vec::new(),
+        }
+    }
+
+    fn monster(index: i32, x: i32, y: i32) -> MonsterRef {
+        MonsterRef {
+            index,
+            map_x: x,
+            map_y: y,
+            ..Default::default()
+        }
+    }
+
+    fn patrol(from: (i32, i32), to: (i32, i32)) -> NPC {
+        NPC {
+            name: "Guard".into(),
+            movement_mode: NpcMovementMode::Waypoints,
+            goto1_filled: BooleanFlag::True,
+            goto1_x: from.0,
+            goto1_y: from.1,
+            goto2_filled: BooleanFlag::True,
+            goto2_x: to.0,
+            goto2_y: to.1,
+            ..Default::default()
+        }
+    }
+
+    fn extra(index: i32, x: i32, y: i32, w: SmallRange0to3, h: SmallRange0to3) -> ExtraRef {
+        ExtraRef {
+            record_index: index,
+            map_x: x,
+            map_y: y,
+            footprint_width: w,
+            footprint_height: h,
+            ..Default::default()
+        }
+    }
+
+    fn kinds(issues: &[PlacementIssue]) -> Vec<(PlacedKind, i32, IssueKind)> {
+        issues
+            .iter()
+            .map(|issue| (issue.entity, issue.record, issue.kind))
+            .collect()
+    }
+
+    #[test]
+    fn reports_blocked_and_out_of_bounds_monsters() {
+        let monsters = [monster(0, 1, 1), monster(1, 3, 2), monster(2, 6, 0)];
+        let issues = validate_placements(&map(false), &monsters, &[], &[]);
+        assert_eq!(
+            kinds(&issues),
+            [
+                (PlacedKind::Monster, 1, IssueKind::BlockedTile),
+                (PlacedKind::Monster, 2, IssueKind::OutOfBounds),
+            ]
+        );
+        assert_eq!(issues[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn waypoints_behind_a_sealed_wall_are_unreachable() {
+        let npcs = [patrol((0, 3), (5, 3))];
+        assert!(validate_placements(&map(false), &[], &npcs, &[]).is_empty());
+
+        let issues = validate_placements(&map(true), &[], &npcs, &[]);
+        assert_eq!(
+            kinds(&issues),
+            [(PlacedKind::Npc, 0, IssueKind::UnreachableWaypoint)]
+        );
+        assert_eq!((issues[0].tile_x, issues[0].tile_y), (5, 3));
+    }
+
+    #[test]
+    fn diagonal_steps_do_not_cut_corners() {
+        let mut data = map(false);
+        data.model.tiled_map_width = 2;
+        data.model.tiled_map_height = 2;
+        data.collisions = HashMap::from([
+            ((0, 0), false),
+            ((1, 0), true),
+            ((0, 1), true),
+            ((1, 1), false),
+        ]);
+        let npcs = [patrol((0, 0), (1, 1))];
+        let issues = validate_placements(&data, &[], &npcs, &[]);
+        assert_eq!(
+            kinds(&issues),
+            [(PlacedKind::Npc, 0, IssueKind::UnreachableWaypoint)]
+        );
+    }
+
+    #[test]
+    fn overlapping_footprints_and_blocked_extras_warn() {
+        use SmallRange0to3::{Value0, Value2};
+        let extras = [
+            extra(0, 0, 0, Value2, Value2),
+            extra(1, 1, 1, Value2, Value2),
+            extra(2, 3, 1, Value0, Value0),
+        ];
+        let issues = validate_placements(&map(false), &[], &[], &extras);
+        assert_eq!(
+            kinds(&issues),
+            [
+                (PlacedKind::Extra, 1, IssueKind::FootprintOverlap),
+                (PlacedKind::Extra, 2, IssueKind::BlockedTile),
+            ]
+        );
+        assert!(issues.iter().all(|i| i.severity == Severity::Warning));
+        assert_eq!((issues[0].tile_x, issues[0].tile_y), (1, 1));
+    }
+
+    #[test]
+    fn wandering_npc_needs_a_walkable_tile_in_its_rectangle() {
+        let npc = NPC {
+            movement_mode: NpcMovementMode::RandomInActivationRect,
+            activation_rect_x1: 3,
+            activation_rect_y1: 1,
+            activation_rect_x2: 3,
+            activation_rect_y2: 3,
+            ..Default::default()
+        };
+        let issues = validate_placements(&map(false), &[], &[npc], &[]);
+        assert_eq!(
+            kinds(&issues),
+            [(PlacedKind::Npc, 0, IssueKind::NoRoamingRoom)]
+        );
+    }
+}
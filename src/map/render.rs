@@ -40,6 +40,9 @@ pub struct LayerToggles {
     pub show_events: bool,
     pub show_draw_items: bool,
     pub show_npc_waypoints: bool,
+    /// Heat-map of walking distance from the `Map.ini` start position.
+    /// Needs the game path to find the start.
+    pub show_reachability: bool,
 }
 
 impl Default for LayerToggles {
@@ -58,6 +61,7 @@ impl Default for LayerToggles {
             show_events: false,
             show_draw_items: false,
             show_npc_waypoints: false,
+            show_reachability: false,
         }
     }
 }
@@ -84,8 +88,14 @@ pub struct ExternalEntities {
     pub extras: Vec<EntityRenderInfo>,
     /// Full NPC records kept for waypoint overlay rendering.
     pub npc_records: Vec<crate::references::npc_ref::NPC>,
+    /// Full monster placement records, parallel to `monsters`.
+    pub monster_records: Vec<crate::references::monster_ref::MonsterRef>,
+    /// Full extra placement records, parallel to `extras`.
+    pub extra_records: Vec<crate::references::extra_ref::ExtraRef>,
     /// Draw items placed on this map.
     pub draw_items: Vec<crate::references::draw_item::DrawItem>,
+    /// Player start tile from `Map.ini`.
+    pub start_pos: Coords,
 }
 
 // --------------------------------------------------------------------------
@@ -111,7 +121,8 @@ pub struct MapRenderConfig<'a> {
 /// 1. Ground tiles (if `show_ground`)
 /// 2. Interleaved objects + entities sorted by Y-depth (if their toggles are on)
 /// 3. Roof tiles (if `show_roofs`)
-/// 4. Overlays: collisions, events, draw items, NPC waypoints (if their toggles are on)
+/// 4. Overlays: collisions, events, draw items, NPC waypoints, reachability
+///    (if their toggles are on)
 pub fn render_map(config: MapRenderConfig) -> Result<()> {
     let MapRenderConfig {
         reader,
@@ -359,6 +370,17 @@ pub fn render_map(config: MapRenderConfig) -> Result<()> {
         );
     }
 
+    if toggles.show_reachability {
+        match external {
+            Some(ref ext) => {
+                plot_reachability_overlay(&mut imgbuf, data, ext.start_pos, occlusion, diagonal)
+            }
+            None => {
+                eprintln!("Warning: reachability overlay needs the map's Map.ini entry; skipped")
+            }
+        }
+    }
+
     // ── Save: RGBA PNG (transparent) or RGB PNG (solid black) ───────────
     if toggles.transparent {
         let (w, h) = imgbuf.dimensions();
@@ -650,6 +672,7 @@ pub fn collect_external_entities(
 
     let diagonal = model.tiled_map_width + model.tiled_map_height;
     let _ = diagonal; // used for resolving offsets, kept for consistency
+    let start_pos = (map_ini.start_pos_x, map_ini.start_pos_y);
 
     let resolve = |dir: &str, filename: &str| -> PathBuf {
         let upper = filename.to_ascii_uppercase();
@@ -700,6 +723,8 @@ pub fn collect_external_entities(
     let mut npcs = Vec::new();
     let mut extras = Vec::new();
     let mut npc_records = Vec::new();
+    let mut monster_records = Vec::new();
+    let mut extra_records = Vec::new();
 
     if let Some(f) = map_ini.monsters_filename {
         let p = resolve("MonsterInGame", &f);
@@ -716,6 +741,7 @@ pub fn collect_external_entities(
                     sequence: 3,
                     flip: false,
                 });
+                monster_records.push(m);
             }
         }
     }
@@ -783,6 +809,7 @@ pub fn collect_external_entities(
                     sequence: seq,
                     flip: false,
                 });
+                extra_records.push(e);
             }
         }
     }
@@ -804,7 +831,10 @@ pub fn collect_external_entities(
         npcs,
         extras,
         npc_records,
+        monster_records,
+        extra_records,
         draw_items,
+        start_pos,
     })
 }
 
@@ -1379,6 +1409,70 @@ pub fn plot_atlas_tile(params: AtlasTileParams) {
     }
 }
 
+// --------------------------------------------------------------------------
+// Reachability overlay — walking distance from the start position
+// --------------------------------------------------------------------------
+
+/// Heat colour for a reached tile, `t` in `0.0..=1.0` from the start (green)
+/// through yellow to the farthest tile (red).
+fn reachability_color(t: f32) -> [u8; 3] {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        [(t * 2.0 * 255.0) as u8, 200, 40]
+    } else {
+        [255, ((1.0 - t) * 2.0 * 200.0) as u8, 40]
+    }
+}
+
+/// Tints every reached tile by its walking cost from `start`, greys out
+/// walkable tiles that cannot be reached and marks the start tile.
+fn plot_reachability_overlay(
+    imgbuf: &mut ImageBuffer<Rgb<u8>, Vec<u8>>,
+    data: &super::MapData,
+    start: Coords,
+    occlusion: bool,
+    diagonal: i32,
+) {
+    let model = &data.model;
+    let grid = super::pathfinding::CollisionGrid::new(data);
+    let field = grid.distances_from(start);
+    let max_cost = field.max_cost().unwrap_or(0).max(1) as f32;
+    let r = super::tileset::TILE_WIDTH as i32 / 4;
+
+    let center = |x: i32, y: i32| {
+        let (mut px, mut py) = convert_map_coords_to_image_coords(x, y, diagonal);
+        if occlusion {
+            px -= model.map_non_occluded_start_x;
+            py -= model.map_non_occluded_start_y;
+        }
+        (
+            px + super::tileset::TILE_WIDTH as i32 / 2,
+            py + TILE_HEIGHT as i32 / 2,
+        )
+    };
+
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let (cx, cy) = center(x, y);
+            match field.get((x, y)) {
+                Some(cost) => {
+                    let color = reachability_color(cost as f32 / max_cost);
+                    fill_diamond_blended(imgbuf, cx, cy, r, color, 90);
+                }
+                None if grid.is_walkable((x, y)) => {
+                    fill_diamond_blended(imgbuf, cx, cy, r, [90, 60, 120], 110);
+                }
+                None => {}
+            }
+        }
+    }
+
+    if grid.in_bounds(start) {
+        let (cx, cy) = center(start.0, start.1);
+        fill_circle_blended(imgbuf, cx, cy, 6, [255, 255, 255], 220);
+    }
+}
+
 // --------------------------------------------------------------------------
 // Tests
 // --------------------------------------------------------------------------
@@ -1491,3 +1585,12 @@ fn test_draw_number_clips_at_max_digits() {
     let has_white = img.pixels().any(|p| *p == Rgb([255, 255, 255]));
     assert!(has_white, "draw_number with max_digits should still render");
 }
+
+#[test]
+fn test_reachability_color_runs_green_to_red() {
+    assert_eq!(reachability_color(0.0), [0, 200, 40]);
+    assert_eq!(reachability_color(1.0), [255, 0, 40]);
+    let mid = reachability_color(0.5);
+    assert_eq!(mid[0], 255);
+    assert!(mid[1] > 150);
+}
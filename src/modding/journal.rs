@@ -0,0 +1,412 @@
+//! Project-wide, branching edit journal.
+//!
+//! Every field edit made in a project is appended as a [`ChangeAction`] on
+//! top of the current head. Undo moves the head back to the entry's parent
+//! without discarding anything; a new edit after an undo starts a branch.
+//! [`Journal::checkout`] can therefore return the project to any recorded
+//! point, walking up to the common ancestor and down the target branch.
+//!
+//! Each move is a [`JournalStep`]. [`JournalStep::write`] applies one to the
+//! game files through the [`PatcherRegistry`], so changes made in an editor
+//! that has since been closed can still be undone.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::change::{ChangeAction, ChangeOp};
+use super::error::{ModdingError, Result};
+use super::registry::PatcherRegistry;
+use super::value::Value;
+
+/// Edits of the same field closer together than this (seconds) are merged
+/// into one entry, so typing a name does not record every keystroke.
+pub const COALESCE_WINDOW_SECS: i64 = 2;
+
+/// One recorded edit and the entry it was made on top of.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub action: ChangeAction,
+    /// `None` for edits made on the unedited project.
+    pub parent: Option<usize>,
+}
+
+/// A single move of the journal head.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalStep {
+    /// Restore the `old` value of an applied entry.
+    Revert(ChangeAction),
+    /// Re-apply the `new` value of an entry.
+    Apply(ChangeAction),
+}
+
+impl JournalStep {
+    /// File, record, field and the value the step writes.
+    pub fn target(&self) -> Option<(&str, u32, &str, &Value)> {
+        let (action, reverting) = match self {
+            JournalStep::Revert(action) => (action, true),
+            JournalStep::Apply(action) => (action, false),
+        };
+        let ChangeOp::FieldDelta {
+            record_id,
+            field,
+            old,
+            new,
+        } = &action.op
+        else {
+            return None;
+        };
+        let value = if reverting { old } else { new };
+        Some((&action.file_path, *record_id, field, value))
+    }
+
+    /// Write the step's value into the game file under `game_dir`.
+    pub fn write(&self, game_dir: &Path, registry: &PatcherRegistry) -> Result<()> {
+        let (file_path, record_id, field, value) = self.target().ok_or_else(|| {
+            ModdingError::Malformed("journal entries must be field edits".to_string())
+        })?;
+        let patcher = registry.lookup(file_path).ok_or_else(|| {
+            ModdingError::Malformed(format!("no field patcher registered for `{file_path}`"))
+        })?;
+        let path = game_dir.join(file_path);
+        let bytes = std::fs::read(&path)?;
+        let new_bytes = patcher.apply_field(&bytes, record_id, field, value)?;
+        std::fs::write(&path, new_bytes)?;
+        Ok(())
+    }
+}
+
+/// Branching history of every edit in one project.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+    /// Latest applied entry; `None` when every entry is undone.
+    head: Option<usize>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a journal saved by [`save`](Self::save); a missing file is an
+    /// empty journal.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let journal: Self = serde_json::from_slice(&std::fs::read(path)?)?;
+        let dangling = journal
+            .entries
+            .iter()
+            .enumerate()
+            .any(|(id, entry)| entry.parent.is_some_and(|parent| parent >= id));
+        if dangling
+            || journal
+                .head
+                .is_some_and(|head| head >= journal.entries.len())
+        {
+            return Err(ModdingError::Malformed(format!(
+                "journal {} links to missing entries",
+                path.display()
+            )));
+        }
+        Ok(journal)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    pub fn head(&self) -> Option<usize> {
+        self.head
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Append an edit on top of the head and make it the new head. A field
+    /// edit repeating the head's field within [`COALESCE_WINDOW_SECS`] updates
+    /// the head instead, unless the head already has later branches.
+    /// Returns the id of the entry holding the edit.
+    pub fn record(&mut self, action: ChangeAction) -> usize {
+        if let Some(head) = self.head
+            && self.children(Some(head)).next().is_none()
+            && let Some(merged) = coalesce(&self.entries[head].action, &action)
+        {
+            self.entries[head].action = merged;
+            return head;
+        }
+        self.entries.push(JournalEntry {
+            action,
+            parent: self.head,
+        });
+        let id = self.entries.len() - 1;
+        self.head = Some(id);
+        id
+    }
+
+    /// Entries recorded directly on top of `id` (`None`: the unedited
+    /// project), oldest first.
+    pub fn children(&self, id: Option<usize>) -> impl Iterator<Item = usize> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(move |(_, entry)| entry.parent == id)
+            .map(|(child, _)| child)
+    }
+
+    /// Whether entry `id` is part of the current project state (on the path
+    /// from the head to the root).
+    pub fn is_applied(&self, id: usize) -> bool {
+        self.ancestors(self.head).contains(&id)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.head.is_some()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.redo_target().is_some()
+    }
+
+    /// Revert the head entry. Returns `Ok(false)` when nothing is applied.
+    pub fn undo<E>(
+        &mut self,
+        apply: impl FnMut(&JournalStep) -> std::result::Result<(), E>,
+    ) -> std::result::Result<bool, E> {
+        let Some(head) = self.head else {
+            return Ok(false);
+        };
+        self.checkout(self.entries[head].parent, apply)?;
+        Ok(true)
+    }
+
+    /// Re-apply the newest entry recorded on top of the head. Returns
+    /// `Ok(false)` when there is none.
+    pub fn redo<E>(
+        &mut self,
+        apply: impl FnMut(&JournalStep) -> std::result::Result<(), E>,
+    ) -> std::result::Result<bool, E> {
+        let Some(target) = self.redo_target() else {
+            return Ok(false);
+        };
+        self.checkout(Some(target), apply)?;
+        Ok(true)
+    }
+
+    /// Move the head to `target` (`None`: the unedited project), passing
+    /// each step to `apply` in order. The head follows every step that
+    /// succeeds, so after an error it still matches what was applied.
+    ///
+    /// # Panics
+    ///
+    /// If `target` is not an entry id.
+    pub fn checkout<E>(
+        &mut self,
+        target: Option<usize>,
+        mut apply: impl FnMut(&JournalStep) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        if let Some(target) = target {
+            assert!(
+                target < self.entries.len(),
+                "journal entry {target} does not exist"
+            );
+        }
+        let from = self.ancestors(self.head);
+        let to = self.ancestors(target);
+        let common = from.iter().copied().find(|id| to.contains(id));
+
+        for id in from.into_iter().take_while(|&id| Some(id) != common) {
+            let entry = &self.entries[id];
+            apply(&JournalStep::Revert(entry.action.clone()))?;
+            self.head = entry.parent;
+        }
+        let down: Vec<usize> = to
+            .into_iter()
+            .take_while(|&id| Some(id) != common)
+            .collect();
+        for id in down.into_iter().rev() {
+            apply(&JournalStep::Apply(self.entries[id].action.clone()))?;
+            self.head = Some(id);
+        }
+        Ok(())
+    }
+
+    fn redo_target(&self) -> Option<usize> {
+        self.children(self.head).last()
+    }
+
+    /// `id` and its ancestors, nearest first.
+    fn ancestors(&self, mut id: Option<usize>) -> Vec<usize> {
+        let mut chain = Vec::new();
+        while let Some(current) = id {
+            chain.push(current);
+            id = self.entries[current].parent;
+        }
+        chain
+    }
+}
+
+/// `earlier` extended by `later` when both edit the same field in quick
+/// succession.
+fn coalesce(earlier: &ChangeAction, later: &ChangeAction) -> Option<ChangeAction> {
+    let (
+        ChangeOp::FieldDelta {
+            record_id,
+            field,
+            old,
+            ..
+        },
+        ChangeOp::FieldDelta {
+            record_id: later_record,
+            field: later_field,
+            new,
+            ..
+        },
+    ) = (&earlier.op, &later.op)
+    else {
+        return None;
+    };
+    let same_field =
+        earlier.file_path == later.file_path && record_id == later_record && field == later_field;
+    if !same_field || later.timestamp - earlier.timestamp > COALESCE_WINDOW_SECS {
+        return None;
+    }
+    let mut merged = later.clone();
+    merged.op = ChangeOp::FieldDelta {
+        record_id: *record_id,
+        field: field.clone(),
+        old: old.clone(),
+        new: new.clone(),
+    };
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(field: &str, old: &str, new: &str) -> ChangeAction {
+        ChangeAction::new(
+            "CharacterInGame/MiscItem.db",
+            ChangeOp::FieldDelta {
+                record_id: 0,
+                field: field.into(),
+                old: Value::String(old.into()),
+                new: Value::String(new.into()),
+            },
+        )
+    }
+
+    fn values(steps: &[JournalStep]) -> Vec<String> {
+        steps
+            .iter()
+            .map(|step| {
+                let (_, _, field, value) = step.target().unwrap();
+                format!("{field}={value}")
+            })
+            .collect()
+    }
+
+    fn collect(journal: &mut Journal, target: Option<usize>) -> Vec<JournalStep> {
+        let mut steps = Vec::new();
+        journal
+            .checkout(target, |step| {
+                steps.push(step.clone());
+                Ok::<_, ()>(())
+            })
+            .unwrap();
+        steps
+    }
+
+    #[test]
+    fn record_after_undo_starts_a_branch() {
+        let mut journal = Journal::new();
+        let a = journal.record(edit("name", "A", "B"));
+        let b = journal.record(edit("price", "1", "2"));
+        assert!(journal.undo(|_| Ok::<_, ()>(())).unwrap());
+        let c = journal.record(edit("price", "1", "3"));
+
+        assert_eq!(journal.len(), 3);
+        assert_eq!(journal.children(Some(a)).collect::<Vec<_>>(), [b, c]);
+        assert!(journal.is_applied(c));
+        assert!(!journal.is_applied(b));
+
+        // Jumping to the other branch reverts `c` and re-applies `b`.
+        let steps = collect(&mut journal, Some(b));
+        assert_eq!(values(&steps), ["price=1", "price=2"]);
+        assert!(matches!(steps[0], JournalStep::Revert(_)));
+        assert!(matches!(steps[1], JournalStep::Apply(_)));
+        assert_eq!(journal.head(), Some(b));
+
+        let steps = collect(&mut journal, None);
+        assert_eq!(values(&steps), ["price=1", "name=A"]);
+        assert!(!journal.can_undo());
+        assert!(journal.can_redo());
+    }
+
+    #[test]
+    fn quick_edits_of_one_field_are_merged() {
+        let mut journal = Journal::new();
+        let first = journal.record(edit("name", "Helt", "Helm"));
+        let second = journal.record(edit("name", "Helm", "Helmet"));
+        assert_eq!(first, second);
+        assert_eq!(journal.len(), 1);
+        let ChangeOp::FieldDelta { old, new, .. } = &journal.entries()[0].action.op else {
+            unreachable!()
+        };
+        assert_eq!(old, &Value::String("Helt".into()));
+        assert_eq!(new, &Value::String("Helmet".into()));
+
+        let mut late = edit("name", "Helmet", "Hat");
+        late.timestamp += COALESCE_WINDOW_SECS + 1;
+        journal.record(late);
+        assert_eq!(journal.len(), 2);
+    }
+
+    #[test]
+    fn failed_step_leaves_head_at_last_applied_entry() {
+        let mut journal = Journal::new();
+        journal.record(edit("name", "A", "B"));
+        let b = journal.record(edit("price", "1", "2"));
+        let mut calls = 0;
+
+        let result = journal.checkout(None, |_| {
+            calls += 1;
+            if calls == 2 { Err("disk full") } else { Ok(()) }
+        });
+
+        assert_eq!(result, Err("disk full"));
+        assert_eq!(journal.head(), Some(journal.entries()[b].parent.unwrap()));
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journals/game.json");
+        let mut journal = Journal::new();
+        journal.record(edit("name", "A", "B"));
+        journal.save(&path).unwrap();
+
+        assert_eq!(Journal::load(&path).unwrap(), journal);
+        assert!(
+            Journal::load(&dir.path().join("missing.json"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
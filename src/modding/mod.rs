@@ -12,6 +12,7 @@ pub mod change;
 pub mod changelog;
 pub mod conflicts;
 pub mod error;
+pub mod journal;
 pub mod manifest;
 pub mod package;
 pub mod patcher;
@@ -28,6 +29,7 @@ pub use change::{BlobKind, ChangeAction, ChangeOp};
 pub use changelog::{ChangeLog, HISTORY_CAP};
 pub use conflicts::{Conflict, ConflictKind, ConflictParticipant, detect_conflicts};
 pub use error::{ModdingError, Result};
+pub use journal::{Journal, JournalEntry, JournalStep};
 pub use manifest::{MANIFEST_VERSION, ModManifest};
 pub use package::{ModPackage, read_zip, write_zip};
 pub use patcher::RecordPatcher;
@@ -2,11 +2,12 @@
 //!
 //! Hand-written because [`Store`] has a conditional structure: when
 //! `inn_night_cost > 0` the record is an inn (144 bytes of padding,
-//! no products); otherwise it's a shop with up to 71 `(type, id)`
-//! product pairs. Field patching only covers the scalar fields —
-//! mutating the product list is best done by replacing the whole
-//! file via `FileReplace`, since rebalancing 71 packed slots from
-//! a single `FieldDelta` would be fragile.
+//! no products); otherwise it's a shop with up to 15 `(type, id)`
+//! product pairs. The `products` field takes the whole table at once as
+//! a `type:id` list (`"weapon:3, healing:0"`, see [`ProductSlot`]) and
+//! goes through [`Store::set_products`], so the slot limit and the inn
+//! layout are checked before anything is written. Turning a shop into
+//! an inn drops its products, as the file has no room for them.
 
 use std::io::Cursor;
 
@@ -15,6 +16,7 @@ use crate::modding::patcher::{RecordPatcher, out_of_range, unknown_field, wrong_
 use crate::modding::value::Value;
 use crate::references::extractor::Extractor;
 use crate::references::store_db::Store;
+use crate::references::store_inventory::ProductSlot;
 
 pub struct StorePatcher;
 
@@ -49,7 +51,12 @@ impl RecordPatcher for StorePatcher {
             "invitation" => rec.invitation = parse_string(field, new)?,
             "haggle_success" => rec.haggle_success = parse_string(field, new)?,
             "haggle_fail" => rec.haggle_fail = parse_string(field, new)?,
-            "inn_night_cost" => rec.inn_night_cost = parse_i32(field, new)?,
+            "inn_night_cost" => {
+                rec.inn_night_cost = parse_i32(field, new)?;
+                if rec.is_inn() {
+                    rec.products.clear();
+                }
+            }
             "price_modifier" => rec.price_modifier = parse_i16(field, new)?,
             "index" => {
                 return Err(ModdingError::Malformed(format!(
@@ -58,11 +65,19 @@ impl RecordPatcher for StorePatcher {
                 )));
             }
             "products" => {
-                return Err(ModdingError::Malformed(format!(
-                    "{}.products is a structured list; replace the whole \
-                     Store.db via FileReplace to change inventories",
-                    Self::RECORD_NAME
-                )));
+                let slots = match new {
+                    Value::String(s) => ProductSlot::parse_list(s),
+                    Value::Null => Ok(Vec::new()),
+                    _ => return Err(wrong_type(Self::RECORD_NAME, field, "string", new)),
+                };
+                slots
+                    .and_then(|slots| rec.set_products(slots))
+                    .map_err(|e| {
+                        ModdingError::Malformed(format!(
+                            "{}[{record_id}].products: {e}",
+                            Self::RECORD_NAME
+                        ))
+                    })?;
             }
             other => return Err(unknown_field(Self::RECORD_NAME, other)),
         }
@@ -179,13 +194,58 @@ mod tests {
         assert_eq!(parse_back(&out)[0].haggle_fail, "Get out!");
     }
 
+    fn one_shop_blob(name: &str) -> Vec<u8> {
+        let shop = Store {
+            store_name: name.into(),
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        Store::to_writer(&[shop], &mut data).unwrap();
+        data
+    }
+
     #[test]
-    fn products_field_rejected_with_helpful_message() {
+    fn products_replaced_from_slot_list() {
+        let p = StorePatcher;
+        let out = p
+            .apply_field(
+                &one_shop_blob("Smithy"),
+                0,
+                "products",
+                &Value::String("weapon:3, healing:7".into()),
+            )
+            .unwrap();
+        let recs = parse_back(&out);
+        assert_eq!(
+            recs[0].product_slots(),
+            ProductSlot::parse_list("weapon:3, healing:7").unwrap()
+        );
+        assert_eq!(recs[0].products[1].0, 1);
+    }
+
+    #[test]
+    fn products_rejected_for_inns_and_overflow() {
         let p = StorePatcher;
         let err = p
-            .apply_field(&one_inn_blob("Tavern", 50), 0, "products", &Value::Null)
+            .apply_field(
+                &one_inn_blob("Tavern", 50),
+                0,
+                "products",
+                &Value::String("weapon:1".into()),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("inns"));
+
+        let sixteen = vec!["misc:1"; 16].join(",");
+        let err = p
+            .apply_field(
+                &one_shop_blob("Smithy"),
+                0,
+                "products",
+                &Value::String(sixteen),
+            )
             .unwrap_err();
-        assert!(err.to_string().contains("FileReplace"));
+        assert!(err.to_string().contains("at most 15"));
     }
 
     #[test]
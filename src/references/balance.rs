@@ -0,0 +1,758 @@
+//! Economy and balance report.
+//!
+//! Joins the item catalogs, `STORE.DB`, `Monster.db`, `PrtLevel.db` and the
+//! monster/extra placements of every map into tables designers can sort and
+//! chart:
+//!
+//! - **maps** — per map in `AllMap.ini` order: monster count, expected XP
+//!   and gold, chest gold, loot value, running totals of XP and income, and
+//!   how many shop products the running income can buy;
+//! - **levels** — the party stat curve from `PrtLevel.db`, averaged over the
+//!   party members that have the level. The XP needed per level is not in
+//!   the data files, so the curve is meant to be read against the running
+//!   XP of the maps table;
+//! - **shop_items** — every store product with its buy and sell price;
+//! - **price_outliers** — weapons, edit items and heal items whose price per
+//!   point of stats is far from the median of their catalog.
+//!
+//! Expected values use the midpoint of each min/max range. A monster that
+//! drops one of its loot slots counts the mean slot value, one with
+//! `drop_all_loot` the sum; drop chances are not modelled. Loot counts as
+//! income at its sell price. Only the primary loot slot of an extra is
+//! valued, since the encoding of the additional slots is not mapped yet.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::map::atlas::{discover_maps, html_escape, resolve_case_insensitive};
+use crate::references::chdata_db::ChData;
+use crate::references::draw_item::DrawItem;
+use crate::references::edit_item_db::EditItem;
+use crate::references::enums::{InventoryItem, ItemTypeId, TriStateFlag};
+use crate::references::event_item_db::EventItem;
+use crate::references::extra_ref::ExtraRef;
+use crate::references::extractor::read_or_warn;
+use crate::references::heal_item_db::HealItem;
+use crate::references::misc_item_db::MiscItem;
+use crate::references::monster_db::Monster;
+use crate::references::monster_ref::MonsterRef;
+use crate::references::npc_ref::NPC;
+use crate::references::party_level_db::PartyLevelNpc;
+use crate::references::store_db::Store;
+use crate::references::store_inventory::{StoreCatalog, store_sell_price};
+use crate::references::usages::RecordKind;
+use crate::references::weapons_db::WeaponItem;
+
+/// An item is an outlier when its price per stat point is this many times
+/// above or below the median of its catalog.
+pub const OUTLIER_FACTOR: f64 = 2.0;
+
+/// HP and MP points count this much towards item power, relative to one
+/// point of attack, defense or an attribute.
+const POOL_POINT_WEIGHT: f64 = 0.2;
+
+/// Monster, extra and NPC placements of one map.
+#[derive(Debug, Clone, Default)]
+pub struct MapPlacements {
+    /// `AllMap.ini` id, which `DRAWITEM.ref` placements refer to.
+    pub map_id: i32,
+    pub map_filename: String,
+    pub map_name: String,
+    pub monsters: Vec<MonsterRef>,
+    pub extras: Vec<ExtraRef>,
+    pub npcs: Vec<NPC>,
+    /// The placement files read, relative to the game directory.
+    pub monster_file: Option<String>,
+    pub extra_file: Option<String>,
+    pub npc_file: Option<String>,
+}
+
+/// Everything the balance, difficulty and loot reports read from a game
+/// directory.
+#[derive(Debug, Clone, Default)]
+pub struct BalanceInputs {
+    pub weapons: Vec<WeaponItem>,
+    pub heal_items: Vec<HealItem>,
+    pub edit_items: Vec<EditItem>,
+    pub misc_items: Vec<MiscItem>,
+    pub event_items: Vec<EventItem>,
+    pub stores: Vec<Store>,
+    pub monsters: Vec<Monster>,
+    pub party_levels: Vec<PartyLevelNpc>,
+    /// Items lying on maps (`Ref/DRAWITEM.ref`).
+    pub draw_items: Vec<DrawItem>,
+    /// Class attributes at character creation (`ChData.db`, one record).
+    pub ch_data: Option<ChData>,
+    /// In `AllMap.ini` order.
+    pub maps: Vec<MapPlacements>,
+    /// Files that were missing or failed to parse.
+    pub warnings: Vec<String>,
+}
+
+impl BalanceInputs {
+    /// Read every input under `game_dir`. Missing or unreadable files leave
+    /// their catalog empty and add a warning.
+    pub fn load(game_dir: &Path) -> Self {
+        let mut inputs = Self::default();
+        let mut warnings = Vec::new();
+        let read = |dir: &str, name: &str| resolve_case_insensitive(game_dir, dir, name);
+
+        inputs.weapons = read_or_warn(&read("CharacterInGame", "weaponItem.db"), &mut warnings);
+        inputs.heal_items = read_or_warn(&read("CharacterInGame", "HealItem.db"), &mut warnings);
+        inputs.edit_items = read_or_warn(&read("CharacterInGame", "EditItem.db"), &mut warnings);
+        inputs.misc_items = read_or_warn(&read("CharacterInGame", "MiscItem.db"), &mut warnings);
+        inputs.event_items = read_or_warn(&read("CharacterInGame", "EventItem.db"), &mut warnings);
+        inputs.stores = read_or_warn(&read("CharacterInGame", "STORE.DB"), &mut warnings);
+        inputs.monsters = read_or_warn(&read("MonsterInGame", "Monster.db"), &mut warnings);
+        inputs.party_levels = read_or_warn(&read("NpcInGame", "PrtLevel.db"), &mut warnings);
+        inputs.ch_data = read_or_warn(&read("CharacterInGame", "ChData.db"), &mut warnings)
+            .into_iter()
+            .next();
+        inputs.draw_items = read_or_warn(&read("Ref", "DRAWITEM.ref"), &mut warnings);
+
+        match discover_maps(game_dir) {
+            Ok(maps) => {
+                for m in maps {
+                    let mut placements = MapPlacements {
+                        map_id: m.map.id,
+                        map_filename: m.map.map_filename.clone(),
+                        map_name: m.map.map_name.clone(),
+                        ..Default::default()
+                    };
+                    if let Some(ini) = &m.map_ini {
+                        if let Some(f) = &ini.monsters_filename {
+                            placements.monsters =
+                                read_or_warn(&read("MonsterInGame", f), &mut warnings);
+                            placements.monster_file = Some(format!("MonsterInGame/{f}"));
+                        }
+                        if let Some(f) = &ini.extra_filename {
+                            placements.extras =
+                                read_or_warn(&read("ExtraInGame", f), &mut warnings);
+                            placements.extra_file = Some(format!("ExtraInGame/{f}"));
+                        }
+                        if let Some(f) = &ini.npc_filename {
+                            placements.npcs = read_or_warn(&read("NpcInGame", f), &mut warnings);
+                            placements.npc_file = Some(format!("NpcInGame/{f}"));
+                        }
+                    }
+                    inputs.maps.push(placements);
+                }
+            }
+            Err(e) => warnings.push(format!("AllMap.ini: {e}")),
+        }
+
+        inputs.warnings = warnings;
+        inputs
+    }
+
+    /// Base price of an inventory item, `None` for empty slots and unknown
+    /// items.
+    pub fn item_price(&self, item: InventoryItem) -> Option<i32> {
+        self.item(item).map(|(_, price)| price)
+    }
+
+    /// Name and base price of an inventory item, `None` for empty slots and
+    /// unknown items.
+    pub fn item(&self, item: InventoryItem) -> Option<(&str, i32)> {
+        if item.is_empty() {
+            return None;
+        }
+        let id = usize::from(item.item_id());
+        let (name, price) = match item.item_type()? {
+            ItemTypeId::Weapon => self.weapons.get(id).map(|i| (&i.name, i.base_price)),
+            ItemTypeId::Healing => self.heal_items.get(id).map(|i| (&i.name, i.base_price)),
+            ItemTypeId::Edit => self.edit_items.get(id).map(|i| (&i.name, i.base_price)),
+            ItemTypeId::Event => self.event_items.get(id).map(|i| (&i.name, i.base_price)),
+            ItemTypeId::Misc => self.misc_items.get(id).map(|i| (&i.name, i.base_price)),
+            ItemTypeId::Other => None,
+        }?;
+        Some((name.as_str(), price))
+    }
+
+    /// The item databases store products resolve against.
+    pub fn store_catalog(&self) -> StoreCatalog<'_> {
+        StoreCatalog::new(
+            &self.weapons,
+            &self.heal_items,
+            &self.edit_items,
+            &self.misc_items,
+        )
+    }
+}
+
+/// One map's income and threat.
+#[derive(Debug, Clone, Serialize)]
+pub struct MapBalance {
+    pub order: usize,
+    pub map_filename: String,
+    pub map_name: String,
+    pub monsters: usize,
+    pub monster_xp: f64,
+    pub cumulative_xp: f64,
+    pub monster_gold: f64,
+    pub chest_gold: i64,
+    /// Sell value of monster and chest loot.
+    pub loot_value: f64,
+    pub income: f64,
+    pub cumulative_income: f64,
+    pub avg_monster_hp: f64,
+    pub max_monster_offense: i32,
+    /// Store products whose buy price is at most the cumulative income.
+    pub affordable_products: usize,
+}
+
+/// Party stats at one level, averaged over the members that have it.
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelBalance {
+    pub level: u32,
+    pub members: usize,
+    pub health_points: f64,
+    pub mana_points: f64,
+    pub strength: f64,
+    pub constitution: f64,
+    pub wisdom: f64,
+    pub agility: f64,
+    pub attack: f64,
+}
+
+/// One store product with its prices after the store's modifier.
+#[derive(Debug, Clone, Serialize)]
+pub struct ShopItem {
+    pub store: String,
+    pub product_type: String,
+    pub item_id: i16,
+    pub item_name: String,
+    pub base_price: i32,
+    pub buy_price: i32,
+    pub sell_price: i32,
+}
+
+/// An item priced far from its catalog's median price per stat point.
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceOutlier {
+    pub catalog: String,
+    pub item_id: usize,
+    pub item_name: String,
+    pub base_price: i32,
+    pub power: f64,
+    pub price_per_power: f64,
+    pub catalog_median: f64,
+    /// `price_per_power / catalog_median`.
+    pub factor: f64,
+}
+
+/// All tables of the report.
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceReport {
+    pub maps: Vec<MapBalance>,
+    pub levels: Vec<LevelBalance>,
+    pub shop_items: Vec<ShopItem>,
+    pub price_outliers: Vec<PriceOutlier>,
+    pub warnings: Vec<String>,
+}
+
+pub(crate) fn midpoint(min: i32, max: i32) -> f64 {
+    (f64::from(min) + f64::from(max)) / 2.0
+}
+
+impl BalanceReport {
+    pub fn build(inputs: &BalanceInputs) -> Self {
+        let shop_items = shop_items(inputs);
+        Self {
+            maps: map_rows(inputs, &shop_items),
+            levels: level_rows(inputs),
+            price_outliers: price_outliers(inputs),
+            shop_items,
+            warnings: inputs.warnings.clone(),
+        }
+    }
+
+    /// The report as `(file name, CSV text)` pairs, one per table.
+    pub fn to_csv_tables(&self) -> Result<Vec<(&'static str, String)>, csv::Error> {
+        Ok(vec![
+            ("maps.csv", to_csv(&self.maps)?),
+            ("levels.csv", to_csv(&self.levels)?),
+            ("shop_items.csv", to_csv(&self.shop_items)?),
+            ("price_outliers.csv", to_csv(&self.price_outliers)?),
+        ])
+    }
+
+    /// A self-contained HTML page with one table per section.
+    pub fn to_html(&self) -> String {
+        let mut html = html_page_start("Dispel balance report", &self.warnings);
+        push_html_table(&mut html, "Maps", &self.maps);
+        push_html_table(&mut html, "Party levels", &self.levels);
+        push_html_table(&mut html, "Shop items", &self.shop_items);
+        push_html_table(&mut html, "Price outliers", &self.price_outliers);
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Head of a report page: styles, `<h1>title</h1>` and the warnings.
+pub(crate) fn html_page_start(title: &str, warnings: &[String]) -> String {
+    let title = html_escape(title);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; background: #1e1e1e; color: #ddd; }}\n\
+         table {{ border-collapse: collapse; margin-bottom: 2em; }}\n\
+         td, th {{ border: 1px solid #444; padding: 4px 8px; }}\n\
+         td.num {{ text-align: right; }}\n\
+         .warning {{ color: #fc6; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    for warning in warnings {
+        html.push_str(&format!(
+            "<p class=\"warning\">{}</p>\n",
+            html_escape(warning)
+        ));
+    }
+    html
+}
+
+fn shop_items(inputs: &BalanceInputs) -> Vec<ShopItem> {
+    let mut rows = Vec::new();
+    let catalog = inputs.store_catalog();
+    for store in &inputs.stores {
+        for product in catalog.resolve(store) {
+            rows.push(ShopItem {
+                store: store.store_name.clone(),
+                product_type: format!("{:?}", product.slot.product_type),
+                item_id: product.slot.item_id,
+                item_name: product.name.unwrap_or_else(|| "?".to_string()),
+                base_price: product.base_price,
+                buy_price: product.buy_price,
+                sell_price: product.sell_price,
+            });
+        }
+    }
+    rows
+}
+
+fn map_rows(inputs: &BalanceInputs, shop: &[ShopItem]) -> Vec<MapBalance> {
+    let monsters: BTreeMap<i32, &Monster> = inputs.monsters.iter().map(|m| (m.id, m)).collect();
+    let sell = |base: i32| f64::from(store_sell_price(base, 0));
+    let mut cumulative_xp = 0.0;
+    let mut cumulative_income = 0.0;
+    let mut rows = Vec::new();
+
+    for (order, map) in inputs.maps.iter().enumerate() {
+        let mut monster_xp = 0.0;
+        let mut monster_gold = 0.0;
+        let mut loot_value = 0.0;
+        let mut hp_total = 0.0;
+        let mut hp_count = 0;
+        let mut max_monster_offense = 0;
+
+        for placement in &map.monsters {
+            if let Some(monster) = monsters.get(&placement.monster_db_id) {
+                monster_xp += midpoint(monster.exp_gain_min, monster.exp_gain_max);
+                monster_gold += midpoint(monster.gold_drop_min, monster.gold_drop_max);
+                hp_total += midpoint(monster.health_points_min, monster.health_points_max);
+                hp_count += 1;
+                max_monster_offense = max_monster_offense.max(monster.offense_max);
+            }
+            let slots: Vec<f64> = [
+                placement.loot_item_1,
+                placement.loot_item_2,
+                placement.loot_item_3,
+            ]
+            .into_iter()
+            .filter_map(|item| inputs.item_price(item))
+            .map(sell)
+            .collect();
+            if !slots.is_empty() {
+                let total: f64 = slots.iter().sum();
+                loot_value += if placement.drop_all_loot == TriStateFlag::One {
+                    total
+                } else {
+                    total / slots.len() as f64
+                };
+            }
+        }
+
+        let mut chest_gold = 0i64;
+        for extra in &map.extras {
+            chest_gold += i64::from(extra.gold_amount.max(0));
+            if let Some(price) = inputs.item_price(extra.loot_item) {
+                loot_value += sell(price) * f64::from(extra.loot_item_count.max(1));
+            }
+        }
+
+        let income = monster_gold + chest_gold as f64 + loot_value;
+        cumulative_xp += monster_xp;
+        cumulative_income += income;
+        rows.push(MapBalance {
+            order,
+            map_filename: map.map_filename.clone(),
+            map_name: map.map_name.clone(),
+            monsters: map.monsters.len(),
+            monster_xp,
+            cumulative_xp,
+            monster_gold,
+            chest_gold,
+            loot_value,
+            income,
+            cumulative_income,
+            avg_monster_hp: if hp_count == 0 {
+                0.0
+            } else {
+                hp_total / f64::from(hp_count)
+            },
+            max_monster_offense,
+            affordable_products: shop
+                .iter()
+                .filter(|item| f64::from(item.buy_price) <= cumulative_income)
+                .count(),
+        });
+    }
+    rows
+}
+
+fn level_rows(inputs: &BalanceInputs) -> Vec<LevelBalance> {
+    let mut by_level: BTreeMap<u32, Vec<_>> = BTreeMap::new();
+    for npc in &inputs.party_levels {
+        for record in &npc.records {
+            by_level.entry(record.level).or_default().push(record);
+        }
+    }
+    by_level
+        .into_iter()
+        .map(|(level, records)| {
+            let mean = |f: &dyn Fn(&crate::references::party_level_db::PartyLevelRecord) -> f64| {
+                records.iter().map(|r| f(r)).sum::<f64>() / records.len() as f64
+            };
+            LevelBalance {
+                level,
+                members: records.len(),
+                health_points: mean(&|r| f64::from(r.health_points)),
+                mana_points: mean(&|r| f64::from(r.mana_points)),
+                strength: mean(&|r| f64::from(r.strength)),
+                constitution: mean(&|r| f64::from(r.constitution)),
+                wisdom: mean(&|r| f64::from(r.wisdom)),
+                agility: mean(&|r| f64::from(r.agility)),
+                attack: mean(&|r| f64::from(r.attack)),
+            }
+        })
+        .collect()
+}
+
+fn weapon_power(w: &WeaponItem) -> f64 {
+    let points = [
+        w.attack,
+        w.defense,
+        w.magical_strength,
+        w.to_hit,
+        w.to_dodge,
+        w.strength,
+        w.agility,
+        w.wisdom,
+        w.constitution,
+    ];
+    let pools = f64::from(w.health_points) + f64::from(w.mana_points);
+    points.iter().map(|&p| f64::from(p)).sum::<f64>() + pools * POOL_POINT_WEIGHT
+}
+
+fn edit_item_power(e: &EditItem) -> f64 {
+    let points = [
+        e.offense,
+        e.defense,
+        e.magical_power,
+        e.to_hit,
+        e.to_dodge,
+        e.strength,
+        e.agility,
+        e.wisdom,
+        e.constitution,
+    ];
+    let pools = f64::from(e.health_points) + f64::from(e.mana_points);
+    points.iter().map(|&p| f64::from(p)).sum::<f64>() + pools * POOL_POINT_WEIGHT
+}
+
+/// Heal items are rated by the HP and MP they restore; full restores and
+/// pure cures have no comparable power and are left out.
+fn heal_item_power(h: &HealItem) -> f64 {
+    f64::from(h.health_points) + f64::from(h.mana_points)
+}
+
+fn price_outliers(inputs: &BalanceInputs) -> Vec<PriceOutlier> {
+    let mut outliers = Vec::new();
+    let weapons = inputs
+        .weapons
+        .iter()
+        .map(|w| (w.name.as_str(), w.base_price, weapon_power(w)));
+    catalog_outliers(RecordKind::WeaponItem, weapons, &mut outliers);
+    let edit_items = inputs
+        .edit_items
+        .iter()
+        .map(|e| (e.name.as_str(), e.base_price, edit_item_power(e)));
+    catalog_outliers(RecordKind::EditItem, edit_items, &mut outliers);
+    let heal_items = inputs
+        .heal_items
+        .iter()
+        .map(|h| (h.name.as_str(), h.base_price, heal_item_power(h)));
+    catalog_outliers(RecordKind::HealItem, heal_items, &mut outliers);
+    outliers
+}
+
+/// Compare each priced item with power to the catalog's median price per
+/// power. Items are `(name, base price, power)` in record order; the
+/// catalog is labelled with the kind's CLI key.
+fn catalog_outliers<'a>(
+    kind: RecordKind,
+    items: impl Iterator<Item = (&'a str, i32, f64)>,
+    out: &mut Vec<PriceOutlier>,
+) {
+    let rated: Vec<(usize, &str, i32, f64, f64)> = items
+        .enumerate()
+        .filter(|(_, (_, price, power))| *price > 0 && *power > 0.0)
+        .map(|(id, (name, price, power))| (id, name, price, power, f64::from(price) / power))
+        .collect();
+    let mut ratios: Vec<f64> = rated.iter().map(|r| r.4).collect();
+    ratios.sort_by(f64::total_cmp);
+    let Some(median) = median(&ratios) else {
+        return;
+    };
+    for (id, name, price, power, ratio) in rated {
+        let factor = ratio / median;
+        if !(1.0 / OUTLIER_FACTOR..=OUTLIER_FACTOR).contains(&factor) {
+            out.push(PriceOutlier {
+                catalog: kind.key().to_string(),
+                item_id: id,
+                item_name: name.to_string(),
+                base_price: price,
+                power,
+                price_per_power: ratio,
+                catalog_median: median,
+                factor,
+            });
+        }
+    }
+}
+
+fn median(sorted: &[f64]) -> Option<f64> {
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2]),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+    }
+}
+
+pub(crate) fn to_csv<T: Serialize>(rows: &[T]) -> Result<String, csv::Error> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        wtr.serialize(row)?;
+    }
+    let bytes = wtr.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Append `<h2>title</h2>` and a table of `rows`, columns in field order
+/// (taken from the rows' CSV serialization).
+pub(crate) fn push_html_table<T: Serialize>(html: &mut String, title: &str, rows: &[T]) {
+    html.push_str(&format!("<h2>{}</h2>\n", html_escape(title)));
+    if rows.is_empty() {
+        html.push_str("<p>No data.</p>\n");
+        return;
+    }
+    let Ok(text) = to_csv(rows) else {
+        return;
+    };
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    html.push_str("<table>\n<tr>");
+    if let Ok(headers) = reader.headers() {
+        for header in headers {
+            html.push_str(&format!("<th>{}</th>", html_escape(header)));
+        }
+    }
+    html.push_str("</tr>\n");
+    for record in reader.records().flatten() {
+        html.push_str("<tr>");
+        for cell in &record {
+            let class = if cell.parse::<f64>().is_ok() {
+                " class=\"num\""
+            } else {
+                ""
+            };
+            html.push_str(&format!("<td{class}>{}</td>", html_escape(cell)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::references::enums::ProductType;
+    use crate::references::party_level_db::PartyLevelRecord;
+
+    fn weapon(name: &str, price: i32, attack: i16) -> WeaponItem {
+        WeaponItem {
+            name: name.into(),
+            base_price: price,
+            attack,
+            ..Default::default()
+        }
+    }
+
+    fn monster(id: i32, xp: (i32, i32), gold: (i32, i32)) -> Monster {
+        Monster {
+            id,
+            exp_gain_min: xp.0,
+            exp_gain_max: xp.1,
+            gold_drop_min: gold.0,
+            gold_drop_max: gold.1,
+            health_points_min: 10,
+            health_points_max: 20,
+            offense_max: 7,
+            ..Default::default()
+        }
+    }
+
+    fn inputs() -> BalanceInputs {
+        BalanceInputs {
+            weapons: vec![
+                weapon("Dagger", 100, 10),
+                weapon("Sword", 220, 20),
+                weapon("Axe", 300, 30),
+                weapon("Gold Stick", 5000, 10),
+            ],
+            stores: vec![Store {
+                store_name: "Smith".into(),
+                price_modifier: 50,
+                products: vec![(0, ProductType::Weapon, 0), (1, ProductType::Weapon, 2)],
+                ..Default::default()
+            }],
+            monsters: vec![monster(1, (10, 30), (4, 6)), monster(2, (100, 100), (0, 0))],
+            maps: vec![
+                MapPlacements {
+                    map_filename: "cat1".into(),
+                    monsters: vec![
+                        MonsterRef {
+                            monster_db_id: 1,
+                            loot_item_1: InventoryItem::new(ItemTypeId::Weapon, 0),
+                            loot_item_2: InventoryItem::new(ItemTypeId::Weapon, 2),
+                            loot_item_3: InventoryItem::from(-1i16),
+                            ..Default::default()
+                        },
+                        MonsterRef {
+                            monster_db_id: 1,
+                            loot_item_1: InventoryItem::from(-1i16),
+                            loot_item_2: InventoryItem::from(-1i16),
+                            loot_item_3: InventoryItem::from(-1i16),
+                            ..Default::default()
+                        },
+                    ],
+                    extras: vec![ExtraRef {
+                        gold_amount: 25,
+                        loot_item: InventoryItem::new(ItemTypeId::Weapon, 1),
+                        loot_item_count: 2,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                MapPlacements {
+                    map_filename: "dun1".into(),
+                    monsters: vec![MonsterRef {
+                        monster_db_id: 2,
+                        loot_item_1: InventoryItem::from(-1i16),
+                        loot_item_2: InventoryItem::from(-1i16),
+                        loot_item_3: InventoryItem::from(-1i16),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ],
+            party_levels: vec![
+                PartyLevelNpc {
+                    npc_index: 0,
+                    records: vec![PartyLevelRecord {
+                        level: 1,
+                        health_points: 40,
+                        ..Default::default()
+                    }],
+                },
+                PartyLevelNpc {
+                    npc_index: 1,
+                    records: vec![PartyLevelRecord {
+                        level: 1,
+                        health_points: 60,
+                        ..Default::default()
+                    }],
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn maps_accumulate_xp_and_income() {
+        let report = BalanceReport::build(&inputs());
+        let cat1 = &report.maps[0];
+        assert_eq!(cat1.monsters, 2);
+        assert_eq!(cat1.monster_xp, 40.0);
+        assert_eq!(cat1.monster_gold, 10.0);
+        assert_eq!(cat1.chest_gold, 25);
+        // One slot of Dagger/Axe (sell 50/150) on average, two Swords (110 each).
+        assert_eq!(cat1.loot_value, 100.0 + 220.0);
+        assert_eq!(cat1.income, 10.0 + 25.0 + 320.0);
+        // Smith sells Dagger for 150 and Axe for 450.
+        assert_eq!(cat1.affordable_products, 1);
+
+        let dun1 = &report.maps[1];
+        assert_eq!(dun1.cumulative_xp, 140.0);
+        assert_eq!(dun1.cumulative_income, cat1.income);
+    }
+
+    #[test]
+    fn levels_average_party_members() {
+        let report = BalanceReport::build(&inputs());
+        assert_eq!(report.levels.len(), 1);
+        assert_eq!(report.levels[0].members, 2);
+        assert_eq!(report.levels[0].health_points, 50.0);
+    }
+
+    #[test]
+    fn flags_items_priced_far_from_the_median() {
+        let report = BalanceReport::build(&inputs());
+        let flagged: Vec<&str> = report
+            .price_outliers
+            .iter()
+            .map(|o| o.item_name.as_str())
+            .collect();
+        assert_eq!(flagged, ["Gold Stick"]);
+        assert!(report.price_outliers[0].factor > OUTLIER_FACTOR);
+        assert_eq!(
+            report.price_outliers[0].catalog,
+            RecordKind::WeaponItem.key()
+        );
+    }
+
+    #[test]
+    fn csv_and_html_include_every_table() {
+        let report = BalanceReport::build(&inputs());
+        let tables = report.to_csv_tables().unwrap();
+        let names: Vec<&str> = tables.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            [
+                "maps.csv",
+                "levels.csv",
+                "shop_items.csv",
+                "price_outliers.csv"
+            ]
+        );
+        assert!(
+            tables[0]
+                .1
+                .starts_with("order,map_filename,map_name,monsters,")
+        );
+        let html = report.to_html();
+        assert!(html.contains("<h2>Price outliers</h2>"));
+        assert!(html.contains("<td>Gold Stick</td>"));
+    }
+}
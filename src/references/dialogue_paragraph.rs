@@ -8,6 +8,7 @@ use std::{
 };
 
 use crate::references::extractor::Extractor;
+use crate::references::validation::{FieldConstraint, FieldConstraints, FieldRule};
 use dispel_macros::Localizable;
 
 /// Dialogue Paragraph (*.pgp) - Dialogue Text
@@ -79,6 +80,21 @@ pub struct DialogueParagraph {
     pub wave_ini_entry_id: i32,
 }
 
+/// Byte limits come from `#[translatable]`; the text must also stay in its
+/// `|`-separated column.
+impl FieldConstraints for DialogueParagraph {
+    fn field_constraints() -> Vec<FieldConstraint> {
+        vec![FieldConstraint {
+            field: "text",
+            rule: FieldRule::Text {
+                encoding: WINDOWS_1250,
+                max_bytes: None,
+                forbidden: &['|', '\r', '\n'],
+            },
+        }]
+    }
+}
+
 impl Extractor for DialogueParagraph {
     fn parse<R: Read + Seek>(reader: &mut R, _len: u64) -> std::io::Result<Vec<Self>> {
         let decoded = DecodeReaderBytesBuilder::new()
@@ -3,6 +3,7 @@ use std::path::Path;
 
 use crate::references::enums::{DialogOwner, DialogType};
 use crate::references::extractor::{Extractor, parse_int};
+use crate::references::validation::FieldConstraints;
 use encoding_rs::EUC_KR;
 use encoding_rs_io::DecodeReaderBytesBuilder;
 use rusqlite::{Connection, Result, params};
@@ -91,6 +92,8 @@ pub struct DialogueScript {
     pub triggered_event_id: Option<i32>,
 }
 
+impl FieldConstraints for DialogueScript {}
+
 impl Extractor for DialogueScript {
     fn parse<R: Read + Seek>(reader: &mut R, _len: u64) -> std::io::Result<Vec<Self>> {
         let decoded = DecodeReaderBytesBuilder::new()
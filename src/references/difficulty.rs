@@ -0,0 +1,500 @@
+//! Monster encounter difficulty per map.
+//!
+//! Every monster placement of a map is joined with its `Monster.db` stats
+//! and fought, on paper, one on one against an average party member of the
+//! level the party is expected to have on that map. The result is the
+//! monster's *threat*: the share of that member's HP lost while killing it.
+//!
+//! - The party member at level `L` averages the `PrtLevel.db` records of
+//!   level `L` together with the hero, whose attributes are the class
+//!   average from `ChData.db` grown by the same amount as the party's
+//!   since level 1.
+//! - The expected level of a map follows the XP curve: maps are played in
+//!   `AllMap.ini` order, and a map whose preceding maps hand out `p`% of all
+//!   monster XP sits `p`% of the way up the `PrtLevel.db` levels.
+//! - A round trades one blow each way. Hits land with
+//!   `to_hit / (to_hit + agility)` for monsters and
+//!   `agility / (agility + to_dodge)` for the party; damage is the better of
+//!   offense and magic attack (raised by the special attack chance) minus
+//!   half the defender's defense, at least 1. The AI type scales the result
+//!   (see [`ai_factor`]).
+//!
+//! A map's score is its mean threat in percent. Spikes flag maps much harder
+//! than the one before, monsters far above their map's mean and monsters
+//! that win a one-on-one fight outright. The model is deliberately simple
+//! and deterministic: it is meant to catch regressions between data
+//! versions, not to predict fights.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::references::balance::{
+    BalanceInputs, html_page_start, midpoint, push_html_table, to_csv,
+};
+use crate::references::enums::MonsterAiType;
+use crate::references::monster_db::Monster;
+use crate::references::party_level_db::PartyLevelRecord;
+
+/// A map is a spike when its score is this many times the previous map's.
+pub const MAP_SPIKE_FACTOR: f64 = 1.5;
+/// A monster is a spike when its threat is this many times its map's mean.
+pub const MONSTER_SPIKE_FACTOR: f64 = 3.0;
+
+/// How much the AI type changes a monster's threat.
+pub fn ai_factor(ai: MonsterAiType) -> f64 {
+    match ai {
+        MonsterAiType::Aggressor | MonsterAiType::AttackWhenOutnumbered => 1.0,
+        MonsterAiType::HitAndFlee => 1.1,
+        MonsterAiType::TeleportTactic => 1.2,
+        MonsterAiType::AttackWhenProvoked => 0.7,
+        MonsterAiType::FleeWhenApproached | MonsterAiType::RunAwayWhenAttacked => 0.5,
+    }
+}
+
+/// An average party member at one level.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PartyStrength {
+    pub level: u32,
+    pub health_points: f64,
+    /// Attack plus half of strength.
+    pub damage: f64,
+    /// Half of constitution.
+    pub defense: f64,
+    pub agility: f64,
+}
+
+/// Difficulty of one map.
+#[derive(Debug, Clone, Serialize)]
+pub struct MapDifficulty {
+    pub order: usize,
+    pub map_filename: String,
+    pub map_name: String,
+    pub expected_level: u32,
+    pub monsters: usize,
+    /// Mean monster threat in percent of a party member's HP.
+    pub score: f64,
+    /// Highest single monster threat in percent.
+    pub peak_threat: f64,
+    pub peak_monster: String,
+    /// Sum of all monster threats in percent.
+    pub total_threat: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SpikeKind {
+    /// Score jumps over [`MAP_SPIKE_FACTOR`] × the previous map's.
+    MapJump,
+    /// Threat over [`MONSTER_SPIKE_FACTOR`] × the map's mean.
+    MonsterOutlier,
+    /// The monster wins a one-on-one fight (threat ≥ 100%).
+    Overwhelming,
+}
+
+/// A map or monster placement that stands out.
+#[derive(Debug, Clone, Serialize)]
+pub struct DifficultySpike {
+    pub kind: SpikeKind,
+    pub map_filename: String,
+    /// `MonsterRef` record index, `None` for map-level spikes.
+    pub placement: Option<i32>,
+    pub monster: String,
+    pub threat: f64,
+    pub message: String,
+}
+
+impl std::fmt::Display for DifficultySpike {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} {}", self.kind, self.map_filename)?;
+        if let Some(placement) = self.placement {
+            write!(f, " monster #{placement} {}", self.monster)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DifficultyReport {
+    pub maps: Vec<MapDifficulty>,
+    pub spikes: Vec<DifficultySpike>,
+    /// The party member each level is compared against.
+    pub party: Vec<PartyStrength>,
+    pub warnings: Vec<String>,
+}
+
+impl DifficultyReport {
+    pub fn build(inputs: &BalanceInputs) -> Self {
+        let mut warnings = inputs.warnings.clone();
+        let party = party_curve(inputs);
+        if party.is_empty() {
+            warnings.push("PrtLevel.db has no level records; no map can be scored".to_string());
+        }
+        let monsters: BTreeMap<i32, &Monster> = inputs.monsters.iter().map(|m| (m.id, m)).collect();
+
+        let xp: Vec<f64> = inputs
+            .maps
+            .iter()
+            .map(|map| {
+                map.monsters
+                    .iter()
+                    .filter_map(|p| monsters.get(&p.monster_db_id))
+                    .map(|m| midpoint(m.exp_gain_min, m.exp_gain_max))
+                    .sum()
+            })
+            .collect();
+        let total_xp: f64 = xp.iter().sum();
+
+        let mut maps = Vec::new();
+        let mut spikes = Vec::new();
+        let mut xp_before = 0.0;
+        // Score and file name of the last map with monsters.
+        let mut previous: Option<(f64, String)> = None;
+        for (order, map) in inputs.maps.iter().enumerate() {
+            let share = if total_xp > 0.0 {
+                xp_before / total_xp
+            } else {
+                0.0
+            };
+            xp_before += xp[order];
+            let Some(member) = party_at_share(&party, share) else {
+                continue;
+            };
+
+            let threats: Vec<(i32, &Monster, f64)> = map
+                .monsters
+                .iter()
+                .filter_map(|p| {
+                    let monster = monsters.get(&p.monster_db_id)?;
+                    Some((p.index, *monster, monster_threat(monster, &member)))
+                })
+                .collect();
+            let total: f64 = threats.iter().map(|t| t.2).sum();
+            let mean = if threats.is_empty() {
+                0.0
+            } else {
+                total / threats.len() as f64
+            };
+            let peak = threats.iter().max_by(|a, b| a.2.total_cmp(&b.2));
+
+            if let Some((prev_score, prev_map)) = &previous
+                && *prev_score > 0.0
+                && mean > prev_score * MAP_SPIKE_FACTOR
+            {
+                spikes.push(DifficultySpike {
+                    kind: SpikeKind::MapJump,
+                    map_filename: map.map_filename.clone(),
+                    placement: None,
+                    monster: peak.map(|p| p.1.name.clone()).unwrap_or_default(),
+                    threat: mean * 100.0,
+                    message: format!(
+                        "score {:.0} after {:.0} on {prev_map}",
+                        mean * 100.0,
+                        prev_score * 100.0
+                    ),
+                });
+            }
+            if !threats.is_empty() {
+                previous = Some((mean, map.map_filename.clone()));
+            }
+
+            for &(placement, monster, threat) in &threats {
+                let spike = |kind, message: String| DifficultySpike {
+                    kind,
+                    map_filename: map.map_filename.clone(),
+                    placement: Some(placement),
+                    monster: monster.name.clone(),
+                    threat: threat * 100.0,
+                    message,
+                };
+                if threat >= 1.0 {
+                    spikes.push(spike(
+                        SpikeKind::Overwhelming,
+                        format!(
+                            "costs {:.0}% HP of a level {} party member",
+                            threat * 100.0,
+                            member.level
+                        ),
+                    ));
+                } else if threats.len() > 1 && threat >= mean * MONSTER_SPIKE_FACTOR {
+                    spikes.push(spike(
+                        SpikeKind::MonsterOutlier,
+                        format!(
+                            "threat {:.0}% vs. map mean {:.0}%",
+                            threat * 100.0,
+                            mean * 100.0
+                        ),
+                    ));
+                }
+            }
+
+            maps.push(MapDifficulty {
+                order,
+                map_filename: map.map_filename.clone(),
+                map_name: map.map_name.clone(),
+                expected_level: member.level,
+                monsters: threats.len(),
+                score: mean * 100.0,
+                peak_threat: peak.map_or(0.0, |p| p.2 * 100.0),
+                peak_monster: peak.map(|p| p.1.name.clone()).unwrap_or_default(),
+                total_threat: total * 100.0,
+            });
+        }
+
+        Self {
+            maps,
+            spikes,
+            party,
+            warnings,
+        }
+    }
+
+    /// The report as `(file name, CSV text)` pairs, one per table.
+    pub fn to_csv_tables(&self) -> Result<Vec<(&'static str, String)>, csv::Error> {
+        Ok(vec![
+            ("maps.csv", to_csv(&self.maps)?),
+            ("spikes.csv", to_csv(&self.spikes)?),
+            ("party.csv", to_csv(&self.party)?),
+        ])
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = html_page_start("Dispel encounter difficulty", &self.warnings);
+        push_html_table(&mut html, "Maps", &self.maps);
+        push_html_table(&mut html, "Spikes", &self.spikes);
+        push_html_table(&mut html, "Party member per level", &self.party);
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Average party member per `PrtLevel.db` level, hero included.
+fn party_curve(inputs: &BalanceInputs) -> Vec<PartyStrength> {
+    let mut by_level: BTreeMap<u32, Vec<&PartyLevelRecord>> = BTreeMap::new();
+    for npc in &inputs.party_levels {
+        for record in &npc.records {
+            by_level.entry(record.level).or_default().push(record);
+        }
+    }
+    let mean = |records: &[&PartyLevelRecord], f: fn(&PartyLevelRecord) -> f64| {
+        records.iter().map(|r| f(r)).sum::<f64>() / records.len() as f64
+    };
+    let strength = |r: &PartyLevelRecord| f64::from(r.strength);
+    let agility = |r: &PartyLevelRecord| f64::from(r.agility);
+    let constitution = |r: &PartyLevelRecord| f64::from(r.constitution);
+
+    // Hero attributes at creation, averaged over the four classes.
+    let hero = inputs.ch_data.as_ref().map(|c| {
+        let avg = |values: [i16; 4]| values.iter().map(|&v| f64::from(v)).sum::<f64>() / 4.0;
+        (
+            avg([
+                c.warrior_strength,
+                c.knight_strength,
+                c.archer_strength,
+                c.mage_strength,
+            ]),
+            avg([
+                c.warrior_agility,
+                c.knight_agility,
+                c.archer_agility,
+                c.mage_agility,
+            ]),
+            avg([
+                c.warrior_constitution,
+                c.knight_constitution,
+                c.archer_constitution,
+                c.mage_constitution,
+            ]),
+        )
+    });
+    let first = by_level.values().next().cloned().unwrap_or_default();
+
+    by_level
+        .iter()
+        .map(|(&level, records)| {
+            let mut attributes = [
+                mean(records, strength),
+                mean(records, agility),
+                mean(records, constitution),
+            ];
+            if let Some((str0, agi0, con0)) = hero {
+                let grown = [
+                    str0 + attributes[0] - mean(&first, strength),
+                    agi0 + attributes[1] - mean(&first, agility),
+                    con0 + attributes[2] - mean(&first, constitution),
+                ];
+                let members = records.len() as f64;
+                for (value, hero_value) in attributes.iter_mut().zip(grown) {
+                    *value = (*value * members + hero_value) / (members + 1.0);
+                }
+            }
+            PartyStrength {
+                level,
+                health_points: mean(records, |r| f64::from(r.health_points)),
+                damage: mean(records, |r| f64::from(r.attack)) + attributes[0] / 2.0,
+                defense: attributes[2] / 2.0,
+                agility: attributes[1],
+            }
+        })
+        .collect()
+}
+
+/// The party member `share` (0..=1) of the way up the level curve.
+fn party_at_share(party: &[PartyStrength], share: f64) -> Option<PartyStrength> {
+    let last = party.len().checked_sub(1)?;
+    let index = (share.clamp(0.0, 1.0) * last as f64).round() as usize;
+    party.get(index).copied()
+}
+
+/// Share of `member`'s HP lost while killing `monster` one on one.
+pub fn monster_threat(monster: &Monster, member: &PartyStrength) -> f64 {
+    let to_hit = midpoint(monster.to_hit_min, monster.to_hit_max).max(0.0);
+    let to_dodge = midpoint(monster.to_dodge_min, monster.to_dodge_max).max(0.0);
+    let chance = |a: f64, b: f64| {
+        if a + b <= 0.0 {
+            0.5
+        } else {
+            (a / (a + b)).clamp(0.05, 0.95)
+        }
+    };
+
+    let mut monster_damage = midpoint(monster.offense_min, monster.offense_max)
+        .max(midpoint(monster.magic_attack_min, monster.magic_attack_max));
+    if monster.special_attack != 0 {
+        monster_damage *= 1.0 + f64::from(monster.special_attack_chance.clamp(0, 100)) / 100.0;
+    }
+    let taken = (monster_damage - member.defense / 2.0).max(1.0) * chance(to_hit, member.agility);
+
+    let defense = midpoint(monster.defense_min, monster.defense_max);
+    let dealt = (member.damage - defense / 2.0).max(1.0) * chance(member.agility, to_dodge);
+    let monster_hp = midpoint(monster.health_points_min, monster.health_points_max).max(1.0);
+    let rounds = monster_hp / dealt;
+
+    rounds * taken / member.health_points.max(1.0) * ai_factor(monster.ai_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::references::balance::MapPlacements;
+    use crate::references::monster_ref::MonsterRef;
+    use crate::references::party_level_db::PartyLevelNpc;
+
+    fn member() -> PartyStrength {
+        PartyStrength {
+            level: 1,
+            health_points: 100.0,
+            damage: 20.0,
+            defense: 0.0,
+            agility: 10.0,
+        }
+    }
+
+    fn monster(id: i32, name: &str, hp: i32, offense: i32) -> Monster {
+        Monster {
+            id,
+            name: name.into(),
+            health_points_min: hp,
+            health_points_max: hp,
+            offense_min: offense,
+            offense_max: offense,
+            to_hit_min: 10,
+            to_hit_max: 10,
+            exp_gain_min: 10,
+            exp_gain_max: 10,
+            ..Default::default()
+        }
+    }
+
+    fn placed(index: i32, monster_db_id: i32) -> MonsterRef {
+        MonsterRef {
+            index,
+            monster_db_id,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn threat_is_hp_lost_per_kill() {
+        let rat = monster(1, "Rat", 40, 10);
+        // The party hits 95% of the time (the rat cannot dodge), so the
+        // fight lasts 40 / 19 rounds; the rat hits half the time for 10.
+        let expected = (40.0 / (20.0 * 0.95)) * 5.0 / 100.0;
+        assert!((monster_threat(&rat, &member()) - expected).abs() < 1e-9);
+
+        let fleeing = Monster {
+            ai_type: MonsterAiType::RunAwayWhenAttacked,
+            ..rat.clone()
+        };
+        assert!(monster_threat(&fleeing, &member()) < monster_threat(&rat, &member()));
+    }
+
+    #[test]
+    fn later_maps_face_stronger_party_and_spikes_are_reported() {
+        let level = |level, hp| PartyLevelRecord {
+            level,
+            health_points: hp,
+            attack: 20,
+            agility: 10,
+            ..Default::default()
+        };
+        let inputs = BalanceInputs {
+            monsters: vec![
+                monster(1, "Rat", 40, 10),
+                monster(2, "Wolf", 60, 20),
+                monster(3, "Dragon", 2000, 80),
+            ],
+            party_levels: vec![PartyLevelNpc {
+                npc_index: 0,
+                records: vec![level(1, 100), level(2, 150), level(3, 200)],
+            }],
+            maps: vec![
+                MapPlacements {
+                    map_filename: "cat1".into(),
+                    monsters: vec![placed(0, 1), placed(1, 1)],
+                    ..Default::default()
+                },
+                MapPlacements {
+                    map_filename: "cat2".into(),
+                    monsters: vec![placed(0, 2), placed(1, 1), placed(2, 1), placed(3, 3)],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let report = DifficultyReport::build(&inputs);
+        assert_eq!(report.party.len(), 3);
+        assert_eq!(report.maps[0].expected_level, 1);
+        // cat1 hands out 20 of 60 XP: a third of the way up → level 2.
+        assert_eq!(report.maps[1].expected_level, 2);
+        assert_eq!(report.maps[1].peak_monster, "Dragon");
+
+        let kinds: Vec<(SpikeKind, &str)> = report
+            .spikes
+            .iter()
+            .map(|s| (s.kind, s.monster.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (SpikeKind::MapJump, "Dragon"),
+                (SpikeKind::Overwhelming, "Dragon"),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_party_levels_means_no_scores() {
+        let inputs = BalanceInputs {
+            monsters: vec![monster(1, "Rat", 40, 10)],
+            maps: vec![MapPlacements {
+                map_filename: "cat1".into(),
+                monsters: vec![placed(0, 1)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let report = DifficultyReport::build(&inputs);
+        assert!(report.maps.is_empty());
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
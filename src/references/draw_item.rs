@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::references::enums::{InventoryItem, ItemTypeId};
 use crate::references::extractor::Extractor;
+use crate::references::validation::FieldConstraints;
 
 /// Stores map placement data for drawn items/objects.
 ///
@@ -55,6 +56,8 @@ pub struct DrawItem {
     pub item: InventoryItem,
 }
 
+impl FieldConstraints for DrawItem {}
+
 impl Extractor for DrawItem {
     fn parse<R: Read + Seek>(reader: &mut R, _len: u64) -> std::io::Result<Vec<Self>> {
         let decoded = DecodeReaderBytesBuilder::new()
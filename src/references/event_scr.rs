@@ -3,6 +3,7 @@ use std::io::{BufRead, BufReader, Cursor, Read, Seek, Write};
 use std::path::Path;
 
 use crate::references::extractor::Extractor;
+use crate::references::validation::{FieldConstraint, FieldConstraints, FieldRule};
 use encoding_rs::EUC_KR;
 use encoding_rs_io::DecodeReaderBytesBuilder;
 use rusqlite;
@@ -276,6 +277,29 @@ impl std::fmt::Display for ActionFunction {
     }
 }
 
+/// Section lines are written one per line in EUC-KR.
+impl FieldConstraints for EventScript {
+    fn field_constraints() -> Vec<FieldConstraint> {
+        [
+            "header_comments",
+            "map_content",
+            "chr_content",
+            "npc_content",
+            "wav_content",
+        ]
+        .into_iter()
+        .map(|field| FieldConstraint {
+            field,
+            rule: FieldRule::Text {
+                encoding: EUC_KR,
+                max_bytes: None,
+                forbidden: &['\r', '\n'],
+            },
+        })
+        .collect()
+    }
+}
+
 impl Extractor for EventScript {
     fn parse<R: Read + Seek>(reader: &mut R, _len: u64) -> std::io::Result<Vec<Self>> {
         let decoded = DecodeReaderBytesBuilder::new()
@@ -24,6 +24,18 @@ pub trait Extractor: Sized {
     }
 }
 
+/// Reads `path`, or records why it could not be read in `warnings` and
+/// returns no records, so reports still cover the files that did load.
+pub(crate) fn read_or_warn<T: Extractor>(path: &Path, warnings: &mut Vec<String>) -> Vec<T> {
+    match T::read_file(path) {
+        Ok(records) => records,
+        Err(e) => {
+            warnings.push(format!("{}: {e}", path.display()));
+            Vec::new()
+        }
+    }
+}
+
 pub fn read_null_terminated_windows_1250(bytes: &[u8]) -> core::result::Result<String, String> {
     // Find the first null byte (or use a fixed length if no null terminator)
     let (_data, _) = bytes.split_last().ok_or("Empty input")?;
@@ -0,0 +1,518 @@
+//! Loot sources: "where can I get item X?" and "what does map Y drop?".
+//!
+//! Items reach the party from five places, all resolved against the item
+//! databases by [`LootReport::build`]:
+//!
+//! - **monster drops** — `loot_item_1..3` of each map's monster `.ref`. A
+//!   placement with `drop_all_loot` drops every filled slot, any other drops
+//!   one of them, so each slot counts `1/n` of a kill;
+//! - **extras** — chests and other objects of each map's extra `.ref`, with
+//!   `loot_item` × `loot_item_count` and `gold_amount`. The additional loot
+//!   slots are skipped, as in the balance report;
+//! - **NPC rewards** — `interaction_result_item` of each map's NPC `.ref`;
+//! - **map items** — `Ref/DRAWITEM.ref` placements, joined on the
+//!   `AllMap.ini` id;
+//! - **shops** — `STORE.DB` products, which belong to no map.
+//!
+//! Event scripts that hand out items (`additem`) are not followed; `refs`
+//! lists them.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::references::balance::{BalanceInputs, html_page_start, push_html_table, to_csv};
+use crate::references::enums::{InventoryItem, TriStateFlag};
+use crate::references::monster_db::Monster;
+use crate::references::usages::{RecordId, RecordKind};
+
+/// Where a [`LootSource`] hands out its item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum LootSourceKind {
+    MonsterDrop,
+    Extra,
+    NpcReward,
+    MapItem,
+    Shop,
+}
+
+impl std::fmt::Display for LootSourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LootSourceKind::MonsterDrop => "monster drop",
+            LootSourceKind::Extra => "extra",
+            LootSourceKind::NpcReward => "NPC reward",
+            LootSourceKind::MapItem => "map item",
+            LootSourceKind::Shop => "shop",
+        })
+    }
+}
+
+/// One place an item can be obtained.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LootSource {
+    pub item_kind: RecordKind,
+    pub item_id: i32,
+    /// `None` when the id points past the end of its database.
+    pub item_name: Option<String>,
+    pub base_price: Option<i32>,
+    pub source: LootSourceKind,
+    /// Monster, object, NPC or store name.
+    pub source_name: String,
+    /// `AllMap.ini` file name of the map; `None` for shops.
+    pub map_filename: Option<String>,
+    pub map_name: Option<String>,
+    /// File holding the source record, relative to the game directory.
+    pub file: String,
+    /// Index of the source record within `file`.
+    pub record: usize,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    /// Copies handed out per kill, chest or visit: the chest count, or `1/n`
+    /// for a monster that drops one of `n` slots.
+    pub expected_quantity: f64,
+}
+
+impl LootSource {
+    pub fn item(&self) -> RecordId {
+        RecordId {
+            kind: self.item_kind,
+            id: self.item_id,
+        }
+    }
+
+    /// Item name, or `file #id` for dangling references.
+    pub fn item_label(&self) -> String {
+        match &self.item_name {
+            Some(name) => name.clone(),
+            None => self.item().to_string(),
+        }
+    }
+}
+
+/// Loot totals of one map.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MapLoot {
+    /// Position in `AllMap.ini`.
+    pub order: usize,
+    pub map_filename: String,
+    pub map_name: String,
+    pub monster_drops: usize,
+    pub extra_items: usize,
+    pub npc_rewards: usize,
+    pub map_items: usize,
+    pub distinct_items: usize,
+    /// Gold in extras (`gold_amount`).
+    pub extra_gold: i64,
+    /// Base price of every source times its expected quantity.
+    pub item_value: f64,
+}
+
+/// Every loot source of a game directory, with per-map totals.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LootReport {
+    pub maps: Vec<MapLoot>,
+    pub sources: Vec<LootSource>,
+    pub warnings: Vec<String>,
+}
+
+/// Fields shared by the sources of one record.
+struct Origin<'a> {
+    source: LootSourceKind,
+    source_name: &'a str,
+    map: Option<(&'a str, &'a str)>,
+    file: &'a str,
+    record: usize,
+    position: Option<(i32, i32)>,
+}
+
+impl LootReport {
+    pub fn build(inputs: &BalanceInputs) -> Self {
+        let monsters: BTreeMap<i32, &Monster> = inputs.monsters.iter().map(|m| (m.id, m)).collect();
+        let mut sources = Vec::new();
+        let mut maps = Vec::new();
+
+        for (order, map) in inputs.maps.iter().enumerate() {
+            let first = sources.len();
+            let map_key = Some((map.map_filename.as_str(), map.map_name.as_str()));
+            let mut extra_gold = 0i64;
+
+            let monster_file = map.monster_file.as_deref().unwrap_or_default();
+            for (record, placement) in map.monsters.iter().enumerate() {
+                let slots: Vec<InventoryItem> = [
+                    placement.loot_item_1,
+                    placement.loot_item_2,
+                    placement.loot_item_3,
+                ]
+                .into_iter()
+                .filter(|item| item_kind(*item).is_some())
+                .collect();
+                let quantity = if placement.drop_all_loot == TriStateFlag::One {
+                    1.0
+                } else {
+                    1.0 / slots.len().max(1) as f64
+                };
+                let origin = Origin {
+                    source: LootSourceKind::MonsterDrop,
+                    source_name: monsters
+                        .get(&placement.monster_db_id)
+                        .map_or("?", |m| m.name.as_str()),
+                    map: map_key,
+                    file: monster_file,
+                    record,
+                    position: Some((placement.map_x, placement.map_y)),
+                };
+                for item in slots {
+                    push_source(&mut sources, inputs, item, &origin, quantity);
+                }
+            }
+
+            let extra_file = map.extra_file.as_deref().unwrap_or_default();
+            for (record, extra) in map.extras.iter().enumerate() {
+                extra_gold += i64::from(extra.gold_amount.max(0));
+                let origin = Origin {
+                    source: LootSourceKind::Extra,
+                    source_name: &extra.object_name,
+                    map: map_key,
+                    file: extra_file,
+                    record,
+                    position: Some((extra.map_x, extra.map_y)),
+                };
+                let quantity = f64::from(extra.loot_item_count.max(1));
+                push_source(&mut sources, inputs, extra.loot_item, &origin, quantity);
+            }
+
+            let npc_file = map.npc_file.as_deref().unwrap_or_default();
+            for (record, npc) in map.npcs.iter().enumerate() {
+                let origin = Origin {
+                    source: LootSourceKind::NpcReward,
+                    source_name: &npc.name,
+                    map: map_key,
+                    file: npc_file,
+                    record,
+                    position: None,
+                };
+                push_source(
+                    &mut sources,
+                    inputs,
+                    npc.interaction_result_item,
+                    &origin,
+                    1.0,
+                );
+            }
+
+            for (record, draw_item) in inputs.draw_items.iter().enumerate() {
+                if draw_item.map_id != map.map_id {
+                    continue;
+                }
+                let origin = Origin {
+                    source: LootSourceKind::MapItem,
+                    source_name: "",
+                    map: map_key,
+                    file: "Ref/DRAWITEM.ref",
+                    record,
+                    position: Some((draw_item.x_coord, draw_item.y_coord)),
+                };
+                push_source(&mut sources, inputs, draw_item.item, &origin, 1.0);
+            }
+
+            let on_map = &sources[first..];
+            let count = |kind| on_map.iter().filter(|s| s.source == kind).count();
+            maps.push(MapLoot {
+                order,
+                map_filename: map.map_filename.clone(),
+                map_name: map.map_name.clone(),
+                monster_drops: count(LootSourceKind::MonsterDrop),
+                extra_items: count(LootSourceKind::Extra),
+                npc_rewards: count(LootSourceKind::NpcReward),
+                map_items: count(LootSourceKind::MapItem),
+                distinct_items: on_map
+                    .iter()
+                    .map(LootSource::item)
+                    .collect::<BTreeSet<_>>()
+                    .len(),
+                extra_gold,
+                item_value: on_map
+                    .iter()
+                    .map(|s| f64::from(s.base_price.unwrap_or(0)) * s.expected_quantity)
+                    .sum(),
+            });
+        }
+
+        let catalog = inputs.store_catalog();
+        for (record, store) in inputs.stores.iter().enumerate() {
+            for product in catalog.resolve(store) {
+                let base_price = product.name.as_ref().map(|_| product.base_price);
+                sources.push(LootSource {
+                    item_kind: RecordKind::from_product_type(product.slot.product_type),
+                    item_id: i32::from(product.slot.item_id),
+                    item_name: product.name,
+                    base_price,
+                    source: LootSourceKind::Shop,
+                    source_name: store.store_name.clone(),
+                    map_filename: None,
+                    map_name: None,
+                    file: "CharacterInGame/STORE.DB".to_string(),
+                    record,
+                    x: None,
+                    y: None,
+                    expected_quantity: 1.0,
+                });
+            }
+        }
+
+        Self {
+            maps,
+            sources,
+            warnings: inputs.warnings.clone(),
+        }
+    }
+
+    /// Every source of one item ("where can I get item X?").
+    pub fn sources_of(&self, item: RecordId) -> Vec<&LootSource> {
+        self.sources.iter().filter(|s| s.item() == item).collect()
+    }
+
+    /// Every source placed on a map ("what does map Y drop?"), matched on
+    /// the `AllMap.ini` file or display name, ignoring case.
+    pub fn sources_on(&self, map: &str) -> Vec<&LootSource> {
+        self.sources
+            .iter()
+            .filter(|s| {
+                s.map_filename
+                    .iter()
+                    .chain(&s.map_name)
+                    .any(|name| name.eq_ignore_ascii_case(map))
+            })
+            .collect()
+    }
+
+    /// Keep only the sources of `item` and/or on `map`, and the totals of
+    /// `map`.
+    pub fn filtered(mut self, item: Option<RecordId>, map: Option<&str>) -> Self {
+        let matches_map = |filename: &str, name: &str| {
+            map.is_none_or(|m| filename.eq_ignore_ascii_case(m) || name.eq_ignore_ascii_case(m))
+        };
+        self.sources.retain(|s| {
+            item.is_none_or(|item| s.item() == item)
+                && (map.is_none()
+                    || matches_map(
+                        s.map_filename.as_deref().unwrap_or_default(),
+                        s.map_name.as_deref().unwrap_or_default(),
+                    ))
+        });
+        self.maps
+            .retain(|m| matches_map(&m.map_filename, &m.map_name));
+        self
+    }
+
+    /// The report as `(file name, CSV text)` pairs, one per table.
+    pub fn to_csv_tables(&self) -> Result<Vec<(&'static str, String)>, csv::Error> {
+        Ok(vec![
+            ("maps.csv", to_csv(&self.maps)?),
+            ("sources.csv", to_csv(&self.sources)?),
+        ])
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = html_page_start("Dispel loot sources", &self.warnings);
+        push_html_table(&mut html, "Maps", &self.maps);
+        push_html_table(&mut html, "Sources", &self.sources);
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Catalog an inventory item lives in; `None` for empty and unknown slots.
+fn item_kind(item: InventoryItem) -> Option<RecordKind> {
+    if item.is_empty() {
+        return None;
+    }
+    RecordKind::from_item_type(item.item_type()?)
+}
+
+fn push_source(
+    sources: &mut Vec<LootSource>,
+    inputs: &BalanceInputs,
+    item: InventoryItem,
+    origin: &Origin,
+    expected_quantity: f64,
+) {
+    let Some(item_kind) = item_kind(item) else {
+        return;
+    };
+    let resolved = inputs.item(item);
+    sources.push(LootSource {
+        item_kind,
+        item_id: i32::from(item.item_id()),
+        item_name: resolved.map(|(name, _)| name.to_string()),
+        base_price: resolved.map(|(_, price)| price),
+        source: origin.source,
+        source_name: origin.source_name.to_string(),
+        map_filename: origin.map.map(|(filename, _)| filename.to_string()),
+        map_name: origin.map.map(|(_, name)| name.to_string()),
+        file: origin.file.to_string(),
+        record: origin.record,
+        x: origin.position.map(|(x, _)| x),
+        y: origin.position.map(|(_, y)| y),
+        expected_quantity,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::references::balance::MapPlacements;
+    use crate::references::draw_item::DrawItem;
+    use crate::references::enums::{ItemTypeId, ProductType};
+    use crate::references::extra_ref::ExtraRef;
+    use crate::references::heal_item_db::HealItem;
+    use crate::references::monster_ref::MonsterRef;
+    use crate::references::npc_ref::NPC;
+    use crate::references::store_db::Store;
+    use crate::references::weapons_db::WeaponItem;
+
+    const EMPTY: i16 = -1;
+
+    fn weapon(id: u8) -> InventoryItem {
+        InventoryItem::new(ItemTypeId::Weapon, id)
+    }
+
+    fn inputs() -> BalanceInputs {
+        BalanceInputs {
+            weapons: vec![
+                WeaponItem {
+                    name: "Dagger".into(),
+                    base_price: 100,
+                    ..Default::default()
+                },
+                WeaponItem {
+                    name: "Axe".into(),
+                    base_price: 300,
+                    ..Default::default()
+                },
+            ],
+            heal_items: vec![HealItem {
+                name: "Potion".into(),
+                base_price: 20,
+                ..Default::default()
+            }],
+            monsters: vec![Monster {
+                id: 4,
+                name: "Goblin".into(),
+                ..Default::default()
+            }],
+            stores: vec![Store {
+                store_name: "Smithy".into(),
+                products: vec![(0, ProductType::Weapon, 1)],
+                ..Default::default()
+            }],
+            draw_items: vec![
+                DrawItem {
+                    map_id: 7,
+                    x_coord: 3,
+                    y_coord: 4,
+                    item: weapon(0),
+                },
+                DrawItem {
+                    map_id: 8,
+                    item: weapon(1),
+                    ..Default::default()
+                },
+            ],
+            maps: vec![MapPlacements {
+                map_id: 7,
+                map_filename: "cat1".into(),
+                map_name: "Catacombs".into(),
+                monster_file: Some("MonsterInGame/Moncat1.ref".into()),
+                monsters: vec![MonsterRef {
+                    monster_db_id: 4,
+                    loot_item_1: weapon(0),
+                    loot_item_2: InventoryItem::new(ItemTypeId::Healing, 0),
+                    loot_item_3: InventoryItem::from(EMPTY),
+                    ..Default::default()
+                }],
+                extras: vec![ExtraRef {
+                    object_name: "Chest".into(),
+                    gold_amount: 50,
+                    loot_item: InventoryItem::new(ItemTypeId::Healing, 0),
+                    loot_item_count: 3,
+                    ..Default::default()
+                }],
+                npcs: vec![NPC {
+                    name: "Hermit".into(),
+                    interaction_result_item: InventoryItem::new(ItemTypeId::Weapon, 9),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sources_of_item_span_every_source_kind() {
+        let report = LootReport::build(&inputs());
+        let dagger = RecordId {
+            kind: RecordKind::WeaponItem,
+            id: 0,
+        };
+        let sources = report.sources_of(dagger);
+        let kinds: Vec<_> = sources.iter().map(|s| s.source).collect();
+        assert_eq!(
+            kinds,
+            vec![LootSourceKind::MonsterDrop, LootSourceKind::MapItem]
+        );
+        assert_eq!(sources[0].source_name, "Goblin");
+        assert_eq!(sources[0].expected_quantity, 0.5);
+        assert_eq!(sources[0].file, "MonsterInGame/Moncat1.ref");
+        assert_eq!((sources[1].x, sources[1].y), (Some(3), Some(4)));
+
+        let axe = report.sources_of(RecordId {
+            kind: RecordKind::WeaponItem,
+            id: 1,
+        });
+        assert_eq!(axe.len(), 1);
+        assert_eq!(axe[0].source, LootSourceKind::Shop);
+        assert_eq!(axe[0].map_filename, None);
+    }
+
+    #[test]
+    fn map_totals_and_unresolved_items() {
+        let report = LootReport::build(&inputs());
+        let map = &report.maps[0];
+        assert_eq!(map.monster_drops, 2);
+        assert_eq!(map.extra_items, 1);
+        assert_eq!(map.npc_rewards, 1);
+        assert_eq!(map.map_items, 1);
+        assert_eq!(map.distinct_items, 3);
+        assert_eq!(map.extra_gold, 50);
+        // Dagger and potion at half a kill each, three potions, one dagger.
+        assert_eq!(map.item_value, 50.0 + 10.0 + 60.0 + 100.0);
+
+        let on_map = report.sources_on("CATACOMBS");
+        assert_eq!(on_map.len(), 5);
+        let reward = on_map
+            .iter()
+            .find(|s| s.source == LootSourceKind::NpcReward)
+            .unwrap();
+        assert_eq!(reward.item_name, None);
+        assert_eq!(reward.item_label(), "weaponItem.db #9");
+    }
+
+    #[test]
+    fn filtered_keeps_matching_sources_and_maps() {
+        let potion = RecordId {
+            kind: RecordKind::HealItem,
+            id: 0,
+        };
+        let report = LootReport::build(&inputs()).filtered(Some(potion), Some("cat1"));
+        assert_eq!(report.sources.len(), 2);
+        assert!(report.sources.iter().all(|s| s.item() == potion));
+        assert_eq!(report.maps.len(), 1);
+
+        let elsewhere = LootReport::build(&inputs()).filtered(None, Some("dun9"));
+        assert!(elsewhere.sources.is_empty());
+        assert!(elsewhere.maps.is_empty());
+    }
+}
@@ -64,9 +64,11 @@
 // ===========================================================================
 
 pub mod all_map_ini;
+pub mod balance;
 pub mod chdata_db;
 pub mod dialogue_paragraph;
 pub mod dialogue_script;
+pub mod difficulty;
 pub mod draw_item;
 pub mod edit_item_db;
 pub mod enums;
@@ -79,6 +81,7 @@ pub mod extra_ref;
 pub mod extractor;
 pub mod heal_item_db;
 pub mod layout;
+pub mod loot;
 pub mod magic_db;
 pub mod map_ini;
 pub mod message_scr;
@@ -91,8 +94,13 @@ pub mod npc_ref;
 pub mod party_ini_db;
 pub mod party_level_db;
 pub mod party_ref;
+pub mod quest_flow;
 pub mod quest_scr;
+pub mod renumber;
 pub mod save_file;
 pub mod store_db;
+pub mod store_inventory;
+pub mod usages;
+pub mod validation;
 pub mod wave_ini;
 pub mod weapons_db;
@@ -1,4 +1,5 @@
 use crate::references::extractor::Extractor;
+use crate::references::validation::{FieldConstraint, FieldConstraints, FieldRule};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use rusqlite::{Connection, Result as DbResult, params};
 use serde::{Deserialize, Serialize};
@@ -62,6 +63,15 @@ impl Extractor for PartyLevelRecord {
     }
 }
 
+impl FieldConstraints for PartyLevelNpc {
+    fn field_constraints() -> Vec<FieldConstraint> {
+        vec![FieldConstraint {
+            field: "records",
+            rule: FieldRule::Items { min: 20, max: 20 },
+        }]
+    }
+}
+
 impl Extractor for PartyLevelNpc {
     fn parse<R: Read + Seek>(reader: &mut R, _len: u64) -> Result<Vec<Self>> {
         let mut npcs = Vec::with_capacity(8);
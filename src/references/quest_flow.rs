@@ -0,0 +1,898 @@
+//! Quest flow: one dependency graph over everything that requires,
+//! triggers or advances an `Event.ini` event.
+//!
+//! [`QuestGraph::build`] links:
+//!
+//! - **events** — `Event.ini` `required_event_id` prerequisites, labelled
+//!   with the event type;
+//! - **event scripts** — `[ACT]` calls of `Ref/Event<id>.scr`, joined on
+//!   the id in the file name as the database import does.
+//!   `addquest(quest, state)` starts, updates or finishes a `Quest.scr`
+//!   entry, and calls from [`ACT_SIGNATURES`] naming an event trigger it;
+//! - **dialogues** — lines of each map's `.dlg` that need an event to show
+//!   (`required_event_id`) or trigger one (`triggered_event_id`);
+//! - **monsters and extras** — `event_id_on_kill` and
+//!   `interaction_event_id` of each map's placements;
+//! - **maps** — the per-tile triggers of each `.map` event block and the
+//!   `Map.ini` camera event.
+//!
+//! What the `addquest` state means is not documented: the lowest state a
+//! quest is given counts as its start and the highest as its finish, so a
+//! quest only ever given one state is started but never finished.
+//!
+//! An event is *untriggered* when an event or dialogue requires it but no
+//! dialogue, placement, map or script triggers it. Script functions outside
+//! [`ACT_SIGNATURES`] are not followed, so such an event may still fire
+//! from a call that is not mapped yet.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::map::atlas::{discover_maps, resolve_case_insensitive};
+use crate::map::read_map_events;
+use crate::references::dialogue_script::DialogueScript;
+use crate::references::enums::EventType;
+use crate::references::event_ini::Event;
+use crate::references::event_scr::EventScript;
+use crate::references::extra_ref::ExtraRef;
+use crate::references::extractor::read_or_warn;
+use crate::references::monster_db::Monster;
+use crate::references::monster_ref::MonsterRef;
+use crate::references::quest_scr::Quest;
+use crate::references::usages::{ACT_SIGNATURES, ActParameter, RecordKind};
+
+/// Event triggers and placements of one map.
+#[derive(Debug, Clone, Default)]
+pub struct MapTriggers {
+    pub map_filename: String,
+    pub map_name: String,
+    /// `Map.ini` event run when the camera moves, `0` for none.
+    pub camera_event: i32,
+    /// `(x, y, event_id)` of every tile with an event.
+    pub tile_events: Vec<(i32, i32, i32)>,
+    pub monsters: Vec<MonsterRef>,
+    pub extras: Vec<ExtraRef>,
+    /// The placement files read, relative to the game directory.
+    pub monster_file: Option<String>,
+    pub extra_file: Option<String>,
+}
+
+/// Everything the quest flow graph reads from a game directory.
+#[derive(Debug, Clone, Default)]
+pub struct QuestFlowInputs {
+    pub events: Vec<Event>,
+    pub quests: Vec<Quest>,
+    /// `Monster.db`, for the names of placements.
+    pub monsters: Vec<Monster>,
+    /// `Ref/Event*.scr` scripts with their file, relative to the game
+    /// directory.
+    pub scripts: Vec<(String, EventScript)>,
+    /// The `.dlg` file of each map, relative to the game directory.
+    pub dialogues: Vec<(String, Vec<DialogueScript>)>,
+    /// In `AllMap.ini` order.
+    pub maps: Vec<MapTriggers>,
+    /// Files that were missing or failed to parse.
+    pub warnings: Vec<String>,
+}
+
+impl QuestFlowInputs {
+    /// Read every input under `game_dir`. Missing or unreadable files are
+    /// skipped with a warning.
+    pub fn load(game_dir: &Path) -> Self {
+        let mut inputs = Self::default();
+        let mut warnings = Vec::new();
+        let read = |dir: &str, name: &str| resolve_case_insensitive(game_dir, dir, name);
+
+        inputs.events = read_or_warn(&read("", "Event.ini"), &mut warnings);
+        inputs.quests = read_or_warn(&read("ExtraInGame", "Quest.scr"), &mut warnings);
+        inputs.monsters = read_or_warn(&read("MonsterInGame", "Monster.db"), &mut warnings);
+
+        let mut script_files: Vec<String> = std::fs::read_dir(game_dir.join("Ref"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| {
+                let lower = name.to_ascii_lowercase();
+                lower.starts_with("event") && lower.ends_with(".scr")
+            })
+            .collect();
+        script_files.sort();
+        for name in script_files {
+            let scripts: Vec<EventScript> = read_or_warn(&read("Ref", &name), &mut warnings);
+            let file = format!("Ref/{name}");
+            inputs
+                .scripts
+                .extend(scripts.into_iter().map(|script| (file.clone(), script)));
+        }
+
+        match discover_maps(game_dir) {
+            Ok(maps) => {
+                let mut dialogue_files = BTreeSet::new();
+                for m in maps {
+                    if let Some(f) = &m.map.dlg_filename
+                        && dialogue_files.insert(f.to_ascii_lowercase())
+                    {
+                        let dialogues = read_or_warn(&read("NpcInGame", f), &mut warnings);
+                        inputs.dialogues.push((format!("NpcInGame/{f}"), dialogues));
+                    }
+
+                    let mut triggers = MapTriggers {
+                        map_filename: m.map.map_filename.clone(),
+                        map_name: m.map.map_name.clone(),
+                        ..Default::default()
+                    };
+                    match File::open(&m.map_path)
+                        .and_then(|file| read_map_events(&mut BufReader::new(file)))
+                    {
+                        Ok(events) => {
+                            triggers.tile_events = events
+                                .values()
+                                .filter(|e| e.event_id > 0)
+                                .map(|e| (e.x, e.y, i32::from(e.event_id)))
+                                .collect();
+                            triggers.tile_events.sort_by_key(|&(x, y, id)| (id, y, x));
+                        }
+                        Err(e) => warnings.push(format!("{}: {e}", m.map_path.display())),
+                    }
+                    if let Some(ini) = &m.map_ini {
+                        triggers.camera_event = ini.event_id_on_camera_move;
+                        if let Some(f) = &ini.monsters_filename {
+                            triggers.monsters =
+                                read_or_warn(&read("MonsterInGame", f), &mut warnings);
+                            triggers.monster_file = Some(format!("MonsterInGame/{f}"));
+                        }
+                        if let Some(f) = &ini.extra_filename {
+                            triggers.extras = read_or_warn(&read("ExtraInGame", f), &mut warnings);
+                            triggers.extra_file = Some(format!("ExtraInGame/{f}"));
+                        }
+                    }
+                    inputs.maps.push(triggers);
+                }
+            }
+            Err(e) => warnings.push(format!("AllMap.ini: {e}")),
+        }
+
+        inputs.warnings = warnings;
+        inputs
+    }
+}
+
+/// A vertex of the [`QuestGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FlowNode {
+    /// `Event.ini` event.
+    Event { id: i32 },
+    /// `Quest.scr` journal entry.
+    Quest { id: i32 },
+    /// Line `id` of a `.dlg` file.
+    Dialogue { file: String, id: i32 },
+    /// Monster placement, by record of its `.ref` file.
+    Monster { file: String, record: usize },
+    /// Extra placement, by record of its `.ref` file.
+    Extra { file: String, record: usize },
+    /// The tiles and camera of a map, by `AllMap.ini` file name.
+    Map { map: String },
+}
+
+impl FlowNode {
+    /// Identifier edges refer to and the DOT node id, e.g. `event:12`.
+    pub fn key(&self) -> String {
+        match self {
+            FlowNode::Event { id } => format!("event:{id}"),
+            FlowNode::Quest { id } => format!("quest:{id}"),
+            FlowNode::Dialogue { file, id } => format!("dialogue:{file}:{id}"),
+            FlowNode::Monster { file, record } => format!("monster:{file}:{record}"),
+            FlowNode::Extra { file, record } => format!("extra:{file}:{record}"),
+            FlowNode::Map { map } => format!("map:{map}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlowNodeInfo {
+    pub key: String,
+    #[serde(flatten)]
+    pub node: FlowNode,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlowEdgeKind {
+    /// The target event or dialogue waits for the source event.
+    Requires,
+    /// The source runs the target event.
+    Triggers,
+    StartsQuest,
+    UpdatesQuest,
+    FinishesQuest,
+}
+
+/// A directed edge between two [`FlowNode::key`]s.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct FlowEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: FlowEdgeKind,
+    /// How the source relates to the target, e.g. `on kill` or `state 2`.
+    pub detail: String,
+}
+
+/// One `addquest` call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuestStep {
+    pub event_id: i32,
+    pub state: Option<i32>,
+    /// Labels of what triggers the event.
+    pub triggered_by: Vec<String>,
+}
+
+/// A `Quest.scr` entry and the events that move it along.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuestFlow {
+    pub id: i32,
+    pub type_id: i32,
+    pub title: String,
+    pub started_by: Vec<QuestStep>,
+    pub updated_by: Vec<QuestStep>,
+    pub finished_by: Vec<QuestStep>,
+}
+
+/// An event something waits for that nothing triggers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UntriggeredEvent {
+    pub event_id: i32,
+    /// Whether `Event.ini` defines the event.
+    pub defined: bool,
+    /// Labels of the events and dialogues that require it.
+    pub required_by: Vec<String>,
+}
+
+/// Event dependency graph of a game directory.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QuestGraph {
+    /// Every linked node; events, quests and the rest without an edge are
+    /// left out.
+    pub nodes: Vec<FlowNodeInfo>,
+    pub edges: Vec<FlowEdge>,
+    /// Every `Quest.scr` entry, in file order.
+    pub quests: Vec<QuestFlow>,
+    pub untriggered: Vec<UntriggeredEvent>,
+    /// Event ids something refers to that `Event.ini` does not define.
+    pub undefined_events: Vec<i32>,
+    pub warnings: Vec<String>,
+}
+
+/// `addquest` call of the script of `event_id`.
+struct QuestCall {
+    quest_id: i32,
+    event_id: i32,
+    state: Option<i32>,
+}
+
+#[derive(Default)]
+struct GraphBuilder {
+    nodes: BTreeMap<FlowNode, String>,
+    edges: BTreeSet<(FlowNode, FlowNode, FlowEdgeKind, String)>,
+}
+
+impl GraphBuilder {
+    fn edge(
+        &mut self,
+        from: (FlowNode, String),
+        to: (FlowNode, String),
+        kind: FlowEdgeKind,
+        detail: impl Into<String>,
+    ) {
+        self.nodes.entry(from.0.clone()).or_insert(from.1);
+        self.nodes.entry(to.0.clone()).or_insert(to.1);
+        self.edges.insert((from.0, to.0, kind, detail.into()));
+    }
+}
+
+impl QuestGraph {
+    pub fn build(inputs: &QuestFlowInputs) -> Self {
+        let events: BTreeMap<i32, &Event> = inputs.events.iter().map(|e| (e.event_id, e)).collect();
+        let monsters: BTreeMap<i32, &Monster> = inputs.monsters.iter().map(|m| (m.id, m)).collect();
+        let event = |id: i32| {
+            let label = match events.get(&id) {
+                Some(Event {
+                    event_filename: Some(file),
+                    ..
+                }) => format!("Event {id} ({file})"),
+                Some(_) => format!("Event {id}"),
+                None => format!("Event {id} (undefined)"),
+            };
+            (FlowNode::Event { id }, label)
+        };
+        let mut graph = GraphBuilder::default();
+
+        for e in &inputs.events {
+            if e.required_event_id > 0 {
+                graph.edge(
+                    event(e.required_event_id),
+                    event(e.event_id),
+                    FlowEdgeKind::Requires,
+                    event_condition(e.event_type),
+                );
+            }
+        }
+
+        let mut calls = Vec::new();
+        for (file, script) in &inputs.scripts {
+            for action in script.actions.iter().filter(|a| a.raw_content.is_none()) {
+                let Some(signature) = ACT_SIGNATURES
+                    .iter()
+                    .find(|s| s.function.eq_ignore_ascii_case(&action.function_name))
+                else {
+                    continue;
+                };
+                let ActParameter::Record { kind, index } = signature.parameter else {
+                    continue;
+                };
+                let param = |index: usize| -> Option<i32> {
+                    action.parameters.get(index)?.trim().parse().ok()
+                };
+                let Some(id) = param(index) else {
+                    continue;
+                };
+                match kind {
+                    RecordKind::Quest => calls.push(QuestCall {
+                        quest_id: id,
+                        event_id: script.id,
+                        state: param(index + 1),
+                    }),
+                    RecordKind::Event => graph.edge(
+                        event(script.id),
+                        event(id),
+                        FlowEdgeKind::Triggers,
+                        format!("{} in {file}", signature.function),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+
+        for (file, dialogues) in &inputs.dialogues {
+            let name = file.rsplit('/').next().unwrap_or(file);
+            for dialogue in dialogues {
+                let node = (
+                    FlowNode::Dialogue {
+                        file: file.clone(),
+                        id: dialogue.id,
+                    },
+                    format!("{name} #{}", dialogue.id),
+                );
+                if let Some(id) = dialogue.required_event_id.filter(|&id| id > 0) {
+                    graph.edge(
+                        event(id),
+                        node.clone(),
+                        FlowEdgeKind::Requires,
+                        "shows dialogue",
+                    );
+                }
+                if let Some(id) = dialogue.triggered_event_id.filter(|&id| id > 0) {
+                    graph.edge(node, event(id), FlowEdgeKind::Triggers, "dialogue");
+                }
+            }
+        }
+
+        for map in &inputs.maps {
+            let map_node = (
+                FlowNode::Map {
+                    map: map.map_filename.clone(),
+                },
+                format!("{}: {}", map.map_filename, map.map_name),
+            );
+            if map.camera_event > 0 {
+                graph.edge(
+                    map_node.clone(),
+                    event(map.camera_event),
+                    FlowEdgeKind::Triggers,
+                    "camera",
+                );
+            }
+            let mut tiles: BTreeMap<i32, Vec<(i32, i32)>> = BTreeMap::new();
+            for &(x, y, id) in &map.tile_events {
+                tiles.entry(id).or_default().push((x, y));
+            }
+            for (id, tiles) in tiles {
+                let detail = match tiles.as_slice() {
+                    [(x, y)] => format!("tile ({x}, {y})"),
+                    _ => format!("{} tiles", tiles.len()),
+                };
+                graph.edge(map_node.clone(), event(id), FlowEdgeKind::Triggers, detail);
+            }
+
+            let file = map.monster_file.as_deref().unwrap_or_default();
+            for (record, placement) in map.monsters.iter().enumerate() {
+                if placement.event_id_on_kill <= 0 {
+                    continue;
+                }
+                let name = monsters
+                    .get(&placement.monster_db_id)
+                    .map_or("?", |m| m.name.as_str());
+                let node = (
+                    FlowNode::Monster {
+                        file: file.to_string(),
+                        record,
+                    },
+                    format!(
+                        "{name} ({}, {}, {})",
+                        map.map_filename, placement.map_x, placement.map_y
+                    ),
+                );
+                graph.edge(
+                    node,
+                    event(placement.event_id_on_kill),
+                    FlowEdgeKind::Triggers,
+                    "on kill",
+                );
+            }
+
+            let file = map.extra_file.as_deref().unwrap_or_default();
+            for (record, extra) in map.extras.iter().enumerate() {
+                if extra.interaction_event_id <= 0 {
+                    continue;
+                }
+                let node = (
+                    FlowNode::Extra {
+                        file: file.to_string(),
+                        record,
+                    },
+                    format!(
+                        "{} ({}, {}, {})",
+                        extra.object_name, map.map_filename, extra.map_x, extra.map_y
+                    ),
+                );
+                graph.edge(
+                    node,
+                    event(extra.interaction_event_id),
+                    FlowEdgeKind::Triggers,
+                    "on interaction",
+                );
+            }
+        }
+
+        let quests: BTreeMap<i32, &Quest> = inputs.quests.iter().map(|q| (q.id, q)).collect();
+        // Lowest and highest state each quest is given.
+        let mut states: BTreeMap<i32, (i32, i32)> = BTreeMap::new();
+        for call in &calls {
+            if let Some(state) = call.state {
+                let (low, high) = states.entry(call.quest_id).or_insert((state, state));
+                *low = (*low).min(state);
+                *high = (*high).max(state);
+            }
+        }
+        let step_kind = |call: &QuestCall| match (call.state, states.get(&call.quest_id)) {
+            (Some(state), Some(&(low, high))) if state != low => {
+                if state == high {
+                    FlowEdgeKind::FinishesQuest
+                } else {
+                    FlowEdgeKind::UpdatesQuest
+                }
+            }
+            _ => FlowEdgeKind::StartsQuest,
+        };
+        for call in &calls {
+            let kind = step_kind(call);
+            let label = match quests.get(&call.quest_id) {
+                Some(quest) => format!("Quest {}: {}", quest.id, quest.title),
+                None => format!("Quest {} (undefined)", call.quest_id),
+            };
+            graph.edge(
+                event(call.event_id),
+                (FlowNode::Quest { id: call.quest_id }, label),
+                kind,
+                call.state
+                    .map_or_else(|| "addquest".to_string(), |s| format!("state {s}")),
+            );
+        }
+
+        let edges: Vec<FlowEdge> = graph
+            .edges
+            .iter()
+            .map(|(from, to, kind, detail)| FlowEdge {
+                from: from.key(),
+                to: to.key(),
+                kind: *kind,
+                detail: detail.clone(),
+            })
+            .collect();
+        let labels: BTreeMap<String, &String> = graph
+            .nodes
+            .iter()
+            .map(|(node, label)| (node.key(), label))
+            .collect();
+        let sources_of = |key: &str, kind: FlowEdgeKind| -> Vec<String> {
+            edges
+                .iter()
+                .filter(|e| e.to == key && e.kind == kind)
+                .map(|e| labels[&e.from].clone())
+                .collect()
+        };
+
+        let steps = |kind: FlowEdgeKind, quest_id: i32| -> Vec<QuestStep> {
+            calls
+                .iter()
+                .filter(|call| call.quest_id == quest_id && step_kind(call) == kind)
+                .map(|call| QuestStep {
+                    event_id: call.event_id,
+                    state: call.state,
+                    triggered_by: sources_of(
+                        &FlowNode::Event { id: call.event_id }.key(),
+                        FlowEdgeKind::Triggers,
+                    ),
+                })
+                .collect()
+        };
+        let quests = inputs
+            .quests
+            .iter()
+            .map(|quest| QuestFlow {
+                id: quest.id,
+                type_id: quest.type_id,
+                title: quest.title.clone(),
+                started_by: steps(FlowEdgeKind::StartsQuest, quest.id),
+                updated_by: steps(FlowEdgeKind::UpdatesQuest, quest.id),
+                finished_by: steps(FlowEdgeKind::FinishesQuest, quest.id),
+            })
+            .collect();
+
+        let mut required = BTreeSet::new();
+        let mut triggered = BTreeSet::new();
+        let mut undefined_events = BTreeSet::new();
+        for (from, to, kind, _) in &graph.edges {
+            for node in [from, to] {
+                if let FlowNode::Event { id } = node
+                    && !events.contains_key(id)
+                {
+                    undefined_events.insert(*id);
+                }
+            }
+            match (kind, from, to) {
+                (FlowEdgeKind::Requires, FlowNode::Event { id }, _) => {
+                    required.insert(*id);
+                }
+                (FlowEdgeKind::Triggers, _, FlowNode::Event { id }) => {
+                    triggered.insert(*id);
+                }
+                _ => {}
+            }
+        }
+        let untriggered = required
+            .difference(&triggered)
+            .map(|&id| {
+                let key = FlowNode::Event { id }.key();
+                UntriggeredEvent {
+                    event_id: id,
+                    defined: events.contains_key(&id),
+                    required_by: edges
+                        .iter()
+                        .filter(|e| e.from == key && e.kind == FlowEdgeKind::Requires)
+                        .map(|e| labels[&e.to].clone())
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            nodes: graph
+                .nodes
+                .into_iter()
+                .map(|(node, label)| FlowNodeInfo {
+                    key: node.key(),
+                    node,
+                    label,
+                })
+                .collect(),
+            edges,
+            quests,
+            untriggered,
+            undefined_events: undefined_events.into_iter().collect(),
+            warnings: inputs.warnings.clone(),
+        }
+    }
+
+    /// The part of the graph leading up to quest `quest_id`: the quest and
+    /// every node with a path to it.
+    pub fn upstream_of(mut self, quest_id: i32) -> Self {
+        let mut kept = BTreeSet::from([FlowNode::Quest { id: quest_id }.key()]);
+        let mut pending: Vec<String> = kept.iter().cloned().collect();
+        while let Some(key) = pending.pop() {
+            for edge in self.edges.iter().filter(|e| e.to == key) {
+                if kept.insert(edge.from.clone()) {
+                    pending.push(edge.from.clone());
+                }
+            }
+        }
+        let is_kept_event = |id: i32| kept.contains(&FlowNode::Event { id }.key());
+        self.nodes.retain(|n| kept.contains(&n.key));
+        self.edges
+            .retain(|e| kept.contains(&e.from) && kept.contains(&e.to));
+        self.quests.retain(|q| q.id == quest_id);
+        self.untriggered.retain(|u| is_kept_event(u.event_id));
+        self.undefined_events.retain(|&id| is_kept_event(id));
+        self
+    }
+
+    /// Graphviz DOT source: quests as notes, untriggered events in red and
+    /// undefined ones dashed.
+    pub fn to_dot(&self) -> String {
+        let untriggered: BTreeSet<i32> = self.untriggered.iter().map(|u| u.event_id).collect();
+        let mut dot = String::from(
+            "digraph quest_flow {\n  rankdir=LR;\n  node [fontname=\"Helvetica\", fontsize=10];\n  edge [fontname=\"Helvetica\", fontsize=9];\n",
+        );
+        for node in &self.nodes {
+            let style = match &node.node {
+                FlowNode::Event { id } => {
+                    let mut style = String::from("shape=box");
+                    if untriggered.contains(id) {
+                        style.push_str(", color=red, fontcolor=red");
+                    }
+                    if self.undefined_events.contains(id) {
+                        style.push_str(", style=dashed");
+                    }
+                    style
+                }
+                FlowNode::Quest { .. } => "shape=note, style=filled, fillcolor=\"#fff2b3\"".into(),
+                FlowNode::Dialogue { .. } => "shape=ellipse".into(),
+                FlowNode::Monster { .. } => "shape=octagon".into(),
+                FlowNode::Extra { .. } => "shape=box3d".into(),
+                FlowNode::Map { .. } => "shape=folder".into(),
+            };
+            dot.push_str(&format!(
+                "  {} [label={}, {style}];\n",
+                dot_quote(&node.key),
+                dot_quote(&node.label)
+            ));
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                FlowEdgeKind::Requires => ", style=dashed",
+                FlowEdgeKind::Triggers => "",
+                FlowEdgeKind::StartsQuest => ", color=\"#2b7bb9\"",
+                FlowEdgeKind::UpdatesQuest => ", color=\"#7f7f7f\"",
+                FlowEdgeKind::FinishesQuest => ", color=\"#2e8b57\", penwidth=2",
+            };
+            dot.push_str(&format!(
+                "  {} -> {} [label={}{style}];\n",
+                dot_quote(&edge.from),
+                dot_quote(&edge.to),
+                dot_quote(&edge.detail)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// When an event with a prerequisite runs, from its `Event.ini` type.
+fn event_condition(event_type: EventType) -> &'static str {
+    match event_type {
+        EventType::ContinueOnUnsatisfied => "while unsatisfied",
+        EventType::ExecuteOnSatisfied => "once satisfied",
+        EventType::Conditional | EventType::Unknown => "requires",
+    }
+}
+
+/// A DOT string literal.
+fn dot_quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::references::event_scr::ActionFunction;
+
+    fn event(event_id: i32, required_event_id: i32) -> Event {
+        Event {
+            event_id,
+            required_event_id,
+            event_type: EventType::ExecuteOnSatisfied,
+            event_filename: Some(format!("Event{event_id}.scr")),
+            counter: 0,
+        }
+    }
+
+    fn addquest(event_id: i32, quest: i32, state: i32) -> (String, EventScript) {
+        let script = EventScript {
+            id: event_id,
+            actions: vec![ActionFunction {
+                prefix: None,
+                function_name: "AddQuest".into(),
+                parameters: vec![quest.to_string(), state.to_string()],
+                raw_content: None,
+            }],
+            ..Default::default()
+        };
+        (format!("Ref/Event{event_id}.scr"), script)
+    }
+
+    fn inputs() -> QuestFlowInputs {
+        QuestFlowInputs {
+            // 3 waits for 2, which a dialogue triggers; 5 waits for 4,
+            // which nothing triggers.
+            events: vec![event(2, 0), event(3, 2), event(4, 0), event(5, 4)],
+            quests: vec![
+                Quest {
+                    id: 1,
+                    type_id: 0,
+                    title: "Find the \"relic\"".into(),
+                    description: String::new(),
+                },
+                Quest {
+                    id: 9,
+                    type_id: 1,
+                    title: "Never given".into(),
+                    description: String::new(),
+                },
+            ],
+            monsters: vec![Monster {
+                id: 4,
+                name: "Goblin".into(),
+                ..Default::default()
+            }],
+            scripts: vec![addquest(2, 1, 1), addquest(3, 1, 2)],
+            dialogues: vec![(
+                "NpcInGame/Dlgmap1.dlg".into(),
+                vec![
+                    DialogueScript {
+                        id: 10,
+                        triggered_event_id: Some(2),
+                        ..Default::default()
+                    },
+                    DialogueScript {
+                        id: 11,
+                        required_event_id: Some(7),
+                        ..Default::default()
+                    },
+                ],
+            )],
+            maps: vec![MapTriggers {
+                map_filename: "map1".into(),
+                map_name: "Aesh".into(),
+                camera_event: 3,
+                tile_events: vec![(1, 1, 4), (2, 1, 3), (3, 1, 3)],
+                monster_file: Some("MonsterInGame/Monmap1.ref".into()),
+                monsters: vec![
+                    MonsterRef::default(),
+                    MonsterRef {
+                        monster_db_id: 4,
+                        event_id_on_kill: 5,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            warnings: Vec::new(),
+        }
+    }
+
+    fn has_edge(graph: &QuestGraph, from: &str, to: &str, kind: FlowEdgeKind) -> bool {
+        graph
+            .edges
+            .iter()
+            .any(|e| e.from == from && e.to == to && e.kind == kind)
+    }
+
+    #[test]
+    fn links_every_source_and_finds_untriggered_events() {
+        let graph = QuestGraph::build(&inputs());
+
+        assert!(has_edge(
+            &graph,
+            "event:2",
+            "event:3",
+            FlowEdgeKind::Requires
+        ));
+        assert!(has_edge(
+            &graph,
+            "dialogue:NpcInGame/Dlgmap1.dlg:10",
+            "event:2",
+            FlowEdgeKind::Triggers
+        ));
+        assert!(has_edge(
+            &graph,
+            "map:map1",
+            "event:3",
+            FlowEdgeKind::Triggers
+        ));
+        assert!(has_edge(
+            &graph,
+            "monster:MonsterInGame/Monmap1.ref:1",
+            "event:5",
+            FlowEdgeKind::Triggers
+        ));
+        // Camera and the two tiles of event 3 collapse into one edge each.
+        let map_edges: Vec<_> = graph
+            .edges
+            .iter()
+            .filter(|e| e.from == "map:map1")
+            .map(|e| e.detail.as_str())
+            .collect();
+        assert_eq!(map_edges, ["2 tiles", "camera", "tile (1, 1)"]);
+        let goblin = graph
+            .nodes
+            .iter()
+            .find(|n| n.key == "monster:MonsterInGame/Monmap1.ref:1")
+            .unwrap();
+        assert_eq!(goblin.label, "Goblin (map1, 0, 0)");
+
+        // Event 4 is required by 5 but only a tile triggers it; event 7
+        // gates a dialogue and is triggered by nothing and defined nowhere.
+        let untriggered: Vec<_> = graph.untriggered.iter().map(|u| u.event_id).collect();
+        assert_eq!(untriggered, [7]);
+        assert!(!graph.untriggered[0].defined);
+        assert_eq!(graph.untriggered[0].required_by, ["Dlgmap1.dlg #11"]);
+        assert_eq!(graph.undefined_events, [7]);
+    }
+
+    #[test]
+    fn quest_states_split_into_start_and_finish() {
+        let mut inputs = inputs();
+        inputs.scripts.push(addquest(5, 1, 3));
+        inputs.scripts.push(addquest(4, 1, 2));
+        let graph = QuestGraph::build(&inputs);
+
+        let quest = &graph.quests[0];
+        let events = |steps: &[QuestStep]| steps.iter().map(|s| s.event_id).collect::<Vec<_>>();
+        assert_eq!(events(&quest.started_by), [2]);
+        assert_eq!(events(&quest.updated_by), [3, 4]);
+        assert_eq!(events(&quest.finished_by), [5]);
+        assert_eq!(quest.started_by[0].triggered_by, ["Dlgmap1.dlg #10"]);
+        assert_eq!(quest.finished_by[0].triggered_by, ["Goblin (map1, 0, 0)"]);
+        assert!(has_edge(
+            &graph,
+            "event:5",
+            "quest:1",
+            FlowEdgeKind::FinishesQuest
+        ));
+
+        let never = &graph.quests[1];
+        assert!(never.started_by.is_empty() && never.finished_by.is_empty());
+    }
+
+    #[test]
+    fn upstream_view_and_dot_output() {
+        let graph = QuestGraph::build(&inputs()).upstream_of(1);
+        let keys: BTreeSet<_> = graph.nodes.iter().map(|n| n.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            BTreeSet::from([
+                "event:2",
+                "event:3",
+                "quest:1",
+                "map:map1",
+                "dialogue:NpcInGame/Dlgmap1.dlg:10",
+            ])
+        );
+        assert!(graph.untriggered.is_empty());
+        assert_eq!(graph.quests.len(), 1);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph quest_flow {"));
+        assert!(dot.contains(r#""quest:1" [label="Quest 1: Find the \"relic\"", shape=note"#));
+        assert!(dot.contains(r#""event:2" -> "event:3" [label="once satisfied", style=dashed];"#));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+}
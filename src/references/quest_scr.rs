@@ -2,6 +2,7 @@ use std::io::{BufRead, BufReader, Read, Seek, Write};
 use std::path::Path;
 
 use crate::references::extractor::Extractor;
+use crate::references::validation::{FieldConstraint, FieldConstraints, FieldRule};
 use dispel_macros::Localizable;
 use encoding_rs::WINDOWS_1250;
 use encoding_rs_io::DecodeReaderBytesBuilder;
@@ -77,6 +78,24 @@ pub struct Quest {
     pub description: String,
 }
 
+/// Byte limits come from `#[translatable]`; both texts must also stay in
+/// their `|`-separated columns.
+impl FieldConstraints for Quest {
+    fn field_constraints() -> Vec<FieldConstraint> {
+        ["title", "description"]
+            .into_iter()
+            .map(|field| FieldConstraint {
+                field,
+                rule: FieldRule::Text {
+                    encoding: WINDOWS_1250,
+                    max_bytes: None,
+                    forbidden: &['|', '\r', '\n'],
+                },
+            })
+            .collect()
+    }
+}
+
 impl Extractor for Quest {
     fn parse<R: Read + Seek>(reader: &mut R, _len: u64) -> std::io::Result<Vec<Self>> {
         let decoded = DecodeReaderBytesBuilder::new()
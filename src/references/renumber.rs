@@ -0,0 +1,869 @@
+//! Safe deletion, insertion and reordering of positional catalog records.
+//!
+//! Item databases, `Monster.db`, `Magic.db` and `PrtIni.db` have no id
+//! column: a record's id is its position. Deleting or moving a record shifts
+//! every record after it, and each shop, drop, placement, event script and
+//! save that points at a shifted id silently changes meaning.
+//!
+//! [`Renumbering::plan`] applies one [`RecordEdit`] to a catalog and rewrites
+//! every reference the [`ReferenceIndex`] knows about. Nothing is written to
+//! the game directory: the result holds the new file contents and a per-file
+//! preview of rewritten fields, and [`Renumbering::to_changelog`] turns it
+//! into a mod.
+
+use std::collections::BTreeSet;
+use std::io::Cursor;
+use std::path::Path;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::modding::{ChangeAction, ChangeLog, ChangeOp, HISTORY_CAP};
+use crate::references::draw_item::DrawItem;
+use crate::references::edit_item_db::EditItem;
+use crate::references::enums::{InventoryItem, ItemTypeId};
+use crate::references::event_item_db::EventItem;
+use crate::references::event_scr::EventScript;
+use crate::references::extra_ref::ExtraRef;
+use crate::references::extractor::Extractor;
+use crate::references::heal_item_db::HealItem;
+use crate::references::magic_db::MagicSpell;
+use crate::references::misc_item_db::MiscItem;
+use crate::references::monster_db::Monster;
+use crate::references::monster_ref::MonsterRef;
+use crate::references::npc_ref::NPC;
+use crate::references::party_ini_db::PartyIniNpc;
+use crate::references::party_level_db::PartyLevelNpc;
+use crate::references::save_file::SaveFile;
+use crate::references::store_db::Store;
+use crate::references::usages::{
+    ACT_SIGNATURES, ActParameter, NO_PARTY_SPELL, RecordKind, ReferenceIndex,
+    SAVE_EMPTY_CATALOG_INDEX, Source, Usage, collect_files,
+};
+use crate::references::weapons_db::WeaponItem;
+
+#[derive(Debug, Error)]
+pub enum RenumberError {
+    #[error("could not read {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("{0} ids are not record positions and cannot be renumbered")]
+    NotPositional(RecordKind),
+
+    #[error("{0} not found in the game directory")]
+    CatalogNotFound(RecordKind),
+
+    #[error("record {index} is out of range ({kind} has {len} records)")]
+    OutOfRange {
+        kind: RecordKind,
+        index: usize,
+        len: usize,
+    },
+
+    #[error("a deleted record cannot be redirected to itself")]
+    RedirectToSelf,
+
+    #[error(
+        "{kind} #{id} is still referenced in {} place(s) (first: {}); pass a redirect target",
+        .usages.len(),
+        .usages[0]
+    )]
+    StillReferenced {
+        kind: RecordKind,
+        id: i32,
+        usages: Vec<Usage>,
+    },
+
+    #[error("{file} #{record} {field}: new id {id} does not fit the field")]
+    Unrepresentable {
+        file: String,
+        record: usize,
+        field: String,
+        id: i32,
+    },
+
+    #[error("renumbering touches {0} files, more than a mod change log can hold ({HISTORY_CAP})")]
+    TooManyFiles(usize),
+}
+
+pub type Result<T> = std::result::Result<T, RenumberError>;
+
+/// One structural change to a positional catalog. Indices are positions
+/// before the edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum RecordEdit {
+    /// Remove record `index`. References to it are pointed at `redirect_to`;
+    /// without a redirect the edit is refused while any remain.
+    Delete {
+        index: usize,
+        redirect_to: Option<usize>,
+    },
+    /// Insert a new record at `index`: a copy of record `copy_of`, or an
+    /// all-default record.
+    Insert {
+        index: usize,
+        copy_of: Option<usize>,
+    },
+    /// Move record `from` so that it ends up at position `to`.
+    Move { from: usize, to: usize },
+}
+
+impl RecordEdit {
+    /// Id that record `id` has after the edit; `None` for a deleted record
+    /// without a redirect. Negative ids are left alone.
+    pub fn map_id(&self, id: i32) -> Option<i32> {
+        let Ok(old) = usize::try_from(id) else {
+            return Some(id);
+        };
+        let new = match *self {
+            RecordEdit::Delete { index, redirect_to } => {
+                let target = if old == index { redirect_to? } else { old };
+                if target > index { target - 1 } else { target }
+            }
+            RecordEdit::Insert { index, .. } => {
+                if old >= index {
+                    old + 1
+                } else {
+                    old
+                }
+            }
+            RecordEdit::Move { from, to } => match old {
+                _ if old == from => to,
+                _ if from < to && (from + 1..=to).contains(&old) => old - 1,
+                _ if to < from && (to..from).contains(&old) => old + 1,
+                _ => old,
+            },
+        };
+        i32::try_from(new).ok()
+    }
+
+    /// Records whose id changes (or disappears) when the edit is applied to
+    /// a catalog of `len` records.
+    fn affected(&self, len: usize) -> impl Iterator<Item = i32> + '_ {
+        (0..len as i32).filter(move |&id| self.map_id(id) != Some(id))
+    }
+
+    fn check(&self, kind: RecordKind, len: usize) -> Result<()> {
+        let out_of_range = |index: usize| RenumberError::OutOfRange { kind, index, len };
+        match *self {
+            RecordEdit::Delete { index, redirect_to } => {
+                if index >= len {
+                    return Err(out_of_range(index));
+                }
+                match redirect_to {
+                    Some(target) if target == index => return Err(RenumberError::RedirectToSelf),
+                    Some(target) if target >= len => return Err(out_of_range(target)),
+                    _ => {}
+                }
+            }
+            RecordEdit::Insert { index, copy_of } => {
+                if index > len {
+                    return Err(out_of_range(index));
+                }
+                if let Some(source) = copy_of.filter(|&source| source >= len) {
+                    return Err(out_of_range(source));
+                }
+            }
+            RecordEdit::Move { from, to } => {
+                if let Some(index) = [from, to].into_iter().find(|&index| index >= len) {
+                    return Err(out_of_range(index));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn apply<T: Clone + Default>(&self, records: &mut Vec<T>) {
+        match *self {
+            RecordEdit::Delete { index, .. } => {
+                records.remove(index);
+            }
+            RecordEdit::Insert { index, copy_of } => {
+                let record = copy_of.map(|i| records[i].clone()).unwrap_or_default();
+                records.insert(index, record);
+            }
+            RecordEdit::Move { from, to } => {
+                let record = records.remove(from);
+                records.insert(to, record);
+            }
+        }
+    }
+}
+
+/// One rewritten reference.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fixup {
+    /// Index of the referencing record within its file.
+    pub record: usize,
+    /// Field path, as in [`Usage::field`].
+    pub field: String,
+    pub label: Option<String>,
+    pub old: i32,
+    pub new: i32,
+}
+
+/// New content of one file touched by a renumbering.
+#[derive(Debug, Clone)]
+pub struct RewrittenFile {
+    /// Path relative to the game directory with `/` separators.
+    pub file: String,
+    pub content: Vec<u8>,
+    /// Rewritten references; empty for the catalog itself.
+    pub fixups: Vec<Fixup>,
+}
+
+/// A planned [`RecordEdit`] with every dependent reference rewritten.
+#[derive(Debug, Clone)]
+pub struct Renumbering {
+    pub kind: RecordKind,
+    pub edit: RecordEdit,
+    /// The catalog first, then referencing files in path order.
+    pub files: Vec<RewrittenFile>,
+}
+
+impl Renumbering {
+    /// Apply `edit` to the `kind` catalog under `game_dir` and rewrite the
+    /// files `index` lists as referencing a renumbered record. `index` should
+    /// have been built over the same directory.
+    pub fn plan(
+        game_dir: &Path,
+        index: &ReferenceIndex,
+        kind: RecordKind,
+        edit: RecordEdit,
+    ) -> Result<Self> {
+        let catalog = find_catalog(game_dir, kind)?;
+        let catalog_path = game_dir.join(&catalog);
+        let (content, len) = match kind {
+            RecordKind::WeaponItem => edit_catalog::<WeaponItem>(&catalog_path, kind, &edit),
+            RecordKind::HealItem => edit_catalog::<HealItem>(&catalog_path, kind, &edit),
+            RecordKind::EditItem => edit_catalog::<EditItem>(&catalog_path, kind, &edit),
+            RecordKind::EventItem => edit_catalog::<EventItem>(&catalog_path, kind, &edit),
+            RecordKind::MiscItem => edit_catalog::<MiscItem>(&catalog_path, kind, &edit),
+            RecordKind::MagicSpell => edit_catalog::<MagicSpell>(&catalog_path, kind, &edit),
+            RecordKind::Monster => edit_catalog::<Monster>(&catalog_path, kind, &edit),
+            RecordKind::PartyIni => edit_catalog::<PartyIniNpc>(&catalog_path, kind, &edit),
+            _ => return Err(RenumberError::NotPositional(kind)),
+        }
+        .map_err(|error| error.in_file(&catalog))?;
+
+        if let RecordEdit::Delete {
+            index: deleted,
+            redirect_to: None,
+        } = edit
+        {
+            let usages = index.usages(kind, deleted as i32);
+            if !usages.is_empty() {
+                return Err(RenumberError::StillReferenced {
+                    kind,
+                    id: deleted as i32,
+                    usages: usages.to_vec(),
+                });
+            }
+        }
+
+        let referencing: BTreeSet<&str> = edit
+            .affected(len)
+            .flat_map(|id| index.usages(kind, id))
+            .map(|usage| usage.file.as_str())
+            .collect();
+
+        let mut files = vec![RewrittenFile {
+            file: catalog,
+            content,
+            fixups: Vec::new(),
+        }];
+        for file in referencing {
+            let Some(source) = Source::of(file) else {
+                continue;
+            };
+            let mut fixer = Fixer {
+                kind,
+                edit,
+                file,
+                fixups: Vec::new(),
+            };
+            let content = fixer
+                .rewrite(source, &game_dir.join(file))
+                .map_err(|error| error.in_file(file))?;
+            if !fixer.fixups.is_empty() {
+                files.push(RewrittenFile {
+                    file: file.to_string(),
+                    content,
+                    fixups: fixer.fixups,
+                });
+            }
+        }
+        Ok(Self { kind, edit, files })
+    }
+
+    /// Number of rewritten references across all files.
+    pub fn fixup_count(&self) -> usize {
+        self.files.iter().map(|file| file.fixups.len()).sum()
+    }
+
+    /// One `FileReplace` action per touched file, catalog first.
+    pub fn to_changelog(&self) -> Result<ChangeLog> {
+        if self.files.len() > HISTORY_CAP {
+            return Err(RenumberError::TooManyFiles(self.files.len()));
+        }
+        let description = self.description();
+        let actions = self
+            .files
+            .iter()
+            .map(|file| {
+                ChangeAction::new(
+                    file.file.clone(),
+                    ChangeOp::FileReplace {
+                        content: file.content.clone(),
+                    },
+                )
+                .with_description(description.clone())
+            })
+            .collect();
+        Ok(ChangeLog::from_actions(actions))
+    }
+
+    /// Short summary of the edit, e.g. `delete Monster.db #12 (redirect to #3)`.
+    pub fn description(&self) -> String {
+        let kind = self.kind;
+        match self.edit {
+            RecordEdit::Delete {
+                index,
+                redirect_to: None,
+            } => format!("delete {kind} #{index}"),
+            RecordEdit::Delete {
+                index,
+                redirect_to: Some(target),
+            } => format!("delete {kind} #{index} (redirect to #{target})"),
+            RecordEdit::Insert {
+                index,
+                copy_of: None,
+            } => format!("insert {kind} #{index}"),
+            RecordEdit::Insert {
+                index,
+                copy_of: Some(source),
+            } => format!("insert {kind} #{index} (copy of #{source})"),
+            RecordEdit::Move { from, to } => format!("move {kind} #{from} to #{to}"),
+        }
+    }
+}
+
+/// Errors raised while a file is being processed, before its path is known
+/// to the caller.
+enum FileError {
+    Io(std::io::Error),
+    Renumber(RenumberError),
+}
+
+impl FileError {
+    fn in_file(self, file: &str) -> RenumberError {
+        match self {
+            FileError::Io(source) => RenumberError::Io {
+                path: file.to_string(),
+                source,
+            },
+            FileError::Renumber(error) => error,
+        }
+    }
+}
+
+impl From<std::io::Error> for FileError {
+    fn from(error: std::io::Error) -> Self {
+        FileError::Io(error)
+    }
+}
+
+impl From<RenumberError> for FileError {
+    fn from(error: RenumberError) -> Self {
+        FileError::Renumber(error)
+    }
+}
+
+fn find_catalog(game_dir: &Path, kind: RecordKind) -> Result<String> {
+    let mut files = Vec::new();
+    collect_files(game_dir, game_dir, &mut files);
+    files.sort();
+    files
+        .into_iter()
+        .find(|file| {
+            let name = file.rsplit('/').next().unwrap_or(file);
+            name.eq_ignore_ascii_case(kind.file_name())
+        })
+        .ok_or(RenumberError::CatalogNotFound(kind))
+}
+
+fn edit_catalog<T: Extractor + Clone + Default>(
+    path: &Path,
+    kind: RecordKind,
+    edit: &RecordEdit,
+) -> std::result::Result<(Vec<u8>, usize), FileError> {
+    let mut records = T::read_file(path)?;
+    let len = records.len();
+    edit.check(kind, len)?;
+    edit.apply(&mut records);
+    Ok((write_records(&records)?, len))
+}
+
+fn write_records<T: Extractor>(records: &[T]) -> std::io::Result<Vec<u8>> {
+    let mut content = Cursor::new(Vec::new());
+    T::to_writer(records, &mut content)?;
+    Ok(content.into_inner())
+}
+
+/// Rewrites the references of one file, mirroring the fields
+/// [`ReferenceIndex`] indexes.
+struct Fixer<'a> {
+    kind: RecordKind,
+    edit: RecordEdit,
+    file: &'a str,
+    fixups: Vec<Fixup>,
+}
+
+impl Fixer<'_> {
+    fn rewrite(&mut self, source: Source, path: &Path) -> std::result::Result<Vec<u8>, FileError> {
+        let content = match source {
+            Source::Stores => self.rewrite_file(path, Self::fix_store)?,
+            Source::Monsters => self.rewrite_file(path, Self::fix_monster)?,
+            Source::PartyLevels => self.rewrite_file(path, Self::fix_party_level)?,
+            Source::DrawItems => self.rewrite_file(path, Self::fix_draw_item)?,
+            Source::ExtraRefs => self.rewrite_file(path, Self::fix_extra_ref)?,
+            Source::MonsterRefs => self.rewrite_file(path, Self::fix_monster_ref)?,
+            Source::NpcRefs => self.rewrite_file(path, Self::fix_npc_ref)?,
+            Source::EventScripts => self.rewrite_file(path, Self::fix_event_script)?,
+            Source::Saves => self.rewrite_file(path, Self::fix_save)?,
+            // Only reference text catalogs, which are never renumbered.
+            Source::PartyRefs | Source::EventNpcs | Source::Dialogs => std::fs::read(path)?,
+        };
+        Ok(content)
+    }
+
+    fn rewrite_file<T: Extractor>(
+        &mut self,
+        path: &Path,
+        fix: fn(&mut Self, usize, &mut T) -> Result<()>,
+    ) -> std::result::Result<Vec<u8>, FileError> {
+        let mut records = T::read_file(path)?;
+        for (record, value) in records.iter_mut().enumerate() {
+            fix(self, record, value)?;
+        }
+        Ok(write_records(&records)?)
+    }
+
+    fn fix_store(&mut self, record: usize, store: &mut Store) -> Result<()> {
+        let label = label_of(&store.store_name);
+        for (slot, (_, product_type, product_id)) in store.products.iter_mut().enumerate() {
+            let kind = RecordKind::from_product_type(*product_type);
+            let field = format!("products[{slot}]");
+            self.fix(record, &label, kind, product_id, field, |_| false)?;
+        }
+        Ok(())
+    }
+
+    fn fix_monster(&mut self, record: usize, monster: &mut Monster) -> Result<()> {
+        let label = label_of(&monster.name);
+        // Zero and negative values are empty spell slots.
+        let empty = |id: i32| id <= 0;
+        let slots = [
+            ("known_spell_slot1", &mut monster.known_spell_slot1),
+            ("known_spell_slot2", &mut monster.known_spell_slot2),
+            ("known_spell_slot3", &mut monster.known_spell_slot3),
+        ];
+        for (field, slot) in slots {
+            self.fix(record, &label, RecordKind::MagicSpell, slot, field, empty)?;
+        }
+        Ok(())
+    }
+
+    fn fix_party_level(&mut self, record: usize, npc: &mut PartyLevelNpc) -> Result<()> {
+        let party = RecordKind::PartyIni;
+        self.fix(
+            record,
+            &None,
+            party,
+            &mut npc.npc_index,
+            "npc_index",
+            |_| false,
+        )?;
+        let no_spell = |id: i32| id == i32::from(NO_PARTY_SPELL);
+        for (level, row) in npc.records.iter_mut().enumerate() {
+            let spells = [
+                ("magic_spell_id_1", &mut row.magic_spell_id_1),
+                ("magic_spell_id_2", &mut row.magic_spell_id_2),
+                ("magic_spell_id_3", &mut row.magic_spell_id_3),
+            ];
+            for (field, spell) in spells {
+                let field = format!("records[{level}].{field}");
+                self.fix(
+                    record,
+                    &None,
+                    RecordKind::MagicSpell,
+                    spell,
+                    field,
+                    no_spell,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fix_draw_item(&mut self, record: usize, draw_item: &mut DrawItem) -> Result<()> {
+        self.fix_item(record, &None, &mut draw_item.item, "item")
+    }
+
+    fn fix_extra_ref(&mut self, record: usize, extra: &mut ExtraRef) -> Result<()> {
+        let label = label_of(&extra.object_name);
+        self.fix_item(record, &label, &mut extra.required_item, "required_item")?;
+        self.fix_item(record, &label, &mut extra.required_item2, "required_item2")?;
+        self.fix_item(record, &label, &mut extra.loot_item, "loot_item")
+    }
+
+    fn fix_monster_ref(&mut self, record: usize, placement: &mut MonsterRef) -> Result<()> {
+        let monster = &mut placement.monster_db_id;
+        self.fix(
+            record,
+            &None,
+            RecordKind::Monster,
+            monster,
+            "monster_db_id",
+            |_| false,
+        )?;
+        self.fix_item(record, &None, &mut placement.loot_item_1, "loot_item_1")?;
+        self.fix_item(record, &None, &mut placement.loot_item_2, "loot_item_2")?;
+        self.fix_item(record, &None, &mut placement.loot_item_3, "loot_item_3")
+    }
+
+    fn fix_npc_ref(&mut self, record: usize, npc: &mut NPC) -> Result<()> {
+        let label = label_of(&npc.name);
+        let item = &mut npc.interaction_result_item;
+        self.fix_item(record, &label, item, "interaction_result_item")
+    }
+
+    fn fix_event_script(&mut self, record: usize, script: &mut EventScript) -> Result<()> {
+        for (line, action) in script.actions.iter_mut().enumerate() {
+            if action.raw_content.is_some() {
+                continue;
+            }
+            let Some(signature) = ACT_SIGNATURES
+                .iter()
+                .find(|s| s.function.eq_ignore_ascii_case(&action.function_name))
+            else {
+                continue;
+            };
+            let param = |parameters: &[String], index: usize| -> Option<i32> {
+                parameters.get(index)?.trim().parse().ok()
+            };
+            let (kind, index) = match signature.parameter {
+                ActParameter::Record { kind, index } => (kind, index),
+                ActParameter::Item {
+                    type_index,
+                    id_index,
+                } => {
+                    let kind = param(&action.parameters, type_index)
+                        .and_then(|t| u8::try_from(t).ok())
+                        .and_then(ItemTypeId::from_u8)
+                        .and_then(RecordKind::from_item_type);
+                    let Some(kind) = kind else {
+                        continue;
+                    };
+                    (kind, id_index)
+                }
+            };
+            let Some(mut id) = param(&action.parameters, index) else {
+                continue;
+            };
+            let field = format!("actions[{line}].parameters[{index}]");
+            if self.fix(record, &None, kind, &mut id, field, |_| false)? {
+                action.parameters[index] = id.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    fn fix_save(&mut self, record: usize, save: &mut SaveFile) -> Result<()> {
+        let never = |_: i32| false;
+        let inventory = &mut save.inventory;
+        for (i, item) in inventory.weapon_items.iter_mut().enumerate() {
+            let field = format!("inventory.weapon_items[{i}]");
+            let id = &mut item.weapon_item_id;
+            self.fix(record, &None, RecordKind::WeaponItem, id, field, never)?;
+        }
+        for (i, item) in inventory.heal_items.iter_mut().enumerate() {
+            let field = format!("inventory.heal_items[{i}]");
+            let id = &mut item.heal_item_id;
+            self.fix(record, &None, RecordKind::HealItem, id, field, never)?;
+        }
+        for (i, item) in inventory.edit_items.iter_mut().enumerate() {
+            let field = format!("inventory.edit_items[{i}]");
+            let id = &mut item.edit_item_id;
+            self.fix(record, &None, RecordKind::EditItem, id, field, never)?;
+        }
+        for (i, item) in inventory.event_items.iter_mut().enumerate() {
+            let field = format!("inventory.event_items[{i}]");
+            let id = &mut item.event_item_id;
+            self.fix(record, &None, RecordKind::EventItem, id, field, never)?;
+        }
+        for (i, item) in inventory.misc_items.iter_mut().enumerate() {
+            let field = format!("inventory.misc_items[{i}]");
+            let id = &mut item.misc_item_id;
+            self.fix(record, &None, RecordKind::MiscItem, id, field, never)?;
+        }
+        let empty = |id: i32| id == SAVE_EMPTY_CATALOG_INDEX;
+        let equipment = &mut save.inventory_slots.equipped_equipment;
+        for (i, slot) in equipment.iter_mut().enumerate() {
+            let field = format!("inventory_slots.equipped_equipment[{i}]");
+            let id = &mut slot.weapon_catalog_index;
+            self.fix(record, &None, RecordKind::WeaponItem, id, field, empty)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite `value` when it is an id of the renumbered catalog. `empty`
+    /// recognises "no record" values, which are skipped and which a new id
+    /// must not collide with. Returns whether the value changed.
+    fn fix<I>(
+        &mut self,
+        record: usize,
+        label: &Option<String>,
+        kind: RecordKind,
+        value: &mut I,
+        field: impl Into<String>,
+        empty: impl Fn(i32) -> bool,
+    ) -> Result<bool>
+    where
+        I: Copy + TryInto<i32> + TryFrom<i32>,
+    {
+        if kind != self.kind {
+            return Ok(false);
+        }
+        let Ok(old) = (*value).try_into() else {
+            return Ok(false);
+        };
+        if empty(old) {
+            return Ok(false);
+        }
+        let usage = |field: String| Usage {
+            file: self.file.to_string(),
+            record,
+            field,
+            label: label.clone(),
+        };
+        let Some(new) = self.edit.map_id(old) else {
+            return Err(RenumberError::StillReferenced {
+                kind,
+                id: old,
+                usages: vec![usage(field.into())],
+            });
+        };
+        if new == old {
+            return Ok(false);
+        }
+        let field = field.into();
+        match I::try_from(new) {
+            Ok(converted) if !empty(new) => *value = converted,
+            _ => {
+                return Err(RenumberError::Unrepresentable {
+                    file: self.file.to_string(),
+                    record,
+                    field,
+                    id: new,
+                });
+            }
+        }
+        self.fixups.push(Fixup {
+            record,
+            field,
+            label: label.clone(),
+            old,
+            new,
+        });
+        Ok(true)
+    }
+
+    /// [`fix`](Self::fix) for packed type/id item references, whose id is a
+    /// single byte.
+    fn fix_item(
+        &mut self,
+        record: usize,
+        label: &Option<String>,
+        item: &mut InventoryItem,
+        field: &str,
+    ) -> Result<()> {
+        if item.is_empty() {
+            return Ok(());
+        }
+        let Some(kind) = item.item_type().and_then(RecordKind::from_item_type) else {
+            return Ok(());
+        };
+        let mut id = item.item_id();
+        if self.fix(record, label, kind, &mut id, field, |_| false)? {
+            *item = InventoryItem::from((item.raw() & !0xFF) | i32::from(id));
+        }
+        Ok(())
+    }
+}
+
+fn label_of(label: &str) -> Option<String> {
+    let label = label.trim();
+    (!label.is_empty()).then(|| label.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::references::enums::ProductType;
+
+    #[test]
+    fn test_map_id_shifts_records_after_the_edit() {
+        let delete = RecordEdit::Delete {
+            index: 2,
+            redirect_to: None,
+        };
+        let ids: Vec<_> = (0..5).map(|id| delete.map_id(id)).collect();
+        assert_eq!(ids, [Some(0), Some(1), None, Some(2), Some(3)]);
+
+        let redirect = RecordEdit::Delete {
+            index: 1,
+            redirect_to: Some(3),
+        };
+        assert_eq!(redirect.map_id(1), Some(2));
+
+        let insert = RecordEdit::Insert {
+            index: 1,
+            copy_of: None,
+        };
+        let ids: Vec<_> = (0..3).filter_map(|id| insert.map_id(id)).collect();
+        assert_eq!(ids, [0, 2, 3]);
+
+        let forward = RecordEdit::Move { from: 1, to: 3 };
+        let ids: Vec<_> = (0..5).filter_map(|id| forward.map_id(id)).collect();
+        assert_eq!(ids, [0, 3, 1, 2, 4]);
+
+        let backward = RecordEdit::Move { from: 3, to: 1 };
+        let ids: Vec<_> = (0..5).filter_map(|id| backward.map_id(id)).collect();
+        assert_eq!(ids, [0, 2, 3, 1, 4]);
+        assert_eq!(backward.map_id(-1), Some(-1));
+    }
+
+    #[test]
+    fn test_apply_matches_map_id() {
+        let edits = [
+            RecordEdit::Delete {
+                index: 1,
+                redirect_to: None,
+            },
+            RecordEdit::Insert {
+                index: 2,
+                copy_of: Some(0),
+            },
+            RecordEdit::Move { from: 0, to: 3 },
+            RecordEdit::Move { from: 3, to: 0 },
+        ];
+        for edit in edits {
+            let mut records: Vec<i32> = (0..4).collect();
+            edit.apply(&mut records);
+            for old in 0..4 {
+                if let Some(new) = edit.map_id(old) {
+                    assert_eq!(records[new as usize], old, "{edit:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixer_rewrites_shifted_references_and_reports_overflow() {
+        let mut fixer = Fixer {
+            kind: RecordKind::HealItem,
+            edit: RecordEdit::Delete {
+                index: 3,
+                redirect_to: None,
+            },
+            file: "CharacterInGame/STORE.DB",
+            fixups: Vec::new(),
+        };
+        let mut store = Store {
+            store_name: "Apothecary".to_string(),
+            products: vec![
+                (0, ProductType::Healing, 7),
+                (1, ProductType::Weapon, 7),
+                (2, ProductType::Healing, 1),
+            ],
+            ..Default::default()
+        };
+
+        fixer.fix_store(0, &mut store).unwrap();
+
+        assert_eq!(store.products[0].2, 6);
+        assert_eq!(store.products[1].2, 7, "other catalogs are untouched");
+        assert_eq!(store.products[2].2, 1);
+        assert_eq!(fixer.fixups.len(), 1);
+        assert_eq!(fixer.fixups[0].field, "products[0]");
+        assert_eq!(fixer.fixups[0].label.as_deref(), Some("Apothecary"));
+
+        fixer.edit = RecordEdit::Insert {
+            index: 0,
+            copy_of: None,
+        };
+        let mut item = InventoryItem::new(ItemTypeId::Healing, 255);
+        let error = fixer.fix_item(0, &None, &mut item, "item").unwrap_err();
+        assert!(matches!(
+            error,
+            RenumberError::Unrepresentable { id: 256, .. }
+        ));
+    }
+
+    #[test]
+    fn test_plan_refuses_deleting_a_referenced_record() {
+        let dir = std::env::temp_dir().join(format!("dispel_renumber_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("CharacterInGame")).unwrap();
+        std::fs::create_dir_all(dir.join("Ref")).unwrap();
+        let items: Vec<HealItem> = (0..3)
+            .map(|id| HealItem {
+                id,
+                name: format!("Potion {id}"),
+                ..Default::default()
+            })
+            .collect();
+        HealItem::save_file(&items, &dir.join("CharacterInGame/HealItem.db")).unwrap();
+        // Heal item #2 (0x0202) on map 1.
+        std::fs::write(dir.join("Ref/DRAWITEM.ref"), "(1,5,10,514)\r\n").unwrap();
+        let index = ReferenceIndex::build(&dir);
+
+        let delete = |redirect_to| RecordEdit::Delete {
+            index: 2,
+            redirect_to,
+        };
+        let refused = Renumbering::plan(&dir, &index, RecordKind::HealItem, delete(None));
+        let redirected = Renumbering::plan(&dir, &index, RecordKind::HealItem, delete(Some(0)));
+        let shifted = Renumbering::plan(
+            &dir,
+            &index,
+            RecordKind::HealItem,
+            RecordEdit::Delete {
+                index: 1,
+                redirect_to: None,
+            },
+        );
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(matches!(
+            refused,
+            Err(RenumberError::StillReferenced { id: 2, .. })
+        ));
+        let redirected = redirected.unwrap();
+        assert_eq!(redirected.files.len(), 2);
+        assert_eq!(redirected.files[1].fixups[0].new, 0);
+
+        let shifted = shifted.unwrap();
+        assert_eq!(shifted.files[0].file, "CharacterInGame/HealItem.db");
+        assert_eq!(shifted.files[1].file, "Ref/DRAWITEM.ref");
+        assert_eq!(shifted.files[1].fixups[0].old, 2);
+        assert_eq!(shifted.files[1].fixups[0].new, 1);
+        let changelog = shifted.to_changelog().unwrap();
+        assert_eq!(changelog.len(), 2);
+        assert_eq!(changelog.actions()[0].description, "delete HealItem.db #1");
+    }
+}
@@ -0,0 +1,210 @@
+// Semantic comparison of two save files.
+//
+// Diffing a save taken before and after a single in-game action is the
+// quickest way to learn which fields that action touches. Both saves are
+// compared field by field on the parsed model, grouped by the writer's
+// top-level sections, and each section's serialized bytes are compared as
+// well so changes inside reserved or not-yet-understood fields can be
+// located by their offset within the section.
+
+use super::SaveFile;
+use super::edit::DERIVED_FIELDS;
+use super::writer::{SaveSection, SaveWriter};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Error;
+
+/// Differences between two saves, in file order.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SaveDiff {
+    /// Sections that differ; identical sections are omitted.
+    pub sections: Vec<SectionDiff>,
+}
+
+/// Differences within one top-level save section.
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionDiff {
+    /// Section name, as used in read and write error messages.
+    pub section: &'static str,
+    /// Byte range of the section in the first save.
+    pub range_a: (u64, u64),
+    /// Byte range of the section in the second save.
+    pub range_b: (u64, u64),
+    /// Changed model fields.
+    pub changes: Vec<FieldChange>,
+    /// Changed byte runs within the serialized section.
+    pub byte_ranges: Vec<ByteRangeDiff>,
+}
+
+/// One changed leaf value of the save model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Field path, e.g. `maps[0].monsters[3].health_points`.
+    pub path: String,
+    /// Value in the first save; `None` when the record only exists in the second.
+    pub before: Option<Value>,
+    /// Value in the second save; `None` when the record only exists in the first.
+    pub after: Option<Value>,
+}
+
+/// A run of differing bytes inside a section.
+///
+/// Offsets are relative to the start of the section. When a section changed
+/// size, the run covers everything between the common prefix and the common
+/// suffix, so `len_a` and `len_b` differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ByteRangeDiff {
+    /// Offset of the run within the section in the first save.
+    pub offset: u64,
+    /// Length of the run in the first save.
+    pub len_a: u64,
+    /// Length of the run in the second save.
+    pub len_b: u64,
+}
+
+/// Top-level model fields serialized by each writer section.
+const SECTION_FIELDS: &[(&str, &[&str])] = &[
+    ("header and maps", &["game_tmp_blob_size", "maps"]),
+    ("post-maps", &["post_maps"]),
+    ("map viewport", &["map_viewport_state"]),
+    ("sprite paths", &["sprite_paths"]),
+    ("character stats", &["character"]),
+    ("inventory", &["inventory"]),
+    ("character state", &["character_state"]),
+    ("character identity", &["character_identity"]),
+    ("inventory slots", &["inventory_slots"]),
+    ("learned spells", &["learned_spells"]),
+    ("party members", &["party_members_count", "party_members"]),
+    ("events", &["events"]),
+    ("post-events", &["post_events"]),
+    ("journal", &["journal"]),
+];
+
+impl SaveFile {
+    /// Serialize this save and return the byte range of every section.
+    pub fn write_with_layout(&self) -> std::io::Result<(Vec<u8>, Vec<SaveSection>)> {
+        let mut bytes = Vec::new();
+        let sections = SaveWriter::new(self, &mut bytes).write_with_layout()?;
+        Ok((bytes, sections))
+    }
+
+    /// Compare this save (`a`) with `other` (`b`).
+    pub fn diff(&self, other: &SaveFile) -> std::io::Result<SaveDiff> {
+        let (bytes_a, layout_a) = self.write_with_layout()?;
+        let (bytes_b, layout_b) = other.write_with_layout()?;
+        let model_a = serde_json::to_value(self).map_err(Error::other)?;
+        let model_b = serde_json::to_value(other).map_err(Error::other)?;
+
+        let mut sections = Vec::new();
+        for ((section_a, section_b), (name, fields)) in
+            layout_a.iter().zip(&layout_b).zip(SECTION_FIELDS)
+        {
+            debug_assert_eq!(section_a.name, *name);
+            let mut changes = Vec::new();
+            for field in fields
+                .iter()
+                .filter(|field| !DERIVED_FIELDS.contains(field))
+            {
+                diff_values(
+                    field.to_string(),
+                    model_a.get(*field),
+                    model_b.get(*field),
+                    &mut changes,
+                );
+            }
+            let byte_ranges = diff_bytes(
+                &bytes_a[section_a.start as usize..section_a.end as usize],
+                &bytes_b[section_b.start as usize..section_b.end as usize],
+            );
+            if changes.is_empty() && byte_ranges.is_empty() {
+                continue;
+            }
+            sections.push(SectionDiff {
+                section: section_a.name,
+                range_a: (section_a.start, section_a.end),
+                range_b: (section_b.start, section_b.end),
+                changes,
+                byte_ranges,
+            });
+        }
+        Ok(SaveDiff { sections })
+    }
+}
+
+impl SaveDiff {
+    /// Whether the two saves are identical.
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Total number of changed model fields across all sections.
+    pub fn change_count(&self) -> usize {
+        self.sections
+            .iter()
+            .map(|section| section.changes.len())
+            .sum()
+    }
+}
+
+/// Recursively collect changed leaves below `path`.
+fn diff_values(path: String, a: Option<&Value>, b: Option<&Value>, out: &mut Vec<FieldChange>) {
+    match (a, b) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            for (key, value) in a {
+                diff_values(format!("{path}.{key}"), Some(value), b.get(key), out);
+            }
+            for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                diff_values(format!("{path}.{key}"), None, Some(value), out);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for index in 0..a.len().max(b.len()) {
+                diff_values(format!("{path}[{index}]"), a.get(index), b.get(index), out);
+            }
+        }
+        (a, b) if a != b => out.push(FieldChange {
+            path,
+            before: a.cloned(),
+            after: b.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+/// Differing byte runs between two serialized sections.
+fn diff_bytes(a: &[u8], b: &[u8]) -> Vec<ByteRangeDiff> {
+    if a.len() == b.len() {
+        let mut ranges = Vec::new();
+        let mut index = 0;
+        while index < a.len() {
+            if a[index] == b[index] {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while index < a.len() && a[index] != b[index] {
+                index += 1;
+            }
+            let len = (index - start) as u64;
+            ranges.push(ByteRangeDiff {
+                offset: start as u64,
+                len_a: len,
+                len_b: len,
+            });
+        }
+        return ranges;
+    }
+
+    let prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    vec![ByteRangeDiff {
+        offset: prefix as u64,
+        len_a: (a.len() - prefix - suffix) as u64,
+        len_b: (b.len() - prefix - suffix) as u64,
+    }]
+}
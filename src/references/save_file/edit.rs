@@ -0,0 +1,211 @@
+// Write-back support for edited save files.
+//
+// Editing happens on the parsed [`SaveFile`] model; this module turns the
+// model back into bytes that are known to reload identically, writes them
+// without clobbering the player's original save, and copies item-database
+// definitions into existing inventory records.
+
+use super::{
+    InventoryEditItem, InventoryEventItem, InventoryHealItem, InventoryMiscItem,
+    InventoryWeaponItem, SaveFile,
+};
+use crate::references::edit_item_db::EditItem;
+use crate::references::event_item_db::EventItem;
+use crate::references::heal_item_db::HealItem;
+use crate::references::misc_item_db::MiscItem;
+use crate::references::weapons_db::WeaponItem;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Extension appended to the original save when the first backup is taken.
+pub const BACKUP_EXTENSION: &str = "bak";
+
+/// Path of the automatic backup kept next to `path` (`3.sav` → `3.sav.bak`).
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(BACKUP_EXTENSION);
+    path.with_file_name(name)
+}
+
+impl SaveFile {
+    /// Serialize this save and verify that the bytes load back unchanged.
+    ///
+    /// The output is parsed again and compared twice: the re-parsed model
+    /// must equal this one (catching values the binary format cannot hold,
+    /// such as over-long or unencodable names) and must re-serialize to the
+    /// same bytes. The error names the first top-level section that drifted.
+    pub fn to_verified_bytes(&self) -> std::io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes)?;
+
+        let reparsed = SaveFile::parse(&bytes).map_err(|error| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("round-trip check failed: written save does not parse: {error}"),
+            )
+        })?;
+        if let Some(section) = first_model_difference(self, &reparsed)? {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("round-trip check failed: {section} does not survive serialization"),
+            ));
+        }
+
+        let mut rewritten = Vec::with_capacity(bytes.len());
+        reparsed.write_to(&mut rewritten)?;
+        if rewritten != bytes {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "round-trip check failed: re-serialized bytes differ",
+            ));
+        }
+        Ok(bytes)
+    }
+
+    /// Write this save to `path` after a round-trip check.
+    ///
+    /// The first time an existing file is overwritten, its original bytes are
+    /// copied to [`backup_path`]; later saves leave that backup untouched so it
+    /// always holds the save as it was before editing. The new contents go to
+    /// a temporary sibling first and are renamed into place, so a failed write
+    /// never leaves a truncated save behind.
+    ///
+    /// Returns the backup path when one was created by this call.
+    pub fn save_with_backup(&self, path: &Path) -> std::io::Result<Option<PathBuf>> {
+        let bytes = self.to_verified_bytes()?;
+
+        let backup = backup_path(path);
+        let created_backup = if path.exists() && !backup.exists() {
+            std::fs::copy(path, &backup)?;
+            Some(backup)
+        } else {
+            None
+        };
+
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp = path.with_file_name(tmp_name);
+        std::fs::write(&tmp, &bytes)?;
+        if let Err(error) = std::fs::rename(&tmp, path) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(error);
+        }
+        Ok(created_backup)
+    }
+}
+
+/// Model fields the writer recomputes instead of serializing verbatim.
+pub(super) const DERIVED_FIELDS: &[&str] = &["game_tmp_blob_size"];
+
+fn first_model_difference(
+    expected: &SaveFile,
+    actual: &SaveFile,
+) -> std::io::Result<Option<String>> {
+    let expected = serde_json::to_value(expected).map_err(Error::other)?;
+    let actual = serde_json::to_value(actual).map_err(Error::other)?;
+    let (Some(expected), Some(actual)) = (expected.as_object(), actual.as_object()) else {
+        return Ok((expected != actual).then(|| "save".to_string()));
+    };
+    Ok(expected
+        .iter()
+        .filter(|(key, _)| !DERIVED_FIELDS.contains(&key.as_str()))
+        .find(|(key, value)| actual.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone()))
+}
+
+// Inventory records embed a copy of the item definition followed by runtime
+// bookkeeping. Replacing an item copies the definition part and keeps the
+// runtime part (category, record index, instance id), which equipment and
+// placement cells refer to. The definition id slot holds the zero-based
+// database record index, which the game writes there when it loads the DB,
+// rather than the on-disk value of the same slot.
+
+impl InventoryWeaponItem {
+    /// Replace this item's definition with `item` from `weaponItem.db`.
+    pub fn apply_definition(&mut self, item: &WeaponItem) {
+        self.name = item.name.clone();
+        self.description = item.description.clone();
+        self.base_price = item.base_price as u32;
+        self.weapon_item_id = item.id as u32;
+        self.health_points = item.health_points;
+        self.mana_points = item.mana_points;
+        self.strength = item.strength;
+        self.agility = item.agility;
+        self.wisdom = item.wisdom;
+        self.constitution = item.constitution;
+        self.to_dodge = item.to_dodge;
+        self.to_hit = item.to_hit;
+        self.attack = item.attack;
+        self.defense = item.defense;
+        self.magical_strength = item.magical_strength;
+        self.durability = item.durability;
+        self.req_strength = item.req_strength;
+        self.req_agility = item.req_agility;
+        self.req_wisdom = item.req_wisdom;
+    }
+}
+
+impl InventoryHealItem {
+    /// Replace this item's definition with `item` from `HealItem.db`.
+    pub fn apply_definition(&mut self, item: &HealItem) {
+        self.name = item.name.clone();
+        self.description = item.description.clone();
+        self.base_price = item.base_price as u32;
+        self.heal_item_id = item.id as u32;
+        self.health_points = item.health_points;
+        self.mana_points = item.mana_points;
+        self.restore_full_health = item.restores_full_health.into();
+        self.restore_full_mana = item.restores_full_mana.into();
+        self.poison_heal = item.cures_poison.into();
+        self.petrif_heal = item.cures_petrification.into();
+        self.polimorph_heal = item.cures_polymorph.into();
+        self.reserved_definition_byte = item.reserved_trailer.first().copied().unwrap_or(0);
+    }
+}
+
+impl InventoryEditItem {
+    /// Replace this item's definition with `item` from `EditItem.db`.
+    pub fn apply_definition(&mut self, item: &EditItem) {
+        self.name = item.name.clone();
+        self.description = item.description.clone();
+        self.base_price = item.base_price as u32;
+        self.edit_item_id = item.index as u32;
+        self.health_points = item.health_points;
+        self.mana_points = item.mana_points;
+        self.strength = item.strength;
+        self.agility = item.agility;
+        self.wisdom = item.wisdom;
+        self.constitution = item.constitution;
+        self.to_dodge = item.to_dodge;
+        self.to_hit = item.to_hit;
+        self.offense = item.offense;
+        self.defense = item.defense;
+        self.magical_power = item.magical_power;
+        self.modification_resistance = item.modification_resistance;
+        self.reserved_byte = item.reserved_byte;
+        self.modifies_item = item.modifies_item.into();
+        self.additional_effect = item.additional_effect.into();
+    }
+}
+
+impl InventoryMiscItem {
+    /// Replace this item's definition with `item` from `MiscItem.db`.
+    pub fn apply_definition(&mut self, item: &MiscItem) {
+        self.name = item.name.clone();
+        self.description = item.description.clone();
+        self.base_price = item.base_price as u32;
+        self.reserved_definition_bytes = item.reserved_bytes.clone();
+        self.misc_item_id = item.id as u32;
+    }
+}
+
+impl InventoryEventItem {
+    /// Replace this item's definition with `item` from `EventItem.db`.
+    pub fn apply_definition(&mut self, item: &EventItem) {
+        self.name = item.name.clone();
+        self.description = item.description.clone();
+        self.base_price = item.base_price as u32;
+        self.event_item_id = item.id as u32;
+    }
+}
@@ -1,6 +1,8 @@
 // Save file extraction and serialization for Dispel RPG.
 
 pub mod character;
+mod diff;
+mod edit;
 pub mod events;
 pub mod game_tmp;
 pub mod inventory;
@@ -15,6 +17,8 @@ mod writer;
 use super::extractor::Extractor;
 use character::CharacterData;
 pub use character::{CharacterIdentity, CharacterState, LearnedSpells};
+pub use diff::{ByteRangeDiff, FieldChange, SaveDiff, SectionDiff};
+pub use edit::{BACKUP_EXTENSION, backup_path};
 pub use events::{
     DismissedCompanionProgression, EventRecord, PostEventsData, WalkCompletionRecord,
     WalkMilestoneRecord,
@@ -36,6 +40,7 @@ pub use map_viewport::{
 pub use party_members::PartyMember;
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Seek, Write};
+pub use writer::SaveSection;
 
 /// Complete save file structure.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
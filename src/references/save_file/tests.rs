@@ -5,7 +5,9 @@ use super::character::{
 use super::events::{
     DismissedCompanionProgression, EVENT_RECORD_SIZE, PostEventsData, read_events,
 };
-use super::game_tmp::{EXTRA_OBJECT_TRAILER_RECORD_SIZE, read_maps, write_maps};
+use super::game_tmp::{
+    EXTRA_OBJECT_TRAILER_FIXED_SIZE, EXTRA_OBJECT_TRAILER_RECORD_SIZE, read_maps, write_maps,
+};
 use super::inventory::{INVENTORY_SLOTS_SIZE, InventoryData, InventorySlots};
 use super::journal::{JOURNAL_ENTRY_SIZE, JOURNAL_HEADER_SIZE, JournalData};
 use super::map_viewport::{MAP_VIEWPORT_STATE_SIZE, MapViewportState, PostMapsData};
@@ -735,6 +737,18 @@ fn valid_save() -> SaveFile {
     }
 }
 
+/// A [`valid_save`] whose fixed-size slot tables are fully populated, so it
+/// also parses back from its own serialization.
+fn round_trip_save() -> SaveFile {
+    let mut save = valid_save();
+    save.inventory_slots = InventorySlots {
+        equipped_equipment: vec![Default::default(); 12],
+        belt_potions: vec![Default::default(); 6],
+        inventory_placement: vec![Default::default(); 189],
+    };
+    save
+}
+
 fn invalid_save(change: impl FnOnce(&mut SaveFile)) -> SaveFile {
     let mut save = valid_save();
     change(&mut save);
@@ -773,3 +787,172 @@ impl Write for FailAfter {
         Ok(())
     }
 }
+
+#[test]
+fn test_verified_bytes_match_plain_serialization() {
+    let save = round_trip_save();
+    let mut plain = Vec::new();
+    save.write_to(&mut plain).unwrap();
+
+    assert_eq!(save.to_verified_bytes().unwrap(), plain);
+}
+
+#[test]
+fn test_verified_bytes_reject_values_the_format_cannot_hold() {
+    let mut save = round_trip_save();
+    save.sprite_paths[0] = "x".repeat(SPRITE_PATH_SIZE + 5);
+
+    let error = save.to_verified_bytes().unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("sprite_paths"), "{error}");
+}
+
+#[test]
+fn test_save_with_backup_keeps_the_first_original() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("3.sav");
+    std::fs::write(&path, b"original").unwrap();
+    let save = round_trip_save();
+
+    let backup = save.save_with_backup(&path).unwrap();
+    assert_eq!(backup.as_deref(), Some(super::backup_path(&path).as_path()));
+    assert_eq!(
+        std::fs::read(dir.path().join("3.sav.bak")).unwrap(),
+        b"original"
+    );
+    assert_eq!(
+        std::fs::read(&path).unwrap(),
+        save.to_verified_bytes().unwrap()
+    );
+
+    assert_eq!(save.save_with_backup(&path).unwrap(), None);
+    assert_eq!(
+        std::fs::read(dir.path().join("3.sav.bak")).unwrap(),
+        b"original"
+    );
+}
+
+#[test]
+fn test_apply_definition_keeps_inventory_runtime_fields() {
+    let mut owned = super::InventoryWeaponItem {
+        name: "Old".to_string(),
+        item_category: 0,
+        inventory_instance_id: 5152,
+        ..Default::default()
+    };
+    let definition = crate::references::weapons_db::WeaponItem {
+        id: 7,
+        name: "Long Sword".to_string(),
+        weapon_item_id: 0,
+        attack: 42,
+        req_strength: 30,
+        ..Default::default()
+    };
+
+    owned.apply_definition(&definition);
+
+    assert_eq!(owned.name, "Long Sword");
+    assert_eq!(owned.weapon_item_id, 7);
+    assert_eq!(owned.attack, 42);
+    assert_eq!(owned.req_strength, 30);
+    assert_eq!(owned.inventory_instance_id, 5152);
+}
+
+fn save_with_monster_map() -> SaveFile {
+    let mut save = valid_save();
+    save.post_maps.number_of_visited_maps = 1;
+    save.post_maps.map_ids = vec![3];
+    save.maps = vec![MapSectionData {
+        map_id: 3,
+        monsters: vec![MonsterRecord::default(); 2],
+        extra_objects_trailer: super::MapExtraObjectsTrailer {
+            tail_size: EXTRA_OBJECT_TRAILER_FIXED_SIZE as u32,
+            ..Default::default()
+        },
+        ..Default::default()
+    }];
+    save
+}
+
+#[test]
+fn test_diff_of_identical_saves_is_empty() {
+    let save = save_with_monster_map();
+
+    let diff = save.diff(&save.clone()).unwrap();
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.change_count(), 0);
+}
+
+#[test]
+fn test_diff_reports_field_paths_per_section() {
+    let before = save_with_monster_map();
+    let mut after = before.clone();
+    after.maps[0].monsters[1].hp_current = 12;
+    after.character.script_event_active = 1;
+    after.events[7].execution_count = 2;
+
+    let diff = before.diff(&after).unwrap();
+
+    let sections: Vec<_> = diff.sections.iter().map(|s| s.section).collect();
+    assert_eq!(sections, ["header and maps", "character stats", "events"]);
+    assert_eq!(
+        diff.sections[0].changes,
+        [super::FieldChange {
+            path: "maps[0].monsters[1].hp_current".to_string(),
+            before: Some(serde_json::json!(0)),
+            after: Some(serde_json::json!(12)),
+        }]
+    );
+    assert_eq!(
+        diff.sections[1].changes[0].path,
+        "character.script_event_active"
+    );
+    assert_eq!(
+        diff.sections[2].changes[0].path,
+        "events[7].execution_count"
+    );
+}
+
+#[test]
+fn test_diff_maps_byte_changes_to_section_offsets() {
+    let before = save_with_monster_map();
+    let mut after = before.clone();
+    after.post_events.shake_frames_remaining = 0x0102;
+
+    let diff = before.diff(&after).unwrap();
+
+    let section = &diff.sections[0];
+    assert_eq!(section.section, "post-events");
+    assert_eq!(
+        section.byte_ranges,
+        [super::ByteRangeDiff {
+            offset: 4,
+            len_a: 2,
+            len_b: 2,
+        }]
+    );
+    let (_, layout) = before.write_with_layout().unwrap();
+    let post_events = layout.iter().find(|s| s.name == "post-events").unwrap();
+    assert_eq!(section.range_a, (post_events.start, post_events.end));
+}
+
+#[test]
+fn test_diff_reports_added_records_and_resized_sections() {
+    let before = save_with_monster_map();
+    let mut after = before.clone();
+    after.maps[0].monsters.push(MonsterRecord::default());
+
+    let diff = before.diff(&after).unwrap();
+
+    let maps = &diff.sections[0];
+    assert_eq!(maps.changes.len(), 1);
+    assert_eq!(maps.changes[0].path, "maps[0].monsters[2]");
+    assert!(maps.changes[0].before.is_none());
+    assert_eq!(maps.byte_ranges.len(), 1);
+    let range = maps.byte_ranges[0];
+    let growth = (maps.range_b.1 - maps.range_b.0) - (maps.range_a.1 - maps.range_a.0);
+    assert!(growth > 0);
+    assert_eq!(range.len_b - range.len_a, growth);
+}
@@ -10,11 +10,24 @@ use super::journal::JOURNAL_ENTRIES_PER_SECTION;
 use super::map_viewport::MAP_VIEWPORT_CELL_COUNT;
 use super::party_members::write_party_members;
 use byteorder::{LittleEndian, WriteBytesExt};
+use serde::Serialize;
 use std::io::Write;
 
 pub(super) struct SaveWriter<'a, W> {
     save: &'a SaveFile,
     output: PositionWriter<'a, W>,
+    sections: Vec<SaveSection>,
+}
+
+/// Byte range one top-level section occupies in a serialized save.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SaveSection {
+    /// Section name, as used in read and write error messages.
+    pub name: &'static str,
+    /// Offset of the first byte of the section.
+    pub start: u64,
+    /// Offset one past the last byte of the section.
+    pub end: u64,
 }
 
 impl<'a, W: Write> SaveWriter<'a, W> {
@@ -22,10 +35,16 @@ impl<'a, W: Write> SaveWriter<'a, W> {
         Self {
             save,
             output: PositionWriter::new(output),
+            sections: Vec::new(),
         }
     }
 
-    pub(super) fn write(mut self) -> std::io::Result<()> {
+    pub(super) fn write(self) -> std::io::Result<()> {
+        self.write_with_layout().map(|_| ())
+    }
+
+    /// Write the save and return the byte range of every section.
+    pub(super) fn write_with_layout(mut self) -> std::io::Result<Vec<SaveSection>> {
         validate(self.save)?;
 
         let mut maps = Vec::new();
@@ -66,7 +85,8 @@ impl<'a, W: Write> SaveWriter<'a, W> {
         self.section("post-events", |output| {
             self.save.post_events.write_to(output)
         })?;
-        self.section("journal", |output| self.save.journal.write_to(output))
+        self.section("journal", |output| self.save.journal.write_to(output))?;
+        Ok(self.sections)
     }
 
     fn section(
@@ -74,8 +94,15 @@ impl<'a, W: Write> SaveWriter<'a, W> {
         name: &'static str,
         write: impl FnOnce(&mut PositionWriter<'a, W>) -> std::io::Result<()>,
     ) -> std::io::Result<()> {
+        let start = self.output.position();
         write(&mut self.output)
-            .map_err(|error| contextual_error(name, self.output.position(), error))
+            .map_err(|error| contextual_error(name, self.output.position(), error))?;
+        self.sections.push(SaveSection {
+            name,
+            start,
+            end: self.output.position(),
+        });
+        Ok(())
     }
 }
 
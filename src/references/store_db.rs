@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::references::enums::ProductType;
 use crate::references::extractor::{Extractor, read_mapper, read_null_terminated_windows_1250};
+use crate::references::store_inventory::{INN_PADDING_BYTES, MAX_STORE_PRODUCTS};
+use crate::references::validation::{FieldConstraint, FieldConstraints, FieldRule};
 use dispel_macros::Localizable;
 
 /// Store.db - Shop & Inn Database
@@ -71,6 +73,9 @@ use dispel_macros::Localizable;
 /// - Terminated by `type == 0`
 /// - Max 15 products per shop (the game iterates `iVar4 < 0xf`)
 ///
+/// [`crate::references::store_inventory`] resolves products to item records
+/// and edits the table within these limits.
+///
 /// # Field Categories
 ///
 /// - **Identification**: `index`, `store_name` (32 bytes, WINDOWS-1250)
@@ -120,6 +125,20 @@ pub struct Store {
 
 pub type StoreProduct = (i16, ProductType, i16); // order, product_type, product_id
 
+/// Text limits come from `#[translatable]`; the product table holds at most
+/// 15 entries (the game iterates `0..0xf`).
+impl FieldConstraints for Store {
+    fn field_constraints() -> Vec<FieldConstraint> {
+        vec![FieldConstraint {
+            field: "products",
+            rule: FieldRule::Items {
+                min: 0,
+                max: MAX_STORE_PRODUCTS,
+            },
+        }]
+    }
+}
+
 impl Extractor for Store {
     fn parse<R: Read + Seek>(reader: &mut R, len: u64) -> std::io::Result<Vec<Self>> {
         const COUNTER_SIZE: u8 = 4;
@@ -138,16 +157,16 @@ impl Extractor for Store {
             let mut products: Vec<StoreProduct> = vec![];
 
             if inn_night_cost > 0 {
-                reader.seek(SeekFrom::Current(144))?;
+                reader.seek(SeekFrom::Current(INN_PADDING_BYTES as i64))?;
             } else {
                 price_modifier = reader.read_i16::<LittleEndian>()?; // price modifier
 
                 // 15 product slots (4 bytes each) + 82 bytes padding
-                let mut buffer = [0u8; 142];
+                let mut buffer = [0u8; INN_PADDING_BYTES - 2];
                 reader.read_exact(&mut buffer)?;
                 let mut cursor = Cursor::new(&buffer);
 
-                for i in 0..15 {
+                for i in 0..MAX_STORE_PRODUCTS {
                     let item_type_raw = cursor.read_i16::<LittleEndian>().unwrap();
                     if item_type_raw == 0 {
                         break;
@@ -205,11 +224,17 @@ impl Extractor for Store {
             writer.write_all(&name_buf)?;
 
             writer.write_i32::<LittleEndian>(record.inn_night_cost)?;
-            if record.inn_night_cost > 0 {
-                writer.write_all(&[0u8; 144])?;
+            if record.is_inn() {
+                writer.write_all(&[0u8; INN_PADDING_BYTES])?;
             } else {
+                if record.products.len() > MAX_STORE_PRODUCTS {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("{record}: {}", record.validate_inventory().unwrap_err()),
+                    ));
+                }
                 writer.write_i16::<LittleEndian>(record.price_modifier)?;
-                let mut prod_buf = [0u8; 142];
+                let mut prod_buf = [0u8; INN_PADDING_BYTES - 2];
                 let mut cursor = Cursor::new(&mut prod_buf[..]);
                 for prod in &record.products {
                     cursor.write_i16::<LittleEndian>(i32::from(prod.1) as i16)?; // type
@@ -345,4 +370,24 @@ mod tests {
         Store::to_writer(&records, &mut out).unwrap();
         assert_eq!(out, data);
     }
+
+    #[test]
+    fn shop_products_round_trip_and_overflow_is_rejected() {
+        let mut shop = Store {
+            store_name: "Smithy".into(),
+            price_modifier: 10,
+            products: vec![(0, ProductType::Weapon, 3), (1, ProductType::Healing, 7)],
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        Store::to_writer(std::slice::from_ref(&shop), &mut out).unwrap();
+        assert_eq!(out.len(), 952);
+        let back = Store::parse(&mut Cursor::new(&out[..]), out.len() as u64).unwrap();
+        assert_eq!(back[0].products, shop.products);
+        assert_eq!(back[0].price_modifier, 10);
+
+        shop.products = (0..16).map(|i| (i, ProductType::MiscItem, i)).collect();
+        let err = Store::to_writer(&[shop], &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
 }
@@ -0,0 +1,472 @@
+//! Typed view of a [`Store`]'s product table.
+//!
+//! `Store.db` keeps products as raw `(type, item_id)` pairs that point into
+//! `weaponItem.db`, `HealItem.db`, `EditItem.db` and `MiscItem.db` by record
+//! position. [`ProductSlot`] names one such reference, [`StoreCatalog`]
+//! resolves it to the item's name and price, and the `Store` methods below
+//! keep the table within what the game can read: at most
+//! [`MAX_STORE_PRODUCTS`] entries, and none at all for inns, whose product
+//! area is [`INN_PADDING_BYTES`] of zeroes.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::references::edit_item_db::EditItem;
+use crate::references::enums::ProductType;
+use crate::references::heal_item_db::HealItem;
+use crate::references::misc_item_db::MiscItem;
+use crate::references::store_db::{Store, StoreProduct};
+use crate::references::weapons_db::WeaponItem;
+
+/// Product slots per shop; the game iterates `0..0xf`.
+pub const MAX_STORE_PRODUCTS: usize = 15;
+
+/// Size of the price modifier plus product area, zero-filled for inns.
+pub const INN_PADDING_BYTES: usize = 144;
+
+/// Every product type, in file order.
+pub const PRODUCT_TYPES: [ProductType; 4] = [
+    ProductType::Weapon,
+    ProductType::Healing,
+    ProductType::EditItem,
+    ProductType::MiscItem,
+];
+
+/// `buy_price = base + modifier% of base`, as the game charges it.
+pub fn store_buy_price(base: i32, modifier: i16) -> i32 {
+    base + i32::from(modifier) * base / 100
+}
+
+/// `sell_price = base/2 + modifier% of base/2`.
+pub fn store_sell_price(base: i32, modifier: i16) -> i32 {
+    let half = base / 2;
+    half + i32::from(modifier) * half / 100
+}
+
+/// One product reference: which item database and which record in it.
+///
+/// Written and parsed as `type:id`, e.g. `weapon:12` or `healing:3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductSlot {
+    pub product_type: ProductType,
+    pub item_id: i16,
+}
+
+impl ProductSlot {
+    pub fn new(product_type: ProductType, item_id: i16) -> Self {
+        Self {
+            product_type,
+            item_id,
+        }
+    }
+
+    /// Parse a comma-separated list of `type:id` entries. Blank input is an
+    /// empty list.
+    pub fn parse_list(text: &str) -> Result<Vec<Self>, StoreInventoryError> {
+        text.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Inverse of [`ProductSlot::parse_list`].
+    pub fn format_list(slots: &[Self]) -> String {
+        slots
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl From<&StoreProduct> for ProductSlot {
+    fn from(&(_, product_type, item_id): &StoreProduct) -> Self {
+        Self::new(product_type, item_id)
+    }
+}
+
+/// Lower-case keyword used by the `type:id` text form.
+pub fn product_type_keyword(product_type: ProductType) -> &'static str {
+    match product_type {
+        ProductType::Weapon => "weapon",
+        ProductType::Healing => "healing",
+        ProductType::EditItem => "edit",
+        ProductType::MiscItem => "misc",
+    }
+}
+
+fn parse_product_type(text: &str) -> Option<ProductType> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "weapon" | "1" => Some(ProductType::Weapon),
+        "healing" | "heal" | "2" => Some(ProductType::Healing),
+        "edit" | "edititem" | "3" => Some(ProductType::EditItem),
+        "misc" | "miscitem" | "4" => Some(ProductType::MiscItem),
+        _ => None,
+    }
+}
+
+impl fmt::Display for ProductSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}",
+            product_type_keyword(self.product_type),
+            self.item_id
+        )
+    }
+}
+
+impl FromStr for ProductSlot {
+    type Err = StoreInventoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || StoreInventoryError::InvalidSlot(s.trim().to_string());
+        let (kind, id) = s.split_once(':').ok_or_else(invalid)?;
+        let product_type = parse_product_type(kind).ok_or_else(invalid)?;
+        let item_id = id.trim().parse::<i16>().map_err(|_| invalid())?;
+        if item_id < 0 {
+            return Err(invalid());
+        }
+        Ok(Self::new(product_type, item_id))
+    }
+}
+
+/// Why a product table was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreInventoryError {
+    /// More than [`MAX_STORE_PRODUCTS`] entries.
+    TooManyProducts(usize),
+    /// Inns (`inn_night_cost > 0`) cannot list products.
+    InnHasProducts,
+    /// Text that is not a `type:id` entry.
+    InvalidSlot(String),
+}
+
+impl fmt::Display for StoreInventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyProducts(n) => write!(
+                f,
+                "a store holds at most {MAX_STORE_PRODUCTS} products, got {n}"
+            ),
+            Self::InnHasProducts => write!(f, "inns (inn_night_cost > 0) cannot list products"),
+            Self::InvalidSlot(s) => write!(
+                f,
+                "invalid product '{s}', expected type:id (weapon, healing, edit, misc)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StoreInventoryError {}
+
+impl Store {
+    /// Whether the record is an inn rather than a shop.
+    pub fn is_inn(&self) -> bool {
+        self.inn_night_cost > 0
+    }
+
+    /// The product table without the positional `order` column.
+    pub fn product_slots(&self) -> Vec<ProductSlot> {
+        self.products.iter().map(ProductSlot::from).collect()
+    }
+
+    /// Replace the product table, numbering the slots from 0.
+    pub fn set_products(
+        &mut self,
+        slots: impl IntoIterator<Item = ProductSlot>,
+    ) -> Result<(), StoreInventoryError> {
+        let slots: Vec<ProductSlot> = slots.into_iter().collect();
+        check_inventory(self.is_inn(), slots.len())?;
+        self.products = slots
+            .into_iter()
+            .enumerate()
+            .map(|(i, slot)| (i as i16, slot.product_type, slot.item_id))
+            .collect();
+        Ok(())
+    }
+
+    /// Append one product to the end of the table.
+    pub fn push_product(&mut self, slot: ProductSlot) -> Result<(), StoreInventoryError> {
+        check_inventory(self.is_inn(), self.products.len() + 1)?;
+        self.products
+            .push((self.products.len() as i16, slot.product_type, slot.item_id));
+        Ok(())
+    }
+
+    /// Check the product table against the slot limit and the inn layout.
+    pub fn validate_inventory(&self) -> Result<(), StoreInventoryError> {
+        check_inventory(self.is_inn(), self.products.len())
+    }
+}
+
+fn check_inventory(is_inn: bool, count: usize) -> Result<(), StoreInventoryError> {
+    if is_inn && count > 0 {
+        return Err(StoreInventoryError::InnHasProducts);
+    }
+    if count > MAX_STORE_PRODUCTS {
+        return Err(StoreInventoryError::TooManyProducts(count));
+    }
+    Ok(())
+}
+
+/// A catalog item a product can point at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatalogItem<'a> {
+    pub slot: ProductSlot,
+    pub name: &'a str,
+    pub base_price: i32,
+}
+
+impl fmt::Display for CatalogItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (#{})", self.name, self.slot.item_id)
+    }
+}
+
+/// A store product resolved against the item databases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedProduct {
+    /// Position in the store's product table.
+    pub order: usize,
+    pub slot: ProductSlot,
+    /// `None` when the id points past the end of its database.
+    pub name: Option<String>,
+    pub base_price: i32,
+    pub buy_price: i32,
+    pub sell_price: i32,
+}
+
+impl ResolvedProduct {
+    /// Item name, or `Type #id` for dangling references.
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("{:?} #{}", self.slot.product_type, self.slot.item_id),
+        }
+    }
+}
+
+/// The four item databases stores sell from.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StoreCatalog<'a> {
+    pub weapons: &'a [WeaponItem],
+    pub heal_items: &'a [HealItem],
+    pub edit_items: &'a [EditItem],
+    pub misc_items: &'a [MiscItem],
+}
+
+impl<'a> StoreCatalog<'a> {
+    pub fn new(
+        weapons: &'a [WeaponItem],
+        heal_items: &'a [HealItem],
+        edit_items: &'a [EditItem],
+        misc_items: &'a [MiscItem],
+    ) -> Self {
+        Self {
+            weapons,
+            heal_items,
+            edit_items,
+            misc_items,
+        }
+    }
+
+    /// Look up the record a product points at.
+    pub fn item(&self, slot: ProductSlot) -> Option<CatalogItem<'a>> {
+        let id = usize::try_from(slot.item_id).ok()?;
+        let (name, base_price) = match slot.product_type {
+            ProductType::Weapon => self.weapons.get(id).map(|i| (&i.name, i.base_price))?,
+            ProductType::Healing => self.heal_items.get(id).map(|i| (&i.name, i.base_price))?,
+            ProductType::EditItem => self.edit_items.get(id).map(|i| (&i.name, i.base_price))?,
+            ProductType::MiscItem => self.misc_items.get(id).map(|i| (&i.name, i.base_price))?,
+        };
+        Some(CatalogItem {
+            slot,
+            name: name.as_str(),
+            base_price,
+        })
+    }
+
+    /// Every record of one database, in id order, for item pickers.
+    pub fn items(&self, product_type: ProductType) -> Vec<CatalogItem<'a>> {
+        let entries: Vec<(&'a str, i32)> = match product_type {
+            ProductType::Weapon => self
+                .weapons
+                .iter()
+                .map(|i| (i.name.as_str(), i.base_price))
+                .collect(),
+            ProductType::Healing => self
+                .heal_items
+                .iter()
+                .map(|i| (i.name.as_str(), i.base_price))
+                .collect(),
+            ProductType::EditItem => self
+                .edit_items
+                .iter()
+                .map(|i| (i.name.as_str(), i.base_price))
+                .collect(),
+            ProductType::MiscItem => self
+                .misc_items
+                .iter()
+                .map(|i| (i.name.as_str(), i.base_price))
+                .collect(),
+        };
+        entries
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, (name, base_price))| {
+                Some(CatalogItem {
+                    slot: ProductSlot::new(product_type, i16::try_from(id).ok()?),
+                    name,
+                    base_price,
+                })
+            })
+            .collect()
+    }
+
+    /// First item whose name matches case-insensitively, searching the
+    /// databases in file order unless `product_type` narrows it down.
+    pub fn find_by_name(
+        &self,
+        name: &str,
+        product_type: Option<ProductType>,
+    ) -> Option<CatalogItem<'a>> {
+        let name = name.trim();
+        PRODUCT_TYPES
+            .into_iter()
+            .filter(|t| product_type.is_none_or(|only| only == *t))
+            .flat_map(|t| self.items(t))
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Name and prices of every product in `store`, after its
+    /// `price_modifier`. Inns resolve to an empty list.
+    pub fn resolve(&self, store: &Store) -> Vec<ResolvedProduct> {
+        if store.is_inn() {
+            return Vec::new();
+        }
+        store
+            .product_slots()
+            .into_iter()
+            .enumerate()
+            .map(|(order, slot)| {
+                let item = self.item(slot);
+                let base_price = item.map_or(0, |i| i.base_price);
+                ResolvedProduct {
+                    order,
+                    slot,
+                    name: item.map(|i| i.name.to_string()),
+                    base_price,
+                    buy_price: store_buy_price(base_price, store.price_modifier),
+                    sell_price: store_sell_price(base_price, store.price_modifier),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shop(price_modifier: i16) -> Store {
+        Store {
+            store_name: "Smithy".into(),
+            price_modifier,
+            ..Default::default()
+        }
+    }
+
+    fn weapon(name: &str, base_price: i32) -> WeaponItem {
+        WeaponItem {
+            name: name.into(),
+            base_price,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn slot_text_round_trip() {
+        let slots = ProductSlot::parse_list("weapon:3, heal:0,misc:12,4:1").unwrap();
+        assert_eq!(
+            slots,
+            vec![
+                ProductSlot::new(ProductType::Weapon, 3),
+                ProductSlot::new(ProductType::Healing, 0),
+                ProductSlot::new(ProductType::MiscItem, 12),
+                ProductSlot::new(ProductType::MiscItem, 1),
+            ]
+        );
+        assert_eq!(
+            ProductSlot::format_list(&slots),
+            "weapon:3, healing:0, misc:12, misc:1"
+        );
+        assert!(ProductSlot::parse_list(" ").unwrap().is_empty());
+        assert!(ProductSlot::parse_list("sword:1").is_err());
+        assert!(ProductSlot::parse_list("weapon:-1").is_err());
+    }
+
+    #[test]
+    fn set_products_enforces_limits() {
+        let mut store = shop(0);
+        let slot = ProductSlot::new(ProductType::Weapon, 0);
+        store.set_products(vec![slot; MAX_STORE_PRODUCTS]).unwrap();
+        assert_eq!(store.products[14].0, 14);
+        assert_eq!(
+            store.push_product(slot),
+            Err(StoreInventoryError::TooManyProducts(16))
+        );
+
+        let mut inn = Store {
+            inn_night_cost: 30,
+            ..Default::default()
+        };
+        assert_eq!(
+            inn.push_product(slot),
+            Err(StoreInventoryError::InnHasProducts)
+        );
+        inn.set_products(Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn resolve_applies_price_modifier() {
+        let weapons = vec![weapon("Dagger", 100), weapon("Short Sword", 200)];
+        let catalog = StoreCatalog {
+            weapons: &weapons,
+            ..Default::default()
+        };
+        let mut store = shop(50);
+        store
+            .set_products([
+                ProductSlot::new(ProductType::Weapon, 1),
+                ProductSlot::new(ProductType::Weapon, 9),
+            ])
+            .unwrap();
+
+        let resolved = catalog.resolve(&store);
+        assert_eq!(resolved[0].name.as_deref(), Some("Short Sword"));
+        assert_eq!(resolved[0].buy_price, 300);
+        assert_eq!(resolved[0].sell_price, 150);
+        assert_eq!(resolved[1].name, None);
+        assert_eq!(resolved[1].label(), "Weapon #9");
+
+        let found = catalog.find_by_name("short sword", None).unwrap();
+        assert_eq!(found.slot, ProductSlot::new(ProductType::Weapon, 1));
+        assert!(
+            catalog
+                .find_by_name("Dagger", Some(ProductType::Healing))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn buy_and_sell_follow_the_game_formula() {
+        assert_eq!(store_buy_price(100, 50), 150);
+        assert_eq!(store_sell_price(100, 50), 75);
+        assert_eq!(store_buy_price(100, -20), 80);
+        assert_eq!(store_sell_price(100, 0), 50);
+    }
+}
@@ -0,0 +1,820 @@
+//! Cross-file reference index ("find usages").
+//!
+//! Game records point at each other by numeric id: a shop sells heal item
+//! #7, a chest on a map holds it, a monster drops it, a save file carries it.
+//! [`ReferenceIndex`] walks a game directory once, reads every file type that
+//! holds such pointers and answers "where is `(kind, id)` used?" without
+//! re-reading anything.
+//!
+//! Ids are the zero-based record positions of catalogs (item databases,
+//! `Monster.db`, `Magic.db`, `PrtIni.db`) and the explicit `id` column of
+//! text catalogs (`Event.ini`, `Quest.scr`, `Message.scr`, `AllMap.ini`,
+//! `Npc.ini`, `Extra.ini`).
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::references::dialogue_script::DialogueScript;
+use crate::references::draw_item::DrawItem;
+use crate::references::enums::{InventoryItem, ItemTypeId, ProductType};
+use crate::references::event_npc_ref::EventNpcRef;
+use crate::references::event_scr::EventScript;
+use crate::references::extra_ref::ExtraRef;
+use crate::references::extractor::Extractor;
+use crate::references::monster_db::Monster;
+use crate::references::monster_ref::MonsterRef;
+use crate::references::npc_ref::NPC;
+use crate::references::party_level_db::PartyLevelNpc;
+use crate::references::party_ref::PartyRef;
+use crate::references::save_file::SaveFile;
+use crate::references::store_db::Store;
+
+/// A catalog whose records can be referenced from other files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RecordKind {
+    WeaponItem,
+    HealItem,
+    EditItem,
+    EventItem,
+    MiscItem,
+    MagicSpell,
+    Monster,
+    PartyIni,
+    Event,
+    Quest,
+    Message,
+    Map,
+    NpcIni,
+    ExtraIni,
+}
+
+impl RecordKind {
+    pub const ALL: [RecordKind; 14] = [
+        RecordKind::WeaponItem,
+        RecordKind::HealItem,
+        RecordKind::EditItem,
+        RecordKind::EventItem,
+        RecordKind::MiscItem,
+        RecordKind::MagicSpell,
+        RecordKind::Monster,
+        RecordKind::PartyIni,
+        RecordKind::Event,
+        RecordKind::Quest,
+        RecordKind::Message,
+        RecordKind::Map,
+        RecordKind::NpcIni,
+        RecordKind::ExtraIni,
+    ];
+
+    /// The item databases, which inventory slots and loot point into.
+    pub const ITEMS: [RecordKind; 5] = [
+        RecordKind::WeaponItem,
+        RecordKind::HealItem,
+        RecordKind::EditItem,
+        RecordKind::EventItem,
+        RecordKind::MiscItem,
+    ];
+
+    /// File type key used by the CLI (`extract --type`, `refs --type`).
+    pub fn key(self) -> &'static str {
+        match self {
+            RecordKind::WeaponItem => "weapons",
+            RecordKind::HealItem => "heal_item",
+            RecordKind::EditItem => "edit_item",
+            RecordKind::EventItem => "event_item",
+            RecordKind::MiscItem => "misc_item",
+            RecordKind::MagicSpell => "magic",
+            RecordKind::Monster => "monsters",
+            RecordKind::PartyIni => "party_ini",
+            RecordKind::Event => "event_ini",
+            RecordKind::Quest => "quest",
+            RecordKind::Message => "message",
+            RecordKind::Map => "all_maps",
+            RecordKind::NpcIni => "npc_ini",
+            RecordKind::ExtraIni => "extra_ini",
+        }
+    }
+
+    /// File the referenced records live in.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            RecordKind::WeaponItem => "weaponItem.db",
+            RecordKind::HealItem => "HealItem.db",
+            RecordKind::EditItem => "EditItem.db",
+            RecordKind::EventItem => "EventItem.db",
+            RecordKind::MiscItem => "MiscItem.db",
+            RecordKind::MagicSpell => "Magic.db",
+            RecordKind::Monster => "Monster.db",
+            RecordKind::PartyIni => "PrtIni.db",
+            RecordKind::Event => "Event.ini",
+            RecordKind::Quest => "Quest.scr",
+            RecordKind::Message => "Message.scr",
+            RecordKind::Map => "AllMap.ini",
+            RecordKind::NpcIni => "Npc.ini",
+            RecordKind::ExtraIni => "Extra.ini",
+        }
+    }
+
+    /// Parse a [`key`](Self::key), ignoring case and a singular/plural `s`
+    /// (`heal_items`, `weapon` and `Monster` are all accepted).
+    pub fn from_key(key: &str) -> Option<Self> {
+        let wanted = key.to_ascii_lowercase();
+        let wanted = wanted.trim_end_matches('s');
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.key().trim_end_matches('s') == wanted)
+    }
+
+    /// Catalog selected by an inventory item type byte.
+    pub fn from_item_type(item_type: ItemTypeId) -> Option<Self> {
+        match item_type {
+            ItemTypeId::Weapon => Some(RecordKind::WeaponItem),
+            ItemTypeId::Healing => Some(RecordKind::HealItem),
+            ItemTypeId::Edit => Some(RecordKind::EditItem),
+            ItemTypeId::Event => Some(RecordKind::EventItem),
+            ItemTypeId::Misc => Some(RecordKind::MiscItem),
+            ItemTypeId::Other => None,
+        }
+    }
+
+    /// Catalog selected by a `Store.db` product type.
+    pub fn from_product_type(product_type: ProductType) -> Self {
+        match product_type {
+            ProductType::Weapon => RecordKind::WeaponItem,
+            ProductType::Healing => RecordKind::HealItem,
+            ProductType::EditItem => RecordKind::EditItem,
+            ProductType::MiscItem => RecordKind::MiscItem,
+        }
+    }
+}
+
+impl fmt::Display for RecordKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.file_name())
+    }
+}
+
+/// A referenced record: catalog plus id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RecordId {
+    pub kind: RecordKind,
+    pub id: i32,
+}
+
+impl fmt::Display for RecordId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} #{}", self.kind, self.id)
+    }
+}
+
+/// One place that refers to a record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Referencing file, relative to the game directory with `/` separators.
+    pub file: String,
+    /// Index of the referencing record within `file`.
+    pub record: usize,
+    /// Field path within that record, e.g. `products[3]` or
+    /// `actions[12].parameters[1]`.
+    pub field: String,
+    /// Human-readable name of the referencing record, when it has one.
+    pub label: Option<String>,
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} #{} {}", self.file, self.record, self.field)?;
+        if let Some(label) = &self.label {
+            write!(f, " ({label})")?;
+        }
+        Ok(())
+    }
+}
+
+/// How an event-script `[ACT]` function's parameters name records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActParameter {
+    /// Parameter `index` is an id of `kind`.
+    Record { kind: RecordKind, index: usize },
+    /// Parameters `type_index` and `id_index` are an [`ItemTypeId`] and an
+    /// item id.
+    Item { type_index: usize, id_index: usize },
+}
+
+/// An `[ACT]` function whose parameters point at records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActSignature {
+    /// Function name, matched case-insensitively.
+    pub function: &'static str,
+    pub parameter: ActParameter,
+}
+
+/// `[ACT]` functions with known record parameters. Parameters that are not
+/// integer literals (variables, expressions) are skipped.
+pub const ACT_SIGNATURES: &[ActSignature] = &[
+    ActSignature {
+        function: "additem",
+        parameter: ActParameter::Item {
+            type_index: 0,
+            id_index: 1,
+        },
+    },
+    ActSignature {
+        function: "delitem",
+        parameter: ActParameter::Item {
+            type_index: 0,
+            id_index: 1,
+        },
+    },
+    ActSignature {
+        function: "haveitem",
+        parameter: ActParameter::Item {
+            type_index: 0,
+            id_index: 1,
+        },
+    },
+    ActSignature {
+        function: "addquest",
+        parameter: ActParameter::Record {
+            kind: RecordKind::Quest,
+            index: 0,
+        },
+    },
+];
+
+/// Equipment slots and catalog cells of a save use `100` for "empty".
+pub(crate) const SAVE_EMPTY_CATALOG_INDEX: i32 = 100;
+
+/// PrtLevel.db spell slots use `0xff` for "no spell".
+pub(crate) const NO_PARTY_SPELL: u8 = 0xff;
+
+/// Usages of every referenced record in a game directory.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceIndex {
+    usages: BTreeMap<RecordId, Vec<Usage>>,
+    /// Files that were read, relative to the game directory.
+    pub files_indexed: Vec<String>,
+    /// Files that looked indexable but failed to parse, with the error.
+    pub errors: Vec<(String, String)>,
+}
+
+impl ReferenceIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index every recognised file under `game_dir`. Unreadable files are
+    /// recorded in [`errors`](Self::errors) and skipped; the `mods`
+    /// directory (backups and mod sessions) is not descended into.
+    pub fn build(game_dir: &Path) -> Self {
+        let mut index = Self::new();
+        let mut files = Vec::new();
+        collect_files(game_dir, game_dir, &mut files);
+        files.sort();
+        for relative in files {
+            let path = game_dir.join(&relative);
+            match index.index_file(&relative, &path) {
+                Ok(true) => index.files_indexed.push(relative),
+                Ok(false) => {}
+                Err(error) => index.errors.push((relative, error.to_string())),
+            }
+        }
+        index
+    }
+
+    /// Read and index one file. Returns `Ok(false)` when `relative` is not a
+    /// file type that holds references.
+    pub fn index_file(&mut self, relative: &str, path: &Path) -> std::io::Result<bool> {
+        let Some(source) = Source::of(relative) else {
+            return Ok(false);
+        };
+        match source {
+            Source::Stores => self.index_stores(relative, &Store::read_file(path)?),
+            Source::Monsters => self.index_monsters(relative, &Monster::read_file(path)?),
+            Source::PartyLevels => {
+                self.index_party_levels(relative, &PartyLevelNpc::read_file(path)?)
+            }
+            Source::DrawItems => self.index_draw_items(relative, &DrawItem::read_file(path)?),
+            Source::PartyRefs => self.index_party_refs(relative, &PartyRef::read_file(path)?),
+            Source::EventNpcs => self.index_event_npcs(relative, &EventNpcRef::read_file(path)?),
+            Source::ExtraRefs => self.index_extra_refs(relative, &ExtraRef::read_file(path)?),
+            Source::MonsterRefs => self.index_monster_refs(relative, &MonsterRef::read_file(path)?),
+            Source::NpcRefs => self.index_npc_refs(relative, &NPC::read_file(path)?),
+            Source::Dialogs => self.index_dialogs(relative, &DialogueScript::read_file(path)?),
+            Source::EventScripts => {
+                self.index_event_scripts(relative, &EventScript::read_file(path)?)
+            }
+            Source::Saves => self.index_saves(relative, &SaveFile::read_file(path)?),
+        }
+        Ok(true)
+    }
+
+    /// Every place that refers to `id` of `kind`, in file order.
+    pub fn usages(&self, kind: RecordKind, id: i32) -> &[Usage] {
+        self.usages
+            .get(&RecordId { kind, id })
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// All referenced records with their usages, ordered by kind and id.
+    pub fn iter(&self) -> impl Iterator<Item = (RecordId, &[Usage])> {
+        self.usages
+            .iter()
+            .map(|(id, usages)| (*id, usages.as_slice()))
+    }
+
+    /// Number of distinct referenced records.
+    pub fn len(&self) -> usize {
+        self.usages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.usages.is_empty()
+    }
+
+    pub fn index_stores(&mut self, file: &str, stores: &[Store]) {
+        for (record, store) in stores.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, Some(&store.store_name));
+            for (slot, (_, product_type, product_id)) in store.products.iter().enumerate() {
+                refs.add(
+                    RecordKind::from_product_type(*product_type),
+                    i32::from(*product_id),
+                    format!("products[{slot}]"),
+                );
+            }
+        }
+    }
+
+    pub fn index_draw_items(&mut self, file: &str, draw_items: &[DrawItem]) {
+        for (record, draw_item) in draw_items.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, None);
+            refs.add(RecordKind::Map, draw_item.map_id, "map_id");
+            refs.add_item(draw_item.item, "item");
+        }
+    }
+
+    pub fn index_monster_refs(&mut self, file: &str, placements: &[MonsterRef]) {
+        for (record, placement) in placements.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, None);
+            refs.add(
+                RecordKind::Monster,
+                placement.monster_db_id,
+                "monster_db_id",
+            );
+            refs.add_positive(
+                RecordKind::Event,
+                placement.event_id_on_kill,
+                "event_id_on_kill",
+            );
+            refs.add_item(placement.loot_item_1, "loot_item_1");
+            refs.add_item(placement.loot_item_2, "loot_item_2");
+            refs.add_item(placement.loot_item_3, "loot_item_3");
+        }
+    }
+
+    pub fn index_monsters(&mut self, file: &str, monsters: &[Monster]) {
+        for (record, monster) in monsters.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, Some(&monster.name));
+            // Zero and negative values are empty spell slots.
+            refs.add_positive(
+                RecordKind::MagicSpell,
+                monster.known_spell_slot1,
+                "known_spell_slot1",
+            );
+            refs.add_positive(
+                RecordKind::MagicSpell,
+                monster.known_spell_slot2,
+                "known_spell_slot2",
+            );
+            refs.add_positive(
+                RecordKind::MagicSpell,
+                monster.known_spell_slot3,
+                "known_spell_slot3",
+            );
+        }
+    }
+
+    pub fn index_extra_refs(&mut self, file: &str, extras: &[ExtraRef]) {
+        for (record, extra) in extras.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, Some(&extra.object_name));
+            refs.add(
+                RecordKind::ExtraIni,
+                i32::from(extra.extra_definition_id),
+                "extra_definition_id",
+            );
+            refs.add_item(extra.required_item, "required_item");
+            refs.add_item(extra.required_item2, "required_item2");
+            refs.add_item(extra.loot_item, "loot_item");
+            refs.add_positive(
+                RecordKind::Event,
+                extra.interaction_event_id,
+                "interaction_event_id",
+            );
+            refs.add_positive(
+                RecordKind::Message,
+                extra.interaction_message_id,
+                "interaction_message_id",
+            );
+        }
+    }
+
+    pub fn index_npc_refs(&mut self, file: &str, npcs: &[NPC]) {
+        for (record, npc) in npcs.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, Some(&npc.name));
+            refs.add(RecordKind::NpcIni, npc.npc_ini_id, "npc_ini_id");
+            refs.add_positive(RecordKind::Event, npc.show_on_event, "show_on_event");
+            refs.add_item(npc.interaction_result_item, "interaction_result_item");
+        }
+    }
+
+    pub fn index_event_npcs(&mut self, file: &str, npcs: &[EventNpcRef]) {
+        for (record, npc) in npcs.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, Some(&npc.name));
+            refs.add_positive(RecordKind::Event, npc.event_id, "event_id");
+        }
+    }
+
+    pub fn index_party_refs(&mut self, file: &str, members: &[PartyRef]) {
+        for (record, member) in members.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, member.full_name.as_deref());
+            refs.add(RecordKind::NpcIni, member.npc_id, "npc_id");
+            refs.add(RecordKind::Map, member.root_map_id, "root_map_id");
+        }
+    }
+
+    pub fn index_party_levels(&mut self, file: &str, party: &[PartyLevelNpc]) {
+        for (record, npc) in party.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, None);
+            refs.add(RecordKind::PartyIni, npc.npc_index as i32, "npc_index");
+            for (level, row) in npc.records.iter().enumerate() {
+                let spells = [
+                    ("magic_spell_id_1", row.magic_spell_id_1),
+                    ("magic_spell_id_2", row.magic_spell_id_2),
+                    ("magic_spell_id_3", row.magic_spell_id_3),
+                ];
+                for (field, spell) in spells {
+                    if spell != NO_PARTY_SPELL {
+                        refs.add(
+                            RecordKind::MagicSpell,
+                            i32::from(spell),
+                            format!("records[{level}].{field}"),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn index_dialogs(&mut self, file: &str, dialogs: &[DialogueScript]) {
+        for (record, dialog) in dialogs.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, None);
+            if let Some(event_id) = dialog.required_event_id {
+                refs.add_positive(RecordKind::Event, event_id, "required_event_id");
+            }
+            if let Some(event_id) = dialog.triggered_event_id {
+                refs.add_positive(RecordKind::Event, event_id, "triggered_event_id");
+            }
+        }
+    }
+
+    pub fn index_event_scripts(&mut self, file: &str, scripts: &[EventScript]) {
+        for (record, script) in scripts.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, None);
+            for (line, action) in script.actions.iter().enumerate() {
+                if action.raw_content.is_some() {
+                    continue;
+                }
+                let Some(signature) = ACT_SIGNATURES
+                    .iter()
+                    .find(|s| s.function.eq_ignore_ascii_case(&action.function_name))
+                else {
+                    continue;
+                };
+                let param = |index: usize| -> Option<i32> {
+                    action.parameters.get(index)?.trim().parse().ok()
+                };
+                match signature.parameter {
+                    ActParameter::Record { kind, index } => {
+                        if let Some(id) = param(index) {
+                            refs.add(kind, id, format!("actions[{line}].parameters[{index}]"));
+                        }
+                    }
+                    ActParameter::Item {
+                        type_index,
+                        id_index,
+                    } => {
+                        let kind = param(type_index)
+                            .and_then(|t| u8::try_from(t).ok())
+                            .and_then(ItemTypeId::from_u8)
+                            .and_then(RecordKind::from_item_type);
+                        if let (Some(kind), Some(id)) = (kind, param(id_index)) {
+                            refs.add(kind, id, format!("actions[{line}].parameters[{id_index}]"));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn index_saves(&mut self, file: &str, saves: &[SaveFile]) {
+        for (record, save) in saves.iter().enumerate() {
+            let mut refs = Refs::new(self, file, record, None);
+            let inventory = &save.inventory;
+            for (i, item) in inventory.weapon_items.iter().enumerate() {
+                refs.add(
+                    RecordKind::WeaponItem,
+                    item.weapon_item_id as i32,
+                    format!("inventory.weapon_items[{i}]"),
+                );
+            }
+            for (i, item) in inventory.heal_items.iter().enumerate() {
+                refs.add(
+                    RecordKind::HealItem,
+                    item.heal_item_id as i32,
+                    format!("inventory.heal_items[{i}]"),
+                );
+            }
+            for (i, item) in inventory.edit_items.iter().enumerate() {
+                refs.add(
+                    RecordKind::EditItem,
+                    item.edit_item_id as i32,
+                    format!("inventory.edit_items[{i}]"),
+                );
+            }
+            for (i, item) in inventory.event_items.iter().enumerate() {
+                refs.add(
+                    RecordKind::EventItem,
+                    item.event_item_id as i32,
+                    format!("inventory.event_items[{i}]"),
+                );
+            }
+            for (i, item) in inventory.misc_items.iter().enumerate() {
+                refs.add(
+                    RecordKind::MiscItem,
+                    item.misc_item_id as i32,
+                    format!("inventory.misc_items[{i}]"),
+                );
+            }
+            for (i, slot) in save.inventory_slots.equipped_equipment.iter().enumerate() {
+                if slot.weapon_catalog_index != SAVE_EMPTY_CATALOG_INDEX {
+                    refs.add(
+                        RecordKind::WeaponItem,
+                        slot.weapon_catalog_index,
+                        format!("inventory_slots.equipped_equipment[{i}]"),
+                    );
+                }
+            }
+            let journal = [
+                ("main", &save.journal.main),
+                ("side", &save.journal.side),
+                ("trade", &save.journal.trade),
+            ];
+            for (section, entries) in journal {
+                for (i, entry) in entries.iter().enumerate() {
+                    refs.add(
+                        RecordKind::Quest,
+                        i32::from(entry.quest_id),
+                        format!("journal.{section}[{i}].quest_id"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// File formats that hold references, recognised by file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Source {
+    Stores,
+    Monsters,
+    PartyLevels,
+    DrawItems,
+    PartyRefs,
+    EventNpcs,
+    ExtraRefs,
+    MonsterRefs,
+    NpcRefs,
+    Dialogs,
+    EventScripts,
+    Saves,
+}
+
+impl Source {
+    pub(crate) fn of(relative: &str) -> Option<Self> {
+        let name = relative
+            .rsplit('/')
+            .next()
+            .unwrap_or(relative)
+            .to_ascii_lowercase();
+        let (stem, extension) = name.rsplit_once('.').unwrap_or((name.as_str(), ""));
+        let source = match (stem, extension) {
+            ("store", "db") => Source::Stores,
+            ("monster", "db") => Source::Monsters,
+            ("prtlevel", "db") => Source::PartyLevels,
+            ("drawitem", "ref") => Source::DrawItems,
+            ("partyref", "ref") => Source::PartyRefs,
+            ("eventnpc", "ref") => Source::EventNpcs,
+            (stem, "ref") if stem.starts_with("ext") => Source::ExtraRefs,
+            (stem, "ref") if stem.starts_with("mon") => Source::MonsterRefs,
+            (stem, "ref") if stem.starts_with("npc") => Source::NpcRefs,
+            (_, "dlg") => Source::Dialogs,
+            (stem, "scr") if stem.starts_with("event") => Source::EventScripts,
+            (_, "sav") => Source::Saves,
+            _ => return None,
+        };
+        Some(source)
+    }
+}
+
+/// Collects the usages of one referencing record.
+struct Refs<'a> {
+    index: &'a mut ReferenceIndex,
+    file: &'a str,
+    record: usize,
+    label: Option<&'a str>,
+}
+
+impl<'a> Refs<'a> {
+    fn new(
+        index: &'a mut ReferenceIndex,
+        file: &'a str,
+        record: usize,
+        label: Option<&'a str>,
+    ) -> Self {
+        Self {
+            index,
+            file,
+            record,
+            label: label.map(str::trim).filter(|label| !label.is_empty()),
+        }
+    }
+
+    fn add(&mut self, kind: RecordKind, id: i32, field: impl Into<String>) {
+        self.index
+            .usages
+            .entry(RecordId { kind, id })
+            .or_default()
+            .push(Usage {
+                file: self.file.to_string(),
+                record: self.record,
+                field: field.into(),
+                label: self.label.map(str::to_string),
+            });
+    }
+
+    /// [`add`](Self::add) for id fields where `0` and negatives mean "none".
+    fn add_positive(&mut self, kind: RecordKind, id: i32, field: &str) {
+        if id > 0 {
+            self.add(kind, id, field);
+        }
+    }
+
+    fn add_item(&mut self, item: InventoryItem, field: &str) {
+        if item.is_empty() {
+            return;
+        }
+        if let Some(kind) = item.item_type().and_then(RecordKind::from_item_type) {
+            self.add(kind, i32::from(item.item_id()), field);
+        }
+    }
+}
+
+pub(crate) fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let is_mods = path
+                .file_name()
+                .is_some_and(|name| name.eq_ignore_ascii_case("mods"));
+            if !is_mods {
+                collect_files(root, &path, out);
+            }
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            out.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::references::event_scr::ActionFunction;
+
+    fn store(name: &str, products: Vec<(i16, ProductType, i16)>) -> Store {
+        Store {
+            store_name: name.to_string(),
+            products,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_kind_from_key_accepts_plural_and_singular() {
+        assert_eq!(
+            RecordKind::from_key("heal_items"),
+            Some(RecordKind::HealItem)
+        );
+        assert_eq!(
+            RecordKind::from_key("heal_item"),
+            Some(RecordKind::HealItem)
+        );
+        assert_eq!(RecordKind::from_key("weapon"), Some(RecordKind::WeaponItem));
+        assert_eq!(RecordKind::from_key("Monsters"), Some(RecordKind::Monster));
+        assert_eq!(RecordKind::from_key("magic"), Some(RecordKind::MagicSpell));
+        assert_eq!(RecordKind::from_key("sprites"), None);
+    }
+
+    #[test]
+    fn test_index_collects_usages_across_sources() {
+        let mut index = ReferenceIndex::new();
+        index.index_stores(
+            "CharacterInGame/STORE.DB",
+            &[
+                store("Armory", vec![(0, ProductType::Weapon, 7)]),
+                store(
+                    "Apothecary",
+                    vec![(0, ProductType::MiscItem, 1), (1, ProductType::Healing, 7)],
+                ),
+            ],
+        );
+        index.index_monster_refs(
+            "MonsterInGame/Mondun01.ref",
+            &[MonsterRef {
+                monster_db_id: 3,
+                loot_item_2: InventoryItem::new(ItemTypeId::Healing, 7),
+                loot_item_3: InventoryItem::from(0xFFFFu16),
+                ..Default::default()
+            }],
+        );
+
+        let usages = index.usages(RecordKind::HealItem, 7);
+        assert_eq!(usages.len(), 2);
+        assert_eq!(usages[0].file, "CharacterInGame/STORE.DB");
+        assert_eq!(usages[0].record, 1);
+        assert_eq!(usages[0].field, "products[1]");
+        assert_eq!(usages[0].label.as_deref(), Some("Apothecary"));
+        assert_eq!(usages[1].file, "MonsterInGame/Mondun01.ref");
+        assert_eq!(usages[1].field, "loot_item_2");
+
+        assert_eq!(index.usages(RecordKind::WeaponItem, 7).len(), 1);
+        assert_eq!(index.usages(RecordKind::Monster, 3).len(), 1);
+        assert!(index.usages(RecordKind::HealItem, 8).is_empty());
+    }
+
+    #[test]
+    fn test_event_script_parameters_use_act_signatures() {
+        let call = |name: &str, params: &[&str]| ActionFunction {
+            prefix: None,
+            function_name: name.to_string(),
+            parameters: params.iter().map(|p| p.to_string()).collect(),
+            raw_content: None,
+        };
+        let script = EventScript {
+            actions: vec![
+                call("AddItem", &["2", "7"]),
+                call("additem", &["2", "$var"]),
+                call("setmappos", &["2", "7"]),
+                call("addquest", &["4", "1"]),
+            ],
+            ..Default::default()
+        };
+        let mut index = ReferenceIndex::new();
+
+        index.index_event_scripts("Ref/Event12.scr", &[script]);
+
+        let usages = index.usages(RecordKind::HealItem, 7);
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].field, "actions[0].parameters[1]");
+        assert_eq!(index.usages(RecordKind::Quest, 4).len(), 1);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn test_build_skips_unrelated_files_and_reports_parse_errors() {
+        let dir = std::env::temp_dir().join(format!("dispel_usages_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("Ref")).unwrap();
+        std::fs::create_dir_all(dir.join("mods/backup/Ref")).unwrap();
+        let draw_items = "(1,5,10,519)\r\n";
+        std::fs::write(dir.join("Ref/DRAWITEM.ref"), draw_items).unwrap();
+        std::fs::write(dir.join("mods/backup/Ref/DRAWITEM.ref"), draw_items).unwrap();
+        std::fs::write(dir.join("Ref/readme.txt"), "not game data").unwrap();
+
+        let index = ReferenceIndex::build(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(index.files_indexed, ["Ref/DRAWITEM.ref"]);
+        assert!(index.errors.is_empty(), "{:?}", index.errors);
+        // 519 = 0x0207: item type 2 (heal item), id 7.
+        let usages = index.usages(RecordKind::HealItem, 7);
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].field, "item");
+        assert_eq!(index.usages(RecordKind::Map, 1).len(), 1);
+    }
+}
@@ -0,0 +1,290 @@
+//! Field-level validation of JSON records against their on-disk constraints.
+//!
+//! Deserializing JSON into a record type only proves the shape is right. The
+//! binary and text writers then silently truncate strings to their slot size,
+//! replace characters the file encoding cannot hold, and write byte arrays of
+//! whatever length they are given. The rules here describe those limits per
+//! field; `#[derive(Extractor)]` and `#[derive(TextExtractor)]` generate them
+//! from the same `#[extractor(...)]` attributes the writers use, and
+//! `#[translatable(...)]` limits are checked through [`Localizable`].
+
+use crate::localization::Localizable;
+use encoding_rs::Encoding;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// A limit one record field must respect to be written without loss.
+#[derive(Debug, Clone, Copy)]
+pub enum FieldRule {
+    /// Text stored in `encoding`, at most `max_bytes` bytes once encoded
+    /// (fixed-size slots) and free of `forbidden` characters (delimiters and
+    /// line breaks of text formats). `null` is accepted for optional text;
+    /// an array is checked element by element.
+    Text {
+        encoding: &'static Encoding,
+        max_bytes: Option<usize>,
+        forbidden: &'static [char],
+    },
+    /// Integer that must fit the on-disk type.
+    Integer { min: i64, max: i64 },
+    /// Enum value that must name a known variant of `type_name`.
+    Enum {
+        type_name: &'static str,
+        check: fn(&Value) -> Result<(), String>,
+    },
+    /// Array whose length must lie within `min..=max`.
+    Items { min: usize, max: usize },
+}
+
+/// A [`FieldRule`] bound to a top-level record field.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldConstraint {
+    pub field: &'static str,
+    pub rule: FieldRule,
+}
+
+/// A field value that would be truncated, corrupted or rejected on write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldViolation {
+    /// Field path within the record, e.g. `name` or `products[3]`.
+    pub path: String,
+    pub message: String,
+}
+
+/// On-disk limits of a record type's fields.
+///
+/// Derived by `Extractor` and `TextExtractor`; implemented by hand next to
+/// hand-written extractors. The default is for types whose fields are stored
+/// at their Rust width, where deserialization is already the whole check.
+pub trait FieldConstraints {
+    fn field_constraints() -> Vec<FieldConstraint> {
+        Vec::new()
+    }
+}
+
+/// `check` for [`FieldRule::Enum`]: the value must deserialize as `E`.
+pub fn enum_domain<E: DeserializeOwned>(value: &Value) -> Result<(), String> {
+    serde_json::from_value::<E>(value.clone())
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// Check one record against `constraints` without deserializing it.
+pub fn check_fields(constraints: &[FieldConstraint], record: &Value) -> Vec<FieldViolation> {
+    let mut violations = Vec::new();
+    for constraint in constraints {
+        if let Some(value) = record.get(constraint.field) {
+            check_rule(constraint.field, &constraint.rule, value, &mut violations);
+        }
+    }
+    violations
+}
+
+/// Validate one JSON record of type `T`: field constraints first, then
+/// deserialization (which catches missing fields and wrong shapes).
+pub fn validate_record<T>(record: &Value) -> Vec<FieldViolation>
+where
+    T: FieldConstraints + DeserializeOwned,
+{
+    let mut violations = check_fields(&T::field_constraints(), record);
+    if let Err(error) = serde_json::from_value::<T>(record.clone()) {
+        violations.push(FieldViolation {
+            path: String::new(),
+            message: error.to_string(),
+        });
+    }
+    violations
+}
+
+/// [`validate_record`] plus the `#[translatable]` byte limits and encodings
+/// of `T`'s text fields.
+pub fn validate_localizable_record<T>(record: &Value) -> Vec<FieldViolation>
+where
+    T: FieldConstraints + Localizable + DeserializeOwned,
+{
+    let mut violations = check_fields(&T::field_constraints(), record);
+    let parsed = match serde_json::from_value::<T>(record.clone()) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            violations.push(FieldViolation {
+                path: String::new(),
+                message: error.to_string(),
+            });
+            return violations;
+        }
+    };
+    for mut entry in parsed.extract_texts(0, "") {
+        // Field rules from the binary layout may already cover this field.
+        if violations.iter().any(|v| v.path == entry.field_name) {
+            continue;
+        }
+        entry.translation = std::mem::take(&mut entry.original);
+        let bad_chars = entry.unrepresentable_chars();
+        if !bad_chars.is_empty() {
+            violations.push(FieldViolation {
+                path: entry.field_name.to_string(),
+                message: format!(
+                    "characters {bad_chars:?} cannot be encoded as {}",
+                    entry.encoding.label()
+                ),
+            });
+        } else if entry.would_truncate() {
+            violations.push(FieldViolation {
+                path: entry.field_name.to_string(),
+                message: format!(
+                    "text is {} bytes, limit is {}",
+                    entry.encoded_translation_len(),
+                    entry.max_bytes
+                ),
+            });
+        }
+    }
+    violations
+}
+
+fn check_rule(path: &str, rule: &FieldRule, value: &Value, out: &mut Vec<FieldViolation>) {
+    let message = match *rule {
+        FieldRule::Text {
+            encoding,
+            max_bytes,
+            forbidden,
+        } => match value {
+            Value::Null => None,
+            Value::String(text) => text_problem(text, encoding, max_bytes, forbidden),
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    check_rule(&format!("{path}[{index}]"), rule, item, out);
+                }
+                None
+            }
+            _ => Some("expected text".to_string()),
+        },
+        FieldRule::Integer { min, max } => match value.as_i64() {
+            Some(number) if (min..=max).contains(&number) => None,
+            Some(number) => Some(format!("{number} is outside {min}..={max}")),
+            None if value.is_number() => Some(format!("{value} is outside {min}..={max}")),
+            None => Some("expected an integer".to_string()),
+        },
+        FieldRule::Enum { type_name, check } => check(value)
+            .err()
+            .map(|error| format!("not a known {type_name} value: {error}")),
+        FieldRule::Items { min, max } => match value.as_array() {
+            Some(items) if (min..=max).contains(&items.len()) => None,
+            Some(items) if min == max => {
+                Some(format!("has {} items, expected exactly {min}", items.len()))
+            }
+            Some(items) => Some(format!("has {} items, expected {min}..={max}", items.len())),
+            None => Some("expected an array".to_string()),
+        },
+    };
+    if let Some(message) = message {
+        out.push(FieldViolation {
+            path: path.to_string(),
+            message,
+        });
+    }
+}
+
+fn text_problem(
+    text: &str,
+    encoding: &'static Encoding,
+    max_bytes: Option<usize>,
+    forbidden: &[char],
+) -> Option<String> {
+    let bad_chars: Vec<char> = text
+        .chars()
+        .filter(|c| encoding.encode(c.encode_utf8(&mut [0; 4])).2)
+        .collect();
+    if !bad_chars.is_empty() {
+        return Some(format!(
+            "characters {bad_chars:?} cannot be encoded as {}",
+            encoding.name()
+        ));
+    }
+    if let Some(c) = text.chars().find(|c| forbidden.contains(c)) {
+        return Some(format!(
+            "contains {c:?}, which the file format uses as a separator"
+        ));
+    }
+    let (encoded, _, _) = encoding.encode(text);
+    match max_bytes {
+        Some(max_bytes) if encoded.len() > max_bytes => Some(format!(
+            "text is {} bytes, limit is {max_bytes}",
+            encoded.len()
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const RULES: &[FieldConstraint] = &[
+        FieldConstraint {
+            field: "name",
+            rule: FieldRule::Text {
+                encoding: encoding_rs::WINDOWS_1250,
+                max_bytes: Some(4),
+                forbidden: &[],
+            },
+        },
+        FieldConstraint {
+            field: "level",
+            rule: FieldRule::Integer { min: 0, max: 255 },
+        },
+        FieldConstraint {
+            field: "flags",
+            rule: FieldRule::Items { min: 2, max: 2 },
+        },
+    ];
+
+    #[test]
+    fn test_check_fields_reports_paths_and_limits() {
+        let violations = check_fields(
+            RULES,
+            &json!({ "name": "Sword", "level": 300, "flags": [1] }),
+        );
+
+        let paths: Vec<_> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, ["name", "level", "flags"]);
+        assert_eq!(violations[0].message, "text is 5 bytes, limit is 4");
+        assert_eq!(violations[1].message, "300 is outside 0..=255");
+        assert_eq!(violations[2].message, "has 1 items, expected exactly 2");
+    }
+
+    #[test]
+    fn test_check_fields_accepts_values_that_fit() {
+        let violations = check_fields(
+            RULES,
+            &json!({ "name": "Axe", "level": 9, "flags": [0, 1] }),
+        );
+
+        assert!(violations.is_empty(), "{violations:?}");
+    }
+
+    #[test]
+    fn test_text_rule_rejects_unencodable_and_separator_characters() {
+        let rule = FieldRule::Text {
+            encoding: encoding_rs::WINDOWS_1250,
+            max_bytes: None,
+            forbidden: &[',', '\n'],
+        };
+        let mut violations = Vec::new();
+
+        check_rule(
+            "lines",
+            &rule,
+            &json!(["ok", "日本", "a,b"]),
+            &mut violations,
+        );
+
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].path, "lines[1]");
+        assert!(violations[0].message.contains("cannot be encoded"));
+        assert_eq!(violations[1].path, "lines[2]");
+        assert!(violations[1].message.contains("separator"));
+    }
+}
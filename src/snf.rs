@@ -1,4 +1,5 @@
 use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::Result;
 use std::io::prelude::*;
@@ -26,6 +27,60 @@ use std::path::Path;
 // After the header, there are 2 bytes that typically contain the value 8.
 // The remainder of the file contains raw PCM audio data.
 
+/// Sample layout of an SNF or WAV file, the target of [`SnfFile::convert`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcmFormat {
+    pub pcmaudio_format: i16,
+    pub number_of_channels: i16,
+    pub sample_rate: i32,
+    pub bits_per_sample: i16,
+}
+
+impl PcmFormat {
+    pub fn block_align(&self) -> i16 {
+        self.number_of_channels * (self.bits_per_sample / 8)
+    }
+
+    pub fn byte_rate(&self) -> i32 {
+        self.sample_rate * i32::from(self.block_align())
+    }
+
+    /// Integer PCM with 8, 16, 24 or 32 bits per sample and at least one
+    /// channel: the layouts [`SnfFile::channel_samples`] can decode.
+    fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| Err(std::io::Error::new(std::io::ErrorKind::InvalidData, msg));
+        if self.pcmaudio_format != 1 {
+            return invalid(format!(
+                "unsupported audio format {} (only PCM is supported)",
+                self.pcmaudio_format
+            ));
+        }
+        if !matches!(self.bits_per_sample, 8 | 16 | 24 | 32) {
+            return invalid(format!(
+                "unsupported sample size of {} bits",
+                self.bits_per_sample
+            ));
+        }
+        if self.number_of_channels < 1 || self.sample_rate < 1 {
+            return invalid(format!(
+                "invalid layout: {} channel(s) at {} Hz",
+                self.number_of_channels, self.sample_rate
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for PcmFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} Hz, {}-bit, {} channel(s)",
+            self.sample_rate, self.bits_per_sample, self.number_of_channels
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SnfFile {
     pub pcmaudio_format: i16,
@@ -246,6 +301,116 @@ impl SnfFile {
         out
     }
 
+    pub fn format(&self) -> PcmFormat {
+        PcmFormat {
+            pcmaudio_format: self.pcmaudio_format,
+            number_of_channels: self.number_of_channels,
+            sample_rate: self.sample_rate,
+            bits_per_sample: self.bits_per_sample,
+        }
+    }
+
+    /// Decodes the PCM data into one vector of `[-1.0, 1.0]` samples per
+    /// channel. 8-bit PCM is unsigned, wider PCM signed little-endian.
+    pub fn channel_samples(&self) -> Result<Vec<Vec<f32>>> {
+        let format = self.format();
+        format.validate()?;
+        let width = (format.bits_per_sample / 8) as usize;
+        let channels = format.number_of_channels as usize;
+        let frames = self.pcm_data.len() / (width * channels);
+        let mut out = vec![Vec::with_capacity(frames); channels];
+        for frame in self.pcm_data.chunks_exact(width * channels) {
+            for (channel, sample) in out.iter_mut().zip(frame.chunks_exact(width)) {
+                channel.push(decode_sample(sample));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Encodes per-channel `[-1.0, 1.0]` samples as `format`. Samples out of
+    /// range are clipped; all channels must have the same length.
+    pub fn from_channel_samples(format: PcmFormat, channels: &[Vec<f32>]) -> Result<Self> {
+        format.validate()?;
+        if channels.len() != format.number_of_channels as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "{} channel(s) of samples for a {}-channel format",
+                    channels.len(),
+                    format.number_of_channels
+                ),
+            ));
+        }
+        let frames = channels.first().map_or(0, Vec::len);
+        let mut pcm_data = Vec::with_capacity(frames * format.block_align() as usize);
+        for i in 0..frames {
+            for channel in channels {
+                encode_sample(channel[i], format.bits_per_sample, &mut pcm_data);
+            }
+        }
+        Ok(SnfFile {
+            pcmaudio_format: format.pcmaudio_format,
+            number_of_channels: format.number_of_channels,
+            sample_rate: format.sample_rate,
+            byte_rate: format.byte_rate(),
+            block_align: format.block_align(),
+            bits_per_sample: format.bits_per_sample,
+            data_size: pcm_data.len() as i32,
+            pcm_data,
+        })
+    }
+
+    /// Converts to `target`: channels are averaged down to mono or
+    /// duplicated up from it, the sample rate is changed by linear
+    /// interpolation and samples are re-quantised to the target width.
+    pub fn convert(&self, target: PcmFormat) -> Result<Self> {
+        target.validate()?;
+        if self.format() == target {
+            return Ok(self.clone());
+        }
+        let channels = remix(self.channel_samples()?, target.number_of_channels as usize);
+        let channels: Vec<Vec<f32>> = channels
+            .iter()
+            .map(|c| resample(c, self.sample_rate, target.sample_rate))
+            .collect();
+        Self::from_channel_samples(target, &channels)
+    }
+
+    /// RMS level in dBFS, `None` for silence.
+    pub fn rms_dbfs(&self) -> Result<Option<f32>> {
+        let channels = self.channel_samples()?;
+        let count: usize = channels.iter().map(Vec::len).sum();
+        let sum: f64 = channels
+            .iter()
+            .flatten()
+            .map(|&s| f64::from(s) * f64::from(s))
+            .sum();
+        if count == 0 || sum == 0.0 {
+            return Ok(None);
+        }
+        Ok(Some((10.0 * (sum / count as f64).log10()) as f32))
+    }
+
+    /// Scales the sound to an RMS level of `target_dbfs`, lowering the gain
+    /// where it would clip the loudest sample. Silence is left unchanged.
+    pub fn normalize(&self, target_dbfs: f32) -> Result<Self> {
+        let Some(level) = self.rms_dbfs()? else {
+            return Ok(self.clone());
+        };
+        let mut channels = self.channel_samples()?;
+        let peak = channels
+            .iter()
+            .flatten()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let gain = 10f32
+            .powf((target_dbfs - level) / 20.0)
+            .min(1.0 / peak.max(f32::EPSILON));
+        for sample in channels.iter_mut().flatten() {
+            *sample *= gain;
+        }
+        Self::from_channel_samples(self.format(), &channels)
+    }
+
     /// Returns `num_points` (min, max) amplitude pairs in `[-1.0, 1.0]` for waveform display.
     /// Supports 8-bit unsigned and 16-bit signed PCM.
     pub fn waveform_points(&self, num_points: usize) -> Vec<(f32, f32)> {
@@ -307,6 +472,105 @@ impl SnfFile {
     }
 }
 
+fn decode_sample(bytes: &[u8]) -> f32 {
+    match *bytes {
+        [b] => (f32::from(b) - 128.0) / 128.0,
+        [b0, b1] => f32::from(i16::from_le_bytes([b0, b1])) / 32768.0,
+        [b0, b1, b2] => (i32::from_le_bytes([0, b0, b1, b2]) >> 8) as f32 / 8_388_608.0,
+        [b0, b1, b2, b3] => i32::from_le_bytes([b0, b1, b2, b3]) as f32 / 2_147_483_648.0,
+        _ => 0.0,
+    }
+}
+
+fn encode_sample(sample: f32, bits_per_sample: i16, out: &mut Vec<u8>) {
+    let sample = f64::from(sample);
+    let scaled = |full_scale: f64| {
+        (sample * full_scale)
+            .round()
+            .clamp(-full_scale, full_scale - 1.0)
+    };
+    match bits_per_sample {
+        8 => out.push((scaled(128.0) + 128.0) as u8),
+        16 => out.extend_from_slice(&(scaled(32_768.0) as i16).to_le_bytes()),
+        24 => out.extend_from_slice(&(scaled(8_388_608.0) as i32).to_le_bytes()[..3]),
+        _ => out.extend_from_slice(&(scaled(2_147_483_648.0) as i32).to_le_bytes()),
+    }
+}
+
+/// Mixes `channels` down to mono by averaging, up from mono by duplicating,
+/// and otherwise keeps the first `target` channels (repeating the last).
+fn remix(channels: Vec<Vec<f32>>, target: usize) -> Vec<Vec<f32>> {
+    if channels.len() == target || channels.is_empty() {
+        return channels;
+    }
+    if target == 1 {
+        let frames = channels[0].len();
+        let scale = 1.0 / channels.len() as f32;
+        return vec![
+            (0..frames)
+                .map(|i| channels.iter().map(|c| c[i]).sum::<f32>() * scale)
+                .collect(),
+        ];
+    }
+    (0..target)
+        .map(|c| channels[c.min(channels.len() - 1)].clone())
+        .collect()
+}
+
+/// Zero crossings of the resampling kernel on each side of its centre.
+const RESAMPLE_ZERO_CROSSINGS: f64 = 16.0;
+
+/// Changes the sample rate of one channel with a Blackman-windowed sinc
+/// interpolator. When downsampling, the cutoff moves down to the target
+/// Nyquist frequency so content above it is filtered out instead of
+/// aliasing into the audible band.
+fn resample(samples: &[f32], from: i32, to: i32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let len = ((samples.len() as u64 * to as u64) / from as u64).max(1) as usize;
+    let step = f64::from(from) / f64::from(to);
+    let cutoff = (f64::from(to) / f64::from(from)).min(1.0);
+    let half_width = RESAMPLE_ZERO_CROSSINGS / cutoff;
+    let last = samples.len() as i64 - 1;
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 * step;
+            let first = ((pos - half_width).ceil() as i64).max(0);
+            let end = ((pos + half_width).floor() as i64).min(last);
+            let (mut acc, mut norm) = (0.0f64, 0.0f64);
+            for k in first..=end {
+                let t = pos - k as f64;
+                let w = sinc(cutoff * t) * blackman(t / half_width);
+                acc += w * f64::from(samples[k as usize]);
+                norm += w;
+            }
+            // Normalising by the tap sum keeps unity gain at DC, including
+            // near the edges where the kernel is cut short.
+            if norm.abs() > f64::EPSILON {
+                (acc / norm) as f32
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Blackman window over `x` in `-1.0..=1.0`.
+fn blackman(x: f64) -> f64 {
+    let px = std::f64::consts::PI * x;
+    0.42 + 0.5 * px.cos() + 0.08 * (2.0 * px).cos()
+}
+
 /// Reads an SNF file into memory.
 pub fn read(path: &Path) -> Result<SnfFile> {
     let mut file = File::open(path)?;
@@ -369,6 +633,135 @@ pub fn import_wav(wav_path: &Path, snf_path: &Path) -> Result<()> {
     save(snf_path, &snf)
 }
 
+/// Reads a WAV, OGG Vorbis, FLAC or MP3 file as PCM in its own sample rate
+/// and channel count. WAV keeps its sample format; FLAC keeps its bit depth
+/// and lossy formats decode to 16-bit.
+pub fn read_audio(path: &Path) -> Result<SnfFile> {
+    let bytes = std::fs::read(path)?;
+    if bytes.starts_with(b"RIFF") {
+        return SnfFile::from_wav_bytes(&bytes);
+    }
+    let extension = path.extension().and_then(|e| e.to_str());
+    decode_audio(bytes, extension)
+}
+
+/// Decodes compressed audio with symphonia; `extension` is a hint for the
+/// container probe.
+fn decode_audio(bytes: Vec<u8>, extension: Option<&str>) -> Result<SnfFile> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::errors::Error as DecodeError;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let invalid = |e: DecodeError| match e {
+        DecodeError::IoError(e) => e,
+        e => std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()),
+    };
+
+    let stream = MediaSourceStream::new(Box::new(std::io::Cursor::new(bytes)), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = extension {
+        hint.with_extension(extension);
+    }
+    let mut reader = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(invalid)?
+        .format;
+    let track = reader
+        .default_track()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "no audio track"))?;
+    let track_id = track.id;
+    let bits_per_sample = match track.codec_params.bits_per_sample {
+        Some(bits @ (8 | 16 | 24 | 32)) => bits as i16,
+        _ => 16,
+    };
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(invalid)?;
+
+    let mut channels: Vec<Vec<f32>> = Vec::new();
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    loop {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(DecodeError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(e) => return Err(invalid(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet is dropped, as players do.
+            Err(DecodeError::DecodeError(_)) => continue,
+            Err(e) => return Err(invalid(e)),
+        };
+        let spec = *decoded.spec();
+        let frames = decoded.frames();
+        sample_rate = spec.rate;
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_planar_ref(decoded);
+        let count = spec.channels.count();
+        channels.resize_with(count.max(channels.len()), Vec::new);
+        for (channel, samples) in channels
+            .iter_mut()
+            .zip(buffer.samples().chunks(frames.max(1)))
+        {
+            channel.extend_from_slice(samples);
+        }
+    }
+
+    if channels.is_empty() || sample_rate == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "no audio decoded",
+        ));
+    }
+    let format = PcmFormat {
+        pcmaudio_format: 1,
+        number_of_channels: channels.len() as i16,
+        sample_rate: sample_rate as i32,
+        bits_per_sample,
+    };
+    SnfFile::from_channel_samples(format, &channels)
+}
+
+/// Converts any [`read_audio`] input to SNF at `snf_path` in `target`.
+/// Without a target, the format of the SNF being replaced at `snf_path` is
+/// kept; a new file gets the source's rate and channels at 16 bits or less.
+/// An existing SNF that cannot be read is an error rather than a new file.
+pub fn import_audio(
+    audio_path: &Path,
+    snf_path: &Path,
+    target: Option<PcmFormat>,
+) -> Result<SnfFile> {
+    let source = read_audio(audio_path)?;
+    let target = match target {
+        Some(target) => target,
+        None => match read(snf_path) {
+            Ok(replaced) => replaced.format(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => PcmFormat {
+                bits_per_sample: source.bits_per_sample.min(16),
+                ..source.format()
+            },
+            Err(e) => return Err(e),
+        },
+    };
+    let snf = source.convert(target)?;
+    save(snf_path, &snf)?;
+    Ok(snf)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -428,6 +821,247 @@ mod tests {
         assert_eq!(original.pcm_data, reloaded.pcm_data);
     }
 
+    fn tone(format: PcmFormat, frames: usize) -> SnfFile {
+        let channels: Vec<Vec<f32>> = (0..format.number_of_channels)
+            .map(|c| {
+                (0..frames)
+                    .map(|i| ((i as f32 * 0.3).sin() * 0.5) * if c == 0 { 1.0 } else { -1.0 })
+                    .collect()
+            })
+            .collect();
+        SnfFile::from_channel_samples(format, &channels).unwrap()
+    }
+
+    #[test]
+    fn samples_round_trip_at_every_width() {
+        for bits in [8, 16, 24, 32] {
+            let format = PcmFormat {
+                pcmaudio_format: 1,
+                number_of_channels: 2,
+                sample_rate: 22050,
+                bits_per_sample: bits,
+            };
+            let snf = tone(format, 50);
+            assert_eq!(snf.pcm_data.len(), 50 * format.block_align() as usize);
+            let again = SnfFile::from_channel_samples(format, &snf.channel_samples().unwrap());
+            assert_eq!(again.unwrap().pcm_data, snf.pcm_data, "{bits}-bit");
+        }
+    }
+
+    #[test]
+    fn convert_changes_rate_channels_and_width() {
+        let stereo = tone(
+            PcmFormat {
+                pcmaudio_format: 1,
+                number_of_channels: 2,
+                sample_rate: 44100,
+                bits_per_sample: 16,
+            },
+            400,
+        );
+        let target = PcmFormat {
+            pcmaudio_format: 1,
+            number_of_channels: 1,
+            sample_rate: 22050,
+            bits_per_sample: 8,
+        };
+        let mono = stereo.convert(target).unwrap();
+        assert_eq!(mono.format(), target);
+        assert_eq!(mono.block_align, 1);
+        assert_eq!(mono.byte_rate, 22050);
+        assert_eq!(mono.pcm_data.len(), 200);
+        // The channels are in antiphase, so the mono mix is silence.
+        assert!(mono.pcm_data.iter().all(|&b| b == 128));
+
+        let up = mono.convert(stereo.format()).unwrap();
+        assert_eq!(up.format(), stereo.format());
+        assert_eq!(up.pcm_data.len(), 400 * 4);
+        assert!(
+            stereo
+                .convert(PcmFormat {
+                    bits_per_sample: 12,
+                    ..target
+                })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn downsampling_filters_content_above_the_new_nyquist() {
+        let sine = |hz: f64| -> Vec<f32> {
+            (0..4410)
+                .map(|i| (2.0 * std::f64::consts::PI * hz * i as f64 / 44100.0).sin() as f32)
+                .collect()
+        };
+        // RMS away from the edges, where the kernel is cut short.
+        let rms = |s: &[f32]| {
+            let mid = &s[200..s.len() - 200];
+            (mid.iter().map(|x| x * x).sum::<f32>() / mid.len() as f32).sqrt()
+        };
+
+        let passband = resample(&sine(1000.0), 44100, 22050);
+        assert_eq!(passband.len(), 2205);
+        assert!((rms(&passband) - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.02);
+
+        // 15 kHz would alias to 7.05 kHz at 22.05 kHz; it must be filtered.
+        let stopband = resample(&sine(15000.0), 44100, 22050);
+        assert!(rms(&stopband) < 0.01, "aliased energy {}", rms(&stopband));
+
+        let dc = resample(&[0.5; 64], 22050, 44100);
+        assert!(dc.iter().all(|&x| (x - 0.5).abs() < 1e-4));
+    }
+
+    #[test]
+    fn normalize_reaches_target_without_clipping() {
+        let format = PcmFormat {
+            pcmaudio_format: 1,
+            number_of_channels: 1,
+            sample_rate: 22050,
+            bits_per_sample: 16,
+        };
+        let quiet = SnfFile::from_channel_samples(format, &[vec![0.1, -0.1, 0.1, -0.1]]).unwrap();
+        assert!((quiet.rms_dbfs().unwrap().unwrap() + 20.0).abs() < 0.01);
+        let louder = quiet.normalize(-12.0).unwrap();
+        assert!((louder.rms_dbfs().unwrap().unwrap() + 12.0).abs() < 0.01);
+        // 0 dBFS RMS would need a gain of 10; the peak limits it to 1.0.
+        let limited = quiet.normalize(0.0).unwrap();
+        let peak = limited.channel_samples().unwrap()[0][0];
+        assert!((peak - 1.0).abs() < 0.001);
+        let silence = SnfFile::from_channel_samples(format, &[vec![0.0; 4]]).unwrap();
+        assert_eq!(silence.rms_dbfs().unwrap(), None);
+        assert_eq!(silence.normalize(-12.0).unwrap().pcm_data, silence.pcm_data);
+    }
+
+    /// A FLAC stream of one frame with verbatim (uncompressed) subframes.
+    fn verbatim_flac(sample_rate: u32, channels: &[Vec<i16>]) -> Vec<u8> {
+        fn crc8(bytes: &[u8]) -> u8 {
+            bytes.iter().fold(0u8, |mut crc, &b| {
+                crc ^= b;
+                for _ in 0..8 {
+                    crc = if crc & 0x80 != 0 {
+                        (crc << 1) ^ 0x07
+                    } else {
+                        crc << 1
+                    };
+                }
+                crc
+            })
+        }
+        fn crc16(bytes: &[u8]) -> u16 {
+            bytes.iter().fold(0u16, |mut crc, &b| {
+                crc ^= u16::from(b) << 8;
+                for _ in 0..8 {
+                    crc = if crc & 0x8000 != 0 {
+                        (crc << 1) ^ 0x8005
+                    } else {
+                        crc << 1
+                    };
+                }
+                crc
+            })
+        }
+        let block = channels[0].len() as u64;
+        let mut out = b"fLaC".to_vec();
+        out.extend_from_slice(&[0x80, 0, 0, 34]);
+        out.extend_from_slice(&(block as u16).to_be_bytes());
+        out.extend_from_slice(&(block as u16).to_be_bytes());
+        out.extend_from_slice(&[0; 6]);
+        let info = (u64::from(sample_rate) << 44)
+            | ((channels.len() as u64 - 1) << 41)
+            | (15 << 36)
+            | block;
+        out.extend_from_slice(&info.to_be_bytes());
+        out.extend_from_slice(&[0; 16]);
+
+        let mut frame = vec![
+            0xFF,
+            0xF8,
+            0x60,
+            ((channels.len() as u8 - 1) << 4) | 0x08,
+            0,
+        ];
+        frame.push(block as u8 - 1);
+        frame.push(crc8(&frame));
+        for channel in channels {
+            frame.push(0x02);
+            for sample in channel {
+                frame.extend_from_slice(&sample.to_be_bytes());
+            }
+        }
+        frame.extend_from_slice(&crc16(&frame).to_be_bytes());
+        out.extend_from_slice(&frame);
+        out
+    }
+
+    #[test]
+    fn read_audio_decodes_flac() {
+        let left: Vec<i16> = (0..32).map(|i| i * 1000).collect();
+        let right: Vec<i16> = left.iter().map(|s| -s).collect();
+        let path = std::env::temp_dir().join("dispel_test_tone.flac");
+        std::fs::write(&path, verbatim_flac(11025, &[left.clone(), right])).unwrap();
+
+        let snf = read_audio(&path).unwrap();
+        assert_eq!(
+            snf.format(),
+            PcmFormat {
+                pcmaudio_format: 1,
+                number_of_channels: 2,
+                sample_rate: 11025,
+                bits_per_sample: 16,
+            }
+        );
+        let samples = snf.channel_samples().unwrap();
+        assert_eq!(samples[0].len(), 32);
+        assert_eq!(samples[0][3], 3000.0 / 32768.0);
+        assert_eq!(samples[1][3], -3000.0 / 32768.0);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn import_audio_matches_the_replaced_snf() {
+        let dir = std::env::temp_dir();
+        let flac = dir.join("dispel_test_import.flac");
+        let snf_path = dir.join("dispel_test_import.snf");
+        let wav_path = dir.join("dispel_test_import.wav");
+        let tone: Vec<i16> = (0..64).map(|i| (i % 8) * 2000).collect();
+        std::fs::write(&flac, verbatim_flac(22050, &[tone.clone(), tone])).unwrap();
+
+        let _ = std::fs::remove_file(&snf_path);
+        let fresh = import_audio(&flac, &snf_path, None).unwrap();
+        assert_eq!(fresh.number_of_channels, 2);
+        assert_eq!(fresh.sample_rate, 22050);
+
+        let game = PcmFormat {
+            pcmaudio_format: 1,
+            number_of_channels: 1,
+            sample_rate: 11025,
+            bits_per_sample: 8,
+        };
+        save(
+            &snf_path,
+            &SnfFile::from_channel_samples(game, &[vec![0.0; 4]]).unwrap(),
+        )
+        .unwrap();
+        let replaced = import_audio(&flac, &snf_path, None).unwrap();
+        assert_eq!(replaced.format(), game);
+        assert_eq!(replaced.pcm_data.len(), 32);
+        assert_eq!(read(&snf_path).unwrap().pcm_data, replaced.pcm_data);
+
+        // WAV input takes the same route.
+        std::fs::write(&wav_path, replaced.to_wav_bytes()).unwrap();
+        assert_eq!(read_audio(&wav_path).unwrap().format(), game);
+        assert!(read_audio(&snf_path).is_err());
+
+        // A truncated SNF is not silently replaced in the source's format.
+        std::fs::write(&snf_path, [1u8, 2, 3]).unwrap();
+        assert!(import_audio(&flac, &snf_path, None).is_err());
+        assert_eq!(std::fs::read(&snf_path).unwrap(), [1, 2, 3]);
+
+        for path in [flac, snf_path, wav_path] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
     #[test]
     fn import_wav_export_snf() {
         let original = SnfFile {
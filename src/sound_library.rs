@@ -0,0 +1,483 @@
+//! Wave.ini-aware sound library.
+//!
+//! `Wave.ini` maps sound ids to SNF files, which may sit at the game root,
+//! under `Sound/` or in any other folder, and event scripts name sound ids
+//! in their `[WAV]` section. [`SoundLibrary`] gathers the three, exports
+//! every listed SNF to a WAV folder with a [`SoundManifest`], imports such a
+//! folder back in each sound's original format, and reports missing and
+//! unused files and ids.
+
+use crate::references::event_scr::EventScript;
+use crate::references::extractor::{Extractor, read_or_warn};
+use crate::references::usages::collect_files;
+use crate::references::wave_ini::WaveIni;
+use crate::snf::{self, PcmFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// File name of the manifest in an exported WAV folder.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// One `[WAV]` line of an event script.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScriptSound {
+    /// Script file, relative to the game directory.
+    pub file: String,
+    pub line: String,
+    /// Sound id named by the line, if it names one.
+    pub id: Option<i32>,
+}
+
+/// `Wave.ini`, the SNF files on disk and the sounds event scripts use.
+#[derive(Debug, Clone, Default)]
+pub struct SoundLibrary {
+    pub waves: Vec<WaveIni>,
+    /// Every `.snf` file under the game directory, relative and sorted.
+    pub snf_files: Vec<String>,
+    pub script_sounds: Vec<ScriptSound>,
+    /// Event scripts that could not be read.
+    pub warnings: Vec<String>,
+}
+
+/// A `Wave.ini` entry whose SNF file is not in the game directory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingSnf {
+    pub id: i32,
+    pub snf_filename: String,
+}
+
+/// Result of [`SoundLibrary::check`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LibraryReport {
+    pub missing_snf: Vec<MissingSnf>,
+    /// SNF files no `Wave.ini` entry names.
+    pub unused_snf: Vec<String>,
+    /// `[WAV]` lines naming an id `Wave.ini` does not define.
+    pub undefined_ids: Vec<ScriptSound>,
+    /// `[WAV]` lines that do not name a sound id.
+    pub unparsed: Vec<ScriptSound>,
+    /// `Wave.ini` ids no event script names. The engine plays most sounds
+    /// by id on its own, so these are informational.
+    pub unreferenced_ids: Vec<i32>,
+}
+
+impl LibraryReport {
+    /// No missing SNF files and no undefined script ids.
+    pub fn is_clean(&self) -> bool {
+        self.missing_snf.is_empty() && self.undefined_ids.is_empty()
+    }
+}
+
+/// One `Wave.ini` entry of an exported WAV folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub id: i32,
+    pub max_simultaneous_plays: i32,
+    /// SNF file, relative to the game directory.
+    pub snf: String,
+    /// WAV file, relative to the manifest.
+    pub wav: String,
+    /// Format of the original SNF; imports convert back to it.
+    pub format: PcmFormat,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SoundManifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl SoundManifest {
+    pub fn read(dir: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(dir.join(MANIFEST_FILE))?;
+        serde_json::from_str(&text).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(Error::other)?;
+        std::fs::write(dir.join(MANIFEST_FILE), text)
+    }
+}
+
+/// Result of [`export_all`].
+#[derive(Debug, Clone, Default)]
+pub struct ExportSummary {
+    pub manifest: SoundManifest,
+    /// WAV files written, relative to the output directory.
+    pub written: Vec<String>,
+    /// SNF files that could not be converted, with the reason.
+    pub failed: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// Normalize every sound to this RMS level in dBFS.
+    pub normalize_dbfs: Option<f32>,
+}
+
+/// Result of [`import_all`].
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    /// SNF files written, relative to the output directory.
+    pub written: Vec<String>,
+    /// How many of them needed a format conversion.
+    pub converted: usize,
+    /// WAV files that could not be imported, with the reason.
+    pub failed: Vec<(String, String)>,
+}
+
+impl SoundLibrary {
+    /// Reads `Wave.ini` from the game root, which must exist, and every
+    /// `Ref/Event*.scr`; unreadable scripts are skipped with a warning.
+    pub fn load(game_dir: &Path) -> Result<Self> {
+        let wave_ini = crate::map::atlas::resolve_case_insensitive(game_dir, "", "Wave.ini");
+        let waves = WaveIni::read_file(&wave_ini)
+            .map_err(|e| Error::new(e.kind(), format!("{}: {e}", wave_ini.display())))?;
+
+        let mut files = Vec::new();
+        collect_files(game_dir, game_dir, &mut files);
+        let mut snf_files: Vec<String> = files
+            .into_iter()
+            .filter(|f| f.to_ascii_lowercase().ends_with(".snf"))
+            .collect();
+        snf_files.sort();
+
+        let mut warnings = Vec::new();
+        let mut script_files: Vec<String> = std::fs::read_dir(game_dir.join("Ref"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| {
+                let lower = name.to_ascii_lowercase();
+                lower.starts_with("event") && lower.ends_with(".scr")
+            })
+            .collect();
+        script_files.sort();
+        let mut script_sounds = Vec::new();
+        for name in script_files {
+            let scripts: Vec<EventScript> =
+                read_or_warn(&game_dir.join("Ref").join(&name), &mut warnings);
+            let file = format!("Ref/{name}");
+            for line in scripts.into_iter().flat_map(|s| s.wav_content) {
+                script_sounds.push(ScriptSound {
+                    file: file.clone(),
+                    id: sound_id(&line),
+                    line,
+                });
+            }
+        }
+
+        Ok(Self {
+            waves,
+            snf_files,
+            script_sounds,
+            warnings,
+        })
+    }
+
+    /// SNF file a `Wave.ini` file name refers to, matched by file name
+    /// ignoring case, like the editor's lookup of sound files.
+    /// Prefers the game root, then `Sound/`, then any other folder.
+    pub fn resolve(&self, snf_filename: &str) -> Option<&str> {
+        let wanted = file_name(snf_filename);
+        self.snf_files
+            .iter()
+            .filter(|f| file_name(f).eq_ignore_ascii_case(wanted))
+            .min_by_key(|f| {
+                let depth = f.matches('/').count();
+                let in_sound = f.to_ascii_lowercase().starts_with("sound/");
+                (depth, !in_sound, f.as_str())
+            })
+            .map(String::as_str)
+    }
+
+    pub fn check(&self) -> LibraryReport {
+        let mut report = LibraryReport::default();
+        let mut used = BTreeSet::new();
+        for wave in &self.waves {
+            let Some(name) = wave.snf_filename.as_deref() else {
+                continue;
+            };
+            match self.resolve(name) {
+                Some(path) => {
+                    used.insert(path.to_string());
+                }
+                None => report.missing_snf.push(MissingSnf {
+                    id: wave.id,
+                    snf_filename: name.to_string(),
+                }),
+            }
+        }
+        report.unused_snf = self
+            .snf_files
+            .iter()
+            .filter(|f| !used.contains(*f))
+            .cloned()
+            .collect();
+
+        let defined: BTreeSet<i32> = self.waves.iter().map(|w| w.id).collect();
+        let mut referenced = BTreeSet::new();
+        for sound in &self.script_sounds {
+            match sound.id {
+                Some(id) if defined.contains(&id) => {
+                    referenced.insert(id);
+                }
+                Some(_) => report.undefined_ids.push(sound.clone()),
+                None => report.unparsed.push(sound.clone()),
+            }
+        }
+        report.unreferenced_ids = defined.difference(&referenced).copied().collect();
+        report
+    }
+}
+
+/// Sound id of a `[WAV]` line: the line itself or the number inside its
+/// parentheses, e.g. `12` or `wav(12)`.
+fn sound_id(line: &str) -> Option<i32> {
+    let line = line.split(';').next().unwrap_or_default().trim();
+    let inner = match (line.find('('), line.rfind(')')) {
+        (Some(open), Some(close)) if open < close => &line[open + 1..close],
+        _ => line,
+    };
+    inner.trim().parse().ok()
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// Exports every SNF `Wave.ini` names to `<out_dir>/<snf path>.wav` and
+/// writes the manifest. SNF files shared by several ids are written once;
+/// missing ones are left out (see [`SoundLibrary::check`]).
+pub fn export_all(
+    library: &SoundLibrary,
+    game_dir: &Path,
+    out_dir: &Path,
+) -> Result<ExportSummary> {
+    std::fs::create_dir_all(out_dir)?;
+    let mut summary = ExportSummary::default();
+    let mut formats: BTreeMap<&str, Option<PcmFormat>> = BTreeMap::new();
+    for wave in &library.waves {
+        let Some(snf_path) = wave
+            .snf_filename
+            .as_deref()
+            .and_then(|n| library.resolve(n))
+        else {
+            continue;
+        };
+        let wav = Path::new(snf_path)
+            .with_extension("wav")
+            .to_string_lossy()
+            .replace('\\', "/");
+        let format = *formats.entry(snf_path).or_insert_with(|| {
+            let result = snf::read(&game_dir.join(snf_path)).and_then(|snf| {
+                let target = out_dir.join(&wav);
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&target, snf.to_wav_bytes())?;
+                Ok(snf.format())
+            });
+            match result {
+                Ok(format) => {
+                    summary.written.push(wav.clone());
+                    Some(format)
+                }
+                Err(e) => {
+                    summary.failed.push((snf_path.to_string(), e.to_string()));
+                    None
+                }
+            }
+        });
+        if let Some(format) = format {
+            summary.manifest.entries.push(ManifestEntry {
+                id: wave.id,
+                max_simultaneous_plays: wave.max_simultaneous_plays,
+                snf: snf_path.to_string(),
+                wav,
+                format,
+            });
+        }
+    }
+    summary.manifest.write(out_dir)?;
+    Ok(summary)
+}
+
+/// Imports the WAV folder `wav_dir` (see [`export_all`]) into SNF files at
+/// `<out_dir>/<snf path>`, converting each WAV to its manifest format and
+/// optionally normalizing it. Manifest entries may also point at OGG, FLAC
+/// or MP3 files (see [`snf::read_audio`]). `Wave.ini` itself is not
+/// rewritten.
+pub fn import_all(wav_dir: &Path, out_dir: &Path, options: ImportOptions) -> Result<ImportSummary> {
+    let manifest = SoundManifest::read(wav_dir)?;
+    let mut summary = ImportSummary::default();
+    let mut done = BTreeSet::new();
+    for entry in &manifest.entries {
+        if !done.insert(entry.snf.as_str()) {
+            continue;
+        }
+        match import_entry(wav_dir, out_dir, entry, options) {
+            Ok(converted) => {
+                summary.converted += usize::from(converted);
+                summary.written.push(entry.snf.clone());
+            }
+            Err(e) => summary.failed.push((entry.wav.clone(), e.to_string())),
+        }
+    }
+    Ok(summary)
+}
+
+/// Converts one manifest entry; returns whether its format changed.
+fn import_entry(
+    wav_dir: &Path,
+    out_dir: &Path,
+    entry: &ManifestEntry,
+    options: ImportOptions,
+) -> Result<bool> {
+    let source = manifest_path(wav_dir, &entry.wav)?;
+    let target = manifest_path(out_dir, &entry.snf)?;
+    let wav = snf::read_audio(&source)?;
+    let converted = wav.format() != entry.format;
+    let mut snf = wav.convert(entry.format)?;
+    if let Some(level) = options.normalize_dbfs {
+        snf = snf.normalize(level)?;
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    snf::save(&target, &snf)?;
+    Ok(converted)
+}
+
+/// Joins a manifest path onto `base`. Absolute paths and `..` are rejected
+/// so an edited manifest cannot read or write outside the two folders.
+fn manifest_path(base: &Path, relative: &str) -> Result<PathBuf> {
+    let path = Path::new(relative);
+    let confined = path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if relative.is_empty() || !confined {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("manifest path must stay inside its folder: {relative}"),
+        ));
+    }
+    Ok(base.join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snf::SnfFile;
+
+    fn format(channels: i16, rate: i32, bits: i16) -> PcmFormat {
+        PcmFormat {
+            pcmaudio_format: 1,
+            number_of_channels: channels,
+            sample_rate: rate,
+            bits_per_sample: bits,
+        }
+    }
+
+    fn write_snf(path: &Path, format: PcmFormat) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let samples = vec![
+            (0..64).map(|i| (i as f32 * 0.2).sin() * 0.25).collect();
+            format.number_of_channels as usize
+        ];
+        let snf = SnfFile::from_channel_samples(format, &samples).unwrap();
+        snf::save(path, &snf).unwrap();
+    }
+
+    fn game() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("Wave.ini"),
+            "1,Hit.snf,2\n2,HIT.SNF,1\n3,Gone.snf,1\n4,null,1\n5,Music.snf,1\n",
+        )
+        .unwrap();
+        write_snf(&root.join("Sound/hit.snf"), format(1, 22050, 8));
+        write_snf(&root.join("Sound/Music.snf"), format(2, 22050, 16));
+        write_snf(&root.join("Sound/Old/unused.snf"), format(1, 11025, 8));
+        std::fs::create_dir_all(root.join("Ref")).unwrap();
+        std::fs::write(root.join("Ref/Event1.scr"), "[WAV]\nwav(1)\n9\nfanfare\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn check_reports_missing_unused_and_undefined() {
+        let dir = game();
+        let library = SoundLibrary::load(dir.path()).unwrap();
+        assert_eq!(library.resolve("sound\\HIT.snf"), Some("Sound/hit.snf"));
+        let report = library.check();
+        assert_eq!(
+            report.missing_snf,
+            vec![MissingSnf {
+                id: 3,
+                snf_filename: "Gone.snf".to_string()
+            }]
+        );
+        assert_eq!(report.unused_snf, vec!["Sound/Old/unused.snf"]);
+        let ids: Vec<Option<i32>> = report.undefined_ids.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![Some(9)]);
+        assert_eq!(report.unparsed[0].line, "fanfare");
+        assert_eq!(report.unreferenced_ids, vec![2, 3, 4, 5]);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn export_then_import_restores_the_game_format() {
+        let dir = game();
+        let library = SoundLibrary::load(dir.path()).unwrap();
+        let wavs = tempfile::tempdir().unwrap();
+        let exported = export_all(&library, dir.path(), wavs.path()).unwrap();
+        assert_eq!(exported.written, vec!["Sound/hit.wav", "Sound/Music.wav"]);
+        let ids: Vec<i32> = exported.manifest.entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![1, 2, 5]);
+
+        // Replace one sound with a stereo 44.1 kHz recording.
+        let hit = wavs.path().join("Sound/hit.wav");
+        let louder =
+            SnfFile::from_channel_samples(format(2, 44100, 16), &[vec![0.5; 100], vec![-0.5; 100]])
+                .unwrap();
+        std::fs::write(&hit, louder.to_wav_bytes()).unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let options = ImportOptions {
+            normalize_dbfs: Some(-6.0),
+        };
+        let imported = import_all(wavs.path(), out.path(), options).unwrap();
+        assert!(imported.failed.is_empty());
+        assert_eq!(imported.written, vec!["Sound/hit.snf", "Sound/Music.snf"]);
+        assert_eq!(imported.converted, 1);
+
+        let hit = snf::read(&out.path().join("Sound/hit.snf")).unwrap();
+        assert_eq!(hit.format(), format(1, 22050, 8));
+        assert_eq!(hit.pcm_data.len(), 50);
+        let music = snf::read(&out.path().join("Sound/Music.snf")).unwrap();
+        assert_eq!(music.format(), format(2, 22050, 16));
+        assert!((music.rms_dbfs().unwrap().unwrap() + 6.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn import_rejects_manifest_paths_outside_the_folders() {
+        let dir = game();
+        let library = SoundLibrary::load(dir.path()).unwrap();
+        let root = tempfile::tempdir().unwrap();
+        let wavs = root.path().join("wavs");
+        let mut exported = export_all(&library, dir.path(), &wavs).unwrap();
+        let entries = &mut exported.manifest.entries;
+        entries[0].snf = "../escaped.snf".into();
+        entries[1].wav = "/etc/passwd".into();
+        exported.manifest.write(&wavs).unwrap();
+
+        let out = root.path().join("out");
+        let imported = import_all(&wavs, &out, ImportOptions::default()).unwrap();
+        assert_eq!(imported.failed.len(), 2);
+        assert!(imported.failed[0].1.contains("must stay inside its folder"));
+        assert!(!root.path().join("escaped.snf").exists());
+    }
+}
@@ -0,0 +1,478 @@
+//! Headless waveform and spectrogram PNGs and level statistics for SNF
+//! files, for auditing a sound library for clipping, silence, DC offset and
+//! unexpected formats.
+
+use crate::references::usages::collect_files;
+use crate::snf::{self, PcmFormat, SnfFile};
+use image::{Rgba, RgbaImage};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Result;
+use std::path::Path;
+
+/// Mean above which a file is flagged for DC offset.
+const DC_OFFSET_LIMIT: f32 = 0.01;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    pub width: u32,
+    /// Height of each image; the waveform splits it between channels.
+    pub height: u32,
+    /// Samples per spectrogram column, a power of two.
+    pub fft_size: usize,
+    /// Level below which samples count as silence, in dBFS.
+    pub silence_dbfs: f32,
+    /// Only compute statistics, write no PNGs.
+    pub stats_only: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: 1024,
+            height: 256,
+            fft_size: 512,
+            silence_dbfs: -60.0,
+            stats_only: false,
+        }
+    }
+}
+
+/// Level statistics of one sound. Levels are in dBFS, `None` for silence.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoundStats {
+    pub file: String,
+    pub format: PcmFormat,
+    pub duration_secs: f32,
+    pub peak_dbfs: Option<f32>,
+    pub rms_dbfs: Option<f32>,
+    /// Samples at the lowest or highest value the sample size can hold.
+    pub clipped_samples: usize,
+    /// Mean sample value in `[-1.0, 1.0]`.
+    pub dc_offset: f32,
+    pub leading_silence_secs: f32,
+    pub trailing_silence_secs: f32,
+    /// `clipping`, `silent` or `dc_offset`.
+    pub issues: Vec<String>,
+    /// Image path below the output folder, without the `.waveform.png` /
+    /// `.spectrogram.png` suffix. `None` when only statistics were taken.
+    pub output: Option<String>,
+}
+
+/// Result of [`render_all`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RenderReport {
+    pub sounds: Vec<SoundStats>,
+    /// Number of files per sample rate, to spot the odd one out.
+    pub sample_rates: BTreeMap<i32, usize>,
+    /// Files that could not be read or rendered, with the reason.
+    pub failed: Vec<(String, String)>,
+}
+
+/// Computes the statistics of `snf`; `file` only labels the result.
+pub fn analyze(snf: &SnfFile, file: &str, silence_dbfs: f32) -> Result<SoundStats> {
+    let channels = snf.channel_samples()?;
+    let frames = channels.first().map_or(0, Vec::len);
+    let count = frames * channels.len();
+    let full_scale = 2f32.powi(i32::from(snf.bits_per_sample) - 1);
+    let highest = (full_scale - 1.0) / full_scale;
+
+    let mut peak = 0f32;
+    let mut sum = 0f64;
+    let mut clipped_samples = 0;
+    for &sample in channels.iter().flatten() {
+        peak = peak.max(sample.abs());
+        sum += f64::from(sample);
+        if sample >= highest || sample <= -1.0 {
+            clipped_samples += 1;
+        }
+    }
+    let dc_offset = if count == 0 {
+        0.0
+    } else {
+        (sum / count as f64) as f32
+    };
+
+    let threshold = 10f32.powf(silence_dbfs / 20.0);
+    let silent = |i: usize| channels.iter().all(|c| c[i].abs() < threshold);
+    let leading = (0..frames).take_while(|&i| silent(i)).count();
+    let trailing = if leading == frames {
+        0
+    } else {
+        (0..frames).rev().take_while(|&i| silent(i)).count()
+    };
+    let rate = snf.sample_rate.max(1) as f32;
+
+    let peak_dbfs = (peak > 0.0).then(|| 20.0 * peak.log10());
+    let rms_dbfs = snf.rms_dbfs()?;
+    let mut issues = Vec::new();
+    if clipped_samples > 0 {
+        issues.push("clipping".to_string());
+    }
+    if leading == frames {
+        issues.push("silent".to_string());
+    }
+    if dc_offset.abs() > DC_OFFSET_LIMIT {
+        issues.push("dc_offset".to_string());
+    }
+    Ok(SoundStats {
+        file: file.to_string(),
+        format: snf.format(),
+        duration_secs: frames as f32 / rate,
+        peak_dbfs,
+        rms_dbfs,
+        clipped_samples,
+        dc_offset,
+        leading_silence_secs: leading as f32 / rate,
+        trailing_silence_secs: trailing as f32 / rate,
+        issues,
+        output: None,
+    })
+}
+
+const BACKGROUND: Rgba<u8> = Rgba([24, 24, 28, 255]);
+const AXIS: Rgba<u8> = Rgba([70, 70, 80, 255]);
+const PEAK: Rgba<u8> = Rgba([90, 160, 230, 255]);
+const CLIPPED: Rgba<u8> = Rgba([230, 70, 60, 255]);
+
+/// Draws one lane per channel: the min/max envelope of the samples under
+/// each column, red where a column holds a clipped sample.
+pub fn render_waveform(snf: &SnfFile, width: u32, height: u32) -> Result<RgbaImage> {
+    let channels = snf.channel_samples()?;
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+    let lanes = channels.len().max(1) as u32;
+    let lane_height = (height / lanes).max(1);
+    let full_scale = 2f32.powi(i32::from(snf.bits_per_sample) - 1);
+    let highest = (full_scale - 1.0) / full_scale;
+
+    for (lane, samples) in channels.iter().enumerate() {
+        let top = lane as u32 * lane_height;
+        let middle = top + lane_height / 2;
+        let to_y = |s: f32| {
+            let offset = (-s * (lane_height as f32 / 2.0 - 1.0)).round() as i64;
+            (i64::from(middle) + offset).clamp(i64::from(top), i64::from(top + lane_height - 1))
+                as u32
+        };
+        for x in 0..width {
+            image.put_pixel(x, middle.min(height - 1), AXIS);
+            let start = samples.len() * x as usize / width as usize;
+            let end = (samples.len() * (x as usize + 1) / width as usize).max(start + 1);
+            let Some(column) = samples.get(start..end.min(samples.len())) else {
+                continue;
+            };
+            if column.is_empty() {
+                continue;
+            }
+            let (low, high) = column
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(lo, hi), &s| (lo.min(s), hi.max(s)));
+            let clipped = high >= highest || low <= -1.0;
+            for y in to_y(high)..=to_y(low) {
+                if y < height {
+                    image.put_pixel(x, y, if clipped { CLIPPED } else { PEAK });
+                }
+            }
+        }
+    }
+    Ok(image)
+}
+
+/// Draws the magnitude of the channel mix: time to the right, frequency
+/// (linear, up to half the sample rate) upwards, -100 to 0 dB from dark to
+/// bright.
+pub fn render_spectrogram(
+    snf: &SnfFile,
+    width: u32,
+    height: u32,
+    fft_size: usize,
+) -> Result<RgbaImage> {
+    let channels = snf.channel_samples()?;
+    let frames = channels.first().map_or(0, Vec::len);
+    let scale = 1.0 / channels.len().max(1) as f32;
+    let mono: Vec<f32> = (0..frames)
+        .map(|i| channels.iter().map(|c| c[i]).sum::<f32>() * scale)
+        .collect();
+
+    let n = fft_size.next_power_of_two().max(2);
+    let window: Vec<f32> = (0..n)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / n as f32).cos())
+        .collect();
+    let window_gain: f32 = window.iter().sum::<f32>() / 2.0;
+    let bins = n / 2;
+    let mut image = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+    let mut re = vec![0f32; n];
+    let mut im = vec![0f32; n];
+    for x in 0..width {
+        let start = if width > 1 {
+            mono.len().saturating_sub(n) * x as usize / (width as usize - 1)
+        } else {
+            0
+        };
+        for i in 0..n {
+            re[i] = mono.get(start + i).copied().unwrap_or(0.0) * window[i];
+            im[i] = 0.0;
+        }
+        fft(&mut re, &mut im);
+        for y in 0..height {
+            let bin = ((height - 1 - y) as usize * bins / height as usize).min(bins - 1);
+            let magnitude = (re[bin] * re[bin] + im[bin] * im[bin]).sqrt() / window_gain;
+            let db = 20.0 * magnitude.max(1e-10).log10();
+            image.put_pixel(x, y, heat(((db + 100.0) / 100.0).clamp(0.0, 1.0)));
+        }
+    }
+    Ok(image)
+}
+
+/// In-place iterative radix-2 FFT; the length must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Black → blue → red → yellow → white.
+fn heat(t: f32) -> Rgba<u8> {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 0.0],
+        [40.0, 30.0, 160.0],
+        [200.0, 40.0, 60.0],
+        [250.0, 200.0, 40.0],
+        [255.0, 255.0, 255.0],
+    ];
+    let pos = t * (STOPS.len() - 1) as f32;
+    let i = (pos.floor() as usize).min(STOPS.len() - 2);
+    let frac = pos - i as f32;
+    let channel = |c: usize| (STOPS[i][c] + (STOPS[i + 1][c] - STOPS[i][c]) * frac).round() as u8;
+    Rgba([channel(0), channel(1), channel(2), 255])
+}
+
+/// Renders every SNF file in `inputs` (files, or folders searched
+/// recursively) to `<out_dir>/<path>.waveform.png` and
+/// `<out_dir>/<path>.spectrogram.png`, where `<path>` is the file's path
+/// below the folder it was found in (its file name for file inputs), and
+/// collects the statistics. Paths that would collide with an earlier input
+/// get a `-2`, `-3`, … suffix; [`SoundStats::output`] records the result.
+pub fn render_all(inputs: &[&Path], out_dir: &Path, options: RenderOptions) -> RenderReport {
+    let mut report = RenderReport::default();
+    let mut used = BTreeSet::new();
+    for input in inputs {
+        let files: Vec<(String, std::path::PathBuf)> = if input.is_dir() {
+            let mut found = Vec::new();
+            collect_files(input, input, &mut found);
+            found.sort();
+            found
+                .into_iter()
+                .filter(|f| f.to_ascii_lowercase().ends_with(".snf"))
+                .map(|f| (f.clone(), input.join(f)))
+                .collect()
+        } else {
+            let name = input
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            vec![(name, input.to_path_buf())]
+        };
+        for (relative, path) in files {
+            let relative = unique_output(&relative, &mut used);
+            match render_one(&path, &relative, out_dir, options) {
+                Ok(stats) => {
+                    *report
+                        .sample_rates
+                        .entry(stats.format.sample_rate)
+                        .or_default() += 1;
+                    report.sounds.push(stats);
+                }
+                Err(e) => report
+                    .failed
+                    .push((path.display().to_string(), e.to_string())),
+            }
+        }
+    }
+    report
+}
+
+/// `relative` without its extension, suffixed until no earlier input used
+/// it. Compared case-insensitively, as the game folders are.
+fn unique_output(relative: &str, used: &mut BTreeSet<String>) -> String {
+    let stem = Path::new(relative)
+        .with_extension("")
+        .to_string_lossy()
+        .replace('\\', "/");
+    let mut candidate = stem.clone();
+    let mut n = 1;
+    while !used.insert(candidate.to_lowercase()) {
+        n += 1;
+        candidate = format!("{stem}-{n}");
+    }
+    candidate
+}
+
+fn render_one(
+    path: &Path,
+    relative: &str,
+    out_dir: &Path,
+    options: RenderOptions,
+) -> Result<SoundStats> {
+    let snf = snf::read(path)?;
+    let mut stats = analyze(&snf, &path.display().to_string(), options.silence_dbfs)?;
+    if options.stats_only {
+        return Ok(stats);
+    }
+    stats.output = Some(relative.to_string());
+    let base = out_dir.join(relative);
+    if let Some(parent) = base.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let save = |image: RgbaImage, suffix: &str| {
+        let mut target = base.clone().into_os_string();
+        target.push(suffix);
+        image.save(&target).map_err(std::io::Error::other)
+    };
+    save(
+        render_waveform(&snf, options.width, options.height)?,
+        ".waveform.png",
+    )?;
+    save(
+        render_spectrogram(&snf, options.width, options.height, options.fft_size)?,
+        ".spectrogram.png",
+    )?;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mono16(samples: Vec<f32>) -> SnfFile {
+        let format = PcmFormat {
+            pcmaudio_format: 1,
+            number_of_channels: 1,
+            sample_rate: 1000,
+            bits_per_sample: 16,
+        };
+        SnfFile::from_channel_samples(format, &[samples]).unwrap()
+    }
+
+    #[test]
+    fn analyze_finds_clipping_offset_and_silence() {
+        let mut samples = vec![0.0; 100];
+        samples.extend((0..200).map(|i| if i % 2 == 0 { 1.0 } else { -0.5 }));
+        samples.extend(vec![0.0; 50]);
+        let stats = analyze(&mono16(samples), "hit.snf", -60.0).unwrap();
+        assert_eq!(stats.duration_secs, 0.35);
+        assert_eq!(stats.clipped_samples, 100);
+        assert_eq!(stats.peak_dbfs.map(|p| p.round()), Some(0.0));
+        assert!((stats.dc_offset - 50.0 / 350.0).abs() < 0.001);
+        assert_eq!(stats.leading_silence_secs, 0.1);
+        assert_eq!(stats.trailing_silence_secs, 0.05);
+        assert_eq!(stats.issues, vec!["clipping", "dc_offset"]);
+
+        let silence = analyze(&mono16(vec![0.0; 10]), "quiet.snf", -60.0).unwrap();
+        assert_eq!(silence.peak_dbfs, None);
+        assert_eq!(silence.leading_silence_secs, 0.01);
+        assert_eq!(silence.trailing_silence_secs, 0.0);
+        assert_eq!(silence.issues, vec!["silent"]);
+    }
+
+    #[test]
+    fn spectrogram_peaks_at_the_tone_frequency() {
+        // 125 Hz at 1 kHz: a quarter of the way up the 0-500 Hz axis.
+        let tone: Vec<f32> = (0..2048)
+            .map(|i| (2.0 * std::f32::consts::PI * 125.0 * i as f32 / 1000.0).sin() * 0.5)
+            .collect();
+        let image = render_spectrogram(&mono16(tone), 8, 64, 256).unwrap();
+        let brightest = (0..64)
+            .max_by_key(|&y| {
+                image.get_pixel(4, y).0[..3]
+                    .iter()
+                    .map(|&c| u32::from(c))
+                    .sum::<u32>()
+            })
+            .unwrap();
+        assert!((46..=48).contains(&brightest), "row {brightest}");
+    }
+
+    #[test]
+    fn render_all_writes_pngs_per_file() {
+        let game = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(game.path().join("Sound")).unwrap();
+        snf::save(
+            &game.path().join("Sound/hit.snf"),
+            &mono16(vec![0.25, -0.25, 0.5, -0.5]),
+        )
+        .unwrap();
+        std::fs::write(game.path().join("Sound/broken.snf"), [1, 2]).unwrap();
+
+        let options = RenderOptions {
+            width: 16,
+            height: 8,
+            ..Default::default()
+        };
+        let report = render_all(&[game.path()], out.path(), options);
+        assert_eq!(report.sounds.len(), 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.sample_rates, BTreeMap::from([(1000, 1)]));
+        let waveform = image::open(out.path().join("Sound/hit.waveform.png")).unwrap();
+        assert_eq!((waveform.width(), waveform.height()), (16, 8));
+        assert!(out.path().join("Sound/hit.spectrogram.png").is_file());
+    }
+
+    #[test]
+    fn render_all_suffixes_colliding_file_names() {
+        let game = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        for dir in ["a", "b"] {
+            std::fs::create_dir_all(game.path().join(dir)).unwrap();
+            snf::save(
+                &game.path().join(dir).join("Hit.snf"),
+                &mono16(vec![0.25, -0.25]),
+            )
+            .unwrap();
+        }
+        let a = game.path().join("a/Hit.snf");
+        let b = game.path().join("b/Hit.snf");
+
+        let options = RenderOptions {
+            width: 16,
+            height: 8,
+            ..Default::default()
+        };
+        let report = render_all(&[&a, &b], out.path(), options);
+        let outputs: Vec<_> = report.sounds.iter().map(|s| s.output.as_deref()).collect();
+        assert_eq!(outputs, [Some("Hit"), Some("Hit-2")]);
+        assert_ne!(report.sounds[0].file, report.sounds[1].file);
+        for name in ["Hit", "Hit-2"] {
+            assert!(out.path().join(format!("{name}.waveform.png")).is_file());
+            assert!(out.path().join(format!("{name}.spectrogram.png")).is_file());
+        }
+    }
+}